@@ -0,0 +1,17 @@
+CREATE TABLE saved_searches (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    radius_km DOUBLE PRECISION NOT NULL DEFAULT 5,
+    statuses report_status[] NOT NULL DEFAULT ARRAY['pending', 'claimed']::report_status[],
+    categories TEXT[] NOT NULL DEFAULT '{}',
+    notify_on_new BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_saved_searches_user_id ON saved_searches(user_id);
+CREATE INDEX idx_saved_searches_notify ON saved_searches(user_id) WHERE notify_on_new;
+
+CREATE TRIGGER update_saved_searches_updated_at BEFORE UPDATE ON saved_searches
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
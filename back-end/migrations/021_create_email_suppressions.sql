@@ -0,0 +1,13 @@
+-- Addresses that bounced permanently or reported our mail as spam.
+-- Sends to these addresses are skipped until an admin clears the entry.
+CREATE TABLE email_suppressions (
+    email VARCHAR(255) PRIMARY KEY,
+    reason VARCHAR(20) NOT NULL CHECK (reason IN ('bounce', 'complaint')),
+    provider VARCHAR(20) NOT NULL,
+    details TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_email_suppressions_updated_at BEFORE UPDATE ON email_suppressions
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,22 @@
+-- Append-only log of notable events. Inbound webhook deliveries are stored with
+-- their raw payload and processing status so admins can inspect and replay them.
+CREATE TABLE event_log (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    event_type VARCHAR(100) NOT NULL,
+    entity_type VARCHAR(50),
+    entity_id TEXT,
+    payload TEXT NOT NULL DEFAULT '',
+    delivery_status VARCHAR(20) CHECK (delivery_status IN ('pending', 'succeeded', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_event_log_created_at ON event_log(created_at DESC);
+CREATE INDEX idx_event_log_type_created_at ON event_log(event_type, created_at DESC);
+CREATE INDEX idx_event_log_entity_id ON event_log(entity_id);
+CREATE INDEX idx_event_log_failed ON event_log(created_at DESC) WHERE delivery_status = 'failed';
+
+CREATE TRIGGER update_event_log_updated_at BEFORE UPDATE ON event_log
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
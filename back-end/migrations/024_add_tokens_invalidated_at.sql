@@ -0,0 +1,3 @@
+-- Access tokens issued before this instant are rejected (logout from all devices,
+-- password reset). NULL means no revocation has happened.
+ALTER TABLE users ADD COLUMN tokens_invalidated_at TIMESTAMPTZ;
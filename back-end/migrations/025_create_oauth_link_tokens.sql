@@ -0,0 +1,12 @@
+-- Pending OAuth identity links, confirmed by email before the identity is attached
+CREATE TABLE oauth_link_tokens (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(255) NOT NULL UNIQUE,
+    oauth_provider VARCHAR(50) NOT NULL,
+    oauth_subject VARCHAR(255) NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_oauth_link_tokens_user_id ON oauth_link_tokens(user_id);
@@ -0,0 +1,53 @@
+-- Keep feed_posts.like_count / comment_count in step with their source tables.
+-- Counters were maintained by the application and could drift (double deletes,
+-- failed decrements); triggers make every change to the source rows count exactly once.
+
+CREATE OR REPLACE FUNCTION sync_feed_post_like_count()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        UPDATE feed_posts SET like_count = like_count + 1 WHERE id = NEW.post_id;
+    ELSIF TG_OP = 'DELETE' THEN
+        UPDATE feed_posts SET like_count = like_count - 1 WHERE id = OLD.post_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER sync_feed_post_like_count
+    AFTER INSERT OR DELETE ON feed_post_likes
+    FOR EACH ROW EXECUTE FUNCTION sync_feed_post_like_count();
+
+-- Only visible (not soft-deleted) comments are counted
+CREATE OR REPLACE FUNCTION sync_feed_post_comment_count()
+RETURNS TRIGGER AS $$
+DECLARE
+    delta INTEGER := 0;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        IF NOT NEW.is_deleted THEN delta := 1; END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        IF NOT OLD.is_deleted THEN delta := -1; END IF;
+    ELSIF OLD.is_deleted <> NEW.is_deleted THEN
+        delta := CASE WHEN NEW.is_deleted THEN -1 ELSE 1 END;
+    END IF;
+
+    IF delta <> 0 THEN
+        UPDATE feed_posts
+        SET comment_count = comment_count + delta
+        WHERE id = COALESCE(NEW.post_id, OLD.post_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER sync_feed_post_comment_count
+    AFTER INSERT OR DELETE OR UPDATE OF is_deleted ON feed_comments
+    FOR EACH ROW EXECUTE FUNCTION sync_feed_post_comment_count();
+
+-- Repair any drift accumulated before the triggers existed
+UPDATE feed_posts fp
+SET like_count = (SELECT COUNT(*) FROM feed_post_likes l WHERE l.post_id = fp.id),
+    comment_count = (
+        SELECT COUNT(*) FROM feed_comments c WHERE c.post_id = fp.id AND NOT c.is_deleted
+    );
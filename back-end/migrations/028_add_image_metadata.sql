@@ -0,0 +1,15 @@
+-- Store dimensions and a BlurHash placeholder alongside each uploaded image.
+-- Columns are nullable: images uploaded before this migration have no metadata.
+
+ALTER TABLE litter_reports
+    ADD COLUMN photo_before_width INTEGER,
+    ADD COLUMN photo_before_height INTEGER,
+    ADD COLUMN photo_before_blurhash VARCHAR(64),
+    ADD COLUMN photo_after_width INTEGER,
+    ADD COLUMN photo_after_height INTEGER,
+    ADD COLUMN photo_after_blurhash VARCHAR(64);
+
+ALTER TABLE feed_post_images
+    ADD COLUMN width INTEGER,
+    ADD COLUMN height INTEGER,
+    ADD COLUMN blurhash VARCHAR(64);
@@ -0,0 +1,28 @@
+-- Resumable chunked photo uploads.
+-- Chunks are appended to `pending` until a full S3 multipart part is available,
+-- so clients can send small chunks over slow connections and resume after a drop.
+
+CREATE TABLE photo_uploads (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    s3_key VARCHAR(512) NOT NULL,
+    s3_upload_id VARCHAR(1024) NOT NULL,
+    total_size BIGINT NOT NULL CHECK (total_size > 0),
+    received_bytes BIGINT NOT NULL DEFAULT 0,
+    pending BYTEA NOT NULL DEFAULT ''::bytea,
+    next_part_number INTEGER NOT NULL DEFAULT 1,
+    completed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_photo_uploads_user_id ON photo_uploads(user_id);
+CREATE INDEX idx_photo_uploads_expires_at ON photo_uploads(expires_at);
+
+CREATE TABLE photo_upload_parts (
+    upload_id UUID NOT NULL REFERENCES photo_uploads(id) ON DELETE CASCADE,
+    part_number INTEGER NOT NULL,
+    etag VARCHAR(255) NOT NULL,
+    size BIGINT NOT NULL,
+    PRIMARY KEY (upload_id, part_number)
+);
@@ -0,0 +1,33 @@
+-- Content-addressed image storage: identical processed images share one S3 object.
+
+CREATE TABLE stored_images (
+    content_hash CHAR(64) PRIMARY KEY,  -- SHA-256 of the processed WebP bytes
+    s3_key VARCHAR(512) NOT NULL,
+    url VARCHAR(512) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Which photos each user attached to which report, to spot reuse across reports
+CREATE TABLE report_photo_hashes (
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    content_hash CHAR(64) NOT NULL REFERENCES stored_images(content_hash),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (report_id, user_id, content_hash)
+);
+
+CREATE INDEX idx_report_photo_hashes_user_hash ON report_photo_hashes(user_id, content_hash);
+
+-- Accounts flagged for moderator review
+CREATE TABLE account_flags (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason VARCHAR(50) NOT NULL,
+    content_hash CHAR(64),
+    details TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, reason, content_hash)
+);
+
+CREATE INDEX idx_account_flags_created_at ON account_flags(created_at DESC);
@@ -0,0 +1,6 @@
+-- Device details for refresh tokens so users can review and revoke their sessions
+
+ALTER TABLE refresh_tokens
+    ADD COLUMN user_agent VARCHAR(512),
+    ADD COLUMN ip_address VARCHAR(64),
+    ADD COLUMN last_used_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
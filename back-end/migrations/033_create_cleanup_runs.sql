@@ -0,0 +1,14 @@
+-- A batch of reports claimed together, so clearing them can be summarised as one run
+CREATE TABLE cleanup_runs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_cleanup_runs_user_id ON cleanup_runs(user_id);
+
+ALTER TABLE litter_reports
+    ADD COLUMN cleanup_run_id UUID REFERENCES cleanup_runs(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_litter_reports_cleanup_run_id ON litter_reports(cleanup_run_id)
+    WHERE cleanup_run_id IS NOT NULL;
@@ -0,0 +1,12 @@
+-- OAuth flows started by a signed-in user to link a provider to their account,
+-- keyed by the OAuth state parameter so the callback knows which user to link
+CREATE TABLE oauth_link_requests (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    state_hash VARCHAR(255) NOT NULL UNIQUE,
+    oauth_provider VARCHAR(50) NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_oauth_link_requests_user_id ON oauth_link_requests(user_id);
@@ -0,0 +1,9 @@
+-- In-flight OAuth authorization requests: the CSRF state and the nonce expected in the ID token
+CREATE TABLE oauth_states (
+    state_hash VARCHAR(255) PRIMARY KEY,
+    nonce TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_oauth_states_expires_at ON oauth_states(expires_at);
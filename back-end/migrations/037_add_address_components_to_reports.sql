@@ -0,0 +1,5 @@
+-- Structured parts of the reverse-geocoded address, alongside the display label in `address`
+ALTER TABLE litter_reports
+    ADD COLUMN address_street TEXT,
+    ADD COLUMN address_city TEXT,
+    ADD COLUMN address_country TEXT;
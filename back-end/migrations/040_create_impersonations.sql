@@ -0,0 +1,15 @@
+-- Audit trail of support staff signing in as users; rows outlive both accounts
+CREATE TABLE impersonations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    admin_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Emails as they were at the time, kept if the accounts are deleted
+    admin_email VARCHAR(255) NOT NULL,
+    user_email VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_impersonations_user ON impersonations(user_id, created_at DESC);
+CREATE INDEX idx_impersonations_created ON impersonations(created_at DESC);
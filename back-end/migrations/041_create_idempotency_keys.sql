@@ -0,0 +1,19 @@
+-- Responses to mutating requests sent with an Idempotency-Key header, so a client that
+-- retries after a dropped connection gets the original response instead of a duplicate
+CREATE TABLE idempotency_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Method and concrete path, e.g. "POST /api/reports/<id>/clear"
+    endpoint VARCHAR(255) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    -- SHA-256 of the request body; reusing a key with a different body is rejected
+    request_hash VARCHAR(64) NOT NULL,
+    -- NULL while the first request is still being handled
+    status_code SMALLINT,
+    content_type VARCHAR(255),
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, endpoint, idempotency_key)
+);
+
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
@@ -0,0 +1,64 @@
+-- Some databases lost rows or constraints to manual cleanup. Remove what points at
+-- missing rows, then re-assert the foreign keys with their intended ON DELETE rules.
+
+DELETE FROM user_scores s WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.id = s.user_id);
+DELETE FROM litter_reports r WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.id = r.reporter_id);
+UPDATE litter_reports r SET claimed_by = NULL
+WHERE claimed_by IS NOT NULL AND NOT EXISTS (SELECT 1 FROM users u WHERE u.id = r.claimed_by);
+UPDATE litter_reports r SET cleared_by = NULL
+WHERE cleared_by IS NOT NULL AND NOT EXISTS (SELECT 1 FROM users u WHERE u.id = r.cleared_by);
+DELETE FROM report_verifications v
+WHERE NOT EXISTS (SELECT 1 FROM litter_reports r WHERE r.id = v.report_id)
+   OR NOT EXISTS (SELECT 1 FROM users u WHERE u.id = v.verifier_id);
+UPDATE score_events e SET report_id = NULL
+WHERE report_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM litter_reports r WHERE r.id = e.report_id);
+
+ALTER TABLE user_scores
+    DROP CONSTRAINT IF EXISTS user_scores_user_id_fkey,
+    ADD CONSTRAINT user_scores_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE litter_reports
+    DROP CONSTRAINT IF EXISTS litter_reports_reporter_id_fkey,
+    ADD CONSTRAINT litter_reports_reporter_id_fkey
+        FOREIGN KEY (reporter_id) REFERENCES users(id) ON DELETE CASCADE,
+    DROP CONSTRAINT IF EXISTS litter_reports_claimed_by_fkey,
+    ADD CONSTRAINT litter_reports_claimed_by_fkey
+        FOREIGN KEY (claimed_by) REFERENCES users(id) ON DELETE SET NULL,
+    DROP CONSTRAINT IF EXISTS litter_reports_cleared_by_fkey,
+    ADD CONSTRAINT litter_reports_cleared_by_fkey
+        FOREIGN KEY (cleared_by) REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE report_verifications
+    DROP CONSTRAINT IF EXISTS report_verifications_report_id_fkey,
+    ADD CONSTRAINT report_verifications_report_id_fkey
+        FOREIGN KEY (report_id) REFERENCES litter_reports(id) ON DELETE CASCADE,
+    DROP CONSTRAINT IF EXISTS report_verifications_verifier_id_fkey,
+    ADD CONSTRAINT report_verifications_verifier_id_fkey
+        FOREIGN KEY (verifier_id) REFERENCES users(id) ON DELETE CASCADE;
+
+-- Score history outlives the report it was earned on
+ALTER TABLE score_events
+    ADD CONSTRAINT score_events_report_id_fkey
+        FOREIGN KEY (report_id) REFERENCES litter_reports(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_score_events_report_id ON score_events(report_id);
+
+-- ON DELETE SET NULL alone would leave a deleted user's claims stuck as 'claimed'
+-- with nobody to clear them; put them back up for grabs instead
+CREATE OR REPLACE FUNCTION release_claims_of_deleted_user()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE litter_reports
+    SET status = 'pending', claimed_by = NULL, claimed_at = NULL, cleanup_run_id = NULL
+    WHERE claimed_by = OLD.id AND status = 'claimed';
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER release_claims_before_user_delete BEFORE DELETE ON users
+    FOR EACH ROW EXECUTE FUNCTION release_claims_of_deleted_user();
+
+UPDATE litter_reports
+SET status = 'pending', claimed_at = NULL, cleanup_run_id = NULL
+WHERE status = 'claimed' AND claimed_by IS NULL;
@@ -0,0 +1,15 @@
+-- Results of the data consistency check, scheduled or started by an admin
+CREATE TABLE consistency_check_runs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    -- NULL for scheduled runs
+    triggered_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    dry_run BOOLEAN NOT NULL DEFAULT FALSE,
+    -- [{ "check", "description", "found", "fixed" }] for every check, including clean ones
+    anomalies JSONB NOT NULL DEFAULT '[]',
+    total_found BIGINT NOT NULL DEFAULT 0,
+    total_fixed BIGINT NOT NULL DEFAULT 0,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_consistency_check_runs_started_at ON consistency_check_runs(started_at DESC);
@@ -0,0 +1,16 @@
+-- One row per successful sign-in, kept after the session itself is revoked
+CREATE TABLE login_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id UUID REFERENCES refresh_tokens(id) ON DELETE SET NULL,
+    ip_address TEXT,
+    user_agent TEXT,
+    -- ISO 3166-1 alpha-2 code from the edge proxy, when it supplies one
+    country VARCHAR(2),
+    -- First sign-in from this user agent / country; either one triggers an alert email
+    new_device BOOLEAN NOT NULL DEFAULT FALSE,
+    new_country BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_login_events_user_created ON login_events(user_id, created_at DESC);
@@ -0,0 +1,13 @@
+-- Guests may report litter without an account; such reports have no reporter until
+-- someone claims them with the code they were given
+ALTER TABLE litter_reports ALTER COLUMN reporter_id DROP NOT NULL;
+
+CREATE TABLE anonymous_report_claims (
+    report_id UUID PRIMARY KEY REFERENCES litter_reports(id) ON DELETE CASCADE,
+    -- SHA-256 of the claim code handed to the guest
+    code_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_anonymous_report_claims_expires ON anonymous_report_claims(expires_at);
@@ -0,0 +1,7 @@
+-- Moderators handle day-to-day content moderation without full admin rights
+ALTER TYPE user_role ADD VALUE IF NOT EXISTS 'moderator';
+
+-- Flags stay listed after review, marked with who resolved them
+ALTER TABLE account_flags
+    ADD COLUMN resolved_at TIMESTAMPTZ,
+    ADD COLUMN resolved_by UUID REFERENCES users(id) ON DELETE SET NULL;
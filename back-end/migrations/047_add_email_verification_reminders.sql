@@ -0,0 +1,9 @@
+-- Accounts left unverified get one reminder email, which carries its own opt-out link
+ALTER TABLE users
+    ADD COLUMN verification_reminder_sent_at TIMESTAMPTZ,
+    ADD COLUMN verification_reminders_unsubscribed BOOLEAN NOT NULL DEFAULT FALSE,
+    -- SHA-256 of the token in the reminder's unsubscribe link
+    ADD COLUMN reminder_unsubscribe_token TEXT;
+
+CREATE UNIQUE INDEX idx_users_reminder_unsubscribe_token ON users(reminder_unsubscribe_token);
+CREATE INDEX idx_users_unverified_created ON users(created_at) WHERE NOT email_verified;
@@ -0,0 +1,27 @@
+-- Verifiers can ask the clearer for a better after photo instead of judging a blurry one
+ALTER TABLE report_verifications
+    ADD COLUMN requested_better_photo BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Set when a retake request resets the report's tally; superseded verifications no
+    -- longer count and their verifiers may judge the new photo
+    ADD COLUMN superseded_at TIMESTAMPTZ;
+
+ALTER TABLE report_verifications
+    DROP CONSTRAINT report_verifications_report_id_verifier_id_key;
+CREATE UNIQUE INDEX idx_verifications_current
+    ON report_verifications(report_id, verifier_id)
+    WHERE superseded_at IS NULL;
+
+CREATE TABLE photo_retake_requests (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- The clearer may upload a replacement until then; afterwards verification resumes
+    -- on the original photo
+    expires_at TIMESTAMPTZ NOT NULL,
+    fulfilled_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_photo_retake_requests_open
+    ON photo_retake_requests(report_id)
+    WHERE fulfilled_at IS NULL;
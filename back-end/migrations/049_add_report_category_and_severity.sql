@@ -0,0 +1,11 @@
+-- What kind of litter a report is about, and how much of it there is
+CREATE TYPE litter_category AS ENUM (
+    'plastic', 'glass', 'metal', 'paper', 'hazardous', 'fly_tipping', 'dog_waste', 'other'
+);
+CREATE TYPE litter_severity AS ENUM ('small', 'medium', 'large');
+
+ALTER TABLE litter_reports
+    ADD COLUMN category litter_category NOT NULL DEFAULT 'other',
+    ADD COLUMN severity litter_severity NOT NULL DEFAULT 'small';
+
+CREATE INDEX idx_reports_category ON litter_reports(category);
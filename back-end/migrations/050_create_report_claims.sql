@@ -0,0 +1,25 @@
+-- Every claim on a report and how it ended, so released and expired claims are not lost
+CREATE TYPE claim_outcome AS ENUM ('active', 'cleared', 'released', 'expired', 'reassigned');
+
+CREATE TABLE report_claims (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    claimed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at TIMESTAMPTZ,
+    outcome claim_outcome NOT NULL DEFAULT 'active'
+);
+
+-- A report has at most one claim in progress
+CREATE UNIQUE INDEX idx_report_claims_active ON report_claims(report_id) WHERE outcome = 'active';
+CREATE INDEX idx_report_claims_report ON report_claims(report_id, claimed_at);
+CREATE INDEX idx_report_claims_user_ended ON report_claims(user_id, ended_at)
+    WHERE outcome IN ('released', 'expired');
+
+-- Existing claims start the history
+INSERT INTO report_claims (report_id, user_id, claimed_at, ended_at, outcome)
+SELECT id, claimed_by, COALESCE(claimed_at, created_at), cleared_at,
+       CASE WHEN status IN ('cleared', 'verified') THEN 'cleared'::claim_outcome
+            ELSE 'active'::claim_outcome END
+FROM litter_reports
+WHERE claimed_by IS NOT NULL;
@@ -0,0 +1,28 @@
+-- Reports can carry several before and after photos. litter_reports.photo_before and
+-- photo_after stay as the first (position 0) of each for existing clients.
+CREATE TYPE photo_kind AS ENUM ('before', 'after');
+
+CREATE TABLE report_photos (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    kind photo_kind NOT NULL,
+    position INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    width INTEGER,
+    height INTEGER,
+    blurhash TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (report_id, kind, position)
+);
+
+INSERT INTO report_photos (report_id, kind, position, url, width, height, blurhash, created_at)
+SELECT id, 'before', 0, photo_before, photo_before_width, photo_before_height,
+       photo_before_blurhash, created_at
+FROM litter_reports
+WHERE photo_before IS NOT NULL;
+
+INSERT INTO report_photos (report_id, kind, position, url, width, height, blurhash, created_at)
+SELECT id, 'after', 0, photo_after, photo_after_width, photo_after_height,
+       photo_after_blurhash, COALESCE(cleared_at, updated_at)
+FROM litter_reports
+WHERE photo_after IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Why a claimer gave a report up, when they said
+ALTER TABLE report_claims ADD COLUMN reason TEXT;
@@ -0,0 +1,16 @@
+-- Changes a reporter made to their report before anyone claimed it, kept for moderators.
+-- Each row holds the edited fields before and after, so no edit can hide what was there.
+CREATE TABLE report_edits (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    editor_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    previous_description TEXT,
+    new_description TEXT,
+    previous_category litter_category NOT NULL,
+    new_category litter_category NOT NULL,
+    previous_photo TEXT,
+    new_photo TEXT,
+    edited_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_report_edits_report ON report_edits(report_id, edited_at);
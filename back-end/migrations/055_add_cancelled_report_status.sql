@@ -0,0 +1,3 @@
+-- Reporters can withdraw a pending report made by mistake. The row stays, without its
+-- photos, so anything that points at it still resolves.
+ALTER TYPE report_status ADD VALUE IF NOT EXISTS 'cancelled';
@@ -0,0 +1,22 @@
+-- Verifiers rejecting a clear dispute it; admins archive reports that should leave the map
+ALTER TYPE report_status ADD VALUE IF NOT EXISTS 'disputed';
+ALTER TYPE report_status ADD VALUE IF NOT EXISTS 'archived';
+
+-- Every status change a report goes through, and what caused it
+CREATE TYPE report_transition AS ENUM (
+    'claim', 'release', 'expire', 'clear', 'verify', 'dispute', 'cancel', 'archive', 'correct'
+);
+
+CREATE TABLE report_status_changes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    transition report_transition NOT NULL,
+    from_status report_status NOT NULL,
+    to_status report_status NOT NULL,
+    -- The claimer, verifier or admin behind the change; null for jobs
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    reason TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_report_status_changes_report ON report_status_changes(report_id, changed_at);
@@ -0,0 +1,25 @@
+-- Users flag reports that should not be on the map; enough open flags hide a report
+-- until a moderator reviews it
+CREATE TYPE report_flag_reason AS ENUM (
+    'not_litter', 'private_property', 'offensive_photo', 'wrong_location'
+);
+
+CREATE TABLE report_flags (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    flagger_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason report_flag_reason NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- One open flag per user and report
+CREATE UNIQUE INDEX idx_report_flags_open ON report_flags(report_id, flagger_id)
+    WHERE resolved_at IS NULL;
+CREATE INDEX idx_report_flags_report ON report_flags(report_id, created_at);
+
+-- Set while a report is hidden awaiting review
+ALTER TABLE litter_reports ADD COLUMN hidden_at TIMESTAMPTZ;
+CREATE INDEX idx_litter_reports_hidden ON litter_reports(hidden_at) WHERE hidden_at IS NOT NULL;
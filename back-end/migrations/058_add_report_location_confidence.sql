@@ -0,0 +1,4 @@
+-- How well the first photo's EXIF position and time agree with where and when the
+-- report was made, from 0 to 1; null when the photo carried neither
+ALTER TABLE litter_reports ADD COLUMN location_confidence DOUBLE PRECISION
+    CHECK (location_confidence BETWEEN 0 AND 1);
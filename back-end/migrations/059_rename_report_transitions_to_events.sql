@@ -0,0 +1,15 @@
+-- Report history entries are named for what happened, and also cover a report being
+-- created and deleted. Kept apart from the table changes, since a new enum value
+-- cannot be used in the transaction that adds it.
+ALTER TYPE report_transition RENAME TO report_event_kind;
+ALTER TYPE report_event_kind RENAME VALUE 'claim' TO 'claimed';
+ALTER TYPE report_event_kind RENAME VALUE 'release' TO 'released';
+ALTER TYPE report_event_kind RENAME VALUE 'expire' TO 'expired';
+ALTER TYPE report_event_kind RENAME VALUE 'clear' TO 'cleared';
+ALTER TYPE report_event_kind RENAME VALUE 'verify' TO 'verified';
+ALTER TYPE report_event_kind RENAME VALUE 'dispute' TO 'disputed';
+ALTER TYPE report_event_kind RENAME VALUE 'cancel' TO 'cancelled';
+ALTER TYPE report_event_kind RENAME VALUE 'archive' TO 'archived';
+ALTER TYPE report_event_kind RENAME VALUE 'correct' TO 'corrected';
+ALTER TYPE report_event_kind ADD VALUE 'created' BEFORE 'claimed';
+ALTER TYPE report_event_kind ADD VALUE 'deleted';
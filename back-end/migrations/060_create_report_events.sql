@@ -0,0 +1,18 @@
+-- The status change log becomes each report's audit trail. Creation has no previous
+-- status and deletion no next one, and entries outlive the report they describe.
+ALTER TABLE report_status_changes RENAME TO report_events;
+ALTER INDEX report_status_changes_pkey RENAME TO report_events_pkey;
+ALTER INDEX idx_report_status_changes_report RENAME TO idx_report_events_report;
+ALTER TABLE report_events RENAME COLUMN transition TO kind;
+ALTER TABLE report_events RENAME COLUMN changed_at TO occurred_at;
+ALTER TABLE report_events
+    ALTER COLUMN from_status DROP NOT NULL,
+    ALTER COLUMN to_status DROP NOT NULL,
+    DROP CONSTRAINT report_status_changes_report_id_fkey,
+    ADD COLUMN metadata JSONB NOT NULL DEFAULT '{}';
+
+-- Reports made before the history existed start with their creation
+INSERT INTO report_events (report_id, kind, to_status, actor_id, metadata, occurred_at)
+SELECT id, 'created', 'pending', reporter_id,
+       jsonb_build_object('category', category, 'severity', severity), created_at
+FROM litter_reports;
@@ -0,0 +1,2 @@
+-- Disputed clears that nobody settles go back to pending on their own
+ALTER TYPE report_event_kind ADD VALUE IF NOT EXISTS 'reopened';
@@ -0,0 +1,15 @@
+-- Neighbourhoods a user watches for new reports. A circle keeps its centre and radius
+-- for display; either way `area` is the polygon new reports are matched against.
+CREATE TABLE watched_areas (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    center GEOMETRY(POINT, 4326),
+    radius_km DOUBLE PRECISION,
+    area GEOMETRY(POLYGON, 4326) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((center IS NULL) = (radius_km IS NULL))
+);
+
+CREATE INDEX idx_watched_areas_user_id ON watched_areas(user_id);
+CREATE INDEX idx_watched_areas_area ON watched_areas USING GIST(area);
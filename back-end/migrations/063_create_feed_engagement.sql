@@ -0,0 +1,24 @@
+-- Raw engagement with feed posts, kept only as long as it still moves a post's score
+CREATE TYPE feed_engagement_kind AS ENUM ('view', 'like', 'comment', 'dwell');
+
+CREATE TABLE feed_engagement_events (
+    id BIGSERIAL PRIMARY KEY,
+    post_id UUID NOT NULL REFERENCES feed_posts(id) ON DELETE CASCADE,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    kind feed_engagement_kind NOT NULL,
+    -- Set only for dwell events
+    dwell_ms INTEGER,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_feed_engagement_events_occurred_at ON feed_engagement_events(occurred_at);
+
+-- Decayed engagement per post, recomputed periodically for the trending feed. Kept out
+-- of feed_posts so rescoring leaves the posts' updated_at alone.
+CREATE TABLE feed_post_engagement_scores (
+    post_id UUID PRIMARY KEY REFERENCES feed_posts(id) ON DELETE CASCADE,
+    score DOUBLE PRECISION NOT NULL,
+    scored_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_feed_post_engagement_scores_score ON feed_post_engagement_scores(score DESC);
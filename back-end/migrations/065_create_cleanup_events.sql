@@ -0,0 +1,36 @@
+-- Group cleanups: an organiser picks a time, a meeting point and the reports to clear.
+-- A linked report cleared during the event by an attendee earns every attendee a bonus,
+-- once; `bonus_awarded_at` records that it has been paid.
+CREATE TABLE cleanup_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    organizer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title VARCHAR(100) NOT NULL,
+    description TEXT,
+    meeting_point GEOMETRY(POINT, 4326) NOT NULL,
+    meeting_point_description VARCHAR(255),
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX idx_cleanup_events_ends_at ON cleanup_events(ends_at);
+CREATE INDEX idx_cleanup_events_meeting_point ON cleanup_events USING GIST(meeting_point);
+
+CREATE TABLE cleanup_event_reports (
+    event_id UUID NOT NULL REFERENCES cleanup_events(id) ON DELETE CASCADE,
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    bonus_awarded_at TIMESTAMPTZ,
+    PRIMARY KEY (event_id, report_id)
+);
+
+CREATE INDEX idx_cleanup_event_reports_report_id ON cleanup_event_reports(report_id);
+
+CREATE TABLE cleanup_event_rsvps (
+    event_id UUID NOT NULL REFERENCES cleanup_events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, user_id)
+);
+
+CREATE INDEX idx_cleanup_event_rsvps_user_id ON cleanup_event_rsvps(user_id);
@@ -0,0 +1,13 @@
+-- Machine translations of report descriptions, kept so each is only paid for once.
+-- `source_hash` is the SHA-256 of the description translated; an edited description
+-- no longer matches and is translated again.
+CREATE TABLE report_translations (
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    target_language VARCHAR(10) NOT NULL,
+    source_hash VARCHAR(64) NOT NULL,
+    source_language VARCHAR(10),
+    translated_text TEXT NOT NULL,
+    provider VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (report_id, target_language)
+);
@@ -0,0 +1,15 @@
+-- Reverse-geocoded addresses, keyed by coordinates rounded to `decimals` places so
+-- nearby reports share one provider lookup. A NULL label records that the provider
+-- found nothing there.
+CREATE TABLE geocode_cache (
+    lat_key DOUBLE PRECISION NOT NULL,
+    lon_key DOUBLE PRECISION NOT NULL,
+    decimals SMALLINT NOT NULL,
+    label TEXT,
+    street TEXT,
+    city TEXT,
+    country TEXT,
+    provider VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (lat_key, lon_key, decimals)
+);
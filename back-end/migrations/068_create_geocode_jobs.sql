@@ -0,0 +1,12 @@
+-- Reports waiting for their address. A report is saved straight away and its row here
+-- is resolved after commit, then retried with backoff while the provider is failing.
+-- The row is removed once the lookup finishes, whether or not it found an address.
+CREATE TABLE geocode_jobs (
+    report_id UUID PRIMARY KEY REFERENCES litter_reports(id) ON DELETE CASCADE,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_geocode_jobs_due ON geocode_jobs(next_attempt_at);
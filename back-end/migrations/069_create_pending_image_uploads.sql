@@ -0,0 +1,15 @@
+-- Processed images accepted while object storage was unreachable. The bytes wait here,
+-- and are served from here, until the retry job uploads them; the row is then removed.
+CREATE TABLE pending_image_uploads (
+    content_hash CHAR(64) PRIMARY KEY REFERENCES stored_images(content_hash) ON DELETE CASCADE,
+    data BYTEA NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pending_image_uploads_due ON pending_image_uploads(next_attempt_at);
+
+-- Photos are looked up by URL to tell whether they are still pending
+CREATE INDEX idx_stored_images_url ON stored_images(url);
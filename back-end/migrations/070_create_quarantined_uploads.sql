@@ -0,0 +1,13 @@
+-- Uploads the malware scanner flagged. The bytes are kept out of object storage, here
+-- only, so an admin can hand them to a security team before deleting the row.
+CREATE TABLE quarantined_uploads (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_hash CHAR(64) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    signature TEXT NOT NULL,
+    scanner VARCHAR(32) NOT NULL,
+    data BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_quarantined_uploads_created_at ON quarantined_uploads(created_at DESC);
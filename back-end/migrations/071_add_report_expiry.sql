@@ -0,0 +1,15 @@
+-- Pending reports nobody clears for months expire: they leave the map, and photos only
+-- expired reports use move to a cheaper storage class until an admin reactivates one
+ALTER TYPE report_status ADD VALUE IF NOT EXISTS 'expired';
+ALTER TYPE report_event_kind ADD VALUE IF NOT EXISTS 'lapsed';
+ALTER TYPE report_event_kind ADD VALUE IF NOT EXISTS 'reactivated';
+
+-- Reactivated reports get a full expiry period again, counted from here
+ALTER TABLE litter_reports ADD COLUMN reactivated_at TIMESTAMPTZ;
+
+-- Set while the object sits in the archive storage class
+ALTER TABLE stored_images ADD COLUMN archived_at TIMESTAMPTZ;
+
+CREATE INDEX idx_litter_reports_pending_age
+    ON litter_reports (COALESCE(reactivated_at, created_at))
+    WHERE status = 'pending';
@@ -0,0 +1,8 @@
+-- When each scheduled job last ran anywhere in the cluster. Every instance schedules
+-- the jobs, and the first to claim a tick records it here so the others skip it.
+CREATE TABLE job_runs (
+    name TEXT PRIMARY KEY,
+    last_started_at TIMESTAMPTZ NOT NULL,
+    last_finished_at TIMESTAMPTZ,
+    last_error TEXT
+);
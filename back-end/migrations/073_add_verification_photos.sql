@@ -0,0 +1,6 @@
+-- An on-site photo a verifier may attach as evidence for their verdict
+ALTER TABLE report_verifications
+    ADD COLUMN photo TEXT,
+    ADD COLUMN photo_width INTEGER,
+    ADD COLUMN photo_height INTEGER,
+    ADD COLUMN photo_blurhash TEXT;
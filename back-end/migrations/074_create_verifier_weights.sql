@@ -0,0 +1,12 @@
+-- How much each verifier's verdict counts towards a clear's consensus, from how often
+-- their past verdicts matched how the clear was settled
+CREATE TABLE verifier_weights (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    agreed INTEGER NOT NULL DEFAULT 0,
+    settled INTEGER NOT NULL DEFAULT 0,
+    weight DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Set once the verdict has counted towards its verifier's weight
+ALTER TABLE report_verifications ADD COLUMN settled_at TIMESTAMPTZ;
@@ -0,0 +1,7 @@
+-- Where a user lives or usually picks litter; their "my area" dashboard covers their
+-- search radius around it
+CREATE TABLE user_home_areas (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    location GEOMETRY(POINT, 4326) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,22 @@
+-- Collective weekly targets per city ("London: clear 200 reports this week"). Progress
+-- is counted from clears; a week is recorded in city_goal_completions once reached so
+-- the celebration goes out only once.
+CREATE TABLE city_goals (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    city VARCHAR(100) NOT NULL,
+    weekly_target INTEGER NOT NULL CHECK (weekly_target > 0),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_city_goals_city ON city_goals (LOWER(city));
+
+CREATE TABLE city_goal_completions (
+    goal_id UUID NOT NULL REFERENCES city_goals(id) ON DELETE CASCADE,
+    week_start TIMESTAMPTZ NOT NULL,
+    -- The target in force when it was reached
+    target INTEGER NOT NULL,
+    reached_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (goal_id, week_start)
+);
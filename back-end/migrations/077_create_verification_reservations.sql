@@ -0,0 +1,13 @@
+-- A verifier holds one of a cleared report's open verification slots for a short while,
+-- so several people don't travel out to check the same clear. Rows past expires_at are
+-- ignored and overwritten on the next reservation.
+CREATE TABLE verification_reservations (
+    report_id UUID NOT NULL REFERENCES litter_reports(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reserved_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (report_id, user_id)
+);
+
+CREATE INDEX idx_verification_reservations_active
+    ON verification_reservations (report_id, expires_at);
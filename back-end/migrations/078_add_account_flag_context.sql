@@ -0,0 +1,4 @@
+-- Collusion flags name the other account in the pattern and the report that tripped it
+ALTER TABLE account_flags
+    ADD COLUMN related_user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    ADD COLUMN report_id UUID REFERENCES litter_reports(id) ON DELETE SET NULL;
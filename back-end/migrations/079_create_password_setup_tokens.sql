@@ -0,0 +1,11 @@
+-- Emailed links that let an account without a password (e.g. Google-only) add one.
+-- Following the link re-authenticates the user in place of the password they don't have.
+CREATE TABLE password_setup_tokens (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(255) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_password_setup_tokens_user_id ON password_setup_tokens(user_id);
@@ -0,0 +1,78 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, HeaderMap},
+};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+/// Longest user agent stored with a session
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Device details recorded with a session
+#[derive(Clone, Debug, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// ISO 3166-1 alpha-2 country code, when an edge proxy such as Cloudflare supplies one
+    pub country: Option<String>,
+}
+
+impl ClientInfo {
+    /// Prefer the proxy-supplied client address, falling back to the socket peer
+    fn ip_from_headers(headers: &HeaderMap) -> Option<String> {
+        headers
+            .get("x-forwarded-for")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .or_else(|| headers.get("x-real-ip").and_then(|h| h.to_str().ok()))
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(String::from)
+    }
+
+    /// Country from `CF-IPCountry` (Cloudflare) or `X-Country-Code`, ignoring the
+    /// placeholders proxies use for unknown origins and Tor
+    fn country_from_headers(headers: &HeaderMap) -> Option<String> {
+        headers
+            .get("cf-ipcountry")
+            .or_else(|| headers.get("x-country-code"))
+            .and_then(|h| h.to_str().ok())
+            .map(|code| code.trim().to_ascii_uppercase())
+            .filter(|code| {
+                code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) && code != "XX"
+            })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get("user-agent")
+            .and_then(|h| h.to_str().ok())
+            .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect());
+
+        let ip_address = Self::ip_from_headers(&parts.headers).or_else(|| {
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        });
+
+        Ok(Self {
+            user_agent,
+            ip_address,
+            country: Self::country_from_headers(&parts.headers),
+        })
+    }
+}
@@ -1,11 +1,15 @@
 use crate::{
-    config::JwtConfig,
+    config::{JwtConfig, JwtKeyConfig, LEGACY_JWT_KID},
     error::{AppError, Result},
-    models::UserRole,
+    models::{TokenScope, UserRole},
 };
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,17 +19,65 @@ pub struct Claims {
     pub role: String,
     pub exp: i64,
     pub iat: i64,
+    /// Whether this session completed a second factor (absent in older tokens)
+    #[serde(default)]
+    pub two_factor_verified: bool,
+    /// Session (refresh token) this access token was issued for (absent in older tokens)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Admin acting as this user through support impersonation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<Uuid>,
+    /// Limits a service token to routes accepting this scope; absent on full access tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<TokenScope>,
+}
+
+struct SigningKey {
+    kid: String,
+    algorithm: Algorithm,
+    key: EncodingKey,
 }
 
+/// Signs access tokens with the configured signing key and verifies them against
+/// every configured key, picked by the token's `kid` header
 #[derive(Clone)]
 pub struct JwtService {
     config: JwtConfig,
+    signing_key: Arc<SigningKey>,
+    verifying_keys: Arc<HashMap<String, (Algorithm, DecodingKey)>>,
 }
 
 impl JwtService {
-    #[must_use]
-    pub fn new(config: JwtConfig) -> Self {
-        Self { config }
+    pub fn new(config: JwtConfig) -> Result<Self> {
+        let mut verifying_keys = HashMap::new();
+        let mut signing_key = None;
+
+        for key in &config.keys {
+            let algorithm = parse_algorithm(key)?;
+            verifying_keys.insert(key.kid.clone(), (algorithm, decoding_key(key, algorithm)?));
+
+            if key.kid == config.signing_kid {
+                signing_key = Some(SigningKey {
+                    kid: key.kid.clone(),
+                    algorithm,
+                    key: encoding_key(key, algorithm)?,
+                });
+            }
+        }
+
+        let signing_key = signing_key.ok_or_else(|| {
+            key_error(
+                &config.signing_kid,
+                "is set to sign tokens but is not configured",
+            )
+        })?;
+
+        Ok(Self {
+            config,
+            signing_key: Arc::new(signing_key),
+            verifying_keys: Arc::new(verifying_keys),
+        })
     }
 
     pub fn create_access_token(
@@ -33,6 +85,8 @@ impl JwtService {
         user_id: Uuid,
         email: &str,
         role: &UserRole,
+        two_factor_verified: bool,
+        session_id: Uuid,
     ) -> Result<String> {
         let now = Utc::now();
         let exp = now + Duration::seconds(self.config.access_expiry);
@@ -40,29 +94,165 @@ impl JwtService {
         let claims = Claims {
             sub: user_id.to_string(),
             email: email.to_string(),
-            role: match role {
-                UserRole::Admin => "admin".to_string(),
-                UserRole::User => "user".to_string(),
-            },
+            role: role.as_str().to_string(),
+            exp: exp.timestamp(),
+            iat: now.timestamp(),
+            two_factor_verified,
+            sid: Some(session_id),
+            impersonated_by: None,
+            scope: None,
+        };
+
+        self.sign(&claims)
+    }
+
+    /// Short-lived token that only routes accepting `scope` admit, so a background job
+    /// never holds full account access. It carries the user role and cannot be refreshed.
+    pub fn create_service_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        scope: TokenScope,
+        session_id: Option<Uuid>,
+    ) -> Result<String> {
+        let now = Utc::now();
+        let exp = now + Duration::seconds(self.config.service_token_expiry);
+
+        let claims = Claims {
+            sub: user_id.to_string(),
+            email: email.to_string(),
+            role: UserRole::User.as_str().to_string(),
             exp: exp.timestamp(),
             iat: now.timestamp(),
+            two_factor_verified: false,
+            sid: session_id,
+            impersonated_by: None,
+            scope: Some(scope),
+        };
+
+        self.sign(&claims)
+    }
+
+    /// Seconds a service token stays valid
+    #[must_use]
+    pub fn service_token_expiry(&self) -> i64 {
+        self.config.service_token_expiry
+    }
+
+    /// Access token for an admin acting as `user_id`. It belongs to no session and
+    /// cannot be refreshed.
+    pub fn create_impersonation_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        admin_id: Uuid,
+        expires_in: Duration,
+    ) -> Result<String> {
+        let now = Utc::now();
+
+        let claims = Claims {
+            sub: user_id.to_string(),
+            email: email.to_string(),
+            role: "user".to_string(),
+            exp: (now + expires_in).timestamp(),
+            iat: now.timestamp(),
+            two_factor_verified: false,
+            sid: None,
+            impersonated_by: Some(admin_id),
+            scope: None,
+        };
+
+        self.sign(&claims)
+    }
+
+    fn sign(&self, claims: &Claims) -> Result<String> {
+        let header = Header {
+            kid: Some(self.signing_key.kid.clone()),
+            ..Header::new(self.signing_key.algorithm)
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.config.secret.as_bytes()),
-        )
-        .map_err(|e| AppError::Auth(format!("Failed to create token: {e}")))
+        encode(&header, claims, &self.signing_key.key)
+            .map_err(|e| AppError::Auth(format!("Failed to create token: {e}")))
     }
 
     pub fn verify_token(&self, token: &str) -> Result<Claims> {
-        decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.config.secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map(|data| data.claims)
-        .map_err(|e| AppError::Auth(format!("Invalid token: {e}")))
+        let header =
+            decode_header(token).map_err(|e| AppError::Auth(format!("Invalid token: {e}")))?;
+        // Tokens issued before key rotation carry no kid and were signed with JWT_SECRET
+        let kid = header.kid.as_deref().unwrap_or(LEGACY_JWT_KID);
+        let (algorithm, key) = self
+            .verifying_keys
+            .get(kid)
+            .ok_or_else(|| AppError::Auth("Invalid token: unknown signing key".to_string()))?;
+
+        decode::<Claims>(token, key, &Validation::new(*algorithm))
+            .map(|data| data.claims)
+            .map_err(|e| AppError::Auth(format!("Invalid token: {e}")))
+    }
+}
+
+fn key_error(kid: &str, problem: &str) -> AppError {
+    AppError::Internal(anyhow::anyhow!("JWT key '{kid}' {problem}"))
+}
+
+fn parse_algorithm(key: &JwtKeyConfig) -> Result<Algorithm> {
+    match key.algorithm.to_uppercase().as_str() {
+        "HS256" => Ok(Algorithm::HS256),
+        "RS256" => Ok(Algorithm::RS256),
+        "EDDSA" => Ok(Algorithm::EdDSA),
+        other => Err(key_error(
+            &key.kid,
+            &format!("has unsupported algorithm {other}; use HS256, RS256 or EdDSA"),
+        )),
     }
 }
+
+fn require_material<'a>(
+    key: &'a JwtKeyConfig,
+    value: &'a Option<String>,
+    name: &str,
+) -> Result<&'a [u8]> {
+    value
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .map(str::as_bytes)
+        .ok_or_else(|| key_error(&key.kid, &format!("is missing its {name}")))
+}
+
+fn encoding_key(key: &JwtKeyConfig, algorithm: Algorithm) -> Result<EncodingKey> {
+    let encoding_key = match algorithm {
+        Algorithm::RS256 => {
+            EncodingKey::from_rsa_pem(require_material(key, &key.private_key, "private key")?)
+        }
+        Algorithm::EdDSA => {
+            EncodingKey::from_ed_pem(require_material(key, &key.private_key, "private key")?)
+        }
+        _ => {
+            return Ok(EncodingKey::from_secret(require_material(
+                key,
+                &key.secret,
+                "secret",
+            )?))
+        }
+    };
+    encoding_key.map_err(|e| key_error(&key.kid, &format!("has an invalid private key: {e}")))
+}
+
+fn decoding_key(key: &JwtKeyConfig, algorithm: Algorithm) -> Result<DecodingKey> {
+    let decoding_key = match algorithm {
+        Algorithm::RS256 => {
+            DecodingKey::from_rsa_pem(require_material(key, &key.public_key, "public key")?)
+        }
+        Algorithm::EdDSA => {
+            DecodingKey::from_ed_pem(require_material(key, &key.public_key, "public key")?)
+        }
+        _ => {
+            return Ok(DecodingKey::from_secret(require_material(
+                key,
+                &key.secret,
+                "secret",
+            )?))
+        }
+    };
+    decoding_key.map_err(|e| key_error(&key.kid, &format!("has an invalid public key: {e}")))
+}
@@ -1,7 +1,9 @@
 use crate::{
     auth::JwtService,
     error::{AppError, Result},
-    models::UserRole,
+    models::{ApiClient, ApiKeyScope, Permission, TokenScope, UserRole},
+    rate_limit::{RateLimitKey, RateLimiter},
+    services::ApiKeyService,
 };
 use axum::{
     async_trait,
@@ -10,6 +12,7 @@ use axum::{
     middleware::Next,
     response::Response,
 };
+use sqlx::PgPool;
 use uuid::Uuid;
 
 #[derive(Clone, Debug)]
@@ -17,6 +20,12 @@ pub struct AuthUser {
     pub id: Uuid,
     pub email: String,
     pub role: UserRole,
+    /// The session completed a TOTP or backup-code check
+    pub two_factor_verified: bool,
+    /// The session this access token belongs to, if the token records one
+    pub session_id: Option<Uuid>,
+    /// Set when an admin is acting as this user through support impersonation
+    pub impersonated_by: Option<Uuid>,
 }
 
 // Implement extractor for AuthUser
@@ -39,8 +48,19 @@ where
     }
 }
 
+/// State for `require_auth`: token verification plus the database for revocation checks
+#[derive(Clone)]
+pub struct AuthMiddlewareState {
+    pub jwt_service: JwtService,
+    pub pool: PgPool,
+    /// Per-user limit applied to every authenticated request
+    pub rate_limiter: Option<RateLimiter>,
+    /// Service token scope these routes admit besides full access tokens
+    pub token_scope: Option<TokenScope>,
+}
+
 pub async fn require_auth(
-    State(jwt_service): State<JwtService>,
+    State(state): State<AuthMiddlewareState>,
     mut req: Request,
     next: Next,
 ) -> Result<Response> {
@@ -54,37 +74,165 @@ pub async fn require_auth(
         .strip_prefix("Bearer ")
         .ok_or(AppError::Unauthorized)?;
 
-    let claims = jwt_service.verify_token(token)?;
+    let claims = state.jwt_service.verify_token(token)?;
+
+    if let Some(scope) = claims.scope {
+        if state.token_scope != Some(scope) {
+            return Err(AppError::Forbidden(format!(
+                "A {} token cannot be used here",
+                scope.as_str()
+            )));
+        }
+    }
 
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Auth("Invalid user ID in token".to_string()))?;
 
-    let role = match claims.role.as_str() {
-        "admin" => UserRole::Admin,
-        "user" => UserRole::User,
-        _ => return Err(AppError::Auth("Invalid role in token".to_string())),
+    // Tokens issued before the user's last "logout everywhere" (or password reset) are revoked.
+    // `iat` has one-second resolution, so tokens minted in the same second are still accepted.
+    let user = sqlx::query!(
+        r#"SELECT tokens_invalidated_at, role AS "role: UserRole" FROM users WHERE id = $1"#,
+        user_id
+    )
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::Unauthorized)?;
+
+    if user
+        .tokens_invalidated_at
+        .is_some_and(|at| claims.iat < at.timestamp())
+    {
+        return Err(AppError::Auth("Token has been revoked".to_string()));
+    }
+
+    // The stored role wins over the token's, so a demotion takes effect at once.
+    // Impersonation and service tokens never carry the user's privileges.
+    let role = if claims.impersonated_by.is_some() || claims.scope.is_some() {
+        UserRole::User
+    } else {
+        user.role
     };
 
     let auth_user = AuthUser {
         id: user_id,
         email: claims.email,
         role,
+        two_factor_verified: claims.two_factor_verified,
+        session_id: claims.sid,
+        impersonated_by: claims.impersonated_by,
+    };
+
+    if let Some(admin_id) = auth_user.impersonated_by {
+        tracing::info!(
+            "Admin {} acting as user {}: {} {}",
+            admin_id,
+            auth_user.id,
+            req.method(),
+            req.uri().path()
+        );
+    }
+
+    let rate_limit = match &state.rate_limiter {
+        Some(limiter) => {
+            let key = RateLimitKey::User(auth_user.id);
+            match limiter.check(&key) {
+                Ok(status) => Some(status),
+                Err(retry_after) => return Ok(limiter.too_many_requests(&key, retry_after)),
+            }
+        }
+        None => None,
     };
 
     req.extensions_mut().insert(auth_user);
 
+    let mut response = next.run(req).await;
+    if let Some(status) = rate_limit {
+        status.apply(&mut response);
+    }
+    Ok(response)
+}
+
+/// Header partner services send their API key in
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// State for `accept_api_key`
+#[derive(Clone)]
+pub struct ApiKeyMiddlewareState {
+    pub api_key_service: ApiKeyService,
+    /// Scope the route requires of API keys
+    pub scope: ApiKeyScope,
+    /// Bearer-token auth for requests without a key; `None` leaves the route public
+    pub auth: Option<AuthMiddlewareState>,
+}
+
+/// Lets partner services call a route with an `X-Api-Key` header, recording them as an
+/// `ApiClient`. Requests without a key fall through to `require_auth`, or pass as
+/// anonymous on public routes.
+pub async fn accept_api_key(
+    State(state): State<ApiKeyMiddlewareState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response> {
+    let api_key = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .map(|h| h.to_str().map(str::to_string))
+        .transpose()
+        .map_err(|_| AppError::Auth("Invalid API key".to_string()))?;
+
+    if let Some(api_key) = api_key {
+        let (key, rate_limit) = state
+            .api_key_service
+            .authenticate(&api_key, state.scope)
+            .await?;
+        req.extensions_mut().insert(ApiClient {
+            key_id: key.id,
+            name: key.name,
+        });
+        let mut response = next.run(req).await;
+        rate_limit.apply(&mut response);
+        return Ok(response);
+    }
+
+    match state.auth {
+        Some(auth) => require_auth(State(auth), req, next).await,
+        None => Ok(next.run(req).await),
+    }
+}
+
+/// Admit only users whose role grants `permission`; must run after `require_auth`
+pub async fn require_permission(
+    State(permission): State<Permission>,
+    req: Request,
+    next: Next,
+) -> Result<Response> {
+    let auth_user = req
+        .extensions()
+        .get::<AuthUser>()
+        .ok_or(AppError::Unauthorized)?;
+
+    if !auth_user.role.has_permission(permission) {
+        return Err(AppError::Forbidden(format!(
+            "Missing permission {permission:?}"
+        )));
+    }
+
     Ok(next.run(req).await)
 }
 
-pub async fn require_admin(req: Request, next: Next) -> Result<Response> {
+/// Keep support impersonation read-only for the account itself: sessions, credentials,
+/// two-factor and personal data stay with the real user. Must run after `require_auth`.
+pub async fn reject_impersonation(req: Request, next: Next) -> Result<Response> {
     let auth_user = req
         .extensions()
         .get::<AuthUser>()
-        .ok_or(AppError::Unauthorized)?
-        .clone();
+        .ok_or(AppError::Unauthorized)?;
 
-    match auth_user.role {
-        UserRole::Admin => Ok(next.run(req).await),
-        _ => Err(AppError::Forbidden("Admin access required".to_string())),
+    if auth_user.impersonated_by.is_some() {
+        return Err(AppError::Forbidden(
+            "Not available while impersonating a user".to_string(),
+        ));
     }
+
+    Ok(next.run(req).await)
 }
@@ -1,7 +1,9 @@
+pub mod client;
 pub mod jwt;
 pub mod middleware;
 pub mod tokens;
 
+pub use client::*;
 pub use jwt::*;
 pub use middleware::*;
 pub use tokens::*;
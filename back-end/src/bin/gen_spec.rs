@@ -1,11 +1,10 @@
-use back_end::ApiDoc;
+use back_end::{openapi, ApiDoc};
 use utoipa::OpenApi;
 
 fn main() {
     println!(
         "{}",
-        ApiDoc::openapi()
-            .to_pretty_json()
+        serde_json::to_string_pretty(&openapi::to_json(&ApiDoc::openapi()))
             .expect("Failed to serialize OpenAPI spec")
     );
 }
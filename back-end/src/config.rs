@@ -1,6 +1,9 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
+use std::net::IpAddr;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -11,10 +14,26 @@ pub struct Config {
     pub email: EmailConfig,
     pub rate_limit: RateLimitConfig,
     pub image: ImageConfig,
+    pub image_access: ImageAccessConfig,
     pub scoring: ScoringConfig,
     pub s3: S3Config,
+    pub geocoding: GeocodingConfig,
+    pub jobs: JobsConfig,
     pub tls: Option<TlsConfig>,
-    pub enable_test_helpers: bool,
+    /// Bot check on registration and email-sending endpoints; `None` disables it
+    pub captcha: Option<CaptchaConfig>,
+    /// Access rules for the `/api/test/*` helpers; `None` leaves them unmounted
+    pub test_helpers: Option<TestHelpersConfig>,
+    pub latency: LatencyConfig,
+    pub moderation: ModerationConfig,
+    pub location_check: LocationCheckConfig,
+    /// Engagement tracking and the trending feed; `None` switches both off
+    pub feed_trending: Option<FeedTrendingConfig>,
+    /// Machine translation of report descriptions; `None` switches it off
+    pub translation: Option<TranslationConfig>,
+    /// Malware scanning of uploaded images before they are stored; `None` switches it off
+    pub upload_scan: Option<UploadScanConfig>,
+    pub features: FeaturesConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -26,13 +45,49 @@ pub struct ServerConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct DatabaseConfig {
     pub url: String,
+    /// Longest a single statement may run before Postgres cancels it; 0 for no limit
+    pub statement_timeout_ms: u64,
 }
 
+impl DatabaseConfig {
+    /// Just the database settings, for commands that need nothing else
+    pub fn from_env() -> anyhow::Result<Self> {
+        dotenvy::dotenv().ok();
+
+        Ok(Self {
+            url: require_env("DATABASE_URL")?,
+            statement_timeout_ms: env_or_default("DATABASE_STATEMENT_TIMEOUT_MS", "15000")?
+                .parse()?,
+        })
+    }
+}
+
+/// Key id given to `JWT_SECRET`, and assumed for tokens issued without a `kid` header
+pub const LEGACY_JWT_KID: &str = "default";
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct JwtConfig {
-    pub secret: String,
+    /// Key that signs new tokens
+    pub signing_kid: String,
+    /// Every key whose tokens are accepted, including retired ones
+    pub keys: Vec<JwtKeyConfig>,
     pub access_expiry: i64,
     pub refresh_expiry: i64,
+    /// Lifetime of scoped service tokens, e.g. for background photo uploads
+    pub service_token_expiry: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtKeyConfig {
+    pub kid: String,
+    /// HS256, RS256 or EdDSA
+    pub algorithm: String,
+    /// Shared secret for HS256
+    pub secret: Option<String>,
+    /// PEM private key for RS256/EdDSA; only needed on the signing key
+    pub private_key: Option<String>,
+    /// PEM public key for RS256/EdDSA
+    pub public_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -53,6 +108,8 @@ pub struct EmailConfig {
     pub verification_expiry_hours: i64,
     pub password_reset_expiry_hours: i64,
     pub frontend_url: String,
+    /// Shared secret expected on bounce/complaint webhook calls; webhooks are rejected when unset
+    pub webhook_secret: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -63,6 +120,14 @@ pub struct RateLimitConfig {
     pub general_per_min: u32,
     pub email_verification_per_hour: u32,
     pub password_reset_per_hour: u32,
+    /// Public report photo requests, per IP
+    pub images_per_min: u32,
+    /// Report creations/clears and feed posts one user may have running at once
+    pub concurrent_uploads_per_user: u32,
+    /// Take the client IP from `X-Forwarded-For`/`X-Real-IP` (set behind a reverse proxy)
+    pub trust_proxy_headers: bool,
+    /// Share of a limit after which responses carry `X-RateLimit-Warning` (0 disables)
+    pub warn_percent: u32,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -73,6 +138,23 @@ pub struct ImageConfig {
     pub max_height: u32,
 }
 
+/// Rules for the public `/api/images/*` routes
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageAccessConfig {
+    /// Key that signs image URLs; `None` leaves signing off
+    pub signing_secret: Option<String>,
+    /// Refuse image requests without a valid signature (needs `signing_secret`)
+    pub require_signed_urls: bool,
+    /// How long a signed URL stays valid
+    pub signed_url_ttl_secs: i64,
+    /// Hosts allowed in the `Referer` header, e.g. `littypicky.app`; empty allows any
+    pub allowed_referers: Vec<String>,
+    /// Let requests without a `Referer` through when `allowed_referers` is set
+    pub allow_missing_referer: bool,
+    /// Log every image request with its client IP, referer and status
+    pub access_log: bool,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ScoringConfig {
     pub min_clears_to_verify: i32,
@@ -82,19 +164,318 @@ pub struct ScoringConfig {
     pub streak_bonus_points: i32,
     pub first_in_area_bonus: i32,
     pub verification_bonus: i32,
+    /// Extra points for a verification backed by an on-site photo
+    pub photo_verification_bonus: i32,
     pub verified_report_bonus: i32,
+    /// How long a clearer has to upload a better after photo once a verifier asks
+    pub photo_retake_window_hours: i64,
+    /// How long a verifier's reservation on a cleared report holds its slot
+    pub verification_reservation_mins: i64,
+    /// Days a clear may wait for verifiers before it is settled on the verdicts so far
+    pub verification_window_days: i64,
+    /// IANA time zone weekly and monthly leaderboards turn over in, e.g. `Europe/London`
+    pub leaderboard_timezone: String,
+    /// Multipliers applied to a clear's base points by report severity
+    pub small_severity_multiplier: f64,
+    pub medium_severity_multiplier: f64,
+    pub large_severity_multiplier: f64,
+    /// Points each attendee of a cleanup event gets per linked report cleared during it
+    pub cleanup_event_bonus: i32,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct S3Config {
     pub endpoint: String,
     pub region: String,
-    pub bucket: String,
     pub access_key: String,
     pub secret_key: String,
+    /// Prepended to every object key (e.g. `staging` or a tenant id); empty for none
+    pub key_prefix: String,
+    /// Report photos held in the database while storage is unreachable, across all
+    /// waiting photos; 0 refuses uploads instead
+    pub spill_max_mb: u64,
+    /// Storage class photos of expired reports move to; `None` leaves them where they are
+    pub archive_storage_class: Option<String>,
+    pub avatars: S3BucketConfig,
+    pub report_photos: S3BucketConfig,
+    pub exports: S3BucketConfig,
+}
+
+/// Where one kind of object is stored. Several kinds may share a bucket.
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3BucketConfig {
+    pub bucket: String,
+    /// Base URL objects in this bucket are served from
     pub public_url: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum GeocodingProvider {
+    /// Nominatim, public or self-hosted
+    Nominatim,
+    Google,
+    /// Photon, usually self-hosted
+    Photon,
+}
+
+impl GeocodingProvider {
+    #[must_use]
+    pub fn default_api_url(self) -> &'static str {
+        match self {
+            Self::Nominatim => "https://nominatim.openstreetmap.org",
+            Self::Google => "https://maps.googleapis.com",
+            Self::Photon => "https://photon.komoot.io",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeocodingConfig {
+    pub provider: GeocodingProvider,
+    pub api_url: String,
+    /// Required by Google
+    pub api_key: Option<String>,
+    /// Minimum gap between requests; the public Nominatim allows one per second
+    pub min_interval_ms: u64,
+    /// Decimal places coordinates are rounded to for the cache; 4 is about 11 m
+    pub cache_decimals: u32,
+    /// How long a cached address is used before it is looked up again
+    pub cache_ttl_days: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobsConfig {
+    /// How often to remind nearby verifiers about cleared reports; 0 disables the job
+    pub verification_reminder_interval_mins: u64,
+    pub verification_reminders_per_day: i64,
+    /// How often to repair orphaned rows and dangling references; 0 disables the job
+    pub consistency_check_interval_mins: u64,
+    /// How often to remind users who never verified their email; 0 disables the job
+    pub email_verification_reminder_interval_mins: u64,
+    /// How long after registering an unverified user gets their reminder
+    pub email_verification_reminder_after_hours: i64,
+    /// How often to release claims nobody has cleared; 0 disables the job
+    pub claim_expiry_interval_mins: u64,
+    /// How long a claim may stay uncleared before it is released
+    pub claim_expiry_hours: i64,
+    /// How often to reopen disputed clears nobody has settled; 0 disables the job
+    pub clear_reopen_interval_mins: u64,
+    /// How long admins have to settle a dispute before the report is reopened
+    pub clear_reopen_after_hours: i64,
+    /// How often to finalize clears past the verification window; 0 disables the job
+    pub verification_window_interval_mins: u64,
+    /// How often to retry outbox emails that failed to send; 0 disables the job
+    pub email_outbox_interval_mins: u64,
+    /// How often to retry report addresses that failed to geocode; 0 disables the job
+    pub geocode_retry_interval_mins: u64,
+    /// How often to upload photos held back while storage was down; 0 disables the job
+    pub pending_upload_interval_mins: u64,
+    /// How often to expire pending reports nobody has cleared; 0 disables the job
+    pub report_expiry_interval_mins: u64,
+    /// How long a report may stay pending before it expires
+    pub report_expiry_months: i32,
+    /// How often to recompute the public stats summary; 0 disables the job
+    pub stats_summary_interval_mins: u64,
+}
+
+/// Services whose CAPTCHA tokens we can check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum CaptchaProvider {
+    HCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    /// The provider's server-side token verification endpoint
+    #[must_use]
+    pub fn default_verify_url(self) -> &'static str {
+        match self {
+            Self::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            Self::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+        }
+    }
+}
+
+/// Machine translation services report descriptions can be translated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TranslationProvider {
+    /// LibreTranslate, hosted or self-hosted
+    LibreTranslate,
+    DeepL,
+}
+
+impl TranslationProvider {
+    #[must_use]
+    pub fn default_api_url(self) -> &'static str {
+        match self {
+            Self::LibreTranslate => "https://libretranslate.com",
+            Self::DeepL => "https://api-free.deepl.com",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranslationConfig {
+    pub provider: TranslationProvider,
+    pub api_url: String,
+    /// Required by DeepL; optional for a self-hosted LibreTranslate
+    pub api_key: Option<String>,
+}
+
+/// Malware scanners uploads can be checked with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum UploadScanProvider {
+    /// A `clamd` daemon, spoken to over TCP with `INSTREAM`
+    ClamAv,
+    /// An HTTP endpoint (e.g. a cloud function) that takes the raw bytes and answers
+    /// `{"clean": bool, "signature": string|null}`
+    Http,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadScanConfig {
+    pub provider: UploadScanProvider,
+    /// `host:port` of clamd, or the HTTP scanner's URL
+    pub address: String,
+    /// Sent as a bearer token to the HTTP scanner
+    pub api_key: Option<String>,
+    pub timeout_secs: u64,
+    /// Accept uploads when the scanner cannot be reached instead of refusing them
+    pub fail_open: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaptchaConfig {
+    pub provider: CaptchaProvider,
+    pub secret_key: String,
+    pub verify_url: String,
+}
+
+/// An IP network in CIDR notation; a bare address is a single host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// True for `0.0.0.0/0` and `::/0`
+    #[must_use]
+    pub fn matches_everything(&self) -> bool {
+        self.prefix_len == 0
+    }
+
+    #[must_use]
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 peers may show up as IPv4-mapped IPv6 addresses on dual-stack sockets
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.trim().split_once('/') {
+            Some((addr, prefix_len)) => (addr.parse::<IpAddr>()?, Some(prefix_len.parse::<u8>()?)),
+            None => (s.trim().parse::<IpAddr>()?, None),
+        };
+        let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_prefix_len);
+        if prefix_len > max_prefix_len {
+            anyhow::bail!("Invalid prefix length in network {s}");
+        }
+
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// Guards the test helper endpoints, which can verify accounts and delete data
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestHelpersConfig {
+    /// Networks callers must connect from; proxy headers are not consulted
+    pub allowed_networks: Vec<IpNetwork>,
+    /// Expected in the `X-Test-Helpers-Secret` header
+    pub secret: String,
+}
+
+/// Optional modules a deployment can leave unmounted; advertised at `/api/capabilities`
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct FeaturesConfig {
+    /// The community feed under `/api/feed`
+    pub feed: bool,
+    /// Cleanup events under `/api/cleanup-events`
+    pub cleanup_events: bool,
+    /// The public open data export under `/api/open-data`
+    pub open_data: bool,
+}
+
+/// Response-time budgets checked by the latency middleware
+#[derive(Debug, Clone, Deserialize)]
+pub struct LatencyConfig {
+    /// Budget for routes without their own entry
+    pub default_budget_ms: u64,
+    /// Budgets keyed by method and route pattern, e.g. `GET /api/reports/nearby`
+    pub route_budgets_ms: HashMap<String, u64>,
+}
+
+impl LatencyConfig {
+    #[must_use]
+    pub fn budget_ms(&self, route: &str) -> u64 {
+        self.route_budgets_ms
+            .get(route)
+            .copied()
+            .unwrap_or(self.default_budget_ms)
+    }
+}
+
+/// When user flags take a report off the map
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModerationConfig {
+    /// Open flags from different users that hide a report until a moderator reviews it
+    pub report_flags_to_hide: i64,
+}
+
+/// How far a report photo's EXIF data may stray from the report
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocationCheckConfig {
+    /// Photos taken at least this far from the reported location are refused
+    pub reject_distance_km: f64,
+    /// Photos taken longer ago than this lower the report's location confidence
+    pub max_photo_age_hours: i64,
+}
+
+/// How feed engagement is collected and scored for the trending feed
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedTrendingConfig {
+    /// Hours for an engagement event's weight to halve
+    pub half_life_hours: f64,
+    /// How often to recompute post scores; 0 disables the job
+    pub score_interval_mins: u64,
+    /// How often buffered engagement is written out
+    pub flush_interval_secs: u64,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct TlsConfig {
     pub cert_path: String,
@@ -105,22 +486,312 @@ impl Config {
     pub fn from_env() -> Result<Self, anyhow::Error> {
         dotenvy::dotenv().ok();
 
-        fn read_env_file_value(key: &str) -> Option<String> {
-            let file_key = format!("{key}_FILE");
-            if let Ok(path) = env::var(file_key) {
-                if let Ok(contents) = fs::read_to_string(path) {
-                    return Some(contents.trim().to_string());
+        // JWT_SECRET stays valid as the "default" key; JWT_KEY_IDS lists further keys,
+        // each configured through JWT_KEY_<ID>_ALGORITHM/_SECRET/_PRIVATE_KEY/_PUBLIC_KEY
+        fn jwt_config() -> Result<JwtConfig, anyhow::Error> {
+            let mut keys = Vec::new();
+            if let Some(secret) = read_env_file_value("JWT_SECRET").filter(|s| !s.is_empty()) {
+                keys.push(JwtKeyConfig {
+                    kid: LEGACY_JWT_KID.to_string(),
+                    algorithm: "HS256".to_string(),
+                    secret: Some(secret),
+                    private_key: None,
+                    public_key: None,
+                });
+            }
+
+            let kids = env_or_default("JWT_KEY_IDS", "")?;
+            for kid in kids.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+                let prefix = format!("JWT_KEY_{}", kid.to_uppercase().replace('-', "_"));
+                keys.push(JwtKeyConfig {
+                    kid: kid.to_string(),
+                    algorithm: env_or_default(&format!("{prefix}_ALGORITHM"), "HS256")?,
+                    secret: read_env_file_value(&format!("{prefix}_SECRET")),
+                    private_key: read_env_file_value(&format!("{prefix}_PRIVATE_KEY")),
+                    public_key: read_env_file_value(&format!("{prefix}_PUBLIC_KEY")),
+                });
+            }
+
+            // The last listed key signs unless JWT_SIGNING_KID pins another
+            let default_signing_kid = keys
+                .last()
+                .map(|k| k.kid.clone())
+                .ok_or_else(|| anyhow::anyhow!("Missing env var JWT_SECRET or JWT_KEY_IDS"))?;
+
+            Ok(JwtConfig {
+                signing_kid: env_or_default("JWT_SIGNING_KID", &default_signing_kid)?,
+                keys,
+                access_expiry: env_or_default("JWT_ACCESS_EXPIRY", "900")?.parse()?,
+                refresh_expiry: env_or_default("JWT_REFRESH_EXPIRY", "2592000")?.parse()?,
+                service_token_expiry: env_or_default("JWT_SERVICE_TOKEN_EXPIRY", "3600")?
+                    .parse()?,
+            })
+        }
+
+        // S3_BUCKET/S3_PUBLIC_URL remain the default; S3_<AREA>_BUCKET moves one kind of
+        // object elsewhere, served from S3_<AREA>_PUBLIC_URL (or the bucket's endpoint path)
+        fn s3_bucket_config(area: &str) -> Result<S3BucketConfig, anyhow::Error> {
+            match read_env_file_value(&format!("S3_{area}_BUCKET")).filter(|b| !b.is_empty()) {
+                Some(bucket) => {
+                    let endpoint = env_or_default("S3_ENDPOINT", "http://127.0.0.1:9000")?;
+                    Ok(S3BucketConfig {
+                        public_url: env_or_default(
+                            &format!("S3_{area}_PUBLIC_URL"),
+                            &format!("{}/{}", endpoint.trim_end_matches('/'), bucket),
+                        )?,
+                        bucket,
+                    })
                 }
+                None => Ok(S3BucketConfig {
+                    bucket: env_or_default("S3_BUCKET", "littypicky-images")?,
+                    public_url: env_or_default(
+                        "S3_PUBLIC_URL",
+                        "http://127.0.0.1:9000/littypicky-images",
+                    )?,
+                }),
+            }
+        }
+
+        // CAPTCHA_PROVIDER=hcaptcha|turnstile turns the check on; empty or "none" leaves it off
+        fn captcha_config() -> Result<Option<CaptchaConfig>, anyhow::Error> {
+            let provider = match env_or_default("CAPTCHA_PROVIDER", "")?
+                .trim()
+                .to_lowercase()
+                .as_str()
+            {
+                "" | "none" => return Ok(None),
+                "hcaptcha" => CaptchaProvider::HCaptcha,
+                "turnstile" => CaptchaProvider::Turnstile,
+                other => anyhow::bail!("Unknown CAPTCHA_PROVIDER {other}"),
+            };
+
+            Ok(Some(CaptchaConfig {
+                provider,
+                secret_key: read_env_file_value("CAPTCHA_SECRET_KEY")
+                    .filter(|s| !s.is_empty())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("CAPTCHA_PROVIDER requires CAPTCHA_SECRET_KEY")
+                    })?,
+                verify_url: env_or_default("CAPTCHA_VERIFY_URL", provider.default_verify_url())?,
+            }))
+        }
+
+        // Only classes objects can still be read from straight away, since expired
+        // reports' photos stay viewable
+        fn archive_storage_class() -> Result<Option<String>, anyhow::Error> {
+            match env_or_default("S3_ARCHIVE_STORAGE_CLASS", "STANDARD_IA")?
+                .trim()
+                .to_uppercase()
+                .as_str()
+            {
+                "" | "NONE" => Ok(None),
+                class @ ("STANDARD_IA" | "ONEZONE_IA" | "GLACIER_IR") => {
+                    Ok(Some(class.to_string()))
+                }
+                other => anyhow::bail!(
+                    "S3_ARCHIVE_STORAGE_CLASS must be STANDARD_IA, ONEZONE_IA or GLACIER_IR, not {other}"
+                ),
+            }
+        }
+
+        // UPLOAD_SCAN_PROVIDER=clamav|http turns scanning on; empty or "none" leaves it off
+        fn upload_scan_config() -> Result<Option<UploadScanConfig>, anyhow::Error> {
+            let provider = match env_or_default("UPLOAD_SCAN_PROVIDER", "")?
+                .trim()
+                .to_lowercase()
+                .as_str()
+            {
+                "" | "none" => return Ok(None),
+                "clamav" => UploadScanProvider::ClamAv,
+                "http" => UploadScanProvider::Http,
+                other => anyhow::bail!("Unknown UPLOAD_SCAN_PROVIDER {other}"),
+            };
+
+            let address = match provider {
+                UploadScanProvider::ClamAv => {
+                    env_or_default("UPLOAD_SCAN_ADDRESS", "127.0.0.1:3310")?
+                }
+                UploadScanProvider::Http => env_or_default("UPLOAD_SCAN_ADDRESS", "")?,
+            };
+            if address.trim().is_empty() {
+                anyhow::bail!("UPLOAD_SCAN_PROVIDER=http requires UPLOAD_SCAN_ADDRESS");
+            }
+
+            Ok(Some(UploadScanConfig {
+                provider,
+                address: address.trim().to_string(),
+                api_key: read_env_file_value("UPLOAD_SCAN_API_KEY").filter(|s| !s.is_empty()),
+                timeout_secs: env_or_default("UPLOAD_SCAN_TIMEOUT_SECS", "30")?.parse()?,
+                fail_open: env_or_default("UPLOAD_SCAN_FAIL_OPEN", "false")?.parse()?,
+            }))
+        }
+
+        // TRANSLATION_PROVIDER=libretranslate|deepl turns translation on; empty or "none" leaves it off
+        fn translation_config() -> Result<Option<TranslationConfig>, anyhow::Error> {
+            let provider = match env_or_default("TRANSLATION_PROVIDER", "")?
+                .trim()
+                .to_lowercase()
+                .as_str()
+            {
+                "" | "none" => return Ok(None),
+                "libretranslate" => TranslationProvider::LibreTranslate,
+                "deepl" => TranslationProvider::DeepL,
+                other => anyhow::bail!("Unknown TRANSLATION_PROVIDER {other}"),
+            };
+
+            let api_key = read_env_file_value("TRANSLATION_API_KEY").filter(|s| !s.is_empty());
+            if provider == TranslationProvider::DeepL && api_key.is_none() {
+                anyhow::bail!("TRANSLATION_PROVIDER=deepl requires TRANSLATION_API_KEY");
+            }
+
+            Ok(Some(TranslationConfig {
+                provider,
+                api_url: env_or_default("TRANSLATION_API_URL", provider.default_api_url())?
+                    .trim_end_matches('/')
+                    .to_string(),
+                api_key,
+            }))
+        }
+
+        fn image_access_config() -> Result<ImageAccessConfig, anyhow::Error> {
+            let signing_secret =
+                read_env_file_value("IMAGE_URL_SIGNING_SECRET").filter(|s| !s.is_empty());
+            let require_signed_urls: bool =
+                env_or_default("IMAGE_REQUIRE_SIGNED_URLS", "false")?.parse()?;
+            if require_signed_urls && signing_secret.is_none() {
+                anyhow::bail!("IMAGE_REQUIRE_SIGNED_URLS requires IMAGE_URL_SIGNING_SECRET");
             }
-            env::var(key).ok()
+
+            Ok(ImageAccessConfig {
+                signing_secret,
+                require_signed_urls,
+                signed_url_ttl_secs: env_or_default("IMAGE_SIGNED_URL_TTL_SECS", "3600")?
+                    .parse::<i64>()?
+                    .max(1),
+                allowed_referers: env_or_default("IMAGE_ALLOWED_REFERERS", "")?
+                    .split(',')
+                    .map(|host| host.trim().to_lowercase())
+                    .filter(|host| !host.is_empty())
+                    .collect(),
+                allow_missing_referer: env_or_default("IMAGE_ALLOW_MISSING_REFERER", "true")?
+                    .parse()?,
+                access_log: env_or_default("IMAGE_ACCESS_LOG", "true")?.parse()?,
+            })
         }
 
-        fn require_env(key: &str) -> Result<String, anyhow::Error> {
-            read_env_file_value(key).ok_or_else(|| anyhow::anyhow!("Missing env var {key}"))
+        // GEOCODING_PROVIDER picks nominatim (default), google or photon; NOMINATIM_URL
+        // still sets Nominatim's URL when GEOCODING_API_URL is unset
+        fn geocoding_config() -> Result<GeocodingConfig, anyhow::Error> {
+            let provider = match env_or_default("GEOCODING_PROVIDER", "")?
+                .trim()
+                .to_lowercase()
+                .as_str()
+            {
+                "" | "nominatim" => GeocodingProvider::Nominatim,
+                "google" => GeocodingProvider::Google,
+                "photon" => GeocodingProvider::Photon,
+                other => anyhow::bail!("Unknown GEOCODING_PROVIDER {other}"),
+            };
+
+            let api_key = read_env_file_value("GEOCODING_API_KEY").filter(|s| !s.is_empty());
+            if provider == GeocodingProvider::Google && api_key.is_none() {
+                anyhow::bail!("GEOCODING_PROVIDER=google requires GEOCODING_API_KEY");
+            }
+
+            let default_api_url = match provider {
+                GeocodingProvider::Nominatim => {
+                    env_or_default("NOMINATIM_URL", provider.default_api_url())?
+                }
+                _ => provider.default_api_url().to_string(),
+            };
+
+            Ok(GeocodingConfig {
+                provider,
+                api_url: env_or_default("GEOCODING_API_URL", &default_api_url)?
+                    .trim_end_matches('/')
+                    .to_string(),
+                api_key,
+                min_interval_ms: env_or_default("GEOCODE_MIN_INTERVAL_MS", "1000")?.parse()?,
+                cache_decimals: env_or_default("GEOCODE_CACHE_DECIMALS", "4")?
+                    .parse::<u32>()?
+                    .min(7),
+                cache_ttl_days: env_or_default("GEOCODE_CACHE_TTL_DAYS", "30")?.parse()?,
+            })
         }
 
-        fn env_or_default(key: &str, default: &str) -> Result<String, anyhow::Error> {
-            Ok(read_env_file_value(key).unwrap_or_else(|| default.to_string()))
+        fn test_helpers_config() -> Result<Option<TestHelpersConfig>, anyhow::Error> {
+            if !env_or_default("ENABLE_TEST_HELPERS", "false")?
+                .parse()
+                .unwrap_or(false)
+            {
+                return Ok(None);
+            }
+
+            // Enabling the helpers alone must never expose them, so both guards are required
+            let allowed_networks = env_or_default("TEST_HELPERS_ALLOWED_IPS", "")?
+                .split(',')
+                .filter(|network| !network.trim().is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<IpNetwork>, _>>()?;
+            if allowed_networks.is_empty() {
+                anyhow::bail!("ENABLE_TEST_HELPERS requires TEST_HELPERS_ALLOWED_IPS");
+            }
+
+            Ok(Some(TestHelpersConfig {
+                allowed_networks,
+                secret: read_env_file_value("TEST_HELPERS_SECRET")
+                    .filter(|s| !s.is_empty())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("ENABLE_TEST_HELPERS requires TEST_HELPERS_SECRET")
+                    })?,
+            }))
+        }
+
+        fn feed_trending_config() -> Result<Option<FeedTrendingConfig>, anyhow::Error> {
+            if !env_or_default("FEED_TRENDING_ENABLED", "false")?
+                .parse()
+                .unwrap_or(false)
+            {
+                return Ok(None);
+            }
+
+            let half_life_hours: f64 =
+                env_or_default("FEED_TRENDING_HALF_LIFE_HOURS", "24")?.parse()?;
+            if half_life_hours <= 0.0 {
+                anyhow::bail!("FEED_TRENDING_HALF_LIFE_HOURS must be positive");
+            }
+
+            Ok(Some(FeedTrendingConfig {
+                half_life_hours,
+                score_interval_mins: env_or_default("FEED_TRENDING_SCORE_INTERVAL_MINS", "10")?
+                    .parse()?,
+                flush_interval_secs: env_or_default("FEED_ENGAGEMENT_FLUSH_SECS", "5")?
+                    .parse::<u64>()?
+                    .max(1),
+            }))
+        }
+
+        // LATENCY_BUDGETS is a comma-separated list of `METHOD /route=ms` entries
+        fn latency_config() -> Result<LatencyConfig, anyhow::Error> {
+            let route_budgets_ms = env_or_default(
+                "LATENCY_BUDGETS",
+                "GET /api/reports/nearby=250,GET /api/feed=400,GET /api/leaderboards=400",
+            )?
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                let (route, ms) = entry
+                    .rsplit_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Invalid LATENCY_BUDGETS entry {entry}"))?;
+                let route = route.split_whitespace().collect::<Vec<_>>().join(" ");
+                Ok((route, ms.trim().parse()?))
+            })
+            .collect::<Result<HashMap<String, u64>, anyhow::Error>>()?;
+
+            Ok(LatencyConfig {
+                default_budget_ms: env_or_default("LATENCY_BUDGET_DEFAULT_MS", "1000")?.parse()?,
+                route_budgets_ms,
+            })
         }
 
         Ok(Config {
@@ -128,14 +799,8 @@ impl Config {
                 host: env_or_default("HOST", "0.0.0.0")?,
                 port: env_or_default("PORT", "8080")?.parse()?,
             },
-            database: DatabaseConfig {
-                url: require_env("DATABASE_URL")?,
-            },
-            jwt: JwtConfig {
-                secret: require_env("JWT_SECRET")?,
-                access_expiry: env_or_default("JWT_ACCESS_EXPIRY", "900")?.parse()?,
-                refresh_expiry: env_or_default("JWT_REFRESH_EXPIRY", "2592000")?.parse()?,
-            },
+            database: DatabaseConfig::from_env()?,
+            jwt: jwt_config()?,
             oauth: OAuthConfig {
                 google_client_id: require_env("GOOGLE_CLIENT_ID")?,
                 google_client_secret: require_env("GOOGLE_CLIENT_SECRET")?,
@@ -153,6 +818,8 @@ impl Config {
                 password_reset_expiry_hours: env_or_default("PASSWORD_RESET_EXPIRY_HOURS", "1")?
                     .parse()?,
                 frontend_url: require_env("FRONTEND_URL")?,
+                webhook_secret: read_env_file_value("EMAIL_WEBHOOK_SECRET")
+                    .filter(|s| !s.is_empty()),
             },
             rate_limit: RateLimitConfig {
                 auth_per_min: env_or_default("RATE_LIMIT_AUTH_PER_MIN", "5")?.parse()?,
@@ -167,6 +834,14 @@ impl Config {
                 .parse()?,
                 password_reset_per_hour: env_or_default("RATE_LIMIT_PASSWORD_RESET_PER_HOUR", "3")?
                     .parse()?,
+                images_per_min: env_or_default("RATE_LIMIT_IMAGES_PER_MIN", "120")?.parse()?,
+                concurrent_uploads_per_user: env_or_default(
+                    "RATE_LIMIT_CONCURRENT_UPLOADS_PER_USER",
+                    "2",
+                )?
+                .parse()?,
+                trust_proxy_headers: env_or_default("RATE_LIMIT_TRUST_PROXY", "false")?.parse()?,
+                warn_percent: env_or_default("RATE_LIMIT_WARN_PERCENT", "80")?.parse()?,
             },
             image: ImageConfig {
                 max_size_mb: env_or_default("MAX_PHOTO_SIZE_MB", "5")?.parse()?,
@@ -174,6 +849,7 @@ impl Config {
                 max_width: env_or_default("MAX_IMAGE_WIDTH", "1920")?.parse()?,
                 max_height: env_or_default("MAX_IMAGE_HEIGHT", "1920")?.parse()?,
             },
+            image_access: image_access_config()?,
             scoring: ScoringConfig {
                 min_clears_to_verify: env_or_default("MIN_CLEARS_TO_VERIFY", "5")?.parse()?,
                 min_verifications_needed: env_or_default("MIN_VERIFICATIONS_NEEDED", "3")?
@@ -183,18 +859,91 @@ impl Config {
                 streak_bonus_points: env_or_default("STREAK_BONUS_POINTS", "5")?.parse()?,
                 first_in_area_bonus: env_or_default("FIRST_IN_AREA_BONUS", "20")?.parse()?,
                 verification_bonus: env_or_default("VERIFICATION_BONUS", "2")?.parse()?,
+                photo_verification_bonus: env_or_default("PHOTO_VERIFICATION_BONUS", "3")?
+                    .parse()?,
                 verified_report_bonus: env_or_default("VERIFIED_REPORT_BONUS", "10")?.parse()?,
+                photo_retake_window_hours: env_or_default("PHOTO_RETAKE_WINDOW_HOURS", "48")?
+                    .parse()?,
+                verification_reservation_mins: env_or_default(
+                    "VERIFICATION_RESERVATION_MINS",
+                    "30",
+                )?
+                .parse()?,
+                verification_window_days: env_or_default("VERIFICATION_WINDOW_DAYS", "7")?
+                    .parse()?,
+                leaderboard_timezone: env_or_default("LEADERBOARD_TIMEZONE", "UTC")?,
+                small_severity_multiplier: env_or_default("SMALL_SEVERITY_MULTIPLIER", "1.0")?
+                    .parse()?,
+                medium_severity_multiplier: env_or_default("MEDIUM_SEVERITY_MULTIPLIER", "1.5")?
+                    .parse()?,
+                large_severity_multiplier: env_or_default("LARGE_SEVERITY_MULTIPLIER", "2.0")?
+                    .parse()?,
+                cleanup_event_bonus: env_or_default("CLEANUP_EVENT_BONUS", "5")?.parse()?,
             },
             s3: S3Config {
                 endpoint: env_or_default("S3_ENDPOINT", "http://127.0.0.1:9000")?,
                 region: env_or_default("S3_REGION", "us-east-1")?,
-                bucket: env_or_default("S3_BUCKET", "littypicky-images")?,
                 access_key: env_or_default("S3_ACCESS_KEY", "minioadmin")?,
                 secret_key: env_or_default("S3_SECRET_KEY", "minioadmin123")?,
-                public_url: env_or_default(
-                    "S3_PUBLIC_URL",
-                    "http://127.0.0.1:9000/littypicky-images",
-                )?,
+                key_prefix: env_or_default("S3_KEY_PREFIX", "")?
+                    .trim_matches('/')
+                    .to_string(),
+                spill_max_mb: env_or_default("S3_SPILL_MAX_MB", "256")?.parse()?,
+                archive_storage_class: archive_storage_class()?,
+                avatars: s3_bucket_config("AVATARS")?,
+                report_photos: s3_bucket_config("REPORT_PHOTOS")?,
+                exports: s3_bucket_config("EXPORTS")?,
+            },
+            geocoding: geocoding_config()?,
+            jobs: JobsConfig {
+                verification_reminder_interval_mins: env_or_default(
+                    "VERIFICATION_REMINDER_INTERVAL_MINS",
+                    "60",
+                )?
+                .parse()?,
+                verification_reminders_per_day: env_or_default(
+                    "VERIFICATION_REMINDERS_PER_DAY",
+                    "3",
+                )?
+                .parse()?,
+                consistency_check_interval_mins: env_or_default(
+                    "CONSISTENCY_CHECK_INTERVAL_MINS",
+                    "1440",
+                )?
+                .parse()?,
+                email_verification_reminder_interval_mins: env_or_default(
+                    "EMAIL_VERIFICATION_REMINDER_INTERVAL_MINS",
+                    "60",
+                )?
+                .parse()?,
+                email_verification_reminder_after_hours: env_or_default(
+                    "EMAIL_VERIFICATION_REMINDER_AFTER_HOURS",
+                    "48",
+                )?
+                .parse()?,
+                claim_expiry_interval_mins: env_or_default("CLAIM_EXPIRY_INTERVAL_MINS", "60")?
+                    .parse()?,
+                claim_expiry_hours: env_or_default("CLAIM_EXPIRY_HOURS", "72")?.parse()?,
+                clear_reopen_interval_mins: env_or_default("CLEAR_REOPEN_INTERVAL_MINS", "60")?
+                    .parse()?,
+                clear_reopen_after_hours: env_or_default("CLEAR_REOPEN_AFTER_HOURS", "24")?
+                    .parse()?,
+                verification_window_interval_mins: env_or_default(
+                    "VERIFICATION_WINDOW_INTERVAL_MINS",
+                    "60",
+                )?
+                .parse()?,
+                email_outbox_interval_mins: env_or_default("EMAIL_OUTBOX_INTERVAL_MINS", "1")?
+                    .parse()?,
+                geocode_retry_interval_mins: env_or_default("GEOCODE_RETRY_INTERVAL_MINS", "1")?
+                    .parse()?,
+                pending_upload_interval_mins: env_or_default("PENDING_UPLOAD_INTERVAL_MINS", "1")?
+                    .parse()?,
+                report_expiry_interval_mins: env_or_default("REPORT_EXPIRY_INTERVAL_MINS", "60")?
+                    .parse()?,
+                report_expiry_months: env_or_default("REPORT_EXPIRY_MONTHS", "6")?.parse()?,
+                stats_summary_interval_mins: env_or_default("STATS_SUMMARY_INTERVAL_MINS", "5")?
+                    .parse()?,
             },
             tls: match (
                 read_env_file_value("TLS_CERT_PATH").filter(|s| !s.is_empty()),
@@ -206,9 +955,43 @@ impl Config {
                 }),
                 _ => None,
             },
-            enable_test_helpers: env_or_default("ENABLE_TEST_HELPERS", "false")?
-                .parse()
-                .unwrap_or(false),
+            captcha: captcha_config()?,
+            test_helpers: test_helpers_config()?,
+            latency: latency_config()?,
+            moderation: ModerationConfig {
+                report_flags_to_hide: env_or_default("REPORT_FLAGS_TO_HIDE", "3")?.parse()?,
+            },
+            location_check: LocationCheckConfig {
+                reject_distance_km: env_or_default("PHOTO_LOCATION_REJECT_KM", "5")?.parse()?,
+                max_photo_age_hours: env_or_default("PHOTO_MAX_AGE_HOURS", "72")?.parse()?,
+            },
+            feed_trending: feed_trending_config()?,
+            translation: translation_config()?,
+            upload_scan: upload_scan_config()?,
+            features: FeaturesConfig {
+                feed: env_or_default("FEATURE_FEED", "true")?.parse()?,
+                cleanup_events: env_or_default("FEATURE_CLEANUP_EVENTS", "true")?.parse()?,
+                open_data: env_or_default("FEATURE_OPEN_DATA", "true")?.parse()?,
+            },
         })
     }
 }
+
+/// An env var, or the contents of the file named by `<KEY>_FILE` (e.g. a Docker secret)
+fn read_env_file_value(key: &str) -> Option<String> {
+    let file_key = format!("{key}_FILE");
+    if let Ok(path) = env::var(file_key) {
+        if let Ok(contents) = fs::read_to_string(path) {
+            return Some(contents.trim().to_string());
+        }
+    }
+    env::var(key).ok()
+}
+
+fn require_env(key: &str) -> Result<String, anyhow::Error> {
+    read_env_file_value(key).ok_or_else(|| anyhow::anyhow!("Missing env var {key}"))
+}
+
+fn env_or_default(key: &str, default: &str) -> Result<String, anyhow::Error> {
+    Ok(read_env_file_value(key).unwrap_or_else(|| default.to_string()))
+}
@@ -1,9 +1,37 @@
-use crate::config::Config;
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use crate::config::{Config, DatabaseConfig};
+use sqlx::{
+    postgres::{PgConnectOptions, PgPoolOptions},
+    PgPool,
+};
+use std::str::FromStr;
+
+/// SQLSTATE Postgres reports for a statement cancelled by `statement_timeout`
+const QUERY_CANCELED: &str = "57014";
 
 pub async fn create_pool(config: &Config) -> Result<PgPool, sqlx::Error> {
+    connect(&config.database).await
+}
+
+/// A pool for `config` alone, e.g. for running migrations without the rest of the config
+pub async fn connect(config: &DatabaseConfig) -> Result<PgPool, sqlx::Error> {
+    let mut options = PgConnectOptions::from_str(&config.url)?;
+    // Dropping a query future doesn't stop the statement on the server, so every
+    // connection gets a deadline of its own
+    if config.statement_timeout_ms > 0 {
+        options = options.options([("statement_timeout", config.statement_timeout_ms.to_string())]);
+    }
+
     PgPoolOptions::new()
         .max_connections(5)
-        .connect(&config.database.url)
+        .connect_with(options)
         .await
 }
+
+/// Whether the error is a statement Postgres cancelled for running too long
+#[must_use]
+pub fn is_statement_timeout(error: &sqlx::Error) -> bool {
+    error
+        .as_database_error()
+        .and_then(|e| e.code())
+        .is_some_and(|code| code == QUERY_CANCELED)
+}
@@ -41,6 +41,9 @@ pub enum AppError {
 
     #[error("Conflict: {0}")]
     Conflict(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
 }
 
 impl IntoResponse for AppError {
@@ -51,6 +54,13 @@ impl IntoResponse for AppError {
             .unwrap_or(false);
 
         let (status, error_message) = match self {
+            AppError::Database(ref e) if crate::db::is_statement_timeout(e) => {
+                tracing::warn!(%error_id, "Statement timed out: {}", e);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "The request took too long, please try again".to_string(),
+                )
+            }
             AppError::Database(ref e) => {
                 tracing::error!(%error_id, "Database error details: {:#?}", e);
                 eprintln!("DATABASE ERROR: {:?}", e);
@@ -117,6 +127,10 @@ impl IntoResponse for AppError {
                 tracing::warn!(%error_id, "Conflict error: {}", msg);
                 (StatusCode::CONFLICT, msg.clone())
             }
+            AppError::TooManyRequests(ref msg) => {
+                tracing::warn!(%error_id, "Rate limited: {}", msg);
+                (StatusCode::TOO_MANY_REQUESTS, msg.clone())
+            }
         };
 
         let body = Json(json!({
@@ -1,9 +1,32 @@
 use crate::auth::middleware::AuthUser;
 use crate::error::AppError;
-use crate::models::user::{User, UserResponse};
-use crate::models::ReportStatus;
+use crate::jobs::ConsistencyCheckJob;
+use crate::models::api_key::{ApiKeyResponse, CreateApiKeyRequest, UpdateApiKeyRequest};
+use crate::models::city_goal::{CityGoalProgress, CreateCityGoalRequest, UpdateCityGoalRequest};
+use crate::models::dry_run::{ChangeSummary, DryRunQuery};
+use crate::models::event::EventLogQuery;
+use crate::models::impersonation::{ImpersonateRequest, ImpersonationQuery};
+use crate::models::pagination::{PageParams, Paginated};
+use crate::models::perf::PerfReportQuery;
+use crate::models::report_flag::ReviewReportFlagsRequest;
+use crate::models::report_import::{parse_import_csv, ReportImportQuery, ReportImportRequest};
+use crate::models::report_lifecycle::ReportHistoryResponse;
+use crate::models::upload_scan::QuarantineQuery;
+use crate::models::user::{AdminUserView, User, UserRole};
+use crate::models::verification::VerificationVerdict;
+use crate::models::{ReportListQuery, ReportResponse, ReportStatus};
+use crate::perf::LatencyMonitor;
+use crate::services::email_service::{EmailService, EmailTemplate};
+use crate::services::{
+    ApiKeyService, CityGoalService, EmailWebhookService, EventService, FeedService,
+    ImpersonationService, ReportAdminService, ReportFlagService, ReportImportService,
+    ReportService, UploadScanService, VerificationService,
+};
+use crate::templates;
 use axum::{
-    extract::{Path, State},
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
@@ -11,47 +34,102 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool};
 use std::sync::Arc;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
+use validator::Validate;
 
 #[derive(Clone)]
 pub struct AdminHandlerState {
     pub pool: PgPool,
+    pub email_service: EmailService,
+    pub event_service: EventService,
+    pub email_webhook_service: EmailWebhookService,
+    pub report_admin_service: ReportAdminService,
+    pub report_service: ReportService,
+    pub report_flag_service: ReportFlagService,
+    pub report_import_service: ReportImportService,
+    pub api_key_service: ApiKeyService,
+    pub impersonation_service: ImpersonationService,
+    pub consistency_check_job: ConsistencyCheckJob,
+    pub feed_service: FeedService,
+    pub latency_monitor: LatencyMonitor,
+    pub upload_scan_service: UploadScanService,
+    pub city_goal_service: CityGoalService,
+    pub verification_service: VerificationService,
 }
 
-#[derive(Deserialize, ToSchema)]
-pub struct ListUsersQuery {
-    #[schema(example = 1)]
-    pub page: Option<i64>,
-    #[schema(example = 20)]
-    pub limit: Option<i64>,
-}
-
+/// An account flagged for moderator review, e.g. for reusing one photo across many reports
+/// or verifying in a ring
 #[derive(Serialize, FromRow, ToSchema)]
-pub struct AdminReportView {
+pub struct AdminAccountFlagView {
     pub id: Uuid,
-    pub reporter_id: Uuid,
-    pub latitude: f64,
-    pub longitude: f64,
-    pub description: Option<String>,
-    pub status: ReportStatus,
-    pub claimed_by: Option<Uuid>,
-    pub claimed_at: Option<DateTime<Utc>>,
-    pub cleared_by: Option<Uuid>,
-    pub cleared_at: Option<DateTime<Utc>>,
+    pub user_id: Uuid,
+    pub user_email: String,
+    pub user_name: String,
+    #[schema(example = "duplicate_photo")]
+    pub reason: String,
+    /// SHA-256 of the reused image, for photo-related flags
+    pub content_hash: Option<String>,
+    /// The other account in a collusion pattern, e.g. the clearer a verifier is in a
+    /// ring with
+    pub related_user_id: Option<Uuid>,
+    /// The report that tripped the flag
+    pub report_id: Option<Uuid>,
+    #[schema(example = "Identical photo uploaded on 3 distinct reports")]
+    pub details: String,
     pub created_at: DateTime<Utc>,
-    pub reporter_name: String,
-    pub reporter_email: String,
+    /// Set once a moderator has reviewed the flag
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<Uuid>,
+}
+
+/// Raw row for the admin user views: the user plus any email suppression
+#[derive(FromRow)]
+struct AdminUserRow {
+    #[sqlx(flatten)]
+    user: User,
+    email_suppression_reason: Option<String>,
+    email_suppressed_at: Option<DateTime<Utc>>,
+}
+
+impl From<AdminUserRow> for AdminUserView {
+    fn from(row: AdminUserRow) -> Self {
+        let suppression = row.email_suppression_reason.zip(row.email_suppressed_at);
+        AdminUserView::new(row.user, suppression)
+    }
+}
+
+/// One account as admins see it
+async fn fetch_admin_user(pool: &PgPool, user_id: Uuid) -> Result<AdminUserView, AppError> {
+    let user = sqlx::query_as::<_, AdminUserRow>(
+        r"
+        SELECT u.*,
+               es.reason as email_suppression_reason,
+               es.created_at as email_suppressed_at
+        FROM users u
+        LEFT JOIN email_suppressions es ON es.email = LOWER(u.email)
+        WHERE u.id = $1
+        ",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    Ok(user.into())
 }
 
 /// Get all users (paginated)
-/// GET /api/admin/users?page=1&limit=20
+/// GET /api/admin/users?cursor=...&limit=20
 #[utoipa::path(
     get,
+    operation_id = "listAdminUsers",
     path = "/api/admin/users",
-    tag = "Admin",
+    tag = "Admin Users",
+    params(PageParams),
     responses(
-        (status = 200, description = "Returns list of users", body = Vec<UserResponse>),
+        (status = 200, description = "Returns list of users", body = PaginatedAdminUsers),
+        (status = 400, description = "Invalid cursor"),
         (status = 403, description = "Admin access required")
     ),
     security(
@@ -60,35 +138,48 @@ pub struct AdminReportView {
 )]
 pub async fn list_users(
     State(state): State<Arc<AdminHandlerState>>,
+    Query(page): Query<PageParams>,
     _auth_user: AuthUser, // Verified by require_admin middleware
 ) -> Result<impl IntoResponse, AppError> {
-    let users = sqlx::query_as::<_, User>(
+    let offset = page.offset()?;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&state.pool)
+        .await?;
+
+    let users = sqlx::query_as::<_, AdminUserRow>(
         r"
-        SELECT * FROM users
-        ORDER BY created_at DESC
-        LIMIT 100
+        SELECT u.*,
+               es.reason as email_suppression_reason,
+               es.created_at as email_suppressed_at
+        FROM users u
+        LEFT JOIN email_suppressions es ON es.email = LOWER(u.email)
+        ORDER BY u.created_at DESC
+        LIMIT $1 OFFSET $2
         ",
     )
+    .bind(page.limit(20, 100))
+    .bind(offset)
     .fetch_all(&state.pool)
     .await?;
 
-    let user_responses: Vec<UserResponse> =
-        users.into_iter().map(std::convert::Into::into).collect();
+    let user_views: Paginated<AdminUserView> =
+        Paginated::new(users, total, offset).map(std::convert::Into::into);
 
-    Ok(Json(user_responses))
+    Ok(Json(user_views))
 }
 
 /// Get user by ID
 /// GET /api/admin/users/:id
 #[utoipa::path(
     get,
+    operation_id = "getAdminUser",
     path = "/api/admin/users/{id}",
-    tag = "Admin",
+    tag = "Admin Users",
     params(
         ("id" = Uuid, Path, description = "User ID")
     ),
     responses(
-        (status = 200, description = "Returns user details", body = UserResponse),
+        (status = 200, description = "Returns user details", body = AdminUserView),
         (status = 404, description = "User not found"),
         (status = 403, description = "Admin access required")
     ),
@@ -101,13 +192,348 @@ pub async fn get_user_by_id(
     Path(user_id): Path<Uuid>,
     _auth_user: AuthUser,
 ) -> Result<impl IntoResponse, AppError> {
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+    let user = fetch_admin_user(&state.pool, user_id).await?;
+    Ok(Json(user))
+}
+
+/// Clear a bounce/complaint suppression so the user receives email again
+/// DELETE /api/admin/users/:id/email-suppression?dry_run=true
+#[utoipa::path(
+    delete,
+    operation_id = "clearEmailSuppression",
+    path = "/api/admin/users/{id}/email-suppression",
+    tag = "Admin Users",
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        DryRunQuery
+    ),
+    responses(
+        (status = 200, description = "Suppression cleared, or what would be cleared in a dry run", body = ChangeSummary),
+        (status = 404, description = "User not found or address not suppressed"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn clear_email_suppression(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<DryRunQuery>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = state.pool.begin().await?;
+
+    let cleared: Vec<String> = sqlx::query_scalar(
+        r"
+        DELETE FROM email_suppressions es
+        USING users u
+        WHERE u.id = $1 AND es.email = LOWER(u.email)
+        RETURNING es.email
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if cleared.is_empty() {
+        return Err(AppError::NotFound(
+            "No email suppression found for this user".to_string(),
+        ));
+    }
+
+    let summary = ChangeSummary::new(query.dry_run)
+        .count("email_suppressions", cleared.len() as i64)
+        .samples(cleared)
+        .message(
+            "Email suppression cleared",
+            "the email suppression would be cleared",
+        )
+        .finish(tx)
+        .await?;
+
+    Ok(Json(summary))
+}
+
+/// List flagged accounts, unresolved first, then newest first
+/// GET /api/admin/account-flags?cursor=...&limit=50
+#[utoipa::path(
+    get,
+    operation_id = "listAccountFlags",
+    path = "/api/admin/account-flags",
+    tag = "Moderation",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Returns flagged accounts", body = PaginatedAccountFlags),
+        (status = 400, description = "Invalid cursor"),
+        (status = 403, description = "Moderator or admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_account_flags(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(page): Query<PageParams>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let offset = page.offset()?;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM account_flags")
+        .fetch_one(&state.pool)
+        .await?;
+
+    let flags = sqlx::query_as::<_, AdminAccountFlagView>(
+        r"
+        SELECT f.id, f.user_id, u.email as user_email, u.full_name as user_name,
+               f.reason, f.content_hash, f.related_user_id, f.report_id, f.details,
+               f.created_at,
+               f.resolved_at, f.resolved_by
+        FROM account_flags f
+        JOIN users u ON f.user_id = u.id
+        ORDER BY f.resolved_at IS NOT NULL, f.created_at DESC
+        LIMIT $1 OFFSET $2
+        ",
+    )
+    .bind(page.limit(50, 100))
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(Paginated::new(flags, total, offset)))
+}
+
+/// Mark a flagged account as reviewed
+/// POST /api/admin/account-flags/:id/resolve
+#[utoipa::path(
+    post,
+    operation_id = "resolveAccountFlag",
+    path = "/api/admin/account-flags/{id}/resolve",
+    tag = "Moderation",
+    params(
+        ("id" = Uuid, Path, description = "Flag ID")
+    ),
+    responses(
+        (status = 204, description = "Flag resolved"),
+        (status = 404, description = "Flag not found or already resolved"),
+        (status = 403, description = "Moderator or admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn resolve_account_flag(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(flag_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let resolved = sqlx::query!(
+        r#"
+        UPDATE account_flags
+        SET resolved_at = NOW(), resolved_by = $1
+        WHERE id = $2 AND resolved_at IS NULL
+        "#,
+        auth_user.id,
+        flag_id
+    )
+    .execute(&state.pool)
+    .await?
+    .rows_affected();
+
+    if resolved == 0 {
+        return Err(AppError::NotFound(
+            "Flag not found or already resolved".to_string(),
+        ));
+    }
+
+    tracing::info!("{} resolved account flag {}", auth_user.id, flag_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List reports with open user flags, hidden ones first, then most recently flagged
+/// GET /api/admin/report-flags?cursor=...&limit=50
+#[utoipa::path(
+    get,
+    operation_id = "listFlaggedReports",
+    path = "/api/admin/report-flags",
+    tag = "Moderation",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Returns flagged reports", body = PaginatedFlaggedReports),
+        (status = 400, description = "Invalid cursor"),
+        (status = 403, description = "Moderator or admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_flagged_reports(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(page): Query<PageParams>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let offset = page.offset()?;
+    let (reports, total) = state
+        .report_flag_service
+        .list_flagged(offset, page.limit(50, 100))
+        .await?;
+
+    Ok(Json(Paginated::new(reports, total, offset)))
+}
+
+/// Every flag on a report, open or resolved, oldest first
+/// GET /api/admin/reports/:id/flags
+#[utoipa::path(
+    get,
+    operation_id = "listReportFlags",
+    path = "/api/admin/reports/{id}/flags",
+    tag = "Moderation",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "The report's flags, oldest first", body = [ReportFlag]),
+        (status = 404, description = "Report not found"),
+        (status = 403, description = "Moderator or admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_report_flags(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    state.report_service.get_report_by_id(report_id).await?;
+    let flags = state.report_flag_service.get_flags(report_id).await?;
+    Ok(Json(flags))
+}
+
+/// Settle a report's open flags: dismiss them and put it back on the map, or archive it
+/// POST /api/admin/reports/:id/flags/review
+#[utoipa::path(
+    post,
+    operation_id = "reviewReportFlags",
+    path = "/api/admin/reports/{id}/flags/review",
+    tag = "Moderation",
+    request_body = ReviewReportFlagsRequest,
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Flags resolved", body = ReportResponse),
+        (status = 400, description = "Missing note, or the report can no longer be archived"),
+        (status = 404, description = "Report not found or has no open flags"),
+        (status = 403, description = "Moderator or admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn review_report_flags(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(payload): Json<ReviewReportFlagsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let report = state
+        .report_flag_service
+        .review(report_id, auth_user.id, &payload)
+        .await?;
+
+    Ok(Json(ReportResponse::from(report)))
+}
+
+/// Remove any user's feed post
+/// DELETE /api/admin/feed/:id
+#[utoipa::path(
+    delete,
+    operation_id = "moderateDeleteFeedPost",
+    path = "/api/admin/feed/{id}",
+    tag = "Moderation",
+    params(
+        ("id" = Uuid, Path, description = "Post ID")
+    ),
+    responses(
+        (status = 204, description = "Post deleted"),
+        (status = 404, description = "Post not found"),
+        (status = 403, description = "Moderator or admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn moderate_delete_feed_post(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(post_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    state.feed_service.remove_post(post_id).await?;
+    tracing::info!("{} removed feed post {}", auth_user.id, post_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetUserRoleRequest {
+    #[schema(example = "moderator")]
+    pub role: UserRole,
+}
+
+/// Change a user's role; takes effect on their next request
+/// PUT /api/admin/users/:id/role
+#[utoipa::path(
+    put,
+    operation_id = "setUserRole",
+    path = "/api/admin/users/{id}/role",
+    tag = "Admin Users",
+    request_body = SetUserRoleRequest,
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Role updated", body = AdminUserView),
+        (status = 400, description = "Admins cannot change their own role"),
+        (status = 404, description = "User not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_user_role(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(user_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(payload): Json<SetUserRoleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    // Keeps the last admin from locking everyone out by accident
+    if user_id == auth_user.id {
+        return Err(AppError::BadRequest(
+            "You cannot change your own role".to_string(),
+        ));
+    }
+
+    let updated = sqlx::query("UPDATE users SET role = $1, updated_at = NOW() WHERE id = $2")
+        .bind(&payload.role)
         .bind(user_id)
-        .fetch_optional(&state.pool)
+        .execute(&state.pool)
         .await?
-        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        .rows_affected();
+    if updated == 0 {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
 
-    Ok(Json(UserResponse::from(user)))
+    tracing::info!(
+        "Admin {} set role of {} to {}",
+        auth_user.id,
+        user_id,
+        payload.role.as_str()
+    );
+    Ok(Json(fetch_admin_user(&state.pool, user_id).await?))
 }
 
 /// Ban/unban a user
@@ -118,16 +544,24 @@ pub struct BanUserRequest {
     pub is_active: bool,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct BanUserResponse {
+    #[schema(example = "User banned")]
+    pub message: String,
+    pub user: AdminUserView,
+}
+
 #[utoipa::path(
     put,
+    operation_id = "toggleUserBan",
     path = "/api/admin/users/{id}/ban",
-    tag = "Admin",
+    tag = "Admin Users",
     request_body = BanUserRequest,
     params(
         ("id" = Uuid, Path, description = "User ID")
     ),
     responses(
-        (status = 200, description = "User ban status updated", body = UserResponse),
+        (status = 200, description = "User ban status updated", body = BanUserResponse),
         (status = 404, description = "User not found"),
         (status = 403, description = "Admin access required")
     ),
@@ -141,29 +575,38 @@ pub async fn toggle_user_ban(
     _auth_user: AuthUser,
     Json(payload): Json<BanUserRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let user = sqlx::query_as::<_, User>(
-        "UPDATE users SET is_active = $1, updated_at = NOW() WHERE id = $2 RETURNING *",
-    )
-    .bind(payload.is_active)
-    .bind(user_id)
-    .fetch_optional(&state.pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    let updated = sqlx::query("UPDATE users SET is_active = $1, updated_at = NOW() WHERE id = $2")
+        .bind(payload.is_active)
+        .bind(user_id)
+        .execute(&state.pool)
+        .await?
+        .rows_affected();
+    if updated == 0 {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
 
-    Ok(Json(serde_json::json!({
-        "message": if payload.is_active { "User unbanned" } else { "User banned" },
-        "user": UserResponse::from(user)
-    })))
+    let message = if payload.is_active {
+        "User unbanned"
+    } else {
+        "User banned"
+    };
+    Ok(Json(BanUserResponse {
+        message: message.to_string(),
+        user: fetch_admin_user(&state.pool, user_id).await?,
+    }))
 }
 
-/// Get all reports (not just nearby)
-/// GET /api/admin/reports
+/// Get all reports (not just nearby), optionally by status and oldest first
+/// GET /api/admin/reports?status=pending,claimed&sort=oldest
 #[utoipa::path(
     get,
+    operation_id = "listAdminReports",
     path = "/api/admin/reports",
-    tag = "Admin",
+    tag = "Admin Reports",
+    params(ReportListQuery, PageParams),
     responses(
-        (status = 200, description = "Returns all reports", body = Vec<AdminReportView>),
+        (status = 200, description = "Returns all reports", body = PaginatedAdminReports),
+        (status = 400, description = "Invalid status, sort or cursor"),
         (status = 403, description = "Admin access required")
     ),
     security(
@@ -172,47 +615,37 @@ pub async fn toggle_user_ban(
 )]
 pub async fn list_all_reports(
     State(state): State<Arc<AdminHandlerState>>,
+    Query(list): Query<ReportListQuery>,
+    Query(page): Query<PageParams>,
     _auth_user: AuthUser,
 ) -> Result<impl IntoResponse, AppError> {
-    let reports = sqlx::query_as::<_, AdminReportView>(
-        r"
-        SELECT 
-            lr.id,
-            lr.reporter_id,
-            ST_Y(lr.location)::double precision as latitude,
-            ST_X(lr.location)::double precision as longitude,
-            lr.description,
-            lr.status,
-            lr.claimed_by,
-            lr.claimed_at,
-            lr.cleared_by,
-            lr.cleared_at,
-            lr.created_at,
-            u.full_name as reporter_name,
-            u.email as reporter_email
-        FROM litter_reports lr
-        JOIN users u ON lr.reporter_id = u.id
-        ORDER BY lr.created_at DESC
-        LIMIT 100
-        ",
-    )
-    .fetch_all(&state.pool)
-    .await?;
+    let offset = page.offset()?;
+    let statuses = list.statuses()?;
+    let (reports, total) = state
+        .report_service
+        .list_admin_reports(
+            statuses.as_deref(),
+            list.sort(),
+            offset,
+            page.limit(50, 100),
+        )
+        .await?;
 
-    Ok(Json(reports))
+    Ok(Json(Paginated::new(reports, total, offset)))
 }
 
-/// Delete a report (for spam/inappropriate content)
-/// DELETE /api/admin/reports/:id
+/// Every edit the reporter made to a report before it was claimed, oldest first
+/// GET /api/admin/reports/:id/edits
 #[utoipa::path(
-    delete,
-    path = "/api/admin/reports/{id}",
-    tag = "Admin",
+    get,
+    operation_id = "listReportEdits",
+    path = "/api/admin/reports/{id}/edits",
+    tag = "Admin Reports",
     params(
         ("id" = Uuid, Path, description = "Report ID")
     ),
     responses(
-        (status = 200, description = "Report deleted"),
+        (status = 200, description = "The report's edits, oldest first", body = [ReportEdit]),
         (status = 404, description = "Report not found"),
         (status = 403, description = "Admin access required")
     ),
@@ -220,20 +653,1264 @@ pub async fn list_all_reports(
         ("bearer_auth" = [])
     )
 )]
-pub async fn delete_report(
+pub async fn list_report_edits(
     State(state): State<Arc<AdminHandlerState>>,
     Path(report_id): Path<Uuid>,
     _auth_user: AuthUser,
 ) -> Result<impl IntoResponse, AppError> {
-    let result = sqlx::query!("DELETE FROM litter_reports WHERE id = $1", report_id)
-        .execute(&state.pool)
-        .await?;
+    // 404 rather than an empty list for reports that do not exist
+    state.report_service.get_report_by_id(report_id).await?;
+    let edits = state.report_service.get_edits(report_id).await?;
+    Ok(Json(edits))
+}
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound("Report not found".to_string()));
+/// A report's audit trail, including reports that have since been deleted
+/// GET /api/admin/reports/:id/history
+#[utoipa::path(
+    get,
+    operation_id = "getAdminReportHistory",
+    path = "/api/admin/reports/{id}/history",
+    tag = "Admin Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Events, oldest first", body = ReportHistoryResponse),
+        (status = 404, description = "No history for this report"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_admin_report_history(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let events = state.report_service.get_history(report_id).await?;
+    if events.is_empty() {
+        return Err(AppError::NotFound("No history for this report".to_string()));
     }
+    Ok(Json(ReportHistoryResponse { report_id, events }))
+}
 
-    Ok(Json(serde_json::json!({
-        "message": "Report deleted successfully"
-    })))
+/// Import a batch of reports, e.g. from a council's litter database. Send JSON, or CSV
+/// with a `text/csv` content type and a header row naming the columns. Rows are
+/// processed in the background; poll the returned import for progress.
+/// POST /api/admin/reports/import
+#[utoipa::path(
+    post,
+    operation_id = "importReports",
+    path = "/api/admin/reports/import",
+    tag = "Admin Reports",
+    params(
+        ReportImportQuery
+    ),
+    request_body(
+        content = ReportImportRequest,
+        description = "The batch as JSON, or its rows as CSV with a `text/csv` content type"
+    ),
+    responses(
+        (status = 202, description = "Import started", body = ReportImportResponse),
+        (status = 400, description = "Malformed body, no rows, or too many rows"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn import_reports(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(query): Query<ReportImportQuery>,
+    auth_user: AuthUser,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/csv"));
+
+    let (source, rows) = if is_csv {
+        (query.source, parse_import_csv(&body)?)
+    } else {
+        let request: ReportImportRequest = serde_json::from_slice(&body)
+            .map_err(|e| AppError::BadRequest(format!("Invalid import: {e}")))?;
+        (request.source.or(query.source), request.reports)
+    };
+
+    let import = state
+        .report_import_service
+        .start(auth_user.id, source, rows)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(import)))
+}
+
+/// Get an import's progress and the outcome of each processed row
+/// GET /api/admin/reports/import/:id
+#[utoipa::path(
+    get,
+    operation_id = "getReportImport",
+    path = "/api/admin/reports/import/{id}",
+    tag = "Admin Reports",
+    params(
+        ("id" = Uuid, Path, description = "Import ID")
+    ),
+    responses(
+        (status = 200, description = "Import progress", body = ReportImportResponse),
+        (status = 404, description = "Import not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_report_import(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(import_id): Path<Uuid>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let import = state.report_import_service.get(import_id).await?;
+    Ok(Json(import))
+}
+
+/// Delete a report (for spam/inappropriate content)
+/// DELETE /api/admin/reports/:id?dry_run=true
+#[utoipa::path(
+    delete,
+    operation_id = "deleteReport",
+    path = "/api/admin/reports/{id}",
+    tag = "Admin Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID"),
+        DryRunQuery
+    ),
+    responses(
+        (status = 200, description = "Report deleted, or what would be deleted in a dry run", body = ChangeSummary),
+        (status = 404, description = "Report not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_report(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    Query(query): Query<DryRunQuery>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let summary = state
+        .report_admin_service
+        .delete_report(report_id, auth_user.id, query.dry_run)
+        .await?;
+    Ok(Json(summary))
+}
+
+/// Delete every report made by a user (e.g. a spammer)
+/// DELETE /api/admin/users/:id/reports?dry_run=true
+#[utoipa::path(
+    delete,
+    operation_id = "purgeUserReports",
+    path = "/api/admin/users/{id}/reports",
+    tag = "Admin Users",
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        DryRunQuery
+    ),
+    responses(
+        (status = 200, description = "Reports deleted, or what would be deleted in a dry run", body = ChangeSummary),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn purge_user_reports(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<DryRunQuery>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let summary = state
+        .report_admin_service
+        .purge_user_reports(user_id, auth_user.id, query.dry_run)
+        .await?;
+
+    if !summary.dry_run {
+        tracing::info!(
+            "Admin {} purged reports of user {}: {}",
+            auth_user.id,
+            user_id,
+            summary.message
+        );
+    }
+
+    Ok(Json(summary))
+}
+
+/// Hand a report's claim or clear to another user
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ReassignReportRequest {
+    /// New claimer; the report must already be claimed
+    pub claimed_by: Option<Uuid>,
+    /// New clearer, who takes over the clear points; the report must already be cleared
+    pub cleared_by: Option<Uuid>,
+    #[validate(length(min = 1, max = 500))]
+    #[schema(example = "Coordinator moved the claim to a nearby volunteer")]
+    pub reason: String,
+}
+
+/// Force a report into a status
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct OverrideReportStatusRequest {
+    pub status: ReportStatus,
+    /// Claimer for a claimed/cleared/verified report; defaults to the current one
+    pub claimed_by: Option<Uuid>,
+    /// Clearer for a cleared/verified report; defaults to the current one
+    pub cleared_by: Option<Uuid>,
+    #[validate(length(min = 1, max = 500))]
+    #[schema(example = "Clear photo confirmed by support")]
+    pub reason: String,
+}
+
+/// Reassign a report's claimer and/or clearer
+/// POST /api/admin/reports/:id/reassign
+#[utoipa::path(
+    post,
+    operation_id = "reassignReport",
+    path = "/api/admin/reports/{id}/reassign",
+    tag = "Admin Reports",
+    request_body = ReassignReportRequest,
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Report reassigned; clear points moved with the clear", body = ReportResponse),
+        (status = 400, description = "Missing reason, or the report is not in a state to reassign"),
+        (status = 404, description = "Report or user not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reassign_report(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(payload): Json<ReassignReportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let report = state
+        .report_admin_service
+        .reassign(
+            report_id,
+            auth_user.id,
+            payload.claimed_by,
+            payload.cleared_by,
+            &payload.reason,
+        )
+        .await?;
+
+    Ok(Json(ReportResponse::from(report)))
+}
+
+/// Override a report's status, e.g. after a support investigation
+/// POST /api/admin/reports/:id/status
+#[utoipa::path(
+    post,
+    operation_id = "overrideReportStatus",
+    path = "/api/admin/reports/{id}/status",
+    tag = "Admin Reports",
+    request_body = OverrideReportStatusRequest,
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Status overridden; clear points corrected", body = ReportResponse),
+        (status = 400, description = "Missing reason, or no claimer/clearer for the status"),
+        (status = 404, description = "Report or user not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn override_report_status(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(payload): Json<OverrideReportStatusRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let report = state
+        .report_admin_service
+        .override_status(
+            report_id,
+            auth_user.id,
+            payload.status,
+            payload.claimed_by,
+            payload.cleared_by,
+            &payload.reason,
+        )
+        .await?;
+
+    Ok(Json(ReportResponse::from(report)))
+}
+
+/// Settle a clear by hand
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct VerificationOverrideRequest {
+    pub verdict: VerificationVerdict,
+    #[validate(length(min = 1, max = 500))]
+    #[schema(example = "Site visited by the council; litter gone")]
+    pub reason: String,
+}
+
+/// Mark a cleared or disputed report verified or rejected without waiting for
+/// verifiers. A verified clear earns the clearer's bonus; a rejected one goes back to
+/// pending and the clearer loses the clear's points.
+/// POST /api/admin/reports/:id/verification-override
+#[utoipa::path(
+    post,
+    operation_id = "overrideReportVerification",
+    path = "/api/admin/reports/{id}/verification-override",
+    tag = "Admin Reports",
+    request_body = VerificationOverrideRequest,
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Clear settled; scores adjusted", body = ReportResponse),
+        (status = 400, description = "Missing reason, or the report is not cleared or disputed"),
+        (status = 404, description = "Report not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn override_report_verification(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(payload): Json<VerificationOverrideRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let report = state
+        .verification_service
+        .override_verdict(
+            report_id,
+            auth_user.id,
+            payload.verdict == VerificationVerdict::Verified,
+            &payload.reason,
+        )
+        .await?;
+
+    Ok(Json(ReportResponse::from(report)))
+}
+
+/// Put an expired report back on the map with a fresh expiry period
+/// POST /api/admin/reports/:id/reactivate
+#[utoipa::path(
+    post,
+    operation_id = "reactivateReport",
+    path = "/api/admin/reports/{id}/reactivate",
+    tag = "Admin Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Report is pending again, with its photos back in standard storage", body = ReportResponse),
+        (status = 400, description = "Report has not expired"),
+        (status = 404, description = "Report not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reactivate_report(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(report_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .report_service
+        .reactivate_report(report_id, auth_user.id)
+        .await?;
+    tracing::info!("Admin {} reactivated report {}", auth_user.id, report_id);
+
+    Ok(Json(ReportResponse::from(report)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GeoStatsQuery {
+    /// Aggregate by "grid" (default) or by the reporter's "city"
+    #[param(example = "grid")]
+    pub group_by: Option<String>,
+    /// Grid cell size in degrees when grouping by grid
+    #[param(example = 0.05, minimum = 0.001, maximum = 10.0)]
+    pub cell_size_deg: Option<f64>,
+    /// Only include reports created in the last N days
+    #[param(example = 90)]
+    pub days: Option<i32>,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct GeoAreaStats {
+    /// City name ("unknown" for anonymous reports) or grid cell key ("lat,lng" of the
+    /// cell origin)
+    #[schema(example = "51.50,-0.15")]
+    pub area: String,
+    pub center_latitude: f64,
+    pub center_longitude: f64,
+    pub total_reports: i64,
+    pub cleared_reports: i64,
+    pub verified_reports: i64,
+    /// Reports still pending or claimed
+    pub unresolved_backlog: i64,
+    /// Median hours between report creation and clear
+    pub median_hours_to_clear: Option<f64>,
+    /// Share of cleared reports that went on to be verified (0.0 - 1.0)
+    pub verification_rate: Option<f64>,
+}
+
+/// Get per-area response metrics for targeting volunteer recruitment
+/// GET /api/admin/stats/geo?group_by=grid&cell_size_deg=0.05&days=90
+#[utoipa::path(
+    get,
+    operation_id = "getGeoStats",
+    path = "/api/admin/stats/geo",
+    tag = "Admin Reports",
+    params(
+        GeoStatsQuery
+    ),
+    responses(
+        (status = 200, description = "Returns per-area report metrics, largest backlog first", body = Vec<GeoAreaStats>),
+        (status = 400, description = "Invalid grouping or cell size"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_geo_stats(
+    State(state): State<Arc<AdminHandlerState>>,
+    _auth_user: AuthUser,
+    Query(query): Query<GeoStatsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let cell_size = query.cell_size_deg.unwrap_or(0.05);
+    if !(0.001..=10.0).contains(&cell_size) {
+        return Err(AppError::BadRequest(
+            "cell_size_deg must be between 0.001 and 10".to_string(),
+        ));
+    }
+    let days = query.days.unwrap_or(365).clamp(1, 3650);
+
+    // Both groupings share the same metrics; only the area key differs
+    let area_expr = match query.group_by.as_deref() {
+        Some("grid") | None => {
+            "concat_ws(',', \
+                round(ST_Y(ST_SnapToGrid(lr.location, $1))::numeric, 3), \
+                round(ST_X(ST_SnapToGrid(lr.location, $1))::numeric, 3))"
+        }
+        // Anonymous reports have no reporter, so no city
+        Some("city") => "COALESCE(u.city, 'unknown')",
+        _ => {
+            return Err(AppError::BadRequest(
+                "Invalid group_by. Use 'grid' or 'city'".to_string(),
+            ))
+        }
+    };
+
+    let sql = format!(
+        r"
+        SELECT
+            {area_expr} as area,
+            AVG(ST_Y(lr.location))::double precision as center_latitude,
+            AVG(ST_X(lr.location))::double precision as center_longitude,
+            COUNT(*) as total_reports,
+            COUNT(*) FILTER (WHERE lr.status IN ('cleared', 'verified')) as cleared_reports,
+            COUNT(*) FILTER (WHERE lr.status = 'verified') as verified_reports,
+            COUNT(*) FILTER (WHERE lr.status IN ('pending', 'claimed')) as unresolved_backlog,
+            percentile_cont(0.5) WITHIN GROUP (
+                ORDER BY EXTRACT(EPOCH FROM (lr.cleared_at - lr.created_at)) / 3600.0
+            ) FILTER (WHERE lr.cleared_at IS NOT NULL) as median_hours_to_clear,
+            (COUNT(*) FILTER (WHERE lr.status = 'verified'))::double precision
+                / NULLIF(COUNT(*) FILTER (WHERE lr.status IN ('cleared', 'verified')), 0)
+                as verification_rate
+        FROM litter_reports lr
+        LEFT JOIN users u ON lr.reporter_id = u.id
+        WHERE lr.created_at > NOW() - make_interval(days => $2)
+          AND lr.status != 'cancelled'
+        GROUP BY 1
+        ORDER BY unresolved_backlog DESC, total_reports DESC
+        LIMIT 500
+        "
+    );
+
+    let stats = sqlx::query_as::<_, GeoAreaStats>(&sql)
+        .bind(cell_size)
+        .bind(days)
+        .fetch_all(&state.pool)
+        .await?;
+
+    Ok(Json(stats))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EmailPreviewQuery {
+    /// Template locale (defaults to "en")
+    #[param(example = "en")]
+    pub locale: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EmailPreviewResponse {
+    pub template: EmailTemplate,
+    #[schema(example = "en")]
+    pub locale: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct SendTestEmailRequest {
+    pub template: EmailTemplate,
+    #[validate(email)]
+    #[schema(example = "admin@example.com")]
+    pub to: String,
+    #[schema(example = "en")]
+    pub locale: Option<String>,
+}
+
+fn resolve_locale(locale: Option<String>) -> Result<String, AppError> {
+    let locale = locale.unwrap_or_else(|| "en".to_string());
+    if templates::SUPPORTED_LOCALES.contains(&locale.as_str()) {
+        Ok(locale)
+    } else {
+        Err(AppError::BadRequest(format!(
+            "Unsupported locale '{locale}'. Supported: {}",
+            templates::SUPPORTED_LOCALES.join(", ")
+        )))
+    }
+}
+
+/// Render an email template with sample data
+/// GET /api/admin/emails/preview/:template?locale=en
+#[utoipa::path(
+    get,
+    operation_id = "previewEmail",
+    path = "/api/admin/emails/preview/{template}",
+    tag = "Admin Emails",
+    params(
+        ("template" = EmailTemplate, Path, description = "Template name"),
+        EmailPreviewQuery
+    ),
+    responses(
+        (status = 200, description = "Rendered HTML and text bodies", body = EmailPreviewResponse),
+        (status = 400, description = "Unknown template or unsupported locale"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn preview_email(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(template): Path<EmailTemplate>,
+    Query(query): Query<EmailPreviewQuery>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let locale = resolve_locale(query.locale)?;
+    let email = state.email_service.render_sample(template);
+
+    Ok(Json(EmailPreviewResponse {
+        template,
+        locale,
+        subject: email.subject,
+        html_body: email.html_body,
+        text_body: email.text_body,
+    }))
+}
+
+/// Send a sample email to an arbitrary address
+/// POST /api/admin/emails/test
+#[utoipa::path(
+    post,
+    operation_id = "sendTestEmail",
+    path = "/api/admin/emails/test",
+    tag = "Admin Emails",
+    request_body = SendTestEmailRequest,
+    responses(
+        (status = 200, description = "Test email sent"),
+        (status = 400, description = "Invalid address, unsupported locale or suppressed recipient"),
+        (status = 403, description = "Admin access required"),
+        (status = 500, description = "SMTP delivery failed")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn send_test_email(
+    State(state): State<Arc<AdminHandlerState>>,
+    auth_user: AuthUser,
+    Json(payload): Json<SendTestEmailRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+    resolve_locale(payload.locale)?;
+
+    state
+        .email_service
+        .send_test_email(&payload.to, payload.template)
+        .await?;
+
+    tracing::info!(
+        "Admin {} sent test {:?} email to {}",
+        auth_user.id,
+        payload.template,
+        payload.to
+    );
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Test email sent to {}", payload.to)
+    })))
+}
+
+/// Browse the event log, including inbound webhook deliveries
+/// GET /api/admin/events?event_type=webhook.&delivery_status=failed
+#[utoipa::path(
+    get,
+    operation_id = "listEvents",
+    path = "/api/admin/events",
+    tag = "Admin Events",
+    params(EventLogQuery, PageParams),
+    responses(
+        (status = 200, description = "Matching events, newest first", body = PaginatedEvents),
+        (status = 400, description = "Invalid cursor"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_events(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(query): Query<EventLogQuery>,
+    Query(page): Query<PageParams>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let events = state.event_service.list_events(&query, &page).await?;
+    Ok(Json(events))
+}
+
+/// Get a single event with its full payload
+/// GET /api/admin/events/:id
+#[utoipa::path(
+    get,
+    operation_id = "getEvent",
+    path = "/api/admin/events/{id}",
+    tag = "Admin Events",
+    params(
+        ("id" = Uuid, Path, description = "Event ID")
+    ),
+    responses(
+        (status = 200, description = "Returns the event", body = EventLogEntry),
+        (status = 404, description = "Event not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_event(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(event_id): Path<Uuid>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let event = state.event_service.get_event(event_id).await?;
+    Ok(Json(event))
+}
+
+/// Replay a logged webhook delivery
+/// POST /api/admin/events/:id/retry
+#[utoipa::path(
+    post,
+    operation_id = "retryEvent",
+    path = "/api/admin/events/{id}/retry",
+    tag = "Admin Events",
+    params(
+        ("id" = Uuid, Path, description = "Event ID of a webhook delivery")
+    ),
+    responses(
+        (status = 200, description = "Delivery reprocessed; returns the updated event", body = EventLogEntry),
+        (status = 400, description = "Event is not a webhook delivery, or processing failed again"),
+        (status = 404, description = "Event not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn retry_event(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(event_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    tracing::info!("Admin {} retrying event {}", auth_user.id, event_id);
+    state.email_webhook_service.retry_delivery(event_id).await?;
+
+    let event = state.event_service.get_event(event_id).await?;
+    Ok(Json(event))
+}
+
+/// List partner API keys, newest first
+/// GET /api/admin/api-keys
+#[utoipa::path(
+    get,
+    operation_id = "listApiKeys",
+    path = "/api/admin/api-keys",
+    tag = "Admin API Keys",
+    responses(
+        (status = 200, description = "Returns all API keys, including revoked ones", body = Vec<ApiKeyResponse>),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_api_keys(
+    State(state): State<Arc<AdminHandlerState>>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let keys = state.api_key_service.list_keys().await?;
+    let keys: Vec<ApiKeyResponse> = keys.into_iter().map(Into::into).collect();
+    Ok(Json(keys))
+}
+
+/// Issue an API key for a partner service
+/// POST /api/admin/api-keys
+#[utoipa::path(
+    post,
+    operation_id = "createApiKey",
+    path = "/api/admin/api-keys",
+    tag = "Admin API Keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "Key issued; the plaintext key is only shown in this response", body = CreatedApiKeyResponse),
+        (status = 400, description = "Invalid name, scopes or rate limit"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<Arc<AdminHandlerState>>,
+    auth_user: AuthUser,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let created = state
+        .api_key_service
+        .create_key(auth_user.id, payload)
+        .await?;
+    tracing::info!(
+        "Admin {} issued API key {} ({})",
+        auth_user.id,
+        created.key.id,
+        created.key.name
+    );
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Change an API key's name, scopes or rate limit
+/// PATCH /api/admin/api-keys/:id
+#[utoipa::path(
+    patch,
+    operation_id = "updateApiKey",
+    path = "/api/admin/api-keys/{id}",
+    tag = "Admin API Keys",
+    request_body = UpdateApiKeyRequest,
+    params(
+        ("id" = Uuid, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "Key updated", body = ApiKeyResponse),
+        (status = 400, description = "Invalid name, scopes or rate limit"),
+        (status = 404, description = "API key not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_api_key(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(key_id): Path<Uuid>,
+    _auth_user: AuthUser,
+    Json(payload): Json<UpdateApiKeyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let key = state.api_key_service.update_key(key_id, payload).await?;
+    Ok(Json(ApiKeyResponse::from(key)))
+}
+
+/// Revoke an API key; it stops working immediately
+/// DELETE /api/admin/api-keys/:id
+#[utoipa::path(
+    delete,
+    operation_id = "revokeApiKey",
+    path = "/api/admin/api-keys/{id}",
+    tag = "Admin API Keys",
+    params(
+        ("id" = Uuid, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "Key revoked", body = ApiKeyResponse),
+        (status = 404, description = "API key not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_api_key(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(key_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let key = state.api_key_service.revoke_key(key_id).await?;
+    tracing::info!("Admin {} revoked API key {}", auth_user.id, key_id);
+    Ok(Json(ApiKeyResponse::from(key)))
+}
+
+/// List every city's weekly goal with this week's progress
+/// GET /api/admin/city-goals
+#[utoipa::path(
+    get,
+    operation_id = "adminListCityGoals",
+    path = "/api/admin/city-goals",
+    tag = "Admin City Goals",
+    responses(
+        (status = 200, description = "Goals by city name", body = Vec<CityGoalProgress>),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn admin_list_city_goals(
+    State(state): State<Arc<AdminHandlerState>>,
+    _auth_user: AuthUser,
+) -> Result<Json<Vec<CityGoalProgress>>, AppError> {
+    let goals = state.city_goal_service.list_progress(None).await?;
+    Ok(Json(goals))
+}
+
+/// Set a weekly clear target for a city
+/// POST /api/admin/city-goals
+#[utoipa::path(
+    post,
+    operation_id = "createCityGoal",
+    path = "/api/admin/city-goals",
+    tag = "Admin City Goals",
+    request_body = CreateCityGoalRequest,
+    responses(
+        (status = 201, description = "Goal created", body = CityGoalProgress),
+        (status = 400, description = "Invalid city or target"),
+        (status = 403, description = "Admin access required"),
+        (status = 409, description = "The city already has a goal")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_city_goal(
+    State(state): State<Arc<AdminHandlerState>>,
+    auth_user: AuthUser,
+    Json(payload): Json<CreateCityGoalRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let goal = state
+        .city_goal_service
+        .create_goal(auth_user.id, payload)
+        .await?;
+    tracing::info!(
+        "Admin {} set a goal of {} weekly clears for {}",
+        auth_user.id,
+        goal.weekly_target,
+        goal.city
+    );
+
+    Ok((StatusCode::CREATED, Json(goal)))
+}
+
+/// Change a city's weekly target
+/// PUT /api/admin/city-goals/:id
+#[utoipa::path(
+    put,
+    operation_id = "updateCityGoal",
+    path = "/api/admin/city-goals/{id}",
+    tag = "Admin City Goals",
+    request_body = UpdateCityGoalRequest,
+    params(
+        ("id" = Uuid, Path, description = "City goal ID")
+    ),
+    responses(
+        (status = 200, description = "Goal updated", body = CityGoalProgress),
+        (status = 400, description = "Invalid target"),
+        (status = 404, description = "City goal not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_city_goal(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(goal_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(payload): Json<UpdateCityGoalRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let goal = state
+        .city_goal_service
+        .update_goal(goal_id, payload)
+        .await?;
+    tracing::info!(
+        "Admin {} changed the {} goal to {} weekly clears",
+        auth_user.id,
+        goal.city,
+        goal.weekly_target
+    );
+    Ok(Json(goal))
+}
+
+/// Remove a city's goal
+/// DELETE /api/admin/city-goals/:id
+#[utoipa::path(
+    delete,
+    operation_id = "deleteCityGoal",
+    path = "/api/admin/city-goals/{id}",
+    tag = "Admin City Goals",
+    params(
+        ("id" = Uuid, Path, description = "City goal ID")
+    ),
+    responses(
+        (status = 204, description = "Goal removed"),
+        (status = 404, description = "City goal not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_city_goal(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(goal_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    state.city_goal_service.delete_goal(goal_id).await?;
+    tracing::info!("Admin {} removed city goal {}", auth_user.id, goal_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Sign in as a user to debug a problem they reported. Issues a short-lived access
+/// token carrying an `impersonated_by` claim; each use is recorded in the audit trail.
+/// POST /api/admin/users/:id/impersonate
+#[utoipa::path(
+    post,
+    operation_id = "impersonateUser",
+    path = "/api/admin/users/{id}/impersonate",
+    tag = "Admin Users",
+    request_body = ImpersonateRequest,
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 201, description = "Token issued and the impersonation recorded", body = ImpersonationResponse),
+        (status = 400, description = "Missing reason, or impersonating yourself"),
+        (status = 404, description = "User not found"),
+        (status = 403, description = "Admin access required, or the user is an admin")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn impersonate_user(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(user_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(payload): Json<ImpersonateRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let response = state
+        .impersonation_service
+        .impersonate(auth_user.id, user_id, &payload.reason)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Audit trail of impersonations, newest first
+/// GET /api/admin/impersonations?user_id=...
+#[utoipa::path(
+    get,
+    operation_id = "listImpersonations",
+    path = "/api/admin/impersonations",
+    tag = "Admin Users",
+    params(ImpersonationQuery, PageParams),
+    responses(
+        (status = 200, description = "Recorded impersonations", body = PaginatedImpersonations),
+        (status = 400, description = "Invalid cursor"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_impersonations(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(query): Query<ImpersonationQuery>,
+    Query(page): Query<PageParams>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let impersonations = state
+        .impersonation_service
+        .list(query.user_id, &page)
+        .await?;
+    Ok(Json(impersonations))
+}
+
+/// Run the data consistency check now. Orphaned rows and dangling references are
+/// repaired unless `dry_run` is set; either way the run is recorded.
+/// POST /api/admin/consistency-checks?dry_run=true
+#[utoipa::path(
+    post,
+    operation_id = "runConsistencyCheck",
+    path = "/api/admin/consistency-checks",
+    tag = "Admin Maintenance",
+    params(DryRunQuery),
+    responses(
+        (status = 201, description = "Anomalies found, and fixed unless this was a dry run", body = ConsistencyCheckRun),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn run_consistency_check(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(query): Query<DryRunQuery>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let run = state
+        .consistency_check_job
+        .run(Some(auth_user.id), query.dry_run)
+        .await?;
+    tracing::info!(
+        "Admin {} ran a consistency check (dry run: {}): {} found, {} fixed",
+        auth_user.id,
+        run.dry_run,
+        run.total_found,
+        run.total_fixed
+    );
+    Ok((StatusCode::CREATED, Json(run)))
+}
+
+/// Past consistency check runs, scheduled and manual, newest first
+/// GET /api/admin/consistency-checks
+#[utoipa::path(
+    get,
+    operation_id = "listConsistencyChecks",
+    path = "/api/admin/consistency-checks",
+    tag = "Admin Maintenance",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Recorded runs", body = PaginatedConsistencyCheckRuns),
+        (status = 400, description = "Invalid cursor"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_consistency_checks(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(page): Query<PageParams>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let runs = state.consistency_check_job.list_runs(&page).await?;
+    Ok(Json(runs))
+}
+
+/// Routes running over their latency budget on this instance, most violations first
+/// GET /api/admin/perf
+#[utoipa::path(
+    get,
+    operation_id = "getPerfReport",
+    path = "/api/admin/perf",
+    tag = "Admin Maintenance",
+    params(PerfReportQuery),
+    responses(
+        (status = 200, description = "Routes with budget violations since startup", body = Vec<RouteLatencyReport>),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_perf_report(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(query): Query<PerfReportQuery>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(
+        state
+            .latency_monitor
+            .report(query.limit.unwrap_or(20).clamp(1, 100)),
+    ))
+}
+
+/// Malware scan counts on this instance and the uploads held in quarantine
+/// GET /api/admin/quarantine
+#[utoipa::path(
+    get,
+    operation_id = "getUploadQuarantine",
+    path = "/api/admin/quarantine",
+    tag = "Admin Maintenance",
+    params(QuarantineQuery),
+    responses(
+        (status = 200, description = "Scan counts since startup and the most recent quarantined uploads", body = UploadScanReport),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_upload_quarantine(
+    State(state): State<Arc<AdminHandlerState>>,
+    Query(query): Query<QuarantineQuery>,
+    _auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .upload_scan_service
+        .report(query.limit.unwrap_or(20).clamp(1, 100))
+        .await?;
+    Ok(Json(report))
+}
+
+/// Download a quarantined upload's bytes, e.g. for a security team to analyse
+/// GET /api/admin/quarantine/:id/data
+#[utoipa::path(
+    get,
+    operation_id = "downloadQuarantinedUpload",
+    path = "/api/admin/quarantine/{id}/data",
+    tag = "Admin Maintenance",
+    params(
+        ("id" = Uuid, Path, description = "Quarantined upload ID")
+    ),
+    responses(
+        (status = 200, description = "The upload exactly as it was received", content_type = "application/octet-stream"),
+        (status = 404, description = "Quarantined upload not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn download_quarantined_upload(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(upload_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let data = state
+        .upload_scan_service
+        .quarantined_data(upload_id)
+        .await?;
+    tracing::info!(
+        "Admin {} downloaded quarantined upload {}",
+        auth_user.id,
+        upload_id
+    );
+    let disposition = format!("attachment; filename=\"quarantined-{upload_id}.bin\"");
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        data,
+    ))
+}
+
+/// Delete a quarantined upload once it has been dealt with
+/// DELETE /api/admin/quarantine/:id
+#[utoipa::path(
+    delete,
+    operation_id = "deleteQuarantinedUpload",
+    path = "/api/admin/quarantine/{id}",
+    tag = "Admin Maintenance",
+    params(
+        ("id" = Uuid, Path, description = "Quarantined upload ID")
+    ),
+    responses(
+        (status = 204, description = "Quarantined upload deleted"),
+        (status = 404, description = "Quarantined upload not found"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_quarantined_upload(
+    State(state): State<Arc<AdminHandlerState>>,
+    Path(upload_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .upload_scan_service
+        .delete_quarantined(upload_id)
+        .await?;
+    tracing::info!(
+        "Admin {} deleted quarantined upload {}",
+        auth_user.id,
+        upload_id
+    );
+    Ok(StatusCode::NO_CONTENT)
 }
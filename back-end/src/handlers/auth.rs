@@ -1,15 +1,26 @@
 use crate::{
+    auth::{middleware::AuthUser, ClientInfo},
     error::Result,
     models::{
-        AuthTokens, ForgotPasswordRequest, LoginRequest, ResendVerificationRequest,
-        ResetPasswordRequest, VerifyEmailRequest,
+        AuthTokens, ChangePasswordRequest, ConfirmAccountLinkRequest, ConfirmEmailChangeRequest,
+        CreateServiceTokenRequest, EmailChangeRequest, ForgotPasswordRequest, LoginEvent,
+        LoginRequest, MagicLinkRequest, PageParams, Paginated, PasswordSetupRequest,
+        ReauthenticateRequest, ResendVerificationRequest, ResetPasswordRequest,
+        ServiceTokenResponse, SessionResponse, SetPasswordRequest, TwoFactorLoginRequest,
+        UnsubscribeRemindersRequest, VerifyEmailRequest, VerifyMagicLinkRequest,
     },
-    services::AuthService,
+    services::{AuthService, LoginOutcome},
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
 };
-use axum::{extract::State, http::StatusCode, Json};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
+use uuid::Uuid;
 use validator::Validate;
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -29,6 +40,8 @@ pub struct RegisterRequest {
     #[validate(length(min = 1))]
     #[schema(example = "UK")]
     pub country: String,
+    /// hCaptcha/Turnstile response token; required when the server has a CAPTCHA provider
+    pub captcha_token: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -39,17 +52,27 @@ pub struct MessageResponse {
 
 #[utoipa::path(
     post,
+    operation_id = "register",
     path = "/api/auth/register",
     tag = "Authentication",
-    request_body = RegisterRequest,
+    request_body(content = RegisterRequest, example = json!({
+        "email": "jane@example.com",
+        "password": "SecurePassword123",
+        "full_name": "Jane Doe",
+        "city": "Manchester",
+        "country": "UK"
+    })),
     responses(
-        (status = 201, description = "User registered successfully. Verification email sent.", body = MessageResponse),
-        (status = 400, description = "Validation error"),
+        (status = 201, description = "User registered successfully. Verification email sent.", body = MessageResponse, example = json!({
+            "message": "Registration successful. Please check your email to verify your account."
+        })),
+        (status = 400, description = "Validation error, or missing or failed CAPTCHA"),
         (status = 409, description = "Email already registered")
     )
 )]
 pub async fn register(
     State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
     Json(req): Json<RegisterRequest>,
 ) -> Result<(StatusCode, Json<MessageResponse>)> {
     tracing::info!("Registering user: {}", req.email);
@@ -62,6 +85,10 @@ pub async fn register(
         )));
     }
 
+    auth_service
+        .verify_captcha(req.captcha_token.as_deref(), &client)
+        .await?;
+
     let message = match auth_service
         .register_user(
             &req.email,
@@ -86,25 +113,84 @@ pub async fn register(
 
 #[utoipa::path(
     post,
+    operation_id = "login",
     path = "/api/auth/login",
     tag = "Authentication",
-    request_body = LoginRequest,
+    request_body(content = LoginRequest, example = json!({
+        "email": "user@example.com",
+        "password": "SecurePassword123"
+    })),
     responses(
-        (status = 200, description = "Login successful", body = AuthTokens),
+        (status = 200, description = "Login successful", body = AuthTokens, example = json!({
+            "access_token": "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...",
+            "refresh_token": "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...",
+            "user": {
+                "id": "6f1c2a8e-3b4d-4e5f-8a9b-0c1d2e3f4a5b",
+                "email": "user@example.com",
+                "full_name": "John Doe",
+                "city": "London",
+                "country": "UK",
+                "search_radius_km": 5,
+                "role": "user",
+                "email_verified": true,
+                "oauth_provider": null,
+                "has_password": true,
+                "created_at": "2024-05-01T09:30:00Z"
+            }
+        })),
+        (status = 202, description = "Password accepted; complete two-factor via /api/auth/2fa/login", body = TwoFactorChallenge),
         (status = 401, description = "Invalid credentials"),
         (status = 403, description = "Email not verified")
     )
 )]
 pub async fn login(
     State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
     Json(req): Json<LoginRequest>,
+) -> Result<Response> {
+    let outcome = auth_service
+        .login_user(&req.email, &req.password, &client)
+        .await?;
+    Ok(login_response(outcome))
+}
+
+/// Complete a login that requires two-factor authentication
+/// POST /api/auth/2fa/login
+#[utoipa::path(
+    post,
+    operation_id = "completeTwoFactorLogin",
+    path = "/api/auth/2fa/login",
+    tag = "Two-Factor",
+    request_body = TwoFactorLoginRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthTokens),
+        (status = 401, description = "Invalid code or expired challenge")
+    )
+)]
+pub async fn two_factor_login(
+    State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
+    Json(req): Json<TwoFactorLoginRequest>,
 ) -> Result<Json<AuthTokens>> {
-    let tokens = auth_service.login_user(&req.email, &req.password).await?;
+    let tokens = auth_service
+        .complete_two_factor_login(&req.challenge_token, &req.code, &client)
+        .await?;
     Ok(Json(tokens))
 }
 
+/// Tokens on success; 202 with a challenge when a second factor is still needed
+fn login_response(outcome: LoginOutcome) -> Response {
+    match outcome {
+        LoginOutcome::Authenticated(tokens) => Json(tokens).into_response(),
+        LoginOutcome::TwoFactorRequired(challenge) => {
+            (StatusCode::ACCEPTED, Json(challenge)).into_response()
+        }
+    }
+}
+
 #[utoipa::path(
     post,
+    operation_id = "verifyEmail",
     path = "/api/auth/verify-email",
     tag = "Authentication",
     request_body = VerifyEmailRequest,
@@ -115,50 +201,156 @@ pub async fn login(
 )]
 pub async fn verify_email(
     State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
     Json(req): Json<VerifyEmailRequest>,
 ) -> Result<Json<AuthTokens>> {
-    let tokens = auth_service.verify_email(&req.token).await?;
+    let tokens = auth_service.verify_email(&req.token, &client).await?;
     Ok(Json(tokens))
 }
 
+/// Confirm linking a Google account to an existing password account
+/// POST /api/auth/link-account
+#[utoipa::path(
+    post,
+    operation_id = "confirmAccountLink",
+    path = "/api/auth/link-account",
+    tag = "Authentication",
+    request_body = ConfirmAccountLinkRequest,
+    responses(
+        (status = 200, description = "Google account linked; logged in", body = AuthTokens),
+        (status = 202, description = "Google account linked; complete two-factor to log in", body = TwoFactorChallenge),
+        (status = 400, description = "Invalid or expired token"),
+        (status = 409, description = "Account or Google identity already linked")
+    )
+)]
+pub async fn confirm_account_link(
+    State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
+    Json(req): Json<ConfirmAccountLinkRequest>,
+) -> Result<Response> {
+    let outcome = auth_service.confirm_oauth_link(&req.token, &client).await?;
+    Ok(login_response(outcome))
+}
+
 #[utoipa::path(
     post,
+    operation_id = "resendVerification",
     path = "/api/auth/resend-verification",
     tag = "Authentication",
     request_body = ResendVerificationRequest,
     responses(
         (status = 200, description = "Verification email sent", body = MessageResponse),
-        (status = 400, description = "Email already verified"),
+        (status = 400, description = "Email already verified, or missing or failed CAPTCHA"),
         (status = 404, description = "User not found")
     )
 )]
 pub async fn resend_verification(
     State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
     Json(req): Json<ResendVerificationRequest>,
 ) -> Result<Json<MessageResponse>> {
+    auth_service
+        .verify_captcha(req.captcha_token.as_deref(), &client)
+        .await?;
     let message = auth_service.resend_verification(&req.email).await?;
     Ok(Json(MessageResponse { message }))
 }
 
+/// Opt out of verification reminder emails using the link they carry
+/// POST /api/auth/verification-reminders/unsubscribe
 #[utoipa::path(
     post,
+    operation_id = "unsubscribeVerificationReminders",
+    path = "/api/auth/verification-reminders/unsubscribe",
+    tag = "Authentication",
+    request_body = UnsubscribeRemindersRequest,
+    responses(
+        (status = 200, description = "Reminders stopped", body = MessageResponse),
+        (status = 400, description = "Invalid unsubscribe link")
+    )
+)]
+pub async fn unsubscribe_verification_reminders(
+    State(auth_service): State<Arc<AuthService>>,
+    Json(req): Json<UnsubscribeRemindersRequest>,
+) -> Result<Json<MessageResponse>> {
+    let message = auth_service
+        .unsubscribe_verification_reminders(&req.token)
+        .await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+#[utoipa::path(
+    post,
+    operation_id = "forgotPassword",
     path = "/api/auth/forgot-password",
     tag = "Authentication",
-    request_body = ForgotPasswordRequest,
+    request_body(content = ForgotPasswordRequest, example = json!({ "email": "user@example.com" })),
     responses(
-        (status = 200, description = "Password reset email sent (if email exists)", body = MessageResponse)
+        (status = 200, description = "Password reset email sent (if email exists)", body = MessageResponse, example = json!({
+            "message": "If the email exists, a password reset link has been sent"
+        })),
+        (status = 400, description = "Missing or failed CAPTCHA")
     )
 )]
 pub async fn forgot_password(
     State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
     Json(req): Json<ForgotPasswordRequest>,
 ) -> Result<Json<MessageResponse>> {
+    auth_service
+        .verify_captcha(req.captcha_token.as_deref(), &client)
+        .await?;
     let message = auth_service.forgot_password(&req.email).await?;
     Ok(Json(MessageResponse { message }))
 }
 
+/// Email a one-time passwordless sign-in link
+/// POST /api/auth/magic-link
+#[utoipa::path(
+    post,
+    operation_id = "requestMagicLink",
+    path = "/api/auth/magic-link",
+    tag = "Authentication",
+    request_body = MagicLinkRequest,
+    responses(
+        (status = 200, description = "Sign-in link sent (if email exists)", body = MessageResponse)
+    )
+)]
+pub async fn request_magic_link(
+    State(auth_service): State<Arc<AuthService>>,
+    Json(req): Json<MagicLinkRequest>,
+) -> Result<Json<MessageResponse>> {
+    let message = auth_service.request_magic_link(&req.email).await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Exchange a sign-in link token for auth tokens
+/// POST /api/auth/magic-link/verify
 #[utoipa::path(
     post,
+    operation_id = "verifyMagicLink",
+    path = "/api/auth/magic-link/verify",
+    tag = "Authentication",
+    request_body = VerifyMagicLinkRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthTokens),
+        (status = 202, description = "Link accepted; complete two-factor via /api/auth/2fa/login", body = TwoFactorChallenge),
+        (status = 400, description = "Invalid or expired token"),
+        (status = 403, description = "Account is deactivated")
+    )
+)]
+pub async fn verify_magic_link(
+    State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
+    Json(req): Json<VerifyMagicLinkRequest>,
+) -> Result<Response> {
+    let outcome = auth_service.verify_magic_link(&req.token, &client).await?;
+    Ok(login_response(outcome))
+}
+
+#[utoipa::path(
+    post,
+    operation_id = "resetPassword",
     path = "/api/auth/reset-password",
     tag = "Authentication",
     request_body = ResetPasswordRequest,
@@ -191,6 +383,7 @@ pub struct RefreshTokenResponse {
 
 #[utoipa::path(
     post,
+    operation_id = "refreshToken",
     path = "/api/auth/refresh",
     tag = "Authentication",
     request_body = RefreshTokenRequest,
@@ -201,18 +394,20 @@ pub struct RefreshTokenResponse {
 )]
 pub async fn refresh_token(
     State(auth_service): State<Arc<AuthService>>,
+    client: ClientInfo,
     Json(req): Json<RefreshTokenRequest>,
 ) -> Result<Json<RefreshTokenResponse>> {
     let access_token = auth_service
-        .refresh_access_token(&req.refresh_token)
+        .refresh_access_token(&req.refresh_token, &client)
         .await?;
     Ok(Json(RefreshTokenResponse { access_token }))
 }
 
 #[utoipa::path(
     post,
+    operation_id = "logout",
     path = "/api/auth/logout",
-    tag = "Authentication",
+    tag = "Sessions",
     request_body = RefreshTokenRequest,
     responses(
         (status = 200, description = "Logged out successfully", body = MessageResponse)
@@ -225,3 +420,326 @@ pub async fn logout(
     let message = auth_service.logout(&req.refresh_token).await?;
     Ok(Json(MessageResponse { message }))
 }
+
+/// Log out from all devices
+/// POST /api/auth/logout-all
+#[utoipa::path(
+    post,
+    operation_id = "logoutAll",
+    path = "/api/auth/logout-all",
+    tag = "Sessions",
+    responses(
+        (status = 200, description = "All refresh tokens deleted and existing access tokens revoked", body = MessageResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn logout_all(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+) -> Result<Json<MessageResponse>> {
+    let message = auth_service.logout_all(auth_user.id).await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Issue a short-lived token limited to one scope, e.g. for background photo uploads
+/// POST /api/auth/service-tokens
+#[utoipa::path(
+    post,
+    operation_id = "createServiceToken",
+    path = "/api/auth/service-tokens",
+    tag = "Sessions",
+    request_body = CreateServiceTokenRequest,
+    responses(
+        (status = 201, description = "Scoped token issued", body = ServiceTokenResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Scoped tokens and impersonation sessions cannot issue service tokens")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_service_token(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+    Json(req): Json<CreateServiceTokenRequest>,
+) -> Result<(StatusCode, Json<ServiceTokenResponse>)> {
+    let token = auth_service.create_service_token(&auth_user, req.scope)?;
+    Ok((StatusCode::CREATED, Json(token)))
+}
+
+/// List the current user's active sessions (one per refresh token)
+/// GET /api/users/me/sessions
+#[utoipa::path(
+    get,
+    operation_id = "listSessions",
+    path = "/api/users/me/sessions",
+    tag = "Sessions",
+    responses(
+        (status = 200, description = "Active sessions, most recently used first", body = Vec<SessionResponse>),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_sessions(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+) -> Result<Json<Vec<SessionResponse>>> {
+    let sessions = auth_service
+        .list_sessions(auth_user.id, auth_user.session_id)
+        .await?;
+    Ok(Json(sessions))
+}
+
+/// The current user's sign-in history, including sessions since revoked
+/// GET /api/users/me/logins
+#[utoipa::path(
+    get,
+    operation_id = "listLoginHistory",
+    path = "/api/users/me/logins",
+    tag = "Sessions",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Sign-ins, newest first", body = PaginatedLoginEvents),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_login_history(
+    State(auth_service): State<Arc<AuthService>>,
+    Query(page): Query<PageParams>,
+    auth_user: AuthUser,
+) -> Result<Json<Paginated<LoginEvent>>> {
+    let logins = auth_service.list_logins(auth_user.id, &page).await?;
+    Ok(Json(logins))
+}
+
+/// Revoke one session; its refresh token stops working immediately
+/// DELETE /api/users/me/sessions/:id
+#[utoipa::path(
+    delete,
+    operation_id = "revokeSession",
+    path = "/api/users/me/sessions/{id}",
+    tag = "Sessions",
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Session revoked", body = MessageResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Session not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_session(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<MessageResponse>> {
+    let message = auth_service
+        .revoke_session(auth_user.id, session_id)
+        .await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Unlink a sign-in provider; requires re-authentication and a password to fall back on
+/// DELETE /api/users/me/oauth/:provider
+#[utoipa::path(
+    delete,
+    operation_id = "unlinkOAuthProvider",
+    path = "/api/users/me/oauth/{provider}",
+    tag = "OAuth",
+    params(
+        ("provider" = String, Path, description = "Provider to unlink, e.g. google")
+    ),
+    request_body = ReauthenticateRequest,
+    responses(
+        (status = 200, description = "Provider unlinked", body = MessageResponse),
+        (status = 400, description = "Account has no password to fall back on"),
+        (status = 401, description = "Re-authentication failed"),
+        (status = 404, description = "Provider not linked")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn unlink_oauth_provider(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+    Path(provider): Path<String>,
+    Json(req): Json<ReauthenticateRequest>,
+) -> Result<Json<MessageResponse>> {
+    let message = auth_service
+        .unlink_oauth_identity(auth_user.id, &provider, &req.password, req.code.as_deref())
+        .await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Change the password, given the current one; every session is signed out
+/// PUT /api/users/me/password
+#[utoipa::path(
+    put,
+    operation_id = "changePassword",
+    path = "/api/users/me/password",
+    tag = "Account",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed; all sessions revoked", body = MessageResponse),
+        (status = 400, description = "New password too weak or unchanged"),
+        (status = 401, description = "Re-authentication failed")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn change_password(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<Json<MessageResponse>> {
+    if let Err(e) = req.validate() {
+        return Err(crate::error::AppError::BadRequest(format!(
+            "Validation error: {e}"
+        )));
+    }
+
+    let message = auth_service
+        .change_password(
+            auth_user.id,
+            &req.current_password,
+            &req.new_password,
+            req.code.as_deref(),
+        )
+        .await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Start adding a password to an account that signs in only with a provider, e.g. Google.
+/// A link goes to the account's address, standing in for the missing password.
+/// POST /api/users/me/password/setup
+#[utoipa::path(
+    post,
+    operation_id = "requestPasswordSetup",
+    path = "/api/users/me/password/setup",
+    tag = "Account",
+    request_body = PasswordSetupRequest,
+    responses(
+        (status = 200, description = "Password setup link sent to the account's address", body = MessageResponse),
+        (status = 400, description = "Account already has a password"),
+        (status = 401, description = "Two-factor code missing or wrong")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn request_password_setup(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+    Json(req): Json<PasswordSetupRequest>,
+) -> Result<Json<MessageResponse>> {
+    let message = auth_service
+        .request_password_setup(auth_user.id, req.code.as_deref())
+        .await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Add a password from the emailed setup link
+/// POST /api/auth/set-password
+#[utoipa::path(
+    post,
+    operation_id = "setPassword",
+    path = "/api/auth/set-password",
+    tag = "Account",
+    request_body = SetPasswordRequest,
+    responses(
+        (status = 200, description = "Password added; existing sessions stay signed in", body = MessageResponse),
+        (status = 400, description = "Invalid or expired token, or password too weak"),
+        (status = 409, description = "Account already has a password")
+    )
+)]
+pub async fn set_password(
+    State(auth_service): State<Arc<AuthService>>,
+    Json(req): Json<SetPasswordRequest>,
+) -> Result<Json<MessageResponse>> {
+    if let Err(e) = req.validate() {
+        return Err(crate::error::AppError::BadRequest(format!(
+            "Validation error: {e}"
+        )));
+    }
+
+    let message = auth_service
+        .set_password(&req.token, &req.new_password)
+        .await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Start changing the account's email address; a confirmation link goes to the new address
+/// POST /api/users/me/email-change
+#[utoipa::path(
+    post,
+    operation_id = "requestEmailChange",
+    path = "/api/users/me/email-change",
+    tag = "Account",
+    request_body = EmailChangeRequest,
+    responses(
+        (status = 200, description = "Confirmation email sent to the new address", body = MessageResponse),
+        (status = 400, description = "Invalid email or same as current"),
+        (status = 401, description = "Re-authentication failed"),
+        (status = 409, description = "Email already registered")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn request_email_change(
+    State(auth_service): State<Arc<AuthService>>,
+    auth_user: AuthUser,
+    Json(req): Json<EmailChangeRequest>,
+) -> Result<Json<MessageResponse>> {
+    if let Err(e) = req.validate() {
+        return Err(crate::error::AppError::BadRequest(format!(
+            "Validation error: {e}"
+        )));
+    }
+
+    let message = auth_service
+        .request_email_change(
+            auth_user.id,
+            &req.new_email,
+            &req.password,
+            req.code.as_deref(),
+        )
+        .await?;
+    Ok(Json(MessageResponse { message }))
+}
+
+/// Confirm an email change from the link sent to the new address
+/// POST /api/auth/confirm-email-change
+#[utoipa::path(
+    post,
+    operation_id = "confirmEmailChange",
+    path = "/api/auth/confirm-email-change",
+    tag = "Account",
+    request_body = ConfirmEmailChangeRequest,
+    responses(
+        (status = 200, description = "Email changed; all sessions signed out", body = MessageResponse),
+        (status = 400, description = "Invalid or expired token"),
+        (status = 409, description = "Email registered by another account in the meantime")
+    )
+)]
+pub async fn confirm_email_change(
+    State(auth_service): State<Arc<AuthService>>,
+    Json(req): Json<ConfirmEmailChangeRequest>,
+) -> Result<Json<MessageResponse>> {
+    let message = auth_service.confirm_email_change(&req.token).await?;
+    Ok(Json(MessageResponse { message }))
+}
@@ -0,0 +1,35 @@
+use crate::models::Capabilities;
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct CapabilitiesHandlerState {
+    pub capabilities: Capabilities,
+}
+
+/// Get the optional subsystems this deployment has enabled, so clients can hide
+/// features it doesn't offer. Public and cached for five minutes.
+/// GET /api/capabilities
+#[utoipa::path(
+    get,
+    operation_id = "getCapabilities",
+    path = "/api/capabilities",
+    tag = "Capabilities",
+    responses(
+        (status = 200, description = "Which optional subsystems are switched on", body = Capabilities)
+    )
+)]
+pub async fn get_capabilities(
+    State(state): State<Arc<CapabilitiesHandlerState>>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(state.capabilities.clone()),
+    )
+}
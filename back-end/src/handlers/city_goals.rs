@@ -0,0 +1,61 @@
+use crate::error::AppError;
+use crate::models::city_goal::{CityGoalProgress, CityGoalQuery};
+use crate::models::pagination::Paginated;
+use crate::services::city_goal_service::CityGoalService;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct CityGoalHandlerState {
+    pub city_goal_service: CityGoalService,
+}
+
+/// This week's progress towards every city's goal
+/// GET /api/city-goals
+#[utoipa::path(
+    get,
+    operation_id = "listCityGoals",
+    path = "/api/city-goals",
+    tag = "City Goals",
+    params(CityGoalQuery),
+    responses(
+        (status = 200, description = "Goals by city name", body = PaginatedCityGoals)
+    )
+)]
+pub async fn list_city_goals(
+    State(state): State<Arc<CityGoalHandlerState>>,
+    Query(query): Query<CityGoalQuery>,
+) -> Result<Json<Paginated<CityGoalProgress>>, AppError> {
+    let goals = state
+        .city_goal_service
+        .list_progress(query.city.as_deref())
+        .await?;
+    Ok(Json(Paginated::all(goals)))
+}
+
+/// This week's progress towards one city's goal
+/// GET /api/city-goals/:id
+#[utoipa::path(
+    get,
+    operation_id = "getCityGoal",
+    path = "/api/city-goals/{id}",
+    tag = "City Goals",
+    params(
+        ("id" = Uuid, Path, description = "City goal ID")
+    ),
+    responses(
+        (status = 200, description = "The goal and its progress", body = CityGoalProgress),
+        (status = 404, description = "City goal not found")
+    )
+)]
+pub async fn get_city_goal(
+    State(state): State<Arc<CityGoalHandlerState>>,
+    Path(goal_id): Path<Uuid>,
+) -> Result<Json<CityGoalProgress>, AppError> {
+    let goal = state.city_goal_service.get_progress(goal_id).await?;
+    Ok(Json(goal))
+}
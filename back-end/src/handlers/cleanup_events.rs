@@ -0,0 +1,184 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::cleanup_event::{
+    CleanupEventResponse, CleanupEventsQuery, CreateCleanupEventRequest,
+};
+use crate::models::pagination::Paginated;
+use crate::services::cleanup_event_service::CleanupEventService;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone)]
+pub struct CleanupEventHandlerState {
+    pub cleanup_event_service: CleanupEventService,
+}
+
+/// List upcoming and ongoing cleanup events meeting near a point
+/// GET /api/cleanup-events
+#[utoipa::path(
+    get,
+    operation_id = "listCleanupEvents",
+    path = "/api/cleanup-events",
+    tag = "Cleanup Events",
+    params(CleanupEventsQuery),
+    responses(
+        (status = 200, description = "Events soonest first, at most 50", body = PaginatedCleanupEvents)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_cleanup_events(
+    State(state): State<Arc<CleanupEventHandlerState>>,
+    auth_user: AuthUser,
+    Query(query): Query<CleanupEventsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let events = state
+        .cleanup_event_service
+        .list_nearby(auth_user.id, &query)
+        .await?;
+    let responses: Paginated<CleanupEventResponse> =
+        Paginated::all(events).map(std::convert::Into::into);
+    Ok(Json(responses))
+}
+
+/// Organise a cleanup event. Attendees who clear a linked report during the event earn
+/// every attendee a group bonus.
+/// POST /api/cleanup-events
+#[utoipa::path(
+    post,
+    operation_id = "createCleanupEvent",
+    path = "/api/cleanup-events",
+    tag = "Cleanup Events",
+    request_body(content = CreateCleanupEventRequest, example = json!({
+        "title": "Saturday canal clean",
+        "latitude": 51.5074,
+        "longitude": -0.1278,
+        "meeting_point_description": "By the lock gates",
+        "starts_at": "2026-11-07T10:00:00Z",
+        "ends_at": "2026-11-07T13:00:00Z",
+        "report_ids": ["6f1c2a8e-3b4d-4e5f-8a9b-0c1d2e3f4a5b"]
+    })),
+    responses(
+        (status = 201, description = "Event created; you are RSVPed", body = CleanupEventResponse),
+        (status = 400, description = "Invalid window, or a report is closed or too far away")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_cleanup_event(
+    State(state): State<Arc<CleanupEventHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateCleanupEventRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let event = state
+        .cleanup_event_service
+        .create_event(auth_user.id, request)
+        .await?;
+    let response: CleanupEventResponse = event.into();
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Get a cleanup event
+/// GET /api/cleanup-events/:id
+#[utoipa::path(
+    get,
+    operation_id = "getCleanupEvent",
+    path = "/api/cleanup-events/{id}",
+    tag = "Cleanup Events",
+    params(
+        ("id" = Uuid, Path, description = "Cleanup event ID")
+    ),
+    responses(
+        (status = 200, description = "Returns the event", body = CleanupEventResponse),
+        (status = 404, description = "Cleanup event not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_cleanup_event(
+    State(state): State<Arc<CleanupEventHandlerState>>,
+    auth_user: AuthUser,
+    Path(event_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = state
+        .cleanup_event_service
+        .get_event(event_id, auth_user.id)
+        .await?;
+    Ok(Json(CleanupEventResponse::from(event)))
+}
+
+/// RSVP to a cleanup event
+/// POST /api/cleanup-events/:id/rsvp
+#[utoipa::path(
+    post,
+    operation_id = "rsvpCleanupEvent",
+    path = "/api/cleanup-events/{id}/rsvp",
+    tag = "Cleanup Events",
+    params(
+        ("id" = Uuid, Path, description = "Cleanup event ID")
+    ),
+    responses(
+        (status = 200, description = "RSVPed", body = CleanupEventResponse),
+        (status = 400, description = "The event has finished"),
+        (status = 404, description = "Cleanup event not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn rsvp_cleanup_event(
+    State(state): State<Arc<CleanupEventHandlerState>>,
+    auth_user: AuthUser,
+    Path(event_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = state
+        .cleanup_event_service
+        .rsvp(event_id, auth_user.id)
+        .await?;
+    Ok(Json(CleanupEventResponse::from(event)))
+}
+
+/// Withdraw an RSVP
+/// DELETE /api/cleanup-events/:id/rsvp
+#[utoipa::path(
+    delete,
+    operation_id = "cancelCleanupEventRsvp",
+    path = "/api/cleanup-events/{id}/rsvp",
+    tag = "Cleanup Events",
+    params(
+        ("id" = Uuid, Path, description = "Cleanup event ID")
+    ),
+    responses(
+        (status = 204, description = "RSVP withdrawn"),
+        (status = 400, description = "The organiser can't withdraw"),
+        (status = 404, description = "Cleanup event or RSVP not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn cancel_cleanup_event_rsvp(
+    State(state): State<Arc<CleanupEventHandlerState>>,
+    auth_user: AuthUser,
+    Path(event_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .cleanup_event_service
+        .cancel_rsvp(event_id, auth_user.id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,72 @@
+use crate::error::AppError;
+use crate::services::email_webhook_service::EmailWebhookService;
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Clone)]
+pub struct EmailWebhookState {
+    pub email_webhook_service: EmailWebhookService,
+    pub webhook_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EmailWebhookQuery {
+    /// Shared secret configured via EMAIL_WEBHOOK_SECRET
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailWebhookResponse {
+    /// Number of addresses marked as undeliverable by this call
+    #[schema(example = 1)]
+    pub suppressed: usize,
+}
+
+/// Receive bounce and complaint notifications from the email provider
+/// POST /api/webhooks/email/:provider?token=...
+#[utoipa::path(
+    post,
+    operation_id = "receiveEmailWebhook",
+    path = "/api/webhooks/email/{provider}",
+    tag = "Webhooks",
+    params(
+        ("provider" = String, Path, description = "Email provider: ses or sendgrid"),
+        EmailWebhookQuery
+    ),
+    request_body(content = String, description = "Provider-specific notification payload (SNS envelope for SES, event array for SendGrid)"),
+    responses(
+        (status = 200, description = "Notification processed", body = EmailWebhookResponse),
+        (status = 400, description = "Unknown provider or malformed payload"),
+        (status = 401, description = "Missing or invalid webhook token"),
+        (status = 403, description = "Email webhooks are not configured")
+    )
+)]
+pub async fn receive_email_webhook(
+    State(state): State<Arc<EmailWebhookState>>,
+    Path(provider): Path<String>,
+    Query(query): Query<EmailWebhookQuery>,
+    body: String,
+) -> Result<impl IntoResponse, AppError> {
+    let secret = state
+        .webhook_secret
+        .as_deref()
+        .ok_or_else(|| AppError::Forbidden("Email webhooks are not configured".to_string()))?;
+
+    if query.token.as_deref() != Some(secret) {
+        return Err(AppError::Unauthorized);
+    }
+
+    // SNS posts with a text/plain content type, so the raw body is parsed by the service
+    let suppressed = state
+        .email_webhook_service
+        .handle_delivery(&provider, &body)
+        .await?;
+
+    Ok(Json(EmailWebhookResponse { suppressed }))
+}
@@ -0,0 +1,102 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::services::data_export_service::DataExportService;
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ExportHandlerState {
+    pub data_export_service: DataExportService,
+}
+
+/// Start assembling an archive of everything the user has contributed
+/// POST /api/users/me/export
+#[utoipa::path(
+    post,
+    operation_id = "requestDataExport",
+    path = "/api/users/me/export",
+    tag = "Data Export",
+    responses(
+        (status = 202, description = "Export started, or the one in progress", body = DataExportResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn request_data_export(
+    State(state): State<Arc<ExportHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let export = state.data_export_service.request(auth_user.id).await?;
+    Ok((StatusCode::ACCEPTED, Json(export)))
+}
+
+/// Get the status of the user's latest export, with a download link once ready
+/// GET /api/users/me/export
+#[utoipa::path(
+    get,
+    operation_id = "getDataExport",
+    path = "/api/users/me/export",
+    tag = "Data Export",
+    responses(
+        (status = 200, description = "Latest export", body = DataExportResponse),
+        (status = 404, description = "No export requested")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_data_export(
+    State(state): State<Arc<ExportHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let export = state.data_export_service.latest(auth_user.id).await?;
+    Ok(Json(export))
+}
+
+/// Download a finished export archive
+/// GET /api/users/me/export/:id/download
+#[utoipa::path(
+    get,
+    operation_id = "downloadDataExport",
+    path = "/api/users/me/export/{id}/download",
+    tag = "Data Export",
+    params(
+        ("id" = Uuid, Path, description = "Export ID")
+    ),
+    responses(
+        (status = 200, description = "JSON archive", content_type = "application/json"),
+        (status = 404, description = "Export not found, not ready or expired")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn download_data_export(
+    State(state): State<Arc<ExportHandlerState>>,
+    auth_user: AuthUser,
+    Path(export_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let archive = state
+        .data_export_service
+        .download(auth_user.id, export_id)
+        .await?;
+    let disposition = format!("attachment; filename=\"littypicky-export-{export_id}.json\"");
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        archive,
+    ))
+}
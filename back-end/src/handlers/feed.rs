@@ -1,9 +1,11 @@
 use crate::auth::middleware::AuthUser;
 use crate::error::AppError;
 use crate::models::feed::{
-    CreateFeedCommentRequest, CreateFeedPostRequest, FeedQueryParams, UpdateFeedCommentRequest,
-    UpdateFeedPostRequest,
+    CreateFeedCommentRequest, CreateFeedPostRequest, EngagementKind, FeedQueryParams, FeedSort,
+    RecordDwellRequest, UpdateFeedCommentRequest, UpdateFeedPostRequest,
 };
+use crate::models::pagination::{PageParams, Paginated};
+use crate::services::feed_engagement_service::EngagementRecorder;
 use crate::services::feed_service::FeedService;
 use axum::{
     extract::{Path, Query, State},
@@ -13,10 +15,21 @@ use axum::{
 };
 use std::sync::Arc;
 use uuid::Uuid;
+use validator::Validate;
 
 #[derive(Clone)]
 pub struct FeedHandlerState {
     pub feed_service: FeedService,
+    /// Set while the trending feed is switched on
+    pub engagement: Option<EngagementRecorder>,
+}
+
+impl FeedHandlerState {
+    fn record(&self, post_id: Uuid, user_id: Option<Uuid>, kind: EngagementKind) {
+        if let Some(engagement) = &self.engagement {
+            engagement.record(post_id, user_id, kind);
+        }
+    }
 }
 
 // ============================================================================
@@ -27,13 +40,19 @@ pub struct FeedHandlerState {
 /// POST /api/feed
 #[utoipa::path(
     post,
+    operation_id = "createFeedPost",
     path = "/api/feed",
     tag = "Feed",
     request_body = CreateFeedPostRequest,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Unique value per user action; a retry with the same key replays the original response")
+    ),
     responses(
-        (status = 201, description = "Post created successfully", body = crate::models::feed::FeedPostResponse),
+        (status = 201, description = "Post created successfully", body = FeedPostResponse),
         (status = 400, description = "Invalid input (content or images)"),
         (status = 401, description = "Unauthorized"),
+        (status = 409, description = "A request with the same Idempotency-Key is still in progress"),
+        (status = 429, description = "Too many of your requests in progress"),
         (status = 500, description = "Server error")
     ),
     security(
@@ -60,37 +79,47 @@ pub async fn create_post(
 /// GET /api/feed?offset=0&limit=20
 #[utoipa::path(
     get,
+    operation_id = "listFeedPosts",
     path = "/api/feed",
     tag = "Feed",
     params(
         FeedQueryParams
     ),
     responses(
-        (status = 200, description = "Returns paginated posts", body = Vec<crate::models::feed::FeedPostResponse>)
+        (status = 200, description = "Returns paginated posts", body = PaginatedFeedPosts)
     )
 )]
 pub async fn get_feed(
     State(state): State<Arc<FeedHandlerState>>,
     Query(params): Query<FeedQueryParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let posts = state
+    let offset = params.offset();
+    let sort = match state.engagement {
+        Some(_) => params.sort.unwrap_or_default(),
+        None => FeedSort::Newest,
+    };
+    let (posts, total) = state
         .feed_service
-        .get_feed(params.offset(), params.limit())
+        .get_feed(offset, params.limit(), sort)
         .await?;
-    Ok(Json(posts))
+    for post in &posts {
+        state.record(post.id, None, EngagementKind::View);
+    }
+    Ok(Json(Paginated::new(posts, total, i64::from(offset))))
 }
 
 /// Get a single feed post by ID
 /// GET /api/feed/:id
 #[utoipa::path(
     get,
+    operation_id = "getFeedPost",
     path = "/api/feed/{id}",
     tag = "Feed",
     params(
         ("id" = Uuid, Path, description = "Post ID")
     ),
     responses(
-        (status = 200, description = "Returns the post", body = crate::models::feed::FeedPostResponse),
+        (status = 200, description = "Returns the post", body = FeedPostResponse),
         (status = 404, description = "Post not found")
     )
 )]
@@ -99,6 +128,7 @@ pub async fn get_post(
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
     let post = state.feed_service.get_post(id).await?;
+    state.record(post.id, None, EngagementKind::View);
     Ok(Json(post))
 }
 
@@ -106,6 +136,7 @@ pub async fn get_post(
 /// PATCH /api/feed/:id
 #[utoipa::path(
     patch,
+    operation_id = "updateFeedPost",
     path = "/api/feed/{id}",
     tag = "Feed",
     request_body = UpdateFeedPostRequest,
@@ -113,7 +144,7 @@ pub async fn get_post(
         ("id" = Uuid, Path, description = "Post ID")
     ),
     responses(
-        (status = 200, description = "Post updated successfully", body = crate::models::feed::FeedPostResponse),
+        (status = 200, description = "Post updated successfully", body = FeedPostResponse),
         (status = 400, description = "Invalid input"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Not the post owner"),
@@ -140,6 +171,7 @@ pub async fn update_post(
 /// DELETE /api/feed/:id
 #[utoipa::path(
     delete,
+    operation_id = "deleteFeedPost",
     path = "/api/feed/{id}",
     tag = "Feed",
     params(
@@ -172,6 +204,7 @@ pub async fn delete_post(
 /// POST /api/feed/:post_id/comments
 #[utoipa::path(
     post,
+    operation_id = "createFeedComment",
     path = "/api/feed/{post_id}/comments",
     tag = "Feed Comments",
     request_body = CreateFeedCommentRequest,
@@ -179,7 +212,7 @@ pub async fn delete_post(
         ("post_id" = Uuid, Path, description = "Post ID")
     ),
     responses(
-        (status = 201, description = "Comment created successfully", body = crate::models::feed::FeedComment),
+        (status = 201, description = "Comment created successfully", body = FeedComment),
         (status = 400, description = "Invalid input"),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Post not found")
@@ -198,35 +231,44 @@ pub async fn create_comment(
         .feed_service
         .create_comment(post_id, auth_user.id, request)
         .await?;
+    state.record(post_id, Some(auth_user.id), EngagementKind::Comment);
     Ok((StatusCode::CREATED, Json(comment)))
 }
 
-/// Get all comments on a post
-/// GET /api/feed/:post_id/comments
+/// Get comments on a post, oldest first
+/// GET /api/feed/:post_id/comments?cursor=&limit=50
 #[utoipa::path(
     get,
+    operation_id = "listFeedComments",
     path = "/api/feed/{post_id}/comments",
     tag = "Feed Comments",
     params(
-        ("post_id" = Uuid, Path, description = "Post ID")
+        ("post_id" = Uuid, Path, description = "Post ID"),
+        PageParams
     ),
     responses(
-        (status = 200, description = "Returns comments", body = Vec<crate::models::feed::FeedCommentResponse>),
+        (status = 200, description = "Returns comments", body = PaginatedComments),
         (status = 404, description = "Post not found")
     )
 )]
 pub async fn get_comments(
     State(state): State<Arc<FeedHandlerState>>,
     Path(post_id): Path<Uuid>,
+    Query(page): Query<PageParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let comments = state.feed_service.get_comments(post_id).await?;
-    Ok(Json(comments))
+    let offset = page.offset()?;
+    let (comments, total) = state
+        .feed_service
+        .get_comments(post_id, offset, page.limit(50, 100))
+        .await?;
+    Ok(Json(Paginated::new(comments, total, offset)))
 }
 
 /// Update a comment (owner only)
 /// PATCH /api/feed/comments/:comment_id
 #[utoipa::path(
     patch,
+    operation_id = "updateFeedComment",
     path = "/api/feed/comments/{comment_id}",
     tag = "Feed Comments",
     request_body = UpdateFeedCommentRequest,
@@ -234,7 +276,7 @@ pub async fn get_comments(
         ("comment_id" = Uuid, Path, description = "Comment ID")
     ),
     responses(
-        (status = 200, description = "Comment updated successfully", body = crate::models::feed::FeedComment),
+        (status = 200, description = "Comment updated successfully", body = FeedComment),
         (status = 400, description = "Invalid input"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Not the comment owner"),
@@ -261,6 +303,7 @@ pub async fn update_comment(
 /// DELETE /api/feed/comments/:comment_id
 #[utoipa::path(
     delete,
+    operation_id = "deleteFeedComment",
     path = "/api/feed/comments/{comment_id}",
     tag = "Feed Comments",
     params(
@@ -296,6 +339,7 @@ pub async fn delete_comment(
 /// POST /api/feed/:post_id/like
 #[utoipa::path(
     post,
+    operation_id = "likeFeedPost",
     path = "/api/feed/{post_id}/like",
     tag = "Feed Likes",
     params(
@@ -315,7 +359,9 @@ pub async fn like_post(
     auth_user: AuthUser,
     Path(post_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
-    state.feed_service.like_post(post_id, auth_user.id).await?;
+    if state.feed_service.like_post(post_id, auth_user.id).await? {
+        state.record(post_id, Some(auth_user.id), EngagementKind::Like);
+    }
     Ok(StatusCode::CREATED)
 }
 
@@ -323,6 +369,7 @@ pub async fn like_post(
 /// DELETE /api/feed/:post_id/like
 #[utoipa::path(
     delete,
+    operation_id = "unlikeFeedPost",
     path = "/api/feed/{post_id}/like",
     tag = "Feed Likes",
     params(
@@ -348,3 +395,43 @@ pub async fn unlike_post(
         .await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+// ============================================================================
+// ENGAGEMENT HANDLERS
+// ============================================================================
+
+/// Report how long a post stayed on screen; it counts towards the trending feed
+/// POST /api/feed/:post_id/dwell
+#[utoipa::path(
+    post,
+    operation_id = "recordFeedPostDwell",
+    path = "/api/feed/{post_id}/dwell",
+    tag = "Feed",
+    request_body = RecordDwellRequest,
+    params(
+        ("post_id" = Uuid, Path, description = "Post ID")
+    ),
+    responses(
+        (status = 202, description = "Dwell time accepted (ignored while trending is off)"),
+        (status = 400, description = "Invalid dwell time"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn record_dwell(
+    State(state): State<Arc<FeedHandlerState>>,
+    auth_user: AuthUser,
+    Path(post_id): Path<Uuid>,
+    Json(request): Json<RecordDwellRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    if let Some(engagement) = &state.engagement {
+        engagement.record_dwell(post_id, Some(auth_user.id), request.dwell_ms);
+    }
+    Ok(StatusCode::ACCEPTED)
+}
@@ -0,0 +1,90 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::home_area::{HomeAreaDashboard, SetHomeAreaRequest};
+use crate::services::home_area_service::HomeAreaService;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct HomeAreaHandlerState {
+    pub home_area_service: HomeAreaService,
+}
+
+/// Dashboard for the current user's home area: open reports, recent clears, their local
+/// leaderboard position and cleanup events, all within their search radius
+/// GET /api/users/me/area
+#[utoipa::path(
+    get,
+    operation_id = "getHomeAreaDashboard",
+    path = "/api/users/me/area",
+    tag = "Users",
+    responses(
+        (status = 200, description = "Returns the home area dashboard", body = HomeAreaDashboard),
+        (status = 404, description = "No home area set")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_home_area(
+    State(state): State<Arc<HomeAreaHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<Json<HomeAreaDashboard>, AppError> {
+    let dashboard = state.home_area_service.dashboard(auth_user.id).await?;
+    Ok(Json(dashboard))
+}
+
+/// Set or move the current user's home area
+/// PUT /api/users/me/area
+#[utoipa::path(
+    put,
+    operation_id = "setHomeArea",
+    path = "/api/users/me/area",
+    tag = "Users",
+    request_body(content = SetHomeAreaRequest, example = json!({
+        "latitude": 51.5074,
+        "longitude": -0.1278
+    })),
+    responses(
+        (status = 200, description = "Home area set; returns its dashboard", body = HomeAreaDashboard),
+        (status = 400, description = "Invalid coordinates")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_home_area(
+    State(state): State<Arc<HomeAreaHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<SetHomeAreaRequest>,
+) -> Result<Json<HomeAreaDashboard>, AppError> {
+    state
+        .home_area_service
+        .set_home(auth_user.id, request)
+        .await?;
+    let dashboard = state.home_area_service.dashboard(auth_user.id).await?;
+    Ok(Json(dashboard))
+}
+
+/// Forget the current user's home area
+/// DELETE /api/users/me/area
+#[utoipa::path(
+    delete,
+    operation_id = "deleteHomeArea",
+    path = "/api/users/me/area",
+    tag = "Users",
+    responses(
+        (status = 204, description = "Home area removed"),
+        (status = 404, description = "No home area set")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_home_area(
+    State(state): State<Arc<HomeAreaHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    state.home_area_service.clear_home(auth_user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,11 +1,16 @@
 use crate::error::AppError;
+use crate::image_access::ImageAccess;
+use crate::models::report_photo::{PhotoKind, ReportPhotoUrls};
+use crate::services::image_storage_service::ImageStorageService;
 use crate::services::report_service::ReportService;
 use crate::services::s3_service::S3Service;
 use axum::{
     extract::{Path, State},
     http::{header, StatusCode},
     response::IntoResponse,
+    Json,
 };
+use chrono::Utc;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -13,12 +18,29 @@ use uuid::Uuid;
 pub struct ImageHandlerState {
     pub report_service: ReportService,
     pub s3_service: S3Service,
+    pub image_storage: ImageStorageService,
+    pub access: Arc<ImageAccess>,
+}
+
+/// A photo's bytes from storage, or from the database while it waits to be uploaded
+async fn load_photo(state: &ImageHandlerState, url: &str) -> Result<Vec<u8>, AppError> {
+    // Extract bucket and S3 key from URL
+    let (area, key) = state
+        .s3_service
+        .extract_key_from_url(url)
+        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid S3 URL")))?;
+
+    match state.s3_service.get_image(area, &key).await {
+        Ok(image_data) => Ok(image_data),
+        Err(e) => state.image_storage.pending_image(url).await?.ok_or(e),
+    }
 }
 
 /// Get report before photo
 /// GET /api/images/reports/:id/before
 #[utoipa::path(
     get,
+    operation_id = "getReportBeforePhoto",
     path = "/api/images/reports/{id}/before",
     tag = "Images",
     params(
@@ -26,7 +48,9 @@ pub struct ImageHandlerState {
     ),
     responses(
         (status = 200, description = "Returns image", content_type = "image/webp"),
-        (status = 404, description = "Report or image not found")
+        (status = 403, description = "Embedded on another site, or the link is unsigned, invalid or expired"),
+        (status = 404, description = "Report or image not found"),
+        (status = 429, description = "Too many image requests from this IP")
     )
 )]
 pub async fn get_report_before_photo(
@@ -35,19 +59,10 @@ pub async fn get_report_before_photo(
 ) -> Result<impl IntoResponse, AppError> {
     let report = state.report_service.get_report_by_id(report_id).await?;
 
-    // Extract S3 key from URL
-    let key = state
-        .s3_service
-        .extract_key_from_url(
-            report
-                .photo_before
-                .as_ref()
-                .ok_or_else(|| AppError::NotFound("Before photo not found".into()))?,
-        )
-        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid S3 URL")))?;
-
-    // Get image data from S3
-    let image_data = state.s3_service.get_image(&key).await?;
+    let photo_before = report
+        .photo_before
+        .ok_or_else(|| AppError::NotFound("Before photo not found".into()))?;
+    let image_data = load_photo(&state, &photo_before).await?;
 
     Ok((
         StatusCode::OK,
@@ -63,6 +78,7 @@ pub async fn get_report_before_photo(
 /// GET /api/images/reports/:id/after
 #[utoipa::path(
     get,
+    operation_id = "getReportAfterPhoto",
     path = "/api/images/reports/{id}/after",
     tag = "Images",
     params(
@@ -70,7 +86,9 @@ pub async fn get_report_before_photo(
     ),
     responses(
         (status = 200, description = "Returns image", content_type = "image/webp"),
-        (status = 404, description = "Report or image not found")
+        (status = 403, description = "Embedded on another site, or the link is unsigned, invalid or expired"),
+        (status = 404, description = "Report or image not found"),
+        (status = 429, description = "Too many image requests from this IP")
     )
 )]
 pub async fn get_report_after_photo(
@@ -83,14 +101,50 @@ pub async fn get_report_after_photo(
         .photo_after
         .ok_or_else(|| AppError::NotFound("After photo not found".into()))?;
 
-    // Extract S3 key from URL
-    let key = state
-        .s3_service
-        .extract_key_from_url(&photo_after)
-        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid S3 URL")))?;
+    let image_data = load_photo(&state, &photo_after).await?;
 
-    // Get image data from S3
-    let image_data = state.s3_service.get_image(&key).await?;
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "image/webp"),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        image_data,
+    ))
+}
+
+/// Get one of a report's photos by its place in the list (0 is the first)
+/// GET /api/images/reports/:id/:kind/:position
+#[utoipa::path(
+    get,
+    operation_id = "getReportPhotoAt",
+    path = "/api/images/reports/{id}/{kind}/{position}",
+    tag = "Images",
+    params(
+        ("id" = Uuid, Path, description = "Report ID"),
+        ("kind" = PhotoKind, Path, description = "before or after"),
+        ("position" = usize, Path, description = "Index into the report's photos of that kind")
+    ),
+    responses(
+        (status = 200, description = "Returns image", content_type = "image/webp"),
+        (status = 403, description = "Embedded on another site, or the link is unsigned, invalid or expired"),
+        (status = 404, description = "Report or image not found"),
+        (status = 429, description = "Too many image requests from this IP")
+    )
+)]
+pub async fn get_report_photo_at(
+    State(state): State<Arc<ImageHandlerState>>,
+    Path((report_id, kind, position)): Path<(Uuid, PhotoKind, usize)>,
+) -> Result<impl IntoResponse, AppError> {
+    state.report_service.get_report_by_id(report_id).await?;
+    let photos = state.report_service.get_photos(report_id).await?;
+    let photo = match kind {
+        PhotoKind::Before => photos.before.get(position),
+        PhotoKind::After => photos.after.get(position),
+    }
+    .ok_or_else(|| AppError::NotFound("Photo not found".into()))?;
+
+    let image_data = load_photo(&state, &photo.url).await?;
 
     Ok((
         StatusCode::OK,
@@ -101,3 +155,57 @@ pub async fn get_report_after_photo(
         image_data,
     ))
 }
+
+/// Get links to each of a report's photos, signed when image links must be signed
+/// GET /api/images/reports/:id/urls
+#[utoipa::path(
+    get,
+    operation_id = "getReportPhotoUrls",
+    path = "/api/images/reports/{id}/urls",
+    tag = "Images",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Links to the report's photos", body = ReportPhotoUrls),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Report not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_report_photo_urls(
+    State(state): State<Arc<ImageHandlerState>>,
+    Path(report_id): Path<Uuid>,
+) -> Result<Json<ReportPhotoUrls>, AppError> {
+    state.report_service.get_report_by_id(report_id).await?;
+    let photos = state.report_service.get_photos(report_id).await?;
+
+    let now = Utc::now();
+    let mut expires_at = None;
+    let mut link = |kind: &str, position: usize| {
+        let path = format!("/api/images/reports/{report_id}/{kind}/{position}");
+        match state.access.signer() {
+            Some(signer) => {
+                let signed = signer.sign(&path, now);
+                expires_at = Some(signed.expires_at);
+                signed.url
+            }
+            None => path,
+        }
+    };
+
+    let before = (0..photos.before.len())
+        .map(|position| link("before", position))
+        .collect();
+    let after = (0..photos.after.len())
+        .map(|position| link("after", position))
+        .collect();
+
+    Ok(Json(ReportPhotoUrls {
+        before,
+        after,
+        expires_at,
+    }))
+}
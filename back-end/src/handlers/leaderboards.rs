@@ -1,45 +1,65 @@
 use crate::error::AppError;
-use crate::models::score::LeaderboardEntry;
+use crate::models::pagination::{PageParams, Paginated};
+use crate::models::score::{Leaderboard, LeaderboardEntry, LeaderboardPeriod, LeaderboardQuery};
 use axum::{
     extract::{Path, Query, State},
-    response::IntoResponse,
     Json,
 };
-use chrono::{Duration, Utc};
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use std::sync::Arc;
-use utoipa::IntoParams;
 
 #[derive(Clone)]
 pub struct LeaderboardHandlerState {
     pub pool: PgPool,
-}
-
-#[derive(Debug, Deserialize, IntoParams)]
-pub struct LeaderboardQuery {
-    #[param(example = "weekly")]
-    pub period: Option<String>, // "weekly", "monthly", "all_time"
+    /// Time zone weekly and monthly boards turn over in
+    pub timezone: String,
 }
 
 /// Get global leaderboard
 /// GET /api/leaderboards?period=weekly
 #[utoipa::path(
     get,
+    operation_id = "getGlobalLeaderboard",
     path = "/api/leaderboards",
     tag = "Leaderboards",
     params(
-        LeaderboardQuery
+        LeaderboardQuery,
+        PageParams
     ),
     responses(
-        (status = 200, description = "Returns leaderboard", body = Vec<LeaderboardEntry>)
+        (status = 200, description = "Returns leaderboard", body = Leaderboard, example = json!({
+            "items": [{
+                "user_id": "6f1c2a8e-3b4d-4e5f-8a9b-0c1d2e3f4a5b",
+                "full_name": "John Doe",
+                "city": "London",
+                "country": "UK",
+                "total_points": 420,
+                "reports_cleared": 31,
+                "current_streak": 4,
+                "rank": 1
+            }],
+            "total": 1,
+            "next_cursor": null,
+            "period": "weekly",
+            "timezone": "Europe/London",
+            "period_start": "2026-10-11T23:00:00Z",
+            "period_end": "2026-10-18T23:00:00Z"
+        })),
+        (status = 400, description = "Invalid period or cursor"),
+        (status = 429, description = "API key rate limit exceeded")
+    ),
+    security(
+        (),
+        ("api_key" = [])
     )
 )]
 pub async fn get_global_leaderboard(
     State(state): State<Arc<LeaderboardHandlerState>>,
     Query(query): Query<LeaderboardQuery>,
-) -> Result<impl IntoResponse, AppError> {
-    let leaderboard = get_leaderboard(&state.pool, None, None, query.period).await?;
+    Query(page): Query<PageParams>,
+) -> Result<Json<Leaderboard>, AppError> {
+    let leaderboard = get_leaderboard(&state, None, None, query.period, &page).await?;
     Ok(Json(leaderboard))
 }
 
@@ -47,22 +67,48 @@ pub async fn get_global_leaderboard(
 /// GET /api/leaderboards/city/:city?period=weekly
 #[utoipa::path(
     get,
+    operation_id = "getCityLeaderboard",
     path = "/api/leaderboards/city/{city}",
     tag = "Leaderboards",
     params(
-        ("city" = String, Path, description = "City name"),
-        LeaderboardQuery
+        ("city" = String, Path, description = "City name", example = "London"),
+        LeaderboardQuery,
+        PageParams
     ),
     responses(
-        (status = 200, description = "Returns city leaderboard", body = Vec<LeaderboardEntry>)
+        (status = 200, description = "Returns city leaderboard", body = Leaderboard, example = json!({
+            "items": [{
+                "user_id": "6f1c2a8e-3b4d-4e5f-8a9b-0c1d2e3f4a5b",
+                "full_name": "John Doe",
+                "city": "London",
+                "country": "UK",
+                "total_points": 420,
+                "reports_cleared": 31,
+                "current_streak": 4,
+                "rank": 1
+            }],
+            "total": 1,
+            "next_cursor": null,
+            "period": "weekly",
+            "timezone": "Europe/London",
+            "period_start": "2026-10-11T23:00:00Z",
+            "period_end": "2026-10-18T23:00:00Z"
+        })),
+        (status = 400, description = "Invalid period or cursor"),
+        (status = 429, description = "API key rate limit exceeded")
+    ),
+    security(
+        (),
+        ("api_key" = [])
     )
 )]
 pub async fn get_city_leaderboard(
     State(state): State<Arc<LeaderboardHandlerState>>,
     Path(city): Path<String>,
     Query(query): Query<LeaderboardQuery>,
-) -> Result<impl IntoResponse, AppError> {
-    let leaderboard = get_leaderboard(&state.pool, Some(city), None, query.period).await?;
+    Query(page): Query<PageParams>,
+) -> Result<Json<Leaderboard>, AppError> {
+    let leaderboard = get_leaderboard(&state, Some(city), None, query.period, &page).await?;
     Ok(Json(leaderboard))
 }
 
@@ -70,202 +116,174 @@ pub async fn get_city_leaderboard(
 /// GET /api/leaderboards/country/:country?period=weekly
 #[utoipa::path(
     get,
+    operation_id = "getCountryLeaderboard",
     path = "/api/leaderboards/country/{country}",
     tag = "Leaderboards",
     params(
         ("country" = String, Path, description = "Country name"),
-        LeaderboardQuery
+        LeaderboardQuery,
+        PageParams
     ),
     responses(
-        (status = 200, description = "Returns country leaderboard", body = Vec<LeaderboardEntry>)
+        (status = 200, description = "Returns country leaderboard", body = Leaderboard),
+        (status = 400, description = "Invalid period or cursor"),
+        (status = 429, description = "API key rate limit exceeded")
+    ),
+    security(
+        (),
+        ("api_key" = [])
     )
 )]
 pub async fn get_country_leaderboard(
     State(state): State<Arc<LeaderboardHandlerState>>,
     Path(country): Path<String>,
     Query(query): Query<LeaderboardQuery>,
-) -> Result<impl IntoResponse, AppError> {
-    let leaderboard = get_leaderboard(&state.pool, None, Some(country), query.period).await?;
+    Query(page): Query<PageParams>,
+) -> Result<Json<Leaderboard>, AppError> {
+    let leaderboard = get_leaderboard(&state, None, Some(country), query.period, &page).await?;
     Ok(Json(leaderboard))
 }
 
+/// Start and end of the calendar `unit` (`week` or `month`) containing now, as seen
+/// in `timezone`
+async fn period_bounds(
+    pool: &PgPool,
+    unit: &str,
+    timezone: &str,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+    let bounds = sqlx::query_as(
+        r"
+        SELECT date_trunc($1, NOW() AT TIME ZONE $2) AT TIME ZONE $2,
+               (date_trunc($1, NOW() AT TIME ZONE $2) + ('1 ' || $1)::interval) AT TIME ZONE $2
+        ",
+    )
+    .bind(unit)
+    .bind(timezone)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(bounds)
+}
+
 /// Internal helper to build leaderboard query
 async fn get_leaderboard(
-    pool: &PgPool,
+    state: &LeaderboardHandlerState,
     city: Option<String>,
     country: Option<String>,
-    period: Option<String>,
-) -> Result<Vec<LeaderboardEntry>, AppError> {
-    // Calculate time filter based on period
-    let time_filter = match period.as_deref() {
-        Some("weekly") => Some(Utc::now() - Duration::weeks(1)),
-        Some("monthly") => Some(Utc::now() - Duration::days(30)),
-        Some("all_time") | None => None,
-        _ => {
-            return Err(AppError::BadRequest(
-                "Invalid period. Use 'weekly', 'monthly', or 'all_time'".to_string(),
-            ))
-        }
+    period: Option<LeaderboardPeriod>,
+    page: &PageParams,
+) -> Result<Leaderboard, AppError> {
+    let pool = &state.pool;
+    let period = period.unwrap_or_default();
+    let bounds = match period.calendar_unit() {
+        Some(unit) => Some(period_bounds(pool, unit, &state.timezone).await?),
+        None => None,
     };
+    let time_filter = bounds.map(|(start, _)| start);
 
-    // Build the query dynamically based on filters
-    let leaderboard = if let Some(time) = time_filter {
+    let offset = page.offset()?;
+    let limit = page.limit(20, 100);
+
+    // Ranks are computed over the whole filtered set so they stay stable across pages;
+    // the window count gives the total without a second round trip
+    let (leaderboard, total) = if let Some(time) = time_filter {
         // Time-based leaderboard (recent activity) - don't need user_scores for time-based
-        if let Some(c) = city {
-            // City + time filter
-            sqlx::query_as!(
-                LeaderboardEntry,
-                r#"
-                SELECT 
-                    u.id as user_id,
-                    u.full_name,
-                    u.city,
-                    u.country,
-                    COALESCE(SUM(se.points), 0)::int as "total_points!",
-                    COUNT(CASE WHEN se.kind = 'clear' THEN 1 END)::int as "reports_cleared!",
-                    0 as "current_streak!",
-                    ROW_NUMBER() OVER (ORDER BY COALESCE(SUM(se.points), 0) DESC) as "rank!"
-                FROM users u
-                LEFT JOIN score_events se ON u.id = se.user_id AND se.created_at > $1
-                WHERE u.city = $2
-                GROUP BY u.id, u.full_name, u.city, u.country
-                HAVING COALESCE(SUM(se.points), 0) > 0
-                ORDER BY COALESCE(SUM(se.points), 0) DESC
-                LIMIT 20
-                "#,
-                time,
-                c
-            )
-            .fetch_all(pool)
-            .await?
-        } else if let Some(co) = country {
-            // Country + time filter
-            sqlx::query_as!(
-                LeaderboardEntry,
-                r#"
-                SELECT 
-                    u.id as user_id,
-                    u.full_name,
-                    u.city,
-                    u.country,
-                    COALESCE(SUM(se.points), 0)::int as "total_points!",
-                    COUNT(CASE WHEN se.kind = 'clear' THEN 1 END)::int as "reports_cleared!",
-                    0 as "current_streak!",
-                    ROW_NUMBER() OVER (ORDER BY COALESCE(SUM(se.points), 0) DESC) as "rank!"
-                FROM users u
-                LEFT JOIN score_events se ON u.id = se.user_id AND se.created_at > $1
-                WHERE u.country = $2
-                GROUP BY u.id, u.full_name, u.city, u.country
-                HAVING COALESCE(SUM(se.points), 0) > 0
-                ORDER BY COALESCE(SUM(se.points), 0) DESC
-                LIMIT 20
-                "#,
-                time,
-                co
-            )
-            .fetch_all(pool)
-            .await?
-        } else {
-            // Global + time filter
-            sqlx::query_as!(
-                LeaderboardEntry,
-                r#"
-                SELECT 
-                    u.id as user_id,
-                    u.full_name,
-                    u.city,
-                    u.country,
-                    COALESCE(SUM(se.points), 0)::int as "total_points!",
-                    COUNT(CASE WHEN se.kind = 'clear' THEN 1 END)::int as "reports_cleared!",
-                    0 as "current_streak!",
-                    ROW_NUMBER() OVER (ORDER BY COALESCE(SUM(se.points), 0) DESC) as "rank!"
-                FROM users u
-                LEFT JOIN score_events se ON u.id = se.user_id AND se.created_at > $1
-                GROUP BY u.id, u.full_name, u.city, u.country
-                HAVING COALESCE(SUM(se.points), 0) > 0
-                ORDER BY COALESCE(SUM(se.points), 0) DESC
-                LIMIT 20
-                "#,
-                time
-            )
-            .fetch_all(pool)
-            .await?
-        }
+        let rows = sqlx::query!(
+            r#"
+            SELECT 
+                u.id as user_id,
+                u.full_name,
+                u.city,
+                u.country,
+                COALESCE(SUM(se.points), 0)::int as "total_points!",
+                COUNT(CASE WHEN se.kind = 'clear' THEN 1 END)::int as "reports_cleared!",
+                ROW_NUMBER() OVER (ORDER BY COALESCE(SUM(se.points), 0) DESC) as "rank!",
+                COUNT(*) OVER () as "total!"
+            FROM users u
+            LEFT JOIN score_events se ON u.id = se.user_id AND se.created_at > $1
+            WHERE ($2::text IS NULL OR u.city = $2)
+              AND ($3::text IS NULL OR u.country = $3)
+            GROUP BY u.id, u.full_name, u.city, u.country
+            HAVING COALESCE(SUM(se.points), 0) > 0
+            ORDER BY COALESCE(SUM(se.points), 0) DESC
+            LIMIT $4 OFFSET $5
+            "#,
+            time,
+            city,
+            country,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let total = rows.first().map_or(0, |r| r.total);
+        let entries = rows
+            .into_iter()
+            .map(|r| LeaderboardEntry {
+                user_id: r.user_id,
+                full_name: r.full_name,
+                city: r.city,
+                country: r.country,
+                total_points: r.total_points,
+                reports_cleared: r.reports_cleared,
+                current_streak: 0,
+                rank: r.rank,
+            })
+            .collect();
+        (entries, total)
     } else {
         // All-time leaderboard (use user_scores table)
-        if let Some(c) = city {
-            // City filter
-            sqlx::query_as!(
-                LeaderboardEntry,
-                r#"
-                SELECT 
-                    u.id as user_id,
-                    u.full_name,
-                    u.city,
-                    u.country,
-                    us.total_points,
-                    us.total_clears as "reports_cleared!",
-                    us.current_streak,
-                    ROW_NUMBER() OVER (ORDER BY us.total_points DESC) as "rank!"
-                FROM users u
-                INNER JOIN user_scores us ON u.id = us.user_id
-                WHERE u.city = $1 AND us.total_clears > 0
-                ORDER BY us.total_points DESC
-                LIMIT 20
-                "#,
-                c
-            )
-            .fetch_all(pool)
-            .await?
-        } else if let Some(co) = country {
-            // Country filter
-            sqlx::query_as!(
-                LeaderboardEntry,
-                r#"
-                SELECT 
-                    u.id as user_id,
-                    u.full_name,
-                    u.city,
-                    u.country,
-                    us.total_points,
-                    us.total_clears as "reports_cleared!",
-                    us.current_streak,
-                    ROW_NUMBER() OVER (ORDER BY us.total_points DESC) as "rank!"
-                FROM users u
-                INNER JOIN user_scores us ON u.id = us.user_id
-                WHERE u.country = $1 AND us.total_clears > 0
-                ORDER BY us.total_points DESC
-                LIMIT 20
-                "#,
-                co
-            )
-            .fetch_all(pool)
-            .await?
-        } else {
-            // Global
-            sqlx::query_as!(
-                LeaderboardEntry,
-                r#"
-                SELECT 
-                    u.id as user_id,
-                    u.full_name,
-                    u.city,
-                    u.country,
-                    us.total_points,
-                    us.total_clears as "reports_cleared!",
-                    us.current_streak,
-                    ROW_NUMBER() OVER (ORDER BY us.total_points DESC) as "rank!"
-                FROM users u
-                INNER JOIN user_scores us ON u.id = us.user_id
-                WHERE us.total_clears > 0
-                ORDER BY us.total_points DESC
-                LIMIT 20
-                "#
-            )
-            .fetch_all(pool)
-            .await?
-        }
+        let rows = sqlx::query!(
+            r#"
+            SELECT 
+                u.id as user_id,
+                u.full_name,
+                u.city,
+                u.country,
+                us.total_points,
+                us.total_clears as "reports_cleared!",
+                us.current_streak,
+                ROW_NUMBER() OVER (ORDER BY us.total_points DESC) as "rank!",
+                COUNT(*) OVER () as "total!"
+            FROM users u
+            INNER JOIN user_scores us ON u.id = us.user_id
+            WHERE us.total_clears > 0
+              AND ($1::text IS NULL OR u.city = $1)
+              AND ($2::text IS NULL OR u.country = $2)
+            ORDER BY us.total_points DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            city,
+            country,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let total = rows.first().map_or(0, |r| r.total);
+        let entries = rows
+            .into_iter()
+            .map(|r| LeaderboardEntry {
+                user_id: r.user_id,
+                full_name: r.full_name,
+                city: r.city,
+                country: r.country,
+                total_points: r.total_points,
+                reports_cleared: r.reports_cleared,
+                current_streak: r.current_streak,
+                rank: r.rank,
+            })
+            .collect();
+        (entries, total)
     };
 
-    Ok(leaderboard)
+    Ok(Leaderboard {
+        period,
+        timezone: state.timezone.clone(),
+        period_start: bounds.map(|(start, _)| start),
+        period_end: bounds.map(|(_, end)| end),
+        page: Paginated::new(leaderboard, total, offset),
+    })
 }
@@ -1,21 +1,49 @@
 pub mod admin;
 pub mod auth;
+pub mod capabilities;
+pub mod city_goals;
+pub mod cleanup_events;
+pub mod email_webhooks;
+pub mod exports;
 pub mod feed;
+pub mod home_areas;
 pub mod images;
 pub mod leaderboards;
+pub mod notifications;
 pub mod oauth;
+pub mod open_data;
 pub mod reports;
+pub mod saved_searches;
+pub mod scoring;
+pub mod stats;
 pub mod test_helpers;
+pub mod two_factor;
+pub mod uploads;
 pub mod users;
 pub mod verifications;
+pub mod watched_areas;
 
 pub use admin::*;
 pub use auth::*;
+pub use capabilities::*;
+pub use city_goals::*;
+pub use cleanup_events::*;
+pub use email_webhooks::*;
+pub use exports::*;
 pub use feed::*;
+pub use home_areas::*;
 pub use images::*;
 pub use leaderboards::*;
+pub use notifications::*;
 pub use oauth::*;
+pub use open_data::*;
 pub use reports::*;
+pub use saved_searches::*;
+pub use scoring::*;
+pub use stats::*;
 pub use test_helpers::*;
+pub use two_factor::*;
+pub use uploads::*;
 pub use users::*;
 pub use verifications::*;
+pub use watched_areas::*;
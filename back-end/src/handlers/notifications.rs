@@ -0,0 +1,54 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::notification::NotificationPollQuery;
+use crate::services::notification_service::NotificationService;
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Longest a poll is held open; kept below common proxy idle timeouts (30s)
+const MAX_POLL_WAIT_SECS: u64 = 25;
+
+#[derive(Clone)]
+pub struct NotificationHandlerState {
+    pub notification_service: NotificationService,
+}
+
+/// Long-poll for new notifications
+/// GET /api/notifications/poll?since=...
+#[utoipa::path(
+    get,
+    operation_id = "pollNotifications",
+    path = "/api/notifications/poll",
+    tag = "Notifications",
+    params(NotificationPollQuery),
+    responses(
+        (status = 200, description = "New notifications, or an empty list if none arrived before the timeout", body = NotificationPollResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn poll_notifications(
+    State(state): State<Arc<NotificationHandlerState>>,
+    auth_user: AuthUser,
+    Query(query): Query<NotificationPollQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let wait = Duration::from_secs(
+        query
+            .timeout_secs
+            .unwrap_or(MAX_POLL_WAIT_SECS)
+            .min(MAX_POLL_WAIT_SECS),
+    );
+
+    let response = state
+        .notification_service
+        .poll(auth_user.id, query.since, wait)
+        .await?;
+    Ok(Json(response))
+}
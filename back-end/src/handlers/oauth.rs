@@ -1,14 +1,15 @@
+use crate::auth::middleware::AuthUser;
+use crate::auth::ClientInfo;
 use crate::error::AppError;
-use crate::services::{AuthService, OAuthService};
+use crate::services::{AuthService, OAuthLoginOutcome, OAuthService, OAuthStateStore};
 use axum::{
     extract::{Query, State},
     response::{IntoResponse, Redirect},
+    Json,
 };
 use openidconnect::Nonce;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use utoipa::{IntoParams, ToSchema};
 
 /// Shared state for OAuth handlers
@@ -18,8 +19,8 @@ pub struct OAuthHandlerState {
     pub auth_service: Arc<AuthService>,
     pub frontend_url: String,
     pub redirect_url: String,
-    /// Store CSRF tokens and nonces temporarily (in production, use Redis or database)
-    pub session_store: Arc<RwLock<HashMap<String, String>>>,
+    /// Nonces for in-flight authorizations, keyed by CSRF state
+    pub state_store: Arc<dyn OAuthStateStore>,
 }
 
 /// Query parameters for OAuth callback
@@ -36,10 +37,24 @@ pub struct OAuthLoginResponse {
     pub auth_url: String,
 }
 
+/// Start linking a sign-in provider to the current account
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LinkOAuthRequest {
+    #[schema(example = "google")]
+    pub provider: String,
+    /// Current password, to re-authenticate
+    #[schema(example = "SecurePassword123")]
+    pub password: String,
+    /// TOTP or backup code, required when two-factor is enabled
+    #[schema(example = "123456")]
+    pub code: Option<String>,
+}
+
 /// Initiate Google OAuth login
 /// GET /api/auth/google
 #[utoipa::path(
     get,
+    operation_id = "startGoogleLogin",
     path = "/api/auth/google",
     tag = "OAuth",
     responses(
@@ -52,18 +67,70 @@ pub async fn google_login(
     let (auth_url, csrf_token, nonce) = state.oauth_service.get_authorization_url();
 
     // Store the nonce associated with the CSRF token
-    // In production, this should use Redis or a database with TTL
-    let mut session_store = state.session_store.write().await;
-    session_store.insert(csrf_token.secret().clone(), nonce.secret().clone());
+    state
+        .state_store
+        .insert(csrf_token.secret(), nonce.secret())
+        .await?;
 
     // Return the authorization URL for the client to redirect to
     Ok(Redirect::to(&auth_url.to_string()))
 }
 
+/// Start linking Google to the signed-in account; the user finishes at the returned URL
+/// POST /api/users/me/oauth/link
+#[utoipa::path(
+    post,
+    operation_id = "linkOAuthProvider",
+    path = "/api/users/me/oauth/link",
+    tag = "OAuth",
+    request_body = LinkOAuthRequest,
+    responses(
+        (status = 200, description = "Returns the provider authorization URL; the callback links the account", body = OAuthLoginResponse),
+        (status = 400, description = "Unsupported provider"),
+        (status = 401, description = "Re-authentication failed"),
+        (status = 409, description = "Account already linked to a provider")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn link_oauth_provider(
+    State(state): State<Arc<OAuthHandlerState>>,
+    auth_user: AuthUser,
+    Json(req): Json<LinkOAuthRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if req.provider != "google" {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported sign-in provider: {}",
+            req.provider
+        )));
+    }
+
+    let user = state
+        .auth_service
+        .reauthenticate(auth_user.id, &req.password, req.code.as_deref())
+        .await?;
+
+    let (auth_url, csrf_token, nonce) = state.oauth_service.get_authorization_url();
+
+    state
+        .auth_service
+        .begin_oauth_link(&user, &req.provider, csrf_token.secret())
+        .await?;
+
+    state
+        .state_store
+        .insert(csrf_token.secret(), nonce.secret())
+        .await?;
+
+    Ok(Json(OAuthLoginResponse { auth_url }))
+}
+
 /// Handle Google OAuth callback
 /// GET /api/auth/google/callback
 #[utoipa::path(
     get,
+    operation_id = "completeGoogleLogin",
     path = "/api/auth/google/callback",
     tag = "OAuth",
     params(
@@ -71,21 +138,22 @@ pub async fn google_login(
     ),
     responses(
         (status = 200, description = "OAuth login successful", body = AuthTokens),
+        (status = 303, description = "Two-factor required (redirects to the login page with a challenge), or email belongs to an existing account; a link confirmation email was sent"),
         (status = 401, description = "Invalid or expired session"),
         (status = 500, description = "OAuth exchange failed")
     )
 )]
 pub async fn google_callback(
     State(state): State<Arc<OAuthHandlerState>>,
+    client: ClientInfo,
     Query(params): Query<OAuthCallback>,
 ) -> Result<impl IntoResponse, AppError> {
     // Retrieve the nonce for this CSRF token
-    let nonce_secret = {
-        let mut session_store = state.session_store.write().await;
-        session_store
-            .remove(&params.state)
-            .ok_or_else(|| AppError::Auth("Invalid or expired session".to_string()))?
-    };
+    let nonce_secret = state
+        .state_store
+        .take(&params.state)
+        .await?
+        .ok_or_else(|| AppError::Auth("Invalid or expired session".to_string()))?;
 
     let nonce = Nonce::new(nonce_secret);
 
@@ -95,8 +163,33 @@ pub async fn google_callback(
         .exchange_code(params.code, nonce)
         .await?;
 
+    // A signed-in user linking Google rather than logging in
+    if let Some(user_id) = state
+        .auth_service
+        .take_oauth_link_request(&params.state)
+        .await?
+    {
+        state
+            .auth_service
+            .link_oauth_identity(user_id, "google", &oauth_info)
+            .await?;
+        return Ok(Redirect::to(&linked_url(&state.frontend_url)).into_response());
+    }
+
     // Login or create user
-    let auth_tokens = state.auth_service.oauth_login(oauth_info).await?;
+    let auth_tokens = match state.auth_service.oauth_login(oauth_info, &client).await? {
+        OAuthLoginOutcome::LoggedIn(tokens) => tokens,
+        OAuthLoginOutcome::TwoFactorRequired(challenge) => {
+            return Ok(Redirect::to(&two_factor_url(
+                &state.frontend_url,
+                &challenge.challenge_token,
+            ))
+            .into_response());
+        }
+        OAuthLoginOutcome::LinkConfirmationRequired => {
+            return Ok(Redirect::to(&link_pending_url(&state.frontend_url)).into_response());
+        }
+    };
 
     let html = format!(
         r#"<!DOCTYPE html>
@@ -127,15 +220,15 @@ pub async fn google_callback(
 /// This version redirects to the frontend with tokens in URL fragment (client-side only)
 pub async fn google_callback_redirect(
     State(state): State<Arc<OAuthHandlerState>>,
+    client: ClientInfo,
     Query(params): Query<OAuthCallback>,
 ) -> Result<Redirect, AppError> {
     // Retrieve the nonce for this CSRF token
-    let nonce_secret = {
-        let mut session_store = state.session_store.write().await;
-        session_store
-            .remove(&params.state)
-            .ok_or_else(|| AppError::Auth("Invalid or expired session".to_string()))?
-    };
+    let nonce_secret = state
+        .state_store
+        .take(&params.state)
+        .await?
+        .ok_or_else(|| AppError::Auth("Invalid or expired session".to_string()))?;
 
     let nonce = Nonce::new(nonce_secret);
 
@@ -145,8 +238,32 @@ pub async fn google_callback_redirect(
         .exchange_code(params.code, nonce)
         .await?;
 
+    // A signed-in user linking Google rather than logging in
+    if let Some(user_id) = state
+        .auth_service
+        .take_oauth_link_request(&params.state)
+        .await?
+    {
+        state
+            .auth_service
+            .link_oauth_identity(user_id, "google", &oauth_info)
+            .await?;
+        return Ok(Redirect::to(&linked_url(&state.frontend_url)));
+    }
+
     // Login or create user
-    let auth_tokens = state.auth_service.oauth_login(oauth_info).await?;
+    let auth_tokens = match state.auth_service.oauth_login(oauth_info, &client).await? {
+        OAuthLoginOutcome::LoggedIn(tokens) => tokens,
+        OAuthLoginOutcome::TwoFactorRequired(challenge) => {
+            return Ok(Redirect::to(&two_factor_url(
+                &state.frontend_url,
+                &challenge.challenge_token,
+            )));
+        }
+        OAuthLoginOutcome::LinkConfirmationRequired => {
+            return Ok(Redirect::to(&link_pending_url(&state.frontend_url)));
+        }
+    };
 
     let redirect_url = format!(
         "{}#access_token={}&refresh_token={}",
@@ -155,3 +272,18 @@ pub async fn google_callback_redirect(
 
     Ok(Redirect::to(&redirect_url))
 }
+
+/// Profile settings page, after linking a provider to a signed-in account
+fn linked_url(frontend_url: &str) -> String {
+    format!("{frontend_url}/profile/me/edit?linked=google")
+}
+
+/// Frontend page telling the user to confirm the account link from their inbox
+fn link_pending_url(frontend_url: &str) -> String {
+    format!("{frontend_url}/link-account?pending=true")
+}
+
+/// Login page prompting for the second factor of this challenge
+fn two_factor_url(frontend_url: &str, challenge_token: &str) -> String {
+    format!("{frontend_url}/auth/login?challenge={challenge_token}")
+}
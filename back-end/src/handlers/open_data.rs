@@ -0,0 +1,84 @@
+use crate::error::AppError;
+use crate::models::open_data::{OpenDataFormat, OpenDataQuery, OpenDataReport, OpenDataResponse};
+use crate::services::open_data_service::MAX_OPEN_DATA_REPORTS;
+use crate::services::OpenDataService;
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct OpenDataHandlerState {
+    pub open_data_service: OpenDataService,
+}
+
+/// Get anonymized cleared reports with daily totals, as JSON or CSV. Public and cached
+/// for five minutes.
+/// GET /api/open-data/reports?from=2024-04-01&to=2024-04-30&format=csv
+#[utoipa::path(
+    get,
+    operation_id = "getOpenDataReports",
+    path = "/api/open-data/reports",
+    tag = "Open Data",
+    params(
+        OpenDataQuery
+    ),
+    responses(
+        (status = 200, description = "Cleared reports in the range", content(
+            ("application/json" = OpenDataResponse),
+            ("text/csv" = String)
+        )),
+        (status = 400, description = "Invalid date range, category or format")
+    )
+)]
+pub async fn get_open_data_reports(
+    State(state): State<Arc<OpenDataHandlerState>>,
+    Query(query): Query<OpenDataQuery>,
+) -> Result<Response, AppError> {
+    let (from, to) = OpenDataService::range(&query)?;
+    let (reports, total) = state
+        .open_data_service
+        .reports(from, to, query.category, MAX_OPEN_DATA_REPORTS)
+        .await?;
+    let cache = (header::CACHE_CONTROL, "public, max-age=300");
+
+    if query.format.unwrap_or_default() == OpenDataFormat::Csv {
+        let mut csv = String::from(OpenDataReport::CSV_HEADER);
+        csv.push_str("\r\n");
+        for report in &reports {
+            csv.push_str(&report.csv_row());
+            csv.push_str("\r\n");
+        }
+        let disposition = format!("attachment; filename=\"littypicky-reports-{from}-{to}.csv\"");
+
+        return Ok((
+            [
+                cache,
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (header::CONTENT_DISPOSITION, disposition.as_str()),
+            ],
+            csv,
+        )
+            .into_response());
+    }
+
+    let daily = state
+        .open_data_service
+        .daily(from, to, query.category)
+        .await?;
+
+    Ok((
+        [cache],
+        Json(OpenDataResponse {
+            from,
+            to,
+            total,
+            reports,
+            daily,
+        }),
+    )
+        .into_response())
+}
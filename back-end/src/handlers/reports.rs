@@ -1,36 +1,123 @@
 use crate::auth::middleware::AuthUser;
+use crate::auth::ClientInfo;
 use crate::error::AppError;
+use crate::models::cleanup_run::{BatchClaimResult, ClaimBatchRequest, ClaimBatchResponse};
+use crate::models::coordinates::Coordinates;
+use crate::models::geojson::{respond_as, FormatQuery, OutputFormat};
+use crate::models::notification::NotificationKind;
+use crate::models::pagination::{KeysetCursor, KeysetPageParams, Paginated};
 use crate::models::report::{
-    ClearReportRequest, CreateReportRequest, NearbyReportsQuery, ReportResponse,
+    AnonymousReportResponse, ClaimAnonymousReportRequest, ClearReportRequest,
+    CreateAnonymousReportRequest, CreateReportRequest, LitterFilter, LitterReport,
+    NearbyReportsQuery, ReportClustersResponse, ReportFilter, ReportListQuery, ReportResponse,
+    ReportStatus, ReportsInBoundsQuery, ReportsInBoundsResponse, VerificationQueueQuery,
 };
-use crate::services::report_service::ReportService;
+use crate::models::report_claim::{ReportTimelineResponse, UnclaimReportRequest};
+use crate::models::report_edit::UpdateReportRequest;
+use crate::models::report_flag::FlagReportRequest;
+use crate::models::report_lifecycle::ReportHistoryResponse;
+use crate::models::report_photo::{AddReportPhotoRequest, ReportPhotos};
+use crate::models::translation::{parse_language_code, GetReportQuery};
+use crate::services::captcha_service::CaptchaService;
+use crate::services::city_goal_service::CityGoalService;
+use crate::services::cleanup_event_service::CleanupEventService;
+use crate::services::notification_service::NotificationService;
+use crate::services::report_flag_service::ReportFlagService;
+use crate::services::report_service::{
+    cluster_geohash_precision, zoom_for_bounds, ReportService, CLUSTER_BELOW_ZOOM,
+    MAX_REPORTS_IN_BOUNDS,
+};
+use crate::services::saved_search_service::SavedSearchService;
 use crate::services::scoring_service::ScoringService;
+use crate::services::translation_service::TranslationService;
+use crate::services::watched_area_service::WatchedAreaService;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use std::sync::Arc;
 use uuid::Uuid;
+use validator::Validate;
 
 #[derive(Clone)]
 pub struct ReportHandlerState {
     pub report_service: ReportService,
+    pub report_flag_service: ReportFlagService,
     pub scoring_service: ScoringService,
+    pub saved_search_service: SavedSearchService,
+    pub notification_service: NotificationService,
+    pub captcha_service: CaptchaService,
+    pub watched_area_service: WatchedAreaService,
+    pub cleanup_event_service: CleanupEventService,
+    pub city_goal_service: CityGoalService,
+    pub translation_service: TranslationService,
+}
+
+/// Tell everyone watching an area that holds a new report, then everyone with a
+/// notifying saved search it matches who hasn't already heard. Failures are logged,
+/// since the report itself was made.
+async fn notify_watchers(state: &ReportHandlerState, report_id: Uuid) {
+    let watchers = match state.watched_area_service.watchers_of(report_id).await {
+        Ok(watchers) => {
+            state
+                .notification_service
+                .notify_many(
+                    &watchers,
+                    NotificationKind::WatchedAreaReport,
+                    "New litter report in an area you watch",
+                    "Someone has reported litter in one of your watched areas.",
+                    Some(report_id),
+                )
+                .await;
+            watchers
+        }
+        Err(e) => {
+            tracing::warn!("Failed to find watchers of report {}: {:?}", report_id, e);
+            Vec::new()
+        }
+    };
+
+    match state.saved_search_service.subscribers_of(report_id).await {
+        Ok(mut subscribers) => {
+            subscribers.retain(|user_id| !watchers.contains(user_id));
+            state
+                .notification_service
+                .notify_many(
+                    &subscribers,
+                    NotificationKind::SavedSearchReport,
+                    "New litter report matching a saved search",
+                    "Someone has reported litter near your home area that matches one of your saved searches.",
+                    Some(report_id),
+                )
+                .await;
+        }
+        Err(e) => tracing::warn!(
+            "Failed to find saved search subscribers of report {}: {:?}",
+            report_id,
+            e
+        ),
+    }
 }
 
 /// Create a new litter report
 /// POST /api/reports
 #[utoipa::path(
     post,
+    operation_id = "createReport",
     path = "/api/reports",
     tag = "Reports",
     request_body = CreateReportRequest,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Unique value per user action; a retry with the same key replays the original response")
+    ),
     responses(
         (status = 201, description = "Report created successfully", body = ReportResponse),
         (status = 400, description = "Invalid input or image"),
-        (status = 403, description = "Email verification required")
+        (status = 403, description = "Email verification required"),
+        (status = 409, description = "A request with the same Idempotency-Key is still in progress"),
+        (status = 429, description = "Too many of your requests in progress, or over the hourly limit")
     ),
     security(
         ("bearer_auth" = [])
@@ -51,48 +138,184 @@ pub async fn create_report(
         .award_report_points(auth_user.id, report.id)
         .await?;
 
-    let response: ReportResponse = report.into();
+    notify_watchers(&state, report.id).await;
+
+    let photos = state.report_service.get_photos(report.id).await?;
+    let response = ReportResponse::from(report).with_photos(photos);
     Ok((StatusCode::CREATED, Json(response)))
 }
 
-/// Get nearby reports
+/// Report litter without an account. The response carries a claim code that attaches
+/// the report to an account later.
+/// POST /api/reports/anonymous
+#[utoipa::path(
+    post,
+    operation_id = "createAnonymousReport",
+    path = "/api/reports/anonymous",
+    tag = "Reports",
+    request_body = CreateAnonymousReportRequest,
+    responses(
+        (status = 201, description = "Report created; keep the claim code to take ownership later", body = AnonymousReportResponse),
+        (status = 400, description = "Invalid input, image or CAPTCHA"),
+        (status = 429, description = "Too many reports from this address")
+    )
+)]
+pub async fn create_anonymous_report(
+    State(state): State<Arc<ReportHandlerState>>,
+    client: ClientInfo,
+    Json(request): Json<CreateAnonymousReportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .captcha_service
+        .verify(
+            request.captcha_token.as_deref(),
+            client.ip_address.as_deref(),
+        )
+        .await?;
+
+    let (report, claim_code, claim_expires_at) = state
+        .report_service
+        .create_anonymous_report(request)
+        .await?;
+
+    notify_watchers(&state, report.id).await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AnonymousReportResponse {
+            report: report.into(),
+            claim_code,
+            claim_expires_at,
+        }),
+    ))
+}
+
+/// Take ownership of a report made without an account, earning its report points
+/// POST /api/reports/anonymous/claim
+#[utoipa::path(
+    post,
+    operation_id = "claimAnonymousReport",
+    path = "/api/reports/anonymous/claim",
+    tag = "Reports",
+    request_body = ClaimAnonymousReportRequest,
+    responses(
+        (status = 200, description = "Report now belongs to the caller", body = ReportResponse),
+        (status = 400, description = "The caller has already cleaned up this report"),
+        (status = 403, description = "Email verification required"),
+        (status = 404, description = "Invalid or expired claim code")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn claim_anonymous_report(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<ClaimAnonymousReportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .report_service
+        .claim_anonymous_report(&state.scoring_service, auth_user.id, &request.claim_code)
+        .await?;
+
+    let response: ReportResponse = report.into();
+    Ok(Json(response))
+}
+
+/// Get nearby reports, as JSON or GeoJSON. Partner services may call this with an API
+/// key holding the `reports:read` scope instead of a bearer token.
 /// GET /`api/reports/nearby?latitude=X&longitude=Y&radius_km=Z`
 #[utoipa::path(
     get,
+    operation_id = "listNearbyReports",
     path = "/api/reports/nearby",
     tag = "Reports",
     params(
-        NearbyReportsQuery
+        NearbyReportsQuery,
+        LitterFilter,
+        ReportListQuery,
+        KeysetPageParams,
+        FormatQuery
     ),
     responses(
-        (status = 200, description = "Returns reports within radius", body = Vec<ReportResponse>),
-        (status = 400, description = "Invalid coordinates")
+        (status = 200, description = "Returns reports within radius", content(
+            ("application/json" = PaginatedReports),
+            ("application/geo+json" = ReportFeatureCollection)
+        )),
+        (status = 400, description = "Invalid coordinates, status or cursor"),
+        (status = 429, description = "API key rate limit exceeded")
     ),
     security(
-        ("bearer_auth" = [])
+        ("bearer_auth" = []),
+        ("api_key" = [])
     )
 )]
 pub async fn get_nearby_reports(
     State(state): State<Arc<ReportHandlerState>>,
-    _auth_user: AuthUser,
+    auth_user: Option<AuthUser>,
     Query(query): Query<NearbyReportsQuery>,
-) -> Result<impl IntoResponse, AppError> {
+    Query(litter): Query<LitterFilter>,
+    Query(list): Query<ReportListQuery>,
+    Query(page): Query<KeysetPageParams>,
+    format: OutputFormat,
+) -> Result<Response, AppError> {
     tracing::info!(
-        "get_nearby_reports called with lat={}, lng={}, radius={:?}",
-        query.latitude,
-        query.longitude,
-        query.radius_km
+        "get_nearby_reports called with lat={}, lng={}, radius={:?}, search={:?}",
+        query.latitude.degrees(),
+        query.longitude.degrees(),
+        query.radius_km,
+        query.search_id
     );
 
-    let radius = query.radius_km.unwrap_or(5.0);
+    // A saved search supplies the radius, statuses and categories; an explicit radius
+    // or category still wins
+    let (radius, statuses, categories) = match query.search_id {
+        Some(search_id) => {
+            let user = auth_user.ok_or_else(|| {
+                AppError::BadRequest("Saved searches need a signed-in user".to_string())
+            })?;
+            let search = state
+                .saved_search_service
+                .get_search(search_id, user.id)
+                .await?;
+            (
+                query.radius_km.unwrap_or(search.radius_km),
+                search.statuses.clone(),
+                search.litter_categories(),
+            )
+        }
+        None => (
+            query.radius_km.unwrap_or(5.0),
+            vec![ReportStatus::Pending, ReportStatus::Claimed],
+            Vec::new(),
+        ),
+    };
+
+    // An explicit `status` list wins over the saved or default statuses
+    let filter = ReportFilter {
+        statuses: list.statuses()?.unwrap_or(statuses),
+        categories: litter
+            .category
+            .map_or(categories, |category| vec![category]),
+        severity: litter.severity,
+    };
 
-    let reports = match state
+    let after = page.after()?;
+    let limit = page.limit(100, 100);
+    let (reports, total) = match state
         .report_service
-        .get_nearby_reports(query.latitude, query.longitude, radius)
+        .get_nearby_reports(
+            Coordinates::new(query.latitude, query.longitude),
+            radius,
+            &filter,
+            list.sort(),
+            after,
+            limit + 1,
+        )
         .await
     {
         Ok(r) => {
-            tracing::info!("Successfully fetched {} reports", r.len());
+            tracing::info!("Successfully fetched {} of {} reports", r.0.len(), r.1);
             r
         }
         Err(e) => {
@@ -102,23 +325,166 @@ pub async fn get_nearby_reports(
         }
     };
 
-    let responses: Vec<ReportResponse> =
-        reports.into_iter().map(std::convert::Into::into).collect();
-    Ok(Json(responses))
+    let responses: Paginated<ReportResponse> = Paginated::keyset(reports, total, limit, |r| {
+        KeysetCursor::new(r.created_at, r.id)
+    })
+    .map(std::convert::Into::into);
+    Ok(respond_as(format, responses))
 }
 
-/// Get reports available for verification
-/// GET /api/reports/verification-queue?latitude=X&longitude=Y&radius_km=Z
+/// Get the open reports inside a map viewport: individual reports when zoomed in,
+/// clustered markers when zoomed out, as JSON or GeoJSON. Partner services may call this
+/// with an API key holding the `reports:read` scope.
+/// GET /`api/reports/in-bounds?min_lat=A&min_lon=B&max_lat=C&max_lon=D&zoom=Z`
 #[utoipa::path(
     get,
-    path = "/api/reports/verification-queue",
+    operation_id = "listReportsInBounds",
+    path = "/api/reports/in-bounds",
+    tag = "Reports",
+    params(
+        ReportsInBoundsQuery,
+        LitterFilter,
+        FormatQuery
+    ),
+    responses(
+        (status = 200, description = "Reports or clusters in the viewport", content(
+            ("application/json" = ReportsInBoundsResponse),
+            ("application/geo+json" = ReportFeatureCollection)
+        )),
+        (status = 400, description = "Invalid bounds or zoom"),
+        (status = 429, description = "API key rate limit exceeded")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+pub async fn get_reports_in_bounds(
+    State(state): State<Arc<ReportHandlerState>>,
+    Query(bounds): Query<ReportsInBoundsQuery>,
+    Query(litter): Query<LitterFilter>,
+    format: OutputFormat,
+) -> Result<Response, AppError> {
+    check_bounds(&bounds)?;
+    let filter = open_reports_filter(litter);
+
+    let response = match bounds.zoom {
+        Some(zoom) if zoom < CLUSTER_BELOW_ZOOM => {
+            let clusters = state
+                .report_service
+                .get_report_clusters(&bounds, &filter, cluster_geohash_precision(zoom))
+                .await?;
+            ReportsInBoundsResponse {
+                clustered: true,
+                reports: Vec::new(),
+                total: clusters.iter().map(|c| c.count).sum(),
+                clusters,
+            }
+        }
+        _ => {
+            let (reports, total) = state
+                .report_service
+                .get_reports_in_bounds(&bounds, &filter, MAX_REPORTS_IN_BOUNDS)
+                .await?;
+            ReportsInBoundsResponse {
+                clustered: false,
+                reports: reports.into_iter().map(Into::into).collect(),
+                clusters: Vec::new(),
+                total,
+            }
+        }
+    };
+
+    Ok(respond_as(format, response))
+}
+
+/// Get every open report inside a map viewport as clusters of nearby reports, so dense
+/// areas are counted in full rather than cut off at a page size. Partner services may
+/// call this with an API key holding the `reports:read` scope.
+/// GET /`api/reports/clusters?min_lat=A&min_lon=B&max_lat=C&max_lon=D&zoom=Z`
+#[utoipa::path(
+    get,
+    operation_id = "listReportClusters",
+    path = "/api/reports/clusters",
     tag = "Reports",
     params(
-        NearbyReportsQuery
+        ReportsInBoundsQuery,
+        LitterFilter
     ),
     responses(
-        (status = 200, description = "Returns reports needing verification", body = Vec<ReportResponse>),
-        (status = 400, description = "Invalid coordinates")
+        (status = 200, description = "Clusters covering every report in the viewport", body = ReportClustersResponse),
+        (status = 400, description = "Invalid bounds or zoom"),
+        (status = 429, description = "API key rate limit exceeded")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+pub async fn get_report_clusters(
+    State(state): State<Arc<ReportHandlerState>>,
+    Query(bounds): Query<ReportsInBoundsQuery>,
+    Query(litter): Query<LitterFilter>,
+) -> Result<impl IntoResponse, AppError> {
+    check_bounds(&bounds)?;
+    let filter = open_reports_filter(litter);
+
+    let zoom = bounds.zoom.unwrap_or_else(|| zoom_for_bounds(&bounds));
+    let precision = cluster_geohash_precision(zoom);
+    let clusters = state
+        .report_service
+        .get_report_clusters(&bounds, &filter, precision)
+        .await?;
+
+    Ok(Json(ReportClustersResponse {
+        total: clusters.iter().map(|c| c.count).sum(),
+        clusters,
+        precision,
+    }))
+}
+
+/// Reject viewports that are inverted or at an impossible zoom; the corners are
+/// already valid coordinates
+fn check_bounds(bounds: &ReportsInBoundsQuery) -> Result<(), AppError> {
+    if bounds.min_lat > bounds.max_lat || bounds.min_lon > bounds.max_lon {
+        return Err(AppError::BadRequest(
+            "min_lat/min_lon must not exceed max_lat/max_lon".to_string(),
+        ));
+    }
+    if bounds.zoom.is_some_and(|zoom| zoom > 22) {
+        return Err(AppError::BadRequest(
+            "zoom must be between 0 and 22".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Pending and claimed reports, the ones a map shows, narrowed by `litter`
+fn open_reports_filter(litter: LitterFilter) -> ReportFilter {
+    ReportFilter {
+        statuses: vec![ReportStatus::Pending, ReportStatus::Claimed],
+        categories: litter.category.into_iter().collect(),
+        severity: litter.severity,
+    }
+}
+
+/// Get cleared reports near a location that the caller can verify: not their own clears,
+/// not already verified by them, not waiting on a photo retake and not with every open
+/// slot reserved by other verifiers. Radius defaults to 50km.
+/// GET /api/reports/verification-queue?latitude=X&longitude=Y&radius_km=Z&sort=distance
+#[utoipa::path(
+    get,
+    operation_id = "listVerificationQueue",
+    path = "/api/reports/verification-queue",
+    tag = "Verifications",
+    params(
+        NearbyReportsQuery,
+        VerificationQueueQuery,
+        KeysetPageParams
+    ),
+    responses(
+        (status = 200, description = "Returns reports needing verification", body = PaginatedReports),
+        (status = 400, description = "Invalid coordinates, filter, sort or cursor")
     ),
     security(
         ("bearer_auth" = [])
@@ -128,31 +494,42 @@ pub async fn get_verification_queue(
     State(state): State<Arc<ReportHandlerState>>,
     auth_user: AuthUser,
     Query(query): Query<NearbyReportsQuery>,
+    Query(queue): Query<VerificationQueueQuery>,
+    Query(page): Query<KeysetPageParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Default to 50km radius for verification (wider net) if not specified
-    let radius = query.radius_km.unwrap_or(50.0);
-
-    let reports = state
+    let after = page.after()?;
+    let limit = page.limit(50, 100);
+    let (reports, total) = state
         .report_service
-        .get_verification_queue(query.latitude, query.longitude, radius, auth_user.id)
+        .get_verification_queue(
+            &query,
+            auth_user.id,
+            state.scoring_service.min_verifications_needed(),
+            &queue,
+            after,
+            limit + 1,
+        )
         .await?;
 
-    let responses: Vec<ReportResponse> =
-        reports.into_iter().map(std::convert::Into::into).collect();
+    let responses: Paginated<ReportResponse> =
+        Paginated::keyset(reports, total, limit, cleared_cursor).map(std::convert::Into::into);
     Ok(Json(responses))
 }
 
-/// Get a single report by ID
-/// GET /api/reports/:id
+/// Get a single report by ID, optionally with its description machine-translated
+/// GET /api/reports/:id?translate_to=en
 #[utoipa::path(
     get,
+    operation_id = "getReport",
     path = "/api/reports/{id}",
     tag = "Reports",
     params(
-        ("id" = Uuid, Path, description = "Report ID")
+        ("id" = Uuid, Path, description = "Report ID"),
+        GetReportQuery
     ),
     responses(
         (status = 200, description = "Returns report details", body = ReportResponse),
+        (status = 400, description = "Invalid language code, or translation is not available"),
         (status = 404, description = "Report not found")
     ),
     security(
@@ -163,16 +540,162 @@ pub async fn get_report(
     State(state): State<Arc<ReportHandlerState>>,
     _auth_user: AuthUser,
     Path(report_id): Path<Uuid>,
+    Query(query): Query<GetReportQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    let target_language = query
+        .translate_to
+        .as_deref()
+        .map(parse_language_code)
+        .transpose()?;
+    if target_language.is_some() && !state.translation_service.is_enabled() {
+        return Err(AppError::BadRequest(
+            "Translation is not available".to_string(),
+        ));
+    }
+
     let report = state.report_service.get_report_by_id(report_id).await?;
+
+    // A failed translation still returns the report, just untranslated
+    let mut translation = None;
+    if let (Some(target_language), Some(description)) = (
+        target_language,
+        report
+            .description
+            .as_deref()
+            .filter(|d| !d.trim().is_empty()),
+    ) {
+        match state
+            .translation_service
+            .translate_description(report.id, description, &target_language)
+            .await
+        {
+            Ok(translated) => translation = Some(translated),
+            Err(e) => tracing::warn!("Failed to translate report {}: {:?}", report.id, e),
+        }
+    }
+
+    let photos = state.report_service.get_photos(report.id).await?;
+    let response = ReportResponse::from(report)
+        .with_photos(photos)
+        .with_translation(translation);
+    Ok(Json(response))
+}
+
+/// Fix a report's description, category or first before photo. Only the reporter may
+/// edit, and only while the report is pending; moderators see every edit.
+/// PATCH /api/reports/:id
+#[utoipa::path(
+    patch,
+    operation_id = "updateReport",
+    path = "/api/reports/{id}",
+    tag = "Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    request_body = UpdateReportRequest,
+    responses(
+        (status = 200, description = "Report updated", body = ReportResponse),
+        (status = 400, description = "Nothing to change, a bad photo, or the report is no longer pending"),
+        (status = 403, description = "Only the reporter can edit this report"),
+        (status = 404, description = "Report not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_report(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+    Json(request): Json<UpdateReportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .report_service
+        .edit_report(report_id, auth_user.id, request)
+        .await?;
+    let photos = state.report_service.get_photos(report.id).await?;
+    let response = ReportResponse::from(report).with_photos(photos);
+    Ok(Json(response))
+}
+
+/// Withdraw a report made by mistake. Only the reporter may cancel, and only while
+/// the report is pending; it stays as `cancelled`, without its photos.
+/// DELETE /api/reports/:id
+#[utoipa::path(
+    delete,
+    operation_id = "cancelReport",
+    path = "/api/reports/{id}",
+    tag = "Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Report cancelled", body = ReportResponse),
+        (status = 400, description = "The report is no longer pending"),
+        (status = 403, description = "Only the reporter can cancel this report"),
+        (status = 404, description = "Report not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn cancel_report(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .report_service
+        .cancel_report(report_id, auth_user.id)
+        .await?;
     let response: ReportResponse = report.into();
     Ok(Json(response))
 }
 
+/// Flag a report that should not be on the map. Once enough users have, it is hidden
+/// until a moderator reviews it.
+/// POST /api/reports/:id/flag
+#[utoipa::path(
+    post,
+    operation_id = "flagReport",
+    path = "/api/reports/{id}/flag",
+    tag = "Reports",
+    request_body = FlagReportRequest,
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 201, description = "Report flagged", body = FlagReportResponse),
+        (status = 400, description = "Your own report, a cancelled or archived one, or the comment is too long"),
+        (status = 404, description = "Report not found"),
+        (status = 409, description = "You have already flagged this report")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn flag_report(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+    Json(request): Json<FlagReportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let response = state
+        .report_flag_service
+        .flag_report(report_id, auth_user.id, request)
+        .await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
 /// Claim a report for cleanup
 /// POST /api/reports/:id/claim
 #[utoipa::path(
     post,
+    operation_id = "claimReport",
     path = "/api/reports/{id}/claim",
     tag = "Reports",
     params(
@@ -181,7 +704,9 @@ pub async fn get_report(
     responses(
         (status = 200, description = "Report claimed successfully", body = ReportResponse),
         (status = 404, description = "Report not found"),
-        (status = 400, description = "Report already claimed or not in pending status")
+        (status = 400, description = "Report already claimed, not in pending status, or hidden for moderation"),
+        (status = 403, description = "You have given up this report too many times"),
+        (status = 429, description = "You have given up too many claims recently")
     ),
     security(
         ("bearer_auth" = [])
@@ -196,24 +721,231 @@ pub async fn claim_report(
         .report_service
         .claim_report(report_id, auth_user.id)
         .await?;
+
+    if let Some(reporter_id) = report.reporter_id.filter(|id| *id != auth_user.id) {
+        state
+            .notification_service
+            .notify(
+                reporter_id,
+                NotificationKind::ReportClaimed,
+                "Your report was claimed",
+                "Someone has claimed your litter report and is on their way to clean it up.",
+                Some(report.id),
+            )
+            .await;
+    }
+
     let response: ReportResponse = report.into();
     Ok(Json(response))
 }
 
+/// Give up a claim so the report returns to the pending pool. The body, and its
+/// reason, are optional.
+/// POST /api/reports/:id/unclaim
+#[utoipa::path(
+    post,
+    operation_id = "unclaimReport",
+    path = "/api/reports/{id}/unclaim",
+    tag = "Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    request_body(content = Option<UnclaimReportRequest>),
+    responses(
+        (status = 200, description = "Claim released; the report is pending again", body = ReportResponse),
+        (status = 404, description = "Report not found"),
+        (status = 400, description = "You have not claimed this report, or the reason is too long")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn unclaim_report(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+    request: Option<Json<UnclaimReportRequest>>,
+) -> Result<impl IntoResponse, AppError> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    request
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let report = state
+        .report_service
+        .unclaim_report(report_id, auth_user.id, request.reason.as_deref())
+        .await?;
+
+    let response: ReportResponse = report.into();
+    Ok(Json(response))
+}
+
+/// Get a report's history: when it was reported, claimed, given up and cleared
+/// GET /api/reports/:id/timeline
+#[utoipa::path(
+    get,
+    operation_id = "getReportTimeline",
+    path = "/api/reports/{id}/timeline",
+    tag = "Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Events, oldest first", body = ReportTimelineResponse),
+        (status = 404, description = "Report not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_report_timeline(
+    State(state): State<Arc<ReportHandlerState>>,
+    _auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let timeline: ReportTimelineResponse = state.report_service.get_timeline(report_id).await?;
+    Ok(Json(timeline))
+}
+
+/// Get a report's audit trail: every status change with who made it, why, and details
+/// GET /api/reports/:id/history
+#[utoipa::path(
+    get,
+    operation_id = "getReportHistory",
+    path = "/api/reports/{id}/history",
+    tag = "Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Events, oldest first", body = ReportHistoryResponse),
+        (status = 404, description = "Report not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_report_history(
+    State(state): State<Arc<ReportHandlerState>>,
+    _auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let history: ReportHistoryResponse = state.report_service.get_report_history(report_id).await?;
+    Ok(Json(history))
+}
+
+/// Claim several reports at once as a cleanup run
+/// POST /api/reports/claim-batch
+#[utoipa::path(
+    post,
+    operation_id = "claimReportBatch",
+    path = "/api/reports/claim-batch",
+    tag = "Cleanup Runs",
+    request_body = ClaimBatchRequest,
+    responses(
+        (status = 200, description = "Per-report results; claimed reports share the returned run id", body = ClaimBatchResponse),
+        (status = 400, description = "Empty batch or too many reports")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn claim_report_batch(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<ClaimBatchRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let (run_id, outcomes) = state
+        .report_service
+        .claim_reports_batch(&request.report_ids, auth_user.id)
+        .await?;
+
+    let mut results = Vec::with_capacity(outcomes.len());
+    for (report_id, outcome) in outcomes {
+        results.push(match outcome {
+            Ok(report) => {
+                if let Some(reporter_id) = report.reporter_id {
+                    state
+                        .notification_service
+                        .notify(
+                            reporter_id,
+                            NotificationKind::ReportClaimed,
+                            "Your report was claimed",
+                            "Someone has claimed your litter report and is on their way to clean it up.",
+                            Some(report.id),
+                        )
+                        .await;
+                }
+
+                BatchClaimResult {
+                    report_id,
+                    claimed: true,
+                    error: None,
+                    report: Some(report.into()),
+                }
+            }
+            Err(error) => BatchClaimResult {
+                report_id,
+                claimed: false,
+                error: Some(error),
+                report: None,
+            },
+        });
+    }
+
+    let claimed_count = results.iter().filter(|r| r.claimed).count();
+    Ok(Json(ClaimBatchResponse {
+        run_id,
+        claimed_count,
+        results,
+    }))
+}
+
+/// Get combined stats for a cleanup run
+/// GET /api/cleanup-runs/:id
+#[utoipa::path(
+    get,
+    operation_id = "getCleanupRun",
+    path = "/api/cleanup-runs/{id}",
+    tag = "Cleanup Runs",
+    params(
+        ("id" = Uuid, Path, description = "Cleanup run ID")
+    ),
+    responses(
+        (status = 200, description = "Run progress, points and reports", body = CleanupRunResponse),
+        (status = 404, description = "Cleanup run not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_cleanup_run(
+    State(state): State<Arc<ReportHandlerState>>,
+    _auth_user: AuthUser,
+    Path(run_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let run = state.report_service.get_cleanup_run(run_id).await?;
+    Ok(Json(run))
+}
+
 /// Clear a report with after photo
 /// POST /api/reports/:id/clear
 #[utoipa::path(
     post,
+    operation_id = "clearReport",
     path = "/api/reports/{id}/clear",
     tag = "Reports",
     request_body = ClearReportRequest,
     params(
-        ("id" = Uuid, Path, description = "Report ID")
+        ("id" = Uuid, Path, description = "Report ID"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Unique value per user action; a retry with the same key replays the original response")
     ),
     responses(
         (status = 200, description = "Report cleared successfully. Points awarded.", body = ReportResponse),
         (status = 404, description = "Report not found"),
-        (status = 400, description = "Report not claimed by you or invalid status")
+        (status = 400, description = "Report not claimed by you or invalid status"),
+        (status = 409, description = "A request with the same Idempotency-Key is still in progress"),
+        (status = 429, description = "Too many of your requests in progress")
     ),
     security(
         ("bearer_auth" = [])
@@ -228,27 +960,174 @@ pub async fn clear_report(
     // Clear the report
     let report = state
         .report_service
-        .clear_report(report_id, auth_user.id, request.photo_base64)
+        .clear_report(report_id, auth_user.id, request)
         .await?;
 
     // Award points to the user
     state
         .scoring_service
-        .award_clear_points(auth_user.id, report_id, report.latitude, report.longitude)
+        .award_clear_points(
+            auth_user.id,
+            report_id,
+            report.coordinates()?,
+            report.severity,
+        )
         .await?;
 
-    let response: ReportResponse = report.into();
+    // The clear stands even if the group bonus can't be paid
+    if let Err(e) = state
+        .cleanup_event_service
+        .award_group_bonus(report_id, auth_user.id)
+        .await
+    {
+        tracing::warn!(
+            "Failed to award cleanup event bonus for report {}: {:?}",
+            report_id,
+            e
+        );
+    }
+
+    if let Err(e) = state.city_goal_service.record_clear(report_id).await {
+        tracing::warn!(
+            "Failed to count report {} towards its city goal: {:?}",
+            report_id,
+            e
+        );
+    }
+
+    if let Some(reporter_id) = report.reporter_id.filter(|id| *id != auth_user.id) {
+        state
+            .notification_service
+            .notify(
+                reporter_id,
+                NotificationKind::ReportCleared,
+                "Your report was cleared",
+                "The litter you reported has been cleaned up and is awaiting verification.",
+                Some(report.id),
+            )
+            .await;
+    }
+
+    let photos = state.report_service.get_photos(report.id).await?;
+    let response = ReportResponse::from(report).with_photos(photos);
+    Ok(Json(response))
+}
+
+/// Replace the after photo of a report a verifier asked to be retaken
+/// PUT /api/reports/:id/after-photo
+#[utoipa::path(
+    put,
+    operation_id = "replaceAfterPhoto",
+    path = "/api/reports/{id}/after-photo",
+    tag = "Reports",
+    request_body = ClearReportRequest,
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "After photo replaced; verification starts over", body = ReportResponse),
+        (status = 404, description = "Report not found"),
+        (status = 400, description = "No open photo retake request, or invalid image"),
+        (status = 403, description = "Report not cleared by you"),
+        (status = 429, description = "Too many of your requests in progress")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn replace_after_photo(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+    Json(request): Json<ClearReportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .report_service
+        .replace_after_photo(report_id, auth_user.id, request)
+        .await?;
+
+    let photos = state.report_service.get_photos(report.id).await?;
+    let response = ReportResponse::from(report).with_photos(photos);
     Ok(Json(response))
 }
 
+/// Get a report's before and after photos in order
+/// GET /api/reports/:id/photos
+#[utoipa::path(
+    get,
+    operation_id = "listReportPhotos",
+    path = "/api/reports/{id}/photos",
+    tag = "Reports",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Before and after photos, first photo first", body = ReportPhotos),
+        (status = 404, description = "Report not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_report_photos(
+    State(state): State<Arc<ReportHandlerState>>,
+    _auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state.report_service.get_report_by_id(report_id).await?;
+    let photos: ReportPhotos = state.report_service.get_photos(report_id).await?;
+    Ok(Json(photos))
+}
+
+/// Add a before or after photo to a report
+/// POST /api/reports/:id/photos
+#[utoipa::path(
+    post,
+    operation_id = "addReportPhoto",
+    path = "/api/reports/{id}/photos",
+    tag = "Reports",
+    request_body = AddReportPhotoRequest,
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 201, description = "Photo added; returns all of the report's photos", body = ReportPhotos),
+        (status = 400, description = "Already five photos of this kind, report in the wrong state, or invalid image"),
+        (status = 403, description = "Not the reporter (before) or clearer (after)"),
+        (status = 404, description = "Report not found"),
+        (status = 429, description = "Too many of your requests in progress")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn add_report_photo(
+    State(state): State<Arc<ReportHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+    Json(request): Json<AddReportPhotoRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let photos = state
+        .report_service
+        .add_photo(report_id, auth_user.id, request)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(photos)))
+}
+
 /// Get all reports created by the current user
 /// GET /api/reports/my-reports
 #[utoipa::path(
     get,
+    operation_id = "listMyReports",
     path = "/api/reports/my-reports",
     tag = "Reports",
+    params(
+        KeysetPageParams
+    ),
     responses(
-        (status = 200, description = "Returns user's reports", body = Vec<ReportResponse>)
+        (status = 200, description = "Returns user's reports", body = PaginatedReports),
+        (status = 400, description = "Invalid cursor")
     ),
     security(
         ("bearer_auth" = [])
@@ -257,10 +1136,17 @@ pub async fn clear_report(
 pub async fn get_my_reports(
     State(state): State<Arc<ReportHandlerState>>,
     auth_user: AuthUser,
+    Query(page): Query<KeysetPageParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let reports = state.report_service.get_user_reports(auth_user.id).await?;
-    let responses: Vec<ReportResponse> =
-        reports.into_iter().map(std::convert::Into::into).collect();
+    let limit = page.limit(50, 100);
+    let (reports, total) = state
+        .report_service
+        .get_user_reports(auth_user.id, page.after()?, limit + 1)
+        .await?;
+    let responses: Paginated<ReportResponse> = Paginated::keyset(reports, total, limit, |r| {
+        KeysetCursor::new(r.created_at, r.id)
+    })
+    .map(std::convert::Into::into);
     Ok(Json(responses))
 }
 
@@ -268,10 +1154,15 @@ pub async fn get_my_reports(
 /// GET /api/reports/my-clears
 #[utoipa::path(
     get,
+    operation_id = "listMyClearedReports",
     path = "/api/reports/my-clears",
     tag = "Reports",
+    params(
+        KeysetPageParams
+    ),
     responses(
-        (status = 200, description = "Returns user's cleared reports", body = Vec<ReportResponse>)
+        (status = 200, description = "Returns user's cleared reports", body = PaginatedReports),
+        (status = 400, description = "Invalid cursor")
     ),
     security(
         ("bearer_auth" = [])
@@ -280,12 +1171,19 @@ pub async fn get_my_reports(
 pub async fn get_my_cleared_reports(
     State(state): State<Arc<ReportHandlerState>>,
     auth_user: AuthUser,
+    Query(page): Query<KeysetPageParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let reports = state
+    let limit = page.limit(50, 100);
+    let (reports, total) = state
         .report_service
-        .get_user_cleared_reports(auth_user.id)
+        .get_user_cleared_reports(auth_user.id, page.after()?, limit + 1)
         .await?;
-    let responses: Vec<ReportResponse> =
-        reports.into_iter().map(std::convert::Into::into).collect();
+    let responses: Paginated<ReportResponse> =
+        Paginated::keyset(reports, total, limit, cleared_cursor).map(std::convert::Into::into);
     Ok(Json(responses))
 }
+
+/// Cursor for lists of cleared reports, which are sorted by when they were cleared
+fn cleared_cursor(report: &LitterReport) -> KeysetCursor {
+    KeysetCursor::new(report.cleared_at.unwrap_or(report.created_at), report.id)
+}
@@ -0,0 +1,195 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::pagination::Paginated;
+use crate::models::saved_search::{
+    CreateSavedSearchRequest, SavedSearchResponse, UpdateSavedSearchRequest,
+};
+use crate::services::saved_search_service::SavedSearchService;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone)]
+pub struct SavedSearchHandlerState {
+    pub saved_search_service: SavedSearchService,
+}
+
+/// List the current user's saved searches
+/// GET /api/users/me/searches
+#[utoipa::path(
+    get,
+    operation_id = "listSavedSearches",
+    path = "/api/users/me/searches",
+    tag = "Saved Searches",
+    responses(
+        (status = 200, description = "Returns saved searches", body = PaginatedSavedSearches)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_saved_searches(
+    State(state): State<Arc<SavedSearchHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let searches = state
+        .saved_search_service
+        .list_searches(auth_user.id)
+        .await?;
+    // Saved searches are capped per user, so they always fit on one page
+    let responses: Paginated<SavedSearchResponse> =
+        Paginated::all(searches).map(std::convert::Into::into);
+    Ok(Json(responses))
+}
+
+/// Create a saved search
+/// POST /api/users/me/searches
+#[utoipa::path(
+    post,
+    operation_id = "createSavedSearch",
+    path = "/api/users/me/searches",
+    tag = "Saved Searches",
+    request_body(content = CreateSavedSearchRequest, example = json!({
+        "name": "Near the office",
+        "radius_km": 2.5,
+        "statuses": ["pending"],
+        "notify_on_new": true
+    })),
+    responses(
+        (status = 201, description = "Saved search created", body = SavedSearchResponse, example = json!({
+            "id": "0b8e7d6c-5a4b-4c3d-9e2f-1a0b9c8d7e6f",
+            "name": "Near the office",
+            "radius_km": 2.5,
+            "statuses": ["pending"],
+            "categories": [],
+            "notify_on_new": true,
+            "created_at": "2024-05-01T09:30:00Z",
+            "updated_at": "2024-05-01T09:30:00Z"
+        })),
+        (status = 400, description = "Invalid filter values or too many saved searches")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_saved_search(
+    State(state): State<Arc<SavedSearchHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateSavedSearchRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let search = state
+        .saved_search_service
+        .create_search(auth_user.id, request)
+        .await?;
+    let response: SavedSearchResponse = search.into();
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Get a single saved search
+/// GET /api/users/me/searches/:id
+#[utoipa::path(
+    get,
+    operation_id = "getSavedSearch",
+    path = "/api/users/me/searches/{id}",
+    tag = "Saved Searches",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    responses(
+        (status = 200, description = "Returns the saved search", body = SavedSearchResponse),
+        (status = 404, description = "Saved search not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_saved_search(
+    State(state): State<Arc<SavedSearchHandlerState>>,
+    auth_user: AuthUser,
+    Path(search_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let search = state
+        .saved_search_service
+        .get_search(search_id, auth_user.id)
+        .await?;
+    let response: SavedSearchResponse = search.into();
+    Ok(Json(response))
+}
+
+/// Update a saved search
+/// PATCH /api/users/me/searches/:id
+#[utoipa::path(
+    patch,
+    operation_id = "updateSavedSearch",
+    path = "/api/users/me/searches/{id}",
+    tag = "Saved Searches",
+    request_body = UpdateSavedSearchRequest,
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    responses(
+        (status = 200, description = "Saved search updated", body = SavedSearchResponse),
+        (status = 400, description = "Invalid filter values"),
+        (status = 404, description = "Saved search not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_saved_search(
+    State(state): State<Arc<SavedSearchHandlerState>>,
+    auth_user: AuthUser,
+    Path(search_id): Path<Uuid>,
+    Json(request): Json<UpdateSavedSearchRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let search = state
+        .saved_search_service
+        .update_search(search_id, auth_user.id, request)
+        .await?;
+    let response: SavedSearchResponse = search.into();
+    Ok(Json(response))
+}
+
+/// Delete a saved search
+/// DELETE /api/users/me/searches/:id
+#[utoipa::path(
+    delete,
+    operation_id = "deleteSavedSearch",
+    path = "/api/users/me/searches/{id}",
+    tag = "Saved Searches",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    responses(
+        (status = 204, description = "Saved search deleted"),
+        (status = 404, description = "Saved search not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_saved_search(
+    State(state): State<Arc<SavedSearchHandlerState>>,
+    auth_user: AuthUser,
+    Path(search_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .saved_search_service
+        .delete_search(search_id, auth_user.id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
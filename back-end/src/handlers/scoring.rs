@@ -0,0 +1,52 @@
+use crate::error::AppError;
+use crate::models::scoring_rules::{negotiate_locale, ScoringRulesQuery};
+use crate::services::ScoringService;
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ScoringHandlerState {
+    pub scoring_service: ScoringService,
+}
+
+/// Get the scoring rules in force, with each value's meaning described for help
+/// screens. Public and cached for five minutes.
+/// GET /api/scoring/rules?locale=en
+#[utoipa::path(
+    get,
+    operation_id = "getScoringRules",
+    path = "/api/scoring/rules",
+    tag = "Scoring",
+    params(
+        ScoringRulesQuery,
+        ("Accept-Language" = Option<String>, Header, description = "Preferred languages, used when `locale` is absent")
+    ),
+    responses(
+        (status = 200, description = "Points, bonuses, multipliers and verification thresholds", body = ScoringRulesResponse),
+        (status = 400, description = "Unsupported locale")
+    )
+)]
+pub async fn get_scoring_rules(
+    State(state): State<Arc<ScoringHandlerState>>,
+    Query(query): Query<ScoringRulesQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let accept_language = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    let locale = negotiate_locale(query.locale.as_deref(), accept_language)?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CACHE_CONTROL, "public, max-age=300"),
+            (header::VARY, "Accept-Language"),
+        ],
+        Json(state.scoring_service.rules(locale)),
+    ))
+}
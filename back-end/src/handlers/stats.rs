@@ -0,0 +1,102 @@
+use crate::error::AppError;
+use crate::models::stats::{HotspotQuery, LocationHistory, LocationHistoryQuery, StatsSummary};
+use crate::services::StatsService;
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct StatsHandlerState {
+    pub stats_service: StatsService,
+}
+
+/// Get site-wide totals for the landing page. Public and cached for five minutes.
+/// GET /api/stats/summary
+#[utoipa::path(
+    get,
+    operation_id = "getStatsSummary",
+    path = "/api/stats/summary",
+    tag = "Stats",
+    responses(
+        (status = 200, description = "Site-wide counters", body = StatsSummary, example = json!({
+            "total_reports": 1280,
+            "cleared_reports": 1045,
+            "verified_reports": 870,
+            "volunteers": 312,
+            "computed_at": "2024-05-01T12:00:00Z"
+        }))
+    )
+)]
+pub async fn get_stats_summary(
+    State(state): State<Arc<StatsHandlerState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let summary: StatsSummary = state.stats_service.summary().await?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(summary),
+    ))
+}
+
+/// Get the cells with the most reports recently, with whether each is rising or
+/// falling, for city dashboards. Public and cached for five minutes.
+/// GET /api/stats/hotspots?city=London&window_days=30&shape=hex&cell_size_m=500
+#[utoipa::path(
+    get,
+    operation_id = "getHotspots",
+    path = "/api/stats/hotspots",
+    tag = "Stats",
+    params(
+        HotspotQuery
+    ),
+    responses(
+        (status = 200, description = "Busiest cells, most reports first", body = HotspotsResponse),
+        (status = 400, description = "Window or cell size out of range")
+    )
+)]
+pub async fn get_hotspots(
+    State(state): State<Arc<StatsHandlerState>>,
+    Query(query): Query<HotspotQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let hotspots = state.stats_service.hotspots(query).await?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(hotspots),
+    ))
+}
+
+/// Get how often litter has been reported and cleared at roughly one spot, month by
+/// month, and whether it is a repeat offender. Public and cached for five minutes.
+/// GET /api/locations/history?lat=51.5074&lon=-0.1278&radius=50
+#[utoipa::path(
+    get,
+    operation_id = "getLocationHistory",
+    path = "/api/locations/history",
+    tag = "Stats",
+    params(
+        LocationHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Report history of the spot", body = LocationHistory),
+        (status = 400, description = "Invalid coordinates or radius out of range")
+    )
+)]
+pub async fn get_location_history(
+    State(state): State<Arc<StatsHandlerState>>,
+    Query(query): Query<LocationHistoryQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let history: LocationHistory = state.stats_service.location_history(query).await?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(history),
+    ))
+}
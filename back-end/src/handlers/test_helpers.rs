@@ -1,11 +1,27 @@
-use crate::{error::AppError, services::AuthService};
+use crate::{
+    auth::{hash_token, ClientInfo},
+    config::TestHelpersConfig,
+    error::AppError,
+    models::coordinates::{Latitude, Longitude},
+    models::report::{LitterCategory, LitterReport, LitterSeverity, ReportResponse, ReportStatus},
+    models::user::AuthTokens,
+    services::{AuthService, LoginOutcome},
+};
 use axum::{
-    extract::{Path, State},
+    extract::{ConnectInfo, Path, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use uuid::Uuid;
+
+/// Header carrying `TEST_HELPERS_SECRET`
+pub const TEST_HELPERS_SECRET_HEADER: &str = "X-Test-Helpers-Secret";
 
 #[derive(Clone)]
 pub struct TestHelperState {
@@ -13,6 +29,44 @@ pub struct TestHelperState {
     pub auth_service: Arc<AuthService>,
 }
 
+/// Admit a request to `/api/test/*` only from an allowed network and with the shared
+/// secret. The socket peer address is used; forwarding headers could be forged.
+pub async fn require_test_helper_access(
+    State(config): State<Arc<TestHelpersConfig>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let peer = connect_info.map(|ConnectInfo(addr)| addr.ip());
+    let allowed_peer = peer.is_some_and(|ip| {
+        config
+            .allowed_networks
+            .iter()
+            .any(|network| network.contains(ip))
+    });
+
+    // Compare digests so the check does not short-circuit on the first differing byte
+    let valid_secret = req
+        .headers()
+        .get(TEST_HELPERS_SECRET_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|secret| hash_token(secret) == hash_token(&config.secret));
+
+    if !allowed_peer || !valid_secret {
+        tracing::warn!(
+            "Refused test helper request to {} from {:?} (allowed address: {}, valid secret: {})",
+            req.uri().path(),
+            peer,
+            allowed_peer,
+            valid_secret
+        );
+        return AppError::Forbidden("Test helpers are not available to this caller".to_string())
+            .into_response();
+    }
+
+    next.run(req).await
+}
+
 #[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct TestHelperResponse {
     pub success: bool,
@@ -25,13 +79,16 @@ pub struct TestHelperResponse {
 /// **WARNING: This endpoint should ONLY be enabled in test/development environments**
 #[utoipa::path(
     post,
+    operation_id = "verifyEmailForTesting",
     path = "/api/test/verify-email/{email}",
     tag = "test-helpers",
     params(
-        ("email" = String, Path, description = "Email address to verify")
+        ("email" = String, Path, description = "Email address to verify"),
+        ("X-Test-Helpers-Secret" = String, Header, description = "Value of TEST_HELPERS_SECRET")
     ),
     responses(
         (status = 200, description = "Email verified successfully", body = TestHelperResponse),
+        (status = 403, description = "Caller address not allowed or wrong secret"),
         (status = 404, description = "User not found"),
         (status = 500, description = "Internal server error")
     )
@@ -89,11 +146,16 @@ pub struct CleanupRequest {
 /// **WARNING: This endpoint should ONLY be enabled in test/development environments**
 #[utoipa::path(
     delete,
+    operation_id = "cleanupTestData",
     path = "/api/test/cleanup",
     tag = "test-helpers",
     request_body = CleanupRequest,
+    params(
+        ("X-Test-Helpers-Secret" = String, Header, description = "Value of TEST_HELPERS_SECRET")
+    ),
     responses(
         (status = 200, description = "Test data cleaned up successfully", body = TestHelperResponse),
+        (status = 403, description = "Caller address not allowed or wrong secret"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -179,10 +241,15 @@ pub async fn cleanup_test_data(
 /// Get the current test environment status
 #[utoipa::path(
     get,
+    operation_id = "getTestStatus",
     path = "/api/test/status",
     tag = "test-helpers",
+    params(
+        ("X-Test-Helpers-Secret" = String, Header, description = "Value of TEST_HELPERS_SECRET")
+    ),
     responses(
         (status = 200, description = "Test helpers are enabled"),
+        (status = 403, description = "Caller address not allowed or wrong secret"),
     )
 )]
 pub async fn test_status() -> Json<TestHelperResponse> {
@@ -191,3 +258,338 @@ pub async fn test_status() -> Json<TestHelperResponse> {
         message: "Test helpers are enabled".to_string(),
     })
 }
+
+/// Most users one factory call may create
+const MAX_FACTORY_USERS: u32 = 50;
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CreateTestUsersRequest {
+    #[schema(example = 5, minimum = 1, maximum = 50)]
+    pub count: u32,
+    /// Start of each generated email address (defaults to "factory")
+    #[schema(example = "verifier")]
+    pub email_prefix: Option<String>,
+    /// Shared password for every created user (defaults to "password123")
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct TestUser {
+    pub id: Uuid,
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct CreateTestUsersResponse {
+    pub users: Vec<TestUser>,
+    pub password: String,
+}
+
+/// Create verified users ready to sign in
+///
+/// **WARNING: This endpoint should ONLY be enabled in test/development environments**
+#[utoipa::path(
+    post,
+    operation_id = "createTestUsers",
+    path = "/api/test/users",
+    tag = "test-helpers",
+    request_body = CreateTestUsersRequest,
+    params(
+        ("X-Test-Helpers-Secret" = String, Header, description = "Value of TEST_HELPERS_SECRET")
+    ),
+    responses(
+        (status = 201, description = "Users created", body = CreateTestUsersResponse),
+        (status = 400, description = "Count out of range"),
+        (status = 403, description = "Caller address not allowed or wrong secret")
+    )
+)]
+pub async fn create_test_users(
+    State(state): State<Arc<TestHelperState>>,
+    Json(payload): Json<CreateTestUsersRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !(1..=MAX_FACTORY_USERS).contains(&payload.count) {
+        return Err(AppError::BadRequest(format!(
+            "count must be between 1 and {MAX_FACTORY_USERS}"
+        )));
+    }
+
+    let prefix = payload
+        .email_prefix
+        .unwrap_or_else(|| "factory".to_string());
+    let password = payload
+        .password
+        .unwrap_or_else(|| "password123".to_string());
+    // Argon2 is slow by design; every user shares the one hash
+    let password_hash = state.auth_service.hash_password(&password)?;
+    let batch = &Uuid::new_v4().simple().to_string()[..8];
+
+    let mut tx = state.pool.begin().await?;
+    let mut users = Vec::with_capacity(payload.count as usize);
+    for n in 1..=payload.count {
+        let email = format!("{prefix}-{batch}-{n}@example.com");
+        let id = insert_verified_user(&mut tx, &email, &password_hash, &format!("Test User {n}"))
+            .await?;
+        users.push(TestUser { id, email });
+    }
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateTestUsersResponse { users, password }),
+    ))
+}
+
+/// Insert a verified user who can sign in with a password straight away, along with
+/// the empty score registration would give them
+async fn insert_verified_user(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    email: &str,
+    password_hash: &str,
+    full_name: &str,
+) -> Result<Uuid, AppError> {
+    let id = sqlx::query_scalar!(
+        r#"
+            INSERT INTO users
+                (email, password_hash, full_name, city, country, email_verified, email_verified_at)
+            VALUES ($1, $2, $3, 'London', 'UK', true, NOW())
+            RETURNING id
+            "#,
+        email,
+        password_hash,
+        full_name
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    sqlx::query("INSERT INTO user_scores (user_id) VALUES ($1)")
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(id)
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct SwaggerSessionResponse {
+    /// Password of the throwaway user, for signing in again once the tokens expire
+    pub password: String,
+    pub tokens: AuthTokens,
+}
+
+/// Create a throwaway verified user and sign them in, so Swagger UI can start with
+/// a bearer token. `/swagger-auth` calls this from the browser.
+///
+/// **WARNING: This endpoint should ONLY be enabled in test/development environments**
+#[utoipa::path(
+    post,
+    operation_id = "createSwaggerSession",
+    path = "/api/test/swagger-session",
+    tag = "test-helpers",
+    params(
+        ("X-Test-Helpers-Secret" = String, Header, description = "Value of TEST_HELPERS_SECRET")
+    ),
+    responses(
+        (status = 201, description = "User created and signed in", body = SwaggerSessionResponse),
+        (status = 403, description = "Caller address not allowed or wrong secret")
+    )
+)]
+pub async fn create_swagger_session(
+    State(state): State<Arc<TestHelperState>>,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, AppError> {
+    let batch = Uuid::new_v4().simple().to_string();
+    let email = format!("swagger-{}@example.com", &batch[..8]);
+    let password = batch[8..24].to_string();
+    let password_hash = state.auth_service.hash_password(&password)?;
+
+    let mut tx = state.pool.begin().await?;
+    insert_verified_user(&mut tx, &email, &password_hash, "Swagger User").await?;
+    tx.commit().await?;
+
+    // A fresh user has no second factor, so sign-in completes straight away
+    let LoginOutcome::Authenticated(tokens) = state
+        .auth_service
+        .login_user(&email, &password, &client)
+        .await?
+    else {
+        return Err(AppError::Internal(anyhow::anyhow!(
+            "Throwaway Swagger user {email} was asked for a second factor"
+        )));
+    };
+
+    Ok((
+        StatusCode::CREATED,
+        Json(SwaggerSessionResponse { password, tokens }),
+    ))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CreateTestReportRequest {
+    /// Omit for a guest report with no reporter
+    pub reporter_email: Option<String>,
+    #[schema(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Latitude,
+    #[schema(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Longitude,
+    /// Defaults to pending
+    pub status: Option<ReportStatus>,
+    pub description: Option<String>,
+    /// Who claimed (and for cleared/verified, cleared) the report; required unless pending
+    pub claimer_email: Option<String>,
+}
+
+/// Create a report directly in any status, skipping photos and the claim/clear flow
+///
+/// **WARNING: This endpoint should ONLY be enabled in test/development environments**
+#[utoipa::path(
+    post,
+    operation_id = "createTestReport",
+    path = "/api/test/reports",
+    tag = "test-helpers",
+    request_body = CreateTestReportRequest,
+    params(
+        ("X-Test-Helpers-Secret" = String, Header, description = "Value of TEST_HELPERS_SECRET")
+    ),
+    responses(
+        (status = 201, description = "Report created", body = ReportResponse),
+        (status = 400, description = "Claimer missing for a non-pending status"),
+        (status = 403, description = "Caller address not allowed or wrong secret"),
+        (status = 404, description = "Reporter or claimer not found")
+    )
+)]
+pub async fn create_test_report(
+    State(state): State<Arc<TestHelperState>>,
+    Json(payload): Json<CreateTestReportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = payload.status.unwrap_or(ReportStatus::Pending);
+
+    let reporter_id = match &payload.reporter_email {
+        Some(email) => Some(user_id_by_email(&state.pool, email).await?),
+        None => None,
+    };
+    let claimer_id = match (&payload.claimer_email, &status) {
+        (_, ReportStatus::Pending) => None,
+        (Some(email), _) => Some(user_id_by_email(&state.pool, email).await?),
+        (None, _) => {
+            return Err(AppError::BadRequest(
+                "claimer_email is required unless the status is pending".to_string(),
+            ))
+        }
+    };
+    let cleared = matches!(
+        status,
+        ReportStatus::Cleared | ReportStatus::Verified | ReportStatus::Disputed
+    );
+
+    let report = sqlx::query_as!(
+        LitterReport,
+        r#"
+        INSERT INTO litter_reports (
+            reporter_id, location, description, status,
+            claimed_by, claimed_at, cleared_by, cleared_at
+        )
+        VALUES (
+            $1,
+            ST_SetSRID(ST_MakePoint($3, $2), 4326),
+            $4, $5,
+            $6, CASE WHEN $6::UUID IS NULL THEN NULL ELSE NOW() END,
+            CASE WHEN $7 THEN $6::UUID END, CASE WHEN $7 THEN NOW() END
+        )
+        RETURNING
+            id, reporter_id,
+            ST_Y(location)::double precision as "latitude!",
+            ST_X(location)::double precision as "longitude!",
+            description,
+            category as "category: LitterCategory", severity as "severity: LitterSeverity",
+            photo_before, status as "status: ReportStatus",
+            claimed_by, claimed_at, cleared_by, cleared_at,
+            photo_after, created_at, updated_at, address,
+            address_street, address_city, address_country,
+            photo_before_width, photo_before_height, photo_before_blurhash,
+            photo_after_width, photo_after_height, photo_after_blurhash
+        "#,
+        reporter_id,
+        payload.latitude.degrees(),
+        payload.longitude.degrees(),
+        payload.description,
+        status as ReportStatus,
+        claimer_id,
+        cleared
+    )
+    .fetch_one(&state.pool)
+    .await?;
+
+    let response: ReportResponse = report.into();
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct FastForwardClearsRequest {
+    /// Clears to add to the user's count
+    #[schema(example = 5, minimum = 1)]
+    pub count: i32,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct FastForwardClearsResponse {
+    pub user_id: Uuid,
+    pub total_clears: i32,
+}
+
+/// Add clears to a user's score, e.g. to make them eligible to verify reports
+///
+/// **WARNING: This endpoint should ONLY be enabled in test/development environments**
+#[utoipa::path(
+    post,
+    operation_id = "fastForwardClears",
+    path = "/api/test/users/{email}/clears",
+    tag = "test-helpers",
+    request_body = FastForwardClearsRequest,
+    params(
+        ("email" = String, Path, description = "Email address of the user"),
+        ("X-Test-Helpers-Secret" = String, Header, description = "Value of TEST_HELPERS_SECRET")
+    ),
+    responses(
+        (status = 200, description = "Clears added", body = FastForwardClearsResponse),
+        (status = 400, description = "Count is not positive"),
+        (status = 403, description = "Caller address not allowed or wrong secret"),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn fast_forward_clears(
+    State(state): State<Arc<TestHelperState>>,
+    Path(email): Path<String>,
+    Json(payload): Json<FastForwardClearsRequest>,
+) -> Result<Json<FastForwardClearsResponse>, AppError> {
+    if payload.count < 1 {
+        return Err(AppError::BadRequest("count must be positive".to_string()));
+    }
+
+    let user_id = user_id_by_email(&state.pool, &email).await?;
+    let total_clears = sqlx::query_scalar!(
+        r#"
+        INSERT INTO user_scores (user_id, reports_cleared, total_clears)
+        VALUES ($1, $2, $2)
+        ON CONFLICT (user_id) DO UPDATE
+        SET reports_cleared = user_scores.reports_cleared + $2,
+            total_clears = user_scores.total_clears + $2
+        RETURNING total_clears
+        "#,
+        user_id,
+        payload.count
+    )
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(FastForwardClearsResponse {
+        user_id,
+        total_clears,
+    }))
+}
+
+async fn user_id_by_email(pool: &PgPool, email: &str) -> Result<Uuid, AppError> {
+    sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", email)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User with email {email} not found")))
+}
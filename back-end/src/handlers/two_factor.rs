@@ -0,0 +1,125 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::handlers::auth::MessageResponse;
+use crate::models::two_factor::{
+    TwoFactorBackupCodesResponse, TwoFactorCodeRequest, TwoFactorStatusResponse,
+};
+use crate::services::TwoFactorService;
+use axum::{extract::State, response::IntoResponse, Json};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct TwoFactorHandlerState {
+    pub two_factor_service: TwoFactorService,
+}
+
+/// Whether two-factor authentication is enabled for the current user
+/// GET /api/auth/2fa
+#[utoipa::path(
+    get,
+    operation_id = "getTwoFactorStatus",
+    path = "/api/auth/2fa",
+    tag = "Two-Factor",
+    responses(
+        (status = 200, description = "Two-factor status", body = TwoFactorStatusResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_two_factor_status(
+    State(state): State<Arc<TwoFactorHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let enabled = state.two_factor_service.is_enabled(auth_user.id).await?;
+    Ok(Json(TwoFactorStatusResponse { enabled }))
+}
+
+/// Start TOTP enrolment
+/// POST /api/auth/2fa/setup
+#[utoipa::path(
+    post,
+    operation_id = "setupTwoFactor",
+    path = "/api/auth/2fa/setup",
+    tag = "Two-Factor",
+    responses(
+        (status = 200, description = "Secret to add to an authenticator app", body = TwoFactorSetupResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Two-factor already enabled")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn setup_two_factor(
+    State(state): State<Arc<TwoFactorHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let setup = state
+        .two_factor_service
+        .begin_setup(auth_user.id, &auth_user.email)
+        .await?;
+    Ok(Json(setup))
+}
+
+/// Confirm enrolment with a first code, enabling two-factor
+/// POST /api/auth/2fa/verify
+#[utoipa::path(
+    post,
+    operation_id = "verifyTwoFactor",
+    path = "/api/auth/2fa/verify",
+    tag = "Two-Factor",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "Two-factor enabled. Backup codes are only shown once.", body = TwoFactorBackupCodesResponse),
+        (status = 400, description = "Invalid code or setup not started"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Two-factor already enabled")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn verify_two_factor(
+    State(state): State<Arc<TwoFactorHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<TwoFactorCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let backup_codes = state
+        .two_factor_service
+        .enable(auth_user.id, &request.code)
+        .await?;
+    Ok(Json(TwoFactorBackupCodesResponse { backup_codes }))
+}
+
+/// Turn two-factor off with a TOTP or backup code
+/// POST /api/auth/2fa/disable
+#[utoipa::path(
+    post,
+    operation_id = "disableTwoFactor",
+    path = "/api/auth/2fa/disable",
+    tag = "Two-Factor",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "Two-factor disabled", body = MessageResponse),
+        (status = 400, description = "Invalid code or two-factor not enabled"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn disable_two_factor(
+    State(state): State<Arc<TwoFactorHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<TwoFactorCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .two_factor_service
+        .disable(auth_user.id, &request.code)
+        .await?;
+    Ok(Json(MessageResponse {
+        message: "Two-factor authentication disabled".to_string(),
+    }))
+}
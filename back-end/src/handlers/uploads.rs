@@ -0,0 +1,168 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::upload::{AppendChunkQuery, CreateUploadRequest};
+use crate::services::upload_service::UploadService;
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct UploadHandlerState {
+    pub upload_service: UploadService,
+}
+
+/// Start a resumable chunked photo upload
+/// POST /api/uploads
+#[utoipa::path(
+    post,
+    operation_id = "createUpload",
+    path = "/api/uploads",
+    tag = "Uploads",
+    request_body = CreateUploadRequest,
+    responses(
+        (status = 201, description = "Upload started", body = UploadStatusResponse),
+        (status = 400, description = "Invalid upload size"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_upload(
+    State(state): State<Arc<UploadHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateUploadRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let upload = state
+        .upload_service
+        .create(auth_user.id, request.total_size)
+        .await?;
+    Ok((StatusCode::CREATED, Json(upload)))
+}
+
+/// Get upload progress, e.g. to find where to resume after a dropped connection
+/// GET /api/uploads/:id
+#[utoipa::path(
+    get,
+    operation_id = "getUpload",
+    path = "/api/uploads/{id}",
+    tag = "Uploads",
+    params(
+        ("id" = Uuid, Path, description = "Upload ID")
+    ),
+    responses(
+        (status = 200, description = "Upload progress", body = UploadStatusResponse),
+        (status = 404, description = "Upload not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_upload(
+    State(state): State<Arc<UploadHandlerState>>,
+    auth_user: AuthUser,
+    Path(upload_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let upload = state.upload_service.status(upload_id, auth_user.id).await?;
+    Ok(Json(upload))
+}
+
+/// Append a chunk of raw bytes at the given offset
+/// POST /api/uploads/:id/chunks?offset=...
+#[utoipa::path(
+    post,
+    operation_id = "appendUploadChunk",
+    path = "/api/uploads/{id}/chunks",
+    tag = "Uploads",
+    params(
+        ("id" = Uuid, Path, description = "Upload ID"),
+        AppendChunkQuery
+    ),
+    request_body(content = String, description = "Raw chunk bytes", content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Chunk stored", body = UploadStatusResponse),
+        (status = 400, description = "Chunk too large, past the declared size, or upload expired"),
+        (status = 404, description = "Upload not found"),
+        (status = 409, description = "Offset does not match the bytes received so far, or upload already complete")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn append_upload_chunk(
+    State(state): State<Arc<UploadHandlerState>>,
+    auth_user: AuthUser,
+    Path(upload_id): Path<Uuid>,
+    Query(query): Query<AppendChunkQuery>,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let upload = state
+        .upload_service
+        .append(upload_id, auth_user.id, query.offset, &body)
+        .await?;
+    Ok(Json(upload))
+}
+
+/// Finish an upload once every byte has been received
+/// POST /api/uploads/:id/complete
+#[utoipa::path(
+    post,
+    operation_id = "completeUpload",
+    path = "/api/uploads/{id}/complete",
+    tag = "Uploads",
+    params(
+        ("id" = Uuid, Path, description = "Upload ID")
+    ),
+    responses(
+        (status = 200, description = "Upload complete; use its id as photo_upload_id", body = UploadStatusResponse),
+        (status = 400, description = "Upload incomplete or expired"),
+        (status = 404, description = "Upload not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn complete_upload(
+    State(state): State<Arc<UploadHandlerState>>,
+    auth_user: AuthUser,
+    Path(upload_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let upload = state
+        .upload_service
+        .complete(upload_id, auth_user.id)
+        .await?;
+    Ok(Json(upload))
+}
+
+/// Cancel an upload and discard its data
+/// DELETE /api/uploads/:id
+#[utoipa::path(
+    delete,
+    operation_id = "cancelUpload",
+    path = "/api/uploads/{id}",
+    tag = "Uploads",
+    params(
+        ("id" = Uuid, Path, description = "Upload ID")
+    ),
+    responses(
+        (status = 204, description = "Upload cancelled"),
+        (status = 404, description = "Upload not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn cancel_upload(
+    State(state): State<Arc<UploadHandlerState>>,
+    auth_user: AuthUser,
+    Path(upload_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state.upload_service.abort(upload_id, auth_user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
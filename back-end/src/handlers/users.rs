@@ -1,12 +1,17 @@
 use crate::auth::middleware::AuthUser;
 use crate::error::AppError;
-use crate::models::user::{UpdateUserRequest, User, UserResponse, UserRole};
-use axum::{extract::State, response::IntoResponse, Json};
+use crate::models::user::{PublicUserProfile, SelfUserProfile, UpdateUserRequest, User, UserRole};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
 use chrono::NaiveDate;
 use serde::Serialize;
 use sqlx::{FromRow, PgPool};
 use std::sync::Arc;
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[derive(FromRow, Serialize, ToSchema)]
 pub struct UserScoreRecord {
@@ -28,10 +33,23 @@ pub struct UserHandlerState {
 /// GET /api/users/me
 #[utoipa::path(
     get,
+    operation_id = "getCurrentUser",
     path = "/api/users/me",
     tag = "Users",
     responses(
-        (status = 200, description = "Returns user profile", body = UserResponse),
+        (status = 200, description = "Returns user profile", body = SelfUserProfile, example = json!({
+            "id": "6f1c2a8e-3b4d-4e5f-8a9b-0c1d2e3f4a5b",
+            "email": "user@example.com",
+            "full_name": "John Doe",
+            "city": "London",
+            "country": "UK",
+            "search_radius_km": 5,
+            "role": "user",
+            "email_verified": true,
+            "oauth_provider": null,
+            "has_password": true,
+            "created_at": "2024-05-01T09:30:00Z"
+        })),
         (status = 404, description = "User not found")
     ),
     security(
@@ -60,8 +78,8 @@ pub async fn get_current_user(
     .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
     tracing::debug!("Fetched user from DB: {:?}", user);
-    let response: UserResponse = user.into();
-    tracing::debug!("Converted to UserResponse: {:?}", response);
+    let response: SelfUserProfile = user.into();
+    tracing::debug!("Converted to SelfUserProfile: {:?}", response);
     Ok(Json(response))
 }
 
@@ -69,11 +87,12 @@ pub async fn get_current_user(
 /// PATCH /api/users/me
 #[utoipa::path(
     patch,
+    operation_id = "updateCurrentUser",
     path = "/api/users/me",
     tag = "Users",
     request_body = UpdateUserRequest,
     responses(
-        (status = 200, description = "Profile updated successfully", body = UserResponse),
+        (status = 200, description = "Profile updated successfully", body = SelfUserProfile),
         (status = 400, description = "Invalid parameters")
     ),
     security(
@@ -131,7 +150,7 @@ pub async fn update_current_user(
 
     let user = query_builder.fetch_one(&state.pool).await?;
 
-    let response: UserResponse = user.into();
+    let response: SelfUserProfile = user.into();
     Ok(Json(response))
 }
 
@@ -139,6 +158,7 @@ pub async fn update_current_user(
 /// GET /api/users/me/score
 #[utoipa::path(
     get,
+    operation_id = "getCurrentUserScore",
     path = "/api/users/me/score",
     tag = "Users",
     responses(
@@ -168,3 +188,36 @@ pub async fn get_current_user_score(
 
     Ok(Json(score))
 }
+
+/// Get another user's public profile: name, city, country and when they joined
+/// GET /api/users/:id
+#[utoipa::path(
+    get,
+    operation_id = "getUserProfile",
+    path = "/api/users/{id}",
+    tag = "Users",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Returns the public profile", body = PublicUserProfile),
+        (status = 404, description = "User not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_user_profile(
+    State(state): State<Arc<UserHandlerState>>,
+    _auth_user: AuthUser,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    // Banned accounts have no public profile
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND is_active")
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    Ok(Json(PublicUserProfile::from(user)))
+}
@@ -1,34 +1,33 @@
 use crate::auth::middleware::AuthUser;
-use crate::config::ScoringConfig;
+use crate::auth::ClientInfo;
 use crate::error::AppError;
-use crate::models::report::ReportStatus;
+use crate::models::pagination::{PageParams, Paginated};
 use crate::models::verification::{
-    CreateVerificationRequest, ReportVerification, VerificationResponse,
+    CreateVerificationRequest, VerificationReservation, VerificationResponse,
 };
-use crate::services::report_service::ReportService;
-use crate::services::scoring_service::ScoringService;
+use crate::services::fraud_service::FraudService;
+use crate::services::verification_service::VerificationService;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
-use sqlx::PgPool;
 use std::sync::Arc;
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct VerificationHandlerState {
-    pub pool: PgPool,
-    pub report_service: ReportService,
-    pub scoring_service: ScoringService,
-    pub scoring_config: ScoringConfig,
+    pub verification_service: VerificationService,
+    pub fraud_service: FraudService,
 }
 
-/// Verify a cleared report
+/// Verify a cleared report. Verdicts that look collusive are refused and the accounts
+/// flagged for review.
 /// POST /api/reports/:id/verify
 #[utoipa::path(
     post,
+    operation_id = "verifyReport",
     path = "/api/reports/{id}/verify",
     tag = "Verifications",
     request_body = CreateVerificationRequest,
@@ -38,8 +37,8 @@ pub struct VerificationHandlerState {
     responses(
         (status = 201, description = "Report verification submitted", body = VerificationResponse),
         (status = 404, description = "Report not found"),
-        (status = 400, description = "Invalid report status or self-verification"),
-        (status = 403, description = "Not enough experience to verify")
+        (status = 400, description = "Invalid report status, self-verification, or a better photo is awaited"),
+        (status = 403, description = "Not enough experience to verify, or held for review as possible collusion")
     ),
     security(
         ("bearer_auth" = [])
@@ -48,121 +47,108 @@ pub struct VerificationHandlerState {
 pub async fn verify_report(
     State(state): State<Arc<VerificationHandlerState>>,
     auth_user: AuthUser,
+    client: ClientInfo,
     Path(report_id): Path<Uuid>,
     Json(request): Json<CreateVerificationRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Check if user can verify reports (has cleared enough)
-    let can_verify = state
-        .scoring_service
-        .can_verify_reports(auth_user.id)
+    state
+        .fraud_service
+        .check_verification(
+            report_id,
+            auth_user.id,
+            request.is_verified,
+            client.ip_address.as_deref(),
+        )
         .await?;
-    if !can_verify {
-        return Err(AppError::Forbidden(format!(
-            "You need to clear at least {} reports before you can verify others",
-            state.scoring_config.min_clears_to_verify
-        )));
-    }
 
-    // Get the report
-    let report = state.report_service.get_report_by_id(report_id).await?;
-
-    // Check report status
-    if report.status != ReportStatus::Cleared {
-        return Err(AppError::BadRequest(
-            "Report must be cleared before it can be verified".to_string(),
-        ));
-    }
+    let verification = state
+        .verification_service
+        .create_verification(report_id, auth_user.id, request)
+        .await?;
 
-    // Check user is not the clearer
-    // Note: The reporter IS allowed to verify someone else's cleanup of their report
-    if report.cleared_by == Some(auth_user.id) {
-        return Err(AppError::BadRequest(
-            "You cannot verify a report you cleared yourself".to_string(),
-        ));
-    }
+    let response: VerificationResponse = verification.into();
+    Ok((StatusCode::CREATED, Json(response)))
+}
 
-    // Check if user has already verified this report
-    let existing = sqlx::query!(
-        "SELECT id FROM report_verifications WHERE report_id = $1 AND verifier_id = $2",
-        report_id,
-        auth_user.id
+/// Reserve one of a cleared report's open verification slots for a while, so other
+/// verifiers see it is being checked. Reserving again extends the hold.
+/// POST /api/reports/:id/verify/reserve
+#[utoipa::path(
+    post,
+    operation_id = "reserveReportVerification",
+    path = "/api/reports/{id}/verify/reserve",
+    tag = "Verifications",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Slot reserved", body = VerificationReservation),
+        (status = 404, description = "Report not found"),
+        (status = 400, description = "Invalid report status, self-verification, already verified, or a better photo is awaited"),
+        (status = 403, description = "Not enough experience to verify"),
+        (status = 409, description = "Every open slot is reserved by other verifiers")
+    ),
+    security(
+        ("bearer_auth" = [])
     )
-    .fetch_optional(&state.pool)
-    .await?;
+)]
+pub async fn reserve_verification(
+    State(state): State<Arc<VerificationHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+) -> Result<Json<VerificationReservation>, AppError> {
+    let reservation = state
+        .verification_service
+        .reserve(report_id, auth_user.id)
+        .await?;
 
-    if existing.is_some() {
-        return Err(AppError::BadRequest(
-            "You have already verified this report".to_string(),
-        ));
-    }
+    Ok(Json(reservation))
+}
 
-    // Create the verification
-    let verification = sqlx::query_as!(
-        ReportVerification,
-        r#"
-        INSERT INTO report_verifications (report_id, verifier_id, is_verified, comment)
-        VALUES ($1, $2, $3, $4)
-        RETURNING id, report_id, verifier_id, is_verified, comment, created_at
-        "#,
-        report_id,
-        auth_user.id,
-        request.is_verified,
-        request.comment
+/// Give up the caller's verification slot on a report
+/// DELETE /api/reports/:id/verify/reserve
+#[utoipa::path(
+    delete,
+    operation_id = "releaseReportVerification",
+    path = "/api/reports/{id}/verify/reserve",
+    tag = "Verifications",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 204, description = "Reservation released"),
+        (status = 404, description = "No active reservation on this report")
+    ),
+    security(
+        ("bearer_auth" = [])
     )
-    .fetch_one(&state.pool)
-    .await?;
-
-    // Award points to the verifier
+)]
+pub async fn release_verification(
+    State(state): State<Arc<VerificationHandlerState>>,
+    auth_user: AuthUser,
+    Path(report_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
     state
-        .scoring_service
-        .award_verification_points(auth_user.id, request.is_verified)
+        .verification_service
+        .release(report_id, auth_user.id)
         .await?;
 
-    // Check if we have enough positive verifications to mark report as verified
-    if request.is_verified {
-        let positive_count = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM report_verifications WHERE report_id = $1 AND is_verified = true",
-            report_id
-        )
-        .fetch_one(&state.pool)
-        .await?
-        .unwrap_or(0);
-
-        if positive_count >= i64::from(state.scoring_config.min_verifications_needed) {
-            // Update report to verified status
-            sqlx::query!(
-                r#"UPDATE litter_reports SET status = $1 WHERE id = $2"#,
-                ReportStatus::Verified as ReportStatus,
-                report_id
-            )
-            .execute(&state.pool)
-            .await?;
-
-            // Award bonus points to the clearer
-            if let Some(clearer_id) = report.cleared_by {
-                state
-                    .scoring_service
-                    .award_verified_report_bonus(clearer_id)
-                    .await?;
-            }
-        }
-    }
-
-    let response: VerificationResponse = verification.into();
-    Ok((StatusCode::CREATED, Json(response)))
+    Ok(StatusCode::NO_CONTENT)
 }
 
 /// Get all verifications for a report
 /// GET /api/reports/:id/verifications
 #[utoipa::path(
     get,
+    operation_id = "listReportVerifications",
     path = "/api/reports/{id}/verifications",
     tag = "Verifications",
     params(
-        ("id" = Uuid, Path, description = "Report ID")
+        ("id" = Uuid, Path, description = "Report ID"),
+        PageParams
     ),
     responses(
-        (status = 200, description = "Returns list of verifications", body = Vec<VerificationResponse>),
+        (status = 200, description = "Returns list of verifications", body = PaginatedVerifications),
         (status = 404, description = "Report not found")
     ),
     security(
@@ -173,26 +159,15 @@ pub async fn get_report_verifications(
     State(state): State<Arc<VerificationHandlerState>>,
     _auth_user: AuthUser,
     Path(report_id): Path<Uuid>,
+    Query(page): Query<PageParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Verify report exists
-    state.report_service.get_report_by_id(report_id).await?;
-
-    let verifications = sqlx::query_as!(
-        ReportVerification,
-        r#"
-        SELECT id, report_id, verifier_id, is_verified, comment, created_at
-        FROM report_verifications
-        WHERE report_id = $1
-        ORDER BY created_at DESC
-        "#,
-        report_id
-    )
-    .fetch_all(&state.pool)
-    .await?;
+    let offset = page.offset()?;
+    let (verifications, total) = state
+        .verification_service
+        .list_verifications(report_id, offset, page.limit(50, 100))
+        .await?;
 
-    let responses: Vec<VerificationResponse> = verifications
-        .into_iter()
-        .map(std::convert::Into::into)
-        .collect();
+    let responses: Paginated<VerificationResponse> =
+        Paginated::new(verifications, total, offset).map(std::convert::Into::into);
     Ok(Json(responses))
 }
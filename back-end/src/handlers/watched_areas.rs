@@ -0,0 +1,112 @@
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::models::pagination::Paginated;
+use crate::models::watched_area::{CreateWatchedAreaRequest, WatchedAreaResponse};
+use crate::services::watched_area_service::WatchedAreaService;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone)]
+pub struct WatchedAreaHandlerState {
+    pub watched_area_service: WatchedAreaService,
+}
+
+/// List the areas the current user watches
+/// GET /api/users/me/watched-areas
+#[utoipa::path(
+    get,
+    operation_id = "listWatchedAreas",
+    path = "/api/users/me/watched-areas",
+    tag = "Watched Areas",
+    responses(
+        (status = 200, description = "Returns watched areas", body = PaginatedWatchedAreas)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_watched_areas(
+    State(state): State<Arc<WatchedAreaHandlerState>>,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let areas = state.watched_area_service.list_areas(auth_user.id).await?;
+    // Watched areas are capped per user, so they always fit on one page
+    let responses: Paginated<WatchedAreaResponse> =
+        Paginated::all(areas).map(std::convert::Into::into);
+    Ok(Json(responses))
+}
+
+/// Watch an area: new reports inside it send a `watched_area_report` notification
+/// POST /api/users/me/watched-areas
+#[utoipa::path(
+    post,
+    operation_id = "createWatchedArea",
+    path = "/api/users/me/watched-areas",
+    tag = "Watched Areas",
+    request_body(content = CreateWatchedAreaRequest, example = json!({
+        "name": "Around the park",
+        "latitude": 51.5074,
+        "longitude": -0.1278,
+        "radius_km": 1.5
+    })),
+    responses(
+        (status = 201, description = "Area watched", body = WatchedAreaResponse),
+        (status = 400, description = "Invalid shape or too many watched areas")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_watched_area(
+    State(state): State<Arc<WatchedAreaHandlerState>>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateWatchedAreaRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request
+        .validate()
+        .map_err(|e| AppError::BadRequest(format!("Validation error: {e}")))?;
+
+    let area = state
+        .watched_area_service
+        .create_area(auth_user.id, request)
+        .await?;
+    let response: WatchedAreaResponse = area.into();
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Stop watching an area
+/// DELETE /api/users/me/watched-areas/:id
+#[utoipa::path(
+    delete,
+    operation_id = "deleteWatchedArea",
+    path = "/api/users/me/watched-areas/{id}",
+    tag = "Watched Areas",
+    params(
+        ("id" = Uuid, Path, description = "Watched area ID")
+    ),
+    responses(
+        (status = 204, description = "Area no longer watched"),
+        (status = 404, description = "Watched area not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_watched_area(
+    State(state): State<Arc<WatchedAreaHandlerState>>,
+    auth_user: AuthUser,
+    Path(area_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .watched_area_service
+        .delete_area(area_id, auth_user.id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,125 @@
+//! `Idempotency-Key` support for mutating endpoints: a retried request with the same
+//! key gets the original response back instead of being applied twice.
+
+use crate::auth::middleware::AuthUser;
+use crate::error::AppError;
+use crate::services::idempotency_service::{
+    IdempotencyOutcome, IdempotencyService, StoredResponse,
+};
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Header clients send a unique value in, e.g. a UUID generated per user action
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// Set on responses replayed from an earlier request
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Deduplicate requests that carry an `Idempotency-Key`; must run after `require_auth`.
+/// Only successful responses are stored, so a failed request can be retried with the
+/// same key. Requests without the header are handled as usual.
+pub async fn idempotent(
+    State(service): State<IdempotencyService>,
+    req: Request,
+    next: Next,
+) -> Response {
+    handle(&service, req, next)
+        .await
+        .unwrap_or_else(IntoResponse::into_response)
+}
+
+async fn handle(
+    service: &IdempotencyService,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(req).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| {
+            !key.is_empty()
+                && key.len() <= MAX_KEY_LENGTH
+                && key.chars().all(|c| c.is_ascii_graphic())
+        })
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "{IDEMPOTENCY_KEY_HEADER} must be 1-{MAX_KEY_LENGTH} printable ASCII characters"
+            ))
+        })?
+        .to_string();
+    let user_id = req
+        .extensions()
+        .get::<AuthUser>()
+        .map(|user| user.id)
+        .ok_or(AppError::Unauthorized)?;
+    let endpoint = format!("{} {}", req.method(), req.uri().path());
+
+    let (parts, body) = req.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to read request body: {e}")))?;
+    let request_hash = format!("{:x}", Sha256::digest(&body));
+
+    if let IdempotencyOutcome::Replay(stored) = service
+        .begin(user_id, &endpoint, &key, &request_hash)
+        .await?
+    {
+        tracing::debug!("Replaying response for {} key {}", endpoint, key);
+        return Ok(replay(stored));
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    if !response.status().is_success() {
+        if let Err(e) = service.release(user_id, &endpoint, &key).await {
+            tracing::error!("Failed to release idempotency key {}: {:?}", key, e);
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to read response body: {e}")))?;
+    let stored = StoredResponse {
+        status_code: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+    if let Err(e) = service.complete(user_id, &endpoint, &key, &stored).await {
+        tracing::error!(
+            "Failed to store response for idempotency key {}: {:?}",
+            key,
+            e
+        );
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status_code).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|v| HeaderValue::from_str(&v).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
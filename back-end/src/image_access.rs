@@ -0,0 +1,223 @@
+//! Guards for the public report photo routes: optional signed URLs, hotlink
+//! protection by `Referer`, and an access log.
+//!
+//! A signed URL carries `expires` (Unix seconds) and `signature`, an HMAC-SHA256 over
+//! the path and expiry, so a link handed to one client stops working after its TTL
+//! and cannot be edited to point at another photo.
+
+use crate::config::ImageAccessConfig;
+use crate::error::AppError;
+use crate::rate_limit;
+use axum::{
+    extract::{Query, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::sync::Arc;
+
+/// `tracing` target of the image access log, so it can be routed on its own
+pub const ACCESS_LOG_TARGET: &str = "image_access";
+
+/// A path with its signature, and when it stops working
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedUrl {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Signs and checks image URLs with a shared secret
+#[derive(Clone)]
+pub struct ImageUrlSigner {
+    secret: Arc<[u8]>,
+    ttl: Duration,
+}
+
+impl ImageUrlSigner {
+    #[must_use]
+    pub fn new(secret: &str, ttl_secs: i64) -> Self {
+        Self {
+            secret: secret.as_bytes().into(),
+            ttl: Duration::seconds(ttl_secs.max(1)),
+        }
+    }
+
+    fn mac(&self, path: &str, expires: i64) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(path.as_bytes());
+        mac.update(b"\n");
+        mac.update(expires.to_string().as_bytes());
+        mac
+    }
+
+    /// The `signature` for `path` valid until `expires`
+    #[must_use]
+    pub fn signature(&self, path: &str, expires: i64) -> String {
+        URL_SAFE_NO_PAD.encode(self.mac(path, expires).finalize().into_bytes())
+    }
+
+    /// `path` with `expires` and `signature` appended, valid for the configured TTL
+    #[must_use]
+    pub fn sign(&self, path: &str, now: DateTime<Utc>) -> SignedUrl {
+        let expires_at = now + self.ttl;
+        let expires = expires_at.timestamp();
+        SignedUrl {
+            url: format!(
+                "{path}?expires={expires}&signature={}",
+                self.signature(path, expires)
+            ),
+            expires_at,
+        }
+    }
+
+    /// Whether `signature` was issued for `path` and `expires` has not passed
+    #[must_use]
+    pub fn verify(&self, path: &str, expires: i64, signature: &str, now: DateTime<Utc>) -> bool {
+        if expires < now.timestamp() {
+            return false;
+        }
+        let Ok(signature) = URL_SAFE_NO_PAD.decode(signature) else {
+            return false;
+        };
+        // Constant-time comparison
+        self.mac(path, expires).verify_slice(&signature).is_ok()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SignatureParams {
+    expires: Option<i64>,
+    signature: Option<String>,
+}
+
+/// The rules from `ImageAccessConfig`, shared by the image routes' middleware and the
+/// handler that hands out signed URLs
+pub struct ImageAccess {
+    signer: Option<ImageUrlSigner>,
+    require_signed_urls: bool,
+    allowed_referers: Vec<String>,
+    allow_missing_referer: bool,
+    access_log: bool,
+    trust_proxy_headers: bool,
+}
+
+impl ImageAccess {
+    #[must_use]
+    pub fn new(config: &ImageAccessConfig, trust_proxy_headers: bool) -> Self {
+        Self {
+            signer: config
+                .signing_secret
+                .as_deref()
+                .map(|secret| ImageUrlSigner::new(secret, config.signed_url_ttl_secs)),
+            require_signed_urls: config.require_signed_urls && config.signing_secret.is_some(),
+            allowed_referers: config.allowed_referers.clone(),
+            allow_missing_referer: config.allow_missing_referer,
+            access_log: config.access_log,
+            trust_proxy_headers,
+        }
+    }
+
+    /// `None` when signing is off
+    #[must_use]
+    pub fn signer(&self) -> Option<&ImageUrlSigner> {
+        self.signer.as_ref()
+    }
+
+    /// Whether a request with this `Referer` may load images. A referer matches an
+    /// allowed host or any of its subdomains.
+    #[must_use]
+    pub fn referer_allowed(&self, referer: Option<&str>) -> bool {
+        if self.allowed_referers.is_empty() {
+            return true;
+        }
+        let Some(referer) = referer else {
+            return self.allow_missing_referer;
+        };
+        let Some(host) = reqwest::Url::parse(referer)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+        else {
+            return false;
+        };
+
+        self.allowed_referers.iter().any(|allowed| {
+            host == *allowed
+                || host
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+
+    /// Refuse the request, or say whether it came with a valid signature
+    fn check(&self, req: &Request, referer: Option<&str>) -> Result<bool, AppError> {
+        if !self.referer_allowed(referer) {
+            return Err(AppError::Forbidden(
+                "Images may not be embedded on this site".to_string(),
+            ));
+        }
+
+        let Some(signer) = &self.signer else {
+            return Ok(false);
+        };
+        let params = Query::<SignatureParams>::try_from_uri(req.uri())
+            .map(|Query(params)| params)
+            .unwrap_or_default();
+
+        match (params.expires, params.signature) {
+            (Some(expires), Some(signature)) => {
+                if signer.verify(req.uri().path(), expires, &signature, Utc::now()) {
+                    Ok(true)
+                } else {
+                    Err(AppError::Forbidden(
+                        "Image link is invalid or has expired".to_string(),
+                    ))
+                }
+            }
+            _ if self.require_signed_urls => Err(AppError::Forbidden(
+                "Image links must be signed".to_string(),
+            )),
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Apply `ImageAccess` to a route and log each request. Runs outside the per-IP
+/// limit so refused and rate-limited requests are logged too.
+pub async fn guard_images(
+    State(access): State<Arc<ImageAccess>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let referer = req
+        .headers()
+        .get(header::REFERER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+    let ip = rate_limit::client_ip(&req, access.trust_proxy_headers);
+    let path = req.uri().path().to_string();
+
+    let (response, signed) = match access.check(&req, referer.as_deref()) {
+        Ok(signed) => (next.run(req).await, signed),
+        Err(e) => (e.into_response(), false),
+    };
+
+    if access.access_log {
+        tracing::info!(
+            target: ACCESS_LOG_TARGET,
+            ip = ?ip,
+            path = %path,
+            referer = referer.as_deref().unwrap_or("-"),
+            status = response.status().as_u16(),
+            signed,
+            "Image request"
+        );
+    }
+
+    response
+}
@@ -0,0 +1,59 @@
+use crate::error::AppError;
+use crate::models::notification::NotificationKind;
+use crate::services::{NotificationService, ReportService};
+
+/// Return reports to the pending pool when their claimer has sat on them too long, so
+/// litter doesn't stay reserved by someone who is never going to pick it up
+#[derive(Clone)]
+pub struct ClaimExpiryJob {
+    report_service: ReportService,
+    notification_service: NotificationService,
+    max_claim_age_hours: i64,
+}
+
+impl ClaimExpiryJob {
+    #[must_use]
+    pub fn new(
+        report_service: ReportService,
+        notification_service: NotificationService,
+        max_claim_age_hours: i64,
+    ) -> Self {
+        Self {
+            report_service,
+            notification_service,
+            max_claim_age_hours,
+        }
+    }
+
+    /// Expire one round of stale claims, returning how many were released.
+    ///
+    /// Each expiry counts against the claimer's abandonment limits, and they are told the
+    /// report is open to others again.
+    pub async fn run(&self) -> Result<usize, AppError> {
+        let expired = self
+            .report_service
+            .expire_stale_claims(self.max_claim_age_hours)
+            .await?;
+
+        for (report_id, user_id) in &expired {
+            self.notification_service
+                .notify(
+                    *user_id,
+                    NotificationKind::ClaimExpired,
+                    "Your claim expired",
+                    &format!(
+                        "You didn't clear this report within {} hours, so it is open to other pickers again.",
+                        self.max_claim_age_hours
+                    ),
+                    Some(*report_id),
+                )
+                .await;
+        }
+
+        if !expired.is_empty() {
+            tracing::info!("Expired {} stale claims", expired.len());
+        }
+
+        Ok(expired.len())
+    }
+}
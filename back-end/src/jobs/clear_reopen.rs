@@ -0,0 +1,58 @@
+use crate::error::AppError;
+use crate::models::notification::NotificationKind;
+use crate::services::{NotificationService, VerificationService};
+
+/// Put disputed reports back in the pending pool when no admin has settled the dispute,
+/// so litter verifiers say is still there gets picked up again
+#[derive(Clone)]
+pub struct ClearReopenJob {
+    verification_service: VerificationService,
+    notification_service: NotificationService,
+    after_hours: i64,
+}
+
+impl ClearReopenJob {
+    #[must_use]
+    pub fn new(
+        verification_service: VerificationService,
+        notification_service: NotificationService,
+        after_hours: i64,
+    ) -> Self {
+        Self {
+            verification_service,
+            notification_service,
+            after_hours,
+        }
+    }
+
+    /// Reopen one round of unsettled disputes, returning how many were reopened.
+    ///
+    /// Each clearer loses the points the clear earned and is told why.
+    pub async fn run(&self) -> Result<usize, AppError> {
+        let reopened = self
+            .verification_service
+            .reopen_rejected_clears(self.after_hours)
+            .await?;
+
+        for (report_id, cleared_by) in &reopened {
+            let Some(clearer_id) = cleared_by else {
+                continue;
+            };
+            self.notification_service
+                .notify(
+                    *clearer_id,
+                    NotificationKind::ClearRejected,
+                    "Your cleanup was rejected",
+                    "Verifiers found litter still there, so the report is open to pickers again and the points for clearing it have been removed.",
+                    Some(*report_id),
+                )
+                .await;
+        }
+
+        if !reopened.is_empty() {
+            tracing::info!("Reopened {} disputed reports", reopened.len());
+        }
+
+        Ok(reopened.len())
+    }
+}
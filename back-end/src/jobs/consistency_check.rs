@@ -0,0 +1,277 @@
+use crate::error::AppError;
+use crate::models::consistency_check::{ConsistencyAnomaly, ConsistencyCheckRun};
+use crate::models::pagination::{PageParams, Paginated};
+use crate::models::report_lifecycle::ReportTransition;
+use crate::services::report_service::ReportService;
+use chrono::Utc;
+use sqlx::types::Json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DEFAULT_RUN_PAGE_SIZE: i64 = 20;
+const MAX_RUN_PAGE_SIZE: i64 = 100;
+
+/// Find and repair rows that point at deleted data or derived state that no longer
+/// matches it. The foreign keys prevent most of this; the check catches what slipped
+/// through manual cleanups, and every run is recorded so admins can see what changed.
+#[derive(Clone)]
+pub struct ConsistencyCheckJob {
+    pool: PgPool,
+}
+
+impl ConsistencyCheckJob {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Run every check in one transaction, rolled back for a dry run, and record the
+    /// result. `triggered_by` is the admin who asked for the run, if any.
+    pub async fn run(
+        &self,
+        triggered_by: Option<Uuid>,
+        dry_run: bool,
+    ) -> Result<ConsistencyCheckRun, AppError> {
+        let started_at = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        let mut anomalies = Vec::new();
+        let mut record = |check: &str, description: &str, rows: u64| {
+            let found = i64::try_from(rows).unwrap_or(i64::MAX);
+            anomalies.push(ConsistencyAnomaly {
+                check: check.to_string(),
+                description: description.to_string(),
+                found,
+                fixed: if dry_run { 0 } else { found },
+            });
+        };
+
+        let rows = sqlx::query!(
+            "DELETE FROM user_scores s WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.id = s.user_id)"
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        record(
+            "orphaned_user_scores",
+            "Scores of deleted users (deleted)",
+            rows,
+        );
+
+        let orphaned: Vec<Uuid> = sqlx::query_scalar(
+            r"
+            SELECT id FROM litter_reports r
+            WHERE r.reporter_id IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM users u WHERE u.id = r.reporter_id)
+            FOR UPDATE
+            ",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        ReportService::record_deletions(
+            &mut tx,
+            &orphaned,
+            None,
+            Some("Reporter's account was deleted"),
+        )
+        .await?;
+        sqlx::query("DELETE FROM litter_reports WHERE id = ANY($1)")
+            .bind(&orphaned)
+            .execute(&mut *tx)
+            .await?;
+        record(
+            "reports_without_reporter",
+            "Reports whose reporter no longer exists (deleted)",
+            orphaned.len() as u64,
+        );
+
+        let rows = sqlx::query!(
+            r#"
+            UPDATE litter_reports r SET claimed_by = NULL
+            WHERE claimed_by IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM users u WHERE u.id = r.claimed_by)
+            "#
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        record(
+            "dangling_report_claimers",
+            "Reports naming a deleted user as claimer (claimer removed)",
+            rows,
+        );
+
+        let rows = sqlx::query!(
+            r#"
+            UPDATE litter_reports r SET cleared_by = NULL
+            WHERE cleared_by IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM users u WHERE u.id = r.cleared_by)
+            "#
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        record(
+            "dangling_report_clearers",
+            "Reports naming a deleted user as clearer (clearer removed)",
+            rows,
+        );
+
+        let rows = sqlx::query!(
+            r#"
+            DELETE FROM report_verifications v
+            WHERE NOT EXISTS (SELECT 1 FROM litter_reports r WHERE r.id = v.report_id)
+               OR NOT EXISTS (SELECT 1 FROM users u WHERE u.id = v.verifier_id)
+            "#
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        record(
+            "orphaned_verifications",
+            "Verifications of deleted reports or by deleted users (deleted)",
+            rows,
+        );
+
+        let rows = sqlx::query!(
+            r#"
+            UPDATE score_events e SET report_id = NULL
+            WHERE report_id IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM litter_reports r WHERE r.id = e.report_id)
+            "#
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        record(
+            "dangling_score_event_reports",
+            "Score events pointing at deleted reports (report link removed)",
+            rows,
+        );
+
+        let released: Vec<Uuid> = sqlx::query_scalar(
+            r"
+            UPDATE litter_reports
+            SET status = 'pending', claimed_at = NULL, cleanup_run_id = NULL
+            WHERE status = 'claimed' AND claimed_by IS NULL
+            RETURNING id
+            ",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        ReportService::record_status_changes(
+            &mut tx,
+            &released,
+            ReportTransition::Expire,
+            Some("Claimer's account was deleted"),
+        )
+        .await?;
+        record(
+            "claims_without_claimer",
+            "Reports still claimed after the claimer's account was deleted (released)",
+            released.len() as u64,
+        );
+
+        let rows = sqlx::query!(
+            r#"
+            INSERT INTO user_scores (user_id)
+            SELECT u.id FROM users u
+            WHERE NOT EXISTS (SELECT 1 FROM user_scores s WHERE s.user_id = u.id)
+            "#
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        record(
+            "users_without_scores",
+            "Users with no score row (created)",
+            rows,
+        );
+
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+
+        let total_found = anomalies.iter().map(|a| a.found).sum::<i64>();
+        let total_fixed = anomalies.iter().map(|a| a.fixed).sum::<i64>();
+        if total_found > 0 {
+            tracing::warn!(
+                "Consistency check found {} inconsistent rows, fixed {}",
+                total_found,
+                total_fixed
+            );
+        }
+
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO consistency_check_runs
+                (triggered_by, dry_run, anomalies, total_found, total_fixed, started_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, finished_at
+            "#,
+            triggered_by,
+            dry_run,
+            Json(&anomalies) as _,
+            total_found,
+            total_fixed,
+            started_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ConsistencyCheckRun {
+            id: row.id,
+            triggered_by,
+            dry_run,
+            anomalies,
+            total_found,
+            total_fixed,
+            started_at,
+            finished_at: row.finished_at,
+        })
+    }
+
+    /// Recorded runs, newest first
+    pub async fn list_runs(
+        &self,
+        page: &PageParams,
+    ) -> Result<Paginated<ConsistencyCheckRun>, AppError> {
+        let offset = page.offset()?;
+        let limit = page.limit(DEFAULT_RUN_PAGE_SIZE, MAX_RUN_PAGE_SIZE);
+
+        let total =
+            sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM consistency_check_runs"#)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let runs = sqlx::query!(
+            r#"
+            SELECT id, triggered_by, dry_run,
+                   anomalies AS "anomalies: Json<Vec<ConsistencyAnomaly>>",
+                   total_found, total_fixed, started_at, finished_at
+            FROM consistency_check_runs
+            ORDER BY started_at DESC
+            LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| ConsistencyCheckRun {
+            id: row.id,
+            triggered_by: row.triggered_by,
+            dry_run: row.dry_run,
+            anomalies: row.anomalies.0,
+            total_found: row.total_found,
+            total_fixed: row.total_fixed,
+            started_at: row.started_at,
+            finished_at: row.finished_at,
+        })
+        .collect();
+
+        Ok(Paginated::new(runs, total, offset))
+    }
+}
@@ -0,0 +1,82 @@
+use crate::error::AppError;
+use crate::services::AuthService;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Accounts older than this are left alone, so enabling the job doesn't mail every
+/// abandoned sign-up at once
+const MAX_ACCOUNT_AGE_DAYS: i32 = 14;
+/// Reminders sent per run; the rest wait for the next tick
+const BATCH_SIZE: i64 = 100;
+
+/// Email users who registered a while ago but never verified their address, since
+/// many never notice the resend button
+#[derive(Clone)]
+pub struct EmailVerificationReminderJob {
+    pool: PgPool,
+    auth_service: Arc<AuthService>,
+    remind_after_hours: i64,
+}
+
+impl EmailVerificationReminderJob {
+    #[must_use]
+    pub fn new(pool: PgPool, auth_service: Arc<AuthService>, remind_after_hours: i64) -> Self {
+        Self {
+            pool,
+            auth_service,
+            remind_after_hours,
+        }
+    }
+
+    /// Send one round of reminders, returning how many were sent.
+    ///
+    /// Each account gets at most one reminder, never after unsubscribing. Accounts are
+    /// marked before sending so concurrent runs on other instances skip them.
+    pub async fn run(&self) -> Result<usize, AppError> {
+        let users = sqlx::query!(
+            r#"
+            UPDATE users SET verification_reminder_sent_at = NOW()
+            WHERE id IN (
+                SELECT id FROM users
+                WHERE NOT email_verified
+                  AND is_active
+                  AND verification_reminder_sent_at IS NULL
+                  AND NOT verification_reminders_unsubscribed
+                  AND created_at < NOW() - make_interval(hours => $1)
+                  AND created_at > NOW() - make_interval(days => $2)
+                ORDER BY created_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, email, full_name
+            "#,
+            i32::try_from(self.remind_after_hours).unwrap_or(i32::MAX),
+            MAX_ACCOUNT_AGE_DAYS,
+            BATCH_SIZE
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut sent = 0;
+        for user in &users {
+            match self
+                .auth_service
+                .send_verification_reminder(user.id, &user.email, &user.full_name)
+                .await
+            {
+                Ok(()) => sent += 1,
+                Err(e) => tracing::error!(
+                    "Failed to send verification reminder to user {}: {:?}",
+                    user.id,
+                    e
+                ),
+            }
+        }
+
+        if sent > 0 {
+            tracing::info!("Sent {} email verification reminders", sent);
+        }
+
+        Ok(sent)
+    }
+}
@@ -0,0 +1,30 @@
+use crate::error::AppError;
+use crate::services::FeedEngagementService;
+
+/// Re-rank the trending feed from recent engagement, so posts people stop engaging with
+/// drift down
+#[derive(Clone)]
+pub struct FeedEngagementScoreJob {
+    engagement_service: FeedEngagementService,
+    half_life_hours: f64,
+}
+
+impl FeedEngagementScoreJob {
+    #[must_use]
+    pub fn new(engagement_service: FeedEngagementService, half_life_hours: f64) -> Self {
+        Self {
+            engagement_service,
+            half_life_hours,
+        }
+    }
+
+    /// Recompute every post's score, returning how many posts have one
+    pub async fn run(&self) -> Result<u64, AppError> {
+        let scored = self
+            .engagement_service
+            .recompute_scores(self.half_life_hours)
+            .await?;
+        tracing::debug!("Scored engagement for {} feed posts", scored);
+        Ok(scored)
+    }
+}
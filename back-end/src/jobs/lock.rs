@@ -0,0 +1,118 @@
+use crate::error::AppError;
+use sqlx::PgPool;
+use std::future::Future;
+use std::time::Duration;
+
+/// First key of every job's advisory lock, so they cannot clash with other locks
+const JOB_LOCK_NAMESPACE: i32 = 0x4A4F_4253;
+
+/// How early another instance's tick may come and still count as the same tick, as a
+/// fraction of the interval. Replicas start at different times, so their ticks drift.
+const TICK_TOLERANCE: f64 = 0.1;
+
+/// Makes a job scheduled on every instance run once per tick across all of them.
+///
+/// A Postgres advisory lock keyed on the job's name keeps two runs from overlapping, and
+/// the `job_runs` table records when the job last started so instances whose ticks come
+/// later skip it. If an instance dies mid-run its connection closes and the lock goes
+/// with it.
+#[derive(Clone)]
+pub struct JobLock {
+    pool: PgPool,
+}
+
+impl JobLock {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Run `job` as `name` unless another instance is running it or has started it
+    /// within the last `interval`. Returns `None` when the run was skipped.
+    pub async fn run_exclusive<T, Fut>(
+        &self,
+        name: &str,
+        interval: Duration,
+        job: Fut,
+    ) -> Result<Option<T>, AppError>
+    where
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        // Session locks belong to a connection, so the same one must release it
+        let mut conn = self.pool.acquire().await?;
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1, hashtext($2))")
+            .bind(JOB_LOCK_NAMESPACE)
+            .bind(name)
+            .fetch_one(&mut *conn)
+            .await?;
+        if !locked {
+            tracing::debug!("Job {} is running on another instance", name);
+            return Ok(None);
+        }
+
+        let result = self.run_locked(name, interval, job).await;
+
+        let unlocked: Result<bool, sqlx::Error> =
+            sqlx::query_scalar("SELECT pg_advisory_unlock($1, hashtext($2))")
+                .bind(JOB_LOCK_NAMESPACE)
+                .bind(name)
+                .fetch_one(&mut *conn)
+                .await;
+        if !matches!(unlocked, Ok(true)) {
+            // A lock left on a pooled connection would stop the job everywhere, so close
+            // the connection instead of returning it
+            tracing::warn!(
+                "Could not release the lock for job {}: {:?}",
+                name,
+                unlocked
+            );
+            drop(conn.detach());
+        }
+
+        result
+    }
+
+    async fn run_locked<T, Fut>(
+        &self,
+        name: &str,
+        interval: Duration,
+        job: Fut,
+    ) -> Result<Option<T>, AppError>
+    where
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let claimed: Option<String> = sqlx::query_scalar(
+            r"
+            INSERT INTO job_runs (name, last_started_at)
+            VALUES ($1, NOW())
+            ON CONFLICT (name) DO UPDATE
+            SET last_started_at = NOW(), last_finished_at = NULL
+            WHERE job_runs.last_started_at <= NOW() - make_interval(secs => $2)
+            RETURNING name
+            ",
+        )
+        .bind(name)
+        .bind(interval.as_secs_f64() * (1.0 - TICK_TOLERANCE))
+        .fetch_optional(&self.pool)
+        .await?;
+        if claimed.is_none() {
+            tracing::debug!("Job {} already ran this tick on another instance", name);
+            return Ok(None);
+        }
+
+        let result = job.await;
+
+        if let Err(e) = sqlx::query(
+            "UPDATE job_runs SET last_finished_at = NOW(), last_error = $2 WHERE name = $1",
+        )
+        .bind(name)
+        .bind(result.as_ref().err().map(|e| format!("{e:?}")))
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!("Could not record the run of job {}: {:?}", name, e);
+        }
+
+        result.map(Some)
+    }
+}
@@ -0,0 +1,66 @@
+//! Background jobs that run on a fixed interval inside the server process
+
+pub mod claim_expiry;
+pub mod clear_reopen;
+pub mod consistency_check;
+pub mod email_verification_reminders;
+pub mod feed_engagement_scores;
+pub mod lock;
+pub mod report_expiry;
+pub mod verification_reminders;
+pub mod verification_window;
+
+use crate::error::AppError;
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+pub use claim_expiry::ClaimExpiryJob;
+pub use clear_reopen::ClearReopenJob;
+pub use consistency_check::ConsistencyCheckJob;
+pub use email_verification_reminders::EmailVerificationReminderJob;
+pub use feed_engagement_scores::FeedEngagementScoreJob;
+pub use lock::JobLock;
+pub use report_expiry::ReportExpiryJob;
+pub use verification_reminders::VerificationReminderJob;
+pub use verification_window::VerificationWindowJob;
+
+/// Run `job` every `interval` until the process exits. A failed run is logged and
+/// the job simply tries again on the next tick.
+pub fn spawn_periodic<F, Fut>(name: &'static str, interval: Duration, job: F) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), AppError>> + Send,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // A slow run should not be followed by a burst of catch-up runs
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            if let Err(e) = job().await {
+                tracing::error!("Job {} failed: {:?}", name, e);
+            }
+        }
+    })
+}
+
+/// Like [`spawn_periodic`], for jobs that work on shared data: every instance schedules
+/// the job, but only one of them runs it each tick.
+pub fn spawn_exclusive<F, Fut>(
+    lock: JobLock,
+    name: &'static str,
+    interval: Duration,
+    job: F,
+) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), AppError>> + Send,
+{
+    spawn_periodic(name, interval, move || {
+        let lock = lock.clone();
+        let run = job();
+        async move { lock.run_exclusive(name, interval, run).await.map(|_| ()) }
+    })
+}
@@ -0,0 +1,56 @@
+use crate::error::AppError;
+use crate::services::{ImageStorageService, ReportService};
+
+/// Take reports nobody has cleared for months off the map, so it shows litter that is
+/// likely still there, and move photos only they use to cheaper storage
+#[derive(Clone)]
+pub struct ReportExpiryJob {
+    report_service: ReportService,
+    image_storage: ImageStorageService,
+    max_age_months: i32,
+    /// `None` leaves photos in their storage class
+    archive_storage_class: Option<String>,
+}
+
+impl ReportExpiryJob {
+    #[must_use]
+    pub fn new(
+        report_service: ReportService,
+        image_storage: ImageStorageService,
+        max_age_months: i32,
+        archive_storage_class: Option<String>,
+    ) -> Self {
+        Self {
+            report_service,
+            image_storage,
+            max_age_months,
+            archive_storage_class,
+        }
+    }
+
+    /// Expire one round of stale reports, returning how many expired.
+    ///
+    /// Photos are archived in batches, so a large first run spreads over a few ticks.
+    pub async fn run(&self) -> Result<usize, AppError> {
+        let expired = self
+            .report_service
+            .expire_stale_reports(self.max_age_months)
+            .await?;
+        if !expired.is_empty() {
+            tracing::info!("Expired {} stale reports", expired.len());
+        }
+
+        if let Some(storage_class) = &self.archive_storage_class {
+            let archived = self.image_storage.archive_expired(storage_class).await?;
+            if archived > 0 {
+                tracing::info!(
+                    "Moved {} photos of expired reports to {}",
+                    archived,
+                    storage_class
+                );
+            }
+        }
+
+        Ok(expired.len())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::AppError;
+use crate::models::notification::NotificationKind;
+use crate::services::NotificationService;
+use sqlx::PgPool;
+
+/// A verifier's own clears from this far back define the area they get reminded about
+const HOME_AREA_LOOKBACK_DAYS: i32 = 90;
+
+/// Tell eligible verifiers about cleared reports near where they pick, so clears
+/// don't sit unverified because nobody knows they exist
+#[derive(Clone)]
+pub struct VerificationReminderJob {
+    pool: PgPool,
+    notification_service: NotificationService,
+    min_clears_to_verify: i32,
+    reminders_per_day: i64,
+}
+
+impl VerificationReminderJob {
+    #[must_use]
+    pub fn new(
+        pool: PgPool,
+        notification_service: NotificationService,
+        min_clears_to_verify: i32,
+        reminders_per_day: i64,
+    ) -> Self {
+        Self {
+            pool,
+            notification_service,
+            min_clears_to_verify,
+            reminders_per_day,
+        }
+    }
+
+    /// Send one round of reminders, returning how many were sent.
+    ///
+    /// A verifier is reminded about a report when they have enough clears to verify,
+    /// the report lies within their search radius of one of their recent clears, and
+    /// they neither reported, claimed, cleared nor already verified it. Each report is
+    /// mentioned to a verifier at most once, oldest clears first, and no verifier gets
+    /// more than the daily cap.
+    pub async fn run(&self) -> Result<usize, AppError> {
+        let reminders = sqlx::query!(
+            r#"
+            WITH eligible AS (
+                SELECT u.id AS user_id, u.search_radius_km
+                FROM users u
+                JOIN user_scores s ON s.user_id = u.id
+                WHERE u.is_active AND s.total_clears >= $1
+            ),
+            candidates AS (
+                SELECT DISTINCT e.user_id, r.id AS report_id, r.address, r.cleared_at
+                FROM eligible e
+                JOIN litter_reports home
+                  ON home.cleared_by = e.user_id
+                 AND home.cleared_at > NOW() - make_interval(days => $3)
+                JOIN litter_reports r
+                  ON r.status = 'cleared'
+                 AND ST_DWithin(
+                        r.location::geography,
+                        home.location::geography,
+                        e.search_radius_km * 1000.0
+                     )
+                WHERE r.reporter_id IS DISTINCT FROM e.user_id
+                  AND r.claimed_by IS DISTINCT FROM e.user_id
+                  AND r.cleared_by IS DISTINCT FROM e.user_id
+                  AND NOT EXISTS (
+                      SELECT 1 FROM report_verifications v
+                      WHERE v.report_id = r.id AND v.verifier_id = e.user_id
+                        AND v.superseded_at IS NULL
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM photo_retake_requests p
+                      WHERE p.report_id = r.id
+                        AND p.fulfilled_at IS NULL
+                        AND p.expires_at > NOW()
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM notifications n
+                      WHERE n.user_id = e.user_id AND n.report_id = r.id AND n.kind = $4
+                  )
+            ),
+            ranked AS (
+                SELECT c.*,
+                       ROW_NUMBER() OVER (PARTITION BY c.user_id ORDER BY c.cleared_at ASC) AS rank
+                FROM candidates c
+            ),
+            sent_today AS (
+                SELECT user_id, COUNT(*) AS sent
+                FROM notifications
+                WHERE kind = $4 AND created_at > NOW() - INTERVAL '1 day'
+                GROUP BY user_id
+            )
+            SELECT ranked.user_id AS "user_id!", ranked.report_id AS "report_id!", ranked.address
+            FROM ranked
+            LEFT JOIN sent_today ON sent_today.user_id = ranked.user_id
+            WHERE ranked.rank + COALESCE(sent_today.sent, 0) <= $2
+            "#,
+            self.min_clears_to_verify,
+            self.reminders_per_day,
+            HOME_AREA_LOOKBACK_DAYS,
+            NotificationKind::VerificationReminder.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for reminder in &reminders {
+            let body = match &reminder.address {
+                Some(address) => format!("Help confirm the cleanup at {address}"),
+                None => "Help confirm a cleanup in your area".to_string(),
+            };
+            self.notification_service
+                .notify(
+                    reminder.user_id,
+                    NotificationKind::VerificationReminder,
+                    "A cleanup near you needs verifying",
+                    &body,
+                    Some(reminder.report_id),
+                )
+                .await;
+        }
+
+        if !reminders.is_empty() {
+            tracing::info!("Sent {} verification reminders", reminders.len());
+        }
+
+        Ok(reminders.len())
+    }
+}
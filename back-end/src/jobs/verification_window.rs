@@ -0,0 +1,43 @@
+use crate::error::AppError;
+use crate::services::VerificationService;
+
+/// Settle clears that verifiers left waiting, so a report in a quiet area doesn't sit at
+/// cleared forever for want of enough verdicts
+#[derive(Clone)]
+pub struct VerificationWindowJob {
+    verification_service: VerificationService,
+    window_days: i64,
+}
+
+impl VerificationWindowJob {
+    #[must_use]
+    pub fn new(verification_service: VerificationService, window_days: i64) -> Self {
+        Self {
+            verification_service,
+            window_days,
+        }
+    }
+
+    /// Finalize one round of overdue clears, returning how many were settled.
+    ///
+    /// Verified clearers get the usual bonus and notification; disputed ones are left to
+    /// the dispute process.
+    pub async fn run(&self) -> Result<usize, AppError> {
+        let finalized = self
+            .verification_service
+            .finalize_overdue_clears(self.window_days)
+            .await?;
+
+        if !finalized.is_empty() {
+            let verified = finalized.iter().filter(|(_, verified)| *verified).count();
+            tracing::info!(
+                "Finalized {} overdue clears ({} verified, {} disputed)",
+                finalized.len(),
+                verified,
+                finalized.len() - verified
+            );
+        }
+
+        Ok(finalized.len())
+    }
+}
@@ -5,8 +5,13 @@ pub mod config;
 pub mod db;
 pub mod error;
 pub mod handlers;
+pub mod idempotency;
+pub mod image_access;
+pub mod jobs;
+pub mod migration_policy;
 pub mod models;
 pub mod openapi;
+pub mod perf;
 pub mod rate_limit;
 pub mod services;
 pub mod templates;
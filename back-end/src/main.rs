@@ -1,31 +1,56 @@
-use back_end::{auth, config, db, handlers, openapi::ApiDoc, services};
+use back_end::{
+    auth, config, db, handlers, idempotency, image_access, jobs,
+    migration_policy::{self, StartupMode},
+    models,
+    openapi::{self, ApiDoc},
+    perf, rate_limit, services,
+};
 
 use axum::{
     extract::DefaultBodyLimit,
+    http::header,
+    response::Html,
     routing::{delete, get, patch, post, put},
     Router,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
+    // Initialize tracing; the SQL timing layer has its own filter so latency
+    // budget warnings can break down slow requests whatever RUST_LOG says
     tracing_subscriber::registry()
         .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "back_end=debug,tower_http=debug".into()),
+            tracing_subscriber::fmt::layer().with_filter(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| "back_end=debug,tower_http=debug,image_access=info".into()),
+            ),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(perf::SqlTimingLayer::filtered())
         .init();
 
+    // `back-end migrate` applies migrations and exits; it needs only the database settings
+    let mode = StartupMode::from_args(std::env::args().skip(1)).map_err(anyhow::Error::msg)?;
+    let (skip_migrations, allow_destructive) = match mode {
+        StartupMode::Migrate { allow_destructive } => {
+            run_migrations(&config::DatabaseConfig::from_env()?, allow_destructive).await?;
+            return Ok(());
+        }
+        StartupMode::Serve {
+            skip_migrations,
+            allow_destructive,
+        } => (skip_migrations, allow_destructive),
+    };
+
     // Load configuration
     let config = config::Config::from_env()?;
     tracing::info!("Configuration loaded");
@@ -34,9 +59,13 @@ async fn main() -> anyhow::Result<()> {
     let pool = db::create_pool(&config).await?;
     tracing::info!("Database pool created");
 
-    // Run migrations
-    sqlx::migrate!("./migrations").run(&pool).await?;
-    tracing::info!("Migrations completed");
+    // Run migrations, or with --skip-migrations check a pre-deploy `migrate` ran them
+    if skip_migrations {
+        migration_policy::ensure_applied(&sqlx::migrate!("./migrations"), &pool).await?;
+        tracing::info!("Migrations skipped; schema is up to date");
+    } else {
+        run_migrations(&config.database, allow_destructive).await?;
+    }
 
     // Initialize S3 service
     let s3_service = services::S3Service::new(config.s3.clone()).await?;
@@ -44,57 +73,497 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("S3 service initialized");
 
     // Initialize services
-    let jwt_service = auth::JwtService::new(config.jwt.clone());
-    let email_service = services::EmailService::new(config.email.clone())?;
-    let image_service = services::ImageService::new(config.image.clone());
-    let report_service =
-        services::ReportService::new(pool.clone(), image_service.clone(), s3_service.clone());
+    let jwt_service = auth::JwtService::new(config.jwt.clone())?;
+    let email_service = services::EmailService::new(pool.clone(), config.email.clone())?;
+    let upload_scan_service =
+        services::UploadScanService::new(pool.clone(), config.upload_scan.clone());
+    if upload_scan_service.is_enabled() {
+        tracing::info!("Uploads will be scanned for malware");
+    }
+    let image_service =
+        services::ImageService::new(config.image.clone()).with_scanner(upload_scan_service.clone());
+    let upload_service =
+        services::UploadService::new(pool.clone(), s3_service.clone(), config.image.clone());
+    let image_storage = services::ImageStorageService::new(
+        pool.clone(),
+        s3_service.clone(),
+        config.s3.spill_max_mb,
+    );
+    let geocoding_service = services::GeocodingService::new(pool.clone(), config.geocoding.clone());
+    let report_service = services::ReportService::new(
+        pool.clone(),
+        image_service.clone(),
+        image_storage.clone(),
+        upload_service.clone(),
+        geocoding_service.clone(),
+        config.location_check.clone(),
+    );
     let scoring_service = services::ScoringService::new(pool.clone(), config.scoring.clone());
     let feed_service =
-        services::FeedService::new(pool.clone(), image_service.clone(), s3_service.clone());
+        services::FeedService::new(pool.clone(), image_service.clone(), image_storage.clone());
+    let saved_search_service = services::SavedSearchService::new(pool.clone());
+    let watched_area_service = services::WatchedAreaService::new(pool.clone());
+    let home_area_service = services::HomeAreaService::new(pool.clone());
+    let cleanup_event_service =
+        services::CleanupEventService::new(pool.clone(), config.scoring.cleanup_event_bonus);
+    let notification_service = services::NotificationService::new(pool.clone());
+    let city_goal_service =
+        services::CityGoalService::new(pool.clone(), notification_service.clone());
+    let event_service = services::EventService::new(pool.clone());
+    let email_webhook_service =
+        services::EmailWebhookService::new(email_service.clone(), event_service.clone());
     let oauth_service = Arc::new(services::OAuthService::new(config.oauth.clone()).await?);
 
+    let two_factor_service = services::TwoFactorService::new(pool.clone());
+
     let auth_service = Arc::new(services::AuthService::new(
         pool.clone(),
         jwt_service.clone(),
-        email_service,
+        email_service.clone(),
+        two_factor_service.clone(),
         config.clone(),
     ));
 
+    let rate_limiters = rate_limit::RateLimiters::new(&config.rate_limit);
+    {
+        let rate_limiters = rate_limiters.clone();
+        jobs::spawn_periodic("rate_limit_prune", Duration::from_secs(60), move || {
+            rate_limiters.prune();
+            async { Ok(()) }
+        });
+    }
+
+    // Jobs that change shared data run on one instance per tick; jobs that only touch
+    // this instance's memory stay on spawn_periodic
+    let job_lock = jobs::JobLock::new(pool.clone());
+
+    // Idempotency-Key support for POST endpoints that create things
+    let idempotency_service = services::IdempotencyService::new(pool.clone());
+    {
+        let idempotency_service = idempotency_service.clone();
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "idempotency_key_purge",
+            Duration::from_secs(60 * 60),
+            move || {
+                let service = idempotency_service.clone();
+                async move { service.purge_expired().await.map(|_| ()) }
+            },
+        );
+    }
+
+    let auth_middleware_state = auth::AuthMiddlewareState {
+        jwt_service: jwt_service.clone(),
+        pool: pool.clone(),
+        rate_limiter: Some(rate_limiters.general.clone()),
+        token_scope: None,
+    };
+    // Upload routes also admit upload-only service tokens from background sync
+    let upload_auth_state = auth::AuthMiddlewareState {
+        token_scope: Some(models::TokenScope::UploadOnly),
+        ..auth_middleware_state.clone()
+    };
+
+    // Partner API keys, accepted alongside bearer tokens on read-only routes
+    let api_key_service =
+        services::ApiKeyService::new(pool.clone()).with_warning_at(config.rate_limit.warn_percent);
+    let reports_api_key_state = auth::ApiKeyMiddlewareState {
+        api_key_service: api_key_service.clone(),
+        scope: models::ApiKeyScope::ReportsRead,
+        auth: Some(auth_middleware_state.clone()),
+    };
+    let leaderboards_api_key_state = auth::ApiKeyMiddlewareState {
+        api_key_service: api_key_service.clone(),
+        scope: models::ApiKeyScope::LeaderboardsRead,
+        auth: None,
+    };
+
     // Handler states
     let user_state = Arc::new(handlers::UserHandlerState { pool: pool.clone() });
 
+    let report_admin_service = services::ReportAdminService::new(
+        pool.clone(),
+        scoring_service.clone(),
+        event_service.clone(),
+    );
+    let report_flag_service = services::ReportFlagService::new(
+        pool.clone(),
+        report_service.clone(),
+        report_admin_service.clone(),
+        config.moderation.clone(),
+    );
+
     let report_state = Arc::new(handlers::ReportHandlerState {
         report_service: report_service.clone(),
+        report_flag_service: report_flag_service.clone(),
         scoring_service: scoring_service.clone(),
+        saved_search_service: saved_search_service.clone(),
+        notification_service: notification_service.clone(),
+        captcha_service: services::CaptchaService::new(config.captcha.clone()),
+        watched_area_service: watched_area_service.clone(),
+        cleanup_event_service: cleanup_event_service.clone(),
+        city_goal_service: city_goal_service.clone(),
+        translation_service: services::TranslationService::new(
+            pool.clone(),
+            config.translation.clone(),
+        ),
+    });
+
+    let saved_search_state = Arc::new(handlers::SavedSearchHandlerState {
+        saved_search_service: saved_search_service.clone(),
+    });
+
+    let watched_area_state = Arc::new(handlers::WatchedAreaHandlerState {
+        watched_area_service: watched_area_service.clone(),
     });
 
+    let home_area_state = Arc::new(handlers::HomeAreaHandlerState { home_area_service });
+
+    let cleanup_event_state = Arc::new(handlers::CleanupEventHandlerState {
+        cleanup_event_service: cleanup_event_service.clone(),
+    });
+
+    let verification_service = services::VerificationService::new(
+        pool.clone(),
+        report_service.clone(),
+        scoring_service.clone(),
+        notification_service.clone(),
+        config.scoring.clone(),
+    );
     let verification_state = Arc::new(handlers::VerificationHandlerState {
-        pool: pool.clone(),
-        report_service: report_service.clone(),
-        scoring_service: scoring_service.clone(),
-        scoring_config: config.scoring.clone(),
+        verification_service: verification_service.clone(),
+        fraud_service: services::FraudService::new(pool.clone()),
+    });
+
+    // Background jobs
+    if config.jobs.verification_reminder_interval_mins > 0 {
+        let reminder_job = jobs::VerificationReminderJob::new(
+            pool.clone(),
+            notification_service.clone(),
+            config.scoring.min_clears_to_verify,
+            config.jobs.verification_reminders_per_day,
+        );
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "verification_reminders",
+            Duration::from_secs(config.jobs.verification_reminder_interval_mins * 60),
+            move || {
+                let job = reminder_job.clone();
+                async move { job.run().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Verification reminders scheduled every {} minutes",
+            config.jobs.verification_reminder_interval_mins
+        );
+    }
+
+    if config.jobs.email_verification_reminder_interval_mins > 0 {
+        let job = jobs::EmailVerificationReminderJob::new(
+            pool.clone(),
+            auth_service.clone(),
+            config.jobs.email_verification_reminder_after_hours,
+        );
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "email_verification_reminders",
+            Duration::from_secs(config.jobs.email_verification_reminder_interval_mins * 60),
+            move || {
+                let job = job.clone();
+                async move { job.run().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Email verification reminders scheduled every {} minutes",
+            config.jobs.email_verification_reminder_interval_mins
+        );
+    }
+
+    if config.jobs.email_outbox_interval_mins > 0 {
+        let outbox = services::EmailOutboxService::new(pool.clone(), email_service.clone());
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "email_outbox",
+            Duration::from_secs(config.jobs.email_outbox_interval_mins * 60),
+            move || {
+                let outbox = outbox.clone();
+                async move { outbox.send_due().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Email outbox retries scheduled every {} minutes",
+            config.jobs.email_outbox_interval_mins
+        );
+    }
+
+    if config.jobs.geocode_retry_interval_mins > 0 {
+        let queue = services::GeocodeQueueService::new(pool.clone(), geocoding_service);
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "geocode_retry",
+            Duration::from_secs(config.jobs.geocode_retry_interval_mins * 60),
+            move || {
+                let queue = queue.clone();
+                async move { queue.resolve_due().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Geocode retries scheduled every {} minutes",
+            config.jobs.geocode_retry_interval_mins
+        );
+    }
+
+    if config.jobs.pending_upload_interval_mins > 0 {
+        let image_storage = image_storage.clone();
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "pending_image_upload",
+            Duration::from_secs(config.jobs.pending_upload_interval_mins * 60),
+            move || {
+                let image_storage = image_storage.clone();
+                async move { image_storage.upload_pending().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Pending image uploads scheduled every {} minutes",
+            config.jobs.pending_upload_interval_mins
+        );
+    }
+
+    if config.jobs.claim_expiry_interval_mins > 0 {
+        let job = jobs::ClaimExpiryJob::new(
+            report_service.clone(),
+            notification_service.clone(),
+            config.jobs.claim_expiry_hours,
+        );
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "claim_expiry",
+            Duration::from_secs(config.jobs.claim_expiry_interval_mins * 60),
+            move || {
+                let job = job.clone();
+                async move { job.run().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Claim expiry scheduled every {} minutes",
+            config.jobs.claim_expiry_interval_mins
+        );
+    }
+
+    if config.jobs.report_expiry_interval_mins > 0 {
+        let job = jobs::ReportExpiryJob::new(
+            report_service.clone(),
+            image_storage.clone(),
+            config.jobs.report_expiry_months,
+            config.s3.archive_storage_class.clone(),
+        );
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "report_expiry",
+            Duration::from_secs(config.jobs.report_expiry_interval_mins * 60),
+            move || {
+                let job = job.clone();
+                async move { job.run().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Report expiry scheduled every {} minutes",
+            config.jobs.report_expiry_interval_mins
+        );
+    }
+
+    if config.jobs.verification_window_interval_mins > 0 {
+        let job = jobs::VerificationWindowJob::new(
+            verification_service.clone(),
+            config.scoring.verification_window_days,
+        );
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "verification_window",
+            Duration::from_secs(config.jobs.verification_window_interval_mins * 60),
+            move || {
+                let job = job.clone();
+                async move { job.run().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Finalization of overdue clears scheduled every {} minutes",
+            config.jobs.verification_window_interval_mins
+        );
+    }
+
+    if config.jobs.clear_reopen_interval_mins > 0 {
+        let job = jobs::ClearReopenJob::new(
+            verification_service.clone(),
+            notification_service.clone(),
+            config.jobs.clear_reopen_after_hours,
+        );
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "clear_reopen",
+            Duration::from_secs(config.jobs.clear_reopen_interval_mins * 60),
+            move || {
+                let job = job.clone();
+                async move { job.run().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Reopening of disputed clears scheduled every {} minutes",
+            config.jobs.clear_reopen_interval_mins
+        );
+    }
+
+    let stats_service = services::StatsService::new(pool.clone());
+    if config.jobs.stats_summary_interval_mins > 0 {
+        let stats_service = stats_service.clone();
+        jobs::spawn_periodic(
+            "stats_summary",
+            Duration::from_secs(config.jobs.stats_summary_interval_mins * 60),
+            move || {
+                let service = stats_service.clone();
+                async move { service.refresh().await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Stats summary refresh scheduled every {} minutes",
+            config.jobs.stats_summary_interval_mins
+        );
+    }
+
+    // Feed engagement is only collected while the trending feed is switched on
+    let feed_engagement = config.feed_trending.as_ref().map(|trending| {
+        let recorder = services::EngagementRecorder::new(Arc::new(
+            services::PgEngagementSink::new(pool.clone()),
+        ));
+        {
+            let recorder = recorder.clone();
+            jobs::spawn_periodic(
+                "feed_engagement_flush",
+                Duration::from_secs(trending.flush_interval_secs),
+                move || {
+                    let recorder = recorder.clone();
+                    async move { recorder.flush().await }
+                },
+            );
+        }
+
+        if trending.score_interval_mins > 0 {
+            let job = jobs::FeedEngagementScoreJob::new(
+                services::FeedEngagementService::new(pool.clone()),
+                trending.half_life_hours,
+            );
+            jobs::spawn_exclusive(
+                job_lock.clone(),
+                "feed_engagement_scores",
+                Duration::from_secs(trending.score_interval_mins * 60),
+                move || {
+                    let job = job.clone();
+                    async move { job.run().await.map(|_| ()) }
+                },
+            );
+            tracing::info!(
+                "Trending feed scores recomputed every {} minutes",
+                trending.score_interval_mins
+            );
+        }
+
+        recorder
     });
 
-    let leaderboard_state = Arc::new(handlers::LeaderboardHandlerState { pool: pool.clone() });
+    let consistency_check_job = jobs::ConsistencyCheckJob::new(pool.clone());
+    if config.jobs.consistency_check_interval_mins > 0 {
+        let job = consistency_check_job.clone();
+        jobs::spawn_exclusive(
+            job_lock.clone(),
+            "consistency_check",
+            Duration::from_secs(config.jobs.consistency_check_interval_mins * 60),
+            move || {
+                let job = job.clone();
+                async move { job.run(None, false).await.map(|_| ()) }
+            },
+        );
+        tracing::info!(
+            "Consistency check scheduled every {} minutes",
+            config.jobs.consistency_check_interval_mins
+        );
+    }
+
+    let export_state = Arc::new(handlers::ExportHandlerState {
+        data_export_service: services::DataExportService::new(
+            pool.clone(),
+            s3_service.clone(),
+            notification_service.clone(),
+        ),
+    });
+
+    let notification_state = Arc::new(handlers::NotificationHandlerState {
+        notification_service,
+    });
+
+    let two_factor_state = Arc::new(handlers::TwoFactorHandlerState { two_factor_service });
+
+    let upload_state = Arc::new(handlers::UploadHandlerState { upload_service });
+
+    let leaderboard_state = Arc::new(handlers::LeaderboardHandlerState {
+        pool: pool.clone(),
+        timezone: config.scoring.leaderboard_timezone.clone(),
+    });
 
     let oauth_state = Arc::new(handlers::OAuthHandlerState {
         oauth_service: oauth_service.clone(),
         auth_service: auth_service.clone(),
         frontend_url: config.email.frontend_url.clone(),
         redirect_url: config.oauth.google_redirect_uri.clone(),
-        session_store: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+        state_store: Arc::new(services::PgOAuthStateStore::new(pool.clone())),
+    });
+
+    // Per-route latency budgets, reported at /api/admin/perf
+    let latency_monitor = perf::LatencyMonitor::new(config.latency.clone());
+
+    let admin_state = Arc::new(handlers::AdminHandlerState {
+        pool: pool.clone(),
+        email_service,
+        event_service: event_service.clone(),
+        email_webhook_service: email_webhook_service.clone(),
+        report_admin_service,
+        report_service: report_service.clone(),
+        report_flag_service,
+        report_import_service: services::ReportImportService::new(
+            pool.clone(),
+            report_service.clone(),
+            image_service.clone(),
+            config.image.max_size_mb,
+        ),
+        api_key_service,
+        impersonation_service: services::ImpersonationService::new(pool.clone(), jwt_service),
+        consistency_check_job,
+        feed_service: feed_service.clone(),
+        latency_monitor: latency_monitor.clone(),
+        upload_scan_service,
+        city_goal_service: city_goal_service.clone(),
+        verification_service,
     });
 
-    let admin_state = Arc::new(handlers::AdminHandlerState { pool: pool.clone() });
+    let email_webhook_state = Arc::new(handlers::EmailWebhookState {
+        email_webhook_service,
+        webhook_secret: config.email.webhook_secret.clone(),
+    });
 
+    let image_access = Arc::new(image_access::ImageAccess::new(
+        &config.image_access,
+        config.rate_limit.trust_proxy_headers,
+    ));
     let image_state = Arc::new(handlers::ImageHandlerState {
         report_service: report_service.clone(),
         s3_service: s3_service.clone(),
+        image_storage: image_storage.clone(),
+        access: image_access.clone(),
     });
 
     let feed_state = Arc::new(handlers::FeedHandlerState {
         feed_service: feed_service.clone(),
+        engagement: feed_engagement,
     });
 
     tracing::info!("Services initialized");
@@ -103,54 +572,261 @@ async fn main() -> anyhow::Result<()> {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([
+            header::RETRY_AFTER,
+            rate_limit::X_RATELIMIT_LIMIT,
+            rate_limit::X_RATELIMIT_REMAINING,
+            rate_limit::X_RATELIMIT_RESET,
+            rate_limit::X_RATELIMIT_WARNING,
+        ]);
 
-    // Build routers - Rate limiting disabled in development
+    // Build routers
     let auth_routes = Router::new()
         .route("/api/auth/register", post(handlers::register))
         .route("/api/auth/login", post(handlers::login))
         .route("/api/auth/verify-email", post(handlers::verify_email))
+        .route(
+            "/api/auth/link-account",
+            post(handlers::confirm_account_link),
+        )
+        .route("/api/auth/2fa/login", post(handlers::two_factor_login))
+        .route(
+            "/api/auth/confirm-email-change",
+            post(handlers::confirm_email_change),
+        )
+        .route("/api/auth/set-password", post(handlers::set_password))
+        .route("/api/auth/magic-link", post(handlers::request_magic_link))
+        .route(
+            "/api/auth/magic-link/verify",
+            post(handlers::verify_magic_link),
+        )
         .route("/api/auth/refresh", post(handlers::refresh_token))
         .route("/api/auth/logout", post(handlers::logout))
-        .with_state(auth_service.clone());
-    //.layer(auth_rate_limiter.clone()); // Disabled - causes "Unable To Extract Key!" error
+        .with_state(auth_service.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.auth.clone(),
+            rate_limit::limit_by_ip,
+        ));
+
+    let auth_session_routes = Router::new()
+        .route("/api/auth/logout-all", post(handlers::logout_all))
+        .route(
+            "/api/auth/service-tokens",
+            post(handlers::create_service_token),
+        )
+        .route("/api/users/me/sessions", get(handlers::list_sessions))
+        .route("/api/users/me/logins", get(handlers::list_login_history))
+        .route(
+            "/api/users/me/sessions/:id",
+            delete(handlers::revoke_session),
+        )
+        .route(
+            "/api/users/me/oauth/:provider",
+            delete(handlers::unlink_oauth_provider),
+        )
+        .route("/api/users/me/password", put(handlers::change_password))
+        .route(
+            "/api/users/me/password/setup",
+            post(handlers::request_password_setup),
+        )
+        .route(
+            "/api/users/me/email-change",
+            post(handlers::request_email_change),
+        )
+        .with_state(auth_service.clone())
+        .route_layer(axum::middleware::from_fn(
+            auth::middleware::reject_impersonation,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
 
     let auth_email_routes = Router::new()
         .route(
             "/api/auth/resend-verification",
             post(handlers::resend_verification),
         )
-        .with_state(auth_service.clone());
-    //.layer(email_verification_limiter.clone()); // Disabled
+        .route(
+            "/api/auth/verification-reminders/unsubscribe",
+            post(handlers::unsubscribe_verification_reminders),
+        )
+        .with_state(auth_service.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.email_verification.clone(),
+            rate_limit::limit_by_ip,
+        ));
 
     let auth_password_routes = Router::new()
         .route("/api/auth/forgot-password", post(handlers::forgot_password))
         .route("/api/auth/reset-password", post(handlers::reset_password))
-        .with_state(auth_service.clone());
-    //.layer(password_reset_limiter.clone()); // Disabled
+        .with_state(auth_service.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.password_reset.clone(),
+            rate_limit::limit_by_ip,
+        ));
 
     let oauth_routes = Router::new()
         .route("/api/auth/google", get(handlers::google_login))
         .route("/api/auth/google/callback", get(handlers::google_callback))
-        .with_state(oauth_state);
-    //.layer(auth_rate_limiter.clone()); // Disabled
+        .with_state(oauth_state.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.auth.clone(),
+            rate_limit::limit_by_ip,
+        ));
+
+    let oauth_link_routes = Router::new()
+        .route(
+            "/api/users/me/oauth/link",
+            post(handlers::link_oauth_provider),
+        )
+        .with_state(oauth_state)
+        .route_layer(axum::middleware::from_fn(
+            auth::middleware::reject_impersonation,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
 
     // User routes (authenticated)
     let user_routes = Router::new()
         .route("/api/users/me", get(handlers::get_current_user))
-        .route("/api/users/me", patch(handlers::update_current_user))
+        .route(
+            "/api/users/me",
+            patch(handlers::update_current_user).route_layer(axum::middleware::from_fn(
+                auth::middleware::reject_impersonation,
+            )),
+        )
         .route("/api/users/me/score", get(handlers::get_current_user_score))
+        .route("/api/users/:id", get(handlers::get_user_profile))
         .with_state(user_state)
-        //.layer(general_rate_limiter.clone()) // Disabled - was causing 500 errors
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Saved search routes (authenticated)
+    let saved_search_routes = Router::new()
+        .route("/api/users/me/searches", get(handlers::list_saved_searches))
+        .route(
+            "/api/users/me/searches",
+            post(handlers::create_saved_search),
+        )
+        .route(
+            "/api/users/me/searches/:id",
+            get(handlers::get_saved_search),
+        )
+        .route(
+            "/api/users/me/searches/:id",
+            patch(handlers::update_saved_search),
+        )
+        .route(
+            "/api/users/me/searches/:id",
+            delete(handlers::delete_saved_search),
+        )
+        .with_state(saved_search_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Watched area routes (authenticated)
+    let watched_area_routes = Router::new()
+        .route(
+            "/api/users/me/watched-areas",
+            get(handlers::list_watched_areas),
+        )
+        .route(
+            "/api/users/me/watched-areas",
+            post(handlers::create_watched_area),
+        )
+        .route(
+            "/api/users/me/watched-areas/:id",
+            delete(handlers::delete_watched_area),
+        )
+        .with_state(watched_area_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Home area dashboard routes (authenticated)
+    let home_area_routes = Router::new()
+        .route(
+            "/api/users/me/area",
+            get(handlers::get_home_area)
+                .put(handlers::set_home_area)
+                .delete(handlers::delete_home_area),
+        )
+        .with_state(home_area_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Cleanup event routes (authenticated)
+    let cleanup_event_routes = Router::new()
+        .route(
+            "/api/cleanup-events",
+            get(handlers::list_cleanup_events).post(handlers::create_cleanup_event),
+        )
+        .route("/api/cleanup-events/:id", get(handlers::get_cleanup_event))
+        .route(
+            "/api/cleanup-events/:id/rsvp",
+            post(handlers::rsvp_cleanup_event).delete(handlers::cancel_cleanup_event_rsvp),
+        )
+        .with_state(cleanup_event_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
             auth::middleware::require_auth,
         ));
 
+    // Nearby reports (authenticated, or a partner API key with reports:read)
+    let nearby_report_routes = Router::new()
+        .route("/api/reports/nearby", get(handlers::get_nearby_reports))
+        .route(
+            "/api/reports/in-bounds",
+            get(handlers::get_reports_in_bounds),
+        )
+        .route("/api/reports/clusters", get(handlers::get_report_clusters))
+        .with_state(report_state.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            reports_api_key_state,
+            auth::middleware::accept_api_key,
+        ));
+
+    // Guest reports (CAPTCHA-checked in the handler, limited per IP)
+    let anonymous_report_routes = Router::new()
+        .route(
+            "/api/reports/anonymous",
+            post(handlers::create_anonymous_report),
+        )
+        .with_state(report_state.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.reports.clone(),
+            rate_limit::limit_by_ip,
+        ));
+
     // Report routes (authenticated)
     let report_routes = Router::new()
-        .route("/api/reports", post(handlers::create_report))
-        .route("/api/reports/nearby", get(handlers::get_nearby_reports))
+        .route(
+            "/api/reports",
+            post(handlers::create_report)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.reports.clone(),
+                    rate_limit::limit_by_user,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    idempotency_service.clone(),
+                    idempotency::idempotent,
+                )),
+        )
         .route(
             "/api/reports/verification-queue",
             get(handlers::get_verification_queue),
@@ -160,29 +836,125 @@ async fn main() -> anyhow::Result<()> {
             "/api/reports/my-clears",
             get(handlers::get_my_cleared_reports),
         )
-        .route("/api/reports/:id", get(handlers::get_report))
+        .route(
+            "/api/reports/:id",
+            get(handlers::get_report)
+                .patch(handlers::update_report)
+                .delete(handlers::cancel_report),
+        )
+        .route(
+            "/api/reports/claim-batch",
+            post(handlers::claim_report_batch),
+        )
         .route("/api/reports/:id/claim", post(handlers::claim_report))
-        .route("/api/reports/:id/clear", post(handlers::clear_report))
+        .route("/api/reports/:id/unclaim", post(handlers::unclaim_report))
+        .route("/api/reports/:id/flag", post(handlers::flag_report))
+        .route(
+            "/api/reports/:id/timeline",
+            get(handlers::get_report_timeline),
+        )
+        .route(
+            "/api/reports/:id/history",
+            get(handlers::get_report_history),
+        )
+        .route(
+            "/api/reports/anonymous/claim",
+            post(handlers::claim_anonymous_report),
+        )
+        .route("/api/cleanup-runs/:id", get(handlers::get_cleanup_run))
+        .route(
+            "/api/reports/:id/clear",
+            post(handlers::clear_report)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    idempotency_service.clone(),
+                    idempotency::idempotent,
+                )),
+        )
+        .route(
+            "/api/reports/:id/after-photo",
+            put(handlers::replace_after_photo).route_layer(axum::middleware::from_fn_with_state(
+                rate_limiters.uploads_in_flight.clone(),
+                rate_limit::limit_in_flight,
+            )),
+        )
+        .route(
+            "/api/reports/:id/photos",
+            post(handlers::add_report_photo)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .get(handlers::get_report_photos),
+        )
         .with_state(report_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Personal data export routes (authenticated)
+    let export_routes = Router::new()
+        .route(
+            "/api/users/me/export",
+            post(handlers::request_data_export).get(handlers::get_data_export),
+        )
+        .route(
+            "/api/users/me/export/:id/download",
+            get(handlers::download_data_export),
+        )
+        .with_state(export_state)
+        .route_layer(axum::middleware::from_fn(
+            auth::middleware::reject_impersonation,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Chunked upload routes (authenticated)
+    let upload_routes = Router::new()
+        .route("/api/uploads", post(handlers::create_upload))
+        .route("/api/uploads/:id", get(handlers::get_upload))
+        .route("/api/uploads/:id", delete(handlers::cancel_upload))
+        .route(
+            "/api/uploads/:id/chunks",
+            post(handlers::append_upload_chunk),
+        )
+        .route("/api/uploads/:id/complete", post(handlers::complete_upload))
+        .with_state(upload_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            upload_auth_state,
             auth::middleware::require_auth,
         ));
 
     // Verification routes (authenticated)
     let verification_routes = Router::new()
-        .route("/api/reports/:id/verify", post(handlers::verify_report))
+        .route(
+            "/api/reports/:id/verify",
+            post(handlers::verify_report).route_layer(axum::middleware::from_fn_with_state(
+                rate_limiters.verifications.clone(),
+                rate_limit::limit_by_user,
+            )),
+        )
+        .route(
+            "/api/reports/:id/verify/reserve",
+            post(handlers::reserve_verification).delete(handlers::release_verification),
+        )
         .route(
             "/api/reports/:id/verifications",
             get(handlers::get_report_verifications),
         )
         .with_state(verification_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
             auth::middleware::require_auth,
         ));
 
-    // Leaderboard routes (authenticated)
+    // Leaderboard routes (public; partner API keys need leaderboards:read)
     let leaderboard_routes = Router::new()
         .route("/api/leaderboards", get(handlers::get_global_leaderboard))
         .route(
@@ -193,24 +965,236 @@ async fn main() -> anyhow::Result<()> {
             "/api/leaderboards/country/:country",
             get(handlers::get_country_leaderboard),
         )
-        .with_state(leaderboard_state);
+        .with_state(leaderboard_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            leaderboards_api_key_state,
+            auth::middleware::accept_api_key,
+        ));
+
+    // Landing page counters (public, served from memory)
+    let stats_routes = Router::new()
+        .route("/api/stats/summary", get(handlers::get_stats_summary))
+        .route("/api/stats/hotspots", get(handlers::get_hotspots))
+        .route(
+            "/api/locations/history",
+            get(handlers::get_location_history),
+        )
+        .with_state(Arc::new(handlers::StatsHandlerState { stats_service }));
+
+    // Scoring rules for clients' help screens (public)
+    let scoring_routes = Router::new()
+        .route("/api/scoring/rules", get(handlers::get_scoring_rules))
+        .with_state(Arc::new(handlers::ScoringHandlerState {
+            scoring_service: scoring_service.clone(),
+        }));
+
+    // Optional subsystems this deployment has enabled (public)
+    let capabilities_routes = Router::new()
+        .route("/api/capabilities", get(handlers::get_capabilities))
+        .with_state(Arc::new(handlers::CapabilitiesHandlerState {
+            capabilities: models::Capabilities::from_config(&config),
+        }));
+
+    // Weekly city goal progress (public)
+    let city_goal_routes = Router::new()
+        .route("/api/city-goals", get(handlers::list_city_goals))
+        .route("/api/city-goals/:id", get(handlers::get_city_goal))
+        .with_state(Arc::new(handlers::CityGoalHandlerState {
+            city_goal_service,
+        }));
+
+    // Anonymized cleared reports for councils and researchers (public)
+    let open_data_routes = Router::new()
+        .route(
+            "/api/open-data/reports",
+            get(handlers::get_open_data_reports),
+        )
+        .with_state(Arc::new(handlers::OpenDataHandlerState {
+            open_data_service: services::OpenDataService::new(pool.clone()),
+        }));
 
-    // Admin routes (authenticated + admin role required)
+    // Admin and moderation routes (authenticated; each group needs its own permission)
+    let require = |permission| {
+        axum::middleware::from_fn_with_state(permission, auth::middleware::require_permission)
+    };
     let admin_routes = Router::new()
-        .route("/api/admin/users", get(handlers::list_users))
-        .route("/api/admin/users/:id", get(handlers::get_user_by_id))
-        .route("/api/admin/users/:id/ban", put(handlers::toggle_user_ban))
-        .route("/api/admin/reports", get(handlers::list_all_reports))
-        .route("/api/admin/reports/:id", delete(handlers::delete_report))
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/feed/:id",
+                    delete(handlers::moderate_delete_feed_post),
+                )
+                .route_layer(require(models::Permission::DeleteFeedPosts)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/account-flags",
+                    get(handlers::list_account_flags),
+                )
+                .route(
+                    "/api/admin/account-flags/:id/resolve",
+                    post(handlers::resolve_account_flag),
+                )
+                .route(
+                    "/api/admin/report-flags",
+                    get(handlers::list_flagged_reports),
+                )
+                .route(
+                    "/api/admin/reports/:id/flags",
+                    get(handlers::list_report_flags),
+                )
+                .route(
+                    "/api/admin/reports/:id/flags/review",
+                    post(handlers::review_report_flags),
+                )
+                .route_layer(require(models::Permission::ResolveFlags)),
+        )
+        .merge(
+            Router::new()
+                .route("/api/admin/users", get(handlers::list_users))
+                .route("/api/admin/users/:id", get(handlers::get_user_by_id))
+                .route(
+                    "/api/admin/users/:id/impersonate",
+                    post(handlers::impersonate_user),
+                )
+                .route(
+                    "/api/admin/impersonations",
+                    get(handlers::list_impersonations),
+                )
+                .route(
+                    "/api/admin/users/:id/email-suppression",
+                    delete(handlers::clear_email_suppression),
+                )
+                .route_layer(require(models::Permission::ManageUsers)),
+        )
+        .merge(
+            Router::new()
+                .route("/api/admin/users/:id/ban", put(handlers::toggle_user_ban))
+                .route_layer(require(models::Permission::BanUsers)),
+        )
+        .merge(
+            Router::new()
+                .route("/api/admin/users/:id/role", put(handlers::set_user_role))
+                .route_layer(require(models::Permission::ManageRoles)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/users/:id/reports",
+                    delete(handlers::purge_user_reports),
+                )
+                .route("/api/admin/reports", get(handlers::list_all_reports))
+                .route("/api/admin/reports/import", post(handlers::import_reports))
+                .route(
+                    "/api/admin/reports/import/:id",
+                    get(handlers::get_report_import),
+                )
+                .route("/api/admin/reports/:id", delete(handlers::delete_report))
+                .route(
+                    "/api/admin/reports/:id/reassign",
+                    post(handlers::reassign_report),
+                )
+                .route(
+                    "/api/admin/reports/:id/status",
+                    post(handlers::override_report_status),
+                )
+                .route(
+                    "/api/admin/reports/:id/verification-override",
+                    post(handlers::override_report_verification),
+                )
+                .route(
+                    "/api/admin/reports/:id/reactivate",
+                    post(handlers::reactivate_report),
+                )
+                .route(
+                    "/api/admin/reports/:id/edits",
+                    get(handlers::list_report_edits),
+                )
+                .route(
+                    "/api/admin/reports/:id/history",
+                    get(handlers::get_admin_report_history),
+                )
+                .route("/api/admin/stats/geo", get(handlers::get_geo_stats))
+                .route_layer(require(models::Permission::ManageReports)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/emails/preview/:template",
+                    get(handlers::preview_email),
+                )
+                .route("/api/admin/emails/test", post(handlers::send_test_email))
+                .route("/api/admin/events", get(handlers::list_events))
+                .route("/api/admin/events/:id", get(handlers::get_event))
+                .route("/api/admin/events/:id/retry", post(handlers::retry_event))
+                .route(
+                    "/api/admin/api-keys",
+                    get(handlers::list_api_keys).post(handlers::create_api_key),
+                )
+                .route(
+                    "/api/admin/api-keys/:id",
+                    patch(handlers::update_api_key).delete(handlers::revoke_api_key),
+                )
+                .route(
+                    "/api/admin/consistency-checks",
+                    get(handlers::list_consistency_checks).post(handlers::run_consistency_check),
+                )
+                .route("/api/admin/perf", get(handlers::get_perf_report))
+                .route(
+                    "/api/admin/city-goals",
+                    get(handlers::admin_list_city_goals).post(handlers::create_city_goal),
+                )
+                .route(
+                    "/api/admin/city-goals/:id",
+                    put(handlers::update_city_goal).delete(handlers::delete_city_goal),
+                )
+                .route(
+                    "/api/admin/quarantine",
+                    get(handlers::get_upload_quarantine),
+                )
+                .route(
+                    "/api/admin/quarantine/:id",
+                    delete(handlers::delete_quarantined_upload),
+                )
+                .route(
+                    "/api/admin/quarantine/:id/data",
+                    get(handlers::download_quarantined_upload),
+                )
+                .route_layer(require(models::Permission::ManageSystem)),
+        )
         .with_state(admin_state)
-        //.layer(general_rate_limiter.clone()) // Disabled
-        .route_layer(axum::middleware::from_fn(auth::middleware::require_admin))
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
             auth::middleware::require_auth,
         ));
 
-    // Image routes (public - no authentication required)
+    // Two-factor management routes (authenticated)
+    let two_factor_routes = Router::new()
+        .route("/api/auth/2fa", get(handlers::get_two_factor_status))
+        .route("/api/auth/2fa/setup", post(handlers::setup_two_factor))
+        .route("/api/auth/2fa/verify", post(handlers::verify_two_factor))
+        .route("/api/auth/2fa/disable", post(handlers::disable_two_factor))
+        .with_state(two_factor_state)
+        .route_layer(axum::middleware::from_fn(
+            auth::middleware::reject_impersonation,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Notification routes (authenticated)
+    let notification_routes = Router::new()
+        .route("/api/notifications/poll", get(handlers::poll_notifications))
+        .with_state(notification_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Image routes (public - rate limited per IP, with optional signed URLs and
+    // hotlink protection; the guard runs first so every request is logged)
     let image_routes = Router::new()
         .route(
             "/api/images/reports/:id/before",
@@ -220,7 +1204,39 @@ async fn main() -> anyhow::Result<()> {
             "/api/images/reports/:id/after",
             get(handlers::get_report_after_photo),
         )
-        .with_state(image_state);
+        .route(
+            "/api/images/reports/:id/:kind/:position",
+            get(handlers::get_report_photo_at),
+        )
+        .with_state(image_state.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.images.clone(),
+            rate_limit::limit_by_ip,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            image_access,
+            image_access::guard_images,
+        ));
+
+    // Image link routes (authenticated)
+    let image_link_routes = Router::new()
+        .route(
+            "/api/images/reports/:id/urls",
+            get(handlers::get_report_photo_urls),
+        )
+        .with_state(image_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Provider webhook routes (public - authenticated by shared secret)
+    let webhook_routes = Router::new()
+        .route(
+            "/api/webhooks/email/:provider",
+            post(handlers::receive_email_webhook),
+        )
+        .with_state(email_webhook_state);
 
     // Test helper routes (only enabled in test/dev environments)
 
@@ -233,7 +1249,18 @@ async fn main() -> anyhow::Result<()> {
 
     // Feed routes (authenticated write)
     let feed_routes = Router::new()
-        .route("/api/feed", post(handlers::create_post))
+        .route(
+            "/api/feed",
+            post(handlers::create_post)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    idempotency_service,
+                    idempotency::idempotent,
+                )),
+        )
         .route("/api/feed/:id", patch(handlers::update_post))
         .route("/api/feed/:id", delete(handlers::delete_post))
         .route(
@@ -250,41 +1277,105 @@ async fn main() -> anyhow::Result<()> {
         )
         .route("/api/feed/:post_id/like", post(handlers::like_post))
         .route("/api/feed/:post_id/like", delete(handlers::unlike_post))
+        .route("/api/feed/:post_id/dwell", post(handlers::record_dwell))
         .with_state(feed_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
             auth::middleware::require_auth,
         ));
 
+    // With test helpers on, /swagger-auth seeds Swagger UI with a test user's token
+    let mut spec = openapi::to_json(&ApiDoc::openapi());
+    if config.test_helpers.is_some() {
+        openapi::add_swagger_auth_link(&mut spec, "/swagger-auth");
+    }
+
     // Build main router
-    let app = Router::new()
+    let mut app = Router::new()
         // Health check
         .route("/", get(|| async { "LittyPicky API v0.1.0" }))
         .route("/api/health", get(health_check))
-        // OpenAPI/Swagger documentation
-        .merge(SwaggerUi::new("/swagger-ui").url("/api/openapi.json", ApiDoc::openapi()))
+        // OpenAPI documentation: Swagger UI and Redoc over the same spec
+        .merge(
+            SwaggerUi::new("/swagger-ui")
+                .external_url_unchecked("/api/openapi.json", spec)
+                .config(
+                    utoipa_swagger_ui::Config::new(["/api/openapi.json"])
+                        .display_operation_id(true)
+                        .persist_authorization(config.test_helpers.is_some()),
+                ),
+        )
+        .route("/redoc", get(redoc))
         // Merge route groups
         .merge(auth_routes)
+        .merge(auth_session_routes)
+        .merge(two_factor_routes)
         .merge(auth_email_routes)
         .merge(auth_password_routes)
         .merge(oauth_routes)
+        .merge(oauth_link_routes)
         .merge(user_routes)
+        .merge(saved_search_routes)
+        .merge(watched_area_routes)
+        .merge(home_area_routes)
+        .merge(nearby_report_routes)
+        .merge(anonymous_report_routes)
         .merge(report_routes)
+        .merge(upload_routes)
+        .merge(export_routes)
         .merge(verification_routes)
         .merge(leaderboard_routes)
+        .merge(stats_routes)
+        .merge(scoring_routes)
+        .merge(capabilities_routes)
+        .merge(city_goal_routes)
+        .merge(notification_routes)
         .merge(admin_routes)
         .merge(image_routes)
-        .merge(feed_public_routes)
-        .merge(feed_routes);
+        .merge(image_link_routes)
+        .merge(webhook_routes);
+
+    // Optional modules stay unmounted when switched off; /api/capabilities says which
+    if config.features.cleanup_events {
+        app = app.merge(cleanup_event_routes);
+    }
+    if config.features.open_data {
+        app = app.merge(open_data_routes);
+    }
+    if config.features.feed {
+        app = app.merge(feed_public_routes).merge(feed_routes);
+    }
 
     let mut app = app
         // Global layers
+        .layer(axum::middleware::from_fn_with_state(
+            latency_monitor,
+            perf::track_latency,
+        ))
         .layer(TraceLayer::new_for_http())
         .layer(DefaultBodyLimit::disable()) // Disable default 10MB limit - we handle this in the image service
         .layer(cors);
     // Conditionally add test helper routes
-    if config.enable_test_helpers {
+    if let Some(test_helpers) = &config.test_helpers {
         tracing::warn!("⚠️  TEST HELPER ENDPOINTS ARE ENABLED - DO NOT USE IN PRODUCTION!");
+        tracing::warn!(
+            "⚠️  /api/test/* accepts callers from {} presenting TEST_HELPERS_SECRET",
+            test_helpers
+                .allowed_networks
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if test_helpers
+            .allowed_networks
+            .iter()
+            .any(config::IpNetwork::matches_everything)
+        {
+            tracing::error!(
+                "⚠️  TEST_HELPERS_ALLOWED_IPS admits every address; only the secret protects the test helpers"
+            );
+        }
 
         let test_helper_state = Arc::new(handlers::TestHelperState {
             pool: pool.clone(),
@@ -298,9 +1389,26 @@ async fn main() -> anyhow::Result<()> {
                 post(handlers::verify_email_for_testing),
             )
             .route("/api/test/cleanup", delete(handlers::cleanup_test_data))
-            .with_state(test_helper_state);
+            .route("/api/test/users", post(handlers::create_test_users))
+            .route(
+                "/api/test/users/:email/clears",
+                post(handlers::fast_forward_clears),
+            )
+            .route("/api/test/reports", post(handlers::create_test_report))
+            .route(
+                "/api/test/swagger-session",
+                post(handlers::create_swagger_session),
+            )
+            .with_state(test_helper_state)
+            .route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(test_helpers.clone()),
+                handlers::require_test_helper_access,
+            ));
 
-        app = app.merge(test_helper_routes);
+        // The page itself is static; the session request it makes carries the secret
+        app = app
+            .merge(test_helper_routes)
+            .route("/swagger-auth", get(swagger_auth));
     }
 
     // Build main router
@@ -314,40 +1422,142 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("    POST /api/auth/register");
     tracing::info!("    POST /api/auth/login");
     tracing::info!("    POST /api/auth/verify-email");
+    tracing::info!("    POST /api/auth/link-account");
+    tracing::info!("    POST /api/auth/confirm-email-change");
+    tracing::info!("    POST /api/auth/set-password");
+    tracing::info!("    POST /api/auth/magic-link");
+    tracing::info!("    POST /api/auth/magic-link/verify");
     tracing::info!("    POST /api/auth/resend-verification");
+    tracing::info!("    POST /api/auth/verification-reminders/unsubscribe");
     tracing::info!("    POST /api/auth/forgot-password");
     tracing::info!("    POST /api/auth/reset-password");
     tracing::info!("    POST /api/auth/refresh");
     tracing::info!("    POST /api/auth/logout");
+    tracing::info!("    POST /api/auth/logout-all (authenticated)");
+    tracing::info!("    POST /api/auth/service-tokens (authenticated)");
+    tracing::info!("    GET  /api/users/me/sessions (authenticated)");
+    tracing::info!("    DELETE /api/users/me/sessions/:id (authenticated)");
+    tracing::info!("    GET  /api/users/me/logins (authenticated)");
+    tracing::info!("    POST /api/users/me/oauth/link (authenticated)");
+    tracing::info!("    DELETE /api/users/me/oauth/:provider (authenticated)");
+    tracing::info!("    PUT  /api/users/me/password (authenticated)");
+    tracing::info!("    POST /api/users/me/password/setup (authenticated)");
+    tracing::info!("    POST /api/users/me/email-change (authenticated)");
+    tracing::info!("    POST /api/auth/2fa/login");
+    tracing::info!("  Two-factor (authenticated):");
+    tracing::info!("    GET  /api/auth/2fa");
+    tracing::info!("    POST /api/auth/2fa/setup");
+    tracing::info!("    POST /api/auth/2fa/verify");
+    tracing::info!("    POST /api/auth/2fa/disable");
     tracing::info!("  User (authenticated):");
     tracing::info!("    GET  /api/users/me");
+    tracing::info!("    GET  /api/users/:id");
+    tracing::info!("    GET  /api/users/me/searches");
+    tracing::info!("    POST /api/users/me/searches");
+    tracing::info!("    GET|PATCH|DELETE /api/users/me/searches/:id");
+    tracing::info!("    GET|POST /api/users/me/watched-areas");
+    tracing::info!("    DELETE /api/users/me/watched-areas/:id");
+    tracing::info!("    GET|PUT|DELETE /api/users/me/area");
+    tracing::info!("    POST|GET /api/users/me/export");
+    tracing::info!("    GET  /api/users/me/export/:id/download");
     tracing::info!("  Reports (authenticated):");
     tracing::info!("    POST /api/reports");
-    tracing::info!("    GET  /api/reports/nearby?latitude=X&longitude=Y&radius_km=Z");
+    tracing::info!("    POST /api/reports/anonymous (no auth, CAPTCHA)");
+    tracing::info!("    POST /api/reports/anonymous/claim");
+    tracing::info!("    GET  /api/reports/nearby?latitude=X&longitude=Y&radius_km=Z&search_id=S (or X-Api-Key)");
     tracing::info!("    GET  /api/reports/my-reports");
     tracing::info!("    GET  /api/reports/my-clears");
-    tracing::info!("    GET  /api/reports/:id");
+    tracing::info!("    GET  /api/reports/:id?translate_to=xx");
+    tracing::info!("    PATCH /api/reports/:id");
+    tracing::info!("    DELETE /api/reports/:id");
+    tracing::info!("    POST /api/reports/claim-batch");
     tracing::info!("    POST /api/reports/:id/claim");
+    tracing::info!("    POST /api/reports/:id/unclaim");
+    tracing::info!("    POST /api/reports/:id/flag");
+    tracing::info!("    GET  /api/reports/:id/timeline");
+    tracing::info!("    GET  /api/reports/:id/history");
     tracing::info!("    POST /api/reports/:id/clear");
+    tracing::info!("    PUT  /api/reports/:id/after-photo");
+    tracing::info!("    GET  /api/reports/:id/photos");
+    tracing::info!("    POST /api/reports/:id/photos");
+    tracing::info!("    GET  /api/cleanup-runs/:id");
+    tracing::info!("  Cleanup events (authenticated):");
+    tracing::info!("    GET|POST /api/cleanup-events?latitude=X&longitude=Y&radius_km=Z");
+    tracing::info!("    GET  /api/cleanup-events/:id");
+    tracing::info!("    POST|DELETE /api/cleanup-events/:id/rsvp");
+    tracing::info!("  Uploads (authenticated):");
+    tracing::info!("    POST /api/uploads");
+    tracing::info!("    POST /api/uploads/:id/chunks?offset=N");
+    tracing::info!("    GET|DELETE /api/uploads/:id");
+    tracing::info!("    POST /api/uploads/:id/complete");
     tracing::info!("  Verifications (authenticated):");
     tracing::info!("    POST /api/reports/:id/verify");
+    tracing::info!("    POST|DELETE /api/reports/:id/verify/reserve");
     tracing::info!("    GET  /api/reports/:id/verifications");
-    tracing::info!("  Leaderboards (authenticated):");
+    tracing::info!("  Leaderboards (public, or X-Api-Key):");
     tracing::info!("    GET  /api/leaderboards?period=weekly|monthly|all_time");
     tracing::info!("    GET  /api/leaderboards/city/:city?period=...");
     tracing::info!("    GET  /api/leaderboards/country/:country?period=...");
+    tracing::info!("  Open data (public):");
+    tracing::info!("    GET  /api/open-data/reports?from=...&to=...&format=json|csv");
+    tracing::info!("  Stats (public):");
+    tracing::info!("    GET  /api/stats/summary");
+    tracing::info!("    GET  /api/stats/hotspots?city=...&window_days=30&shape=hex|grid");
+    tracing::info!("    GET  /api/locations/history?lat=...&lon=...&radius=50");
+    tracing::info!("  Scoring (public):");
+    tracing::info!("    GET  /api/scoring/rules?locale=en");
+    tracing::info!("    GET  /api/capabilities");
+    tracing::info!("    GET  /api/city-goals, /api/city-goals/:id");
+    tracing::info!("  Notifications (authenticated):");
+    tracing::info!("    GET  /api/notifications/poll?since=...&timeout_secs=25");
+    tracing::info!("  Moderation (moderator or admin role):");
+    tracing::info!("    DELETE /api/admin/feed/:id");
+    tracing::info!("    GET    /api/admin/account-flags");
+    tracing::info!("    POST   /api/admin/account-flags/:id/resolve");
+    tracing::info!("    GET    /api/admin/report-flags");
+    tracing::info!("    GET    /api/admin/reports/:id/flags");
+    tracing::info!("    POST   /api/admin/reports/:id/flags/review");
     tracing::info!("  Admin (authenticated, admin role required):");
     tracing::info!("    GET    /api/admin/users");
     tracing::info!("    GET    /api/admin/users/:id");
     tracing::info!("    PUT    /api/admin/users/:id/ban");
+    tracing::info!("    PUT    /api/admin/users/:id/role");
+    tracing::info!("    POST   /api/admin/users/:id/impersonate");
+    tracing::info!("    GET    /api/admin/impersonations?user_id=");
+    tracing::info!("    DELETE /api/admin/users/:id/email-suppression?dry_run=true");
+    tracing::info!("    DELETE /api/admin/users/:id/reports?dry_run=true");
     tracing::info!("    GET    /api/admin/reports");
-    tracing::info!("    DELETE /api/admin/reports/:id");
+    tracing::info!("    POST   /api/admin/reports/import");
+    tracing::info!("    GET    /api/admin/reports/import/:id");
+    tracing::info!("    DELETE /api/admin/reports/:id?dry_run=true");
+    tracing::info!("    POST   /api/admin/reports/:id/reassign");
+    tracing::info!("    POST   /api/admin/reports/:id/status");
+    tracing::info!("    POST   /api/admin/reports/:id/verification-override");
+    tracing::info!("    GET    /api/admin/reports/:id/edits");
+    tracing::info!("    GET    /api/admin/reports/:id/history");
+    tracing::info!("    GET    /api/admin/stats/geo?group_by=grid|city");
+    tracing::info!("    GET    /api/admin/emails/preview/:template?locale=en");
+    tracing::info!("    POST   /api/admin/emails/test");
+    tracing::info!("    GET    /api/admin/events?event_type=&entity_id=&from=&to=");
+    tracing::info!("    GET    /api/admin/events/:id");
+    tracing::info!("    POST   /api/admin/events/:id/retry");
+    tracing::info!("    GET|POST /api/admin/api-keys");
+    tracing::info!("    PATCH|DELETE /api/admin/api-keys/:id");
+    tracing::info!("    GET|POST /api/admin/city-goals");
+    tracing::info!("    PUT|DELETE /api/admin/city-goals/:id");
+    tracing::info!("    GET|POST /api/admin/consistency-checks?dry_run=true");
+    tracing::info!("    GET    /api/admin/perf?limit=");
     tracing::info!("  Images (public):");
     tracing::info!("    GET  /api/images/reports/:id/before");
     tracing::info!("    GET  /api/images/reports/:id/after");
+    tracing::info!("    GET  /api/images/reports/:id/:kind/:position");
+    tracing::info!("  Image links (authenticated):");
+    tracing::info!("    GET  /api/images/reports/:id/urls");
+    tracing::info!("  Webhooks (shared secret):");
+    tracing::info!("    POST /api/webhooks/email/:provider?token=...");
     tracing::info!("  Feed (authenticated):");
     tracing::info!("    POST /api/feed");
-    tracing::info!("    GET  /api/feed?offset=0&limit=20");
+    tracing::info!("    GET  /api/feed?offset=0&limit=20&sort=newest|trending");
     tracing::info!("    GET  /api/feed/:id");
     tracing::info!("    PATCH /api/feed/:id");
     tracing::info!("    DELETE /api/feed/:id");
@@ -357,15 +1567,22 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("    DELETE /api/feed/comments/:comment_id");
     tracing::info!("    POST /api/feed/:post_id/like");
     tracing::info!("    DELETE /api/feed/:post_id/like");
+    tracing::info!("    POST /api/feed/:post_id/dwell");
     tracing::info!("  Documentation:");
     tracing::info!("    GET  /api/openapi.json - OpenAPI 3.0 specification");
     tracing::info!("    GET  /swagger-ui - Interactive API documentation");
+    tracing::info!("    GET  /redoc - API reference grouped by domain");
 
-    if config.enable_test_helpers {
+    if config.test_helpers.is_some() {
         tracing::info!("  Test Helpers (⚠️  TESTING ONLY - DO NOT USE IN PRODUCTION):");
         tracing::info!("    GET    /api/test/status");
         tracing::info!("    POST   /api/test/verify-email/:email");
         tracing::info!("    DELETE /api/test/cleanup");
+        tracing::info!("    POST   /api/test/users");
+        tracing::info!("    POST   /api/test/users/:email/clears");
+        tracing::info!("    POST   /api/test/reports");
+        tracing::info!("    POST   /api/test/swagger-session");
+        tracing::info!("    GET    /swagger-auth - Swagger UI signed in as a throwaway user");
     }
 
     if let Some(tls) = &config.tls {
@@ -378,12 +1595,16 @@ async fn main() -> anyhow::Result<()> {
             tls.key_path
         );
         axum_server::bind_rustls(addr, tls_config)
-            .serve(app.into_make_service())
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
             .await?;
     } else {
         tracing::info!("TLS not enabled - running in HTTP");
         let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(listener, app).await?;
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
     }
 
     Ok(())
@@ -392,3 +1613,32 @@ async fn main() -> anyhow::Result<()> {
 async fn health_check() -> &'static str {
     "OK"
 }
+
+async fn redoc() -> Html<String> {
+    Html(openapi::redoc_html("/api/openapi.json"))
+}
+
+async fn swagger_auth() -> Html<String> {
+    Html(openapi::swagger_auth_html(
+        "/api/test/swagger-session",
+        "/swagger-ui/",
+    ))
+}
+
+/// Apply pending migrations the migration policy allows. Schema changes can run long,
+/// so they get a pool without the statement timeout.
+async fn run_migrations(
+    database: &config::DatabaseConfig,
+    allow_destructive: bool,
+) -> anyhow::Result<()> {
+    let pool = db::connect(&config::DatabaseConfig {
+        statement_timeout_ms: 0,
+        ..database.clone()
+    })
+    .await?;
+    migration_policy::run(sqlx::migrate!("./migrations"), &pool, allow_destructive).await?;
+    pool.close().await;
+
+    tracing::info!("Migrations completed");
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Keeps schema changes safe for blue/green deploys, where the old release keeps
+//! serving while the new one starts. Startup only applies migrations the old release
+//! can live with. A migration whose file carries a `-- migration: destructive` line,
+//! e.g. one dropping a column or changing its type, has to be applied on purpose with
+//! `--allow-destructive-migrations`. `back-end migrate` applies migrations and exits,
+//! so the schema can change before the new release starts with `--skip-migrations`.
+
+use anyhow::bail;
+use sqlx::migrate::{Migration, Migrator};
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+/// The line that marks a migration as destructive
+pub const DESTRUCTIVE_MARKER: &str = "-- migration: destructive";
+
+/// Migrations up to this version predate the policy and are never checked for markers
+pub const LAST_UNCHECKED_MIGRATION: i64 = 64;
+
+/// What the binary was asked to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupMode {
+    /// Serve the API, applying pending migrations first unless `skip_migrations`
+    Serve {
+        skip_migrations: bool,
+        allow_destructive: bool,
+    },
+    /// Apply pending migrations and exit
+    Migrate { allow_destructive: bool },
+}
+
+impl StartupMode {
+    /// Read the mode from the command line arguments, without the program name
+    pub fn from_args<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut migrate = false;
+        let mut skip_migrations = false;
+        let mut allow_destructive = false;
+
+        for (index, arg) in args.into_iter().enumerate() {
+            match arg.as_ref() {
+                "migrate" if index == 0 => migrate = true,
+                "--skip-migrations" => skip_migrations = true,
+                "--allow-destructive-migrations" => allow_destructive = true,
+                other => return Err(format!("Unknown argument {other}")),
+            }
+        }
+
+        match (migrate, skip_migrations) {
+            (true, true) => Err("migrate can't be combined with --skip-migrations".to_string()),
+            (true, false) => Ok(Self::Migrate { allow_destructive }),
+            (false, _) => Ok(Self::Serve {
+                skip_migrations,
+                allow_destructive,
+            }),
+        }
+    }
+}
+
+/// Whether the migration is marked destructive
+#[must_use]
+pub fn is_destructive(sql: &str) -> bool {
+    sql.lines()
+        .any(|line| line.trim().eq_ignore_ascii_case(DESTRUCTIVE_MARKER))
+}
+
+/// Whether the SQL drops or retypes something an older release may still use. Used to
+/// catch migrations that should carry the marker but don't.
+#[must_use]
+pub fn looks_destructive(sql: &str) -> bool {
+    let sql = sql
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase();
+    let words: Vec<&str> = sql.split_whitespace().collect();
+
+    words.windows(2).any(|pair| {
+        matches!(
+            pair,
+            ["DROP", "COLUMN" | "TABLE"] | ["RENAME", "COLUMN" | "TO"] | ["SET", "DATA"]
+        )
+    }) || words
+        .windows(4)
+        .any(|w| w[0] == "ALTER" && w[1] == "COLUMN" && w[3] == "TYPE")
+}
+
+/// The pending migrations startup may not apply on its own: destructive ones, unless
+/// allowed or the database is new and nothing is running against it yet
+#[must_use]
+pub fn blocked<'a>(
+    pending: &[&'a Migration],
+    database_is_new: bool,
+    allow_destructive: bool,
+) -> Vec<&'a Migration> {
+    if database_is_new || allow_destructive {
+        return Vec::new();
+    }
+    pending
+        .iter()
+        .copied()
+        .filter(|migration| is_destructive(&migration.sql))
+        .collect()
+}
+
+/// Versions already applied, empty for a database never migrated
+async fn applied_versions(pool: &PgPool) -> Result<HashSet<i64>, sqlx::Error> {
+    let versions: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await
+            .or_else(|e| match &e {
+                // The table doesn't exist until the first migration runs
+                sqlx::Error::Database(db) if db.code().as_deref() == Some("42P01") => {
+                    Ok(Vec::new())
+                }
+                _ => Err(e),
+            })?;
+
+    Ok(versions.into_iter().collect())
+}
+
+/// Apply pending migrations the policy allows, or fail naming the ones it doesn't.
+/// Migrations applied by a newer release are ignored, so an older release can keep
+/// restarting during a rollout.
+pub async fn run(
+    mut migrator: Migrator,
+    pool: &PgPool,
+    allow_destructive: bool,
+) -> anyhow::Result<()> {
+    let applied = applied_versions(pool).await?;
+    let pending: Vec<&Migration> = migrator
+        .iter()
+        .filter(|migration| !applied.contains(&migration.version))
+        .collect();
+
+    let blocked = blocked(&pending, applied.is_empty(), allow_destructive);
+    if !blocked.is_empty() {
+        let names = blocked
+            .iter()
+            .map(|m| format!("{} {}", m.version, m.description))
+            .collect::<Vec<_>>()
+            .join(", ");
+        bail!(
+            "Refusing to apply destructive migrations ({names}). Apply them with \
+             `back-end migrate --allow-destructive-migrations` once no release still \
+             needs the old schema"
+        );
+    }
+
+    if pending.is_empty() {
+        tracing::info!("No pending migrations");
+        return Ok(());
+    }
+    tracing::info!("Applying {} migrations", pending.len());
+
+    migrator.set_ignore_missing(true);
+    migrator.run(pool).await?;
+    Ok(())
+}
+
+/// Fail unless every migration this release knows of has been applied, for starts that
+/// skip migrating
+pub async fn ensure_applied(migrator: &Migrator, pool: &PgPool) -> anyhow::Result<()> {
+    let applied = applied_versions(pool).await?;
+    let missing: Vec<String> = migrator
+        .iter()
+        .filter(|migration| !applied.contains(&migration.version))
+        .map(|migration| format!("{} {}", migration.version, migration.description))
+        .collect();
+
+    if !missing.is_empty() {
+        bail!(
+            "Migrations not applied yet: {}. Run `back-end migrate` first",
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
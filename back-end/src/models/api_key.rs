@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// What a partner's API key may read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ApiKeyScope {
+    /// Nearby reports
+    #[serde(rename = "reports:read")]
+    ReportsRead,
+    /// Global, city and country leaderboards
+    #[serde(rename = "leaderboards:read")]
+    LeaderboardsRead,
+}
+
+impl ApiKeyScope {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::ReportsRead => "reports:read",
+            ApiKeyScope::LeaderboardsRead => "leaderboards:read",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_min: i32,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    #[must_use]
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.iter().any(|s| s == scope.as_str())
+    }
+}
+
+/// The partner service behind a request authenticated with `X-Api-Key`
+#[derive(Clone, Debug)]
+pub struct ApiClient {
+    pub key_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    #[schema(example = "Camden Council")]
+    pub name: String,
+    /// Start of the key, to tell keys apart
+    #[schema(example = "lp_3kT9xQ")]
+    pub key_prefix: String,
+    #[schema(example = json!(["reports:read", "leaderboards:read"]))]
+    pub scopes: Vec<String>,
+    #[schema(example = 60)]
+    pub rate_limit_per_min: i32,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            key_prefix: key.key_prefix,
+            scopes: key.scopes,
+            rate_limit_per_min: key.rate_limit_per_min,
+            created_by: key.created_by,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+            revoked_at: key.revoked_at,
+        }
+    }
+}
+
+/// A newly issued key. `api_key` is only ever shown here.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiKeyResponse {
+    #[schema(example = "lp_3kT9xQ2v8R1m0WcZpLh4sYbN7eJfUoA6tGdKiXqE5nM")]
+    pub api_key: String,
+    #[serde(flatten)]
+    pub key: ApiKeyResponse,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "Camden Council")]
+    pub name: String,
+    #[validate(length(min = 1))]
+    pub scopes: Vec<ApiKeyScope>,
+    #[validate(range(min = 1, max = 10000))]
+    #[schema(example = 60, minimum = 1, maximum = 10000)]
+    pub rate_limit_per_min: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateApiKeyRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(min = 1))]
+    pub scopes: Option<Vec<ApiKeyScope>>,
+    #[validate(range(min = 1, max = 10000))]
+    #[schema(example = 120, minimum = 1, maximum = 10000)]
+    pub rate_limit_per_min: Option<i32>,
+}
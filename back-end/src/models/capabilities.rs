@@ -0,0 +1,75 @@
+use crate::config::{CaptchaProvider, Config};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Optional subsystems this deployment has switched on, so clients can hide the rest
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Capabilities {
+    pub feed: FeedCapability,
+    /// Organised group cleanups under `/api/cleanup-events`
+    pub cleanup_events: Capability,
+    /// The anonymized public export under `/api/open-data/reports`
+    pub open_data: Capability,
+    /// Machine translation of report descriptions
+    pub translation: Capability,
+    /// Sign in with Google
+    pub google_sign_in: Capability,
+    pub captcha: CaptchaCapability,
+}
+
+/// A subsystem that is either on or off
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct Capability {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct FeedCapability {
+    pub enabled: bool,
+    /// Whether `GET /api/feed?sort=trending` ranks posts by engagement
+    pub trending: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub struct CaptchaCapability {
+    /// Whether registration and email-sending endpoints need a CAPTCHA token
+    pub enabled: bool,
+    /// Widget to render for the token, when enabled
+    #[schema(example = "turnstile")]
+    pub provider: Option<&'static str>,
+}
+
+impl Capabilities {
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        let features = config.features;
+        Self {
+            feed: FeedCapability {
+                enabled: features.feed,
+                trending: features.feed && config.feed_trending.is_some(),
+            },
+            cleanup_events: Capability {
+                enabled: features.cleanup_events,
+            },
+            open_data: Capability {
+                enabled: features.open_data,
+            },
+            translation: Capability {
+                enabled: config.translation.is_some(),
+            },
+            google_sign_in: Capability {
+                enabled: !config.oauth.google_client_id.is_empty(),
+            },
+            captcha: CaptchaCapability {
+                enabled: config.captcha.is_some(),
+                provider: config
+                    .captcha
+                    .as_ref()
+                    .map(|captcha| match captcha.provider {
+                        CaptchaProvider::HCaptcha => "hcaptcha",
+                        CaptchaProvider::Turnstile => "turnstile",
+                    }),
+            },
+        }
+    }
+}
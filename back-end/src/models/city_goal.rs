@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+/// A city's goal with its progress for the current week (weeks start Monday 00:00 UTC).
+/// A clear counts towards the city of the report's address, or the clearer's city when
+/// the report hasn't been geocoded.
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct CityGoalProgress {
+    pub id: Uuid,
+    #[schema(example = "London")]
+    pub city: String,
+    /// Clears the city is aiming for each week
+    #[schema(example = 200)]
+    pub weekly_target: i32,
+    /// Clears so far this week
+    #[schema(example = 137)]
+    pub cleared: i64,
+    pub week_starts_at: DateTime<Utc>,
+    pub week_ends_at: DateTime<Utc>,
+    /// When this week's target was reached
+    pub reached_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CityGoalQuery {
+    /// Only the goal for this city
+    #[param(example = "London")]
+    pub city: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateCityGoalRequest {
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "London")]
+    pub city: String,
+    #[validate(range(min = 1, max = 100000))]
+    #[schema(example = 200, minimum = 1, maximum = 100000)]
+    pub weekly_target: i32,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateCityGoalRequest {
+    #[validate(range(min = 1, max = 100000))]
+    #[schema(example = 250, minimum = 1, maximum = 100000)]
+    pub weekly_target: i32,
+}
@@ -0,0 +1,167 @@
+use crate::error::AppError;
+use crate::models::coordinates::{Latitude, Longitude};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Most reports a single event may take on
+pub const MAX_EVENT_REPORTS: usize = 50;
+
+/// Longest an event may run
+pub const MAX_EVENT_HOURS: i64 = 12;
+
+/// How far ahead an event may be planned
+pub const MAX_EVENT_LEAD_DAYS: i64 = 90;
+
+/// Furthest a linked report may be from the meeting point
+pub const MAX_REPORT_DISTANCE_KM: f64 = 5.0;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct CleanupEvent {
+    pub id: Uuid,
+    pub organizer_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub meeting_point_description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub report_ids: Vec<Uuid>,
+    pub attendee_count: i64,
+    /// Whether the user the event was fetched for has RSVPed
+    pub attending: bool,
+}
+
+/// Where an event is in its window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupEventStatus {
+    Upcoming,
+    Ongoing,
+    Finished,
+}
+
+impl CleanupEventStatus {
+    #[must_use]
+    pub fn at(starts_at: DateTime<Utc>, ends_at: DateTime<Utc>, now: DateTime<Utc>) -> Self {
+        if now < starts_at {
+            Self::Upcoming
+        } else if now < ends_at {
+            Self::Ongoing
+        } else {
+            Self::Finished
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CleanupEventResponse {
+    pub id: Uuid,
+    pub organizer_id: Uuid,
+    #[schema(example = "Saturday canal clean")]
+    pub title: String,
+    pub description: Option<String>,
+    #[schema(example = 51.5074)]
+    pub latitude: f64,
+    #[schema(example = -0.1278)]
+    pub longitude: f64,
+    #[schema(example = "By the lock gates")]
+    pub meeting_point_description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub status: CleanupEventStatus,
+    /// Reports the group means to clear
+    pub report_ids: Vec<Uuid>,
+    /// Users who RSVPed, the organiser included
+    #[schema(example = 8)]
+    pub attendee_count: i64,
+    /// Whether you have RSVPed
+    pub attending: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<CleanupEvent> for CleanupEventResponse {
+    fn from(event: CleanupEvent) -> Self {
+        CleanupEventResponse {
+            id: event.id,
+            organizer_id: event.organizer_id,
+            title: event.title,
+            description: event.description,
+            latitude: event.latitude,
+            longitude: event.longitude,
+            meeting_point_description: event.meeting_point_description,
+            status: CleanupEventStatus::at(event.starts_at, event.ends_at, Utc::now()),
+            starts_at: event.starts_at,
+            ends_at: event.ends_at,
+            report_ids: event.report_ids,
+            attendee_count: event.attendee_count,
+            attending: event.attending,
+            created_at: event.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateCleanupEventRequest {
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "Saturday canal clean")]
+    pub title: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    /// The meeting point
+    #[schema(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Latitude,
+    #[schema(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Longitude,
+    #[validate(length(max = 255))]
+    #[schema(example = "By the lock gates")]
+    pub meeting_point_description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// 1 to 50 open reports within 5 km of the meeting point
+    #[validate(length(min = 1, max = 50))]
+    pub report_ids: Vec<Uuid>,
+}
+
+impl CreateCleanupEventRequest {
+    /// Check the event's window against the current time
+    pub fn check_window(&self, now: DateTime<Utc>) -> Result<(), AppError> {
+        if self.ends_at <= self.starts_at {
+            return Err(AppError::BadRequest(
+                "An event must end after it starts".to_string(),
+            ));
+        }
+        if self.ends_at - self.starts_at > Duration::hours(MAX_EVENT_HOURS) {
+            return Err(AppError::BadRequest(format!(
+                "An event may last at most {MAX_EVENT_HOURS} hours"
+            )));
+        }
+        if self.starts_at < now {
+            return Err(AppError::BadRequest(
+                "An event can't start in the past".to_string(),
+            ));
+        }
+        if self.starts_at > now + Duration::days(MAX_EVENT_LEAD_DAYS) {
+            return Err(AppError::BadRequest(format!(
+                "An event may be planned at most {MAX_EVENT_LEAD_DAYS} days ahead"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Upcoming and ongoing events around a point
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CleanupEventsQuery {
+    #[param(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Latitude,
+    #[param(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Longitude,
+    #[param(example = 10.0, minimum = 0.1, maximum = 50.0)]
+    pub radius_km: Option<f64>,
+}
@@ -0,0 +1,47 @@
+use crate::models::report::ReportResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ClaimBatchRequest {
+    /// Reports to claim together as one cleanup run
+    pub report_ids: Vec<Uuid>,
+}
+
+/// Outcome of claiming one report in a batch
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchClaimResult {
+    pub report_id: Uuid,
+    pub claimed: bool,
+    /// Why the report could not be claimed
+    pub error: Option<String>,
+    pub report: Option<ReportResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClaimBatchResponse {
+    /// Groups the claimed reports; absent when nothing could be claimed
+    pub run_id: Option<Uuid>,
+    pub claimed_count: usize,
+    pub results: Vec<BatchClaimResult>,
+}
+
+/// Combined progress and points for a cleanup run
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CleanupRunResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub total_reports: i64,
+    /// Reports still claimed and awaiting clearing
+    pub claimed_reports: i64,
+    /// Reports cleared (or since verified) by the run's owner
+    pub cleared_reports: i64,
+    /// Points earned for clearing the run's reports
+    pub points_earned: i64,
+    pub first_cleared_at: Option<DateTime<Utc>>,
+    pub last_cleared_at: Option<DateTime<Utc>>,
+    pub reports: Vec<ReportResponse>,
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How many rows one check found inconsistent, and how many it repaired
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConsistencyAnomaly {
+    #[schema(example = "claims_without_claimer")]
+    pub check: String,
+    #[schema(example = "Reports still claimed after the claimer's account was deleted")]
+    pub description: String,
+    #[schema(example = 2)]
+    pub found: i64,
+    /// Zero for a dry run
+    #[schema(example = 2)]
+    pub fixed: i64,
+}
+
+/// One run of the data consistency check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConsistencyCheckRun {
+    pub id: Uuid,
+    /// The admin who started the run; absent for scheduled runs
+    pub triggered_by: Option<Uuid>,
+    pub dry_run: bool,
+    /// Every check that ran, including those that found nothing
+    pub anomalies: Vec<ConsistencyAnomaly>,
+    pub total_found: i64,
+    pub total_fixed: i64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
@@ -0,0 +1,116 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+
+/// Mean radius used for distances between coordinates
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Degrees north of the equator, checked to be within -90 to 90 when deserialized
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Latitude(f64);
+
+/// Degrees east of Greenwich, checked to be within -180 to 180 when deserialized
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Longitude(f64);
+
+impl Latitude {
+    #[must_use]
+    pub fn degrees(self) -> f64 {
+        self.0
+    }
+}
+
+impl Longitude {
+    #[must_use]
+    pub fn degrees(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Latitude {
+    type Error = String;
+
+    fn try_from(degrees: f64) -> Result<Self, Self::Error> {
+        if (-90.0..=90.0).contains(&degrees) {
+            Ok(Self(degrees))
+        } else {
+            Err(format!(
+                "latitude must be between -90 and 90, got {degrees}"
+            ))
+        }
+    }
+}
+
+impl TryFrom<f64> for Longitude {
+    type Error = String;
+
+    fn try_from(degrees: f64) -> Result<Self, Self::Error> {
+        if (-180.0..=180.0).contains(&degrees) {
+            Ok(Self(degrees))
+        } else {
+            Err(format!(
+                "longitude must be between -180 and 180, got {degrees}"
+            ))
+        }
+    }
+}
+
+impl From<Latitude> for f64 {
+    fn from(latitude: Latitude) -> Self {
+        latitude.0
+    }
+}
+
+impl From<Longitude> for f64 {
+    fn from(longitude: Longitude) -> Self {
+        longitude.0
+    }
+}
+
+/// A point on the map. Requests carry the two halves as separate `latitude` and
+/// `longitude` fields, each validated on the way in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: Latitude,
+    pub longitude: Longitude,
+}
+
+impl Coordinates {
+    #[must_use]
+    pub fn new(latitude: Latitude, longitude: Longitude) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Check raw degrees, e.g. a position read back from the database
+    pub fn try_from_degrees(latitude: f64, longitude: f64) -> Result<Self, AppError> {
+        Ok(Self {
+            latitude: Latitude::try_from(latitude).map_err(AppError::BadRequest)?,
+            longitude: Longitude::try_from(longitude).map_err(AppError::BadRequest)?,
+        })
+    }
+
+    #[must_use]
+    pub fn latitude(&self) -> f64 {
+        self.latitude.degrees()
+    }
+
+    #[must_use]
+    pub fn longitude(&self) -> f64 {
+        self.longitude.degrees()
+    }
+
+    /// Great-circle distance to `other` in kilometres
+    #[must_use]
+    pub fn distance_km(&self, other: &Coordinates) -> f64 {
+        let (lat1, lat2) = (self.latitude().to_radians(), other.latitude().to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude() - self.longitude()).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A copy of everything a user has contributed, assembled in the background
+#[derive(Debug, Clone, FromRow)]
+pub struct DataExport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// pending, ready or failed
+    pub status: String,
+    pub s3_key: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Progress of a data export. Poll until `status` is `ready`, then fetch `download_url`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DataExportResponse {
+    pub export_id: Uuid,
+    /// pending, ready or failed
+    #[schema(example = "ready")]
+    pub status: String,
+    /// Set once ready; requires the same bearer token as the rest of the API
+    #[schema(example = "/api/users/me/export/3fa85f64-5717-4562-b3fc-2c963f66afa6/download")]
+    pub download_url: Option<String>,
+    pub size_bytes: Option<i64>,
+    /// Why a failed export failed
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// The download stops working after this
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<DataExport> for DataExportResponse {
+    fn from(export: DataExport) -> Self {
+        let download_url = (export.status == "ready")
+            .then(|| format!("/api/users/me/export/{}/download", export.id));
+        Self {
+            export_id: export.id,
+            status: export.status,
+            download_url,
+            size_bytes: export.size_bytes,
+            error: export.error,
+            created_at: export.created_at,
+            completed_at: export.completed_at,
+            expires_at: export.expires_at,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
+
+/// Most affected ids listed in a change summary
+pub const CHANGE_SAMPLE_SIZE: usize = 10;
+
+/// Accepted by destructive admin endpoints: `?dry_run=true` reports what would change
+/// without committing anything
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct DryRunQuery {
+    #[serde(default)]
+    #[param(example = true)]
+    pub dry_run: bool,
+}
+
+/// What a destructive operation changed, or would change in a dry run
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChangeSummary {
+    #[schema(example = "Dry run: 12 reports would be deleted")]
+    pub message: String,
+    /// True when the changes were rolled back rather than committed
+    pub dry_run: bool,
+    /// Rows affected per table, including cascaded deletes
+    pub affected: BTreeMap<String, i64>,
+    /// A sample of the ids of the rows affected
+    pub sample_ids: Vec<String>,
+}
+
+impl ChangeSummary {
+    #[must_use]
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            message: String::new(),
+            dry_run,
+            affected: BTreeMap::new(),
+            sample_ids: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn count(mut self, table: &str, rows: i64) -> Self {
+        self.affected.insert(table.to_string(), rows);
+        self
+    }
+
+    #[must_use]
+    pub fn samples<I: ToString>(mut self, ids: impl IntoIterator<Item = I>) -> Self {
+        self.sample_ids = ids
+            .into_iter()
+            .take(CHANGE_SAMPLE_SIZE)
+            .map(|id| id.to_string())
+            .collect();
+        self
+    }
+
+    /// Set the message, worded for a real or a dry run
+    #[must_use]
+    pub fn message(mut self, done: &str, would: &str) -> Self {
+        self.message = if self.dry_run {
+            format!("Dry run: {would}")
+        } else {
+            done.to_string()
+        };
+        self
+    }
+
+    /// Commit the transaction the changes ran in, or roll it back for a dry run.
+    /// Both modes run the same statements, so a dry run sees exactly what would happen.
+    pub async fn finish(self, tx: sqlx::Transaction<'_, sqlx::Postgres>) -> Result<Self, AppError> {
+        if self.dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+        Ok(self)
+    }
+}
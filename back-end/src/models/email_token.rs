@@ -29,16 +29,45 @@ pub struct VerifyEmailRequest {
     pub token: String,
 }
 
+/// Token from the unsubscribe link in a verification reminder
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UnsubscribeRemindersRequest {
+    #[schema(example = "VGhpc0lzQVRva2Vu...")]
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmAccountLinkRequest {
+    #[schema(example = "VGhpc0lzQVRva2Vu...")]
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MagicLinkRequest {
+    #[schema(example = "user@example.com")]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyMagicLinkRequest {
+    #[schema(example = "VGhpc0lzQVRva2Vu...")]
+    pub token: String,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ResendVerificationRequest {
     #[schema(example = "user@example.com")]
     pub email: String,
+    /// hCaptcha/Turnstile response token; required when the server has a CAPTCHA provider
+    pub captcha_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ForgotPasswordRequest {
     #[schema(example = "user@example.com")]
     pub email: String,
+    /// hCaptcha/Turnstile response token; required when the server has a CAPTCHA provider
+    pub captcha_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
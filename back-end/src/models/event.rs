@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct EventLogEntry {
+    pub id: Uuid,
+    #[schema(example = "webhook.email.ses")]
+    pub event_type: String,
+    #[schema(example = "email")]
+    pub entity_type: Option<String>,
+    #[schema(example = "user@example.com")]
+    pub entity_id: Option<String>,
+    /// Raw payload; for webhook deliveries this is the request body as received
+    pub payload: String,
+    /// Set for webhook deliveries only: pending, succeeded or failed
+    #[schema(example = "failed")]
+    pub delivery_status: Option<String>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct EventLogQuery {
+    /// Exact event type, or a prefix ending in "." (e.g. "webhook.")
+    #[param(example = "webhook.")]
+    pub event_type: Option<String>,
+    pub entity_id: Option<String>,
+    /// Only deliveries with this status (pending, succeeded, failed)
+    #[param(example = "failed")]
+    pub delivery_status: Option<String>,
+    /// Inclusive lower bound on created_at
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on created_at
+    pub to: Option<DateTime<Utc>>,
+}
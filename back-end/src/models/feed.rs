@@ -1,3 +1,4 @@
+use crate::models::image::ImageMetadata;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -26,6 +27,9 @@ pub struct FeedPostImage {
     pub post_id: Uuid,
     pub image_url: String,
     pub position: i32,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub blurhash: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -61,6 +65,8 @@ pub struct FeedPostResponse {
     pub author_avatar: Option<String>,
     pub content: String,
     pub images: Vec<String>,
+    /// Dimensions and placeholders for `images`, in the same order
+    pub image_metadata: Vec<ImageMetadata>,
     pub like_count: i32,
     pub comment_count: i32,
     pub comments: Vec<FeedCommentResponse>,
@@ -118,21 +124,74 @@ pub struct UpdateFeedCommentRequest {
     pub content: String,
 }
 
+/// How long a post stayed on screen, reported by the client when it scrolls away
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RecordDwellRequest {
+    #[validate(range(min = 1, max = 3_600_000))]
+    #[schema(example = 4200, minimum = 1, maximum = 3_600_000)]
+    pub dwell_ms: i32,
+}
+
+// ============================================================================
+// ENGAGEMENT
+// ============================================================================
+
+/// A kind of engagement with a post, as stored in `feed_engagement_events`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngagementKind {
+    View,
+    Like,
+    Comment,
+    Dwell,
+}
+
+impl EngagementKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EngagementKind::View => "view",
+            EngagementKind::Like => "like",
+            EngagementKind::Comment => "comment",
+            EngagementKind::Dwell => "dwell",
+        }
+    }
+}
+
 // ============================================================================
 // QUERY PARAMETERS
 // ============================================================================
 
+/// Order of the feed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedSort {
+    #[default]
+    Newest,
+    /// Most engagement first, recent engagement counting most. Lists newest first while
+    /// trending is switched off.
+    Trending,
+}
+
 #[derive(Debug, Deserialize, IntoParams, ToSchema)]
 pub struct FeedQueryParams {
+    /// Opaque cursor from the previous page's `next_cursor` (takes precedence over offset)
+    pub cursor: Option<String>,
     #[schema(example = 0)]
     pub offset: Option<i32>,
     #[schema(example = 20)]
     pub limit: Option<i32>,
+    /// `newest` (the default) or `trending`
+    pub sort: Option<FeedSort>,
 }
 
 impl FeedQueryParams {
     pub fn offset(&self) -> i32 {
-        self.offset.unwrap_or(0).max(0)
+        self.cursor
+            .as_deref()
+            .and_then(|cursor| cursor.parse().ok())
+            .or(self.offset)
+            .unwrap_or(0)
+            .max(0)
     }
 
     pub fn limit(&self) -> i32 {
@@ -0,0 +1,204 @@
+use crate::error::AppError;
+use crate::models::pagination::Paginated;
+use crate::models::report::{ReportCluster, ReportResponse, ReportsInBoundsResponse};
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Query};
+use axum::http::{header, request::Parts, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Media type of GeoJSON responses (RFC 7946)
+pub const GEOJSON_CONTENT_TYPE: &str = "application/geo+json";
+
+/// How a report list is encoded. As an extractor it reads `?format=`, then `Accept`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    /// A FeatureCollection of points, for web maps and GIS tools
+    GeoJson,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct FormatQuery {
+    /// `geojson` for a FeatureCollection, like sending `Accept: application/geo+json`
+    pub format: Option<OutputFormat>,
+}
+
+impl OutputFormat {
+    /// The format asked for: `format` if given, else GeoJSON when the `Accept` header
+    /// names it
+    #[must_use]
+    pub fn negotiate(query: &FormatQuery, headers: &HeaderMap) -> Self {
+        if let Some(format) = query.format {
+            return format;
+        }
+
+        let accepts_geojson = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|media_range| media_range.split(';').next())
+            .any(|media_type| media_type.trim().eq_ignore_ascii_case(GEOJSON_CONTENT_TYPE));
+        if accepts_geojson {
+            Self::GeoJson
+        } else {
+            Self::Json
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for OutputFormat
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<FormatQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AppError::BadRequest("format must be json or geojson".to_string()))?;
+        Ok(Self::negotiate(&query, &parts.headers))
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PointGeometry {
+    #[serde(rename = "type")]
+    #[schema(example = "Point")]
+    pub kind: String,
+    /// Longitude, then latitude
+    #[schema(example = json!([-0.1278, 51.5074]))]
+    pub coordinates: Vec<f64>,
+}
+
+impl PointGeometry {
+    #[must_use]
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            kind: "Point".to_string(),
+            coordinates: vec![longitude, latitude],
+        }
+    }
+}
+
+/// What a feature stands for: one report, or a cluster of them when zoomed out
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum FeatureProperties {
+    Report(Box<ReportResponse>),
+    Cluster(ReportCluster),
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Feature {
+    #[serde(rename = "type")]
+    #[schema(example = "Feature")]
+    pub kind: String,
+    /// The report's ID, or the cluster's geohash
+    pub id: String,
+    pub geometry: PointGeometry,
+    pub properties: FeatureProperties,
+}
+
+impl From<ReportResponse> for Feature {
+    fn from(report: ReportResponse) -> Self {
+        Self {
+            kind: "Feature".to_string(),
+            id: report.id.to_string(),
+            geometry: PointGeometry::new(report.latitude, report.longitude),
+            properties: FeatureProperties::Report(Box::new(report)),
+        }
+    }
+}
+
+impl From<ReportCluster> for Feature {
+    fn from(cluster: ReportCluster) -> Self {
+        Self {
+            kind: "Feature".to_string(),
+            id: cluster.geohash.clone(),
+            geometry: PointGeometry::new(cluster.latitude, cluster.longitude),
+            properties: FeatureProperties::Cluster(cluster),
+        }
+    }
+}
+
+/// Reports as GeoJSON. `total`, `next_cursor` and `clustered` carry the same meaning as
+/// in the JSON lists.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReportFeatureCollection {
+    #[serde(rename = "type")]
+    #[schema(example = "FeatureCollection")]
+    pub kind: String,
+    pub features: Vec<Feature>,
+    pub total: i64,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Only on `/in-bounds`: true when the features are clusters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clustered: Option<bool>,
+}
+
+impl ReportFeatureCollection {
+    fn new(features: Vec<Feature>, total: i64) -> Self {
+        Self {
+            kind: "FeatureCollection".to_string(),
+            features,
+            total,
+            next_cursor: None,
+            clustered: None,
+        }
+    }
+}
+
+impl From<Paginated<ReportResponse>> for ReportFeatureCollection {
+    fn from(page: Paginated<ReportResponse>) -> Self {
+        let features = page.items.into_iter().map(Feature::from).collect();
+        Self {
+            next_cursor: page.next_cursor,
+            ..Self::new(features, page.total)
+        }
+    }
+}
+
+impl From<ReportsInBoundsResponse> for ReportFeatureCollection {
+    fn from(response: ReportsInBoundsResponse) -> Self {
+        let features = if response.clustered {
+            response.clusters.into_iter().map(Feature::from).collect()
+        } else {
+            response.reports.into_iter().map(Feature::from).collect()
+        };
+        Self {
+            clustered: Some(response.clustered),
+            ..Self::new(features, response.total)
+        }
+    }
+}
+
+impl IntoResponse for ReportFeatureCollection {
+    fn into_response(self) -> Response {
+        let mut response = Json(self).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(GEOJSON_CONTENT_TYPE),
+        );
+        response
+    }
+}
+
+/// Encode a report list as asked: plain JSON, or a GeoJSON FeatureCollection
+pub fn respond_as<T>(format: OutputFormat, body: T) -> Response
+where
+    T: Serialize + Into<ReportFeatureCollection>,
+{
+    match format {
+        OutputFormat::Json => Json(body).into_response(),
+        OutputFormat::GeoJson => body.into().into_response(),
+    }
+}
@@ -0,0 +1,89 @@
+use crate::models::coordinates::{Latitude, Longitude};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How far back the dashboard looks for recent clears and local points
+pub const HOME_AREA_WINDOW_DAYS: i32 = 30;
+
+/// Most recent clears listed on the dashboard
+pub const HOME_AREA_RECENT_CLEARS: i64 = 10;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetHomeAreaRequest {
+    #[schema(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Latitude,
+    #[schema(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Longitude,
+}
+
+/// A report cleared in the area
+#[derive(Debug, FromRow, Serialize, ToSchema)]
+pub struct AreaClear {
+    pub report_id: Uuid,
+    #[schema(example = 51.5081)]
+    pub latitude: f64,
+    #[schema(example = -0.1262)]
+    pub longitude: f64,
+    pub address: Option<String>,
+    pub cleared_at: DateTime<Utc>,
+    #[schema(example = "Jane Doe")]
+    pub cleared_by_name: String,
+}
+
+/// Where the user stands among people who earned points for reports in the area
+#[derive(Debug, FromRow, Serialize, ToSchema)]
+pub struct AreaLeaderboardPosition {
+    /// Null until the user has earned points in the area
+    #[schema(example = 3)]
+    pub rank: Option<i64>,
+    #[schema(example = 45)]
+    pub points: i64,
+    /// Everyone with points in the area
+    #[schema(example = 27)]
+    pub participants: i64,
+}
+
+/// An upcoming or ongoing cleanup event in the area, where clears earn a group bonus
+#[derive(Debug, FromRow, Serialize, ToSchema)]
+pub struct AreaBoostZone {
+    pub event_id: Uuid,
+    #[schema(example = "Saturday canal clean")]
+    pub title: String,
+    #[schema(example = 51.5074)]
+    pub latitude: f64,
+    #[schema(example = -0.1278)]
+    pub longitude: f64,
+    #[schema(example = 1.2)]
+    pub distance_km: f64,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Whether the event has started
+    pub ongoing: bool,
+    /// Reports the event means to clear
+    #[schema(example = 6)]
+    pub report_count: i64,
+}
+
+/// The dashboard for the user's home area: everything within `radius_km` of it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HomeAreaDashboard {
+    #[schema(example = 51.5074)]
+    pub latitude: f64,
+    #[schema(example = -0.1278)]
+    pub longitude: f64,
+    /// The user's search radius
+    #[schema(example = 5)]
+    pub radius_km: i32,
+    /// Pending and claimed reports waiting to be cleared
+    #[schema(example = 14)]
+    pub open_reports: i64,
+    /// Latest clears in the last 30 days, newest first
+    pub recent_clears: Vec<AreaClear>,
+    /// Ranked by points earned for reports in the area in the last 30 days
+    pub leaderboard: AreaLeaderboardPosition,
+    /// Cleanup events in the area that have not finished, soonest first
+    pub boost_zones: Vec<AreaBoostZone>,
+}
@@ -0,0 +1,20 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Dimensions and a BlurHash placeholder for an uploaded image, so clients can
+/// reserve space and show a preview while the full image loads.
+/// Metadata fields are null for images uploaded before it was recorded.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImageMetadata {
+    pub url: String,
+    #[schema(example = 1920)]
+    pub width: Option<i32>,
+    #[schema(example = 1080)]
+    pub height: Option<i32>,
+    #[schema(example = "LEHV6nWB2yk8pyo0adR*.7kCMdnj")]
+    pub blurhash: Option<String>,
+    /// Accepted while storage was down and not uploaded yet: `url` only works through
+    /// `/api/images` until then. Omitted once the image is in storage.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub processing: bool,
+}
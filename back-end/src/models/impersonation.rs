@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+/// One time support staff signed in as a user
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct Impersonation {
+    pub id: Uuid,
+    /// Absent once the admin's account has been deleted
+    pub admin_id: Option<Uuid>,
+    /// Absent once the user's account has been deleted
+    pub user_id: Option<Uuid>,
+    pub admin_email: String,
+    pub user_email: String,
+    #[schema(example = "Ticket #4521: reports not showing on the map")]
+    pub reason: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ImpersonateRequest {
+    /// Why support needs to see the app as this user; kept in the audit trail
+    #[validate(length(min = 1, max = 500))]
+    #[schema(example = "Ticket #4521: reports not showing on the map")]
+    pub reason: String,
+}
+
+/// A short-lived access token acting as the user. There is no refresh token.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonationResponse {
+    #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
+    pub access_token: String,
+    /// Seconds until the token expires
+    #[schema(example = 900)]
+    pub expires_in: i64,
+    pub impersonation: Impersonation,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ImpersonationQuery {
+    /// Only impersonations of this user
+    pub user_id: Option<Uuid>,
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A successful sign-in to the user's account
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct LoginEvent {
+    pub id: Uuid,
+    #[schema(example = "203.0.113.7")]
+    pub ip_address: Option<String>,
+    #[schema(example = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X)")]
+    pub user_agent: Option<String>,
+    /// Country the request came from, when the edge proxy reports it
+    #[schema(example = "GB")]
+    pub country: Option<String>,
+    /// The first sign-in from this device; an alert email was sent
+    pub new_device: bool,
+    /// The first sign-in from this country; an alert email was sent
+    pub new_country: bool,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,13 +1,83 @@
+pub mod api_key;
+pub mod capabilities;
+pub mod city_goal;
+pub mod cleanup_event;
+pub mod cleanup_run;
+pub mod consistency_check;
+pub mod coordinates;
+pub mod data_export;
+pub mod dry_run;
 pub mod email_token;
+pub mod event;
 pub mod feed;
+pub mod geojson;
+pub mod home_area;
+pub mod image;
+pub mod impersonation;
+pub mod login_event;
+pub mod notification;
+pub mod open_data;
+pub mod pagination;
+pub mod perf;
 pub mod report;
+pub mod report_claim;
+pub mod report_edit;
+pub mod report_flag;
+pub mod report_import;
+pub mod report_lifecycle;
+pub mod report_photo;
+pub mod saved_search;
 pub mod score;
+pub mod scoring_rules;
+pub mod service_token;
+pub mod session;
+pub mod stats;
+pub mod translation;
+pub mod two_factor;
+pub mod upload;
+pub mod upload_scan;
 pub mod user;
 pub mod verification;
+pub mod watched_area;
 
+pub use api_key::*;
+pub use capabilities::*;
+pub use city_goal::*;
+pub use cleanup_event::*;
+pub use cleanup_run::*;
+pub use consistency_check::*;
+pub use coordinates::*;
+pub use data_export::*;
+pub use dry_run::*;
 pub use email_token::*;
+pub use event::*;
 pub use feed::*;
+pub use geojson::*;
+pub use home_area::*;
+pub use image::*;
+pub use impersonation::*;
+pub use login_event::*;
+pub use notification::*;
+pub use open_data::*;
+pub use pagination::*;
+pub use perf::*;
 pub use report::*;
+pub use report_claim::*;
+pub use report_edit::*;
+pub use report_flag::*;
+pub use report_import::*;
+pub use report_lifecycle::*;
+pub use report_photo::*;
+pub use saved_search::*;
 pub use score::*;
+pub use scoring_rules::*;
+pub use service_token::*;
+pub use session::*;
+pub use stats::*;
+pub use translation::*;
+pub use two_factor::*;
+pub use upload::*;
+pub use upload_scan::*;
 pub use user::*;
 pub use verification::*;
+pub use watched_area::*;
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    ReportClaimed,
+    ReportCleared,
+    ReportVerified,
+    VerificationReminder,
+    DataExportReady,
+    PhotoRetakeRequested,
+    ClaimExpired,
+    ClearRejected,
+    WatchedAreaReport,
+    SavedSearchReport,
+    CityGoalReached,
+}
+
+impl NotificationKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationKind::ReportClaimed => "report_claimed",
+            NotificationKind::ReportCleared => "report_cleared",
+            NotificationKind::ReportVerified => "report_verified",
+            NotificationKind::VerificationReminder => "verification_reminder",
+            NotificationKind::DataExportReady => "data_export_ready",
+            NotificationKind::PhotoRetakeRequested => "photo_retake_requested",
+            NotificationKind::ClaimExpired => "claim_expired",
+            NotificationKind::ClearRejected => "clear_rejected",
+            NotificationKind::WatchedAreaReport => "watched_area_report",
+            NotificationKind::SavedSearchReport => "saved_search_report",
+            NotificationKind::CityGoalReached => "city_goal_reached",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// report_claimed, report_cleared, report_verified, verification_reminder,
+    /// data_export_ready, photo_retake_requested, claim_expired, clear_rejected,
+    /// watched_area_report, saved_search_report or city_goal_reached
+    #[schema(example = "report_cleared")]
+    pub kind: String,
+    #[schema(example = "Your report was cleared")]
+    pub title: String,
+    pub body: String,
+    pub report_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct NotificationPollQuery {
+    /// Only return notifications created after this instant (the previous response's
+    /// `next_since`). When omitted, the most recent notifications are returned immediately.
+    pub since: Option<DateTime<Utc>>,
+    /// How long to wait for new notifications before returning an empty list
+    #[param(example = 25, minimum = 0, maximum = 25)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationPollResponse {
+    /// New notifications, oldest first
+    pub notifications: Vec<Notification>,
+    /// Pass as `since` on the next poll
+    pub next_since: DateTime<Utc>,
+}
@@ -0,0 +1,125 @@
+use crate::models::report::{LitterCategory, LitterSeverity, ReportStatus};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// How `/api/open-data/reports` is encoded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenDataFormat {
+    #[default]
+    Json,
+    /// One row per report, for spreadsheets
+    Csv,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct OpenDataQuery {
+    /// First day cleared, inclusive; defaults to 30 days before `to`
+    #[param(value_type = Option<String>, example = "2024-04-01")]
+    pub from: Option<NaiveDate>,
+    /// Last day cleared, inclusive; defaults to today. At most 366 days after `from`.
+    #[param(value_type = Option<String>, example = "2024-04-30")]
+    pub to: Option<NaiveDate>,
+    pub category: Option<LitterCategory>,
+    /// `json` (the default) or `csv`
+    pub format: Option<OpenDataFormat>,
+}
+
+/// A cleared report with nothing that points back to a person: no IDs, description,
+/// photos or street, a position snapped to a grid of about a kilometre and days instead
+/// of timestamps
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct OpenDataReport {
+    /// `cleared` or `verified`
+    pub status: ReportStatus,
+    pub category: LitterCategory,
+    pub severity: LitterSeverity,
+    /// Centre of the report's grid cell
+    #[schema(example = 51.51)]
+    pub latitude: f64,
+    #[schema(example = -0.13)]
+    pub longitude: f64,
+    #[schema(example = "London")]
+    pub city: Option<String>,
+    #[schema(example = "United Kingdom")]
+    pub country: Option<String>,
+    #[schema(value_type = String, example = "2024-04-02")]
+    pub reported_on: NaiveDate,
+    #[schema(value_type = String, example = "2024-04-03")]
+    pub cleared_on: NaiveDate,
+    /// Whole hours from report to clear
+    #[schema(example = 26)]
+    pub hours_to_clear: i32,
+}
+
+/// Reports cleared on one day
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct OpenDataDailyAggregate {
+    #[schema(value_type = String, example = "2024-04-03")]
+    pub date: NaiveDate,
+    pub cleared_reports: i64,
+    /// Of those, how many the community has verified so far
+    pub verified_reports: i64,
+    pub median_hours_to_clear: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpenDataResponse {
+    #[schema(value_type = String, example = "2024-04-01")]
+    pub from: NaiveDate,
+    #[schema(value_type = String, example = "2024-04-30")]
+    pub to: NaiveDate,
+    /// Reports cleared in the range; `reports` stops at the first 10000, newest first
+    pub total: i64,
+    pub reports: Vec<OpenDataReport>,
+    /// One entry per day with clears, oldest first
+    pub daily: Vec<OpenDataDailyAggregate>,
+}
+
+impl OpenDataReport {
+    /// Header row of the CSV export
+    pub const CSV_HEADER: &'static str = "status,category,severity,latitude,longitude,city,country,reported_on,cleared_on,hours_to_clear";
+
+    /// The report as one CSV row, without the line ending
+    #[must_use]
+    pub fn csv_row(&self) -> String {
+        [
+            label(&self.status),
+            label(&self.category),
+            label(&self.severity),
+            self.latitude.to_string(),
+            self.longitude.to_string(),
+            csv_field(self.city.as_deref().unwrap_or_default()),
+            csv_field(self.country.as_deref().unwrap_or_default()),
+            self.reported_on.to_string(),
+            self.cleared_on.to_string(),
+            self.hours_to_clear.to_string(),
+        ]
+        .join(",")
+    }
+}
+
+/// An enum's name as it appears in JSON
+fn label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break (RFC 4180). Text
+/// that spreadsheets would run as a formula gets a leading apostrophe.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error::AppError;
+use crate::handlers::admin::AdminAccountFlagView;
+use crate::models::city_goal::CityGoalProgress;
+use crate::models::cleanup_event::CleanupEventResponse;
+use crate::models::consistency_check::ConsistencyCheckRun;
+use crate::models::event::EventLogEntry;
+use crate::models::feed::{FeedCommentResponse, FeedPostResponse};
+use crate::models::impersonation::Impersonation;
+use crate::models::login_event::LoginEvent;
+use crate::models::report::{AdminReportView, ReportResponse};
+use crate::models::report_flag::FlaggedReportView;
+use crate::models::saved_search::SavedSearchResponse;
+use crate::models::score::LeaderboardEntry;
+use crate::models::user::AdminUserView;
+use crate::models::verification::VerificationResponse;
+use crate::models::watched_area::WatchedAreaResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Cursor and page size accepted by every paginated list endpoint
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct PageParams {
+    /// Opaque cursor taken from the previous page's `next_cursor`
+    pub cursor: Option<String>,
+    /// Maximum number of items to return
+    #[param(example = 20, minimum = 1, maximum = 100)]
+    pub limit: Option<i64>,
+}
+
+impl PageParams {
+    /// Decode the cursor into a row offset (cursors are currently plain offsets)
+    pub fn offset(&self) -> Result<i64, AppError> {
+        match self.cursor.as_deref() {
+            None | Some("") => Ok(0),
+            Some(cursor) => cursor
+                .parse::<i64>()
+                .ok()
+                .filter(|offset| *offset >= 0)
+                .ok_or_else(|| AppError::BadRequest("Invalid pagination cursor".to_string())),
+        }
+    }
+
+    /// Page size, falling back to `default` and capped at `max`
+    #[must_use]
+    pub fn limit(&self, default: i64, max: i64) -> i64 {
+        self.limit.unwrap_or(default).clamp(1, max)
+    }
+}
+
+/// Where a page ends in a list sorted newest first by a timestamp, then by id. Unlike
+/// an offset it stays put when items are added or removed between requests.
+///
+/// Encoded as `<timestamp in microseconds since the Unix epoch>_<id>` of the last item,
+/// e.g. `1714564800000000_6f1c2a8e-3b4d-4e5f-8a9b-0c1d2e3f4a5b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeysetCursor {
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl KeysetCursor {
+    #[must_use]
+    pub fn new(at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { at, id }
+    }
+
+    #[must_use]
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.at.timestamp_micros(), self.id)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest("Invalid pagination cursor".to_string());
+        let (micros, id) = cursor.split_once('_').ok_or_else(invalid)?;
+        let at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+        Ok(Self { at, id })
+    }
+}
+
+/// Cursor and page size for the report lists, which page by `KeysetCursor`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct KeysetPageParams {
+    /// The previous page's `next_cursor`: `<microseconds since the Unix epoch>_<id>` of
+    /// its last item, by `created_at` (`cleared_at` for lists of cleared reports)
+    #[param(example = "1714564800000000_6f1c2a8e-3b4d-4e5f-8a9b-0c1d2e3f4a5b")]
+    pub cursor: Option<String>,
+    /// Maximum number of items to return
+    #[param(example = 20, minimum = 1, maximum = 100)]
+    pub limit: Option<i64>,
+}
+
+impl KeysetPageParams {
+    /// Decode the cursor; `None` starts at the first page
+    pub fn after(&self) -> Result<Option<KeysetCursor>, AppError> {
+        match self.cursor.as_deref() {
+            None | Some("") => Ok(None),
+            Some(cursor) => KeysetCursor::decode(cursor).map(Some),
+        }
+    }
+
+    /// Page size, falling back to `default` and capped at `max`
+    #[must_use]
+    pub fn limit(&self, default: i64, max: i64) -> i64 {
+        self.limit.unwrap_or(default).clamp(1, max)
+    }
+}
+
+/// Envelope returned by list endpoints
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    PaginatedReports = Paginated<ReportResponse>,
+    PaginatedFeedPosts = Paginated<FeedPostResponse>,
+    PaginatedComments = Paginated<FeedCommentResponse>,
+    PaginatedVerifications = Paginated<VerificationResponse>,
+    PaginatedLeaderboard = Paginated<LeaderboardEntry>,
+    PaginatedSavedSearches = Paginated<SavedSearchResponse>,
+    PaginatedWatchedAreas = Paginated<WatchedAreaResponse>,
+    PaginatedCleanupEvents = Paginated<CleanupEventResponse>,
+    PaginatedCityGoals = Paginated<CityGoalProgress>,
+    PaginatedAdminUsers = Paginated<AdminUserView>,
+    PaginatedAdminReports = Paginated<AdminReportView>,
+    PaginatedAccountFlags = Paginated<AdminAccountFlagView>,
+    PaginatedFlaggedReports = Paginated<FlaggedReportView>,
+    PaginatedEvents = Paginated<EventLogEntry>,
+    PaginatedImpersonations = Paginated<Impersonation>,
+    PaginatedConsistencyCheckRuns = Paginated<ConsistencyCheckRun>,
+    PaginatedLoginEvents = Paginated<LoginEvent>
+)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    /// Total number of items across all pages
+    #[schema(example = 42)]
+    pub total: i64,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Paginated<T> {
+    /// Wrap one page of results fetched starting at `offset`
+    #[must_use]
+    pub fn new(items: Vec<T>, total: i64, offset: i64) -> Self {
+        let end = offset + items.len() as i64;
+        let next_cursor = (!items.is_empty() && end < total).then(|| end.to_string());
+        Self {
+            items,
+            total,
+            next_cursor,
+        }
+    }
+
+    /// Wrap a page fetched with up to `limit + 1` items by keyset; the extra item is
+    /// dropped and only shows that another page follows
+    #[must_use]
+    pub fn keyset(
+        mut items: Vec<T>,
+        total: i64,
+        limit: i64,
+        cursor_of: impl Fn(&T) -> KeysetCursor,
+    ) -> Self {
+        let limit = usize::try_from(limit).unwrap_or(0);
+        let has_more = items.len() > limit;
+        items.truncate(limit);
+        let next_cursor = if has_more {
+            items.last().map(|last| cursor_of(last).encode())
+        } else {
+            None
+        };
+        Self {
+            items,
+            total,
+            next_cursor,
+        }
+    }
+
+    /// Wrap a complete, unpaginated result set
+    #[must_use]
+    pub fn all(items: Vec<T>) -> Self {
+        let total = items.len() as i64;
+        Self::new(items, total, 0)
+    }
+
+    /// Convert the items while keeping the paging metadata
+    #[must_use]
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Paginated<U> {
+        Paginated {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Time spent in statements sharing the same leading SQL words during one request
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SqlSpanSummary {
+    #[schema(example = "SELECT r.id, r.reporter_id, …")]
+    pub statement: String,
+    #[schema(example = 2)]
+    pub calls: u32,
+    #[schema(example = 412.5)]
+    pub total_ms: f64,
+}
+
+/// Budget violations for one route since the server started
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RouteLatencyReport {
+    #[schema(example = "GET /api/reports/nearby")]
+    pub route: String,
+    #[schema(example = 250)]
+    pub budget_ms: u64,
+    pub requests: u64,
+    pub violations: u64,
+    /// Requests dropped before finishing, usually because the client disconnected
+    pub cancelled: u64,
+    /// Slowest request seen, within budget or not
+    pub worst_ms: f64,
+    /// SQL breakdown of the slowest violating request, longest first
+    pub worst_sql: Vec<SqlSpanSummary>,
+    pub last_violation_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PerfReportQuery {
+    /// How many routes to return, most violations first (default 20)
+    pub limit: Option<usize>,
+}
@@ -1,3 +1,8 @@
+use crate::error::AppError;
+use crate::models::coordinates::{Coordinates, Latitude, Longitude};
+use crate::models::image::ImageMetadata;
+use crate::models::report_photo::ReportPhotos;
+use crate::models::translation::DescriptionTranslation;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -12,15 +17,143 @@ pub enum ReportStatus {
     Claimed,
     Cleared,
     Verified,
+    /// Enough verifiers rejected the clear; an admin settles it
+    Disputed,
+    /// Withdrawn by the reporter before anyone claimed it; final
+    Cancelled,
+    /// Taken off the map by an admin; final
+    Archived,
+    /// Left pending longer than the expiry policy allows and taken off the map; an
+    /// admin can reactivate it
+    Expired,
+}
+
+impl std::str::FromStr for ReportStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "claimed" => Ok(Self::Claimed),
+            "cleared" => Ok(Self::Cleared),
+            "verified" => Ok(Self::Verified),
+            "disputed" => Ok(Self::Disputed),
+            "cancelled" => Ok(Self::Cancelled),
+            "archived" => Ok(Self::Archived),
+            "expired" => Ok(Self::Expired),
+            other => Err(AppError::BadRequest(format!(
+                "Unknown report status: {other}"
+            ))),
+        }
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for ReportStatus {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_report_status")
+    }
+}
+
+/// What kind of litter a report is about
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema,
+)]
+#[sqlx(type_name = "litter_category", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LitterCategory {
+    Plastic,
+    Glass,
+    Metal,
+    Paper,
+    /// Needles, chemicals, batteries and the like; may need specialist disposal
+    Hazardous,
+    /// Illegally dumped household or building waste
+    FlyTipping,
+    DogWaste,
+    #[default]
+    Other,
+}
+
+impl LitterCategory {
+    /// The name used in JSON and in the database
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Plastic => "plastic",
+            Self::Glass => "glass",
+            Self::Metal => "metal",
+            Self::Paper => "paper",
+            Self::Hazardous => "hazardous",
+            Self::FlyTipping => "fly_tipping",
+            Self::DogWaste => "dog_waste",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::str::FromStr for LitterCategory {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plastic" => Ok(Self::Plastic),
+            "glass" => Ok(Self::Glass),
+            "metal" => Ok(Self::Metal),
+            "paper" => Ok(Self::Paper),
+            "hazardous" => Ok(Self::Hazardous),
+            "fly_tipping" => Ok(Self::FlyTipping),
+            "dog_waste" => Ok(Self::DogWaste),
+            "other" => Ok(Self::Other),
+            other => Err(AppError::BadRequest(format!(
+                "Unknown litter category: {other}"
+            ))),
+        }
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for LitterCategory {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_litter_category")
+    }
+}
+
+/// Roughly how much litter there is; larger clears score more
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema,
+)]
+#[sqlx(type_name = "litter_severity", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LitterSeverity {
+    /// A few items, cleared by hand
+    #[default]
+    Small,
+    /// About a bag's worth
+    Medium,
+    /// Several bags or bulky items
+    Large,
+}
+
+/// Structured parts of a reverse-geocoded address
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct AddressComponents {
+    #[schema(example = "High Street")]
+    pub street: Option<String>,
+    #[schema(example = "London")]
+    pub city: Option<String>,
+    #[schema(example = "United Kingdom")]
+    pub country: Option<String>,
 }
 
 #[derive(Debug, Clone, FromRow, ToSchema)]
 pub struct LitterReport {
     pub id: Uuid,
-    pub reporter_id: Uuid,
+    /// None for guest reports that nobody has claimed yet
+    pub reporter_id: Option<Uuid>,
     pub latitude: f64,
     pub longitude: f64,
     pub description: Option<String>,
+    pub category: LitterCategory,
+    pub severity: LitterSeverity,
     pub photo_before: Option<String>,
     pub status: ReportStatus,
     pub claimed_by: Option<Uuid>,
@@ -31,15 +164,27 @@ pub struct LitterReport {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub address: Option<String>,
+    pub address_street: Option<String>,
+    pub address_city: Option<String>,
+    pub address_country: Option<String>,
+    pub photo_before_width: Option<i32>,
+    pub photo_before_height: Option<i32>,
+    pub photo_before_blurhash: Option<String>,
+    pub photo_after_width: Option<i32>,
+    pub photo_after_height: Option<i32>,
+    pub photo_after_blurhash: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ReportResponse {
     pub id: Uuid,
-    pub reporter_id: Uuid,
+    /// Null for guest reports that nobody has claimed yet
+    pub reporter_id: Option<Uuid>,
     pub latitude: f64,
     pub longitude: f64,
     pub description: Option<String>,
+    pub category: LitterCategory,
+    pub severity: LitterSeverity,
     pub photo_before: Option<String>,
     pub status: ReportStatus,
     pub claimed_by: Option<Uuid>,
@@ -50,16 +195,59 @@ pub struct ReportResponse {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub address: Option<String>,
+    /// The address broken into parts; null when the location could not be geocoded
+    pub address_components: Option<AddressComponents>,
+    /// Dimensions and placeholder for `photo_before`
+    pub photo_before_metadata: Option<ImageMetadata>,
+    /// Dimensions and placeholder for `photo_after`
+    pub photo_after_metadata: Option<ImageMetadata>,
+    /// Every before and after photo in order. Lists only include the first of each;
+    /// fetch the report or its photos for the rest.
+    pub photos: ReportPhotos,
+    /// The description machine-translated, when asked for with `translate_to`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translation: Option<DescriptionTranslation>,
 }
 
 impl From<LitterReport> for ReportResponse {
     fn from(report: LitterReport) -> Self {
+        let photo_before_metadata = report.photo_before.clone().map(|url| ImageMetadata {
+            url,
+            width: report.photo_before_width,
+            height: report.photo_before_height,
+            blurhash: report.photo_before_blurhash.clone(),
+            processing: false,
+        });
+        let photo_after_metadata = report.photo_after.clone().map(|url| ImageMetadata {
+            url,
+            width: report.photo_after_width,
+            height: report.photo_after_height,
+            blurhash: report.photo_after_blurhash.clone(),
+            processing: false,
+        });
+
+        let photos = ReportPhotos {
+            before: photo_before_metadata.clone().into_iter().collect(),
+            after: photo_after_metadata.clone().into_iter().collect(),
+        };
+
+        let has_components = report.address_street.is_some()
+            || report.address_city.is_some()
+            || report.address_country.is_some();
+        let address_components = has_components.then_some(AddressComponents {
+            street: report.address_street,
+            city: report.address_city,
+            country: report.address_country,
+        });
+
         ReportResponse {
             id: report.id,
             reporter_id: report.reporter_id,
             latitude: report.latitude,
             longitude: report.longitude,
             description: report.description,
+            category: report.category,
+            severity: report.severity,
             // Return S3 URL directly (or None if not set)
             photo_before: report.photo_before,
             status: report.status,
@@ -72,34 +260,268 @@ impl From<LitterReport> for ReportResponse {
             created_at: report.created_at,
             updated_at: report.updated_at,
             address: report.address,
+            address_components,
+            photo_before_metadata,
+            photo_after_metadata,
+            photos,
+            translation: None,
+        }
+    }
+}
+
+impl LitterReport {
+    /// Where the report is, checked like a requested position
+    pub fn coordinates(&self) -> Result<Coordinates, AppError> {
+        Coordinates::try_from_degrees(self.latitude, self.longitude)
+    }
+}
+
+impl ReportResponse {
+    /// Replace the first-photo-only lists with the report's full set
+    #[must_use]
+    pub fn with_photos(mut self, photos: ReportPhotos) -> Self {
+        // The first photos carry whether they are still processing
+        for (metadata, first) in [
+            (&mut self.photo_before_metadata, photos.before.first()),
+            (&mut self.photo_after_metadata, photos.after.first()),
+        ] {
+            if let (Some(metadata), Some(first)) = (metadata.as_mut(), first) {
+                metadata.processing = first.processing;
+            }
         }
+        self.photos = photos;
+        self
+    }
+
+    #[must_use]
+    pub fn with_translation(mut self, translation: Option<DescriptionTranslation>) -> Self {
+        self.translation = translation;
+        self
     }
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateReportRequest {
-    #[schema(example = 51.5074)]
-    pub latitude: f64,
-    #[schema(example = -0.1278)]
-    pub longitude: f64,
+    #[schema(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Latitude,
+    #[schema(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Longitude,
+    #[schema(example = "Plastic bottles near the park entrance")]
+    pub description: Option<String>,
+    /// Defaults to `other`
+    #[serde(default)]
+    pub category: LitterCategory,
+    /// Defaults to `small`
+    #[serde(default)]
+    pub severity: LitterSeverity,
+    /// Inline photo; provide this or `photo_upload_id`
+    #[schema(example = "data:image/jpeg;base64,...")]
+    pub photo_base64: Option<String>,
+    /// A completed chunked upload from `/api/uploads`
+    pub photo_upload_id: Option<Uuid>,
+    /// Up to four more photos, shown after the first in this order
+    #[serde(default)]
+    pub extra_photos_base64: Vec<String>,
+}
+
+/// A report from someone without an account
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAnonymousReportRequest {
+    #[schema(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Latitude,
+    #[schema(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Longitude,
     #[schema(example = "Plastic bottles near the park entrance")]
     pub description: Option<String>,
+    /// Defaults to `other`
+    #[serde(default)]
+    pub category: LitterCategory,
+    /// Defaults to `small`
+    #[serde(default)]
+    pub severity: LitterSeverity,
     #[schema(example = "data:image/jpeg;base64,...")]
     pub photo_base64: String,
+    /// hCaptcha/Turnstile response token; required when CAPTCHA is enabled
+    pub captcha_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnonymousReportResponse {
+    pub report: ReportResponse,
+    /// Shown once; redeem it after signing up to take ownership of the report
+    pub claim_code: String,
+    pub claim_expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ClaimAnonymousReportRequest {
+    pub claim_code: String,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ClearReportRequest {
+    /// Inline photo; provide this or `photo_upload_id`
     #[schema(example = "data:image/jpeg;base64,...")]
-    pub photo_base64: String,
+    pub photo_base64: Option<String>,
+    /// A completed chunked upload from `/api/uploads`
+    pub photo_upload_id: Option<Uuid>,
+    /// Up to four more photos, shown after the first in this order
+    #[serde(default)]
+    pub extra_photos_base64: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct NearbyReportsQuery {
-    #[param(example = 51.5074)]
-    pub latitude: f64,
-    #[param(example = -0.1278)]
-    pub longitude: f64,
+    #[param(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Latitude,
+    #[param(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Longitude,
     #[param(example = 5.0, minimum = 0.1, maximum = 100.0)]
     pub radius_km: Option<f64>,
+    /// Apply one of the caller's saved searches (radius and statuses)
+    pub search_id: Option<Uuid>,
+}
+
+/// A map viewport. Below `CLUSTER_BELOW_ZOOM` `/in-bounds` returns clusters.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReportsInBoundsQuery {
+    #[param(value_type = f64, example = 51.45, minimum = -90.0, maximum = 90.0)]
+    pub min_lat: Latitude,
+    #[param(value_type = f64, example = -0.25, minimum = -180.0, maximum = 180.0)]
+    pub min_lon: Longitude,
+    #[param(value_type = f64, example = 51.55, minimum = -90.0, maximum = 90.0)]
+    pub max_lat: Latitude,
+    #[param(value_type = f64, example = 0.0, minimum = -180.0, maximum = 180.0)]
+    pub max_lon: Longitude,
+    /// Map zoom level (0-22). Without it `/in-bounds` returns individual reports and
+    /// `/clusters` picks a level that fits the viewport.
+    #[param(example = 14, minimum = 0, maximum = 22)]
+    pub zoom: Option<u8>,
+}
+
+/// Reports close enough together to share one map marker: those in the same geohash cell
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ReportCluster {
+    /// The cell's geohash; stable between requests at the same zoom
+    #[schema(example = "gcpvj")]
+    pub geohash: String,
+    /// Average position of the reports in the cluster
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: i64,
+    /// The report, when the cluster holds only one
+    pub report_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReportClustersResponse {
+    /// Largest first; every report in the viewport is in exactly one
+    pub clusters: Vec<ReportCluster>,
+    /// Reports in the viewport
+    pub total: i64,
+    /// Geohash length the reports were grouped by
+    pub precision: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReportsInBoundsResponse {
+    /// True when `clusters` is filled instead of `reports`
+    pub clustered: bool,
+    /// Newest first, at most 500
+    pub reports: Vec<ReportResponse>,
+    pub clusters: Vec<ReportCluster>,
+    /// Reports in the viewport, however many are returned
+    pub total: i64,
+}
+
+/// Narrow a nearby search to one kind or size of litter
+#[derive(Debug, Clone, Copy, Default, Deserialize, IntoParams)]
+pub struct LitterFilter {
+    pub category: Option<LitterCategory>,
+    pub severity: Option<LitterSeverity>,
+}
+
+/// Which reports a nearby search returns, besides distance
+#[derive(Debug, Clone)]
+pub struct ReportFilter {
+    pub statuses: Vec<ReportStatus>,
+    /// Any of these categories; empty for every category
+    pub categories: Vec<LitterCategory>,
+    pub severity: Option<LitterSeverity>,
+}
+
+/// Order of a report list. Lists page by a cursor whatever the order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportSort {
+    /// Nearest first; only for lists searched around a location
+    Distance,
+    #[default]
+    Newest,
+    Oldest,
+}
+
+/// Status filter and order for the nearby and admin report lists
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ReportListQuery {
+    /// Comma-separated statuses to include; replaces the list's default statuses
+    #[param(example = "pending,claimed")]
+    pub status: Option<String>,
+    /// `newest` (the default), `oldest` or, for nearby searches, `distance`
+    pub sort: Option<ReportSort>,
+}
+
+impl ReportListQuery {
+    /// The requested statuses, or `None` to keep the list's default
+    pub fn statuses(&self) -> Result<Option<Vec<ReportStatus>>, AppError> {
+        let Some(status) = self.status.as_deref() else {
+            return Ok(None);
+        };
+        let statuses = status
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<ReportStatus>, _>>()?;
+        if statuses.is_empty() {
+            return Err(AppError::BadRequest(
+                "status must name at least one report status".to_string(),
+            ));
+        }
+        Ok(Some(statuses))
+    }
+
+    #[must_use]
+    pub fn sort(&self) -> ReportSort {
+        self.sort.unwrap_or_default()
+    }
+}
+
+/// Filters and order of the verification queue
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct VerificationQueueQuery {
+    pub category: Option<LitterCategory>,
+    pub severity: Option<LitterSeverity>,
+    /// `newest` clears first (the default), `oldest` first, or `distance` for the
+    /// closest first
+    pub sort: Option<ReportSort>,
+}
+
+/// A report as the admin list shows it, with the reporter's name and email
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct AdminReportView {
+    pub id: Uuid,
+    /// Null for unclaimed guest reports
+    pub reporter_id: Option<Uuid>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub description: Option<String>,
+    pub status: ReportStatus,
+    pub claimed_by: Option<Uuid>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub cleared_by: Option<Uuid>,
+    pub cleared_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub reporter_name: Option<String>,
+    pub reporter_email: Option<String>,
 }
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// How a claim on a report ended, or `active` while the claimer is still on it
+#[derive(Debug, Clone, Copy, Serialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "claim_outcome", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ClaimOutcome {
+    Active,
+    Cleared,
+    /// The claimer gave the report up
+    Released,
+    /// The claimer neither cleared nor released it in time
+    Expired,
+    /// An admin handed the claim to someone else
+    Reassigned,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ReportClaim {
+    pub id: Uuid,
+    pub report_id: Uuid,
+    pub user_id: Uuid,
+    pub claimed_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub outcome: ClaimOutcome,
+    /// Why the claimer gave the report up, if they said
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Validate, ToSchema)]
+pub struct UnclaimReportRequest {
+    /// Why you are giving the report up, e.g. "Too much for one person"
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+}
+
+/// What happened to a report at one point in time
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineEventKind {
+    Reported,
+    Claimed,
+    Released,
+    Expired,
+    Reassigned,
+    Cleared,
+    Verified,
+    /// Verifiers rejected the clear
+    Disputed,
+    /// The disputed clear was undone and the report is open again
+    Reopened,
+    Cancelled,
+    Archived,
+    /// An admin put the report into another status
+    Corrected,
+    /// Nobody cleared the report in time, so it was taken off the map
+    Lapsed,
+    /// An admin put the expired report back on the map
+    Reactivated,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimelineEvent {
+    pub kind: TimelineEventKind,
+    /// The reporter, claimer or clearer; null for guest reports
+    pub user_id: Option<Uuid>,
+    pub at: DateTime<Utc>,
+    /// The claimer's reason on `released` events, or the admin's on `corrected` and
+    /// `archived` ones
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReportTimelineResponse {
+    pub report_id: Uuid,
+    /// Oldest first
+    pub events: Vec<TimelineEvent>,
+}
@@ -0,0 +1,49 @@
+use crate::models::report::LitterCategory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Fields a reporter may change while their report is still pending. Omitted fields
+/// stay as they are; send at least one.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpdateReportRequest {
+    /// An empty description removes it
+    #[schema(example = "Plastic bottles and a shopping trolley near the park entrance")]
+    pub description: Option<String>,
+    pub category: Option<LitterCategory>,
+    /// Inline photo replacing the first before photo
+    #[schema(example = "data:image/jpeg;base64,...")]
+    pub photo_base64: Option<String>,
+    /// A completed chunked upload from `/api/uploads` replacing the first before photo
+    pub photo_upload_id: Option<Uuid>,
+}
+
+impl UpdateReportRequest {
+    #[must_use]
+    pub fn replaces_photo(&self) -> bool {
+        self.photo_base64.is_some() || self.photo_upload_id.is_some()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none() && self.category.is_none() && !self.replaces_photo()
+    }
+}
+
+/// One edit to a report, with the edited fields as they were before and after
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ReportEdit {
+    pub id: Uuid,
+    pub report_id: Uuid,
+    pub editor_id: Uuid,
+    pub previous_description: Option<String>,
+    pub new_description: Option<String>,
+    pub previous_category: LitterCategory,
+    pub new_category: LitterCategory,
+    /// URL of the first before photo; the same as `new_photo` when it was not replaced
+    pub previous_photo: Option<String>,
+    pub new_photo: Option<String>,
+    pub edited_at: DateTime<Utc>,
+}
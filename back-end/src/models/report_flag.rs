@@ -0,0 +1,91 @@
+use crate::models::report::ReportStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Why a user thinks a report should not be on the map
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "report_flag_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFlagReason {
+    /// Nothing to pick up, e.g. a prank or a photo of something else
+    NotLitter,
+    /// The spot is on private land volunteers should not enter
+    PrivateProperty,
+    OffensivePhoto,
+    /// The pin is nowhere near the litter
+    WrongLocation,
+}
+
+impl sqlx::postgres::PgHasArrayType for ReportFlagReason {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_report_flag_reason")
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct FlagReportRequest {
+    pub reason: ReportFlagReason,
+    /// Anything the moderators should know
+    #[validate(length(max = 500))]
+    #[schema(example = "The photo shows a garden, not litter")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ReportFlag {
+    pub id: Uuid,
+    pub report_id: Uuid,
+    pub flagger_id: Uuid,
+    pub reason: ReportFlagReason,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Set once a moderator has reviewed the report
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FlagReportResponse {
+    pub flag: ReportFlag,
+    /// Whether the report is now hidden awaiting review
+    pub report_hidden: bool,
+}
+
+/// A report with open flags, as listed in the moderation queue
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct FlaggedReportView {
+    pub report_id: Uuid,
+    pub status: ReportStatus,
+    pub description: Option<String>,
+    pub photo_before: Option<String>,
+    /// Set while the report is hidden from the map
+    pub hidden_at: Option<DateTime<Utc>>,
+    pub open_flags: i64,
+    /// Each reason given at least once
+    pub reasons: Vec<ReportFlagReason>,
+    pub first_flagged_at: DateTime<Utc>,
+    pub last_flagged_at: DateTime<Utc>,
+}
+
+/// What a moderator decided about a flagged report
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FlagReviewDecision {
+    /// The report is fine; it goes back on the map
+    Dismiss,
+    /// The flags are right; the report is archived
+    Archive,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReviewReportFlagsRequest {
+    pub decision: FlagReviewDecision,
+    /// Kept with the report's history
+    #[validate(length(min = 1, max = 500))]
+    #[schema(example = "Photo shows a private garden")]
+    pub note: String,
+}
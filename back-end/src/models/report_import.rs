@@ -0,0 +1,231 @@
+use crate::error::AppError;
+use crate::models::coordinates::{Coordinates, Latitude, Longitude};
+use crate::models::report::{LitterCategory, LitterSeverity};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Most rows one import may hold
+pub const MAX_IMPORT_ROWS: usize = 1000;
+
+/// Most photos one imported report may link to
+pub const MAX_IMPORT_PHOTOS: usize = 5;
+
+/// Longest description an imported report may carry
+const MAX_DESCRIPTION_CHARS: usize = 1000;
+
+/// One report to import, as a JSON object or a CSV row. CSV files name the columns in a
+/// header row and list several photo URLs in one field, separated by spaces.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReportImportRow {
+    #[schema(example = 51.5074)]
+    pub latitude: f64,
+    #[schema(example = -0.1278)]
+    pub longitude: f64,
+    #[schema(example = "Fly-tipped mattress behind the garages")]
+    pub description: Option<String>,
+    /// Defaults to `other`
+    #[schema(example = "fly_tipping")]
+    pub category: Option<String>,
+    /// Defaults to `small`
+    #[schema(example = "large")]
+    pub severity: Option<String>,
+    /// Up to five `https` links to photos, the first becoming the before photo
+    #[serde(default, deserialize_with = "photo_urls")]
+    #[schema(example = json!(["https://council.example.gov.uk/photos/1234.jpg"]))]
+    pub photo_urls: Vec<String>,
+    /// The partner's own reference, echoed back in the results
+    #[schema(example = "WCC-2023-1234")]
+    pub external_id: Option<String>,
+}
+
+/// A row that passed validation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidImportRow {
+    pub location: Coordinates,
+    pub description: Option<String>,
+    pub category: LitterCategory,
+    pub severity: LitterSeverity,
+    pub photo_urls: Vec<String>,
+}
+
+impl ReportImportRow {
+    /// Check the row, naming the first problem found
+    pub fn validate(&self) -> Result<ValidImportRow, String> {
+        let location = Coordinates::new(
+            Latitude::try_from(self.latitude)?,
+            Longitude::try_from(self.longitude)?,
+        );
+
+        let description = self
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        if description.is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_CHARS) {
+            return Err(format!(
+                "description is longer than {MAX_DESCRIPTION_CHARS} characters"
+            ));
+        }
+
+        if self.photo_urls.len() > MAX_IMPORT_PHOTOS {
+            return Err(format!(
+                "at most {MAX_IMPORT_PHOTOS} photo URLs are allowed"
+            ));
+        }
+        if let Some(url) = self
+            .photo_urls
+            .iter()
+            .find(|url| !url.starts_with("https://"))
+        {
+            return Err(format!("photo URL {url} is not https"));
+        }
+
+        Ok(ValidImportRow {
+            location,
+            description: description.map(str::to_string),
+            category: parse_label(self.category.as_deref(), "category")?,
+            severity: parse_label(self.severity.as_deref(), "severity")?,
+            photo_urls: self.photo_urls.clone(),
+        })
+    }
+}
+
+/// An enum by its JSON name; blank means the default
+fn parse_label<T: for<'de> Deserialize<'de> + Default>(
+    value: Option<&str>,
+    field: &str,
+) -> Result<T, String> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(T::default()),
+        Some(v) => serde_json::from_value(serde_json::Value::String(v.to_lowercase()))
+            .map_err(|_| format!("unknown {field} {v}")),
+    }
+}
+
+/// Photo URLs as a list (JSON) or one space-separated field (CSV)
+fn photo_urls<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Urls {
+        List(Vec<String>),
+        Field(String),
+    }
+
+    Ok(match Option::<Urls>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(Urls::List(urls)) => urls,
+        Some(Urls::Field(field)) => field.split_whitespace().map(str::to_string).collect(),
+    })
+}
+
+/// A JSON import batch; CSV uploads carry the same rows with a header line
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReportImportRequest {
+    /// Where the reports come from, e.g. the council's name
+    #[schema(example = "Westminster City Council")]
+    pub source: Option<String>,
+    pub reports: Vec<ReportImportRow>,
+}
+
+/// Options for CSV uploads, which have no room for them in the body
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ReportImportQuery {
+    /// Where the reports come from, e.g. the council's name
+    pub source: Option<String>,
+}
+
+/// Parse a CSV upload into rows; a malformed line fails the whole file
+pub fn parse_import_csv(body: &[u8]) -> Result<Vec<ReportImportRow>, AppError> {
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body)
+        .deserialize()
+        .enumerate()
+        .map(|(index, row)| {
+            row.map_err(|e| AppError::BadRequest(format!("CSV line {}: {e}", index + 2)))
+        })
+        .collect()
+}
+
+/// What happened to one row
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReportImportResult {
+    /// Position in the batch, from 0
+    pub row: usize,
+    pub external_id: Option<String>,
+    /// imported, duplicate or failed
+    #[schema(example = "imported")]
+    pub outcome: String,
+    /// The new report, or for duplicates the existing one
+    pub report_id: Option<Uuid>,
+    /// Why the row failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ReportImport {
+    pub id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub source: Option<String>,
+    /// pending, running, completed or failed
+    pub status: String,
+    pub total_rows: i32,
+    pub processed_rows: i32,
+    pub imported_count: i32,
+    pub duplicate_count: i32,
+    pub failed_count: i32,
+    pub results: Json<Vec<ReportImportResult>>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Progress of an import. Poll until `status` is `completed` or `failed`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReportImportResponse {
+    pub import_id: Uuid,
+    pub source: Option<String>,
+    /// pending, running, completed or failed
+    #[schema(example = "running")]
+    pub status: String,
+    #[schema(example = 250)]
+    pub total_rows: i32,
+    #[schema(example = 120)]
+    pub processed_rows: i32,
+    pub imported_count: i32,
+    /// Rows skipped because an open report already exists within 25m
+    pub duplicate_count: i32,
+    pub failed_count: i32,
+    /// One entry per processed row, in order
+    pub results: Vec<ReportImportResult>,
+    /// Why a failed import stopped
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<ReportImport> for ReportImportResponse {
+    fn from(import: ReportImport) -> Self {
+        Self {
+            import_id: import.id,
+            source: import.source,
+            status: import.status,
+            total_rows: import.total_rows,
+            processed_rows: import.processed_rows,
+            imported_count: import.imported_count,
+            duplicate_count: import.duplicate_count,
+            failed_count: import.failed_count,
+            results: import.results.0,
+            error: import.error,
+            created_at: import.created_at,
+            completed_at: import.completed_at,
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use crate::error::AppError;
+use crate::models::report::ReportStatus;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Something that moves a report from one status to another. Every status change
+/// goes through one of these, so a report can only follow the paths listed here:
+///
+/// ```text
+/// pending ── claim ──> claimed ── clear ──> cleared ── verify ──> verified
+///    ^                    │                    │
+///    ├─ release/expire ───┘                    └── dispute ──> disputed
+///    │                                                            │
+///    └──────────────────────────── reopen ────────────────────────┘
+///
+/// pending ── cancel ──> cancelled      any open status ── archive ──> archived
+///
+/// pending ── lapse ──> expired ── reactivate ──> pending
+/// ```
+///
+/// `correct` is an admin putting a report into any open status, e.g. settling a dispute.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTransition {
+    Claim,
+    /// The claimer gave the report up
+    Release,
+    /// The claim ran out, or the claimer's account went away
+    Expire,
+    Clear,
+    Verify,
+    /// Enough verifiers rejected the clear
+    Dispute,
+    /// Nobody settled the dispute in time, so the litter is taken to still be there
+    Reopen,
+    /// The reporter withdrew the report
+    Cancel,
+    Archive,
+    Correct,
+    /// Nobody cleared the report within the expiry policy's limit
+    Lapse,
+    /// An admin put an expired report back on the map
+    Reactivate,
+}
+
+/// Statuses nothing moves a report out of
+const FINAL_STATUSES: &[ReportStatus] = &[ReportStatus::Cancelled, ReportStatus::Archived];
+
+/// Statuses an admin correction may put a report into
+const OPEN_STATUSES: &[ReportStatus] = &[
+    ReportStatus::Pending,
+    ReportStatus::Claimed,
+    ReportStatus::Cleared,
+    ReportStatus::Verified,
+    ReportStatus::Disputed,
+];
+
+/// Statuses a report may be archived from
+const ARCHIVABLE_STATUSES: &[ReportStatus] = &[
+    ReportStatus::Pending,
+    ReportStatus::Claimed,
+    ReportStatus::Cleared,
+    ReportStatus::Verified,
+    ReportStatus::Disputed,
+    ReportStatus::Expired,
+];
+
+impl ReportTransition {
+    /// Statuses the transition may start from
+    #[must_use]
+    pub fn sources(self) -> &'static [ReportStatus] {
+        match self {
+            Self::Claim | Self::Cancel | Self::Lapse => &[ReportStatus::Pending],
+            Self::Release | Self::Expire | Self::Clear => &[ReportStatus::Claimed],
+            Self::Verify | Self::Dispute => &[ReportStatus::Cleared],
+            Self::Reopen => &[ReportStatus::Disputed],
+            Self::Reactivate => &[ReportStatus::Expired],
+            Self::Archive => ARCHIVABLE_STATUSES,
+            Self::Correct => OPEN_STATUSES,
+        }
+    }
+
+    /// Where the transition leaves a report, or `None` for corrections, where the admin
+    /// picks
+    #[must_use]
+    pub fn target(self) -> Option<ReportStatus> {
+        match self {
+            Self::Claim => Some(ReportStatus::Claimed),
+            Self::Release | Self::Expire | Self::Reopen | Self::Reactivate => {
+                Some(ReportStatus::Pending)
+            }
+            Self::Clear => Some(ReportStatus::Cleared),
+            Self::Verify => Some(ReportStatus::Verified),
+            Self::Dispute => Some(ReportStatus::Disputed),
+            Self::Cancel => Some(ReportStatus::Cancelled),
+            Self::Archive => Some(ReportStatus::Archived),
+            Self::Lapse => Some(ReportStatus::Expired),
+            Self::Correct => None,
+        }
+    }
+
+    /// The transition an admin setting a report to `status` makes
+    #[must_use]
+    pub fn admin_override(status: &ReportStatus) -> Self {
+        if *status == ReportStatus::Archived {
+            Self::Archive
+        } else {
+            Self::Correct
+        }
+    }
+
+    /// Check the transition may take a report from `from` to `to`
+    pub fn check(self, from: &ReportStatus, to: &ReportStatus) -> Result<(), IllegalTransition> {
+        let allowed_target = match self.target() {
+            Some(target) => target == *to,
+            None => OPEN_STATUSES.contains(to),
+        };
+
+        if self.sources().contains(from) && allowed_target {
+            Ok(())
+        } else {
+            Err(IllegalTransition {
+                transition: self,
+                from: from.clone(),
+                to: to.clone(),
+            })
+        }
+    }
+
+    /// Check the transition may start from `from`, returning where it leaves the report.
+    /// Not for corrections, which need a target.
+    pub fn apply(self, from: &ReportStatus) -> Result<ReportStatus, IllegalTransition> {
+        let to = self.target().unwrap_or_else(|| from.clone());
+        self.check(from, &to)?;
+        Ok(to)
+    }
+}
+
+/// A transition tried from a status it does not start from
+#[derive(Debug, Clone, PartialEq)]
+pub struct IllegalTransition {
+    pub transition: ReportTransition,
+    pub from: ReportStatus,
+    pub to: ReportStatus,
+}
+
+impl std::fmt::Display for IllegalTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self.transition {
+            ReportTransition::Claim => "Report is not available for claiming",
+            ReportTransition::Release | ReportTransition::Expire => "Report is not claimed",
+            ReportTransition::Clear => "Report must be claimed before clearing",
+            ReportTransition::Verify => "Report must be cleared before it can be verified",
+            ReportTransition::Dispute => "Only a cleared report can be disputed",
+            ReportTransition::Reopen => "Only a disputed report can be reopened",
+            ReportTransition::Cancel => "Only a pending report can be cancelled",
+            ReportTransition::Lapse => "Only a pending report can expire",
+            ReportTransition::Reactivate => "Only an expired report can be reactivated",
+            ReportTransition::Archive | ReportTransition::Correct
+                if FINAL_STATUSES.contains(&self.from) =>
+            {
+                "A cancelled or archived report can no longer change status"
+            }
+            ReportTransition::Correct if self.from == ReportStatus::Expired => {
+                "Reactivate an expired report before changing its status"
+            }
+            ReportTransition::Archive | ReportTransition::Correct => {
+                "Only the reporter can cancel a report; delete it instead"
+            }
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for IllegalTransition {}
+
+impl From<IllegalTransition> for AppError {
+    fn from(err: IllegalTransition) -> Self {
+        AppError::BadRequest(err.to_string())
+    }
+}
+
+/// What happened to a report, as kept in its history
+#[derive(Debug, Clone, Copy, Serialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "report_event_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReportEventKind {
+    Created,
+    Claimed,
+    Released,
+    Expired,
+    Cleared,
+    Verified,
+    Disputed,
+    /// A disputed clear was undone and the report went back to pending
+    Reopened,
+    Cancelled,
+    Archived,
+    Corrected,
+    /// Left pending too long and taken off the map
+    Lapsed,
+    /// Put back on the map after expiring
+    Reactivated,
+    /// Removed by an admin or the consistency check; the history is all that is left
+    Deleted,
+}
+
+impl From<ReportTransition> for ReportEventKind {
+    fn from(transition: ReportTransition) -> Self {
+        match transition {
+            ReportTransition::Claim => Self::Claimed,
+            ReportTransition::Release => Self::Released,
+            ReportTransition::Expire => Self::Expired,
+            ReportTransition::Clear => Self::Cleared,
+            ReportTransition::Verify => Self::Verified,
+            ReportTransition::Dispute => Self::Disputed,
+            ReportTransition::Reopen => Self::Reopened,
+            ReportTransition::Cancel => Self::Cancelled,
+            ReportTransition::Archive => Self::Archived,
+            ReportTransition::Correct => Self::Corrected,
+            ReportTransition::Lapse => Self::Lapsed,
+            ReportTransition::Reactivate => Self::Reactivated,
+        }
+    }
+}
+
+/// One entry in a report's history
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ReportEvent {
+    pub id: Uuid,
+    pub report_id: Uuid,
+    pub kind: ReportEventKind,
+    /// Null for creation
+    pub from_status: Option<ReportStatus>,
+    /// Null for deletion
+    pub to_status: Option<ReportStatus>,
+    /// The reporter, claimer, verifier or admin behind the event; null for background jobs
+    pub actor_id: Option<Uuid>,
+    pub reason: Option<String>,
+    /// Details that depend on the kind, e.g. the cleanup run a claim belongs to
+    #[schema(value_type = Object)]
+    pub metadata: serde_json::Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReportHistoryResponse {
+    pub report_id: Uuid,
+    /// Oldest first
+    pub events: Vec<ReportEvent>,
+}
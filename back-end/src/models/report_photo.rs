@@ -0,0 +1,45 @@
+use crate::models::image::ImageMetadata;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Whether a photo shows the litter or the cleaned-up spot
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "photo_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PhotoKind {
+    Before,
+    After,
+}
+
+/// A report's photos in display order; the first of each is the report's
+/// `photo_before`/`photo_after`
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ReportPhotos {
+    pub before: Vec<ImageMetadata>,
+    pub after: Vec<ImageMetadata>,
+}
+
+/// Add one photo to a report. The reporter adds before photos until the report is
+/// cleared; the clearer adds after photos until it is verified.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddReportPhotoRequest {
+    pub kind: PhotoKind,
+    /// Inline photo; provide this or `photo_upload_id`
+    #[schema(example = "data:image/jpeg;base64,...")]
+    pub photo_base64: Option<String>,
+    /// A completed chunked upload from `/api/uploads`
+    pub photo_upload_id: Option<Uuid>,
+}
+
+/// Links to a report's photos on the public image routes, signed when signing is on
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReportPhotoUrls {
+    #[schema(example = json!(["/api/images/reports/7f1c2f4e-8a3b-4c55-9d1e-2b6a0c9e5d11/before/0?expires=1767225600&signature=3q2-7w"]))]
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    /// When the signed links stop working; absent when links are not signed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,94 @@
+use crate::models::report::{LitterCategory, ReportStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Clone, FromRow, ToSchema)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub radius_km: f64,
+    pub statuses: Vec<ReportStatus>,
+    pub categories: Vec<String>,
+    pub notify_on_new: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SavedSearch {
+    /// The stored category names as categories; empty means every category
+    #[must_use]
+    pub fn litter_categories(&self) -> Vec<LitterCategory> {
+        self.categories
+            .iter()
+            .filter_map(|name| name.parse().ok())
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SavedSearchResponse {
+    pub id: Uuid,
+    #[schema(example = "Near the office")]
+    pub name: String,
+    #[schema(example = 2.5)]
+    pub radius_km: f64,
+    pub statuses: Vec<ReportStatus>,
+    /// Empty for every category
+    pub categories: Vec<LitterCategory>,
+    /// Notify about new reports that match, within `radius_km` of the user's home area
+    pub notify_on_new: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SavedSearch> for SavedSearchResponse {
+    fn from(search: SavedSearch) -> Self {
+        SavedSearchResponse {
+            categories: search.litter_categories(),
+            id: search.id,
+            name: search.name,
+            radius_km: search.radius_km,
+            statuses: search.statuses,
+            notify_on_new: search.notify_on_new,
+            created_at: search.created_at,
+            updated_at: search.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateSavedSearchRequest {
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "Near the office")]
+    pub name: String,
+    #[validate(range(min = 0.1, max = 100.0))]
+    #[schema(example = 2.5, minimum = 0.1, maximum = 100.0)]
+    pub radius_km: Option<f64>,
+    #[validate(length(min = 1))]
+    pub statuses: Option<Vec<ReportStatus>>,
+    #[validate(length(max = 20))]
+    pub categories: Option<Vec<LitterCategory>>,
+    #[schema(example = false)]
+    pub notify_on_new: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateSavedSearchRequest {
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "Near home")]
+    pub name: Option<String>,
+    #[validate(range(min = 0.1, max = 100.0))]
+    #[schema(example = 5.0, minimum = 0.1, maximum = 100.0)]
+    pub radius_km: Option<f64>,
+    #[validate(length(min = 1))]
+    pub statuses: Option<Vec<ReportStatus>>,
+    #[validate(length(max = 20))]
+    pub categories: Option<Vec<LitterCategory>>,
+    #[schema(example = true)]
+    pub notify_on_new: Option<bool>,
+}
@@ -1,3 +1,4 @@
+use crate::models::pagination::Paginated;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -56,5 +57,44 @@ pub struct LeaderboardEntry {
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct LeaderboardQuery {
     #[param(example = "weekly")]
-    pub period: Option<String>, // "weekly", "monthly", "all_time"
+    pub period: Option<LeaderboardPeriod>,
+}
+
+/// The span a leaderboard ranks points over. Weeks (ISO, starting Monday) and months
+/// follow the calendar in the leaderboard time zone rather than rolling back from now.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardPeriod {
+    Weekly,
+    Monthly,
+    #[default]
+    AllTime,
+}
+
+impl LeaderboardPeriod {
+    /// The `date_trunc` unit the period starts on; `None` for all time
+    #[must_use]
+    pub fn calendar_unit(self) -> Option<&'static str> {
+        match self {
+            LeaderboardPeriod::Weekly => Some("week"),
+            LeaderboardPeriod::Monthly => Some("month"),
+            LeaderboardPeriod::AllTime => None,
+        }
+    }
+}
+
+/// One page of a leaderboard with the period it was ranked over
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Leaderboard {
+    pub period: LeaderboardPeriod,
+    /// Time zone the period's boundaries are drawn in
+    #[schema(example = "Europe/London")]
+    pub timezone: String,
+    /// When the period began; absent for all_time
+    pub period_start: Option<DateTime<Utc>>,
+    /// When the period ends and the board starts afresh; absent for all_time
+    pub period_end: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    #[schema(value_type = PaginatedLeaderboard)]
+    pub page: Paginated<LeaderboardEntry>,
 }
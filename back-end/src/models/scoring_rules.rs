@@ -0,0 +1,261 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Locales the rule descriptions are written in; the first is the fallback
+pub const SCORING_RULE_LOCALES: &[&str] = &["en"];
+
+/// How far apart two clears must be for the later one to count as first in its area
+pub const FIRST_IN_AREA_RADIUS_M: f64 = 1000.0;
+
+/// How long a clear keeps others nearby from counting as first in the area
+pub const FIRST_IN_AREA_WINDOW_HOURS: i64 = 24;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ScoringRulesQuery {
+    /// Language of the titles and descriptions; overrides `Accept-Language`
+    #[param(example = "en")]
+    pub locale: Option<String>,
+}
+
+/// Pick the locale to describe the rules in: an explicit `locale` must be supported,
+/// while `Accept-Language` falls back to the default when nothing in it is
+pub fn negotiate_locale(
+    requested: Option<&str>,
+    accept_language: Option<&str>,
+) -> Result<&'static str, AppError> {
+    if let Some(requested) = requested {
+        return SCORING_RULE_LOCALES
+            .iter()
+            .copied()
+            .find(|locale| locale.eq_ignore_ascii_case(requested))
+            .ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "Unsupported locale '{requested}'. Supported: {}",
+                    SCORING_RULE_LOCALES.join(", ")
+                ))
+            });
+    }
+
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // Stable, so equal weights keep the client's order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let locale = ranges
+        .iter()
+        .find_map(|(tag, _)| {
+            let language = tag.split('-').next().unwrap_or(tag);
+            SCORING_RULE_LOCALES
+                .iter()
+                .copied()
+                .find(|locale| locale.eq_ignore_ascii_case(language))
+        })
+        .unwrap_or(SCORING_RULE_LOCALES[0]);
+    Ok(locale)
+}
+
+/// Which setting a rule describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringRuleKey {
+    ReportPoints,
+    BasePointsPerClear,
+    SmallSeverityMultiplier,
+    MediumSeverityMultiplier,
+    LargeSeverityMultiplier,
+    StreakBonusPoints,
+    FirstInAreaBonus,
+    VerificationBonus,
+    PhotoVerificationBonus,
+    VerifiedReportBonus,
+    CleanupEventBonus,
+    MinClearsToVerify,
+    MinVerificationsNeeded,
+    PhotoRetakeWindowHours,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringRuleCategory {
+    /// Points for an action
+    Points,
+    /// Extra points on top of an action's points
+    Bonus,
+    /// Scales a clear's base points
+    Multiplier,
+    /// A condition for verifying reports
+    Verification,
+}
+
+/// What a rule's `value` counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringRuleUnit {
+    Points,
+    Multiplier,
+    Clears,
+    Verifications,
+    Hours,
+}
+
+impl ScoringRuleKey {
+    #[must_use]
+    pub fn category(self) -> ScoringRuleCategory {
+        match self {
+            Self::ReportPoints | Self::BasePointsPerClear | Self::VerificationBonus => {
+                ScoringRuleCategory::Points
+            }
+            Self::SmallSeverityMultiplier
+            | Self::MediumSeverityMultiplier
+            | Self::LargeSeverityMultiplier => ScoringRuleCategory::Multiplier,
+            Self::StreakBonusPoints
+            | Self::FirstInAreaBonus
+            | Self::PhotoVerificationBonus
+            | Self::VerifiedReportBonus
+            | Self::CleanupEventBonus => ScoringRuleCategory::Bonus,
+            Self::MinClearsToVerify
+            | Self::MinVerificationsNeeded
+            | Self::PhotoRetakeWindowHours => ScoringRuleCategory::Verification,
+        }
+    }
+
+    #[must_use]
+    pub fn unit(self) -> ScoringRuleUnit {
+        match self.category() {
+            ScoringRuleCategory::Points | ScoringRuleCategory::Bonus => ScoringRuleUnit::Points,
+            ScoringRuleCategory::Multiplier => ScoringRuleUnit::Multiplier,
+            ScoringRuleCategory::Verification => match self {
+                Self::MinClearsToVerify => ScoringRuleUnit::Clears,
+                Self::MinVerificationsNeeded => ScoringRuleUnit::Verifications,
+                _ => ScoringRuleUnit::Hours,
+            },
+        }
+    }
+
+    /// The rule's title and description in `locale`, one of `SCORING_RULE_LOCALES`
+    #[must_use]
+    pub fn describe(self, value: f64, _locale: &str) -> (&'static str, String) {
+        // English is the only locale so far
+        match self {
+            Self::ReportPoints => (
+                "Reporting litter",
+                format!("Earn {value} points for each litter report you submit."),
+            ),
+            Self::BasePointsPerClear => (
+                "Clearing litter",
+                format!(
+                    "Earn {value} points for each report you clear, scaled by how much litter there was."
+                ),
+            ),
+            Self::SmallSeverityMultiplier => (
+                "Small reports",
+                format!("Clearing a small report earns {value}× the clearing points."),
+            ),
+            Self::MediumSeverityMultiplier => (
+                "Medium reports",
+                format!("Clearing a medium report earns {value}× the clearing points."),
+            ),
+            Self::LargeSeverityMultiplier => (
+                "Large reports",
+                format!("Clearing a large report earns {value}× the clearing points."),
+            ),
+            Self::StreakBonusPoints => (
+                "Daily streak",
+                format!(
+                    "Clear on consecutive days to earn {value} extra points per day of your streak."
+                ),
+            ),
+            Self::FirstInAreaBonus => (
+                "First in the area",
+                format!(
+                    "Earn {value} extra points when nobody has cleared within {} km in the last {FIRST_IN_AREA_WINDOW_HOURS} hours.",
+                    FIRST_IN_AREA_RADIUS_M / 1000.0
+                ),
+            ),
+            Self::VerificationBonus => (
+                "Verifying clears",
+                format!("Earn {value} points for confirming someone else's clear."),
+            ),
+            Self::PhotoVerificationBonus => (
+                "Photo evidence",
+                format!(
+                    "Earn {value} extra points for attaching an on-site photo to a verification."
+                ),
+            ),
+            Self::VerifiedReportBonus => (
+                "Verified clear",
+                format!("Earn {value} extra points when your clear is verified."),
+            ),
+            Self::CleanupEventBonus => (
+                "Cleanup events",
+                format!(
+                    "Every attendee earns {value} extra points when an attendee clears one of the event's reports during it."
+                ),
+            ),
+            Self::MinClearsToVerify => (
+                "Becoming a verifier",
+                format!("Clear {value} reports to start verifying other people's clears."),
+            ),
+            Self::MinVerificationsNeeded => (
+                "Verifications needed",
+                format!(
+                    "A clear is verified once verifiers with a combined weight of {value} confirm it. A new verifier's weight is 1, and it grows as their verdicts prove right."
+                ),
+            ),
+            Self::PhotoRetakeWindowHours => (
+                "Retaking a photo",
+                format!(
+                    "When a verifier asks for a better after photo, you have {value} hours to upload one."
+                ),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScoringRule {
+    pub key: ScoringRuleKey,
+    pub category: ScoringRuleCategory,
+    #[schema(example = 10.0)]
+    pub value: f64,
+    pub unit: ScoringRuleUnit,
+    #[schema(example = "Reporting litter")]
+    pub title: String,
+    #[schema(example = "Earn 10 points for each litter report you submit.")]
+    pub description: String,
+}
+
+impl ScoringRule {
+    #[must_use]
+    pub fn new(key: ScoringRuleKey, value: f64, locale: &str) -> Self {
+        let (title, description) = key.describe(value, locale);
+        Self {
+            key,
+            category: key.category(),
+            value,
+            unit: key.unit(),
+            title: title.to_string(),
+            description,
+        }
+    }
+}
+
+/// The scoring rules in force, for clients' help screens
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScoringRulesResponse {
+    /// Language of the titles and descriptions
+    #[schema(example = "en")]
+    pub locale: String,
+    pub rules: Vec<ScoringRule>,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What a service token may do; routes admit a scoped token only if they accept its scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TokenScope {
+    /// Chunked photo uploads under `/api/uploads`
+    UploadOnly,
+}
+
+impl TokenScope {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::UploadOnly => "upload-only",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateServiceTokenRequest {
+    pub scope: TokenScope,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ServiceTokenResponse {
+    pub token: String,
+    pub scope: TokenScope,
+    /// Seconds until the token expires; it cannot be refreshed
+    #[schema(example = 3600)]
+    pub expires_in: i64,
+}
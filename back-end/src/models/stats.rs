@@ -0,0 +1,163 @@
+use crate::models::coordinates::{Latitude, Longitude};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Site-wide totals shown on the landing page
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatsSummary {
+    pub total_reports: i64,
+    /// Cleared reports, verified or not
+    pub cleared_reports: i64,
+    pub verified_reports: i64,
+    /// Users who have reported or cleared at least one report
+    pub volunteers: i64,
+    /// When the counters were computed; they may be a few minutes behind
+    pub computed_at: DateTime<Utc>,
+}
+
+/// How hotspot cells tile the map
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HotspotShape {
+    #[default]
+    Hex,
+    Grid,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HotspotQuery {
+    /// Only count reports whose address is in this city (case-insensitive)
+    #[param(example = "London")]
+    pub city: Option<String>,
+    /// Days in the current window, compared with the same number of days before it
+    /// (default 30)
+    #[param(example = 30, minimum = 1, maximum = 365)]
+    pub window_days: Option<i32>,
+    /// Cell shape, `hex` (default) or `grid`
+    pub shape: Option<HotspotShape>,
+    /// Width of a cell in metres (default 500)
+    #[param(example = 500, minimum = 100, maximum = 10000)]
+    pub cell_size_m: Option<f64>,
+    /// How many hotspots to return (default 10)
+    #[param(example = 10, minimum = 1, maximum = 50)]
+    pub limit: Option<i64>,
+}
+
+/// Whether a hotspot is getting worse, comparing the current window with the one before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HotspotTrend {
+    Rising,
+    Steady,
+    Falling,
+}
+
+impl HotspotTrend {
+    /// A change of a quarter or more either way counts; a cell new to the map is rising
+    #[must_use]
+    pub fn from_counts(recent: i64, previous: i64) -> Self {
+        if previous == 0 {
+            return if recent > 0 {
+                Self::Rising
+            } else {
+                Self::Steady
+            };
+        }
+        let ratio = recent as f64 / previous as f64;
+        if ratio >= 1.25 {
+            Self::Rising
+        } else if ratio <= 0.75 {
+            Self::Falling
+        } else {
+            Self::Steady
+        }
+    }
+}
+
+/// One cell with reports in the current window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Hotspot {
+    #[schema(example = 51.5074)]
+    pub center_latitude: f64,
+    #[schema(example = -0.1278)]
+    pub center_longitude: f64,
+    /// The cell's outline as `[longitude, latitude]` corners, the first repeated at the end
+    pub boundary: Vec<[f64; 2]>,
+    /// Reports made in the current window
+    #[schema(example = 14)]
+    pub reports: i64,
+    /// Reports made in the window before it
+    #[schema(example = 9)]
+    pub previous_reports: i64,
+    /// Reports from either window still pending or claimed
+    #[schema(example = 6)]
+    pub open_reports: i64,
+    pub trend: HotspotTrend,
+    /// Change from the previous window; absent when it had no reports
+    #[schema(example = 55.6)]
+    pub change_percent: Option<f64>,
+}
+
+/// The busiest cells, most reports first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HotspotsResponse {
+    pub city: Option<String>,
+    #[schema(example = 30)]
+    pub window_days: i32,
+    pub shape: HotspotShape,
+    #[schema(example = 500)]
+    pub cell_size_m: f64,
+    pub hotspots: Vec<Hotspot>,
+    pub computed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LocationHistoryQuery {
+    #[param(value_type = f64, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub lat: Latitude,
+    #[param(value_type = f64, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub lon: Longitude,
+    /// How close a report must be to count as the same spot, in metres (default 50)
+    #[param(example = 50, minimum = 10, maximum = 500)]
+    pub radius: Option<f64>,
+}
+
+/// Reports made and cleared at a spot in one calendar month (UTC)
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct LocationHistoryMonth {
+    /// First day of the month
+    #[schema(example = "2024-05-01")]
+    pub month: NaiveDate,
+    #[schema(example = 2)]
+    pub reported: i64,
+    #[schema(example = 1)]
+    pub cleared: i64,
+}
+
+/// How often litter has turned up at one spot. Cancelled and hidden reports don't count.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LocationHistory {
+    #[schema(example = 51.5074)]
+    pub latitude: f64,
+    #[schema(example = -0.1278)]
+    pub longitude: f64,
+    #[schema(example = 50)]
+    pub radius_m: f64,
+    /// Reports ever made here
+    #[schema(example = 7)]
+    pub reports: i64,
+    /// Of those, how many were cleared
+    #[schema(example = 6)]
+    pub cleared: i64,
+    /// Still pending or claimed
+    #[schema(example = 1)]
+    pub open_reports: i64,
+    pub first_reported_at: Option<DateTime<Utc>>,
+    pub last_reported_at: Option<DateTime<Utc>>,
+    /// Litter keeps coming back: enough reports here in the recent window
+    pub repeat_offender: bool,
+    /// The last 12 months with any activity, oldest first
+    pub months: Vec<LocationHistoryMonth>,
+}
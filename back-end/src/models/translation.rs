@@ -0,0 +1,44 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// A report description in another language
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DescriptionTranslation {
+    #[schema(example = "Broken glass by the bus stop")]
+    pub text: String,
+    /// The description's language as detected by the provider, when it says
+    #[schema(example = "es")]
+    pub source_language: Option<String>,
+    #[schema(example = "en")]
+    pub target_language: String,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct GetReportQuery {
+    /// Also translate the description into this language, e.g. `en` or `pt-BR`
+    #[param(example = "en")]
+    pub translate_to: Option<String>,
+}
+
+/// Check a language code is `xx` or `xx-YY` and normalise its case, e.g. `PT-br` to
+/// `pt-BR`
+pub fn parse_language_code(code: &str) -> Result<String, AppError> {
+    let invalid = || {
+        AppError::BadRequest(format!(
+            "Invalid language code '{code}'; expected e.g. 'en' or 'pt-BR'"
+        ))
+    };
+    let is_letters = |part: &str| part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic());
+
+    let code = code.trim();
+    match code.split_once('-') {
+        None if is_letters(code) => Ok(code.to_ascii_lowercase()),
+        Some((language, region)) if is_letters(language) && is_letters(region) => Ok(format!(
+            "{}-{}",
+            language.to_ascii_lowercase(),
+            region.to_ascii_uppercase()
+        )),
+        _ => Err(invalid()),
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Returned when setup starts; the secret is added to an authenticator app
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorSetupResponse {
+    /// Base32 secret for manual entry
+    #[schema(example = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP")]
+    pub secret: String,
+    /// otpauth:// URI for QR codes
+    #[schema(
+        example = "otpauth://totp/LittyPicky:user%40example.com?secret=...&issuer=LittyPicky"
+    )]
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+}
+
+/// A code from the authenticator app, or a backup code where noted
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorCodeRequest {
+    #[schema(example = "123456")]
+    pub code: String,
+}
+
+/// Shown once when two-factor is enabled
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorBackupCodesResponse {
+    #[schema(example = json!(["k7m2p-9xq4r", "a3hf8-w2n6t"]))]
+    pub backup_codes: Vec<String>,
+}
+
+/// Login passed the password check but still needs a second factor
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TwoFactorChallenge {
+    /// Always true; lets clients tell this apart from `AuthTokens`
+    pub two_factor_required: bool,
+    #[schema(example = "VGhpc0lzQVRva2Vu...")]
+    pub challenge_token: String,
+    /// Seconds until the challenge expires
+    #[schema(example = 300)]
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorLoginRequest {
+    #[schema(example = "VGhpc0lzQVRva2Vu...")]
+    pub challenge_token: String,
+    /// TOTP code or an unused backup code
+    #[schema(example = "123456")]
+    pub code: String,
+}
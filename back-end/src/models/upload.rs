@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// A resumable chunked upload (bytes still waiting for a full S3 part are not loaded)
+#[derive(Debug, Clone, FromRow)]
+pub struct PhotoUpload {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub s3_key: String,
+    pub s3_upload_id: String,
+    pub total_size: i64,
+    pub received_bytes: i64,
+    pub next_part_number: i32,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateUploadRequest {
+    /// Size of the complete photo in bytes
+    #[schema(example = 3_145_728)]
+    pub total_size: i64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AppendChunkQuery {
+    /// Byte offset of this chunk; must equal the upload's `received_bytes`
+    #[param(example = 0)]
+    pub offset: i64,
+}
+
+/// Progress of a chunked upload. After a dropped connection, fetch this and
+/// resume sending from `received_bytes`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UploadStatusResponse {
+    pub upload_id: Uuid,
+    pub total_size: i64,
+    pub received_bytes: i64,
+    /// Largest chunk accepted by the append endpoint
+    #[schema(example = 1_048_576)]
+    pub max_chunk_size: i64,
+    /// Set once complete; the upload id can then be used in place of `photo_base64`
+    pub completed: bool,
+    pub expires_at: DateTime<Utc>,
+}
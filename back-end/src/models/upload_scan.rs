@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// An upload the malware scanner flagged, held back from storage
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct QuarantinedUpload {
+    pub id: Uuid,
+    /// SHA-256 of the uploaded bytes
+    pub content_hash: String,
+    pub size_bytes: i64,
+    /// What the scanner found
+    #[schema(example = "Win.Test.EICAR_HDB-1")]
+    pub signature: String,
+    #[schema(example = "clamav")]
+    pub scanner: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Scan outcomes since the server started; each instance counts its own uploads
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct UploadScanCounts {
+    pub scanned: u64,
+    pub clean: u64,
+    pub infected: u64,
+    /// Scans that could not finish, e.g. because the scanner was unreachable
+    pub errors: u64,
+    /// Uploads let through without a verdict because scanning fails open
+    pub skipped: u64,
+}
+
+/// Whether uploads are scanned, how scans have gone and what is in quarantine
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UploadScanReport {
+    /// `None` when scanning is switched off
+    #[schema(example = "clamav")]
+    pub scanner: Option<String>,
+    pub counts: UploadScanCounts,
+    /// Most recent first
+    pub quarantined: Vec<QuarantinedUpload>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct QuarantineQuery {
+    /// How many quarantined uploads to list, newest first (default 20)
+    pub limit: Option<i64>,
+}
@@ -3,20 +3,69 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
+use validator::Validate;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "user_role", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum UserRole {
     User,
+    /// Moderates community content but cannot manage accounts or the system
+    Moderator,
     Admin,
 }
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, ToSchema)]
+/// Privileged actions, granted to roles by `UserRole::has_permission`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Remove any user's feed posts
+    DeleteFeedPosts,
+    /// Review and resolve flagged accounts
+    ResolveFlags,
+    /// View accounts, clear email suppressions and impersonate users
+    ManageUsers,
+    /// Ban and unban accounts
+    BanUsers,
+    /// Change other users' roles
+    ManageRoles,
+    /// View, delete, reassign and override reports
+    ManageReports,
+    /// Email templates, webhook events, API keys and maintenance jobs
+    ManageSystem,
+}
+
+impl UserRole {
+    /// The permissions matrix: admins may do everything, moderators only moderate content
+    #[must_use]
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        match self {
+            Self::Admin => true,
+            Self::Moderator => matches!(
+                permission,
+                Permission::DeleteFeedPosts | Permission::ResolveFlags
+            ),
+            Self::User => false,
+        }
+    }
+
+    /// The role's name as stored in the database and in access tokens
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Moderator => "moderator",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+/// An account row. Never serialized itself: responses go through
+/// [`PublicUserProfile`], [`SelfUserProfile`] or [`AdminUserView`], each with an explicit
+/// field set.
+#[derive(Debug, Clone, FromRow)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
-    #[serde(skip_serializing)]
     pub password_hash: Option<String>,
     pub full_name: String,
     pub city: String,
@@ -54,8 +103,35 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// What other users may see of an account: no contact details or account state
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicUserProfile {
+    pub id: Uuid,
+    #[schema(example = "John Doe")]
+    pub full_name: String,
+    #[schema(example = "London")]
+    pub city: String,
+    #[schema(example = "UK")]
+    pub country: String,
+    /// When the account was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<User> for PublicUserProfile {
+    fn from(user: User) -> Self {
+        PublicUserProfile {
+            id: user.id,
+            full_name: user.full_name,
+            city: user.city,
+            country: user.country,
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// The signed-in user's own account
 #[derive(Debug, Serialize, ToSchema)]
-pub struct UserResponse {
+pub struct SelfUserProfile {
     pub id: Uuid,
     pub email: String,
     pub full_name: String,
@@ -64,12 +140,65 @@ pub struct UserResponse {
     pub search_radius_km: i32,
     pub role: UserRole,
     pub email_verified: bool,
+    /// Linked sign-in provider, e.g. "google"
+    pub oauth_provider: Option<String>,
+    /// False for accounts that can only sign in through their provider
+    pub has_password: bool,
     pub created_at: DateTime<Utc>,
 }
 
-impl From<User> for UserResponse {
+impl From<User> for SelfUserProfile {
     fn from(user: User) -> Self {
-        UserResponse {
+        SelfUserProfile {
+            id: user.id,
+            email: user.email,
+            full_name: user.full_name,
+            city: user.city,
+            country: user.country,
+            search_radius_km: user.search_radius_km,
+            role: user.role,
+            email_verified: user.email_verified,
+            has_password: user.password_hash.is_some(),
+            oauth_provider: user.oauth_provider,
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// An account as admins see it, including its state and email deliverability
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminUserView {
+    pub id: Uuid,
+    pub email: String,
+    pub full_name: String,
+    pub city: String,
+    pub country: String,
+    pub search_radius_km: i32,
+    pub role: UserRole,
+    /// False while the account is banned
+    pub is_active: bool,
+    pub email_verified: bool,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    /// Linked sign-in provider, e.g. "google"
+    pub oauth_provider: Option<String>,
+    /// False for accounts that can only sign in through their provider
+    pub has_password: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// False once the address has hard-bounced or complained; no mail is sent to it
+    pub email_deliverable: bool,
+    /// "bounce" or "complaint" when the address is suppressed
+    #[schema(example = "bounce")]
+    pub email_suppression_reason: Option<String>,
+    pub email_suppressed_at: Option<DateTime<Utc>>,
+}
+
+impl AdminUserView {
+    /// The admin view of `user`, with the reason and time its address was suppressed
+    #[must_use]
+    pub fn new(user: User, suppression: Option<(String, DateTime<Utc>)>) -> Self {
+        let (email_suppression_reason, email_suppressed_at) = suppression.unzip();
+        AdminUserView {
             id: user.id,
             email: user.email,
             full_name: user.full_name,
@@ -77,8 +206,16 @@ impl From<User> for UserResponse {
             country: user.country,
             search_radius_km: user.search_radius_km,
             role: user.role,
+            is_active: user.is_active,
             email_verified: user.email_verified,
+            email_verified_at: user.email_verified_at,
+            has_password: user.password_hash.is_some(),
+            oauth_provider: user.oauth_provider,
             created_at: user.created_at,
+            updated_at: user.updated_at,
+            email_deliverable: email_suppression_reason.is_none(),
+            email_suppression_reason,
+            email_suppressed_at,
         }
     }
 }
@@ -101,5 +238,64 @@ pub struct AuthTokens {
     pub access_token: String,
     #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
     pub refresh_token: String,
-    pub user: UserResponse,
+    pub user: SelfUserProfile,
+}
+
+/// Proof of identity for sensitive account changes
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReauthenticateRequest {
+    #[schema(example = "SecurePassword123")]
+    pub password: String,
+    /// TOTP or backup code, required when two-factor is enabled
+    #[schema(example = "123456")]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct EmailChangeRequest {
+    #[validate(email)]
+    #[schema(example = "new-address@example.com")]
+    pub new_email: String,
+    /// Current password, to re-authenticate
+    #[schema(example = "SecurePassword123")]
+    pub password: String,
+    /// TOTP or backup code, required when two-factor is enabled
+    #[schema(example = "123456")]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[schema(example = "SecurePassword123")]
+    pub current_password: String,
+    #[validate(length(min = 8))]
+    #[schema(example = "NewSecurePassword123", min_length = 8)]
+    pub new_password: String,
+    /// TOTP or backup code, required when two-factor is enabled
+    #[schema(example = "123456")]
+    pub code: Option<String>,
+}
+
+/// Start adding a password to an account that signs in only with a provider
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordSetupRequest {
+    /// TOTP or backup code, required when two-factor is enabled
+    #[schema(example = "123456")]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SetPasswordRequest {
+    /// Token from the emailed password setup link
+    #[schema(example = "VGhpc0lzQVRva2Vu...")]
+    pub token: String,
+    #[validate(length(min = 8))]
+    #[schema(example = "NewSecurePassword123", min_length = 8)]
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmEmailChangeRequest {
+    #[schema(example = "VGhpc0lzQVRva2Vu...")]
+    pub token: String,
 }
@@ -1,3 +1,4 @@
+use crate::models::image::ImageMetadata;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -10,7 +11,12 @@ pub struct ReportVerification {
     pub report_id: Uuid,
     pub verifier_id: Uuid,
     pub is_verified: bool,
+    pub requested_better_photo: bool,
     pub comment: Option<String>,
+    pub photo: Option<String>,
+    pub photo_width: Option<i32>,
+    pub photo_height: Option<i32>,
+    pub photo_blurhash: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -18,8 +24,31 @@ pub struct ReportVerification {
 pub struct CreateVerificationRequest {
     #[schema(example = true)]
     pub is_verified: bool,
+    /// Ask the clearer for a better after photo instead of judging this one; requires
+    /// `is_verified: false`. The report's tally restarts once requested.
+    #[serde(default)]
+    pub request_better_photo: bool,
     #[schema(example = "Good job!")]
     pub comment: Option<String>,
+    /// A photo taken on site backing the verdict, as base64; earns extra points
+    pub photo_base64: Option<String>,
+}
+
+/// How an admin settles a clear, overriding the community tally
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationVerdict {
+    Verified,
+    Rejected,
+}
+
+/// A verifier's hold on one of a cleared report's open verification slots
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct VerificationReservation {
+    pub report_id: Uuid,
+    pub reserved_at: DateTime<Utc>,
+    /// The slot is released to others after this
+    pub expires_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -28,18 +57,31 @@ pub struct VerificationResponse {
     pub report_id: Uuid,
     pub verifier_id: Uuid,
     pub is_verified: bool,
+    pub requested_better_photo: bool,
     pub comment: Option<String>,
+    /// The verifier's on-site photo, when they attached one
+    pub photo: Option<ImageMetadata>,
     pub created_at: DateTime<Utc>,
 }
 
 impl From<ReportVerification> for VerificationResponse {
     fn from(verification: ReportVerification) -> Self {
+        let photo = verification.photo.map(|url| ImageMetadata {
+            url,
+            width: verification.photo_width,
+            height: verification.photo_height,
+            blurhash: verification.photo_blurhash,
+            processing: false,
+        });
+
         VerificationResponse {
             id: verification.id,
             report_id: verification.report_id,
             verifier_id: verification.verifier_id,
             is_verified: verification.is_verified,
+            requested_better_photo: verification.requested_better_photo,
             comment: verification.comment,
+            photo,
             created_at: verification.created_at,
         }
     }
@@ -0,0 +1,127 @@
+use crate::error::AppError;
+use crate::models::coordinates::{Coordinates, Latitude, Longitude};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Largest radius a watched circle may have
+pub const MAX_WATCHED_RADIUS_KM: f64 = 25.0;
+
+/// Most corners a watched polygon may have
+pub const MAX_WATCHED_POLYGON_POINTS: usize = 100;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct WatchedArea {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub radius_km: Option<f64>,
+    /// The polygon's outer ring, set only for polygon areas
+    pub polygon: Option<Json<Vec<[f64; 2]>>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A watched area: a circle (`latitude`, `longitude`, `radius_km`) or a `polygon`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WatchedAreaResponse {
+    pub id: Uuid,
+    #[schema(example = "Around the park")]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 51.5074)]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = -0.1278)]
+    pub longitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 1.5)]
+    pub radius_km: Option<f64>,
+    /// `[longitude, latitude]` corners, with the first repeated at the end
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub polygon: Option<Vec<[f64; 2]>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WatchedArea> for WatchedAreaResponse {
+    fn from(area: WatchedArea) -> Self {
+        WatchedAreaResponse {
+            id: area.id,
+            name: area.name,
+            latitude: area.latitude,
+            longitude: area.longitude,
+            radius_km: area.radius_km,
+            polygon: area.polygon.map(|Json(ring)| ring),
+            created_at: area.created_at,
+        }
+    }
+}
+
+/// Give either a centre and radius or a polygon, not both
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateWatchedAreaRequest {
+    #[validate(length(min = 1, max = 100))]
+    #[schema(example = "Around the park")]
+    pub name: String,
+    #[schema(value_type = Option<f64>, example = 51.5074, minimum = -90.0, maximum = 90.0)]
+    pub latitude: Option<Latitude>,
+    #[schema(value_type = Option<f64>, example = -0.1278, minimum = -180.0, maximum = 180.0)]
+    pub longitude: Option<Longitude>,
+    #[validate(range(min = 0.1, max = 25.0))]
+    #[schema(example = 1.5, minimum = 0.1, maximum = 25.0)]
+    pub radius_km: Option<f64>,
+    /// 3 to 100 `[longitude, latitude]` corners, as in GeoJSON; the ring is closed for you
+    #[schema(example = json!([[-0.13, 51.50], [-0.12, 51.50], [-0.12, 51.51]]))]
+    pub polygon: Option<Vec<[f64; 2]>>,
+}
+
+/// The shape of a watched area, checked
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchedAreaShape {
+    Circle {
+        center: Coordinates,
+        radius_km: f64,
+    },
+    /// A closed ring: the first corner is repeated at the end
+    Polygon(Vec<Coordinates>),
+}
+
+impl CreateWatchedAreaRequest {
+    /// The area asked for, or why it can't be watched
+    pub fn shape(&self) -> Result<WatchedAreaShape, AppError> {
+        match (self.latitude, self.longitude, self.radius_km, &self.polygon) {
+            (Some(latitude), Some(longitude), Some(radius_km), None) => {
+                Ok(WatchedAreaShape::Circle {
+                    center: Coordinates::new(latitude, longitude),
+                    radius_km,
+                })
+            }
+            (None, None, None, Some(points)) => {
+                let mut ring = points
+                    .iter()
+                    .map(|[longitude, latitude]| {
+                        Coordinates::try_from_degrees(*latitude, *longitude)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if ring.first() != ring.last() || ring.len() == 1 {
+                    ring.extend(ring.first().copied());
+                }
+                // Three distinct corners plus the repeated first one
+                if ring.len() < 4 || ring.len() > MAX_WATCHED_POLYGON_POINTS + 1 {
+                    return Err(AppError::BadRequest(format!(
+                        "A polygon needs 3 to {MAX_WATCHED_POLYGON_POINTS} corners"
+                    )));
+                }
+                Ok(WatchedAreaShape::Polygon(ring))
+            }
+            _ => Err(AppError::BadRequest(
+                "Give either latitude, longitude and radius_km or a polygon".to_string(),
+            )),
+        }
+    }
+}
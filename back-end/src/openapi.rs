@@ -19,46 +19,185 @@ use utoipa::OpenApi;
         crate::handlers::auth::register,
         crate::handlers::auth::login,
         crate::handlers::auth::verify_email,
+        crate::handlers::auth::confirm_account_link,
         crate::handlers::auth::resend_verification,
+        crate::handlers::auth::unsubscribe_verification_reminders,
         crate::handlers::auth::forgot_password,
         crate::handlers::auth::reset_password,
         crate::handlers::auth::refresh_token,
         crate::handlers::auth::logout,
+        crate::handlers::auth::logout_all,
+        crate::handlers::auth::create_service_token,
+        crate::handlers::auth::request_magic_link,
+        crate::handlers::auth::verify_magic_link,
+        crate::handlers::auth::change_password,
+        crate::handlers::auth::request_password_setup,
+        crate::handlers::auth::set_password,
+        crate::handlers::auth::request_email_change,
+        crate::handlers::auth::confirm_email_change,
+        crate::handlers::auth::list_sessions,
+        crate::handlers::auth::list_login_history,
+        crate::handlers::auth::revoke_session,
+        // Two-factor endpoints
+        crate::handlers::auth::two_factor_login,
+        crate::handlers::two_factor::get_two_factor_status,
+        crate::handlers::two_factor::setup_two_factor,
+        crate::handlers::two_factor::verify_two_factor,
+        crate::handlers::two_factor::disable_two_factor,
         // OAuth endpoints
         crate::handlers::oauth::google_login,
         crate::handlers::oauth::google_callback,
+        crate::handlers::oauth::link_oauth_provider,
+        crate::handlers::auth::unlink_oauth_provider,
         // User endpoints
         crate::handlers::users::get_current_user,
         crate::handlers::users::update_current_user,
         crate::handlers::users::get_current_user_score,
+        crate::handlers::users::get_user_profile,
+        // Saved search endpoints
+        crate::handlers::saved_searches::list_saved_searches,
+        crate::handlers::saved_searches::create_saved_search,
+        crate::handlers::saved_searches::get_saved_search,
+        crate::handlers::saved_searches::update_saved_search,
+        crate::handlers::saved_searches::delete_saved_search,
+        crate::handlers::watched_areas::list_watched_areas,
+        crate::handlers::watched_areas::create_watched_area,
+        crate::handlers::watched_areas::delete_watched_area,
+        crate::handlers::home_areas::get_home_area,
+        crate::handlers::home_areas::set_home_area,
+        crate::handlers::home_areas::delete_home_area,
         // Report endpoints
         crate::handlers::reports::create_report,
+        crate::handlers::reports::create_anonymous_report,
+        crate::handlers::reports::claim_anonymous_report,
         crate::handlers::reports::get_nearby_reports,
+        crate::handlers::reports::get_reports_in_bounds,
+        crate::handlers::reports::get_report_clusters,
+        crate::handlers::reports::get_verification_queue,
         crate::handlers::reports::get_my_reports,
         crate::handlers::reports::get_my_cleared_reports,
         crate::handlers::reports::get_report,
+        crate::handlers::reports::update_report,
+        crate::handlers::reports::cancel_report,
+        crate::handlers::reports::flag_report,
         crate::handlers::reports::claim_report,
+        crate::handlers::reports::unclaim_report,
+        crate::handlers::reports::get_report_timeline,
+        crate::handlers::reports::get_report_history,
+        crate::handlers::reports::claim_report_batch,
+        crate::handlers::reports::get_cleanup_run,
+        // Cleanup event endpoints
+        crate::handlers::cleanup_events::list_cleanup_events,
+        crate::handlers::cleanup_events::create_cleanup_event,
+        crate::handlers::cleanup_events::get_cleanup_event,
+        crate::handlers::cleanup_events::rsvp_cleanup_event,
+        crate::handlers::cleanup_events::cancel_cleanup_event_rsvp,
         crate::handlers::reports::clear_report,
+        crate::handlers::reports::replace_after_photo,
+        crate::handlers::reports::get_report_photos,
+        crate::handlers::reports::add_report_photo,
+        // Personal data export endpoints
+        crate::handlers::exports::request_data_export,
+        crate::handlers::exports::get_data_export,
+        crate::handlers::exports::download_data_export,
+        // Chunked upload endpoints
+        crate::handlers::uploads::create_upload,
+        crate::handlers::uploads::get_upload,
+        crate::handlers::uploads::append_upload_chunk,
+        crate::handlers::uploads::complete_upload,
+        crate::handlers::uploads::cancel_upload,
+        // Feed endpoints
+        crate::handlers::feed::create_post,
+        crate::handlers::feed::get_feed,
+        crate::handlers::feed::get_post,
+        crate::handlers::feed::update_post,
+        crate::handlers::feed::delete_post,
+        crate::handlers::feed::create_comment,
+        crate::handlers::feed::get_comments,
+        crate::handlers::feed::update_comment,
+        crate::handlers::feed::delete_comment,
+        crate::handlers::feed::like_post,
+        crate::handlers::feed::unlike_post,
+        crate::handlers::feed::record_dwell,
         // Image endpoints
         crate::handlers::images::get_report_before_photo,
         crate::handlers::images::get_report_after_photo,
+        crate::handlers::images::get_report_photo_at,
+        crate::handlers::images::get_report_photo_urls,
         // Verification endpoints
         crate::handlers::verifications::verify_report,
+        crate::handlers::verifications::reserve_verification,
+        crate::handlers::verifications::release_verification,
         crate::handlers::verifications::get_report_verifications,
         // Leaderboard endpoints
         crate::handlers::leaderboards::get_global_leaderboard,
+        crate::handlers::stats::get_stats_summary,
+        crate::handlers::stats::get_hotspots,
+        crate::handlers::stats::get_location_history,
+        crate::handlers::scoring::get_scoring_rules,
+        crate::handlers::city_goals::list_city_goals,
+        crate::handlers::city_goals::get_city_goal,
+        crate::handlers::open_data::get_open_data_reports,
+        crate::handlers::capabilities::get_capabilities,
         crate::handlers::leaderboards::get_city_leaderboard,
         crate::handlers::leaderboards::get_country_leaderboard,
+        // Notification endpoints
+        crate::handlers::notifications::poll_notifications,
         // Admin endpoints
         crate::handlers::admin::list_users,
         crate::handlers::admin::get_user_by_id,
+        crate::handlers::admin::clear_email_suppression,
+        crate::handlers::admin::list_account_flags,
+        crate::handlers::admin::resolve_account_flag,
+        crate::handlers::admin::list_flagged_reports,
+        crate::handlers::admin::list_report_flags,
+        crate::handlers::admin::review_report_flags,
+        crate::handlers::admin::moderate_delete_feed_post,
         crate::handlers::admin::toggle_user_ban,
+        crate::handlers::admin::set_user_role,
         crate::handlers::admin::list_all_reports,
         crate::handlers::admin::delete_report,
+        crate::handlers::admin::purge_user_reports,
+        crate::handlers::admin::reassign_report,
+        crate::handlers::admin::override_report_status,
+        crate::handlers::admin::override_report_verification,
+        crate::handlers::admin::reactivate_report,
+        crate::handlers::admin::list_report_edits,
+        crate::handlers::admin::get_admin_report_history,
+        crate::handlers::admin::import_reports,
+        crate::handlers::admin::get_report_import,
+        crate::handlers::admin::get_geo_stats,
+        crate::handlers::admin::preview_email,
+        crate::handlers::admin::send_test_email,
+        crate::handlers::admin::list_events,
+        crate::handlers::admin::get_event,
+        crate::handlers::admin::retry_event,
+        crate::handlers::admin::list_api_keys,
+        crate::handlers::admin::create_api_key,
+        crate::handlers::admin::update_api_key,
+        crate::handlers::admin::revoke_api_key,
+        crate::handlers::admin::admin_list_city_goals,
+        crate::handlers::admin::create_city_goal,
+        crate::handlers::admin::update_city_goal,
+        crate::handlers::admin::delete_city_goal,
+        crate::handlers::admin::impersonate_user,
+        crate::handlers::admin::list_impersonations,
+        crate::handlers::admin::run_consistency_check,
+        crate::handlers::admin::list_consistency_checks,
+        crate::handlers::admin::get_perf_report,
+        crate::handlers::admin::get_upload_quarantine,
+        crate::handlers::admin::download_quarantined_upload,
+        crate::handlers::admin::delete_quarantined_upload,
+        // Webhook endpoints
+        crate::handlers::email_webhooks::receive_email_webhook,
         // Test helper endpoints
         crate::handlers::test_helpers::verify_email_for_testing,
         crate::handlers::test_helpers::cleanup_test_data,
         crate::handlers::test_helpers::test_status,
+        crate::handlers::test_helpers::create_test_users,
+        crate::handlers::test_helpers::create_swagger_session,
+        crate::handlers::test_helpers::create_test_report,
+        crate::handlers::test_helpers::fast_forward_clears,
     ),
     components(
         schemas(
@@ -69,57 +208,429 @@ use utoipa::OpenApi;
             crate::handlers::auth::RefreshTokenResponse,
             crate::models::user::LoginRequest,
             crate::models::user::AuthTokens,
-            crate::models::user::UserResponse,
+            crate::models::user::PublicUserProfile,
+            crate::models::user::SelfUserProfile,
             crate::models::user::UpdateUserRequest,
-            crate::models::user::User,
             crate::models::user::UserRole,
             crate::models::email_token::VerifyEmailRequest,
+            crate::models::email_token::UnsubscribeRemindersRequest,
+            crate::models::email_token::ConfirmAccountLinkRequest,
+            crate::models::email_token::MagicLinkRequest,
+            crate::models::email_token::VerifyMagicLinkRequest,
+            crate::models::session::SessionResponse,
+            crate::models::service_token::TokenScope,
+            crate::models::service_token::CreateServiceTokenRequest,
+            crate::models::service_token::ServiceTokenResponse,
+            crate::models::login_event::LoginEvent,
+            crate::models::two_factor::TwoFactorSetupResponse,
+            crate::models::two_factor::TwoFactorStatusResponse,
+            crate::models::two_factor::TwoFactorCodeRequest,
+            crate::models::two_factor::TwoFactorBackupCodesResponse,
+            crate::models::two_factor::TwoFactorChallenge,
+            crate::models::two_factor::TwoFactorLoginRequest,
             crate::models::email_token::ResendVerificationRequest,
             crate::models::email_token::ForgotPasswordRequest,
             crate::models::email_token::ResetPasswordRequest,
             // OAuth models
             crate::handlers::oauth::OAuthLoginResponse,
+            crate::handlers::oauth::LinkOAuthRequest,
+            crate::models::user::ReauthenticateRequest,
+            crate::models::user::ChangePasswordRequest,
+            crate::models::user::PasswordSetupRequest,
+            crate::models::user::SetPasswordRequest,
+            crate::models::user::EmailChangeRequest,
+            crate::models::user::ConfirmEmailChangeRequest,
             // User models
             crate::handlers::users::UserScoreRecord,
+            crate::models::saved_search::SavedSearchResponse,
+            crate::models::saved_search::CreateSavedSearchRequest,
+            crate::models::saved_search::UpdateSavedSearchRequest,
+            crate::models::watched_area::WatchedAreaResponse,
+            crate::models::watched_area::CreateWatchedAreaRequest,
+            crate::models::home_area::SetHomeAreaRequest,
+            crate::models::home_area::HomeAreaDashboard,
+            crate::models::home_area::AreaClear,
+            crate::models::home_area::AreaLeaderboardPosition,
+            crate::models::home_area::AreaBoostZone,
             // Report models
             crate::models::report::CreateReportRequest,
+            crate::models::report::CreateAnonymousReportRequest,
+            crate::models::report::AnonymousReportResponse,
+            crate::models::report::ClaimAnonymousReportRequest,
             crate::models::report::ClearReportRequest,
+            crate::models::translation::DescriptionTranslation,
             crate::models::report::LitterReport,
             crate::models::report::ReportResponse,
+            crate::models::report::ReportCluster,
+            crate::models::report::ReportsInBoundsResponse,
+            crate::models::report::ReportClustersResponse,
+            crate::models::geojson::ReportFeatureCollection,
+            crate::models::geojson::Feature,
+            crate::models::geojson::FeatureProperties,
+            crate::models::geojson::PointGeometry,
+            crate::models::geojson::OutputFormat,
+            crate::models::report::AddressComponents,
             crate::models::report::ReportStatus,
+            crate::models::report::LitterCategory,
+            crate::models::report::LitterSeverity,
+            crate::models::report::ReportSort,
+            crate::models::report::AdminReportView,
+            crate::models::report_claim::TimelineEventKind,
+            crate::models::report_claim::TimelineEvent,
+            crate::models::report_claim::ReportTimelineResponse,
+            crate::models::report_lifecycle::ReportEvent,
+            crate::models::report_lifecycle::ReportEventKind,
+            crate::models::report_lifecycle::ReportHistoryResponse,
+            crate::models::report_import::ReportImportRow,
+            crate::models::report_import::ReportImportRequest,
+            crate::models::report_import::ReportImportResult,
+            crate::models::report_import::ReportImportResponse,
+            crate::models::report_claim::UnclaimReportRequest,
+            crate::models::report_edit::UpdateReportRequest,
+            crate::models::report_edit::ReportEdit,
+            crate::models::report_flag::ReportFlagReason,
+            crate::models::report_flag::FlagReportRequest,
+            crate::models::report_flag::ReportFlag,
+            crate::models::report_flag::FlagReportResponse,
+            crate::models::report_flag::FlaggedReportView,
+            crate::models::report_flag::FlagReviewDecision,
+            crate::models::report_flag::ReviewReportFlagsRequest,
+            crate::models::report_photo::PhotoKind,
+            crate::models::report_photo::ReportPhotos,
+            crate::models::report_photo::ReportPhotoUrls,
+            crate::models::report_photo::AddReportPhotoRequest,
+            crate::models::cleanup_run::ClaimBatchRequest,
+            crate::models::cleanup_run::BatchClaimResult,
+            crate::models::cleanup_run::ClaimBatchResponse,
+            crate::models::cleanup_run::CleanupRunResponse,
+            crate::models::cleanup_event::CleanupEventResponse,
+            crate::models::cleanup_event::CleanupEventStatus,
+            crate::models::cleanup_event::CreateCleanupEventRequest,
+            crate::models::image::ImageMetadata,
+            crate::models::data_export::DataExportResponse,
+            crate::models::upload::CreateUploadRequest,
+            crate::models::upload::UploadStatusResponse,
+            // Feed models
+            crate::models::feed::FeedPostResponse,
+            crate::models::feed::FeedCommentResponse,
+            crate::models::feed::FeedComment,
+            crate::models::feed::CreateFeedPostRequest,
+            crate::models::feed::UpdateFeedPostRequest,
+            crate::models::feed::CreateFeedCommentRequest,
+            crate::models::feed::UpdateFeedCommentRequest,
+            crate::models::feed::RecordDwellRequest,
+            crate::models::feed::FeedSort,
             // Verification models
             crate::models::verification::CreateVerificationRequest,
             crate::models::verification::VerificationResponse,
+            crate::models::verification::VerificationReservation,
+            crate::models::verification::VerificationVerdict,
             crate::models::verification::ReportVerification,
             // Score models
             crate::models::score::UserScore,
             crate::models::score::ScoreResponse,
             crate::models::score::LeaderboardEntry,
+            crate::models::score::LeaderboardPeriod,
+            crate::models::score::Leaderboard,
+            crate::models::stats::StatsSummary,
+            crate::models::stats::HotspotShape,
+            crate::models::stats::HotspotTrend,
+            crate::models::stats::Hotspot,
+            crate::models::stats::HotspotsResponse,
+            crate::models::stats::LocationHistory,
+            crate::models::stats::LocationHistoryMonth,
+            crate::models::scoring_rules::ScoringRulesResponse,
+            crate::models::scoring_rules::ScoringRule,
+            crate::models::scoring_rules::ScoringRuleKey,
+            crate::models::scoring_rules::ScoringRuleCategory,
+            crate::models::scoring_rules::ScoringRuleUnit,
+            crate::models::capabilities::Capabilities,
+            crate::models::capabilities::Capability,
+            crate::models::capabilities::FeedCapability,
+            crate::models::capabilities::CaptchaCapability,
+            crate::models::open_data::OpenDataFormat,
+            crate::models::open_data::OpenDataReport,
+            crate::models::open_data::OpenDataDailyAggregate,
+            crate::models::open_data::OpenDataResponse,
+            // Notification models
+            crate::models::notification::Notification,
+            crate::models::notification::NotificationPollResponse,
             // Admin models
             crate::handlers::admin::BanUserRequest,
-            crate::handlers::admin::AdminReportView,
-            crate::handlers::admin::ListUsersQuery,
+            crate::handlers::admin::SetUserRoleRequest,
+            crate::models::dry_run::ChangeSummary,
+            crate::handlers::admin::ReassignReportRequest,
+            crate::handlers::admin::OverrideReportStatusRequest,
+            crate::handlers::admin::VerificationOverrideRequest,
+            crate::models::user::AdminUserView,
+            crate::handlers::admin::BanUserResponse,
+            crate::handlers::admin::AdminAccountFlagView,
+            crate::handlers::admin::GeoAreaStats,
+            crate::handlers::admin::EmailPreviewResponse,
+            crate::handlers::admin::SendTestEmailRequest,
+            crate::models::api_key::ApiKeyScope,
+            crate::models::api_key::ApiKeyResponse,
+            crate::models::api_key::CreatedApiKeyResponse,
+            crate::models::api_key::CreateApiKeyRequest,
+            crate::models::city_goal::CityGoalProgress,
+            crate::models::city_goal::CreateCityGoalRequest,
+            crate::models::city_goal::UpdateCityGoalRequest,
+            crate::models::api_key::UpdateApiKeyRequest,
+            crate::models::impersonation::Impersonation,
+            crate::models::impersonation::ImpersonateRequest,
+            crate::models::impersonation::ImpersonationResponse,
+            crate::models::consistency_check::ConsistencyAnomaly,
+            crate::models::consistency_check::ConsistencyCheckRun,
+            crate::models::perf::RouteLatencyReport,
+            crate::models::perf::SqlSpanSummary,
+            crate::models::upload_scan::QuarantinedUpload,
+            crate::models::upload_scan::UploadScanCounts,
+            crate::models::upload_scan::UploadScanReport,
+            crate::services::email_service::EmailTemplate,
+            crate::models::event::EventLogEntry,
+            // Pagination envelopes
+            crate::models::pagination::PaginatedReports,
+            crate::models::pagination::PaginatedFeedPosts,
+            crate::models::pagination::PaginatedComments,
+            crate::models::pagination::PaginatedVerifications,
+            crate::models::pagination::PaginatedLeaderboard,
+            crate::models::pagination::PaginatedSavedSearches,
+            crate::models::pagination::PaginatedWatchedAreas,
+            crate::models::pagination::PaginatedCleanupEvents,
+            crate::models::pagination::PaginatedCityGoals,
+            crate::models::pagination::PaginatedAdminUsers,
+            crate::models::pagination::PaginatedAccountFlags,
+            crate::models::pagination::PaginatedFlaggedReports,
+            crate::models::pagination::PaginatedAdminReports,
+            crate::models::pagination::PaginatedEvents,
+            crate::models::pagination::PaginatedImpersonations,
+            crate::models::pagination::PaginatedConsistencyCheckRuns,
+            crate::models::pagination::PaginatedLoginEvents,
+            // Webhook models
+            crate::handlers::email_webhooks::EmailWebhookResponse,
             // Test helper models
             crate::handlers::test_helpers::TestHelperResponse,
             crate::handlers::test_helpers::CleanupRequest,
+            crate::handlers::test_helpers::CreateTestUsersRequest,
+            crate::handlers::test_helpers::CreateTestUsersResponse,
+            crate::handlers::test_helpers::TestUser,
+            crate::handlers::test_helpers::SwaggerSessionResponse,
+            crate::handlers::test_helpers::CreateTestReportRequest,
+            crate::handlers::test_helpers::FastForwardClearsRequest,
+            crate::handlers::test_helpers::FastForwardClearsResponse,
         )
     ),
     tags(
-        (name = "Authentication", description = "User authentication and registration"),
+        (name = "Authentication", description = "Registration, sign-in, email verification and password reset"),
+        (name = "Two-Factor", description = "TOTP two-factor authentication and backup codes"),
         (name = "OAuth", description = "OAuth authentication with Google"),
-        (name = "Users", description = "User profile management"),
+        (name = "Sessions", description = "Signed-in devices, sign-out and session revocation"),
+        (name = "Account", description = "Password and email address changes"),
+        (name = "Users", description = "User profile and score"),
+        (name = "Saved Searches", description = "Saved report searches with optional alerts"),
+        (name = "Watched Areas", description = "Neighbourhoods that notify you of new reports"),
+        (name = "Data Export", description = "Personal data export archives"),
+        (name = "Notifications", description = "In-app notifications (long-poll delivery)"),
         (name = "Reports", description = "Litter report management"),
-        (name = "Images", description = "Image serving endpoints"),
+        (name = "Cleanup Runs", description = "Claiming several reports at once as one cleanup run"),
+        (name = "Cleanup Events", description = "Group cleanups with RSVPs and a shared bonus"),
         (name = "Verifications", description = "Report verification"),
+        (name = "Uploads", description = "Resumable chunked photo uploads"),
+        (name = "Images", description = "Image serving endpoints"),
+        (name = "Feed", description = "Community feed posts"),
+        (name = "Feed Comments", description = "Comments on feed posts"),
+        (name = "Feed Likes", description = "Likes on feed posts"),
         (name = "Leaderboards", description = "User rankings and leaderboards"),
-        (name = "Admin", description = "Administrative endpoints (admin role required)"),
+        (name = "Scoring", description = "The points rules in force"),
+        (name = "City Goals", description = "Weekly collective clear targets per city"),
+        (name = "Stats", description = "Public site-wide totals"),
+        (name = "Open Data", description = "Anonymized cleared reports for councils and researchers"),
+        (name = "Capabilities", description = "Optional subsystems this deployment has enabled"),
+        (name = "Moderation", description = "Feed post removal and account flag review (moderator or admin role)"),
+        (name = "Admin Users", description = "User bans, roles and impersonation (admin role required)"),
+        (name = "Admin Reports", description = "Report moderation, corrections and statistics (admin role required)"),
+        (name = "Admin Emails", description = "Email template previews and test sends (admin role required)"),
+        (name = "Admin Events", description = "Event log and webhook delivery retries (admin role required)"),
+        (name = "Admin API Keys", description = "Partner API keys (admin role required)"),
+        (name = "Admin City Goals", description = "Weekly city goal targets (admin role required)"),
+        (name = "Admin Maintenance", description = "Data consistency checks and repairs (admin role required)"),
+        (name = "Webhooks", description = "Inbound notifications from third-party providers"),
         (name = "test-helpers", description = "Test helper endpoints (TESTING ONLY - DO NOT USE IN PRODUCTION)"),
     ),
     modifiers(&SecurityAddon)
 )]
 pub struct ApiDoc;
 
-/// Add JWT Bearer authentication to `OpenAPI`
+/// Domains the tags are grouped under, in order. Served as `x-tagGroups`, which Redoc
+/// shows as sections and SDK generators can use to split clients.
+pub const TAG_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "Identity",
+        &["Authentication", "Two-Factor", "OAuth", "Sessions"],
+    ),
+    (
+        "Account",
+        &[
+            "Account",
+            "Users",
+            "Saved Searches",
+            "Watched Areas",
+            "Data Export",
+            "Notifications",
+        ],
+    ),
+    (
+        "Litter",
+        &[
+            "Reports",
+            "Cleanup Runs",
+            "Cleanup Events",
+            "Verifications",
+            "Uploads",
+            "Images",
+        ],
+    ),
+    (
+        "Community",
+        &[
+            "Feed",
+            "Feed Comments",
+            "Feed Likes",
+            "Leaderboards",
+            "Scoring",
+            "City Goals",
+            "Stats",
+            "Open Data",
+            "Capabilities",
+        ],
+    ),
+    (
+        "Administration",
+        &[
+            "Moderation",
+            "Admin Users",
+            "Admin Reports",
+            "Admin Emails",
+            "Admin Events",
+            "Admin API Keys",
+            "Admin City Goals",
+            "Admin Maintenance",
+        ],
+    ),
+    ("Integrations", &["Webhooks", "test-helpers"]),
+];
+
+/// The spec as served at `/api/openapi.json`: the generated document plus `x-tagGroups`
+#[must_use]
+pub fn to_json(openapi: &utoipa::openapi::OpenApi) -> serde_json::Value {
+    let mut spec = serde_json::to_value(openapi).unwrap_or_default();
+
+    let groups: Vec<serde_json::Value> = TAG_GROUPS
+        .iter()
+        .map(|(name, tags)| serde_json::json!({ "name": name, "tags": tags }))
+        .collect();
+    if let Some(spec) = spec.as_object_mut() {
+        spec.insert("x-tagGroups".to_string(), serde_json::Value::Array(groups));
+    }
+
+    spec
+}
+
+/// Point the served spec at the `/swagger-auth` page through `x-swagger-auth`, and
+/// mention it in the description Swagger UI shows. Only done while test helpers are on.
+pub fn add_swagger_auth_link(spec: &mut serde_json::Value, page_url: &str) {
+    let Some(spec) = spec.as_object_mut() else {
+        return;
+    };
+
+    if let Some(description) = spec
+        .get_mut("info")
+        .and_then(|info| info.get_mut("description"))
+    {
+        let text = description.as_str().unwrap_or_default();
+        *description = serde_json::Value::String(format!(
+            "{text}\n\nDevelopment server: open [{page_url}]({page_url}) to try requests as a throwaway verified user."
+        ));
+    }
+    spec.insert(
+        "x-swagger-auth".to_string(),
+        serde_json::Value::String(page_url.to_string()),
+    );
+}
+
+/// Page that asks for `TEST_HELPERS_SECRET`, creates a session at `session_url` and
+/// opens Swagger UI at `swagger_url` already authorized with its access token. Swagger
+/// UI must be configured to persist authorization, which it restores from the
+/// `authorized` local storage entry written here.
+#[must_use]
+pub fn swagger_auth_html(session_url: &str, swagger_url: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>LittyPicky API - Swagger sign-in</title>
+    <meta charset="utf-8"/>
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <style>body {{ font-family: sans-serif; max-width: 32rem; margin: 4rem auto; }}</style>
+  </head>
+  <body>
+    <h1>Try the API as a test user</h1>
+    <p>Creates a throwaway verified user and opens Swagger UI signed in as them.</p>
+    <form id="session">
+      <label>Test helpers secret <input id="secret" type="password" required></label>
+      <button type="submit">Open Swagger UI</button>
+    </form>
+    <p id="error" role="alert"></p>
+    <script>
+      document.getElementById("session").addEventListener("submit", async (event) => {{
+        event.preventDefault();
+        const response = await fetch("{session_url}", {{
+          method: "POST",
+          headers: {{ "X-Test-Helpers-Secret": document.getElementById("secret").value }},
+        }});
+        if (!response.ok) {{
+          document.getElementById("error").textContent = `Could not create a session (${{response.status}})`;
+          return;
+        }}
+        const session = await response.json();
+        localStorage.setItem("authorized", JSON.stringify({{
+          bearer_auth: {{
+            name: "bearer_auth",
+            schema: {{ type: "http", scheme: "bearer", bearerFormat: "JWT" }},
+            value: session.tokens.access_token,
+          }},
+        }}));
+        window.location.href = "{swagger_url}";
+      }});
+    </script>
+  </body>
+</html>
+"#
+    )
+}
+
+/// Redoc page rendering the spec at `spec_url`
+#[must_use]
+pub fn redoc_html(spec_url: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>LittyPicky API</title>
+    <meta charset="utf-8"/>
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <style>body {{ margin: 0; padding: 0; }}</style>
+  </head>
+  <body>
+    <redoc spec-url="{spec_url}"></redoc>
+    <script src="https://cdn.redoc.ly/redoc/latest/bundles/redoc.standalone.js"></script>
+  </body>
+</html>
+"#
+    )
+}
+
+/// Add JWT Bearer and partner API key authentication to `OpenAPI`
 struct SecurityAddon;
 
 impl utoipa::Modify for SecurityAddon {
@@ -134,6 +645,14 @@ impl utoipa::Modify for SecurityAddon {
                         .build(),
                 ),
             );
+            components.add_security_scheme(
+                "api_key",
+                utoipa::openapi::security::SecurityScheme::ApiKey(
+                    utoipa::openapi::security::ApiKey::Header(
+                        utoipa::openapi::security::ApiKeyValue::new("X-Api-Key"),
+                    ),
+                ),
+            );
         }
     }
 }
@@ -0,0 +1,282 @@
+//! Per-route latency budgets. Requests that run over budget are logged with a
+//! breakdown of the SQL that ran during them and tallied for `GET /api/admin/perf`.
+//! Tallies live in memory, so each instance reports on its own traffic.
+
+use crate::config::LatencyConfig;
+use crate::models::perf::{RouteLatencyReport, SqlSpanSummary};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Target of the event sqlx emits after every statement
+const SQLX_QUERY_TARGET: &str = "sqlx::query";
+/// Statement groups listed in a slow-request warning
+const MAX_LOGGED_STATEMENTS: usize = 5;
+
+tokio::task_local! {
+    /// Statements run by the current request, as (summary, milliseconds)
+    static SQL_TIMINGS: RefCell<Vec<(String, f64)>>;
+}
+
+#[derive(Default)]
+struct RouteStats {
+    requests: u64,
+    violations: u64,
+    cancelled: u64,
+    worst_ms: f64,
+    worst_sql: Vec<SqlSpanSummary>,
+    last_violation_at: Option<DateTime<Utc>>,
+}
+
+/// Checks requests against their route's budget and keeps per-route tallies
+#[derive(Clone)]
+pub struct LatencyMonitor {
+    config: Arc<LatencyConfig>,
+    routes: Arc<Mutex<HashMap<String, RouteStats>>>,
+}
+
+impl LatencyMonitor {
+    #[must_use]
+    pub fn new(config: LatencyConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            routes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Count one request to `route` (`METHOD /pattern`); returns whether it ran over budget
+    pub fn record(&self, route: &str, elapsed_ms: f64, statements: &[(String, f64)]) -> bool {
+        let budget_ms = self.config.budget_ms(route);
+        let over_budget = elapsed_ms > budget_ms as f64;
+        let sql = over_budget.then(|| summarize(statements));
+
+        if let Some(sql) = &sql {
+            let sql_ms: f64 = sql.iter().map(|span| span.total_ms).sum();
+            tracing::warn!(
+                "Slow request: {} took {:.1}ms (budget {}ms), {:.1}ms in SQL: {}",
+                route,
+                elapsed_ms,
+                budget_ms,
+                sql_ms,
+                breakdown(sql)
+            );
+        }
+
+        let mut routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+        let stats = routes.entry(route.to_string()).or_default();
+        stats.requests += 1;
+        if elapsed_ms > stats.worst_ms {
+            stats.worst_ms = elapsed_ms;
+            // The slowest request is over budget whenever any request was
+            stats.worst_sql = sql.clone().unwrap_or_default();
+        }
+        if sql.is_some() {
+            stats.violations += 1;
+            stats.last_violation_at = Some(Utc::now());
+        }
+
+        over_budget
+    }
+
+    /// Count one request to `route` that was dropped before it finished, usually because
+    /// the client disconnected. Queries it was awaiting were dropped with it; Postgres
+    /// stops them at the statement timeout at the latest.
+    pub fn record_cancelled(&self, route: &str, elapsed_ms: f64, statements: &[(String, f64)]) {
+        tracing::warn!(
+            "Cancelled request: {} dropped after {:.1}ms, most likely by a client disconnect; finished SQL: {}",
+            route,
+            elapsed_ms,
+            breakdown(&summarize(statements))
+        );
+
+        let mut routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+        let stats = routes.entry(route.to_string()).or_default();
+        stats.requests += 1;
+        stats.cancelled += 1;
+    }
+
+    /// Routes that have run over budget or had requests cancelled, most violations first
+    #[must_use]
+    pub fn report(&self, limit: usize) -> Vec<RouteLatencyReport> {
+        let routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut report: Vec<RouteLatencyReport> = routes
+            .iter()
+            .filter(|(_, stats)| stats.violations > 0 || stats.cancelled > 0)
+            .map(|(route, stats)| RouteLatencyReport {
+                route: route.clone(),
+                budget_ms: self.config.budget_ms(route),
+                requests: stats.requests,
+                violations: stats.violations,
+                cancelled: stats.cancelled,
+                worst_ms: stats.worst_ms,
+                worst_sql: stats.worst_sql.clone(),
+                last_violation_at: stats.last_violation_at,
+            })
+            .collect();
+        report.sort_by(|a, b| {
+            b.violations
+                .cmp(&a.violations)
+                .then(b.worst_ms.total_cmp(&a.worst_ms))
+        });
+        report.truncate(limit);
+        report
+    }
+}
+
+/// Group statements by their summary, longest total first
+fn summarize(statements: &[(String, f64)]) -> Vec<SqlSpanSummary> {
+    let mut spans: Vec<SqlSpanSummary> = Vec::new();
+    for (statement, ms) in statements {
+        match spans.iter_mut().find(|span| &span.statement == statement) {
+            Some(span) => {
+                span.calls += 1;
+                span.total_ms += ms;
+            }
+            None => spans.push(SqlSpanSummary {
+                statement: statement.clone(),
+                calls: 1,
+                total_ms: *ms,
+            }),
+        }
+    }
+    spans.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+    spans
+}
+
+/// The slowest statement groups, for a log line
+fn breakdown(sql: &[SqlSpanSummary]) -> String {
+    if sql.is_empty() {
+        return "no statements recorded".to_string();
+    }
+    sql.iter()
+        .take(MAX_LOGGED_STATEMENTS)
+        .map(|span| format!("{:.1}ms x{} {}", span.total_ms, span.calls, span.statement))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Reports a request whose future is dropped before it finishes, which is how a
+/// client disconnect reaches the handler and the queries it is awaiting
+struct CancellationGuard<'a> {
+    monitor: &'a LatencyMonitor,
+    route: &'a str,
+    started: Instant,
+    finished: bool,
+}
+
+impl Drop for CancellationGuard<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let statements = SQL_TIMINGS.try_with(RefCell::take).unwrap_or_default();
+        self.monitor.record_cancelled(
+            self.route,
+            self.started.elapsed().as_secs_f64() * 1000.0,
+            &statements,
+        );
+    }
+}
+
+/// Time each matched route against its budget; unmatched requests pass through untimed
+pub async fn track_latency(
+    State(monitor): State<LatencyMonitor>,
+    matched_path: Option<MatchedPath>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(matched_path) = matched_path else {
+        return next.run(req).await;
+    };
+    let route = format!("{} {}", req.method(), matched_path.as_str());
+
+    let started = Instant::now();
+    let (response, statements) = SQL_TIMINGS
+        .scope(RefCell::default(), async {
+            let mut guard = CancellationGuard {
+                monitor: &monitor,
+                route: &route,
+                started,
+                finished: false,
+            };
+            let response = next.run(req).await;
+            guard.finished = true;
+            (response, SQL_TIMINGS.with(RefCell::take))
+        })
+        .await;
+    monitor.record(
+        &route,
+        started.elapsed().as_secs_f64() * 1000.0,
+        &statements,
+    );
+
+    response
+}
+
+/// Collects sqlx's per-statement timings for the request being tracked
+pub struct SqlTimingLayer;
+
+impl SqlTimingLayer {
+    /// The layer with a filter admitting sqlx's statement events, which it emits at
+    /// debug level; installing it makes sqlx produce them whatever `RUST_LOG` says
+    pub fn filtered<S>() -> impl Layer<S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        Self.with_filter(Targets::new().with_target(SQLX_QUERY_TARGET, Level::TRACE))
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SqlTimingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != SQLX_QUERY_TARGET {
+            return;
+        }
+        let mut visitor = SqlEventVisitor::default();
+        event.record(&mut visitor);
+        let Some(elapsed_secs) = visitor.elapsed_secs else {
+            return;
+        };
+
+        // Statements outside a tracked request, e.g. from background jobs, are dropped
+        let _ = SQL_TIMINGS.try_with(|timings| {
+            timings
+                .borrow_mut()
+                .push((visitor.summary, elapsed_secs * 1000.0));
+        });
+    }
+}
+
+#[derive(Default)]
+struct SqlEventVisitor {
+    summary: String,
+    elapsed_secs: Option<f64>,
+}
+
+impl Visit for SqlEventVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "summary" {
+            self.summary = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
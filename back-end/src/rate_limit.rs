@@ -1,50 +1,424 @@
-use governor::middleware::NoOpMiddleware;
-use tower_governor::{
-    governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor, GovernorLayer,
+//! In-process rate limiting: per client IP for the auth routes and per user for
+//! authenticated routes, plus a cap on each user's concurrent heavy requests.
+//! Counters live in memory, so each instance limits on its own.
+//!
+//! Limited responses carry `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+//! `X-RateLimit-Reset` so clients can slow down before they are refused, and an
+//! `X-RateLimit-Warning` once they pass the warning threshold.
+
+use crate::auth::middleware::AuthUser;
+use crate::config::RateLimitConfig;
+use crate::error::AppError;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use governor::{
+    clock::{Clock, DefaultClock},
+    middleware::StateInformationMiddleware,
+    DefaultKeyedRateLimiter, Quota,
 };
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex, PoisonError};
+use uuid::Uuid;
+
+/// What a request is counted against
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum RateLimitKey {
+    Ip(IpAddr),
+    User(Uuid),
+}
+
+pub const X_RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub const X_RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+pub const X_RATELIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+pub const X_RATELIMIT_WARNING: HeaderName = HeaderName::from_static("x-ratelimit-warning");
+
+/// Where a client stands against one limit after a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the full allowance is back
+    pub reset_secs: u64,
+    /// Past the warning threshold: still allowed, but close to being refused
+    pub warning: bool,
+}
+
+impl RateLimitStatus {
+    /// Add the `X-RateLimit-*` headers, unless the response already reports a limit
+    /// with less left, so the tightest of several limits is the one shown
+    pub fn apply(&self, response: &mut Response) {
+        let headers = response.headers_mut();
+        let tighter = headers
+            .get(&X_RATELIMIT_REMAINING)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u32>().ok())
+            .is_some_and(|remaining| remaining <= self.remaining);
+        if tighter {
+            return;
+        }
+
+        headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(self.limit));
+        headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(self.remaining));
+        headers.insert(X_RATELIMIT_RESET, HeaderValue::from(self.reset_secs));
+        if self.warning {
+            headers.insert(
+                X_RATELIMIT_WARNING,
+                HeaderValue::from_static("Approaching rate limit; slow down"),
+            );
+        } else {
+            headers.remove(X_RATELIMIT_WARNING);
+        }
+    }
+}
+
+/// One named limit, e.g. sign-in attempts per minute
+#[derive(Clone)]
+pub struct RateLimiter {
+    name: &'static str,
+    quota: Quota,
+    limiter: Arc<DefaultKeyedRateLimiter<RateLimitKey, StateInformationMiddleware>>,
+    /// Take the client IP from `X-Forwarded-For`/`X-Real-IP`; only safe behind a proxy
+    /// that sets them
+    trust_proxy_headers: bool,
+    /// Warn once this few requests are left; `None` never warns
+    warn_remaining: Option<u32>,
+}
+
+impl RateLimiter {
+    /// Allow `requests` per minute, all of which may arrive at once
+    #[must_use]
+    pub fn per_minute(name: &'static str, requests: u32, trust_proxy_headers: bool) -> Self {
+        Self::new(
+            name,
+            Quota::per_minute(at_least_one(requests)),
+            trust_proxy_headers,
+        )
+    }
+
+    /// Allow `requests` per hour, all of which may arrive at once
+    #[must_use]
+    pub fn per_hour(name: &'static str, requests: u32, trust_proxy_headers: bool) -> Self {
+        Self::new(
+            name,
+            Quota::per_hour(at_least_one(requests)),
+            trust_proxy_headers,
+        )
+    }
 
-/// Create a rate limiting layer for general API requests
-/// Uses IP address as the key for rate limiting
-#[must_use]
-pub fn create_rate_limiter(
-    requests_per_min: u32,
-) -> GovernorLayer<'static, SmartIpKeyExtractor, NoOpMiddleware> {
-    let governor_conf = Box::new(
-        GovernorConfigBuilder::default()
-            .per_second(u64::from((requests_per_min / 60).max(1)))
-            .burst_size(requests_per_min.max(10))
-            .key_extractor(SmartIpKeyExtractor)
-            .finish()
-            .unwrap(),
-    );
-
-    GovernorLayer {
-        config: Box::leak(governor_conf),
-    }
-}
-
-/// Create a rate limiter based on requests per hour
-#[must_use]
-pub fn create_rate_limiter_per_hour(
-    requests_per_hour: u32,
-) -> GovernorLayer<'static, SmartIpKeyExtractor, NoOpMiddleware> {
-    let per_minute = (requests_per_hour / 60).max(1);
-    create_rate_limiter(per_minute)
-}
-
-/// Get a simple global rate limiter layer using the default `SmartIpKeyExtractor`
-#[must_use]
-pub fn get_rate_limiter_layer() -> GovernorLayer<'static, SmartIpKeyExtractor, NoOpMiddleware> {
-    let config = Box::new(
-        GovernorConfigBuilder::default()
-            .per_second(2) // ~120 per minute
-            .burst_size(10)
-            .key_extractor(SmartIpKeyExtractor)
-            .finish()
-            .unwrap(),
-    );
-
-    GovernorLayer {
-        config: Box::leak(config),
+    fn new(name: &'static str, quota: Quota, trust_proxy_headers: bool) -> Self {
+        Self {
+            name,
+            quota,
+            limiter: Arc::new(
+                DefaultKeyedRateLimiter::keyed(quota)
+                    .with_middleware::<StateInformationMiddleware>(),
+            ),
+            trust_proxy_headers,
+            warn_remaining: None,
+        }
     }
+
+    /// Warn clients, and log, once they have used `percent` of the allowance; 0 or
+    /// 100 and above never warn
+    #[must_use]
+    pub fn with_warning_at(mut self, percent: u32) -> Self {
+        let limit = self.quota.burst_size().get();
+        self.warn_remaining = (1..100)
+            .contains(&percent)
+            .then(|| limit - limit * percent / 100);
+        self
+    }
+
+    /// Count one request, or say how many seconds to wait before retrying
+    pub fn check(&self, key: &RateLimitKey) -> Result<RateLimitStatus, u64> {
+        let limit = self.quota.burst_size().get();
+        let interval = self.quota.replenish_interval();
+
+        match self.limiter.check_key(key) {
+            Ok(snapshot) => {
+                let remaining = snapshot.remaining_burst_capacity();
+                let warning = self.warn_remaining.is_some_and(|warn| remaining <= warn);
+                if self.warn_remaining == Some(remaining) {
+                    tracing::warn!(
+                        "Rate limit {} nearly reached by {:?}: {} of {} left",
+                        self.name,
+                        key,
+                        remaining,
+                        limit
+                    );
+                }
+
+                Ok(RateLimitStatus {
+                    limit,
+                    remaining,
+                    reset_secs: (interval * (limit - remaining)).as_secs_f64().ceil() as u64,
+                    warning,
+                })
+            }
+            Err(not_until) => Err(not_until
+                .wait_time_from(DefaultClock::default().now())
+                .as_secs()
+                .max(1)),
+        }
+    }
+
+    /// Forget clients whose allowance has fully recovered
+    pub fn prune(&self) {
+        self.limiter.retain_recent();
+    }
+
+    /// The 429 (with `Retry-After`) to send when `check` refuses `key`
+    pub fn too_many_requests(&self, key: &RateLimitKey, retry_after: u64) -> Response {
+        tracing::debug!("Rate limit {} hit by {:?}", self.name, key);
+        let mut response =
+            AppError::TooManyRequests(format!("Too many requests; retry in {retry_after}s"))
+                .into_response();
+        let limit = self.quota.burst_size().get();
+        RateLimitStatus {
+            limit,
+            remaining: 0,
+            reset_secs: retry_after + (self.quota.replenish_interval() * (limit - 1)).as_secs(),
+            warning: true,
+        }
+        .apply(&mut response);
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        response
+    }
+
+    fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        client_ip(req, self.trust_proxy_headers)
+    }
+}
+
+/// The client's IP: from `X-Forwarded-For`/`X-Real-IP` when `trust_proxy_headers` is
+/// set and they are present, otherwise from the connection
+pub fn client_ip(req: &Request, trust_proxy_headers: bool) -> Option<IpAddr> {
+    if trust_proxy_headers {
+        let forwarded = req
+            .headers()
+            .get("X-Forwarded-For")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.split(',').next())
+            .or_else(|| req.headers().get("X-Real-IP").and_then(|h| h.to_str().ok()))
+            .and_then(|ip| ip.trim().parse().ok());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+fn at_least_one(requests: u32) -> NonZeroU32 {
+    NonZeroU32::new(requests).unwrap_or(NonZeroU32::MIN)
+}
+
+/// Caps how many requests each user may have in progress at once, so one user's burst
+/// of uploads cannot occupy the image pipeline while everyone else waits
+#[derive(Clone)]
+pub struct InFlightLimiter {
+    name: &'static str,
+    max_in_flight: usize,
+    in_flight: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+/// A slot held for one in-progress request; released when dropped
+pub struct InFlightPermit {
+    user_id: Uuid,
+    in_flight: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+impl InFlightLimiter {
+    #[must_use]
+    pub fn new(name: &'static str, max_in_flight: u32) -> Self {
+        Self {
+            name,
+            max_in_flight: at_least_one(max_in_flight).get() as usize,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Take a slot for `user_id`, or `None` if they already have the maximum running
+    pub fn try_acquire(&self, user_id: Uuid) -> Option<InFlightPermit> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let count = in_flight.entry(user_id).or_insert(0);
+        if *count >= self.max_in_flight {
+            tracing::debug!("In-flight limit {} hit by user {}", self.name, user_id);
+            return None;
+        }
+        *count += 1;
+
+        Some(InFlightPermit {
+            user_id,
+            in_flight: Arc::clone(&self.in_flight),
+        })
+    }
+}
+
+impl Drop for InFlightPermit {
+    fn drop(&mut self) {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(count) = in_flight.get_mut(&self.user_id) {
+            *count -= 1;
+            // Idle users take no memory
+            if *count == 0 {
+                in_flight.remove(&self.user_id);
+            }
+        }
+    }
+}
+
+/// The limits from `RateLimitConfig`
+#[derive(Clone)]
+pub struct RateLimiters {
+    /// Sign-in, registration and token routes, per IP
+    pub auth: RateLimiter,
+    /// Verification email resends, per IP
+    pub email_verification: RateLimiter,
+    /// Password reset requests, per IP
+    pub password_reset: RateLimiter,
+    /// Public report photos, per IP
+    pub images: RateLimiter,
+    /// Every authenticated request, per user
+    pub general: RateLimiter,
+    /// New reports, per user
+    pub reports: RateLimiter,
+    /// Report verifications, per user
+    pub verifications: RateLimiter,
+    /// Concurrent report creation/clearing and feed posts, per user
+    pub uploads_in_flight: InFlightLimiter,
+}
+
+impl RateLimiters {
+    #[must_use]
+    pub fn new(config: &RateLimitConfig) -> Self {
+        let trust = config.trust_proxy_headers;
+        let warn = config.warn_percent;
+        Self {
+            auth: RateLimiter::per_minute("auth", config.auth_per_min, trust).with_warning_at(warn),
+            email_verification: RateLimiter::per_hour(
+                "email_verification",
+                config.email_verification_per_hour,
+                trust,
+            )
+            .with_warning_at(warn),
+            password_reset: RateLimiter::per_hour(
+                "password_reset",
+                config.password_reset_per_hour,
+                trust,
+            )
+            .with_warning_at(warn),
+            images: RateLimiter::per_minute("images", config.images_per_min, trust)
+                .with_warning_at(warn),
+            general: RateLimiter::per_minute("general", config.general_per_min, trust)
+                .with_warning_at(warn),
+            reports: RateLimiter::per_hour("reports", config.reports_per_hour, trust)
+                .with_warning_at(warn),
+            verifications: RateLimiter::per_hour(
+                "verifications",
+                config.verifications_per_hour,
+                trust,
+            )
+            .with_warning_at(warn),
+            uploads_in_flight: InFlightLimiter::new(
+                "uploads_in_flight",
+                config.concurrent_uploads_per_user,
+            ),
+        }
+    }
+
+    /// Drop idle clients from every limiter so memory does not grow without bound
+    pub fn prune(&self) {
+        for limiter in [
+            &self.auth,
+            &self.email_verification,
+            &self.password_reset,
+            &self.images,
+            &self.general,
+            &self.reports,
+            &self.verifications,
+        ] {
+            limiter.prune();
+        }
+    }
+}
+
+/// Limit a route per client IP. Requests whose IP cannot be determined (no connection
+/// info, e.g. in-process calls) are let through rather than failed.
+pub async fn limit_by_ip(State(limiter): State<RateLimiter>, req: Request, next: Next) -> Response {
+    let Some(ip) = limiter.client_ip(&req) else {
+        return next.run(req).await;
+    };
+
+    let key = RateLimitKey::Ip(ip);
+    match limiter.check(&key) {
+        Ok(status) => {
+            let mut response = next.run(req).await;
+            status.apply(&mut response);
+            response
+        }
+        Err(retry_after) => limiter.too_many_requests(&key, retry_after),
+    }
+}
+
+/// Limit a route per signed-in user; must run after `require_auth`
+pub async fn limit_by_user(
+    State(limiter): State<RateLimiter>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(user_id) = req.extensions().get::<AuthUser>().map(|user| user.id) else {
+        return AppError::Unauthorized.into_response();
+    };
+
+    let key = RateLimitKey::User(user_id);
+    match limiter.check(&key) {
+        Ok(status) => {
+            let mut response = next.run(req).await;
+            status.apply(&mut response);
+            response
+        }
+        Err(retry_after) => limiter.too_many_requests(&key, retry_after),
+    }
+}
+
+/// Limit how many requests a signed-in user may have running on a route at once;
+/// must run after `require_auth`. Extra requests get a `429` rather than queueing.
+pub async fn limit_in_flight(
+    State(limiter): State<InFlightLimiter>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(user_id) = req.extensions().get::<AuthUser>().map(|user| user.id) else {
+        return AppError::Unauthorized.into_response();
+    };
+
+    let Some(_permit) = limiter.try_acquire(user_id) else {
+        let mut response = AppError::TooManyRequests(
+            "Too many requests in progress; wait for one to finish".to_string(),
+        )
+        .into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(1));
+        return response;
+    };
+
+    next.run(req).await
 }
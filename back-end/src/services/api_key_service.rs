@@ -0,0 +1,253 @@
+use crate::auth::tokens::{generate_token, hash_token};
+use crate::error::AppError;
+use crate::models::api_key::{
+    ApiKey, ApiKeyScope, CreateApiKeyRequest, CreatedApiKeyResponse, UpdateApiKeyRequest,
+};
+use crate::rate_limit::RateLimitStatus;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Issued keys start with this so they are easy to spot in logs and secret scanners
+const KEY_PREFIX: &str = "lp_";
+/// Characters of the key kept in clear for admins to tell keys apart
+const DISPLAYED_PREFIX_LEN: usize = 9;
+const DEFAULT_RATE_LIMIT_PER_MIN: i32 = 60;
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Requests seen from one key in the current one-minute window
+struct RateWindow {
+    started: Instant,
+    count: i32,
+}
+
+/// Issues and checks the API keys partner services use for read-only endpoints.
+/// Rate limits are counted in memory per instance, in fixed one-minute windows.
+#[derive(Clone)]
+pub struct ApiKeyService {
+    pool: PgPool,
+    windows: Arc<Mutex<HashMap<Uuid, RateWindow>>>,
+    /// Share of a key's limit after which responses warn; 0 never warns
+    warn_percent: u32,
+}
+
+impl ApiKeyService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+            warn_percent: 0,
+        }
+    }
+
+    /// Warn callers, and log, once a key has used `percent` of its limit
+    #[must_use]
+    pub fn with_warning_at(mut self, percent: u32) -> Self {
+        self.warn_percent = percent;
+        self
+    }
+
+    /// Issue a key. The plaintext is returned once and only its hash is stored.
+    pub async fn create_key(
+        &self,
+        created_by: Uuid,
+        request: CreateApiKeyRequest,
+    ) -> Result<CreatedApiKeyResponse, AppError> {
+        let api_key = format!("{KEY_PREFIX}{}", generate_token());
+        let scopes = scope_names(&request.scopes);
+
+        let key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            INSERT INTO api_keys (name, key_prefix, key_hash, scopes, rate_limit_per_min, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, key_prefix, scopes, rate_limit_per_min, created_by,
+                      created_at, last_used_at, revoked_at
+            "#,
+            request.name.trim(),
+            &api_key[..DISPLAYED_PREFIX_LEN],
+            hash_token(&api_key),
+            &scopes,
+            request
+                .rate_limit_per_min
+                .unwrap_or(DEFAULT_RATE_LIMIT_PER_MIN),
+            created_by
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(CreatedApiKeyResponse {
+            api_key,
+            key: key.into(),
+        })
+    }
+
+    /// All keys, revoked ones included, newest first
+    pub async fn list_keys(&self) -> Result<Vec<ApiKey>, AppError> {
+        let keys = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT id, name, key_prefix, scopes, rate_limit_per_min, created_by,
+                   created_at, last_used_at, revoked_at
+            FROM api_keys
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// Change a key's name, scopes or rate limit
+    pub async fn update_key(
+        &self,
+        key_id: Uuid,
+        request: UpdateApiKeyRequest,
+    ) -> Result<ApiKey, AppError> {
+        let scopes = request.scopes.as_deref().map(scope_names);
+
+        sqlx::query_as!(
+            ApiKey,
+            r#"
+            UPDATE api_keys
+            SET name = COALESCE($2, name),
+                scopes = COALESCE($3, scopes),
+                rate_limit_per_min = COALESCE($4, rate_limit_per_min)
+            WHERE id = $1
+            RETURNING id, name, key_prefix, scopes, rate_limit_per_min, created_by,
+                      created_at, last_used_at, revoked_at
+            "#,
+            key_id,
+            request.name.as_deref().map(str::trim),
+            scopes.as_deref(),
+            request.rate_limit_per_min
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("API key not found".to_string()))
+    }
+
+    /// Stop a key from working. Revoking twice is harmless.
+    pub async fn revoke_key(&self, key_id: Uuid) -> Result<ApiKey, AppError> {
+        sqlx::query_as!(
+            ApiKey,
+            r#"
+            UPDATE api_keys
+            SET revoked_at = COALESCE(revoked_at, NOW())
+            WHERE id = $1
+            RETURNING id, name, key_prefix, scopes, rate_limit_per_min, created_by,
+                      created_at, last_used_at, revoked_at
+            "#,
+            key_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("API key not found".to_string()))
+    }
+
+    /// Resolve a presented key, check it grants `scope` and count the request against
+    /// its rate limit. Also returns where the key stands against that limit.
+    pub async fn authenticate(
+        &self,
+        api_key: &str,
+        scope: ApiKeyScope,
+    ) -> Result<(ApiKey, RateLimitStatus), AppError> {
+        let key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT id, name, key_prefix, scopes, rate_limit_per_min, created_by,
+                   created_at, last_used_at, revoked_at
+            FROM api_keys
+            WHERE key_hash = $1 AND revoked_at IS NULL
+            "#,
+            hash_token(api_key)
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::Auth("Invalid API key".to_string()))?;
+
+        if !key.has_scope(scope) {
+            return Err(AppError::Forbidden(format!(
+                "API key lacks the {} scope",
+                scope.as_str()
+            )));
+        }
+
+        let (opened, status) = self.start_request(&key).await?;
+        if opened {
+            // Recorded once per window rather than on every call
+            sqlx::query!(
+                "UPDATE api_keys SET last_used_at = NOW() WHERE id = $1",
+                key.id
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok((key, status))
+    }
+
+    /// Count a request; true when it opened a new window
+    async fn start_request(&self, key: &ApiKey) -> Result<(bool, RateLimitStatus), AppError> {
+        let mut windows = self.windows.lock().await;
+        let now = Instant::now();
+        let window = windows.entry(key.id).or_insert(RateWindow {
+            started: now,
+            count: 0,
+        });
+
+        let opened = window.count == 0 || now.duration_since(window.started) >= RATE_WINDOW;
+        if opened {
+            window.started = now;
+            window.count = 0;
+        }
+
+        if window.count >= key.rate_limit_per_min {
+            let retry_in = RATE_WINDOW.saturating_sub(now.duration_since(window.started));
+            return Err(AppError::TooManyRequests(format!(
+                "API key rate limit of {} requests per minute exceeded; retry in {}s",
+                key.rate_limit_per_min,
+                retry_in.as_secs().max(1)
+            )));
+        }
+        window.count += 1;
+
+        let limit = u32::try_from(key.rate_limit_per_min).unwrap_or(0);
+        let remaining = u32::try_from(key.rate_limit_per_min - window.count).unwrap_or(0);
+        let warn_remaining = (1..100)
+            .contains(&self.warn_percent)
+            .then(|| limit - limit * self.warn_percent / 100);
+        if warn_remaining == Some(remaining) {
+            tracing::warn!(
+                "API key {} nearly at its rate limit: {} of {} left",
+                key.key_prefix,
+                remaining,
+                limit
+            );
+        }
+
+        let status = RateLimitStatus {
+            limit,
+            remaining,
+            reset_secs: RATE_WINDOW
+                .saturating_sub(now.duration_since(window.started))
+                .as_secs()
+                .max(1),
+            warning: warn_remaining.is_some_and(|warn| remaining <= warn),
+        };
+
+        Ok((opened, status))
+    }
+}
+
+fn scope_names(scopes: &[ApiKeyScope]) -> Vec<String> {
+    let mut names: Vec<String> = scopes.iter().map(|s| s.as_str().to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
@@ -1,9 +1,15 @@
 use crate::{
-    auth::{generate_token, hash_token, JwtService},
+    auth::{generate_token, hash_token, middleware::AuthUser, ClientInfo, JwtService},
     config::Config,
     error::{AppError, Result},
-    models::{AuthTokens, User},
-    services::{oauth_service::OAuthUserInfo, EmailService},
+    models::{
+        AuthTokens, LoginEvent, PageParams, Paginated, ServiceTokenResponse, SessionResponse,
+        TokenScope, TwoFactorChallenge, User,
+    },
+    services::{
+        oauth_service::OAuthUserInfo, CaptchaService, EmailOutboxService, EmailService,
+        LoginHistoryService, TwoFactorService,
+    },
 };
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
@@ -19,6 +25,25 @@ struct TokenRecord {
     expires_at: DateTime<Utc>,
 }
 
+/// How long a signed-in user has to finish the provider's consent screen when linking
+const OAUTH_LINK_REQUEST_EXPIRY_MINUTES: i64 = 10;
+
+const EMAIL_CHANGE_EXPIRY_HOURS: i64 = 24;
+
+/// Following a password setup link stands in for re-authentication, so it expires quickly
+const PASSWORD_SETUP_EXPIRY_MINUTES: i64 = 60;
+
+/// Magic links are short-lived since anyone holding one can sign in
+const MAGIC_LINK_EXPIRY_MINUTES: i64 = 15;
+
+#[derive(FromRow)]
+struct RefreshTokenRecord {
+    id: Uuid,
+    user_id: Uuid,
+    expires_at: DateTime<Utc>,
+    two_factor_verified: bool,
+}
+
 #[derive(FromRow)]
 struct PasswordResetRecord {
     user_id: Uuid,
@@ -26,10 +51,44 @@ struct PasswordResetRecord {
     used: bool,
 }
 
+#[derive(FromRow)]
+struct EmailChangeRecord {
+    user_id: Uuid,
+    new_email: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct OAuthLinkRecord {
+    user_id: Uuid,
+    oauth_provider: String,
+    oauth_subject: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Result of a successful first-factor login
+pub enum LoginOutcome {
+    Authenticated(AuthTokens),
+    /// The user has TOTP enabled and must complete the challenge
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+/// Result of signing in with an OAuth provider
+pub enum OAuthLoginOutcome {
+    LoggedIn(AuthTokens),
+    TwoFactorRequired(TwoFactorChallenge),
+    /// The email belongs to an existing password account; a confirmation link was sent to it
+    LinkConfirmationRequired,
+}
+
 pub struct AuthService {
     pool: PgPool,
     jwt_service: JwtService,
     email_service: EmailService,
+    two_factor_service: TwoFactorService,
+    captcha_service: CaptchaService,
+    login_history: LoginHistoryService,
+    email_outbox: EmailOutboxService,
     config: Config,
 }
 
@@ -39,16 +98,29 @@ impl AuthService {
         pool: PgPool,
         jwt_service: JwtService,
         email_service: EmailService,
+        two_factor_service: TwoFactorService,
         config: Config,
     ) -> Self {
         Self {
+            login_history: LoginHistoryService::new(pool.clone(), email_service.clone()),
+            email_outbox: EmailOutboxService::new(pool.clone(), email_service.clone()),
             pool,
             jwt_service,
             email_service,
+            two_factor_service,
+            captcha_service: CaptchaService::new(config.captcha.clone()),
             config,
         }
     }
 
+    /// Check the CAPTCHA solved before an anonymous request that creates an account
+    /// or sends email; a no-op unless `CAPTCHA_PROVIDER` is set
+    pub async fn verify_captcha(&self, token: Option<&str>, client: &ClientInfo) -> Result<()> {
+        self.captcha_service
+            .verify(token, client.ip_address.as_deref())
+            .await
+    }
+
     pub async fn register_user(
         &self,
         email: &str,
@@ -70,6 +142,9 @@ impl AuthService {
         // Hash password
         let password_hash = self.hash_password(password)?;
 
+        // The user, their score, the token and the email commit together or not at all
+        let mut tx = self.pool.begin().await?;
+
         // Create user
         let user_id = sqlx::query_scalar::<_, Uuid>(
             "INSERT INTO users (email, password_hash, full_name, city, country, email_verified) 
@@ -81,13 +156,13 @@ impl AuthService {
         .bind(full_name)
         .bind(city)
         .bind(country)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
         // Initialize user score
         sqlx::query("INSERT INTO user_scores (user_id) VALUES ($1)")
             .bind(user_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
         // Generate verification token
@@ -102,18 +177,31 @@ impl AuthService {
         .bind(user_id)
         .bind(&token_hash)
         .bind(expires_at)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        // Send verification email (send plain token to user, hash stored in DB)
-        self.email_service
-            .send_verification_email(email, full_name, &token)
-            .await?;
+        // Queue the verification email (plain token to the user, hash stored in DB)
+        let verification_email = self
+            .email_service
+            .render_verification_email(full_name, &token);
+        let outbox_id = EmailOutboxService::enqueue(&mut tx, email, &verification_email).await?;
+
+        tx.commit().await?;
+
+        // The account exists now; if sending fails the outbox job retries it
+        if let Err(e) = self.email_outbox.send(outbox_id).await {
+            tracing::error!("Failed to send verification email to {}: {}", email, e);
+        }
 
         Ok("Registration successful. Please check your email to verify your account.".to_string())
     }
 
-    pub async fn login_user(&self, email: &str, password: &str) -> Result<AuthTokens> {
+    pub async fn login_user(
+        &self,
+        email: &str,
+        password: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginOutcome> {
         // Get user
         let user =
             sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1 AND is_active = true")
@@ -135,11 +223,32 @@ impl AuthService {
             None => return Err(AppError::Auth("Please use OAuth to login".to_string())),
         }
 
-        // Generate tokens
-        self.create_auth_tokens(user).await
+        self.complete_login(user, client).await
+    }
+
+    /// Finish a login that was waiting on a TOTP or backup code
+    pub async fn complete_two_factor_login(
+        &self,
+        challenge_token: &str,
+        code: &str,
+        client: &ClientInfo,
+    ) -> Result<AuthTokens> {
+        let user_id = self
+            .two_factor_service
+            .complete_challenge(challenge_token, code)
+            .await?;
+
+        let user =
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND is_active = true")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| AppError::Forbidden("Account is deactivated".to_string()))?;
+
+        self.create_auth_tokens(user, true, client).await
     }
 
-    pub async fn verify_email(&self, token: &str) -> Result<AuthTokens> {
+    pub async fn verify_email(&self, token: &str, client: &ClientInfo) -> Result<AuthTokens> {
         // Hash the token for database lookup
         let token_hash = hash_token(token);
 
@@ -180,7 +289,7 @@ impl AuthService {
             .fetch_one(&self.pool)
             .await?;
 
-        self.create_auth_tokens(user).await
+        self.create_auth_tokens(user, false, client).await
     }
 
     pub async fn resend_verification(&self, email: &str) -> Result<String> {
@@ -194,13 +303,62 @@ impl AuthService {
             return Err(AppError::BadRequest("Email already verified".to_string()));
         }
 
-        // Delete old tokens
+        let token = self.replace_verification_token(user.id).await?;
+
+        // Send email (send plain token to user, hash stored in DB)
+        self.email_service
+            .send_verification_email(&user.email, &user.full_name, &token)
+            .await?;
+
+        Ok("Verification email sent".to_string())
+    }
+
+    /// Email an unverified user a fresh verification link plus a link to opt out of
+    /// further reminders
+    pub async fn send_verification_reminder(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        full_name: &str,
+    ) -> Result<()> {
+        let token = self.replace_verification_token(user_id).await?;
+
+        let unsubscribe_token = generate_token();
+        sqlx::query("UPDATE users SET reminder_unsubscribe_token = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(hash_token(&unsubscribe_token))
+            .execute(&self.pool)
+            .await?;
+
+        self.email_service
+            .send_verification_reminder(email, full_name, &token, &unsubscribe_token)
+            .await
+    }
+
+    /// Stop verification reminders for the account a reminder's unsubscribe link came from
+    pub async fn unsubscribe_verification_reminders(&self, token: &str) -> Result<String> {
+        let result = sqlx::query(
+            "UPDATE users SET verification_reminders_unsubscribed = TRUE
+             WHERE reminder_unsubscribe_token = $1",
+        )
+        .bind(hash_token(token))
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::BadRequest("Invalid unsubscribe link".to_string()));
+        }
+
+        Ok("You won't receive any more verification reminders".to_string())
+    }
+
+    /// Replace any outstanding verification token with a new one, returning it in plain text
+    async fn replace_verification_token(&self, user_id: Uuid) -> Result<String> {
         sqlx::query("DELETE FROM email_verification_tokens WHERE user_id = $1")
-            .bind(user.id)
+            .bind(user_id)
             .execute(&self.pool)
             .await?;
 
-        // Generate new token
         let token = generate_token();
         let token_hash = hash_token(&token);
         let expires_at = Utc::now() + Duration::hours(self.config.email.verification_expiry_hours);
@@ -209,18 +367,13 @@ impl AuthService {
             "INSERT INTO email_verification_tokens (user_id, token, expires_at) 
              VALUES ($1, $2, $3)",
         )
-        .bind(user.id)
+        .bind(user_id)
         .bind(&token_hash)
         .bind(expires_at)
         .execute(&self.pool)
         .await?;
 
-        // Send email (send plain token to user, hash stored in DB)
-        self.email_service
-            .send_verification_email(&user.email, &user.full_name, &token)
-            .await?;
-
-        Ok("Verification email sent".to_string())
+        Ok(token)
     }
 
     pub async fn forgot_password(&self, email: &str) -> Result<String> {
@@ -271,6 +424,82 @@ impl AuthService {
         Ok("If the email exists, a password reset link has been sent".to_string())
     }
 
+    /// Email a one-time sign-in link. Responds the same whether or not the account exists.
+    pub async fn request_magic_link(&self, email: &str) -> Result<String> {
+        const RESPONSE: &str = "If the email exists, a sign-in link has been sent";
+
+        let Some(user) =
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1 AND is_active = true")
+                .bind(email)
+                .fetch_optional(&self.pool)
+                .await?
+        else {
+            return Ok(RESPONSE.to_string());
+        };
+
+        // Only the latest link is valid
+        sqlx::query("DELETE FROM magic_link_tokens WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&self.pool)
+            .await?;
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::minutes(MAGIC_LINK_EXPIRY_MINUTES);
+
+        sqlx::query(
+            "INSERT INTO magic_link_tokens (user_id, token_hash, expires_at)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(user.id)
+        .bind(hash_token(&token))
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        // Send email (send plain token to user, hash stored in DB)
+        self.email_service
+            .send_magic_link_email(&user.email, &user.full_name, &token)
+            .await?;
+
+        Ok(RESPONSE.to_string())
+    }
+
+    /// Exchange a magic link token for a login, subject to two-factor like a password login
+    pub async fn verify_magic_link(
+        &self,
+        token: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginOutcome> {
+        // Tokens are single-use: consume it whatever the outcome
+        let link = sqlx::query_as::<_, TokenRecord>(
+            "DELETE FROM magic_link_tokens WHERE token_hash = $1
+             RETURNING user_id, expires_at",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Invalid or expired sign-in link".to_string()))?;
+
+        if link.expires_at < Utc::now() {
+            return Err(AppError::BadRequest("Sign-in link has expired".to_string()));
+        }
+
+        // Following the emailed link proves ownership of the address
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users
+             SET email_verified = true,
+                 email_verified_at = COALESCE(email_verified_at, NOW())
+             WHERE id = $1 AND is_active = true
+             RETURNING *",
+        )
+        .bind(link.user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::Forbidden("Account is deactivated".to_string()))?;
+
+        self.complete_login(user, client).await
+    }
+
     pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<String> {
         // Hash the token for database lookup
         let token_hash = hash_token(token);
@@ -309,11 +538,8 @@ impl AuthService {
             .execute(&self.pool)
             .await?;
 
-        // Invalidate all refresh tokens for security
-        sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
-            .bind(reset.user_id)
-            .execute(&self.pool)
-            .await?;
+        // Sign the user out everywhere for security
+        self.revoke_all_sessions(reset.user_id).await?;
 
         // Get user and send confirmation
         let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
@@ -328,13 +554,17 @@ impl AuthService {
         Ok("Password successfully reset".to_string())
     }
 
-    pub async fn refresh_access_token(&self, refresh_token: &str) -> Result<String> {
+    pub async fn refresh_access_token(
+        &self,
+        refresh_token: &str,
+        client: &ClientInfo,
+    ) -> Result<String> {
         // Hash the refresh token for database lookup
         let token_hash = hash_token(refresh_token);
 
         // Verify the refresh token exists and is valid
-        let token_record = sqlx::query_as::<_, TokenRecord>(
-            "SELECT user_id, expires_at FROM refresh_tokens WHERE token_hash = $1",
+        let token_record = sqlx::query_as::<_, RefreshTokenRecord>(
+            "SELECT id, user_id, expires_at, two_factor_verified FROM refresh_tokens WHERE token_hash = $1",
         )
         .bind(&token_hash)
         .fetch_optional(&self.pool)
@@ -357,10 +587,28 @@ impl AuthService {
                 .fetch_one(&self.pool)
                 .await?;
 
-        // Generate new access token
-        let access_token =
-            self.jwt_service
-                .create_access_token(user.id, &user.email, &user.role)?;
+        // Record activity so the session list shows where the session was last used
+        sqlx::query(
+            "UPDATE refresh_tokens
+             SET last_used_at = NOW(),
+                 ip_address = COALESCE($2, ip_address),
+                 user_agent = COALESCE($3, user_agent)
+             WHERE id = $1",
+        )
+        .bind(token_record.id)
+        .bind(&client.ip_address)
+        .bind(&client.user_agent)
+        .execute(&self.pool)
+        .await?;
+
+        // Generate new access token, carrying over the session's two-factor state
+        let access_token = self.jwt_service.create_access_token(
+            user.id,
+            &user.email,
+            &user.role,
+            token_record.two_factor_verified,
+            token_record.id,
+        )?;
 
         Ok(access_token)
     }
@@ -377,8 +625,101 @@ impl AuthService {
         Ok("Logged out successfully".to_string())
     }
 
+    /// Sign a user out of every device: drop all refresh tokens and reject
+    /// access tokens issued before now
+    pub async fn logout_all(&self, user_id: Uuid) -> Result<String> {
+        self.revoke_all_sessions(user_id).await?;
+        Ok("Logged out from all devices".to_string())
+    }
+
+    /// Issue a short-lived token limited to `scope` for the signed-in user's session
+    pub fn create_service_token(
+        &self,
+        auth_user: &AuthUser,
+        scope: TokenScope,
+    ) -> Result<ServiceTokenResponse> {
+        let token = self.jwt_service.create_service_token(
+            auth_user.id,
+            &auth_user.email,
+            scope,
+            auth_user.session_id,
+        )?;
+
+        Ok(ServiceTokenResponse {
+            token,
+            scope,
+            expires_in: self.jwt_service.service_token_expiry(),
+        })
+    }
+
+    /// Active (unexpired) sessions for a user, most recently used first
+    pub async fn list_sessions(
+        &self,
+        user_id: Uuid,
+        current_session: Option<Uuid>,
+    ) -> Result<Vec<SessionResponse>> {
+        let sessions = sqlx::query_as::<_, SessionResponse>(
+            "SELECT id, user_agent, ip_address, created_at, last_used_at, expires_at,
+                    (id = $2) IS TRUE AS current
+             FROM refresh_tokens
+             WHERE user_id = $1 AND expires_at > NOW()
+             ORDER BY last_used_at DESC",
+        )
+        .bind(user_id)
+        .bind(current_session)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Every sign-in to the user's account, newest first, including revoked sessions
+    pub async fn list_logins(
+        &self,
+        user_id: Uuid,
+        page: &PageParams,
+    ) -> Result<Paginated<LoginEvent>> {
+        self.login_history.list(user_id, page).await
+    }
+
+    /// Revoke one of the user's sessions so its refresh token stops working
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<String> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE id = $1 AND user_id = $2")
+            .bind(session_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Session not found".to_string()));
+        }
+
+        Ok("Session revoked".to_string())
+    }
+
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE users SET tokens_invalidated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     /// Login or create user via OAuth
-    pub async fn oauth_login(&self, oauth_info: OAuthUserInfo) -> Result<AuthTokens> {
+    pub async fn oauth_login(
+        &self,
+        oauth_info: OAuthUserInfo,
+        client: &ClientInfo,
+    ) -> Result<OAuthLoginOutcome> {
         // Check if user exists with this OAuth provider and subject
         let existing_user = sqlx::query_as::<_, User>(
             "SELECT * FROM users WHERE oauth_provider = $1 AND oauth_subject = $2",
@@ -403,9 +744,7 @@ impl AuthService {
                     .await?;
 
             if email_exists > 0 {
-                return Err(AppError::Conflict(
-                    "Email already registered. Please login with your password or link your Google account.".to_string()
-                ));
+                return self.request_oauth_link(&oauth_info).await;
             }
 
             // Create new OAuth user
@@ -448,30 +787,590 @@ impl AuthService {
                 .await?
         };
 
-        // Generate auth tokens
-        self.create_auth_tokens(user).await
+        Ok(match self.complete_login(user, client).await? {
+            LoginOutcome::Authenticated(tokens) => OAuthLoginOutcome::LoggedIn(tokens),
+            LoginOutcome::TwoFactorRequired(challenge) => {
+                OAuthLoginOutcome::TwoFactorRequired(challenge)
+            }
+        })
+    }
+
+    /// An OAuth login matched an existing account by email. Rather than linking
+    /// automatically, email the account owner a one-time confirmation link.
+    async fn request_oauth_link(&self, oauth_info: &OAuthUserInfo) -> Result<OAuthLoginOutcome> {
+        // Without a verified address the provider proves nothing about mailbox ownership
+        if !oauth_info.email_verified {
+            return Err(AppError::Conflict(
+                "Email already registered. Please login with your password.".to_string(),
+            ));
+        }
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+            .bind(&oauth_info.email)
+            .fetch_one(&self.pool)
+            .await?;
+
+        if !user.is_active {
+            return Err(AppError::Forbidden("Account is deactivated".to_string()));
+        }
+
+        if user.oauth_provider.is_some() {
+            return Err(AppError::Conflict(
+                "Email already registered with a different sign-in provider.".to_string(),
+            ));
+        }
+
+        // Only the latest link request is valid
+        sqlx::query("DELETE FROM oauth_link_tokens WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&self.pool)
+            .await?;
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::hours(1);
+
+        sqlx::query(
+            "INSERT INTO oauth_link_tokens (user_id, token_hash, oauth_provider, oauth_subject, expires_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(user.id)
+        .bind(hash_token(&token))
+        .bind("google")
+        .bind(&oauth_info.oauth_subject)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.email_service
+            .send_account_link_email(&user.email, &user.full_name, &token)
+            .await?;
+
+        Ok(OAuthLoginOutcome::LinkConfirmationRequired)
+    }
+
+    /// Attach a pending OAuth identity to the account that confirmed it by email
+    pub async fn confirm_oauth_link(
+        &self,
+        token: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginOutcome> {
+        let mut tx = self.pool.begin().await?;
+
+        // Tokens are single-use: consume it whatever the outcome
+        let link = sqlx::query_as::<_, OAuthLinkRecord>(
+            "DELETE FROM oauth_link_tokens WHERE token_hash = $1
+             RETURNING user_id, oauth_provider, oauth_subject, expires_at",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Invalid or expired link token".to_string()))?;
+
+        if link.expires_at < Utc::now() {
+            tx.commit().await?;
+            return Err(AppError::BadRequest("Link token has expired".to_string()));
+        }
+
+        let subject_taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE oauth_provider = $1 AND oauth_subject = $2)",
+        )
+        .bind(&link.oauth_provider)
+        .bind(&link.oauth_subject)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if subject_taken {
+            tx.commit().await?;
+            return Err(AppError::Conflict(
+                "This Google account is already linked to another user".to_string(),
+            ));
+        }
+
+        // Following the emailed link also proves ownership of the address
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users
+             SET oauth_provider = $2,
+                 oauth_subject = $3,
+                 email_verified = true,
+                 email_verified_at = COALESCE(email_verified_at, NOW())
+             WHERE id = $1 AND oauth_provider IS NULL
+             RETURNING *",
+        )
+        .bind(link.user_id)
+        .bind(&link.oauth_provider)
+        .bind(&link.oauth_subject)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let user = user.ok_or_else(|| {
+            AppError::Conflict("Account is already linked to a sign-in provider".to_string())
+        })?;
+
+        if !user.is_active {
+            return Err(AppError::Forbidden("Account is deactivated".to_string()));
+        }
+
+        self.complete_login(user, client).await
+    }
+
+    /// Confirm the user's identity before a sensitive account change: their password,
+    /// plus a TOTP or backup code when two-factor is enabled
+    pub async fn reauthenticate(
+        &self,
+        user_id: Uuid,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<User> {
+        let user =
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND is_active = true")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        match &user.password_hash {
+            Some(hash) => self.verify_password(password, hash)?,
+            None => {
+                return Err(AppError::BadRequest(
+                    "This account has no password to re-authenticate with; add one first"
+                        .to_string(),
+                ))
+            }
+        }
+
+        self.verify_second_factor(user_id, code).await?;
+
+        Ok(user)
+    }
+
+    /// Check the TOTP or backup code of a user who has two-factor enabled
+    async fn verify_second_factor(&self, user_id: Uuid, code: Option<&str>) -> Result<()> {
+        if self.two_factor_service.is_enabled(user_id).await? {
+            let code =
+                code.ok_or_else(|| AppError::Auth("A two-factor code is required".to_string()))?;
+            if !self.two_factor_service.verify_code(user_id, code).await? {
+                return Err(AppError::Auth("Invalid verification code".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Email a link for adding a password to an account that signs in only with a
+    /// provider. The link goes to the account's own address, which re-authenticates the
+    /// user in place of the password they don't have.
+    pub async fn request_password_setup(
+        &self,
+        user_id: Uuid,
+        code: Option<&str>,
+    ) -> Result<String> {
+        let user =
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND is_active = true")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        if user.password_hash.is_some() {
+            return Err(AppError::BadRequest(
+                "This account already has a password; change it instead".to_string(),
+            ));
+        }
+
+        self.verify_second_factor(user_id, code).await?;
+
+        // Only the latest link is valid
+        sqlx::query("DELETE FROM password_setup_tokens WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&self.pool)
+            .await?;
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::minutes(PASSWORD_SETUP_EXPIRY_MINUTES);
+
+        sqlx::query(
+            "INSERT INTO password_setup_tokens (user_id, token_hash, expires_at)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(user.id)
+        .bind(hash_token(&token))
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.email_service
+            .send_password_setup_email(&user.email, &user.full_name, &token)
+            .await?;
+
+        Ok(format!(
+            "We sent a link to {} to add a password to your account.",
+            user.email
+        ))
+    }
+
+    /// Add a password from an emailed setup link. Existing sessions stay signed in,
+    /// since the account only gains a way to sign in.
+    pub async fn set_password(&self, token: &str, new_password: &str) -> Result<String> {
+        let password_hash = self.hash_password(new_password)?;
+        let mut tx = self.pool.begin().await?;
+
+        // Tokens are single-use: consume it whatever the outcome
+        let setup = sqlx::query_as::<_, TokenRecord>(
+            "DELETE FROM password_setup_tokens WHERE token_hash = $1
+             RETURNING user_id, expires_at",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest("Invalid or expired password setup token".to_string())
+        })?;
+
+        if setup.expires_at < Utc::now() {
+            tx.commit().await?;
+            return Err(AppError::BadRequest(
+                "Password setup token has expired".to_string(),
+            ));
+        }
+
+        let result = sqlx::query(
+            "UPDATE users SET password_hash = $2 WHERE id = $1 AND password_hash IS NULL",
+        )
+        .bind(setup.user_id)
+        .bind(password_hash)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "This account already has a password".to_string(),
+            ));
+        }
+
+        Ok("Password added. You can now sign in with your email and password.".to_string())
+    }
+
+    /// Change the password of a signed-in user, signing them out everywhere
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
+        code: Option<&str>,
+    ) -> Result<String> {
+        let user = self.reauthenticate(user_id, current_password, code).await?;
+
+        if current_password == new_password {
+            return Err(AppError::BadRequest(
+                "New password must differ from the current one".to_string(),
+            ));
+        }
+
+        let password_hash = self.hash_password(new_password)?;
+
+        sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+            .bind(password_hash)
+            .bind(user.id)
+            .execute(&self.pool)
+            .await?;
+
+        // Sessions started with the old password may belong to whoever knew it
+        self.revoke_all_sessions(user.id).await?;
+
+        self.email_service
+            .send_password_reset_confirmation(&user.email, &user.full_name)
+            .await?;
+
+        Ok("Password changed. Please sign in again.".to_string())
+    }
+
+    /// Email a confirmation link to a new address; the account keeps its current
+    /// address until the link is followed
+    pub async fn request_email_change(
+        &self,
+        user_id: Uuid,
+        new_email: &str,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<String> {
+        let user = self.reauthenticate(user_id, password, code).await?;
+        let new_email = new_email.trim();
+
+        if new_email.eq_ignore_ascii_case(&user.email) {
+            return Err(AppError::BadRequest(
+                "That is already your email address".to_string(),
+            ));
+        }
+
+        let email_taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE LOWER(email) = LOWER($1))",
+        )
+        .bind(new_email)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if email_taken {
+            return Err(AppError::Conflict("Email already registered".to_string()));
+        }
+
+        // Only the latest change request is valid
+        sqlx::query("DELETE FROM email_change_tokens WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&self.pool)
+            .await?;
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::hours(EMAIL_CHANGE_EXPIRY_HOURS);
+
+        sqlx::query(
+            "INSERT INTO email_change_tokens (user_id, new_email, token_hash, expires_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(user.id)
+        .bind(new_email)
+        .bind(hash_token(&token))
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        // Send to the new address, proving the user controls it
+        self.email_service
+            .send_email_change_email(new_email, &user.full_name, &token)
+            .await?;
+
+        Ok(format!(
+            "Confirmation email sent to {new_email}. Your email will change once you follow the link."
+        ))
+    }
+
+    /// Swap in the new address and sign the user out everywhere
+    pub async fn confirm_email_change(&self, token: &str) -> Result<String> {
+        let mut tx = self.pool.begin().await?;
+
+        // Tokens are single-use: consume it whatever the outcome
+        let change = sqlx::query_as::<_, EmailChangeRecord>(
+            "DELETE FROM email_change_tokens WHERE token_hash = $1
+             RETURNING user_id, new_email, expires_at",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Invalid or expired email change token".to_string()))?;
+
+        if change.expires_at < Utc::now() {
+            tx.commit().await?;
+            return Err(AppError::BadRequest(
+                "Email change token has expired".to_string(),
+            ));
+        }
+
+        // The address may have been registered since the change was requested
+        let email_taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE LOWER(email) = LOWER($1) AND id <> $2)",
+        )
+        .bind(&change.new_email)
+        .bind(change.user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if email_taken {
+            tx.commit().await?;
+            return Err(AppError::Conflict("Email already registered".to_string()));
+        }
+
+        sqlx::query(
+            "UPDATE users
+             SET email = $2,
+                 email_verified = true,
+                 email_verified_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(change.user_id)
+        .bind(&change.new_email)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        // Existing sessions were issued for the old address
+        self.revoke_all_sessions(change.user_id).await?;
+
+        Ok("Email address updated. Please sign in again.".to_string())
+    }
+
+    /// Remember that the OAuth flow with this state was started by a signed-in user
+    /// who wants to link the provider, rather than to log in
+    pub async fn begin_oauth_link(
+        &self,
+        user: &User,
+        provider: &str,
+        oauth_state: &str,
+    ) -> Result<()> {
+        if user.oauth_provider.is_some() {
+            return Err(AppError::Conflict(
+                "Account is already linked to a sign-in provider".to_string(),
+            ));
+        }
+
+        let expires_at = Utc::now() + Duration::minutes(OAUTH_LINK_REQUEST_EXPIRY_MINUTES);
+
+        sqlx::query(
+            "INSERT INTO oauth_link_requests (user_id, state_hash, oauth_provider, expires_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(user.id)
+        .bind(hash_token(oauth_state))
+        .bind(provider)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The user who started a link flow with this OAuth state, if it was one
+    pub async fn take_oauth_link_request(&self, oauth_state: &str) -> Result<Option<Uuid>> {
+        let request = sqlx::query_as::<_, TokenRecord>(
+            "DELETE FROM oauth_link_requests WHERE state_hash = $1
+             RETURNING user_id, expires_at",
+        )
+        .bind(hash_token(oauth_state))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match request {
+            Some(request) if request.expires_at < Utc::now() => Err(AppError::BadRequest(
+                "Account link request has expired".to_string(),
+            )),
+            Some(request) => Ok(Some(request.user_id)),
+            None => Ok(None),
+        }
+    }
+
+    /// Attach a provider identity to a signed-in user's account
+    pub async fn link_oauth_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        oauth_info: &OAuthUserInfo,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let subject_taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE oauth_provider = $1 AND oauth_subject = $2)",
+        )
+        .bind(provider)
+        .bind(&oauth_info.oauth_subject)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if subject_taken {
+            return Err(AppError::Conflict(
+                "This Google account is already linked to another user".to_string(),
+            ));
+        }
+
+        let result = sqlx::query(
+            "UPDATE users SET oauth_provider = $2, oauth_subject = $3
+             WHERE id = $1 AND oauth_provider IS NULL",
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(&oauth_info.oauth_subject)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "Account is already linked to a sign-in provider".to_string(),
+            ));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Detach a provider identity. The account must keep a password so it can still sign in.
+    pub async fn unlink_oauth_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<String> {
+        let user = self.reauthenticate(user_id, password, code).await?;
+
+        if user.oauth_provider.as_deref() != Some(provider) {
+            return Err(AppError::NotFound(format!(
+                "No {provider} account is linked"
+            )));
+        }
+
+        sqlx::query(
+            "UPDATE users SET oauth_provider = NULL, oauth_subject = NULL
+             WHERE id = $1 AND oauth_provider = $2",
+        )
+        .bind(user_id)
+        .bind(provider)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(format!("Unlinked {provider} account"))
     }
 
     // Helper methods
 
-    async fn create_auth_tokens(&self, user: User) -> Result<AuthTokens> {
-        let access_token =
-            self.jwt_service
-                .create_access_token(user.id, &user.email, &user.role)?;
+    /// Issue tokens, or a two-factor challenge if the user has TOTP enabled
+    async fn complete_login(&self, user: User, client: &ClientInfo) -> Result<LoginOutcome> {
+        if self.two_factor_service.is_enabled(user.id).await? {
+            let challenge = self.two_factor_service.create_challenge(user.id).await?;
+            return Ok(LoginOutcome::TwoFactorRequired(challenge));
+        }
 
+        Ok(LoginOutcome::Authenticated(
+            self.create_auth_tokens(user, false, client).await?,
+        ))
+    }
+
+    async fn create_auth_tokens(
+        &self,
+        user: User,
+        two_factor_verified: bool,
+        client: &ClientInfo,
+    ) -> Result<AuthTokens> {
         let refresh_token = generate_token();
         let token_hash = hash_token(&refresh_token);
         let expires_at = Utc::now() + Duration::seconds(self.config.jwt.refresh_expiry);
 
-        sqlx::query(
-            "INSERT INTO refresh_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+        let session_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO refresh_tokens
+                 (user_id, token_hash, expires_at, two_factor_verified, user_agent, ip_address)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id",
         )
         .bind(user.id)
         .bind(&token_hash)
         .bind(expires_at)
-        .execute(&self.pool)
+        .bind(two_factor_verified)
+        .bind(&client.user_agent)
+        .bind(&client.ip_address)
+        .fetch_one(&self.pool)
         .await?;
 
+        self.login_history.record(&user, session_id, client).await?;
+
+        let access_token = self.jwt_service.create_access_token(
+            user.id,
+            &user.email,
+            &user.role,
+            two_factor_verified,
+            session_id,
+        )?;
+
         Ok(AuthTokens {
             access_token,
             refresh_token, // Return plain token to user, hash stored in DB
@@ -479,7 +1378,8 @@ impl AuthService {
         })
     }
 
-    fn hash_password(&self, password: &str) -> Result<String> {
+    /// Argon2 hash of `password` in PHC string format
+    pub fn hash_password(&self, password: &str) -> Result<String> {
         let salt = SaltString::generate(&mut OsRng);
         let argon2 = Argon2::default();
 
@@ -0,0 +1,80 @@
+use crate::config::CaptchaConfig;
+use crate::error::AppError;
+use serde::Deserialize;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT_SECS: u64 = 5;
+
+/// `siteverify` response; hCaptcha and Turnstile share this shape
+#[derive(Debug, Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// Checks CAPTCHA tokens with the configured provider. Without a provider every
+/// request passes, so development and tests need no CAPTCHA keys.
+#[derive(Clone)]
+pub struct CaptchaService {
+    client: reqwest::Client,
+    config: Option<CaptchaConfig>,
+}
+
+impl CaptchaService {
+    #[must_use]
+    pub fn new(config: Option<CaptchaConfig>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self { client, config }
+    }
+
+    /// Reject the request unless `token` is a valid, unused CAPTCHA solution.
+    /// Fails closed: if the provider cannot be reached the request is refused.
+    pub async fn verify(
+        &self,
+        token: Option<&str>,
+        remote_ip: Option<&str>,
+    ) -> Result<(), AppError> {
+        let Some(config) = &self.config else {
+            return Ok(());
+        };
+        let token = token
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| AppError::BadRequest("CAPTCHA token is required".to_string()))?;
+
+        let mut form = vec![("secret", config.secret_key.as_str()), ("response", token)];
+        if let Some(ip) = remote_ip {
+            form.push(("remoteip", ip));
+        }
+
+        let response: SiteVerifyResponse = self
+            .client
+            .post(&config.verify_url)
+            .form(&form)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| anyhow::anyhow!("CAPTCHA verification request failed: {e}"))?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Invalid CAPTCHA verification response: {e}"))?;
+
+        if !response.success {
+            tracing::warn!(
+                "CAPTCHA rejected by {:?}: {:?}",
+                config.provider,
+                response.error_codes
+            );
+            return Err(AppError::BadRequest(
+                "CAPTCHA verification failed".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use crate::error::AppError;
+use crate::models::city_goal::{CityGoalProgress, CreateCityGoalRequest, UpdateCityGoalRequest};
+use crate::models::notification::NotificationKind;
+use crate::services::notification_service::NotificationService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Columns of `CityGoalProgress` for goal `g`, counted over the current week
+const CITY_GOAL_PROGRESS_COLUMNS: &str = r"
+    g.id, g.city, g.weekly_target,
+    (SELECT COUNT(*)
+     FROM litter_reports r
+     JOIN users u ON u.id = r.cleared_by
+     WHERE r.status IN ('cleared', 'verified') AND r.hidden_at IS NULL
+       AND r.cleared_at >= w.week_start
+       AND LOWER(COALESCE(r.address_city, u.city)) = LOWER(g.city)) AS cleared,
+    w.week_start AS week_starts_at,
+    w.week_start + INTERVAL '1 week' AS week_ends_at,
+    (SELECT c.reached_at FROM city_goal_completions c
+     WHERE c.goal_id = g.id AND c.week_start = w.week_start) AS reached_at
+";
+
+/// The current week, Monday 00:00 UTC onwards, joined as `w`
+const CURRENT_WEEK: &str =
+    "CROSS JOIN (SELECT date_trunc('week', NOW() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS week_start) w";
+
+#[derive(Clone)]
+pub struct CityGoalService {
+    pool: PgPool,
+    notification_service: NotificationService,
+}
+
+impl CityGoalService {
+    #[must_use]
+    pub fn new(pool: PgPool, notification_service: NotificationService) -> Self {
+        Self {
+            pool,
+            notification_service,
+        }
+    }
+
+    /// Every goal with this week's progress, or just the one for `city`
+    pub async fn list_progress(
+        &self,
+        city: Option<&str>,
+    ) -> Result<Vec<CityGoalProgress>, AppError> {
+        let goals = sqlx::query_as::<_, CityGoalProgress>(&format!(
+            "SELECT {CITY_GOAL_PROGRESS_COLUMNS} FROM city_goals g {CURRENT_WEEK}
+             WHERE $1::text IS NULL OR LOWER(g.city) = LOWER($1)
+             ORDER BY g.city"
+        ))
+        .bind(city)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(goals)
+    }
+
+    pub async fn get_progress(&self, goal_id: Uuid) -> Result<CityGoalProgress, AppError> {
+        sqlx::query_as::<_, CityGoalProgress>(&format!(
+            "SELECT {CITY_GOAL_PROGRESS_COLUMNS} FROM city_goals g {CURRENT_WEEK} WHERE g.id = $1"
+        ))
+        .bind(goal_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("City goal not found".to_string()))
+    }
+
+    /// Set up a weekly goal for a city; each city has at most one
+    pub async fn create_goal(
+        &self,
+        admin_id: Uuid,
+        request: CreateCityGoalRequest,
+    ) -> Result<CityGoalProgress, AppError> {
+        let city = request.city.trim();
+        if city.is_empty() {
+            return Err(AppError::BadRequest("City is required".to_string()));
+        }
+
+        let goal_id = sqlx::query_scalar::<_, Uuid>(
+            r"
+            INSERT INTO city_goals (city, weekly_target, created_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (LOWER(city)) DO NOTHING
+            RETURNING id
+            ",
+        )
+        .bind(city)
+        .bind(request.weekly_target)
+        .bind(admin_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::Conflict(format!("{city} already has a goal")))?;
+
+        self.get_progress(goal_id).await
+    }
+
+    /// Change a city's weekly target. A week already reached stays reached.
+    pub async fn update_goal(
+        &self,
+        goal_id: Uuid,
+        request: UpdateCityGoalRequest,
+    ) -> Result<CityGoalProgress, AppError> {
+        let updated = sqlx::query(
+            "UPDATE city_goals SET weekly_target = $2, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(goal_id)
+        .bind(request.weekly_target)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if updated == 0 {
+            return Err(AppError::NotFound("City goal not found".to_string()));
+        }
+        self.get_progress(goal_id).await
+    }
+
+    pub async fn delete_goal(&self, goal_id: Uuid) -> Result<(), AppError> {
+        let deleted = sqlx::query("DELETE FROM city_goals WHERE id = $1")
+            .bind(goal_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        if deleted == 0 {
+            return Err(AppError::NotFound("City goal not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Count a clear towards its city's goal. When it takes the city over this week's
+    /// target, the week is marked reached and everyone in the city, plus anyone who
+    /// cleared there this week, is told. Returns the goal's progress, if the city has one.
+    pub async fn record_clear(
+        &self,
+        report_id: Uuid,
+    ) -> Result<Option<CityGoalProgress>, AppError> {
+        let goal = sqlx::query_as::<_, CityGoalProgress>(&format!(
+            "SELECT {CITY_GOAL_PROGRESS_COLUMNS}
+             FROM litter_reports r
+             JOIN users u ON u.id = r.cleared_by
+             JOIN city_goals g ON LOWER(g.city) = LOWER(COALESCE(r.address_city, u.city))
+             {CURRENT_WEEK}
+             WHERE r.id = $1"
+        ))
+        .bind(report_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(mut goal) = goal else {
+            return Ok(None);
+        };
+        if goal.reached_at.is_some() || goal.cleared < i64::from(goal.weekly_target) {
+            return Ok(Some(goal));
+        }
+
+        // Only the clear that claims the week sends the celebration
+        let reached_at = sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>(
+            r"
+            INSERT INTO city_goal_completions (goal_id, week_start, target)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (goal_id, week_start) DO NOTHING
+            RETURNING reached_at
+            ",
+        )
+        .bind(goal.id)
+        .bind(goal.week_starts_at)
+        .bind(goal.weekly_target)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(reached_at) = reached_at {
+            goal.reached_at = Some(reached_at);
+            tracing::info!(
+                "{} reached its weekly goal of {} clears",
+                goal.city,
+                goal.weekly_target
+            );
+            self.celebrate(&goal).await?;
+        }
+        Ok(Some(goal))
+    }
+
+    async fn celebrate(&self, goal: &CityGoalProgress) -> Result<(), AppError> {
+        let recipients = sqlx::query_scalar::<_, Uuid>(
+            r"
+            SELECT id FROM users WHERE is_active AND LOWER(city) = LOWER($1)
+            UNION
+            SELECT r.cleared_by
+            FROM litter_reports r
+            JOIN users u ON u.id = r.cleared_by
+            WHERE r.cleared_at >= $2
+              AND LOWER(COALESCE(r.address_city, u.city)) = LOWER($1)
+            ",
+        )
+        .bind(&goal.city)
+        .bind(goal.week_starts_at)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.notification_service
+            .notify_many(
+                &recipients,
+                NotificationKind::CityGoalReached,
+                &format!("{} reached its weekly goal!", goal.city),
+                &format!(
+                    "Together you've cleared {} reports in {} this week. Thank you!",
+                    goal.weekly_target, goal.city
+                ),
+                None,
+            )
+            .await;
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+use crate::error::AppError;
+use crate::models::cleanup_event::{
+    CleanupEvent, CleanupEventsQuery, CreateCleanupEventRequest, MAX_REPORT_DISTANCE_KM,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Most events a nearby search returns
+const MAX_NEARBY_EVENTS: i64 = 50;
+
+/// Columns of `CleanupEvent`; `$1` is the user `attending` is worked out for
+const CLEANUP_EVENT_COLUMNS: &str = r"
+    e.id, e.organizer_id, e.title, e.description,
+    ST_Y(e.meeting_point)::double precision AS latitude,
+    ST_X(e.meeting_point)::double precision AS longitude,
+    e.meeting_point_description, e.starts_at, e.ends_at, e.created_at,
+    COALESCE(
+        (SELECT array_agg(l.report_id ORDER BY l.report_id)
+         FROM cleanup_event_reports l WHERE l.event_id = e.id),
+        '{}'
+    ) AS report_ids,
+    (SELECT COUNT(*) FROM cleanup_event_rsvps v WHERE v.event_id = e.id) AS attendee_count,
+    EXISTS (
+        SELECT 1 FROM cleanup_event_rsvps v WHERE v.event_id = e.id AND v.user_id = $1
+    ) AS attending
+";
+
+#[derive(Clone)]
+pub struct CleanupEventService {
+    pool: PgPool,
+    /// Points each attendee gets per linked report cleared during the event
+    bonus_points: i32,
+}
+
+impl CleanupEventService {
+    #[must_use]
+    pub fn new(pool: PgPool, bonus_points: i32) -> Self {
+        Self { pool, bonus_points }
+    }
+
+    /// Get an event as seen by `viewer_id`
+    pub async fn get_event(
+        &self,
+        event_id: Uuid,
+        viewer_id: Uuid,
+    ) -> Result<CleanupEvent, AppError> {
+        sqlx::query_as::<_, CleanupEvent>(&format!(
+            "SELECT {CLEANUP_EVENT_COLUMNS} FROM cleanup_events e WHERE e.id = $2"
+        ))
+        .bind(viewer_id)
+        .bind(event_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Cleanup event not found".to_string()))
+    }
+
+    /// Upcoming and ongoing events meeting near a point, soonest first
+    pub async fn list_nearby(
+        &self,
+        viewer_id: Uuid,
+        query: &CleanupEventsQuery,
+    ) -> Result<Vec<CleanupEvent>, AppError> {
+        let radius_km = query.radius_km.unwrap_or(10.0).clamp(0.1, 50.0);
+
+        let events = sqlx::query_as::<_, CleanupEvent>(&format!(
+            "SELECT {CLEANUP_EVENT_COLUMNS} FROM cleanup_events e
+             WHERE e.ends_at > NOW()
+               AND ST_DWithin(
+                   e.meeting_point::geography,
+                   ST_SetSRID(ST_MakePoint($3, $2), 4326)::geography,
+                   $4 * 1000.0
+               )
+             ORDER BY e.starts_at ASC, e.id ASC
+             LIMIT $5"
+        ))
+        .bind(viewer_id)
+        .bind(f64::from(query.latitude))
+        .bind(f64::from(query.longitude))
+        .bind(radius_km)
+        .bind(MAX_NEARBY_EVENTS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Create an event; the organiser is RSVPed to it
+    pub async fn create_event(
+        &self,
+        organizer_id: Uuid,
+        request: CreateCleanupEventRequest,
+    ) -> Result<CleanupEvent, AppError> {
+        request.check_window(Utc::now())?;
+
+        let mut report_ids = request.report_ids.clone();
+        report_ids.sort_unstable();
+        report_ids.dedup();
+
+        let mut tx = self.pool.begin().await?;
+
+        let event_id: Uuid = sqlx::query_scalar(
+            r"
+            INSERT INTO cleanup_events
+                (organizer_id, title, description, meeting_point,
+                 meeting_point_description, starts_at, ends_at)
+            VALUES ($1, $2, $3, ST_SetSRID(ST_MakePoint($5, $4), 4326), $6, $7, $8)
+            RETURNING id
+            ",
+        )
+        .bind(organizer_id)
+        .bind(request.title.trim())
+        .bind(request.description.as_deref().map(str::trim))
+        .bind(f64::from(request.latitude))
+        .bind(f64::from(request.longitude))
+        .bind(request.meeting_point_description.as_deref().map(str::trim))
+        .bind(request.starts_at)
+        .bind(request.ends_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Only open, visible reports near the meeting point can be taken on
+        let linked = sqlx::query(
+            r"
+            INSERT INTO cleanup_event_reports (event_id, report_id)
+            SELECT e.id, r.id
+            FROM cleanup_events e
+            JOIN litter_reports r ON r.id = ANY($2)
+            WHERE e.id = $1
+              AND r.status IN ('pending', 'claimed')
+              AND r.hidden_at IS NULL
+              AND ST_DWithin(r.location::geography, e.meeting_point::geography, $3 * 1000.0)
+            ",
+        )
+        .bind(event_id)
+        .bind(&report_ids)
+        .bind(MAX_REPORT_DISTANCE_KM)
+        .execute(&mut *tx)
+        .await?;
+
+        if linked.rows_affected() != report_ids.len() as u64 {
+            return Err(AppError::BadRequest(format!(
+                "Every report must exist, be open and lie within {MAX_REPORT_DISTANCE_KM} km of the meeting point"
+            )));
+        }
+
+        sqlx::query("INSERT INTO cleanup_event_rsvps (event_id, user_id) VALUES ($1, $2)")
+            .bind(event_id)
+            .bind(organizer_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        self.get_event(event_id, organizer_id).await
+    }
+
+    /// RSVP to an event that hasn't finished; RSVPing twice is fine
+    pub async fn rsvp(&self, event_id: Uuid, user_id: Uuid) -> Result<CleanupEvent, AppError> {
+        let event = self.get_event(event_id, user_id).await?;
+        if event.ends_at <= Utc::now() {
+            return Err(AppError::BadRequest(
+                "This event has already finished".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            "INSERT INTO cleanup_event_rsvps (event_id, user_id) VALUES ($1, $2)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_event(event_id, user_id).await
+    }
+
+    /// Withdraw an RSVP. The organiser can't leave their own event.
+    pub async fn cancel_rsvp(&self, event_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let event = self.get_event(event_id, user_id).await?;
+        if event.organizer_id == user_id {
+            return Err(AppError::BadRequest(
+                "The organiser can't withdraw from their own event".to_string(),
+            ));
+        }
+
+        let result =
+            sqlx::query("DELETE FROM cleanup_event_rsvps WHERE event_id = $1 AND user_id = $2")
+                .bind(event_id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "You haven't RSVPed to this event".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// After a clear, award every attendee of an ongoing event the report is linked to
+    /// the group bonus, if the clearer is one of them. Each linked report pays out once.
+    /// Returns the users awarded.
+    pub async fn award_group_bonus(
+        &self,
+        report_id: Uuid,
+        clearer_id: Uuid,
+    ) -> Result<Vec<Uuid>, AppError> {
+        if self.bonus_points <= 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let attendees: Vec<Uuid> = sqlx::query_scalar(
+            r"
+            WITH linked AS (
+                UPDATE cleanup_event_reports l
+                SET bonus_awarded_at = NOW()
+                FROM cleanup_events e
+                WHERE l.event_id = e.id
+                  AND l.report_id = $1
+                  AND l.bonus_awarded_at IS NULL
+                  AND NOW() >= e.starts_at AND NOW() < e.ends_at
+                  AND EXISTS (
+                      SELECT 1 FROM cleanup_event_rsvps v
+                      WHERE v.event_id = e.id AND v.user_id = $2
+                  )
+                RETURNING l.event_id
+            )
+            SELECT DISTINCT v.user_id
+            FROM cleanup_event_rsvps v
+            JOIN linked ON linked.event_id = v.event_id
+            ",
+        )
+        .bind(report_id)
+        .bind(clearer_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if attendees.is_empty() {
+            return Ok(attendees);
+        }
+
+        sqlx::query(
+            r"
+            INSERT INTO user_scores (user_id, total_points)
+            SELECT user_id, $2 FROM UNNEST($1::uuid[]) AS a(user_id)
+            ON CONFLICT (user_id)
+            DO UPDATE SET total_points = user_scores.total_points + EXCLUDED.total_points
+            ",
+        )
+        .bind(&attendees)
+        .bind(self.bonus_points)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+            INSERT INTO score_events (user_id, points, kind, report_id)
+            SELECT user_id, $2, 'cleanup_event_bonus', $3 FROM UNNEST($1::uuid[]) AS a(user_id)
+            ",
+        )
+        .bind(&attendees)
+        .bind(self.bonus_points)
+        .bind(report_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(attendees)
+    }
+}
@@ -0,0 +1,280 @@
+use crate::error::AppError;
+use crate::models::data_export::{DataExport, DataExportResponse};
+use crate::models::notification::NotificationKind;
+use crate::services::notification_service::NotificationService;
+use crate::services::s3_service::{S3Service, StorageArea};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// How long a finished archive can be downloaded
+const EXPORT_EXPIRY_DAYS: i32 = 7;
+/// A pending export older than this was lost, e.g. to a restart, and is started afresh
+const STALE_PENDING_MINUTES: i32 = 60;
+
+/// Assembles a user's personal data into a JSON archive in the background and
+/// serves it back to them once ready
+#[derive(Clone)]
+pub struct DataExportService {
+    pool: PgPool,
+    s3_service: S3Service,
+    notification_service: NotificationService,
+}
+
+impl DataExportService {
+    #[must_use]
+    pub fn new(
+        pool: PgPool,
+        s3_service: S3Service,
+        notification_service: NotificationService,
+    ) -> Self {
+        Self {
+            pool,
+            s3_service,
+            notification_service,
+        }
+    }
+
+    /// Start an export, or return the one already in progress. Earlier archives are
+    /// deleted so only the newest copy of the user's data is kept.
+    pub async fn request(&self, user_id: Uuid) -> Result<DataExportResponse, AppError> {
+        if let Some(pending) = sqlx::query_as!(
+            DataExport,
+            r#"
+            SELECT id, user_id, status, s3_key, size_bytes, error,
+                   created_at, completed_at, expires_at
+            FROM data_exports
+            WHERE user_id = $1 AND status = 'pending'
+              AND created_at > NOW() - make_interval(mins => $2)
+            "#,
+            user_id,
+            STALE_PENDING_MINUTES
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        {
+            return Ok(pending.into());
+        }
+
+        let previous = sqlx::query!(
+            "DELETE FROM data_exports WHERE user_id = $1 RETURNING s3_key",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        for key in previous.into_iter().filter_map(|row| row.s3_key) {
+            if let Err(e) = self
+                .s3_service
+                .delete_image(StorageArea::Exports, &key)
+                .await
+            {
+                tracing::warn!("Failed to delete old data export {}: {:?}", key, e);
+            }
+        }
+
+        let export = sqlx::query_as!(
+            DataExport,
+            r#"
+            INSERT INTO data_exports (user_id)
+            VALUES ($1)
+            RETURNING id, user_id, status, s3_key, size_bytes, error,
+                      created_at, completed_at, expires_at
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let service = self.clone();
+        let export_id = export.id;
+        tokio::spawn(async move { service.generate(export_id, user_id).await });
+
+        Ok(export.into())
+    }
+
+    /// The user's most recent export
+    pub async fn latest(&self, user_id: Uuid) -> Result<DataExportResponse, AppError> {
+        let export = sqlx::query_as!(
+            DataExport,
+            r#"
+            SELECT id, user_id, status, s3_key, size_bytes, error,
+                   created_at, completed_at, expires_at
+            FROM data_exports
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No data export requested".to_string()))?;
+
+        Ok(export.into())
+    }
+
+    /// Fetch a finished archive. Exports belonging to other users look like missing ones.
+    pub async fn download(&self, user_id: Uuid, export_id: Uuid) -> Result<Vec<u8>, AppError> {
+        let s3_key = sqlx::query_scalar!(
+            r#"
+            SELECT s3_key AS "s3_key!"
+            FROM data_exports
+            WHERE id = $1 AND user_id = $2 AND status = 'ready'
+              AND s3_key IS NOT NULL AND expires_at > NOW()
+            "#,
+            export_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Export not found or expired".to_string()))?;
+
+        self.s3_service
+            .get_image(StorageArea::Exports, &s3_key)
+            .await
+    }
+
+    /// Build the archive: profile, score, reports, clears, verifications, feed posts,
+    /// comments and score events. Credentials and internal flags are left out.
+    pub async fn build_archive(&self, user_id: Uuid) -> Result<String, AppError> {
+        let archive = sqlx::query_scalar!(
+            r#"
+            SELECT json_build_object(
+                'exported_at', NOW(),
+                'profile', (
+                    SELECT row_to_json(u) FROM (
+                        SELECT id, email, full_name, city, country, search_radius_km, role,
+                               email_verified, email_verified_at, oauth_provider,
+                               created_at, updated_at
+                        FROM users WHERE id = $1
+                    ) u
+                ),
+                'score', (
+                    SELECT row_to_json(s) FROM (
+                        SELECT total_points, total_reports, total_clears, total_verifications,
+                               current_streak, longest_streak, last_cleared_date
+                        FROM user_scores WHERE user_id = $1
+                    ) s
+                ),
+                'reports', COALESCE((
+                    SELECT json_agg(r ORDER BY r.created_at) FROM (
+                        SELECT id, ST_Y(location) AS latitude, ST_X(location) AS longitude,
+                               description, status, address, photo_before, photo_after,
+                               (SELECT json_agg(p.url ORDER BY p.position) FROM report_photos p
+                                WHERE p.report_id = litter_reports.id AND p.kind = 'before')
+                                   AS before_photos,
+                               created_at, cleared_at
+                        FROM litter_reports WHERE reporter_id = $1
+                    ) r
+                ), '[]'::json),
+                'clears', COALESCE((
+                    SELECT json_agg(c ORDER BY c.cleared_at) FROM (
+                        SELECT id, ST_Y(location) AS latitude, ST_X(location) AS longitude,
+                               address, status, claimed_at, cleared_at, photo_after,
+                               (SELECT json_agg(p.url ORDER BY p.position) FROM report_photos p
+                                WHERE p.report_id = litter_reports.id AND p.kind = 'after')
+                                   AS after_photos
+                        FROM litter_reports WHERE cleared_by = $1
+                    ) c
+                ), '[]'::json),
+                'verifications', COALESCE((
+                    SELECT json_agg(v ORDER BY v.created_at) FROM (
+                        SELECT id, report_id, is_verified, comment, created_at
+                        FROM report_verifications WHERE verifier_id = $1
+                    ) v
+                ), '[]'::json),
+                'feed_posts', COALESCE((
+                    SELECT json_agg(p ORDER BY p.created_at) FROM (
+                        SELECT id, content, like_count, comment_count, created_at, updated_at
+                        FROM feed_posts WHERE user_id = $1
+                    ) p
+                ), '[]'::json),
+                'comments', COALESCE((
+                    SELECT json_agg(c ORDER BY c.created_at) FROM (
+                        SELECT id, post_id, content, is_deleted, created_at, updated_at
+                        FROM feed_comments WHERE user_id = $1
+                    ) c
+                ), '[]'::json),
+                'score_events', COALESCE((
+                    SELECT json_agg(e ORDER BY e.created_at) FROM (
+                        SELECT id, kind, points, report_id, created_at
+                        FROM score_events WHERE user_id = $1
+                    ) e
+                ), '[]'::json)
+            )::text AS "archive!"
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(archive)
+    }
+
+    async fn generate(&self, export_id: Uuid, user_id: Uuid) {
+        let result = async {
+            let archive = self.build_archive(user_id).await?;
+            let size_bytes = i64::try_from(archive.len()).unwrap_or(i64::MAX);
+            let s3_key = self
+                .s3_service
+                .object_key(&format!("exports/{}/{}.json", user_id, export_id));
+            self.s3_service
+                .put_object(
+                    StorageArea::Exports,
+                    &s3_key,
+                    archive.into_bytes(),
+                    "application/json",
+                )
+                .await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE data_exports
+                SET status = 'ready', s3_key = $2, size_bytes = $3, completed_at = NOW(),
+                    expires_at = NOW() + make_interval(days => $4)
+                WHERE id = $1
+                "#,
+                export_id,
+                s3_key,
+                size_bytes,
+                EXPORT_EXPIRY_DAYS
+            )
+            .execute(&self.pool)
+            .await?;
+
+            Ok::<_, AppError>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                tracing::info!("Data export {} ready for user {}", export_id, user_id);
+                self.notification_service
+                    .notify(
+                        user_id,
+                        NotificationKind::DataExportReady,
+                        "Your data export is ready",
+                        &format!("Download it from your profile within {EXPORT_EXPIRY_DAYS} days"),
+                        None,
+                    )
+                    .await;
+            }
+            Err(e) => {
+                tracing::error!("Data export {} failed: {:?}", export_id, e);
+                if let Err(e) = sqlx::query!(
+                    r#"
+                    UPDATE data_exports
+                    SET status = 'failed', error = $2, completed_at = NOW()
+                    WHERE id = $1
+                    "#,
+                    export_id,
+                    "The export could not be generated, please try again"
+                )
+                .execute(&self.pool)
+                .await
+                {
+                    tracing::error!("Failed to mark data export {} failed: {:?}", export_id, e);
+                }
+            }
+        }
+    }
+}
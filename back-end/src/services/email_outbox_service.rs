@@ -0,0 +1,143 @@
+use crate::error::Result;
+use crate::services::email_service::{EmailService, RenderedEmail};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Attempts after which a message is left for someone to look at
+const MAX_ATTEMPTS: i32 = 8;
+/// Messages claimed per sweep; the rest wait for the next tick
+const BATCH_SIZE: i64 = 50;
+/// Retry backoff doubles from one minute up to this cap
+const MAX_BACKOFF_MINS: i32 = 720;
+
+/// Durable queue for emails triggered by a database change. Enqueue inside the change's
+/// transaction, then call `send` after commit; `send_due` retries whatever is left.
+#[derive(Clone)]
+pub struct EmailOutboxService {
+    pool: PgPool,
+    email_service: EmailService,
+}
+
+impl EmailOutboxService {
+    #[must_use]
+    pub fn new(pool: PgPool, email_service: EmailService) -> Self {
+        Self {
+            pool,
+            email_service,
+        }
+    }
+
+    /// Queue `email` for `recipient`; it only becomes visible if `tx` commits
+    pub async fn enqueue(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        recipient: &str,
+        email: &RenderedEmail,
+    ) -> Result<Uuid> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO email_outbox (recipient, subject, html_body, text_body)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            recipient,
+            email.subject,
+            email.html_body,
+            email.text_body
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Try to send one queued message now, returning whether it went out. A failure is
+    /// recorded on the message and retried by `send_due`.
+    pub async fn send(&self, id: Uuid) -> Result<bool> {
+        Ok(self.claim_and_send(Some(id), 1).await? == 1)
+    }
+
+    /// Send messages whose retry time has come, returning how many went out
+    pub async fn send_due(&self) -> Result<usize> {
+        self.claim_and_send(None, BATCH_SIZE).await
+    }
+
+    /// Claim due messages by pushing back their next attempt, so concurrent senders on
+    /// other instances skip them, then deliver each
+    async fn claim_and_send(&self, id: Option<Uuid>, limit: i64) -> Result<usize> {
+        let messages = sqlx::query!(
+            r#"
+            UPDATE email_outbox
+            SET attempts = attempts + 1,
+                next_attempt_at = NOW() + make_interval(
+                    mins => LEAST(power(2, attempts)::int, $3)
+                )
+            WHERE id IN (
+                SELECT id FROM email_outbox
+                WHERE sent_at IS NULL
+                  AND attempts < $1
+                  AND next_attempt_at <= NOW()
+                  AND ($2::uuid IS NULL OR id = $2)
+                ORDER BY next_attempt_at
+                LIMIT $4
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, recipient, subject, html_body, text_body, attempts
+            "#,
+            MAX_ATTEMPTS,
+            id,
+            MAX_BACKOFF_MINS,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut sent = 0;
+        for message in messages {
+            let email = RenderedEmail {
+                subject: message.subject,
+                html_body: message.html_body,
+                text_body: message.text_body,
+            };
+
+            match self
+                .email_service
+                .send_now(&message.recipient, &email)
+                .await
+            {
+                Ok(()) => {
+                    sqlx::query!(
+                        r#"
+                        UPDATE email_outbox
+                        SET sent_at = NOW(), html_body = '', text_body = '', last_error = NULL
+                        WHERE id = $1
+                        "#,
+                        message.id
+                    )
+                    .execute(&self.pool)
+                    .await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Outbox email {} to {} failed (attempt {}/{}): {}",
+                        message.id,
+                        message.recipient,
+                        message.attempts,
+                        MAX_ATTEMPTS,
+                        e
+                    );
+                    tracing::debug!("Undelivered email content:\n{}", email.text_body);
+                    sqlx::query!(
+                        "UPDATE email_outbox SET last_error = $2 WHERE id = $1",
+                        message.id,
+                        e.to_string()
+                    )
+                    .execute(&self.pool)
+                    .await?;
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+}
@@ -3,19 +3,90 @@ use crate::{
     error::{AppError, Result},
     templates,
 };
+use chrono::{DateTime, Utc};
 use lettre::{
     message::{MultiPart, SinglePart},
     transport::smtp::authentication::Credentials,
     Message, SmtpTransport, Transport,
 };
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
 
+/// Why an address stopped receiving mail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionReason {
+    Bounce,
+    Complaint,
+}
+
+impl SuppressionReason {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SuppressionReason::Bounce => "bounce",
+            SuppressionReason::Complaint => "complaint",
+        }
+    }
+}
+
+/// Transactional email templates that can be rendered and previewed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTemplate {
+    EmailVerification,
+    PasswordReset,
+    PasswordResetConfirmation,
+    AccountLink,
+    MagicLink,
+    EmailChange,
+    PasswordSetup,
+    LoginAlert,
+    VerificationReminder,
+}
+
+/// Where and when a sign-in happened, as shown in a login alert
+#[derive(Debug, Clone)]
+pub struct LoginDetails {
+    pub time: DateTime<Utc>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub country: Option<String>,
+}
+
+impl LoginDetails {
+    fn replacements(&self) -> Vec<(&'static str, String)> {
+        let or_unknown =
+            |value: &Option<String>| value.clone().unwrap_or_else(|| "Unknown".to_string());
+        vec![
+            (
+                "{login_time}",
+                self.time.format("%Y-%m-%d %H:%M UTC").to_string(),
+            ),
+            ("{device}", or_unknown(&self.user_agent)),
+            ("{ip_address}", or_unknown(&self.ip_address)),
+            ("{country}", or_unknown(&self.country)),
+        ]
+    }
+}
+
+/// A fully rendered email, ready to send or preview
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+}
+
+#[derive(Clone)]
 pub struct EmailService {
+    pool: PgPool,
     config: EmailConfig,
     mailer: SmtpTransport,
 }
 
 impl EmailService {
-    pub fn new(config: EmailConfig) -> Result<Self> {
+    pub fn new(pool: PgPool, config: EmailConfig) -> Result<Self> {
         let creds = Credentials::new(config.smtp_username.clone(), config.smtp_password.clone());
 
         // Use builder_dangerous for localhost (MailHog), relay for production SMTP
@@ -30,7 +101,11 @@ impl EmailService {
                 .build()
         };
 
-        Ok(Self { config, mailer })
+        Ok(Self {
+            pool,
+            config,
+            mailer,
+        })
     }
 
     pub async fn send_verification_email(
@@ -39,35 +114,14 @@ impl EmailService {
         user_name: &str,
         token: &str,
     ) -> Result<()> {
-        let verification_link =
-            format!("{}/verify-email?token={}", self.config.frontend_url, token);
-
-        let html_template = templates::get_email_verification_html();
-        let text_template = templates::get_email_verification_text();
-
-        let html_body = templates::render_template(
-            html_template,
-            &[
-                ("{user_name}", user_name),
-                ("{verification_link}", &verification_link),
-            ],
-        );
-
-        let text_body = templates::render_template(
-            text_template,
-            &[
-                ("{user_name}", user_name),
-                ("{verification_link}", &verification_link),
-            ],
-        );
+        let email = self.render(EmailTemplate::EmailVerification, user_name, token);
+        self.send_email(user_email, &email).await
+    }
 
-        self.send_email(
-            user_email,
-            "Verify your LittyPicky account",
-            &text_body,
-            &html_body,
-        )
-        .await
+    /// Render the verification email without sending it, for the outbox
+    #[must_use]
+    pub fn render_verification_email(&self, user_name: &str, token: &str) -> RenderedEmail {
+        self.render(EmailTemplate::EmailVerification, user_name, token)
     }
 
     pub async fn send_password_reset_email(
@@ -76,62 +130,354 @@ impl EmailService {
         user_name: &str,
         token: &str,
     ) -> Result<()> {
-        let reset_link = format!(
-            "{}/reset-password?token={}",
-            self.config.frontend_url, token
-        );
+        let email = self.render(EmailTemplate::PasswordReset, user_name, token);
+        self.send_email(user_email, &email).await
+    }
 
-        let html_template = templates::get_password_reset_html();
-        let text_template = templates::get_password_reset_text();
+    pub async fn send_password_reset_confirmation(
+        &self,
+        user_email: &str,
+        user_name: &str,
+    ) -> Result<()> {
+        let email = self.render(EmailTemplate::PasswordResetConfirmation, user_name, "");
+        self.send_email(user_email, &email).await
+    }
 
-        let html_body = templates::render_template(
-            html_template,
-            &[("{user_name}", user_name), ("{reset_link}", &reset_link)],
-        );
+    pub async fn send_account_link_email(
+        &self,
+        user_email: &str,
+        user_name: &str,
+        token: &str,
+    ) -> Result<()> {
+        let email = self.render(EmailTemplate::AccountLink, user_name, token);
+        self.send_email(user_email, &email).await
+    }
 
-        let text_body = templates::render_template(
-            text_template,
-            &[("{user_name}", user_name), ("{reset_link}", &reset_link)],
-        );
+    pub async fn send_magic_link_email(
+        &self,
+        user_email: &str,
+        user_name: &str,
+        token: &str,
+    ) -> Result<()> {
+        let email = self.render(EmailTemplate::MagicLink, user_name, token);
+        self.send_email(user_email, &email).await
+    }
 
-        self.send_email(
-            user_email,
-            "Reset your LittyPicky password",
-            &text_body,
-            &html_body,
-        )
-        .await
+    pub async fn send_email_change_email(
+        &self,
+        new_email: &str,
+        user_name: &str,
+        token: &str,
+    ) -> Result<()> {
+        let email = self.render(EmailTemplate::EmailChange, user_name, token);
+        self.send_email(new_email, &email).await
     }
 
-    pub async fn send_password_reset_confirmation(
+    /// Let an account without a password add one from the link
+    pub async fn send_password_setup_email(
         &self,
         user_email: &str,
         user_name: &str,
+        token: &str,
     ) -> Result<()> {
-        let html_template = templates::get_password_reset_confirmation_html();
-        let text_template = templates::get_password_reset_confirmation_text();
+        let email = self.render(EmailTemplate::PasswordSetup, user_name, token);
+        self.send_email(user_email, &email).await
+    }
 
-        let html_body = templates::render_template(html_template, &[("{user_name}", user_name)]);
+    /// Tell the user their account was signed in to from an unfamiliar device or country
+    pub async fn send_login_alert(
+        &self,
+        user_email: &str,
+        user_name: &str,
+        login: &LoginDetails,
+    ) -> Result<()> {
+        let email = self.render_with_details(
+            EmailTemplate::LoginAlert,
+            user_name,
+            "",
+            &login.replacements(),
+        );
+        self.send_email(user_email, &email).await
+    }
 
-        let text_body = templates::render_template(text_template, &[("{user_name}", user_name)]);
+    /// One-off nudge for an account still unverified some time after registering
+    pub async fn send_verification_reminder(
+        &self,
+        user_email: &str,
+        user_name: &str,
+        token: &str,
+        unsubscribe_token: &str,
+    ) -> Result<()> {
+        let email = self.render_with_details(
+            EmailTemplate::VerificationReminder,
+            user_name,
+            token,
+            &[(
+                "{unsubscribe_link}",
+                self.unsubscribe_link(unsubscribe_token),
+            )],
+        );
+        self.send_email(user_email, &email).await
+    }
+
+    fn unsubscribe_link(&self, unsubscribe_token: &str) -> String {
+        format!(
+            "{}/unsubscribe?token={}",
+            self.config.frontend_url, unsubscribe_token
+        )
+    }
 
-        self.send_email(
-            user_email,
-            "Your LittyPicky password was reset",
-            &text_body,
-            &html_body,
+    /// Render a template with placeholder data, for previews and test sends
+    #[must_use]
+    pub fn render_sample(&self, template: EmailTemplate) -> RenderedEmail {
+        let login = LoginDetails {
+            time: Utc::now(),
+            user_agent: Some("Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X)".to_string()),
+            ip_address: Some("203.0.113.7".to_string()),
+            country: Some("GB".to_string()),
+        };
+        let mut details = login.replacements();
+        details.push((
+            "{unsubscribe_link}",
+            self.unsubscribe_link("sample-token-9876543210"),
+        ));
+        self.render_with_details(
+            template,
+            "Alex Example",
+            "sample-token-0123456789",
+            &details,
         )
-        .await
     }
 
-    async fn send_email(
+    /// Send a sample rendering of a template, reporting SMTP failures to the caller
+    pub async fn send_test_email(&self, to_email: &str, template: EmailTemplate) -> Result<()> {
+        if self.is_suppressed(to_email).await? {
+            return Err(AppError::BadRequest(format!(
+                "{to_email} has bounced or complained and is suppressed"
+            )));
+        }
+
+        let mut email = self.render_sample(template);
+        email.subject = format!("[Test] {}", email.subject);
+        self.deliver(to_email, &email).await
+    }
+
+    fn render(&self, template: EmailTemplate, user_name: &str, token: &str) -> RenderedEmail {
+        self.render_with_details(template, user_name, token, &[])
+    }
+
+    /// Render a template; `details` fill template-specific placeholders and are
+    /// HTML-escaped in the HTML body since they may come from the client
+    fn render_with_details(
+        &self,
+        template: EmailTemplate,
+        user_name: &str,
+        token: &str,
+        details: &[(&'static str, String)],
+    ) -> RenderedEmail {
+        let (subject, html_template, text_template, link) = match template {
+            EmailTemplate::EmailVerification => (
+                "Verify your LittyPicky account",
+                templates::get_email_verification_html(),
+                templates::get_email_verification_text(),
+                Some((
+                    "{verification_link}",
+                    format!("{}/verify-email?token={}", self.config.frontend_url, token),
+                )),
+            ),
+            EmailTemplate::PasswordReset => (
+                "Reset your LittyPicky password",
+                templates::get_password_reset_html(),
+                templates::get_password_reset_text(),
+                Some((
+                    "{reset_link}",
+                    format!(
+                        "{}/reset-password?token={}",
+                        self.config.frontend_url, token
+                    ),
+                )),
+            ),
+            EmailTemplate::PasswordResetConfirmation => (
+                "Your LittyPicky password was reset",
+                templates::get_password_reset_confirmation_html(),
+                templates::get_password_reset_confirmation_text(),
+                None,
+            ),
+            EmailTemplate::AccountLink => (
+                "Confirm linking Google to your LittyPicky account",
+                templates::get_account_link_html(),
+                templates::get_account_link_text(),
+                Some((
+                    "{link_url}",
+                    format!("{}/link-account?token={}", self.config.frontend_url, token),
+                )),
+            ),
+            EmailTemplate::MagicLink => (
+                "Your LittyPicky sign-in link",
+                templates::get_magic_link_html(),
+                templates::get_magic_link_text(),
+                Some((
+                    "{link_url}",
+                    format!(
+                        "{}/auth/magic-link?token={}",
+                        self.config.frontend_url, token
+                    ),
+                )),
+            ),
+            EmailTemplate::EmailChange => (
+                "Confirm your new LittyPicky email address",
+                templates::get_email_change_html(),
+                templates::get_email_change_text(),
+                Some((
+                    "{link_url}",
+                    format!(
+                        "{}/confirm-email-change?token={}",
+                        self.config.frontend_url, token
+                    ),
+                )),
+            ),
+            EmailTemplate::PasswordSetup => (
+                "Add a password to your LittyPicky account",
+                templates::get_password_setup_html(),
+                templates::get_password_setup_text(),
+                Some((
+                    "{link_url}",
+                    format!("{}/set-password?token={}", self.config.frontend_url, token),
+                )),
+            ),
+            EmailTemplate::VerificationReminder => (
+                "Finish setting up your LittyPicky account",
+                templates::get_verification_reminder_html(),
+                templates::get_verification_reminder_text(),
+                Some((
+                    "{verification_link}",
+                    format!("{}/verify-email?token={}", self.config.frontend_url, token),
+                )),
+            ),
+            EmailTemplate::LoginAlert => (
+                "New sign-in to your LittyPicky account",
+                templates::get_login_alert_html(),
+                templates::get_login_alert_text(),
+                Some((
+                    "{link_url}",
+                    format!("{}/profile/me/edit", self.config.frontend_url),
+                )),
+            ),
+        };
+
+        let mut replacements = vec![("{user_name}", user_name)];
+        if let Some((key, value)) = &link {
+            replacements.push((key, value.as_str()));
+        }
+        let mut text_replacements = replacements.clone();
+        text_replacements.extend(details.iter().map(|(key, value)| (*key, value.as_str())));
+        let escaped: Vec<(&str, String)> = details
+            .iter()
+            .map(|(key, value)| (*key, escape_html(value)))
+            .collect();
+        let mut html_replacements = replacements;
+        html_replacements.extend(escaped.iter().map(|(key, value)| (*key, value.as_str())));
+
+        RenderedEmail {
+            subject: subject.to_string(),
+            html_body: templates::render_template(html_template, &html_replacements),
+            text_body: templates::render_template(text_template, &text_replacements),
+        }
+    }
+
+    /// Mark an address as undeliverable so future sends are skipped
+    pub async fn suppress_address(
         &self,
-        to_email: &str,
-        subject: &str,
-        text_body: &str,
-        html_body: &str,
+        email: &str,
+        reason: SuppressionReason,
+        provider: &str,
+        details: Option<&str>,
     ) -> Result<()> {
-        let email = Message::builder()
+        // A complaint outranks a bounce, so never downgrade an existing complaint
+        sqlx::query!(
+            r#"
+            INSERT INTO email_suppressions (email, reason, provider, details)
+            VALUES (LOWER($1), $2, $3, $4)
+            ON CONFLICT (email) DO UPDATE
+            SET reason = CASE
+                    WHEN email_suppressions.reason = 'complaint' THEN email_suppressions.reason
+                    ELSE EXCLUDED.reason
+                END,
+                provider = EXCLUDED.provider,
+                details = EXCLUDED.details
+            "#,
+            email.trim(),
+            reason.as_str(),
+            provider,
+            details
+        )
+        .execute(&self.pool)
+        .await?;
+
+        tracing::warn!(
+            "Suppressed email address {} ({} via {})",
+            email,
+            reason.as_str(),
+            provider
+        );
+        Ok(())
+    }
+
+    /// Check whether an address has bounced or complained
+    pub async fn is_suppressed(&self, email: &str) -> Result<bool> {
+        let suppressed = sqlx::query_scalar!(
+            "SELECT EXISTS(SELECT 1 FROM email_suppressions WHERE email = LOWER($1))",
+            email.trim()
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(false);
+
+        Ok(suppressed)
+    }
+
+    /// Send a rendered email, reporting SMTP failures so the caller can retry. A
+    /// suppressed address counts as sent.
+    pub async fn send_now(&self, to_email: &str, email: &RenderedEmail) -> Result<()> {
+        if self.is_suppressed(to_email).await? {
+            tracing::info!(
+                "Skipping email to suppressed address {}: {}",
+                to_email,
+                email.subject
+            );
+            return Ok(());
+        }
+
+        self.deliver(to_email, email).await
+    }
+
+    async fn send_email(&self, to_email: &str, email: &RenderedEmail) -> Result<()> {
+        // Callers treat sending as best-effort, so a suppressed address is not an error
+        if self.is_suppressed(to_email).await? {
+            tracing::info!(
+                "Skipping email to suppressed address {}: {}",
+                to_email,
+                email.subject
+            );
+            return Ok(());
+        }
+
+        match self.deliver(to_email, email).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                tracing::error!("Failed to send email to {}: {}", to_email, e);
+                tracing::warn!(
+                    "(Development Mode) Returning success anyway. Email content:\nSubject: {}\nBody: {}",
+                    email.subject,
+                    email.text_body
+                );
+                Ok(()) // Suppress error for development
+            }
+        }
+    }
+
+    async fn deliver(&self, to_email: &str, email: &RenderedEmail) -> Result<()> {
+        let message = Message::builder()
             .from(
                 format!(
                     "{} <{}>",
@@ -143,30 +489,31 @@ impl EmailService {
             .to(to_email
                 .parse()
                 .map_err(|e| AppError::Email(format!("Invalid to address: {e}")))?)
-            .subject(subject)
+            .subject(email.subject.clone())
             .multipart(
                 MultiPart::alternative()
-                    .singlepart(SinglePart::plain(text_body.to_string()))
-                    .singlepart(SinglePart::html(html_body.to_string())),
+                    .singlepart(SinglePart::plain(email.text_body.clone()))
+                    .singlepart(SinglePart::html(email.html_body.clone())),
             )
             .map_err(|e| AppError::Email(format!("Failed to build email: {e}")))?;
 
         // Send email in a blocking task to avoid blocking async runtime
         let mailer = self.mailer.clone();
-        let result = tokio::task::spawn_blocking(move || mailer.send(&email))
+        tokio::task::spawn_blocking(move || mailer.send(&message))
             .await
-            .map_err(|e| AppError::Email(format!("Task join error: {e}")))?;
+            .map_err(|e| AppError::Email(format!("Task join error: {e}")))?
+            .map_err(|e| AppError::Email(format!("SMTP error: {e}")))?;
 
-        match result {
-            Ok(_) => {
-                tracing::info!("Email sent to {}: {}", to_email, subject);
-                Ok(())
-            }
-            Err(e) => {
-                tracing::error!("Failed to send email to {}: {}", to_email, e);
-                tracing::warn!("(Development Mode) Returning success anyway. Email content:\nSubject: {}\nBody: {}", subject, text_body);
-                Ok(()) // Suppress error for development
-            }
-        }
+        tracing::info!("Email sent to {}: {}", to_email, email.subject);
+        Ok(())
     }
 }
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,286 @@
+use crate::error::AppError;
+use crate::services::email_service::{EmailService, SuppressionReason};
+use crate::services::event_service::EventService;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Event type prefix for inbound email provider webhook deliveries
+const DELIVERY_EVENT_PREFIX: &str = "webhook.email.";
+
+/// Providers whose bounce/complaint notifications we understand
+const SUPPORTED_PROVIDERS: &[&str] = &["ses", "sendgrid"];
+
+#[derive(Clone)]
+pub struct EmailWebhookService {
+    email_service: EmailService,
+    event_service: EventService,
+}
+
+impl EmailWebhookService {
+    #[must_use]
+    pub fn new(email_service: EmailService, event_service: EventService) -> Self {
+        Self {
+            email_service,
+            event_service,
+        }
+    }
+
+    /// Log an inbound delivery and process it, returning how many addresses were suppressed
+    pub async fn handle_delivery(&self, provider: &str, body: &str) -> Result<usize, AppError> {
+        if !SUPPORTED_PROVIDERS.contains(&provider) {
+            return Err(AppError::BadRequest(format!(
+                "Unsupported email provider: {provider}"
+            )));
+        }
+
+        let event_id = self
+            .event_service
+            .record_delivery(&format!("{DELIVERY_EVENT_PREFIX}{provider}"), body)
+            .await?;
+
+        self.process_and_complete(event_id, provider, body).await
+    }
+
+    /// Re-run processing for a previously logged delivery
+    pub async fn retry_delivery(&self, event_id: Uuid) -> Result<usize, AppError> {
+        let event = self.event_service.get_event(event_id).await?;
+        let provider = event
+            .event_type
+            .strip_prefix(DELIVERY_EVENT_PREFIX)
+            .filter(|_| event.delivery_status.is_some())
+            .ok_or_else(|| {
+                AppError::BadRequest("Only webhook deliveries can be retried".to_string())
+            })?;
+
+        self.process_and_complete(event_id, provider, &event.payload)
+            .await
+    }
+
+    async fn process_and_complete(
+        &self,
+        event_id: Uuid,
+        provider: &str,
+        body: &str,
+    ) -> Result<usize, AppError> {
+        let result = self.process(provider, body).await;
+        let error = result.as_ref().err().map(ToString::to_string);
+        self.event_service
+            .complete_delivery(event_id, error.as_deref())
+            .await?;
+        result
+    }
+
+    async fn process(&self, provider: &str, body: &str) -> Result<usize, AppError> {
+        let events = match provider {
+            "ses" => parse_ses(body).await?,
+            "sendgrid" => parse_sendgrid(body)?,
+            other => {
+                return Err(AppError::BadRequest(format!(
+                    "Unsupported email provider: {other}"
+                )))
+            }
+        };
+
+        for event in &events {
+            self.email_service
+                .suppress_address(
+                    &event.email,
+                    event.reason,
+                    provider,
+                    event.details.as_deref(),
+                )
+                .await?;
+
+            let payload = serde_json::json!({
+                "reason": event.reason.as_str(),
+                "provider": provider,
+                "details": event.details,
+            });
+            self.event_service
+                .record(
+                    "email.suppressed",
+                    Some("email"),
+                    Some(&event.email.trim().to_lowercase()),
+                    &payload.to_string(),
+                )
+                .await?;
+        }
+
+        Ok(events.len())
+    }
+}
+
+/// A single address the provider told us to stop mailing
+struct SuppressionEvent {
+    email: String,
+    reason: SuppressionReason,
+    details: Option<String>,
+}
+
+// Amazon SES notifications arrive wrapped in an SNS envelope
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SnsEnvelope {
+    #[serde(rename = "Type")]
+    message_type: String,
+    message: Option<String>,
+    #[serde(rename = "SubscribeURL")]
+    subscribe_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesNotification {
+    // Identity notifications use notificationType, configuration-set events use eventType
+    notification_type: Option<String>,
+    event_type: Option<String>,
+    bounce: Option<SesBounce>,
+    complaint: Option<SesComplaint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesBounce {
+    bounce_type: String,
+    bounced_recipients: Vec<SesRecipient>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesComplaint {
+    complained_recipients: Vec<SesRecipient>,
+    complaint_feedback_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesRecipient {
+    email_address: String,
+    diagnostic_code: Option<String>,
+}
+
+// SendGrid's Event Webhook posts a JSON array of events
+
+#[derive(Deserialize)]
+struct SendGridEvent {
+    email: String,
+    event: String,
+    #[serde(rename = "type")]
+    bounce_type: Option<String>,
+    reason: Option<String>,
+}
+
+async fn parse_ses(body: &str) -> Result<Vec<SuppressionEvent>, AppError> {
+    let envelope: SnsEnvelope = serde_json::from_str(body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid SNS payload: {e}")))?;
+
+    match envelope.message_type.as_str() {
+        "SubscriptionConfirmation" => {
+            confirm_sns_subscription(envelope.subscribe_url.as_deref()).await?;
+            Ok(Vec::new())
+        }
+        "Notification" => {
+            let message = envelope.message.ok_or_else(|| {
+                AppError::BadRequest("SNS notification has no message".to_string())
+            })?;
+            let notification: SesNotification = serde_json::from_str(&message)
+                .map_err(|e| AppError::BadRequest(format!("Invalid SES notification: {e}")))?;
+            Ok(ses_events(notification))
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn ses_events(notification: SesNotification) -> Vec<SuppressionEvent> {
+    let kind = notification
+        .notification_type
+        .or(notification.event_type)
+        .unwrap_or_default();
+
+    match kind.as_str() {
+        "Bounce" => notification
+            .bounce
+            // Transient bounces (full mailbox, greylisting) may succeed later
+            .filter(|bounce| bounce.bounce_type == "Permanent")
+            .map(|bounce| {
+                bounce
+                    .bounced_recipients
+                    .into_iter()
+                    .map(|recipient| SuppressionEvent {
+                        email: recipient.email_address,
+                        reason: SuppressionReason::Bounce,
+                        details: recipient.diagnostic_code,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "Complaint" => notification
+            .complaint
+            .map(|complaint| {
+                let feedback = complaint.complaint_feedback_type;
+                complaint
+                    .complained_recipients
+                    .into_iter()
+                    .map(|recipient| SuppressionEvent {
+                        email: recipient.email_address,
+                        reason: SuppressionReason::Complaint,
+                        details: feedback.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+async fn confirm_sns_subscription(subscribe_url: Option<&str>) -> Result<(), AppError> {
+    let url =
+        subscribe_url.ok_or_else(|| AppError::BadRequest("Missing SubscribeURL".to_string()))?;
+
+    // Only ever call back to SNS itself
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| AppError::BadRequest(format!("Invalid SubscribeURL: {e}")))?;
+    let is_sns_host = parsed
+        .host_str()
+        .is_some_and(|host| host.starts_with("sns.") && host.ends_with(".amazonaws.com"));
+    if parsed.scheme() != "https" || !is_sns_host {
+        return Err(AppError::BadRequest(
+            "SubscribeURL does not point at Amazon SNS".to_string(),
+        ));
+    }
+
+    reqwest::get(parsed)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to confirm SNS subscription: {e}"))
+        })?;
+
+    tracing::info!("Confirmed SNS subscription for email notifications");
+    Ok(())
+}
+
+fn parse_sendgrid(body: &str) -> Result<Vec<SuppressionEvent>, AppError> {
+    let events: Vec<SendGridEvent> = serde_json::from_str(body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid SendGrid payload: {e}")))?;
+
+    Ok(events
+        .into_iter()
+        .filter_map(|event| {
+            let reason = match event.event.as_str() {
+                // "blocked" is SendGrid's name for a temporary rejection
+                "bounce" if event.bounce_type.as_deref() != Some("blocked") => {
+                    SuppressionReason::Bounce
+                }
+                "spamreport" => SuppressionReason::Complaint,
+                _ => return None,
+            };
+            Some(SuppressionEvent {
+                email: event.email,
+                reason,
+                details: event.reason,
+            })
+        })
+        .collect())
+}
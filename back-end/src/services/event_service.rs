@@ -0,0 +1,168 @@
+use crate::error::AppError;
+use crate::models::event::{EventLogEntry, EventLogQuery};
+use crate::models::pagination::{PageParams, Paginated};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DEFAULT_EVENT_PAGE_SIZE: i64 = 50;
+const MAX_EVENT_PAGE_SIZE: i64 = 200;
+
+#[derive(Clone)]
+pub struct EventService {
+    pool: PgPool,
+}
+
+impl EventService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append an event to the log
+    pub async fn record(
+        &self,
+        event_type: &str,
+        entity_type: Option<&str>,
+        entity_id: Option<&str>,
+        payload: &str,
+    ) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO event_log (event_type, entity_type, entity_id, payload)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            event_type,
+            entity_type,
+            entity_id,
+            payload
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Log an inbound webhook delivery before it is processed
+    pub async fn record_delivery(&self, event_type: &str, payload: &str) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO event_log (event_type, payload, delivery_status)
+            VALUES ($1, $2, 'pending')
+            RETURNING id
+            "#,
+            event_type,
+            payload
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Record the outcome of one processing attempt for a delivery
+    pub async fn complete_delivery(
+        &self,
+        event_id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE event_log
+            SET delivery_status = CASE WHEN $2::text IS NULL THEN 'succeeded' ELSE 'failed' END,
+                attempts = attempts + 1,
+                last_error = $2
+            WHERE id = $1
+            "#,
+            event_id,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_event(&self, event_id: Uuid) -> Result<EventLogEntry, AppError> {
+        sqlx::query_as!(
+            EventLogEntry,
+            r#"
+            SELECT id, event_type, entity_type, entity_id, payload, delivery_status,
+                   attempts, last_error, created_at, updated_at
+            FROM event_log
+            WHERE id = $1
+            "#,
+            event_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Event not found".to_string()))
+    }
+
+    /// List events, newest first, applying any filters that are set
+    pub async fn list_events(
+        &self,
+        query: &EventLogQuery,
+        page: &PageParams,
+    ) -> Result<Paginated<EventLogEntry>, AppError> {
+        let offset = page.offset()?;
+        let limit = page.limit(DEFAULT_EVENT_PAGE_SIZE, MAX_EVENT_PAGE_SIZE);
+
+        // A trailing "." selects a whole family of events, e.g. "webhook."
+        let (exact_type, type_prefix) = match query.event_type.as_deref() {
+            Some(t) if t.ends_with('.') => (None, Some(format!("{t}%"))),
+            Some(t) => (Some(t), None),
+            None => (None, None),
+        };
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) FROM event_log
+            WHERE ($1::text IS NULL OR event_type = $1)
+              AND ($2::text IS NULL OR event_type LIKE $2)
+              AND ($3::text IS NULL OR entity_id = $3)
+              AND ($4::text IS NULL OR delivery_status = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+            "#,
+            exact_type,
+            type_prefix,
+            query.entity_id.as_deref(),
+            query.delivery_status.as_deref(),
+            query.from,
+            query.to
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(0);
+
+        let events = sqlx::query_as!(
+            EventLogEntry,
+            r#"
+            SELECT id, event_type, entity_type, entity_id, payload, delivery_status,
+                   attempts, last_error, created_at, updated_at
+            FROM event_log
+            WHERE ($1::text IS NULL OR event_type = $1)
+              AND ($2::text IS NULL OR event_type LIKE $2)
+              AND ($3::text IS NULL OR entity_id = $3)
+              AND ($4::text IS NULL OR delivery_status = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+            ORDER BY created_at DESC
+            LIMIT $7 OFFSET $8
+            "#,
+            exact_type,
+            type_prefix,
+            query.entity_id.as_deref(),
+            query.delivery_status.as_deref(),
+            query.from,
+            query.to,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Paginated::new(events, total, offset))
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::AppError;
+use crate::models::feed::EngagementKind;
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Buffered events that trigger a flush without waiting for the next tick
+const FLUSH_BATCH_SIZE: usize = 500;
+
+/// Score weights per event; a dwell scores per second on screen, up to a minute
+const VIEW_WEIGHT: f64 = 1.0;
+const LIKE_WEIGHT: f64 = 5.0;
+const COMMENT_WEIGHT: f64 = 8.0;
+const DWELL_WEIGHT_PER_SEC: f64 = 0.1;
+const MAX_DWELL_MS: i32 = 60_000;
+
+/// Events older than this many half-lives add under 0.1% of their weight and are dropped
+const RETAINED_HALF_LIVES: f64 = 10.0;
+
+/// One view, like, comment or dwell on a feed post
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngagementEvent {
+    pub post_id: Uuid,
+    /// `None` for signed-out readers
+    pub user_id: Option<Uuid>,
+    pub kind: EngagementKind,
+    pub dwell_ms: Option<i32>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Where recorded engagement ends up. Events arrive in batches.
+#[async_trait]
+pub trait EngagementSink: Send + Sync {
+    async fn write(&self, events: Vec<EngagementEvent>) -> Result<(), AppError>;
+}
+
+/// Stores events in `feed_engagement_events`, where the trending scores are computed from
+#[derive(Clone)]
+pub struct PgEngagementSink {
+    pool: PgPool,
+}
+
+impl PgEngagementSink {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl EngagementSink for PgEngagementSink {
+    async fn write(&self, events: Vec<EngagementEvent>) -> Result<(), AppError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let post_ids: Vec<Uuid> = events.iter().map(|e| e.post_id).collect();
+        let user_ids: Vec<Option<Uuid>> = events.iter().map(|e| e.user_id).collect();
+        let kinds: Vec<&str> = events.iter().map(|e| e.kind.as_str()).collect();
+        let dwell_ms: Vec<Option<i32>> = events.iter().map(|e| e.dwell_ms).collect();
+        let occurred_at: Vec<DateTime<Utc>> = events.iter().map(|e| e.occurred_at).collect();
+
+        // Posts deleted since the event was buffered are skipped
+        sqlx::query(
+            r"
+            INSERT INTO feed_engagement_events (post_id, user_id, kind, dwell_ms, occurred_at)
+            SELECT e.post_id, e.user_id, e.kind::feed_engagement_kind, e.dwell_ms, e.occurred_at
+            FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::int[], $5::timestamptz[])
+                AS e(post_id, user_id, kind, dwell_ms, occurred_at)
+            JOIN feed_posts fp ON fp.id = e.post_id
+            ",
+        )
+        .bind(&post_ids)
+        .bind(&user_ids)
+        .bind(&kinds)
+        .bind(&dwell_ms)
+        .bind(&occurred_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Collects engagement in memory and hands it to the sink in batches, so reading the
+/// feed never waits on an insert. Call `flush` periodically to write partial batches.
+#[derive(Clone)]
+pub struct EngagementRecorder {
+    sink: Arc<dyn EngagementSink>,
+    buffer: Arc<Mutex<Vec<EngagementEvent>>>,
+}
+
+impl EngagementRecorder {
+    #[must_use]
+    pub fn new(sink: Arc<dyn EngagementSink>) -> Self {
+        Self {
+            sink,
+            buffer: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn record(&self, post_id: Uuid, user_id: Option<Uuid>, kind: EngagementKind) {
+        self.push(EngagementEvent {
+            post_id,
+            user_id,
+            kind,
+            dwell_ms: None,
+            occurred_at: Utc::now(),
+        });
+    }
+
+    pub fn record_dwell(&self, post_id: Uuid, user_id: Option<Uuid>, dwell_ms: i32) {
+        self.push(EngagementEvent {
+            post_id,
+            user_id,
+            kind: EngagementKind::Dwell,
+            dwell_ms: Some(dwell_ms),
+            occurred_at: Utc::now(),
+        });
+    }
+
+    /// Write everything buffered so far
+    pub async fn flush(&self) -> Result<(), AppError> {
+        let events = self.take();
+        self.sink.write(events).await
+    }
+
+    fn push(&self, event: EngagementEvent) {
+        let full = {
+            let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+            buffer.push(event);
+            buffer.len() >= FLUSH_BATCH_SIZE
+        };
+
+        if full {
+            let sink = Arc::clone(&self.sink);
+            let events = self.take();
+            tokio::spawn(async move {
+                if let Err(e) = sink.write(events).await {
+                    tracing::warn!("Failed to write feed engagement batch: {:?}", e);
+                }
+            });
+        }
+    }
+
+    fn take(&self) -> Vec<EngagementEvent> {
+        std::mem::take(&mut *self.buffer.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Turns stored engagement into the decayed scores the trending feed is ordered by
+#[derive(Clone)]
+pub struct FeedEngagementService {
+    pool: PgPool,
+}
+
+impl FeedEngagementService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Recompute every post's score, with each event's weight halving every
+    /// `half_life_hours`, and drop events too old to count. Returns how many posts
+    /// have a score.
+    pub async fn recompute_scores(&self, half_life_hours: f64) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "DELETE FROM feed_engagement_events
+             WHERE occurred_at < NOW() - make_interval(secs => $1)",
+        )
+        .bind(half_life_hours * RETAINED_HALF_LIVES * 3600.0)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM feed_post_engagement_scores")
+            .execute(&mut *tx)
+            .await?;
+
+        let scored = sqlx::query(
+            r"
+            INSERT INTO feed_post_engagement_scores (post_id, score)
+            SELECT post_id,
+                   SUM(
+                       CASE kind
+                           WHEN 'view' THEN $2
+                           WHEN 'like' THEN $3
+                           WHEN 'comment' THEN $4
+                           WHEN 'dwell' THEN LEAST(dwell_ms, $6)::double precision / 1000.0 * $5
+                       END
+                       * EXP(-LN(2) * EXTRACT(EPOCH FROM (NOW() - occurred_at))::double precision / 3600.0 / $1)
+                   )
+            FROM feed_engagement_events
+            GROUP BY post_id
+            ",
+        )
+        .bind(half_life_hours)
+        .bind(VIEW_WEIGHT)
+        .bind(LIKE_WEIGHT)
+        .bind(COMMENT_WEIGHT)
+        .bind(DWELL_WEIGHT_PER_SEC)
+        .bind(MAX_DWELL_MS)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(scored)
+    }
+}
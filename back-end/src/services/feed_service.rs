@@ -1,28 +1,47 @@
 use crate::error::AppError;
 use crate::models::feed::{
     CreateFeedCommentRequest, CreateFeedPostRequest, FeedComment, FeedCommentResponse, FeedPost,
-    FeedPostResponse, UpdateFeedCommentRequest, UpdateFeedPostRequest,
+    FeedPostResponse, FeedSort, UpdateFeedCommentRequest, UpdateFeedPostRequest,
 };
+use crate::models::image::ImageMetadata;
 use crate::models::user::User;
 use crate::services::image_service::ImageService;
-use crate::services::s3_service::S3Service;
-use sqlx::PgPool;
+use crate::services::image_storage_service::ImageStorageService;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
+/// A listed post with its author's name
+#[derive(FromRow)]
+struct FeedListRow {
+    id: Uuid,
+    user_id: Uuid,
+    content: String,
+    like_count: i32,
+    comment_count: i32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    full_name: String,
+}
+
 #[derive(Clone)]
 pub struct FeedService {
     pool: PgPool,
     image_service: ImageService,
-    s3_service: S3Service,
+    image_storage: ImageStorageService,
 }
 
 impl FeedService {
     #[must_use]
-    pub fn new(pool: PgPool, image_service: ImageService, s3_service: S3Service) -> Self {
+    pub fn new(
+        pool: PgPool,
+        image_service: ImageService,
+        image_storage: ImageStorageService,
+    ) -> Self {
         Self {
             pool,
             image_service,
-            s3_service,
+            image_storage,
         }
     }
 
@@ -67,7 +86,7 @@ impl FeedService {
         .await?;
 
         // Process and upload images if any
-        let mut image_urls = Vec::new();
+        let mut image_metadata = Vec::new();
         for (position, image_base64) in request.images.iter().enumerate() {
             // Process image (compress to WebP, etc.)
             let processed_image = self
@@ -76,25 +95,33 @@ impl FeedService {
                 .await?;
 
             // Upload to S3
-            let image_url = self
-                .s3_service
-                .upload_image(processed_image, "feed/posts")
-                .await?;
+            let stored = self.image_storage.store(processed_image.data).await?;
 
-            image_urls.push(image_url.clone());
+            let metadata = ImageMetadata {
+                url: stored.url,
+                width: Some(processed_image.width as i32),
+                height: Some(processed_image.height as i32),
+                blurhash: Some(processed_image.blurhash),
+                processing: stored.pending,
+            };
 
             // Insert image record
             sqlx::query!(
                 r#"
-                INSERT INTO feed_post_images (post_id, image_url, position)
-                VALUES ($1, $2, $3)
+                INSERT INTO feed_post_images (post_id, image_url, position, width, height, blurhash)
+                VALUES ($1, $2, $3, $4, $5, $6)
                 "#,
                 post.id,
-                image_url,
-                position as i32
+                metadata.url,
+                position as i32,
+                metadata.width,
+                metadata.height,
+                metadata.blurhash
             )
             .execute(&mut *tx)
             .await?;
+
+            image_metadata.push(metadata);
         }
 
         // Commit transaction
@@ -122,7 +149,8 @@ impl FeedService {
             author_name: user.full_name,
             author_avatar: None,
             content: post.content,
-            images: image_urls,
+            images: image_metadata.iter().map(|img| img.url.clone()).collect(),
+            image_metadata,
             like_count: post.like_count,
             comment_count: post.comment_count,
             comments: Vec::new(),
@@ -131,48 +159,53 @@ impl FeedService {
         })
     }
 
-    /// Get paginated feed posts
+    /// Get paginated feed posts along with the total post count. Trending posts are
+    /// ordered by their latest engagement score, unscored ones last.
     pub async fn get_feed(
         &self,
         offset: i32,
         limit: i32,
-    ) -> Result<Vec<FeedPostResponse>, AppError> {
+        sort: FeedSort,
+    ) -> Result<(Vec<FeedPostResponse>, i64), AppError> {
         let limit = limit.clamp(1, 100);
         let offset = offset.max(0);
 
+        let total = sqlx::query_scalar!("SELECT COUNT(*) FROM feed_posts")
+            .fetch_one(&self.pool)
+            .await?
+            .unwrap_or(0);
+
+        let order_by = match sort {
+            FeedSort::Newest => "fp.created_at DESC",
+            FeedSort::Trending => "COALESCE(s.score, 0) DESC, fp.created_at DESC",
+        };
+
         // Fetch posts with user info
-        let posts = sqlx::query!(
-            r#"
-            SELECT 
+        let posts = sqlx::query_as::<_, FeedListRow>(&format!(
+            r"
+            SELECT
                 fp.id, fp.user_id, fp.content, fp.like_count, fp.comment_count,
                 fp.created_at, fp.updated_at,
                 u.full_name
             FROM feed_posts fp
             JOIN users u ON fp.user_id = u.id
-            ORDER BY fp.created_at DESC
+            LEFT JOIN feed_post_engagement_scores s ON s.post_id = fp.id
+            ORDER BY {order_by}, fp.id
             LIMIT $1 OFFSET $2
-            "#,
-            limit as i64,
-            offset as i64
-        )
+            "
+        ))
+        .bind(i64::from(limit))
+        .bind(i64::from(offset))
         .fetch_all(&self.pool)
         .await?;
 
         let mut responses = Vec::new();
         for post in posts {
             // Fetch images for this post
-            let images: Vec<String> = sqlx::query!(
-                "SELECT image_url FROM feed_post_images WHERE post_id = $1 ORDER BY position",
-                post.id
-            )
-            .fetch_all(&self.pool)
-            .await?
-            .into_iter()
-            .map(|img| img.image_url)
-            .collect();
+            let image_metadata = self.get_images_for_post(post.id).await?;
 
             // Fetch comments for this post
-            let comments = self.get_comments_for_post(post.id).await?;
+            let comments = self.get_comments_for_post(post.id, None, 0).await?;
 
             responses.push(FeedPostResponse {
                 id: post.id,
@@ -180,7 +213,8 @@ impl FeedService {
                 author_name: post.full_name,
                 author_avatar: None,
                 content: post.content,
-                images,
+                images: image_metadata.iter().map(|img| img.url.clone()).collect(),
+                image_metadata,
                 like_count: post.like_count,
                 comment_count: post.comment_count,
                 comments,
@@ -189,7 +223,7 @@ impl FeedService {
             });
         }
 
-        Ok(responses)
+        Ok((responses, total))
     }
 
     /// Get a single post by ID
@@ -211,18 +245,10 @@ impl FeedService {
         .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
         // Fetch images
-        let images: Vec<String> = sqlx::query!(
-            "SELECT image_url FROM feed_post_images WHERE post_id = $1 ORDER BY position",
-            post_id
-        )
-        .fetch_all(&self.pool)
-        .await?
-        .into_iter()
-        .map(|img| img.image_url)
-        .collect();
+        let image_metadata = self.get_images_for_post(post_id).await?;
 
         // Fetch comments
-        let comments = self.get_comments_for_post(post_id).await?;
+        let comments = self.get_comments_for_post(post_id, None, 0).await?;
 
         Ok(FeedPostResponse {
             id: post.id,
@@ -230,7 +256,8 @@ impl FeedService {
             author_name: post.full_name,
             author_avatar: None,
             content: post.content,
-            images,
+            images: image_metadata.iter().map(|img| img.url.clone()).collect(),
+            image_metadata,
             like_count: post.like_count,
             comment_count: post.comment_count,
             comments,
@@ -288,24 +315,24 @@ impl FeedService {
             .await?;
 
         // Upload new images
-        let mut image_urls = Vec::new();
         for (position, image_base64) in request.images.iter().enumerate() {
             let processed_image = self
                 .image_service
                 .process_image(image_base64.clone())
                 .await?;
-            let image_url = self
-                .s3_service
-                .upload_image(processed_image, "feed/posts")
-                .await?;
-
-            image_urls.push(image_url.clone());
+            let image_url = self.image_storage.store(processed_image.data).await?.url;
 
             sqlx::query!(
-                "INSERT INTO feed_post_images (post_id, image_url, position) VALUES ($1, $2, $3)",
+                r#"
+                INSERT INTO feed_post_images (post_id, image_url, position, width, height, blurhash)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
                 post_id,
                 image_url,
-                position as i32
+                position as i32,
+                processed_image.width as i32,
+                processed_image.height as i32,
+                processed_image.blurhash
             )
             .execute(&mut *tx)
             .await?;
@@ -331,10 +358,20 @@ impl FeedService {
             ));
         }
 
+        self.remove_post(post_id).await
+    }
+
+    /// Delete a post regardless of its author, for moderation
+    pub async fn remove_post(&self, post_id: Uuid) -> Result<(), AppError> {
         // Delete post (cascade will handle images, comments, likes)
-        sqlx::query!("DELETE FROM feed_posts WHERE id = $1", post_id)
+        let deleted = sqlx::query!("DELETE FROM feed_posts WHERE id = $1", post_id)
             .execute(&self.pool)
-            .await?;
+            .await?
+            .rows_affected();
+
+        if deleted == 0 {
+            return Err(AppError::NotFound("Post not found".to_string()));
+        }
 
         Ok(())
     }
@@ -362,10 +399,7 @@ impl FeedService {
             ));
         }
 
-        // Begin transaction for atomic increment
-        let mut tx = self.pool.begin().await?;
-
-        // Create comment
+        // Create comment (the post's comment_count is maintained by a trigger)
         let comment = sqlx::query_as!(
             FeedComment,
             r#"
@@ -377,26 +411,47 @@ impl FeedService {
             user_id,
             request.content.trim()
         )
-        .fetch_one(&mut *tx)
+        .fetch_one(&self.pool)
         .await?;
 
-        // Increment post comment count
-        sqlx::query!(
-            "UPDATE feed_posts SET comment_count = comment_count + 1 WHERE id = $1",
+        Ok(comment)
+    }
+
+    /// Get a post's images in display order (internal helper)
+    async fn get_images_for_post(&self, post_id: Uuid) -> Result<Vec<ImageMetadata>, AppError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT image_url AS url, width, height, blurhash
+            FROM feed_post_images
+            WHERE post_id = $1
+            ORDER BY position
+            "#,
             post_id
         )
-        .execute(&mut *tx)
+        .fetch_all(&self.pool)
         .await?;
 
-        tx.commit().await?;
+        let urls: Vec<String> = rows.iter().map(|row| row.url.clone()).collect();
+        let pending = self.image_storage.pending_urls(&urls).await?;
 
-        Ok(comment)
+        Ok(rows
+            .into_iter()
+            .map(|row| ImageMetadata {
+                processing: pending.contains(&row.url),
+                url: row.url,
+                width: row.width,
+                height: row.height,
+                blurhash: row.blurhash,
+            })
+            .collect())
     }
 
-    /// Get comments for a post (internal helper)
+    /// Get comments for a post, oldest first (internal helper; `None` limit returns all)
     async fn get_comments_for_post(
         &self,
         post_id: Uuid,
+        limit: Option<i64>,
+        offset: i64,
     ) -> Result<Vec<FeedCommentResponse>, AppError> {
         let comments = sqlx::query!(
             r#"
@@ -406,8 +461,11 @@ impl FeedService {
             LEFT JOIN users u ON fc.user_id = u.id
             WHERE fc.post_id = $1
             ORDER BY fc.created_at ASC
+            LIMIT $2 OFFSET $3
             "#,
-            post_id
+            post_id,
+            limit,
+            offset
         )
         .fetch_all(&self.pool)
         .await?;
@@ -438,15 +496,31 @@ impl FeedService {
         Ok(responses)
     }
 
-    /// Get comments for a post (public API method)
-    pub async fn get_comments(&self, post_id: Uuid) -> Result<Vec<FeedCommentResponse>, AppError> {
+    /// Get one page of comments for a post along with the total comment count
+    pub async fn get_comments(
+        &self,
+        post_id: Uuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<FeedCommentResponse>, i64), AppError> {
         // Verify post exists
         let _post = sqlx::query!("SELECT id FROM feed_posts WHERE id = $1", post_id)
             .fetch_optional(&self.pool)
             .await?
             .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
-        self.get_comments_for_post(post_id).await
+        let total = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM feed_comments WHERE post_id = $1",
+            post_id
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(0);
+
+        let comments = self
+            .get_comments_for_post(post_id, Some(limit), offset)
+            .await?;
+        Ok((comments, total))
     }
 
     /// Update a comment (ownership required)
@@ -511,27 +585,14 @@ impl FeedService {
             ));
         }
 
-        // Begin transaction for atomic soft-delete + decrement
-        let mut tx = self.pool.begin().await?;
-
-        // Soft delete comment
+        // Soft delete comment (the trigger only decrements comment_count on the first delete)
         sqlx::query!(
             "UPDATE feed_comments SET is_deleted = true, updated_at = NOW() WHERE id = $1",
             comment_id
         )
-        .execute(&mut *tx)
-        .await?;
-
-        // Decrement post comment count
-        sqlx::query!(
-            "UPDATE feed_posts SET comment_count = comment_count - 1 WHERE id = $1",
-            comment.post_id
-        )
-        .execute(&mut *tx)
+        .execute(&self.pool)
         .await?;
 
-        tx.commit().await?;
-
         Ok(())
     }
 
@@ -539,7 +600,7 @@ impl FeedService {
     // LIKE OPERATIONS
     // ========================================================================
 
-    /// Like a post (idempotent)
+    /// Like a post (idempotent). The post's like_count is maintained by a trigger.
     pub async fn like_post(&self, post_id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
         // Verify post exists
         let _post = sqlx::query!("SELECT id FROM feed_posts WHERE id = $1", post_id)
@@ -547,46 +608,25 @@ impl FeedService {
             .await?
             .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
-        // Begin transaction
-        let mut tx = self.pool.begin().await?;
-
-        // Check if already liked
-        let existing = sqlx::query!(
-            "SELECT id FROM feed_post_likes WHERE post_id = $1 AND user_id = $2",
-            post_id,
-            user_id
-        )
-        .fetch_optional(&mut *tx)
-        .await?;
-
-        if existing.is_some() {
-            // Already liked, return false (no new like)
-            return Ok(false);
-        }
-
-        // Insert like
-        sqlx::query!(
-            "INSERT INTO feed_post_likes (post_id, user_id) VALUES ($1, $2)",
+        // Insert like; a concurrent duplicate is skipped rather than failing on the unique key
+        let inserted_rows = sqlx::query!(
+            r#"
+            INSERT INTO feed_post_likes (post_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (post_id, user_id) DO NOTHING
+            "#,
             post_id,
             user_id
         )
-        .execute(&mut *tx)
-        .await?;
-
-        // Increment post like count
-        sqlx::query!(
-            "UPDATE feed_posts SET like_count = like_count + 1 WHERE id = $1",
-            post_id
-        )
-        .execute(&mut *tx)
-        .await?;
-
-        tx.commit().await?;
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
 
-        Ok(true)
+        // false when already liked (no new like)
+        Ok(like_changed(inserted_rows))
     }
 
-    /// Unlike a post
+    /// Unlike a post (idempotent)
     pub async fn unlike_post(&self, post_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
         // Verify post exists
         let _post = sqlx::query!("SELECT id FROM feed_posts WHERE id = $1", post_id)
@@ -594,32 +634,14 @@ impl FeedService {
             .await?
             .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
-        // Begin transaction
-        let mut tx = self.pool.begin().await?;
-
-        // Delete like
         sqlx::query!(
             "DELETE FROM feed_post_likes WHERE post_id = $1 AND user_id = $2",
             post_id,
             user_id
         )
-        .execute(&mut *tx)
+        .execute(&self.pool)
         .await?;
 
-        // Decrement post like count (only if like existed)
-        sqlx::query!(
-            r#"
-            UPDATE feed_posts
-            SET like_count = GREATEST(like_count - 1, 0)
-            WHERE id = $1
-            "#,
-            post_id
-        )
-        .execute(&mut *tx)
-        .await?;
-
-        tx.commit().await?;
-
         Ok(())
     }
 
@@ -636,3 +658,33 @@ impl FeedService {
         Ok(like.is_some())
     }
 }
+
+/// Whether a like insert added a new like. Repeats and the losing side of a
+/// concurrent race touch no rows, so they must not count as engagement again.
+fn like_changed(rows_affected: u64) -> bool {
+    rows_affected > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_write_that_touched_the_like_is_a_change() {
+        assert!(like_changed(1));
+    }
+
+    #[test]
+    fn a_repeated_or_raced_write_is_not_a_change() {
+        assert!(!like_changed(0));
+    }
+
+    #[test]
+    fn only_one_of_many_concurrent_likes_counts() {
+        // ON CONFLICT DO NOTHING lets exactly one of the racing inserts affect a row
+        let outcomes = std::iter::once(1)
+            .chain(std::iter::repeat_n(0, 49))
+            .map(like_changed);
+        assert_eq!(outcomes.filter(|changed| *changed).count(), 1);
+    }
+}
@@ -0,0 +1,354 @@
+use crate::error::AppError;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const VERIFICATION_RING_FLAG_REASON: &str = "verification_ring";
+pub const SHARED_IP_FLAG_REASON: &str = "shared_ip";
+pub const IMPOSSIBLE_TRAVEL_FLAG_REASON: &str = "impossible_travel";
+
+/// Approvals each way between two users, within `RING_WINDOW_DAYS`, that make them a ring
+const RING_THRESHOLD: i64 = 3;
+const RING_WINDOW_DAYS: i32 = 90;
+/// How far back the clearer's sign-ins are compared with the verifier's address
+const SHARED_IP_WINDOW_DAYS: i32 = 30;
+/// Faster than this between two verifications and the verifier can't have been to both
+const MAX_TRAVEL_SPEED_KMH: f64 = 150.0;
+/// Hops shorter than this are ignored; report locations are only so precise
+const MIN_TRAVEL_KM: f64 = 5.0;
+
+/// The verifier's previous verification, relative to the report being verified now
+#[derive(sqlx::FromRow)]
+struct PreviousVerification {
+    report_id: Uuid,
+    created_at: DateTime<Utc>,
+    distance_km: f64,
+}
+
+/// Checks verifications against patterns of collusion before they count, flagging the
+/// accounts involved for moderator review. A flag a moderator has resolved lets the same
+/// pair through from then on.
+#[derive(Clone)]
+pub struct FraudService {
+    pool: PgPool,
+}
+
+impl FraudService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Refuse `verifier_id`'s verdict on the report if it looks collusive: a reciprocal
+    /// ring of approvals with the clearer, the verifier using an address the clearer
+    /// signed in from, or a verification too far from their last one to have travelled.
+    /// Reports that aren't cleared are left to the usual verification rules.
+    pub async fn check_verification(
+        &self,
+        report_id: Uuid,
+        verifier_id: Uuid,
+        is_verified: bool,
+        ip_address: Option<&str>,
+    ) -> Result<(), AppError> {
+        let cleared_by: Option<Option<Uuid>> =
+            sqlx::query_scalar("SELECT cleared_by FROM litter_reports WHERE id = $1")
+                .bind(report_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let Some(clearer_id) = cleared_by.flatten() else {
+            return Ok(());
+        };
+        if clearer_id == verifier_id {
+            return Ok(());
+        }
+
+        // Only approvals earn the clearer anything, so rejections can't collude
+        if is_verified {
+            self.check_ring(report_id, verifier_id, clearer_id).await?;
+        }
+        if let Some(ip_address) = ip_address {
+            self.check_shared_ip(report_id, verifier_id, clearer_id, ip_address)
+                .await?;
+        }
+        self.check_travel(report_id, verifier_id).await
+    }
+
+    async fn check_ring(
+        &self,
+        report_id: Uuid,
+        verifier_id: Uuid,
+        clearer_id: Uuid,
+    ) -> Result<(), AppError> {
+        let (given, received): (i64, i64) = sqlx::query_as(
+            r"
+            SELECT
+                (SELECT COUNT(*) FROM report_verifications v
+                 JOIN litter_reports r ON r.id = v.report_id
+                 WHERE v.verifier_id = $1 AND r.cleared_by = $2 AND v.is_verified
+                   AND v.created_at > NOW() - make_interval(days => $3)),
+                (SELECT COUNT(*) FROM report_verifications v
+                 JOIN litter_reports r ON r.id = v.report_id
+                 WHERE v.verifier_id = $2 AND r.cleared_by = $1 AND v.is_verified
+                   AND v.created_at > NOW() - make_interval(days => $3))
+            ",
+        )
+        .bind(verifier_id)
+        .bind(clearer_id)
+        .bind(RING_WINDOW_DAYS)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !is_ring(given, received)
+            || self
+                .cleared_by_moderator(verifier_id, VERIFICATION_RING_FLAG_REASON, clearer_id)
+                .await?
+        {
+            return Ok(());
+        }
+
+        let details = format!(
+            "Approved {given} clears by, and had {received} clears approved by, the same user in {RING_WINDOW_DAYS} days"
+        );
+        self.flag_pair(
+            verifier_id,
+            clearer_id,
+            VERIFICATION_RING_FLAG_REASON,
+            report_id,
+            &details,
+        )
+        .await?;
+
+        Err(AppError::Forbidden(
+            "You and this clearer mostly verify each other's cleanups, so your verification has been sent for review".to_string(),
+        ))
+    }
+
+    async fn check_shared_ip(
+        &self,
+        report_id: Uuid,
+        verifier_id: Uuid,
+        clearer_id: Uuid,
+        ip_address: &str,
+    ) -> Result<(), AppError> {
+        let shared: bool = sqlx::query_scalar(
+            r"
+            SELECT EXISTS (
+                SELECT 1 FROM login_events
+                WHERE user_id = $1 AND ip_address = $2
+                  AND created_at > NOW() - make_interval(days => $3)
+            )
+            ",
+        )
+        .bind(clearer_id)
+        .bind(ip_address)
+        .bind(SHARED_IP_WINDOW_DAYS)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !shared
+            || self
+                .cleared_by_moderator(verifier_id, SHARED_IP_FLAG_REASON, clearer_id)
+                .await?
+        {
+            return Ok(());
+        }
+
+        let details =
+            format!("Verified a clear from {ip_address}, an address the clearer signed in from");
+        self.flag_pair(
+            verifier_id,
+            clearer_id,
+            SHARED_IP_FLAG_REASON,
+            report_id,
+            &details,
+        )
+        .await?;
+
+        Err(AppError::Forbidden(
+            "Verifications must come from someone other than the clearer, so this one has been sent for review".to_string(),
+        ))
+    }
+
+    async fn check_travel(&self, report_id: Uuid, verifier_id: Uuid) -> Result<(), AppError> {
+        let previous = sqlx::query_as::<_, PreviousVerification>(
+            r"
+            SELECT v.report_id, v.created_at,
+                   ST_Distance(
+                       r.location::geography,
+                       (SELECT location::geography FROM litter_reports WHERE id = $2)
+                   ) / 1000.0 AS distance_km
+            FROM report_verifications v
+            JOIN litter_reports r ON r.id = v.report_id
+            WHERE v.verifier_id = $1 AND v.report_id != $2
+            ORDER BY v.created_at DESC
+            LIMIT 1
+            ",
+        )
+        .bind(verifier_id)
+        .bind(report_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(previous) = previous else {
+            return Ok(());
+        };
+        let hours = hours_between(Utc::now() - previous.created_at);
+        if !is_impossible_travel(previous.distance_km, hours) {
+            return Ok(());
+        }
+        let speed_kmh = previous.distance_km / hours;
+
+        let details = format!(
+            "Verified reports {:.1} km apart {:.0} minutes apart (report {})",
+            previous.distance_km,
+            hours * 60.0,
+            previous.report_id
+        );
+        self.flag(
+            verifier_id,
+            IMPOSSIBLE_TRAVEL_FLAG_REASON,
+            None,
+            report_id,
+            &details,
+        )
+        .await?;
+        tracing::warn!(
+            "Flagged user {} for verifying {} at {:.0} km/h from their last verification",
+            verifier_id,
+            report_id,
+            speed_kmh
+        );
+
+        Err(AppError::Forbidden(
+            "This report is too far from your last verification to have checked it in person; it has been sent for review".to_string(),
+        ))
+    }
+
+    /// Whether a moderator has already reviewed and resolved this pattern for the pair
+    async fn cleared_by_moderator(
+        &self,
+        user_id: Uuid,
+        reason: &str,
+        related_user_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let cleared = sqlx::query_scalar(
+            r"
+            SELECT EXISTS (
+                SELECT 1 FROM account_flags
+                WHERE user_id = $1 AND reason = $2 AND related_user_id = $3
+                  AND resolved_at IS NOT NULL
+            )
+            ",
+        )
+        .bind(user_id)
+        .bind(reason)
+        .bind(related_user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(cleared)
+    }
+
+    /// Flag both users in a pattern, each pointing at the other
+    async fn flag_pair(
+        &self,
+        verifier_id: Uuid,
+        clearer_id: Uuid,
+        reason: &str,
+        report_id: Uuid,
+        details: &str,
+    ) -> Result<(), AppError> {
+        self.flag(verifier_id, reason, Some(clearer_id), report_id, details)
+            .await?;
+        self.flag(clearer_id, reason, Some(verifier_id), report_id, details)
+            .await?;
+        tracing::warn!(
+            "Flagged users {} and {} for {} on report {}",
+            verifier_id,
+            clearer_id,
+            reason,
+            report_id
+        );
+        Ok(())
+    }
+
+    /// Flag an account unless the same pattern is already waiting for review
+    async fn flag(
+        &self,
+        user_id: Uuid,
+        reason: &str,
+        related_user_id: Option<Uuid>,
+        report_id: Uuid,
+        details: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r"
+            INSERT INTO account_flags (user_id, reason, related_user_id, report_id, details)
+            SELECT $1, $2, $3, $4, $5
+            WHERE NOT EXISTS (
+                SELECT 1 FROM account_flags
+                WHERE user_id = $1 AND reason = $2
+                  AND related_user_id IS NOT DISTINCT FROM $3 AND resolved_at IS NULL
+            )
+            ",
+        )
+        .bind(user_id)
+        .bind(reason)
+        .bind(related_user_id)
+        .bind(report_id)
+        .bind(details)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Two users approving each other's clears often enough, both ways, to look like a ring
+fn is_ring(given: i64, received: i64) -> bool {
+    given.min(received) >= RING_THRESHOLD
+}
+
+/// Hours between two verifications, at least a second so a speed can be worked out
+fn hours_between(elapsed: Duration) -> f64 {
+    elapsed.num_seconds().max(1) as f64 / 3600.0
+}
+
+/// Whether covering `distance_km` in `hours` is too fast to have checked both reports
+/// in person
+fn is_impossible_travel(distance_km: f64, hours: f64) -> bool {
+    distance_km >= MIN_TRAVEL_KM && distance_km / hours > MAX_TRAVEL_SPEED_KMH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_ring_needs_approvals_both_ways() {
+        assert!(is_ring(RING_THRESHOLD, RING_THRESHOLD));
+        assert!(is_ring(10, RING_THRESHOLD));
+        assert!(!is_ring(10, RING_THRESHOLD - 1));
+        assert!(!is_ring(RING_THRESHOLD - 1, 10));
+    }
+
+    #[test]
+    fn elapsed_time_never_rounds_to_zero() {
+        assert_eq!(hours_between(Duration::hours(2)), 2.0);
+        assert!(hours_between(Duration::zero()) > 0.0);
+        assert!(hours_between(Duration::seconds(-30)) > 0.0);
+    }
+
+    #[test]
+    fn short_hops_are_never_impossible() {
+        let instant = hours_between(Duration::zero());
+        assert!(!is_impossible_travel(MIN_TRAVEL_KM - 0.1, instant));
+    }
+
+    #[test]
+    fn travel_is_judged_by_speed() {
+        // 100 km in half an hour is 200 km/h; in an hour it is 100 km/h
+        assert!(is_impossible_travel(100.0, 0.5));
+        assert!(!is_impossible_travel(100.0, 1.0));
+        assert!(!is_impossible_travel(MAX_TRAVEL_SPEED_KMH, 1.0));
+    }
+}
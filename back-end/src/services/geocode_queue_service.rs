@@ -0,0 +1,158 @@
+use crate::error::Result;
+use crate::services::geocoding_service::{GeocodingService, LookupError};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Attempts after which a report is left without an address
+const MAX_ATTEMPTS: i32 = 6;
+/// Reports claimed per sweep; the rest wait for the next tick
+const BATCH_SIZE: i64 = 20;
+/// Retry backoff doubles from one minute up to this cap
+const MAX_BACKOFF_MINS: i32 = 360;
+
+/// Durable queue of reports waiting for an address, so saving a report never waits on
+/// the geocoding provider. Enqueue inside the report's transaction, then call
+/// `resolve` after commit; `resolve_due` retries whatever is left.
+#[derive(Clone)]
+pub struct GeocodeQueueService {
+    pool: PgPool,
+    geocoding: GeocodingService,
+}
+
+impl GeocodeQueueService {
+    #[must_use]
+    pub fn new(pool: PgPool, geocoding: GeocodingService) -> Self {
+        Self { pool, geocoding }
+    }
+
+    /// Queue `report_id` for an address, starting afresh if it was queued before; it
+    /// only becomes visible if `tx` commits
+    pub async fn enqueue(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO geocode_jobs (report_id) VALUES ($1)
+            ON CONFLICT (report_id) DO UPDATE
+            SET attempts = 0, next_attempt_at = NOW(), last_error = NULL
+            ",
+        )
+        .bind(report_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Resolve one report's address in the background, leaving failures to `resolve_due`
+    pub fn spawn_resolve(&self, report_id: Uuid) {
+        let queue = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = queue.resolve(report_id).await {
+                tracing::warn!("Geocoding report {} failed: {:?}", report_id, e);
+            }
+        });
+    }
+
+    /// Try to resolve one queued report now, returning whether its job finished. A
+    /// failure is recorded on the job and retried by `resolve_due`.
+    pub async fn resolve(&self, report_id: Uuid) -> Result<bool> {
+        Ok(self.claim_and_resolve(Some(report_id), 1).await? == 1)
+    }
+
+    /// Resolve reports whose retry time has come, returning how many jobs finished
+    pub async fn resolve_due(&self) -> Result<usize> {
+        self.claim_and_resolve(None, BATCH_SIZE).await
+    }
+
+    /// Claim due jobs by pushing back their next attempt, so workers on other instances
+    /// skip them, then look up each report's address
+    async fn claim_and_resolve(&self, report_id: Option<Uuid>, limit: i64) -> Result<usize> {
+        let jobs: Vec<(Uuid, f64, f64)> = sqlx::query_as(
+            r"
+            UPDATE geocode_jobs j
+            SET attempts = j.attempts + 1,
+                next_attempt_at = NOW() + make_interval(
+                    mins => LEAST(power(2, j.attempts)::int, $3)
+                )
+            FROM litter_reports r
+            WHERE r.id = j.report_id
+              AND j.report_id IN (
+                SELECT report_id FROM geocode_jobs
+                WHERE attempts < $1
+                  AND next_attempt_at <= NOW()
+                  AND ($2::uuid IS NULL OR report_id = $2)
+                ORDER BY next_attempt_at
+                LIMIT $4
+                FOR UPDATE SKIP LOCKED
+              )
+            RETURNING j.report_id,
+                ST_Y(r.location)::double precision,
+                ST_X(r.location)::double precision
+            ",
+        )
+        .bind(MAX_ATTEMPTS)
+        .bind(report_id)
+        .bind(MAX_BACKOFF_MINS)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut finished = 0;
+        for (report_id, lat, lon) in jobs {
+            match self.geocoding.lookup(lat, lon).await {
+                Ok(address) => {
+                    let mut tx = self.pool.begin().await?;
+                    if let Some(address) = address {
+                        sqlx::query(
+                            r"
+                            UPDATE litter_reports
+                            SET address = $2, address_street = $3,
+                                address_city = $4, address_country = $5
+                            WHERE id = $1 AND address IS NULL
+                            ",
+                        )
+                        .bind(report_id)
+                        .bind(&address.label)
+                        .bind(&address.components.street)
+                        .bind(&address.components.city)
+                        .bind(&address.components.country)
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                    // Nowhere the provider knows of is as finished as an address
+                    sqlx::query("DELETE FROM geocode_jobs WHERE report_id = $1")
+                        .bind(report_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    tx.commit().await?;
+                    finished += 1;
+                }
+                Err(e) => {
+                    let (error, give_up) = match e {
+                        LookupError::Transient(e) => (e, false),
+                        LookupError::Permanent(e) => (e, true),
+                    };
+                    tracing::warn!("Geocoding report {} failed: {}", report_id, error);
+                    sqlx::query(
+                        r"
+                        UPDATE geocode_jobs
+                        SET last_error = $2,
+                            attempts = CASE WHEN $3 THEN $4 ELSE attempts END
+                        WHERE report_id = $1
+                        ",
+                    )
+                    .bind(report_id)
+                    .bind(&error)
+                    .bind(give_up)
+                    .bind(MAX_ATTEMPTS)
+                    .execute(&self.pool)
+                    .await?;
+                }
+            }
+        }
+
+        Ok(finished)
+    }
+}
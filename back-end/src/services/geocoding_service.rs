@@ -0,0 +1,565 @@
+use crate::config::{GeocodingConfig, GeocodingProvider};
+use crate::models::report::AddressComponents;
+use axum::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Largest reverse-geocode response we will read; a single place is a few KB
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 5;
+
+/// A reverse-geocoded location: a short display label plus its structured parts
+#[derive(Debug, Clone)]
+pub struct GeocodedAddress {
+    pub label: String,
+    pub components: AddressComponents,
+}
+
+/// Why a lookup produced no address; only transient failures are worth retrying
+#[derive(Debug)]
+pub enum LookupError {
+    Transient(String),
+    Permanent(String),
+}
+
+/// A reverse geocoding provider
+#[async_trait]
+pub trait Geocoder: Send + Sync {
+    /// Short name stored with each cached address, e.g. `nominatim`
+    fn name(&self) -> &'static str;
+
+    /// The address at a point, or `None` if the provider knows of nothing there
+    async fn reverse(&self, lat: f64, lon: f64) -> Result<Option<GeocodedAddress>, LookupError>;
+}
+
+/// The parts of a place the providers have in common, from which the label is built
+#[derive(Debug, Default)]
+struct Place {
+    poi: Option<String>,
+    house_number: Option<String>,
+    road: Option<String>,
+    suburb: Option<String>,
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    country: Option<String>,
+    display_name: Option<String>,
+}
+
+impl Place {
+    fn into_address(self) -> Option<GeocodedAddress> {
+        let components = AddressComponents {
+            street: self.road.clone(),
+            city: self
+                .city
+                .clone()
+                .or_else(|| self.town.clone())
+                .or_else(|| self.village.clone()),
+            country: self.country,
+        };
+
+        // We want "Tesco, Example Street" or "52 Example Street" or "Example Street"
+        let street = self
+            .road
+            .or(self.suburb)
+            .or(self.village)
+            .or(self.town)
+            .or(self.city);
+
+        let label = match (self.poi, self.house_number, street) {
+            (Some(p), Some(s), _) if p.eq_ignore_ascii_case(&s) => Some(p), // Avoid duplication
+            (Some(p), _, Some(s)) => Some(format!("{}, {}", p, s)),
+            (Some(p), _, None) => Some(p),
+            (None, Some(n), Some(s)) => Some(format!("{} {}", n, s)),
+            (None, None, Some(s)) => Some(s),
+            _ => self.display_name, // Fallback to full display name if nothing clean is found
+        }?;
+
+        Some(GeocodedAddress { label, components })
+    }
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .user_agent("LittyPicky/1.0")
+        .build()
+        .unwrap_or_default()
+}
+
+/// Send a request and parse its JSON body, reading no more than `MAX_RESPONSE_BYTES`
+async fn fetch_json<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    provider: &str,
+) -> Result<T, LookupError> {
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| LookupError::Transient(e.to_string()))?;
+
+    let status = response.status();
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(LookupError::Transient(format!(
+            "{provider} returned {status}"
+        )));
+    }
+    if !status.is_success() {
+        return Err(LookupError::Permanent(format!(
+            "{provider} returned {status}"
+        )));
+    }
+
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_RESPONSE_BYTES as u64)
+    {
+        return Err(LookupError::Permanent("Response too large".to_string()));
+    }
+
+    // The length header may be missing or wrong, so enforce the cap while reading
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| LookupError::Transient(e.to_string()))?
+    {
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err(LookupError::Permanent("Response too large".to_string()));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    serde_json::from_slice(&body)
+        .map_err(|e| LookupError::Permanent(format!("Invalid response: {e}")))
+}
+
+/// Nominatim's `/reverse` API
+pub struct Nominatim {
+    client: reqwest::Client,
+    api_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NominatimAddress {
+    road: Option<String>,
+    amenity: Option<String>,
+    shop: Option<String>,
+    building: Option<String>,
+    house_number: Option<String>,
+    suburb: Option<String>,
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    country: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NominatimResponse {
+    address: Option<NominatimAddress>,
+    display_name: Option<String>,
+}
+
+impl Nominatim {
+    #[must_use]
+    pub fn new(api_url: String) -> Self {
+        Self {
+            client: http_client(),
+            api_url,
+        }
+    }
+}
+
+#[async_trait]
+impl Geocoder for Nominatim {
+    fn name(&self) -> &'static str {
+        "nominatim"
+    }
+
+    async fn reverse(&self, lat: f64, lon: f64) -> Result<Option<GeocodedAddress>, LookupError> {
+        let request = self
+            .client
+            .get(format!("{}/reverse", self.api_url))
+            .query(&[
+                ("format", "json"),
+                ("lat", &lat.to_string()),
+                ("lon", &lon.to_string()),
+                ("zoom", "18"),
+                ("addressdetails", "1"),
+            ]);
+        let data: NominatimResponse = fetch_json(request, "Nominatim").await?;
+
+        let Some(addr) = data.address else {
+            return Ok(None);
+        };
+        Ok(Place {
+            // Check for POI/Building
+            poi: addr.amenity.or(addr.shop).or(addr.building),
+            house_number: addr.house_number,
+            road: addr.road,
+            suburb: addr.suburb,
+            city: addr.city,
+            town: addr.town,
+            village: addr.village,
+            country: addr.country,
+            display_name: data.display_name,
+        }
+        .into_address())
+    }
+}
+
+/// Google's Geocoding API
+pub struct Google {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleResponse {
+    status: String,
+    #[serde(default)]
+    results: Vec<GoogleResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleResult {
+    formatted_address: Option<String>,
+    #[serde(default)]
+    address_components: Vec<GoogleComponent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleComponent {
+    long_name: String,
+    #[serde(default)]
+    types: Vec<String>,
+}
+
+impl Google {
+    #[must_use]
+    pub fn new(api_url: String, api_key: String) -> Self {
+        Self {
+            client: http_client(),
+            api_url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl Geocoder for Google {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    async fn reverse(&self, lat: f64, lon: f64) -> Result<Option<GeocodedAddress>, LookupError> {
+        let request = self
+            .client
+            .get(format!("{}/maps/api/geocode/json", self.api_url))
+            .query(&[
+                ("latlng", format!("{lat},{lon}").as_str()),
+                ("key", &self.api_key),
+            ]);
+        let data: GoogleResponse = fetch_json(request, "Google").await?;
+
+        // Google reports most failures in the body with a 200
+        match data.status.as_str() {
+            "OK" => {}
+            "ZERO_RESULTS" => return Ok(None),
+            "OVER_QUERY_LIMIT" | "UNKNOWN_ERROR" => {
+                return Err(LookupError::Transient(format!(
+                    "Google returned {}",
+                    data.status
+                )))
+            }
+            other => return Err(LookupError::Permanent(format!("Google returned {other}"))),
+        }
+
+        let Some(result) = data.results.into_iter().next() else {
+            return Ok(None);
+        };
+        let find = |kinds: &[&str]| {
+            result
+                .address_components
+                .iter()
+                .find(|c| c.types.iter().any(|t| kinds.contains(&t.as_str())))
+                .map(|c| c.long_name.clone())
+        };
+
+        Ok(Place {
+            poi: find(&["point_of_interest", "establishment", "premise"]),
+            house_number: find(&["street_number"]),
+            road: find(&["route"]),
+            suburb: find(&["sublocality", "neighborhood"]),
+            city: find(&["locality", "postal_town"]),
+            country: find(&["country"]),
+            display_name: result.formatted_address,
+            ..Place::default()
+        }
+        .into_address())
+    }
+}
+
+/// Photon's `/reverse` API, usually self-hosted
+pub struct Photon {
+    client: reqwest::Client,
+    api_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PhotonResponse {
+    #[serde(default)]
+    features: Vec<PhotonFeature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PhotonFeature {
+    properties: PhotonProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct PhotonProperties {
+    name: Option<String>,
+    housenumber: Option<String>,
+    street: Option<String>,
+    district: Option<String>,
+    city: Option<String>,
+    country: Option<String>,
+    /// What the feature is, e.g. `house`, `street` or `city`
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+impl Photon {
+    #[must_use]
+    pub fn new(api_url: String) -> Self {
+        Self {
+            client: http_client(),
+            api_url,
+        }
+    }
+}
+
+#[async_trait]
+impl Geocoder for Photon {
+    fn name(&self) -> &'static str {
+        "photon"
+    }
+
+    async fn reverse(&self, lat: f64, lon: f64) -> Result<Option<GeocodedAddress>, LookupError> {
+        let request = self
+            .client
+            .get(format!("{}/reverse", self.api_url))
+            .query(&[("lat", lat.to_string()), ("lon", lon.to_string())]);
+        let data: PhotonResponse = fetch_json(request, "Photon").await?;
+
+        let Some(feature) = data.features.into_iter().next() else {
+            return Ok(None);
+        };
+        let props = feature.properties;
+
+        // A street's name is the street itself; anything else named is a POI
+        let (poi, road) = match props.kind.as_deref() {
+            Some("street") => (None, props.street.or(props.name)),
+            _ => (props.name, props.street),
+        };
+
+        Ok(Place {
+            poi,
+            house_number: props.housenumber,
+            road,
+            suburb: props.district,
+            city: props.city,
+            country: props.country,
+            ..Place::default()
+        }
+        .into_address())
+    }
+}
+
+/// Round a point for the cache, so lookups within a few metres share one entry
+#[must_use]
+pub fn cache_key(lat: f64, lon: f64, decimals: u32) -> (f64, f64) {
+    let scale = 10f64.powi(decimals as i32);
+    ((lat * scale).round() / scale, (lon * scale).round() / scale)
+}
+
+/// Reverse geocoding through the configured provider, spaced out to respect its usage
+/// policy. Addresses are cached per rounded point, so a busy spot is looked up once.
+#[derive(Clone)]
+pub struct GeocodingService {
+    pool: PgPool,
+    geocoder: Arc<dyn Geocoder>,
+    min_interval: Duration,
+    cache_decimals: u32,
+    cache_ttl_days: i64,
+    last_request: Arc<Mutex<Option<Instant>>>,
+}
+
+impl GeocodingService {
+    #[must_use]
+    pub fn new(pool: PgPool, config: GeocodingConfig) -> Self {
+        let geocoder: Arc<dyn Geocoder> = match config.provider {
+            GeocodingProvider::Nominatim => Arc::new(Nominatim::new(config.api_url.clone())),
+            GeocodingProvider::Google => Arc::new(Google::new(
+                config.api_url.clone(),
+                config.api_key.clone().unwrap_or_default(),
+            )),
+            GeocodingProvider::Photon => Arc::new(Photon::new(config.api_url.clone())),
+        };
+
+        Self::with_geocoder(pool, &config, geocoder)
+    }
+
+    /// Use a specific geocoder, e.g. a fake in tests
+    #[must_use]
+    pub fn with_geocoder(
+        pool: PgPool,
+        config: &GeocodingConfig,
+        geocoder: Arc<dyn Geocoder>,
+    ) -> Self {
+        Self {
+            pool,
+            geocoder,
+            min_interval: Duration::from_millis(config.min_interval_ms),
+            cache_decimals: config.cache_decimals,
+            cache_ttl_days: config.cache_ttl_days,
+            last_request: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Look up the address at a point, from the cache or else the provider, retrying
+    /// once on a transient failure. Geocoding is best-effort: failures are logged and
+    /// give `None`.
+    pub async fn reverse(&self, lat: f64, lon: f64) -> Option<GeocodedAddress> {
+        for attempt in 1..=2 {
+            match self.lookup(lat, lon).await {
+                Ok(address) => return address,
+                Err(LookupError::Transient(e)) => {
+                    tracing::warn!(
+                        "Reverse geocode of ({}, {}) failed on attempt {}: {}",
+                        lat,
+                        lon,
+                        attempt,
+                        e
+                    );
+                }
+                Err(LookupError::Permanent(e)) => {
+                    tracing::warn!("Reverse geocode of ({}, {}) failed: {}", lat, lon, e);
+                    return None;
+                }
+            }
+        }
+        None
+    }
+
+    /// Look up the address at a point once, from the cache or else the provider,
+    /// leaving retries to the caller
+    pub async fn lookup(&self, lat: f64, lon: f64) -> Result<Option<GeocodedAddress>, LookupError> {
+        let (lat_key, lon_key) = cache_key(lat, lon, self.cache_decimals);
+
+        match self.cached(lat_key, lon_key).await {
+            Ok(Some(address)) => return Ok(address),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Geocode cache lookup failed: {}", e),
+        }
+
+        self.wait_for_slot().await;
+        let address = self.geocoder.reverse(lat, lon).await?;
+        if let Err(e) = self.store(lat_key, lon_key, address.as_ref()).await {
+            tracing::warn!("Failed to cache geocode of ({}, {}): {}", lat, lon, e);
+        }
+        Ok(address)
+    }
+
+    /// A fresh cache entry: `Some(None)` when the provider found nothing there
+    async fn cached(
+        &self,
+        lat_key: f64,
+        lon_key: f64,
+    ) -> Result<Option<Option<GeocodedAddress>>, sqlx::Error> {
+        type CachedRow = (
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        );
+        let row: Option<CachedRow> = sqlx::query_as(
+            r"
+            SELECT label, street, city, country FROM geocode_cache
+            WHERE lat_key = $1 AND lon_key = $2 AND decimals = $3
+              AND created_at > NOW() - make_interval(days => $4)
+            ",
+        )
+        .bind(lat_key)
+        .bind(lon_key)
+        .bind(self.cache_decimals as i16)
+        .bind(self.cache_ttl_days as i32)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(label, street, city, country)| {
+            label.map(|label| GeocodedAddress {
+                label,
+                components: AddressComponents {
+                    street,
+                    city,
+                    country,
+                },
+            })
+        }))
+    }
+
+    async fn store(
+        &self,
+        lat_key: f64,
+        lon_key: f64,
+        address: Option<&GeocodedAddress>,
+    ) -> Result<(), sqlx::Error> {
+        let components = address.map(|a| &a.components);
+        sqlx::query(
+            r"
+            INSERT INTO geocode_cache
+                (lat_key, lon_key, decimals, label, street, city, country, provider)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (lat_key, lon_key, decimals) DO UPDATE
+            SET label = EXCLUDED.label,
+                street = EXCLUDED.street,
+                city = EXCLUDED.city,
+                country = EXCLUDED.country,
+                provider = EXCLUDED.provider,
+                created_at = NOW()
+            ",
+        )
+        .bind(lat_key)
+        .bind(lon_key)
+        .bind(self.cache_decimals as i16)
+        .bind(address.map(|a| &a.label))
+        .bind(components.and_then(|c| c.street.as_ref()))
+        .bind(components.and_then(|c| c.city.as_ref()))
+        .bind(components.and_then(|c| c.country.as_ref()))
+        .bind(self.geocoder.name())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Hold callers until the configured interval has passed since the previous request
+    async fn wait_for_slot(&self) {
+        let mut last_request = self.last_request.lock().await;
+
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.min_interval {
+                tokio::time::sleep(self.min_interval - elapsed).await;
+            }
+        }
+
+        *last_request = Some(Instant::now());
+    }
+}
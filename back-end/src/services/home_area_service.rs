@@ -0,0 +1,172 @@
+use crate::error::AppError;
+use crate::models::home_area::{
+    AreaBoostZone, AreaClear, AreaLeaderboardPosition, HomeAreaDashboard, SetHomeAreaRequest,
+    HOME_AREA_RECENT_CLEARS, HOME_AREA_WINDOW_DAYS,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// The user's home point as a geography, bound as `$1` latitude and `$2` longitude
+const HOME_POINT: &str = "ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography";
+
+#[derive(Clone)]
+pub struct HomeAreaService {
+    pool: PgPool,
+}
+
+impl HomeAreaService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Set or move the user's home area
+    pub async fn set_home(
+        &self,
+        user_id: Uuid,
+        request: SetHomeAreaRequest,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r"
+            INSERT INTO user_home_areas (user_id, location)
+            VALUES ($1, ST_SetSRID(ST_MakePoint($3, $2), 4326))
+            ON CONFLICT (user_id) DO UPDATE
+            SET location = EXCLUDED.location, updated_at = NOW()
+            ",
+        )
+        .bind(user_id)
+        .bind(request.latitude.degrees())
+        .bind(request.longitude.degrees())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forget the user's home area
+    pub async fn clear_home(&self, user_id: Uuid) -> Result<(), AppError> {
+        let deleted = sqlx::query("DELETE FROM user_home_areas WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        if deleted == 0 {
+            return Err(AppError::NotFound("No home area set".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The dashboard for everything within the user's search radius of their home. The
+    /// parts are independent, so they are queried side by side.
+    pub async fn dashboard(&self, user_id: Uuid) -> Result<HomeAreaDashboard, AppError> {
+        let (latitude, longitude, radius_km): (f64, f64, i32) = sqlx::query_as(
+            r"
+            SELECT ST_Y(h.location)::double precision, ST_X(h.location)::double precision,
+                   u.search_radius_km
+            FROM user_home_areas h
+            JOIN users u ON u.id = h.user_id
+            WHERE h.user_id = $1
+            ",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound("Set a home area with PUT /api/users/me/area first".to_string())
+        })?;
+        let radius_m = f64::from(radius_km) * 1000.0;
+
+        let open_reports_sql = format!(
+            "SELECT COUNT(*) FROM litter_reports r
+             WHERE r.status IN ('pending', 'claimed') AND r.hidden_at IS NULL
+               AND ST_DWithin(r.location::geography, {HOME_POINT}, $3)"
+        );
+        let open_reports = sqlx::query_scalar::<_, i64>(&open_reports_sql)
+            .bind(latitude)
+            .bind(longitude)
+            .bind(radius_m)
+            .fetch_one(&self.pool);
+
+        let recent_clears_sql = format!(
+            "SELECT r.id AS report_id,
+                    ST_Y(r.location)::double precision AS latitude,
+                    ST_X(r.location)::double precision AS longitude,
+                    r.address, r.cleared_at, u.full_name AS cleared_by_name
+             FROM litter_reports r
+             JOIN users u ON u.id = r.cleared_by
+             WHERE r.status IN ('cleared', 'verified') AND r.hidden_at IS NULL
+               AND r.cleared_at > NOW() - make_interval(days => $4)
+               AND ST_DWithin(r.location::geography, {HOME_POINT}, $3)
+             ORDER BY r.cleared_at DESC
+             LIMIT $5"
+        );
+        let recent_clears = sqlx::query_as::<_, AreaClear>(&recent_clears_sql)
+            .bind(latitude)
+            .bind(longitude)
+            .bind(radius_m)
+            .bind(HOME_AREA_WINDOW_DAYS)
+            .bind(HOME_AREA_RECENT_CLEARS)
+            .fetch_all(&self.pool);
+
+        let leaderboard_sql = format!(
+            "WITH local_points AS (
+                 SELECT se.user_id, SUM(se.points)::bigint AS points
+                 FROM score_events se
+                 JOIN litter_reports r ON r.id = se.report_id
+                 WHERE se.created_at > NOW() - make_interval(days => $4)
+                   AND ST_DWithin(r.location::geography, {HOME_POINT}, $3)
+                 GROUP BY se.user_id
+                 HAVING SUM(se.points) > 0
+             ),
+             ranked AS (
+                 SELECT user_id, points, RANK() OVER (ORDER BY points DESC) AS rank
+                 FROM local_points
+             )
+             SELECT r.rank, COALESCE(r.points, 0) AS points,
+                    (SELECT COUNT(*) FROM local_points) AS participants
+             FROM (SELECT 1) one
+             LEFT JOIN ranked r ON r.user_id = $5"
+        );
+        let leaderboard = sqlx::query_as::<_, AreaLeaderboardPosition>(&leaderboard_sql)
+            .bind(latitude)
+            .bind(longitude)
+            .bind(radius_m)
+            .bind(HOME_AREA_WINDOW_DAYS)
+            .bind(user_id)
+            .fetch_one(&self.pool);
+
+        let boost_zones_sql = format!(
+            "SELECT e.id AS event_id, e.title,
+                    ST_Y(e.meeting_point)::double precision AS latitude,
+                    ST_X(e.meeting_point)::double precision AS longitude,
+                    ST_Distance(e.meeting_point::geography, {HOME_POINT}) / 1000.0
+                        AS distance_km,
+                    e.starts_at, e.ends_at, e.starts_at <= NOW() AS ongoing,
+                    (SELECT COUNT(*) FROM cleanup_event_reports er WHERE er.event_id = e.id)
+                        AS report_count
+             FROM cleanup_events e
+             WHERE e.ends_at > NOW()
+               AND ST_DWithin(e.meeting_point::geography, {HOME_POINT}, $3)
+             ORDER BY e.starts_at"
+        );
+        let boost_zones = sqlx::query_as::<_, AreaBoostZone>(&boost_zones_sql)
+            .bind(latitude)
+            .bind(longitude)
+            .bind(radius_m)
+            .fetch_all(&self.pool);
+
+        let (open_reports, recent_clears, leaderboard, boost_zones) =
+            tokio::try_join!(open_reports, recent_clears, leaderboard, boost_zones)?;
+
+        Ok(HomeAreaDashboard {
+            latitude,
+            longitude,
+            radius_km,
+            open_reports,
+            recent_clears,
+            leaderboard,
+            boost_zones,
+        })
+    }
+}
@@ -0,0 +1,165 @@
+use crate::error::AppError;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// How long a stored response is replayed for the same key
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+/// A request still marked in progress after this long is assumed to have died
+const IN_PROGRESS_TIMEOUT_MINS: i32 = 5;
+
+/// A response recorded for an idempotency key
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status_code: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// What to do with a request carrying an idempotency key
+#[derive(Debug)]
+pub enum IdempotencyOutcome {
+    /// First use of the key: handle the request, then `complete` or `release` it
+    Proceed,
+    /// The key was already used for this request; send the original response
+    Replay(StoredResponse),
+}
+
+/// Remembers responses to mutating requests so retries are not applied twice
+#[derive(Clone)]
+pub struct IdempotencyService {
+    pool: PgPool,
+}
+
+impl IdempotencyService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Claim `key` for this request, or find the response it already produced
+    pub async fn begin(
+        &self,
+        user_id: Uuid,
+        endpoint: &str,
+        key: &str,
+        request_hash: &str,
+    ) -> Result<IdempotencyOutcome, AppError> {
+        let expires_at = Utc::now() + Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+
+        // Insert a fresh claim, or take over one that expired or whose request died
+        let claimed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO idempotency_keys (user_id, endpoint, idempotency_key, request_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, endpoint, idempotency_key) DO UPDATE
+            SET request_hash = EXCLUDED.request_hash,
+                status_code = NULL,
+                content_type = NULL,
+                response_body = NULL,
+                created_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at < NOW()
+               OR (idempotency_keys.status_code IS NULL
+                   AND idempotency_keys.created_at < NOW() - make_interval(mins => $6))
+            RETURNING user_id
+            "#,
+            user_id,
+            endpoint,
+            key,
+            request_hash,
+            expires_at,
+            IN_PROGRESS_TIMEOUT_MINS
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if claimed.is_some() {
+            return Ok(IdempotencyOutcome::Proceed);
+        }
+
+        let existing = sqlx::query!(
+            r#"
+            SELECT request_hash, status_code, content_type, response_body
+            FROM idempotency_keys
+            WHERE user_id = $1 AND endpoint = $2 AND idempotency_key = $3
+            "#,
+            user_id,
+            endpoint,
+            key
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        // Released between the two queries; the client can simply retry
+        .ok_or_else(|| {
+            AppError::Conflict("A request with this Idempotency-Key is in progress".to_string())
+        })?;
+
+        if existing.request_hash != request_hash {
+            return Err(AppError::BadRequest(
+                "Idempotency-Key was already used with a different request".to_string(),
+            ));
+        }
+
+        match (existing.status_code, existing.response_body) {
+            (Some(status_code), Some(body)) => Ok(IdempotencyOutcome::Replay(StoredResponse {
+                status_code: u16::try_from(status_code).unwrap_or(200),
+                content_type: existing.content_type,
+                body,
+            })),
+            _ => Err(AppError::Conflict(
+                "A request with this Idempotency-Key is in progress".to_string(),
+            )),
+        }
+    }
+
+    /// Record the response to replay for `key`
+    pub async fn complete(
+        &self,
+        user_id: Uuid,
+        endpoint: &str,
+        key: &str,
+        response: &StoredResponse,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $4, content_type = $5, response_body = $6
+            WHERE user_id = $1 AND endpoint = $2 AND idempotency_key = $3
+            "#,
+            user_id,
+            endpoint,
+            key,
+            i16::try_from(response.status_code).unwrap_or(i16::MAX),
+            response.content_type,
+            response.body
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Give up the claim on `key` so a retry is handled afresh
+    pub async fn release(&self, user_id: Uuid, endpoint: &str, key: &str) -> Result<(), AppError> {
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE user_id = $1 AND endpoint = $2 AND idempotency_key = $3",
+            user_id,
+            endpoint,
+            key
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete expired keys; returns how many were removed
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        let result = sqlx::query!("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
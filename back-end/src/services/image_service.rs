@@ -1,49 +1,89 @@
 use crate::{
     config::ImageConfig,
     error::{AppError, Result},
+    models::Coordinates,
+    services::upload_scan_service::UploadScanService,
 };
 use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
 use image::{imageops::FilterType, DynamicImage, GenericImageView};
 
+/// BlurHash detail level; 4x3 suits typical landscape photos and stays ~30 chars
+const BLURHASH_COMPONENTS_X: u32 = 4;
+const BLURHASH_COMPONENTS_Y: u32 = 3;
+/// BlurHash only captures coarse colour, so encode from a small thumbnail
+const BLURHASH_SAMPLE_SIZE: u32 = 32;
+
+/// A processed image ready for upload, with the metadata clients need for placeholders
+#[derive(Debug, Clone)]
+pub struct ProcessedImage {
+    /// WebP bytes
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub blurhash: String,
+    /// Read from the upload before conversion; the WebP carries no metadata
+    pub exif: PhotoExif,
+}
+
+/// Where and when the camera says a photo was taken
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhotoExif {
+    pub location: Option<Coordinates>,
+    /// Cameras usually record local time without an offset; that is read as UTC
+    pub taken_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Clone)]
 pub struct ImageService {
     config: ImageConfig,
+    /// Checks the uploaded bytes before they are decoded; `None` skips the check
+    scanner: Option<UploadScanService>,
 }
 
 impl ImageService {
     #[must_use]
     pub fn new(config: ImageConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            scanner: None,
+        }
     }
 
-    /// Process image: decode base64, validate, resize, convert to WebP, return raw bytes
+    /// Run every upload past `scanner` before it is processed
+    #[must_use]
+    pub fn with_scanner(mut self, scanner: UploadScanService) -> Self {
+        self.scanner = Some(scanner);
+        self
+    }
+
+    /// Process image: decode base64, validate, resize, convert to WebP, compute a BlurHash
     /// Uses spawn_blocking to avoid blocking the async runtime during CPU-intensive work
-    /// Returns WebP bytes ready for S3 upload
-    pub async fn process_image(&self, base64_input: String) -> Result<Vec<u8>> {
-        let config = self.config.clone();
+    /// Returns WebP bytes ready for S3 upload along with the final dimensions
+    pub async fn process_image(&self, base64_input: String) -> Result<ProcessedImage> {
+        let image_data =
+            tokio::task::spawn_blocking(move || Self::decode_base64_sync(&base64_input))
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Task join error: {}", e)))??;
 
-        // Move CPU-intensive work to blocking thread pool
-        tokio::task::spawn_blocking(move || Self::process_image_sync(&base64_input, &config))
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Task join error: {}", e)))?
+        self.process_image_bytes(image_data).await
     }
 
-    /// Synchronous image processing implementation
-    /// Returns raw WebP bytes (not base64)
-    fn process_image_sync(base64_input: &str, config: &ImageConfig) -> Result<Vec<u8>> {
-        // Validate base64 format first
-        Self::validate_base64_sync(base64_input)?;
+    /// Process raw image bytes (e.g. from a completed chunked upload) the same way
+    pub async fn process_image_bytes(&self, image_data: Vec<u8>) -> Result<ProcessedImage> {
+        // Scan what the client sent, before any decoder touches it
+        if let Some(scanner) = &self.scanner {
+            scanner.check(&image_data).await?;
+        }
 
-        // Remove data URI prefix if present
-        let base64_data = if base64_input.contains("base64,") {
-            base64_input.split("base64,").nth(1).unwrap() // Safe because validate_base64 already checked this
-        } else {
-            base64_input
-        };
+        let config = self.config.clone();
 
-        // Decode base64
-        let image_data = general_purpose::STANDARD.decode(base64_data).unwrap(); // Safe because validate_base64 already decoded it
+        tokio::task::spawn_blocking(move || Self::process_bytes_sync(&image_data, &config))
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Task join error: {}", e)))?
+    }
 
+    fn process_bytes_sync(image_data: &[u8], config: &ImageConfig) -> Result<ProcessedImage> {
         // Check size limit
         let max_size_bytes = config.max_size_mb * 1024 * 1024;
         if image_data.len() > max_size_bytes {
@@ -54,7 +94,7 @@ impl ImageService {
         }
 
         // Load image
-        let img = image::load_from_memory(&image_data)
+        let img = image::load_from_memory(image_data)
             .map_err(|e| AppError::Image(format!("Failed to load image: {e}")))?;
 
         // Validate dimensions
@@ -68,14 +108,24 @@ impl ImageService {
             ));
         }
 
+        let exif = read_exif(image_data);
+
         // Resize if necessary
         let resized_img = Self::resize_image_static(img, config);
 
         // Convert to WebP
         let webp_data = Self::convert_to_webp_static(&resized_img, config)?;
 
+        let blurhash = Self::compute_blurhash_static(&resized_img)?;
+
         // Return raw bytes (not base64)
-        Ok(webp_data)
+        Ok(ProcessedImage {
+            data: webp_data,
+            width: resized_img.width(),
+            height: resized_img.height(),
+            blurhash,
+            exif,
+        })
     }
 
     fn resize_image_static(img: DynamicImage, config: &ImageConfig) -> DynamicImage {
@@ -115,6 +165,21 @@ impl ImageService {
         Ok(webp_memory.to_vec())
     }
 
+    fn compute_blurhash_static(img: &DynamicImage) -> Result<String> {
+        let sample = img
+            .thumbnail(BLURHASH_SAMPLE_SIZE, BLURHASH_SAMPLE_SIZE)
+            .to_rgba8();
+
+        blurhash::encode(
+            BLURHASH_COMPONENTS_X,
+            BLURHASH_COMPONENTS_Y,
+            sample.width(),
+            sample.height(),
+            sample.as_raw(),
+        )
+        .map_err(|e| AppError::Image(format!("Failed to compute blurhash: {e}")))
+    }
+
     /// Validate that input is valid base64 (async wrapper)
     pub async fn validate_base64(&self, base64_input: String) -> Result<()> {
         tokio::task::spawn_blocking(move || Self::validate_base64_sync(&base64_input))
@@ -124,6 +189,11 @@ impl ImageService {
 
     /// Validate that input is valid base64 (doesn't process, just validates)
     fn validate_base64_sync(base64_input: &str) -> Result<()> {
+        Self::decode_base64_sync(base64_input).map(|_| ())
+    }
+
+    /// Decode base64 input, with or without a data URI prefix
+    fn decode_base64_sync(base64_input: &str) -> Result<Vec<u8>> {
         let base64_data = if base64_input.contains("base64,") {
             base64_input
                 .split("base64,")
@@ -135,8 +205,228 @@ impl ImageService {
 
         general_purpose::STANDARD
             .decode(base64_data)
-            .map_err(|e| AppError::Image(format!("Invalid base64: {e}")))?;
+            .map_err(|e| AppError::Image(format!("Invalid base64: {e}")))
+    }
+}
+
+const JPEG_SOI: [u8; 2] = [0xFF, 0xD8];
+const JPEG_APP1: u8 = 0xE1;
+/// Start of scan and end of image; no metadata segments follow either
+const JPEG_SOS: u8 = 0xDA;
+const JPEG_EOI: u8 = 0xD9;
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+
+/// Read the GPS position and capture time from a JPEG's EXIF block. Anything missing or
+/// malformed is left out rather than failing the upload.
+#[must_use]
+pub fn read_exif(image_data: &[u8]) -> PhotoExif {
+    let Some(tiff) = jpeg_exif_block(image_data).and_then(Tiff::new) else {
+        return PhotoExif::default();
+    };
+    let Some(ifd0) = tiff.first_ifd() else {
+        return PhotoExif::default();
+    };
+
+    let location = tiff
+        .pointer(ifd0, TAG_GPS_IFD)
+        .and_then(|gps| tiff.gps_location(gps));
+
+    let exif_ifd = tiff.pointer(ifd0, TAG_EXIF_IFD);
+    let original = exif_ifd.and_then(|ifd| {
+        let taken = tiff.ascii(ifd, TAG_DATE_TIME_ORIGINAL)?;
+        Some((taken, tiff.ascii(ifd, TAG_OFFSET_TIME_ORIGINAL)))
+    });
+    let taken_at = original
+        .or_else(|| tiff.ascii(ifd0, TAG_DATE_TIME).map(|taken| (taken, None)))
+        .and_then(|(taken, offset)| parse_exif_time(taken, offset));
+
+    PhotoExif { location, taken_at }
+}
+
+/// The TIFF data inside a JPEG's `Exif` APP1 segment
+fn jpeg_exif_block(data: &[u8]) -> Option<&[u8]> {
+    if !data.starts_with(&JPEG_SOI) {
+        return None;
+    }
+
+    let mut pos = JPEG_SOI.len();
+    while let [0xFF, marker, len_hi, len_lo, ..] = *data.get(pos..)? {
+        if marker == JPEG_SOS || marker == JPEG_EOI {
+            return None;
+        }
+        let len = usize::from(u16::from_be_bytes([len_hi, len_lo]));
+        let segment = data.get(pos + 4..pos + 2 + len.max(2))?;
+        if marker == JPEG_APP1 {
+            if let Some(tiff) = segment.strip_prefix(EXIF_HEADER) {
+                return Some(tiff);
+            }
+        }
+        pos += 2 + len;
+    }
+    None
+}
+
+/// `YYYY:MM:DD HH:MM:SS`, with an optional `+HH:MM` offset
+fn parse_exif_time(taken: &str, offset: Option<&str>) -> Option<DateTime<Utc>> {
+    let local = NaiveDateTime::parse_from_str(taken.trim(), "%Y:%m:%d %H:%M:%S").ok()?;
+    match offset.and_then(|o| o.trim().parse::<FixedOffset>().ok()) {
+        Some(offset) => Some(
+            offset
+                .from_local_datetime(&local)
+                .single()?
+                .with_timezone(&Utc),
+        ),
+        None => Some(local.and_utc()),
+    }
+}
+
+/// One IFD entry: its type, count and where its value starts in the TIFF data
+#[derive(Clone, Copy)]
+struct IfdEntry {
+    kind: u16,
+    count: usize,
+    value_at: usize,
+}
+
+/// A TIFF structure in either byte order, as EXIF stores it
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..4)? {
+            [b'I', b'I', 42, 0] => true,
+            [b'M', b'M', 0, 42] => false,
+            _ => return None,
+        };
+        Some(Self {
+            data,
+            little_endian,
+        })
+    }
 
-        Ok(())
+    fn u16_at(&self, at: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(at..at + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32_at(&self, at: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(at..at + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn first_ifd(&self) -> Option<usize> {
+        usize::try_from(self.u32_at(4)?).ok()
+    }
+
+    fn entry(&self, ifd: usize, tag: u16) -> Option<IfdEntry> {
+        let count = usize::from(self.u16_at(ifd)?);
+        (0..count).find_map(|i| {
+            let at = ifd + 2 + i * 12;
+            if self.u16_at(at)? != tag {
+                return None;
+            }
+            let kind = self.u16_at(at + 2)?;
+            let count = usize::try_from(self.u32_at(at + 4)?).ok()?;
+            let size = match kind {
+                TYPE_ASCII => 1,
+                TYPE_SHORT => 2,
+                TYPE_LONG => 4,
+                TYPE_RATIONAL => 8,
+                _ => return None,
+            };
+            // Values of up to four bytes sit in the entry itself
+            let value_at = if size * count <= 4 {
+                at + 8
+            } else {
+                usize::try_from(self.u32_at(at + 8)?).ok()?
+            };
+            Some(IfdEntry {
+                kind,
+                count,
+                value_at,
+            })
+        })
+    }
+
+    /// The offset of the sub-IFD a LONG pointer tag leads to
+    fn pointer(&self, ifd: usize, tag: u16) -> Option<usize> {
+        let entry = self.entry(ifd, tag)?;
+        if entry.kind != TYPE_LONG {
+            return None;
+        }
+        usize::try_from(self.u32_at(entry.value_at)?).ok()
+    }
+
+    fn ascii(&self, ifd: usize, tag: u16) -> Option<&'a str> {
+        let entry = self.entry(ifd, tag)?;
+        if entry.kind != TYPE_ASCII {
+            return None;
+        }
+        let bytes = self
+            .data
+            .get(entry.value_at..entry.value_at + entry.count)?;
+        std::str::from_utf8(bytes)
+            .ok()
+            .map(|s| s.trim_end_matches('\0'))
+    }
+
+    /// Degrees from a degrees, minutes, seconds triple of rationals
+    fn degrees(&self, ifd: usize, tag: u16) -> Option<f64> {
+        let entry = self.entry(ifd, tag)?;
+        if entry.kind != TYPE_RATIONAL || entry.count != 3 {
+            return None;
+        }
+        let mut parts = [0.0; 3];
+        for (i, part) in parts.iter_mut().enumerate() {
+            let at = entry.value_at + i * 8;
+            let denominator = self.u32_at(at + 4)?;
+            if denominator == 0 {
+                return None;
+            }
+            *part = f64::from(self.u32_at(at)?) / f64::from(denominator);
+        }
+        Some(parts[0] + parts[1] / 60.0 + parts[2] / 3600.0)
+    }
+
+    fn gps_location(&self, gps: usize) -> Option<Coordinates> {
+        let mut latitude = self.degrees(gps, TAG_GPS_LATITUDE)?;
+        let mut longitude = self.degrees(gps, TAG_GPS_LONGITUDE)?;
+        if self.ascii(gps, TAG_GPS_LATITUDE_REF)? == "S" {
+            latitude = -latitude;
+        }
+        if self.ascii(gps, TAG_GPS_LONGITUDE_REF)? == "W" {
+            longitude = -longitude;
+        }
+        // Some cameras write zeros when they have no fix
+        if latitude == 0.0 && longitude == 0.0 {
+            return None;
+        }
+        Coordinates::try_from_degrees(latitude, longitude).ok()
     }
 }
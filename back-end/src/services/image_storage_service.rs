@@ -0,0 +1,453 @@
+use crate::error::AppError;
+use crate::services::s3_service::{S3Service, StorageArea};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Distinct reports one user may attach the same photo to before the account is flagged
+pub const DUPLICATE_PHOTO_FLAG_THRESHOLD: i64 = 3;
+pub const DUPLICATE_PHOTO_FLAG_REASON: &str = "duplicate_photo";
+
+/// Pending uploads retried per sweep; the rest wait for the next tick
+const PENDING_BATCH_SIZE: i64 = 20;
+/// Retry backoff doubles from one minute up to this cap
+const PENDING_MAX_BACKOFF_MINS: i32 = 30;
+/// Images moved to the archive storage class per run
+const ARCHIVE_BATCH_SIZE: i64 = 100;
+
+/// A processed image in storage, addressed by its content hash
+#[derive(Debug, Clone)]
+pub struct StoredImage {
+    pub content_hash: String,
+    pub url: String,
+    /// Storage was unreachable, so the image is held in the database until it can be
+    /// uploaded; its URL only works through the API until then
+    pub pending: bool,
+}
+
+/// Content-addressed image storage: identical processed images are stored once
+#[derive(Clone)]
+pub struct ImageStorageService {
+    pool: PgPool,
+    s3_service: S3Service,
+    /// Most image bytes held in the database while storage is down; 0 refuses uploads
+    /// instead
+    spill_max_bytes: i64,
+}
+
+impl ImageStorageService {
+    #[must_use]
+    pub fn new(pool: PgPool, s3_service: S3Service, spill_max_mb: u64) -> Self {
+        Self {
+            pool,
+            s3_service,
+            spill_max_bytes: i64::try_from(spill_max_mb.saturating_mul(1024 * 1024))
+                .unwrap_or(i64::MAX),
+        }
+    }
+
+    /// Store processed WebP bytes, reusing the existing object if identical bytes were stored before.
+    /// If storage is unreachable the bytes are held in the database for `upload_pending`.
+    pub async fn store(&self, image_data: Vec<u8>) -> Result<StoredImage, AppError> {
+        let content_hash = format!("{:x}", Sha256::digest(&image_data));
+
+        if let Some(url) = sqlx::query_scalar!(
+            "SELECT url FROM stored_images WHERE content_hash = $1",
+            content_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        {
+            let pending = self.is_pending(&content_hash).await?;
+            return Ok(StoredImage {
+                content_hash,
+                url,
+                pending,
+            });
+        }
+
+        // The key is derived from the hash, so a concurrent upload of the same image writes identical bytes
+        let s3_key = self
+            .s3_service
+            .object_key(&format!("images/{}.webp", content_hash));
+        let size_bytes = i64::try_from(image_data.len()).unwrap_or(i64::MAX);
+        let url = match self
+            .s3_service
+            .put_image(StorageArea::ReportPhotos, &s3_key, image_data.clone())
+            .await
+        {
+            Ok(url) => url,
+            Err(e) => return self.spill(content_hash, s3_key, image_data, e).await,
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO stored_images (content_hash, s3_key, url, size_bytes)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (content_hash) DO NOTHING
+            "#,
+            content_hash,
+            s3_key,
+            url,
+            size_bytes
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(StoredImage {
+            content_hash,
+            url,
+            pending: false,
+        })
+    }
+
+    /// Hold an image that storage refused in the database, or give back `error` if
+    /// that would go over the spill limit
+    async fn spill(
+        &self,
+        content_hash: String,
+        s3_key: String,
+        image_data: Vec<u8>,
+        error: AppError,
+    ) -> Result<StoredImage, AppError> {
+        let size_bytes = i64::try_from(image_data.len()).unwrap_or(i64::MAX);
+        let spilled: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(octet_length(data)), 0)::bigint FROM pending_image_uploads",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if spilled.saturating_add(size_bytes) > self.spill_max_bytes {
+            return Err(error);
+        }
+
+        tracing::warn!(
+            "Storage unavailable, holding image {} for a later upload: {:?}",
+            content_hash,
+            error
+        );
+        let url = self
+            .s3_service
+            .public_url(StorageArea::ReportPhotos, &s3_key);
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r"
+            INSERT INTO stored_images (content_hash, s3_key, url, size_bytes)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (content_hash) DO NOTHING
+            ",
+        )
+        .bind(&content_hash)
+        .bind(&s3_key)
+        .bind(&url)
+        .bind(size_bytes)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r"
+            INSERT INTO pending_image_uploads (content_hash, data, last_error)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (content_hash) DO NOTHING
+            ",
+        )
+        .bind(&content_hash)
+        .bind(&image_data)
+        .bind(error.to_string())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(StoredImage {
+            content_hash,
+            url,
+            pending: true,
+        })
+    }
+
+    async fn is_pending(&self, content_hash: &str) -> Result<bool, AppError> {
+        Ok(sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pending_image_uploads WHERE content_hash = $1)",
+        )
+        .bind(content_hash)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    /// Which of `urls` are still waiting to be uploaded
+    pub async fn pending_urls(&self, urls: &[String]) -> Result<HashSet<String>, AppError> {
+        if urls.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let pending: Vec<String> = sqlx::query_scalar(
+            r"
+            SELECT s.url FROM stored_images s
+            JOIN pending_image_uploads p ON p.content_hash = s.content_hash
+            WHERE s.url = ANY($1)
+            ",
+        )
+        .bind(urls)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(pending.into_iter().collect())
+    }
+
+    /// The bytes of an image at `url` that is still waiting to be uploaded
+    pub async fn pending_image(&self, url: &str) -> Result<Option<Vec<u8>>, AppError> {
+        Ok(sqlx::query_scalar(
+            r"
+            SELECT p.data FROM stored_images s
+            JOIN pending_image_uploads p ON p.content_hash = s.content_hash
+            WHERE s.url = $1
+            ",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// Upload images held while storage was down, returning how many went up. Each is
+    /// claimed by pushing back its next attempt, so workers on other instances skip it.
+    pub async fn upload_pending(&self) -> Result<usize, AppError> {
+        let claimed: Vec<(String, String, Vec<u8>)> = sqlx::query_as(
+            r"
+            UPDATE pending_image_uploads p
+            SET attempts = p.attempts + 1,
+                next_attempt_at = NOW() + make_interval(
+                    mins => LEAST(power(2, LEAST(p.attempts, 10))::int, $1)
+                )
+            FROM stored_images s
+            WHERE s.content_hash = p.content_hash
+              AND p.content_hash IN (
+                SELECT content_hash FROM pending_image_uploads
+                WHERE next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+              )
+            RETURNING p.content_hash, s.s3_key, p.data
+            ",
+        )
+        .bind(PENDING_MAX_BACKOFF_MINS)
+        .bind(PENDING_BATCH_SIZE)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut uploaded = 0;
+        for (content_hash, s3_key, data) in claimed {
+            match self
+                .s3_service
+                .put_image(StorageArea::ReportPhotos, &s3_key, data)
+                .await
+            {
+                Ok(_) => {
+                    sqlx::query("DELETE FROM pending_image_uploads WHERE content_hash = $1")
+                        .bind(&content_hash)
+                        .execute(&self.pool)
+                        .await?;
+                    uploaded += 1;
+                }
+                Err(e) => {
+                    sqlx::query(
+                        "UPDATE pending_image_uploads SET last_error = $2 WHERE content_hash = $1",
+                    )
+                    .bind(&content_hash)
+                    .bind(e.to_string())
+                    .execute(&self.pool)
+                    .await?;
+                    // Storage is probably still down; leave the rest for the next tick
+                    tracing::warn!("Pending image {} still not uploaded: {:?}", content_hash, e);
+                    break;
+                }
+            }
+        }
+
+        if uploaded > 0 {
+            tracing::info!("Uploaded {} images held while storage was down", uploaded);
+        }
+
+        Ok(uploaded)
+    }
+
+    /// Move images that only expired reports use to `storage_class`, returning how many
+    /// moved. Stops at the first failure and leaves the rest for the next run.
+    pub async fn archive_expired(&self, storage_class: &str) -> Result<usize, AppError> {
+        let images: Vec<(String, String)> = sqlx::query_as(
+            r"
+            WITH expired_urls AS (
+                SELECT p.url FROM report_photos p
+                JOIN litter_reports r ON r.id = p.report_id
+                WHERE r.status = 'expired'
+                UNION
+                SELECT unnest(ARRAY[r.photo_before, r.photo_after]) FROM litter_reports r
+                WHERE r.status = 'expired'
+            )
+            SELECT s.content_hash, s.s3_key FROM stored_images s
+            JOIN expired_urls e ON e.url = s.url
+            WHERE s.archived_at IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM pending_image_uploads u WHERE u.content_hash = s.content_hash
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM litter_reports r
+                  WHERE (r.photo_before = s.url OR r.photo_after = s.url)
+                    AND r.status <> 'expired'
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM report_photos p
+                  JOIN litter_reports r ON r.id = p.report_id
+                  WHERE p.url = s.url AND r.status <> 'expired'
+              )
+              AND NOT EXISTS (SELECT 1 FROM feed_post_images f WHERE f.image_url = s.url)
+            LIMIT $1
+            ",
+        )
+        .bind(ARCHIVE_BATCH_SIZE)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut archived = 0;
+        for (content_hash, s3_key) in images {
+            if let Err(e) = self
+                .s3_service
+                .set_storage_class(StorageArea::ReportPhotos, &s3_key, storage_class)
+                .await
+            {
+                tracing::warn!("Could not archive image {}: {:?}", content_hash, e);
+                break;
+            }
+            sqlx::query("UPDATE stored_images SET archived_at = NOW() WHERE content_hash = $1")
+                .bind(&content_hash)
+                .execute(&self.pool)
+                .await?;
+            archived += 1;
+        }
+
+        Ok(archived)
+    }
+
+    /// Move any archived images at `urls` back to the standard storage class
+    pub async fn restore(&self, urls: &[String]) -> Result<usize, AppError> {
+        let images: Vec<(String, String)> = sqlx::query_as(
+            r"
+            SELECT content_hash, s3_key FROM stored_images
+            WHERE url = ANY($1) AND archived_at IS NOT NULL
+            ",
+        )
+        .bind(urls)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (content_hash, s3_key) in &images {
+            self.s3_service
+                .set_storage_class(StorageArea::ReportPhotos, s3_key, "STANDARD")
+                .await?;
+            sqlx::query("UPDATE stored_images SET archived_at = NULL WHERE content_hash = $1")
+                .bind(content_hash)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(images.len())
+    }
+
+    /// Delete stored images at `urls` that no report, report photo, verification or feed
+    /// post uses any more, e.g. the photos of a cancelled report. Returns how many were deleted.
+    pub async fn delete_unreferenced(&self, urls: &[String]) -> Result<usize, AppError> {
+        if urls.is_empty() {
+            return Ok(0);
+        }
+
+        let s3_keys: Vec<String> = sqlx::query_scalar(
+            r"
+            DELETE FROM stored_images s
+            WHERE s.url = ANY($1)
+              AND NOT EXISTS (
+                  SELECT 1 FROM litter_reports r
+                  WHERE r.photo_before = s.url OR r.photo_after = s.url
+              )
+              AND NOT EXISTS (SELECT 1 FROM report_photos p WHERE p.url = s.url)
+              AND NOT EXISTS (SELECT 1 FROM report_verifications v WHERE v.photo = s.url)
+              AND NOT EXISTS (
+                  SELECT 1 FROM report_photo_hashes h WHERE h.content_hash = s.content_hash
+              )
+              AND NOT EXISTS (SELECT 1 FROM feed_post_images f WHERE f.image_url = s.url)
+            RETURNING s.s3_key
+            ",
+        )
+        .bind(urls)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for s3_key in &s3_keys {
+            self.s3_service
+                .delete_image(StorageArea::ReportPhotos, s3_key)
+                .await?;
+        }
+
+        Ok(s3_keys.len())
+    }
+
+    /// Record that a user attached a photo to a report, flagging the account once the
+    /// same photo appears on too many distinct reports
+    pub async fn record_report_photo(
+        &self,
+        user_id: Uuid,
+        report_id: Uuid,
+        content_hash: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO report_photo_hashes (report_id, user_id, content_hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            report_id,
+            user_id,
+            content_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        let report_count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(DISTINCT report_id) AS "count!"
+            FROM report_photo_hashes
+            WHERE user_id = $1 AND content_hash = $2
+            "#,
+            user_id,
+            content_hash
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if report_count >= DUPLICATE_PHOTO_FLAG_THRESHOLD {
+            let details = format!("Identical photo uploaded on {report_count} distinct reports");
+            sqlx::query!(
+                r#"
+                INSERT INTO account_flags (user_id, reason, content_hash, details)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (user_id, reason, content_hash) DO UPDATE
+                SET details = EXCLUDED.details
+                "#,
+                user_id,
+                DUPLICATE_PHOTO_FLAG_REASON,
+                content_hash,
+                details
+            )
+            .execute(&self.pool)
+            .await?;
+
+            tracing::warn!(
+                "Flagged user {} for reusing photo {} on {} reports",
+                user_id,
+                content_hash,
+                report_count
+            );
+        }
+
+        Ok(())
+    }
+}
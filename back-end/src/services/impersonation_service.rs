@@ -0,0 +1,135 @@
+use crate::auth::JwtService;
+use crate::error::AppError;
+use crate::models::impersonation::{Impersonation, ImpersonationResponse};
+use crate::models::pagination::{PageParams, Paginated};
+use crate::models::UserRole;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// How long an impersonation token lasts; support re-issues one rather than refreshing
+const IMPERSONATION_TOKEN_MINUTES: i64 = 15;
+const DEFAULT_IMPERSONATION_PAGE_SIZE: i64 = 50;
+const MAX_IMPERSONATION_PAGE_SIZE: i64 = 200;
+
+/// Lets support staff sign in as a user to debug user-specific problems.
+/// Every token issued is recorded in the `impersonations` audit table.
+#[derive(Clone)]
+pub struct ImpersonationService {
+    pool: PgPool,
+    jwt_service: JwtService,
+}
+
+impl ImpersonationService {
+    #[must_use]
+    pub fn new(pool: PgPool, jwt_service: JwtService) -> Self {
+        Self { pool, jwt_service }
+    }
+
+    /// Record the impersonation and issue a short-lived access token for `user_id`
+    pub async fn impersonate(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        reason: &str,
+    ) -> Result<ImpersonationResponse, AppError> {
+        if admin_id == user_id {
+            return Err(AppError::BadRequest(
+                "You cannot impersonate yourself".to_string(),
+            ));
+        }
+
+        let user = sqlx::query!(
+            r#"SELECT email, role AS "role: UserRole" FROM users WHERE id = $1"#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        // An impersonation token never carries staff rights
+        if !matches!(user.role, UserRole::User) {
+            return Err(AppError::Forbidden(
+                "Admins and moderators cannot be impersonated".to_string(),
+            ));
+        }
+
+        let expires_in = Duration::minutes(IMPERSONATION_TOKEN_MINUTES);
+        let impersonation = sqlx::query_as!(
+            Impersonation,
+            r#"
+            INSERT INTO impersonations (admin_id, user_id, admin_email, user_email, reason, expires_at)
+            SELECT $1, $2, email, $3, $4, $5 FROM users WHERE id = $1
+            RETURNING id, admin_id, user_id, admin_email, user_email, reason, expires_at, created_at
+            "#,
+            admin_id,
+            user_id,
+            user.email,
+            reason.trim(),
+            Utc::now() + expires_in
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+        let access_token = self.jwt_service.create_impersonation_token(
+            user_id,
+            &user.email,
+            admin_id,
+            expires_in,
+        )?;
+
+        tracing::warn!(
+            "Admin {} ({}) is impersonating user {} ({}): {}",
+            admin_id,
+            impersonation.admin_email,
+            user_id,
+            user.email,
+            impersonation.reason
+        );
+
+        Ok(ImpersonationResponse {
+            access_token,
+            expires_in: expires_in.num_seconds(),
+            impersonation,
+        })
+    }
+
+    /// The audit trail, newest first, optionally for one user
+    pub async fn list(
+        &self,
+        user_id: Option<Uuid>,
+        page: &PageParams,
+    ) -> Result<Paginated<Impersonation>, AppError> {
+        let offset = page.offset()?;
+        let limit = page.limit(DEFAULT_IMPERSONATION_PAGE_SIZE, MAX_IMPERSONATION_PAGE_SIZE);
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!" FROM impersonations
+            WHERE $1::uuid IS NULL OR user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let impersonations = sqlx::query_as!(
+            Impersonation,
+            r#"
+            SELECT id, admin_id, user_id, admin_email, user_email, reason, expires_at, created_at
+            FROM impersonations
+            WHERE $1::uuid IS NULL OR user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Paginated::new(impersonations, total, offset))
+    }
+}
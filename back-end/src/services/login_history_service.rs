@@ -0,0 +1,132 @@
+use crate::auth::ClientInfo;
+use crate::error::AppError;
+use crate::models::login_event::LoginEvent;
+use crate::models::pagination::{PageParams, Paginated};
+use crate::models::User;
+use crate::services::email_service::{EmailService, LoginDetails};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Records sign-ins and warns users about ones from unfamiliar devices or countries
+#[derive(Clone)]
+pub struct LoginHistoryService {
+    pool: PgPool,
+    email_service: EmailService,
+}
+
+impl LoginHistoryService {
+    #[must_use]
+    pub fn new(pool: PgPool, email_service: EmailService) -> Self {
+        Self {
+            pool,
+            email_service,
+        }
+    }
+
+    /// Record a successful sign-in for `session_id`. The user's first sign-in is never
+    /// flagged; after that, an unseen user agent or country sends an alert email.
+    pub async fn record(
+        &self,
+        user: &User,
+        session_id: Uuid,
+        client: &ClientInfo,
+    ) -> Result<LoginEvent, AppError> {
+        let event = sqlx::query_as!(
+            LoginEvent,
+            r#"
+            WITH history AS (
+                SELECT
+                    EXISTS (SELECT 1 FROM login_events WHERE user_id = $1) AS has_logins,
+                    EXISTS (
+                        SELECT 1 FROM login_events
+                        WHERE user_id = $1 AND user_agent IS NOT DISTINCT FROM $3
+                    ) AS seen_device,
+                    EXISTS (
+                        SELECT 1 FROM login_events WHERE user_id = $1 AND country = $5
+                    ) AS seen_country
+            )
+            INSERT INTO login_events
+                (user_id, session_id, ip_address, user_agent, country, new_device, new_country)
+            SELECT $1, $2, $4, $3, $5,
+                   has_logins AND NOT seen_device,
+                   has_logins AND $5::VARCHAR IS NOT NULL AND NOT seen_country
+            FROM history
+            RETURNING id, ip_address, user_agent, country,
+                      new_device AS "new_device!", new_country AS "new_country!", created_at
+            "#,
+            user.id,
+            session_id,
+            client.user_agent,
+            client.ip_address,
+            client.country
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if event.new_device || event.new_country {
+            tracing::info!(
+                "Sign-in to {} from a new {}; sending alert",
+                user.id,
+                if event.new_device {
+                    "device"
+                } else {
+                    "country"
+                }
+            );
+            // SMTP is slow; the alert must not hold up the sign-in
+            let email_service = self.email_service.clone();
+            let (email, name) = (user.email.clone(), user.full_name.clone());
+            let login = LoginDetails {
+                time: event.created_at,
+                user_agent: event.user_agent.clone(),
+                ip_address: event.ip_address.clone(),
+                country: event.country.clone(),
+            };
+            tokio::spawn(async move {
+                if let Err(e) = email_service.send_login_alert(&email, &name, &login).await {
+                    tracing::error!("Failed to send login alert to {}: {:?}", email, e);
+                }
+            });
+        }
+
+        Ok(event)
+    }
+
+    /// The user's sign-ins, newest first
+    pub async fn list(
+        &self,
+        user_id: Uuid,
+        page: &PageParams,
+    ) -> Result<Paginated<LoginEvent>, AppError> {
+        let offset = page.offset()?;
+        let limit = page.limit(DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE);
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM login_events WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let events = sqlx::query_as!(
+            LoginEvent,
+            r#"
+            SELECT id, ip_address, user_agent, country, new_device, new_country, created_at
+            FROM login_events
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Paginated::new(events, total, offset))
+    }
+}
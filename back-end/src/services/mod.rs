@@ -1,17 +1,81 @@
+pub mod api_key_service;
 pub mod auth_service;
+pub mod captcha_service;
+pub mod city_goal_service;
+pub mod cleanup_event_service;
+pub mod data_export_service;
+pub mod email_outbox_service;
 pub mod email_service;
+pub mod email_webhook_service;
+pub mod event_service;
+pub mod feed_engagement_service;
 pub mod feed_service;
+pub mod fraud_service;
+pub mod geocode_queue_service;
+pub mod geocoding_service;
+pub mod home_area_service;
+pub mod idempotency_service;
 pub mod image_service;
+pub mod image_storage_service;
+pub mod impersonation_service;
+pub mod login_history_service;
+pub mod notification_service;
 pub mod oauth_service;
+pub mod oauth_state_store;
+pub mod open_data_service;
+pub mod report_admin_service;
+pub mod report_flag_service;
+pub mod report_import_service;
 pub mod report_service;
 pub mod s3_service;
+pub mod saved_search_service;
 pub mod scoring_service;
+pub mod stats_service;
+pub mod translation_service;
+pub mod two_factor_service;
+pub mod upload_scan_service;
+pub mod upload_service;
+pub mod verification_service;
+pub mod watched_area_service;
 
-pub use auth_service::AuthService;
+pub use api_key_service::ApiKeyService;
+pub use auth_service::{AuthService, LoginOutcome, OAuthLoginOutcome};
+pub use captcha_service::CaptchaService;
+pub use city_goal_service::CityGoalService;
+pub use cleanup_event_service::CleanupEventService;
+pub use data_export_service::DataExportService;
+pub use email_outbox_service::EmailOutboxService;
 pub use email_service::EmailService;
+pub use email_webhook_service::EmailWebhookService;
+pub use event_service::EventService;
+pub use feed_engagement_service::{
+    EngagementRecorder, EngagementSink, FeedEngagementService, PgEngagementSink,
+};
 pub use feed_service::FeedService;
+pub use fraud_service::FraudService;
+pub use geocode_queue_service::GeocodeQueueService;
+pub use geocoding_service::{Geocoder, GeocodingService};
+pub use home_area_service::HomeAreaService;
+pub use idempotency_service::IdempotencyService;
 pub use image_service::ImageService;
+pub use image_storage_service::ImageStorageService;
+pub use impersonation_service::ImpersonationService;
+pub use login_history_service::LoginHistoryService;
+pub use notification_service::NotificationService;
 pub use oauth_service::OAuthService;
+pub use oauth_state_store::{InMemoryOAuthStateStore, OAuthStateStore, PgOAuthStateStore};
+pub use open_data_service::OpenDataService;
+pub use report_admin_service::ReportAdminService;
+pub use report_flag_service::ReportFlagService;
+pub use report_import_service::ReportImportService;
 pub use report_service::ReportService;
-pub use s3_service::S3Service;
+pub use s3_service::{S3Service, StorageArea};
+pub use saved_search_service::SavedSearchService;
 pub use scoring_service::ScoringService;
+pub use stats_service::StatsService;
+pub use translation_service::{TranslationService, Translator};
+pub use two_factor_service::TwoFactorService;
+pub use upload_scan_service::{MalwareScanner, UploadScanService};
+pub use upload_service::UploadService;
+pub use verification_service::VerificationService;
+pub use watched_area_service::WatchedAreaService;
@@ -0,0 +1,213 @@
+use crate::error::AppError;
+use crate::models::notification::{Notification, NotificationKind, NotificationPollResponse};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+/// Maximum notifications returned by a single poll
+const POLL_BATCH_SIZE: i64 = 50;
+/// Buffered wake-ups before slow pollers start lagging (they re-check on lag)
+const BUS_CAPACITY: usize = 1024;
+
+#[derive(Clone)]
+pub struct NotificationService {
+    pool: PgPool,
+    /// In-process event bus: carries the id of each user who just got a notification
+    bus: broadcast::Sender<Uuid>,
+}
+
+impl NotificationService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        let (bus, _) = broadcast::channel(BUS_CAPACITY);
+        Self { pool, bus }
+    }
+
+    /// Store a notification and wake any pollers waiting for this user
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+        report_id: Option<Uuid>,
+    ) -> Result<Notification, AppError> {
+        let notification = sqlx::query_as!(
+            Notification,
+            r#"
+            INSERT INTO notifications (user_id, kind, title, body, report_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, kind, title, body, report_id, created_at
+            "#,
+            user_id,
+            kind.as_str(),
+            title,
+            body,
+            report_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        // No receivers just means nobody is polling right now
+        let _ = self.bus.send(user_id);
+
+        Ok(notification)
+    }
+
+    /// Best-effort variant of `create` for side effects of other actions
+    pub async fn notify(
+        &self,
+        user_id: Uuid,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+        report_id: Option<Uuid>,
+    ) {
+        if let Err(e) = self.create(user_id, kind, title, body, report_id).await {
+            tracing::warn!(
+                "Failed to create {} notification for {}: {:?}",
+                kind.as_str(),
+                user_id,
+                e
+            );
+        }
+    }
+
+    /// Best-effort fan-out of the same notification to many users in one insert
+    pub async fn notify_many(
+        &self,
+        user_ids: &[Uuid],
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+        report_id: Option<Uuid>,
+    ) {
+        if user_ids.is_empty() {
+            return;
+        }
+
+        let result = sqlx::query(
+            r"
+            INSERT INTO notifications (user_id, kind, title, body, report_id)
+            SELECT user_id, $2, $3, $4, $5 FROM UNNEST($1::uuid[]) AS user_id
+            ",
+        )
+        .bind(user_ids)
+        .bind(kind.as_str())
+        .bind(title)
+        .bind(body)
+        .bind(report_id)
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(_) => {
+                for user_id in user_ids {
+                    let _ = self.bus.send(*user_id);
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Failed to create {} notifications for {} users: {:?}",
+                kind.as_str(),
+                user_ids.len(),
+                e
+            ),
+        }
+    }
+
+    /// Return notifications newer than `since`, waiting up to `wait` for one to arrive.
+    /// Without `since` the most recent notifications are returned straight away.
+    pub async fn poll(
+        &self,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        wait: Duration,
+    ) -> Result<NotificationPollResponse, AppError> {
+        let Some(since) = since else {
+            let started = Utc::now();
+            let notifications = self.list_recent(user_id).await?;
+            return Ok(Self::response(notifications, started));
+        };
+
+        // Subscribe before querying so a notification created in between still wakes us
+        let mut rx = self.bus.subscribe();
+        let deadline = Instant::now() + wait;
+
+        loop {
+            let notifications = self.list_since(user_id, since).await?;
+            if !notifications.is_empty() {
+                return Ok(Self::response(notifications, since));
+            }
+
+            loop {
+                match tokio::time::timeout_at(deadline, rx.recv()).await {
+                    Err(_) | Ok(Err(RecvError::Closed)) => {
+                        return Ok(Self::response(Vec::new(), since));
+                    }
+                    Ok(Ok(id)) if id == user_id => break,
+                    Ok(Ok(_)) => {}
+                    // Our wake-up may have been dropped, so check the database again
+                    Ok(Err(RecvError::Lagged(_))) => break,
+                }
+            }
+        }
+    }
+
+    async fn list_since(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Notification>, AppError> {
+        let notifications = sqlx::query_as!(
+            Notification,
+            r#"
+            SELECT id, user_id, kind, title, body, report_id, created_at
+            FROM notifications
+            WHERE user_id = $1 AND created_at > $2
+            ORDER BY created_at ASC
+            LIMIT $3
+            "#,
+            user_id,
+            since,
+            POLL_BATCH_SIZE
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    async fn list_recent(&self, user_id: Uuid) -> Result<Vec<Notification>, AppError> {
+        let mut notifications = sqlx::query_as!(
+            Notification,
+            r#"
+            SELECT id, user_id, kind, title, body, report_id, created_at
+            FROM notifications
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            user_id,
+            POLL_BATCH_SIZE
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        notifications.reverse();
+        Ok(notifications)
+    }
+
+    fn response(
+        notifications: Vec<Notification>,
+        fallback: DateTime<Utc>,
+    ) -> NotificationPollResponse {
+        let next_since = notifications.last().map_or(fallback, |n| n.created_at);
+        NotificationPollResponse {
+            notifications,
+            next_since,
+        }
+    }
+}
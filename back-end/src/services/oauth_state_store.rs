@@ -0,0 +1,102 @@
+use crate::auth::hash_token;
+use crate::error::AppError;
+use axum::async_trait;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+/// How long a user has to complete the provider's consent screen
+pub const OAUTH_STATE_TTL_SECS: i64 = 600;
+
+/// Holds the nonce for each in-flight OAuth authorization, keyed by its CSRF state.
+/// Entries are single-use and expire after [`OAUTH_STATE_TTL_SECS`].
+#[async_trait]
+pub trait OAuthStateStore: Send + Sync {
+    async fn insert(&self, state: &str, nonce: &str) -> Result<(), AppError>;
+
+    /// Remove and return the nonce for a state, or `None` if unknown or expired
+    async fn take(&self, state: &str) -> Result<Option<String>, AppError>;
+}
+
+/// Postgres-backed store, shared by every instance of the API
+#[derive(Clone)]
+pub struct PgOAuthStateStore {
+    pool: PgPool,
+}
+
+impl PgOAuthStateStore {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OAuthStateStore for PgOAuthStateStore {
+    async fn insert(&self, state: &str, nonce: &str) -> Result<(), AppError> {
+        // Abandoned logins never reach the callback, so purge them as new ones start
+        sqlx::query!("DELETE FROM oauth_states WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        let expires_at = Utc::now() + Duration::seconds(OAUTH_STATE_TTL_SECS);
+        sqlx::query!(
+            "INSERT INTO oauth_states (state_hash, nonce, expires_at) VALUES ($1, $2, $3)",
+            hash_token(state),
+            nonce,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<String>, AppError> {
+        let nonce = sqlx::query_scalar!(
+            "DELETE FROM oauth_states WHERE state_hash = $1 AND expires_at > NOW() RETURNING nonce",
+            hash_token(state)
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(nonce)
+    }
+}
+
+/// Single-process store for tests and local development
+#[derive(Default)]
+pub struct InMemoryOAuthStateStore {
+    entries: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl InMemoryOAuthStateStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn ttl() -> std::time::Duration {
+        std::time::Duration::from_secs(OAUTH_STATE_TTL_SECS.unsigned_abs())
+    }
+}
+
+#[async_trait]
+impl OAuthStateStore for InMemoryOAuthStateStore {
+    async fn insert(&self, state: &str, nonce: &str) -> Result<(), AppError> {
+        let mut entries = self.entries.lock().await;
+        entries.retain(|_, (_, created)| created.elapsed() < Self::ttl());
+        entries.insert(state.to_string(), (nonce.to_string(), Instant::now()));
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<String>, AppError> {
+        let mut entries = self.entries.lock().await;
+        Ok(entries
+            .remove(state)
+            .filter(|(_, created)| created.elapsed() < Self::ttl())
+            .map(|(nonce, _)| nonce))
+    }
+}
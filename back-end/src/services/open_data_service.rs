@@ -0,0 +1,150 @@
+use crate::error::AppError;
+use crate::models::open_data::{OpenDataDailyAggregate, OpenDataQuery, OpenDataReport};
+use crate::models::report::{LitterCategory, ReportStatus};
+use chrono::{Duration, NaiveDate, Utc};
+use sqlx::PgPool;
+
+/// Grid cell size, in degrees, that report positions are snapped to (about 1km)
+const GRID_DEG: f64 = 0.01;
+
+/// Days covered when the request gives no `from`
+const DEFAULT_RANGE_DAYS: i64 = 30;
+
+/// Longest range one request may cover
+pub const MAX_OPEN_DATA_DAYS: i64 = 366;
+
+/// Most reports one response lists
+pub const MAX_OPEN_DATA_REPORTS: i64 = 10_000;
+
+/// Anonymized cleared reports for councils and researchers, served without an account
+#[derive(Clone)]
+pub struct OpenDataService {
+    pool: PgPool,
+}
+
+impl OpenDataService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The clear dates the query covers, both inclusive
+    pub fn range(query: &OpenDataQuery) -> Result<(NaiveDate, NaiveDate), AppError> {
+        let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = query
+            .from
+            .unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+
+        if from > to {
+            return Err(AppError::BadRequest(
+                "from must not be after to".to_string(),
+            ));
+        }
+        if (to - from).num_days() >= MAX_OPEN_DATA_DAYS {
+            return Err(AppError::BadRequest(format!(
+                "A request covers at most {MAX_OPEN_DATA_DAYS} days"
+            )));
+        }
+
+        Ok((from, to))
+    }
+
+    /// Reports cleared between `from` and `to`, newest first and at most `limit` of
+    /// them, with how many there are in all
+    pub async fn reports(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        category: Option<LitterCategory>,
+        limit: i64,
+    ) -> Result<(Vec<OpenDataReport>, i64), AppError> {
+        let total: i64 = sqlx::query_scalar(
+            r"
+            SELECT COUNT(*) FROM litter_reports
+            WHERE status = ANY($1)
+              AND hidden_at IS NULL
+              AND (cleared_at AT TIME ZONE 'UTC')::date BETWEEN $2 AND $3
+              AND ($4::litter_category IS NULL OR category = $4)
+            ",
+        )
+        .bind(Self::published_statuses())
+        .bind(from)
+        .bind(to)
+        .bind(category)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let reports = sqlx::query_as::<_, OpenDataReport>(
+            r"
+            SELECT status, category, severity,
+                   round(ST_Y(ST_SnapToGrid(location, $5))::numeric, 2)::double precision
+                       AS latitude,
+                   round(ST_X(ST_SnapToGrid(location, $5))::numeric, 2)::double precision
+                       AS longitude,
+                   address_city AS city,
+                   address_country AS country,
+                   (created_at AT TIME ZONE 'UTC')::date AS reported_on,
+                   (cleared_at AT TIME ZONE 'UTC')::date AS cleared_on,
+                   FLOOR(EXTRACT(EPOCH FROM (cleared_at - created_at)) / 3600)::int
+                       AS hours_to_clear
+            FROM litter_reports
+            WHERE status = ANY($1)
+              AND hidden_at IS NULL
+              AND (cleared_at AT TIME ZONE 'UTC')::date BETWEEN $2 AND $3
+              AND ($4::litter_category IS NULL OR category = $4)
+            ORDER BY cleared_at DESC, id
+            LIMIT $6
+            ",
+        )
+        .bind(Self::published_statuses())
+        .bind(from)
+        .bind(to)
+        .bind(category)
+        .bind(GRID_DEG)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((reports, total))
+    }
+
+    /// Clears per day between `from` and `to`, oldest first. Days without clears are
+    /// left out.
+    pub async fn daily(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        category: Option<LitterCategory>,
+    ) -> Result<Vec<OpenDataDailyAggregate>, AppError> {
+        let daily = sqlx::query_as::<_, OpenDataDailyAggregate>(
+            r"
+            SELECT (cleared_at AT TIME ZONE 'UTC')::date AS date,
+                   COUNT(*) AS cleared_reports,
+                   COUNT(*) FILTER (WHERE status = 'verified') AS verified_reports,
+                   percentile_cont(0.5) WITHIN GROUP (
+                       ORDER BY EXTRACT(EPOCH FROM (cleared_at - created_at)) / 3600.0
+                   ) AS median_hours_to_clear
+            FROM litter_reports
+            WHERE status = ANY($1)
+              AND hidden_at IS NULL
+              AND (cleared_at AT TIME ZONE 'UTC')::date BETWEEN $2 AND $3
+              AND ($4::litter_category IS NULL OR category = $4)
+            GROUP BY 1
+            ORDER BY 1
+            ",
+        )
+        .bind(Self::published_statuses())
+        .bind(from)
+        .bind(to)
+        .bind(category)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(daily)
+    }
+
+    /// Disputed clears are left out until settled
+    fn published_statuses() -> Vec<ReportStatus> {
+        vec![ReportStatus::Cleared, ReportStatus::Verified]
+    }
+}
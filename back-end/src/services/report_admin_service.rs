@@ -0,0 +1,449 @@
+use crate::error::AppError;
+use crate::models::dry_run::ChangeSummary;
+use crate::models::report::{LitterCategory, LitterReport, LitterSeverity, ReportStatus};
+use crate::models::report_claim::ClaimOutcome;
+use crate::models::report_lifecycle::ReportTransition;
+use crate::services::event_service::EventService;
+use crate::services::report_service::{NewReportEvent, ReportService};
+use crate::services::scoring_service::ScoringService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Who holds a report and where it stands
+#[derive(Debug, Clone, PartialEq)]
+struct ReportAssignment {
+    status: ReportStatus,
+    claimed_by: Option<Uuid>,
+    cleared_by: Option<Uuid>,
+}
+
+/// Who made a correction and why, for the event log
+struct Correction<'a> {
+    admin_id: Uuid,
+    event_type: &'a str,
+    reason: &'a str,
+}
+
+impl ReportAssignment {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "status": self.status,
+            "claimed_by": self.claimed_by,
+            "cleared_by": self.cleared_by,
+        })
+    }
+}
+
+/// Admin corrections to a report's claimer, clearer and status, e.g. when a
+/// coordinator hands a claim to another volunteer or support fixes a bad state
+#[derive(Clone)]
+pub struct ReportAdminService {
+    pool: PgPool,
+    scoring_service: ScoringService,
+    event_service: EventService,
+}
+
+impl ReportAdminService {
+    #[must_use]
+    pub fn new(pool: PgPool, scoring_service: ScoringService, event_service: EventService) -> Self {
+        Self {
+            pool,
+            scoring_service,
+            event_service,
+        }
+    }
+
+    /// Hand a claim and/or the credit for a clear to another user, keeping the status
+    pub async fn reassign(
+        &self,
+        report_id: Uuid,
+        admin_id: Uuid,
+        claimed_by: Option<Uuid>,
+        cleared_by: Option<Uuid>,
+        reason: &str,
+    ) -> Result<LitterReport, AppError> {
+        if claimed_by.is_none() && cleared_by.is_none() {
+            return Err(AppError::BadRequest(
+                "Provide claimed_by and/or cleared_by".to_string(),
+            ));
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let current = Self::lock_assignment(&mut tx, report_id).await?;
+
+        if claimed_by.is_some() && current.status == ReportStatus::Pending {
+            return Err(AppError::BadRequest(
+                "Report has not been claimed".to_string(),
+            ));
+        }
+        if cleared_by.is_some()
+            && !matches!(
+                current.status,
+                ReportStatus::Cleared | ReportStatus::Verified | ReportStatus::Disputed
+            )
+        {
+            return Err(AppError::BadRequest(
+                "Report has not been cleared".to_string(),
+            ));
+        }
+
+        let target = ReportAssignment {
+            status: current.status.clone(),
+            claimed_by: claimed_by.or(current.claimed_by),
+            cleared_by: cleared_by.or(current.cleared_by),
+        };
+
+        let correction = Correction {
+            admin_id,
+            event_type: "report.reassigned",
+            reason,
+        };
+        self.apply(tx, report_id, current, target, correction).await
+    }
+
+    /// Force a report into any open status, or archive it. Claimer and clearer are kept
+    /// where the new status needs them (or taken from the request) and cleared where it
+    /// does not.
+    pub async fn override_status(
+        &self,
+        report_id: Uuid,
+        admin_id: Uuid,
+        status: ReportStatus,
+        claimed_by: Option<Uuid>,
+        cleared_by: Option<Uuid>,
+        reason: &str,
+    ) -> Result<LitterReport, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let current = Self::lock_assignment(&mut tx, report_id).await?;
+        ReportTransition::admin_override(&status).check(&current.status, &status)?;
+
+        let target = match status {
+            // Only archiving gets this far; the report keeps whoever worked on it
+            ReportStatus::Archived | ReportStatus::Cancelled | ReportStatus::Expired => {
+                ReportAssignment {
+                    status,
+                    ..current.clone()
+                }
+            }
+            ReportStatus::Pending => ReportAssignment {
+                status,
+                claimed_by: None,
+                cleared_by: None,
+            },
+            ReportStatus::Claimed => ReportAssignment {
+                status,
+                claimed_by: Some(claimed_by.or(current.claimed_by).ok_or_else(|| {
+                    AppError::BadRequest("A claimed report needs claimed_by".to_string())
+                })?),
+                cleared_by: None,
+            },
+            ReportStatus::Cleared | ReportStatus::Verified | ReportStatus::Disputed => {
+                let cleared_by = cleared_by.or(current.cleared_by).ok_or_else(|| {
+                    AppError::BadRequest("A cleared report needs cleared_by".to_string())
+                })?;
+                ReportAssignment {
+                    status,
+                    claimed_by: Some(claimed_by.or(current.claimed_by).unwrap_or(cleared_by)),
+                    cleared_by: Some(cleared_by),
+                }
+            }
+        };
+
+        let correction = Correction {
+            admin_id,
+            event_type: "report.status_overridden",
+            reason,
+        };
+        self.apply(tx, report_id, current, target, correction).await
+    }
+
+    /// Delete one report, e.g. spam or inappropriate content
+    pub async fn delete_report(
+        &self,
+        report_id: Uuid,
+        admin_id: Uuid,
+        dry_run: bool,
+    ) -> Result<ChangeSummary, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM litter_reports WHERE id = $1) AS "exists!""#,
+            report_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if !exists {
+            return Err(AppError::NotFound("Report not found".to_string()));
+        }
+
+        Self::delete_reports(&mut tx, &[report_id], admin_id, None, dry_run)
+            .await?
+            .message("Report deleted successfully", "the report would be deleted")
+            .finish(tx)
+            .await
+    }
+
+    /// Delete every report a user has made, e.g. when purging a spammer
+    pub async fn purge_user_reports(
+        &self,
+        user_id: Uuid,
+        admin_id: Uuid,
+        dry_run: bool,
+    ) -> Result<ChangeSummary, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let report_ids = sqlx::query_scalar!(
+            "SELECT id FROM litter_reports WHERE reporter_id = $1 ORDER BY created_at FOR UPDATE",
+            user_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let deleted = report_ids.len();
+        let reason = Some("Reporter's reports purged");
+        Self::delete_reports(&mut tx, &report_ids, admin_id, reason, dry_run)
+            .await?
+            .message(
+                &format!("{deleted} reports deleted"),
+                &format!("{deleted} reports would be deleted"),
+            )
+            .finish(tx)
+            .await
+    }
+
+    /// Delete reports, counting the verifications and notifications that go with them.
+    /// Each report's history records the deletion and survives it.
+    async fn delete_reports(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_ids: &[Uuid],
+        admin_id: Uuid,
+        reason: Option<&str>,
+        dry_run: bool,
+    ) -> Result<ChangeSummary, AppError> {
+        let verifications = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM report_verifications WHERE report_id = ANY($1)"#,
+            report_ids
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        let notifications = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM notifications WHERE report_id = ANY($1)"#,
+            report_ids
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        ReportService::record_deletions(tx, report_ids, Some(admin_id), reason).await?;
+
+        let deleted = sqlx::query_scalar!(
+            "DELETE FROM litter_reports WHERE id = ANY($1) RETURNING id",
+            report_ids
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(ChangeSummary::new(dry_run)
+            .count("litter_reports", deleted.len() as i64)
+            .count("report_verifications", verifications)
+            .count("notifications", notifications)
+            .samples(deleted))
+    }
+
+    async fn lock_assignment(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+    ) -> Result<ReportAssignment, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT status as "status: ReportStatus", claimed_by, cleared_by
+            FROM litter_reports
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+            report_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+
+        Ok(ReportAssignment {
+            status: row.status,
+            claimed_by: row.claimed_by,
+            cleared_by: row.cleared_by,
+        })
+    }
+
+    /// Close the active claim row and open one for the new claimer, so a correction shows
+    /// up in the report's timeline
+    async fn sync_claim_history(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+        current: &ReportAssignment,
+        target: &ReportAssignment,
+    ) -> Result<(), AppError> {
+        let done = matches!(
+            target.status,
+            ReportStatus::Cleared | ReportStatus::Verified | ReportStatus::Disputed
+        );
+        let ending = if current.claimed_by != target.claimed_by {
+            Some(if target.claimed_by.is_some() {
+                ClaimOutcome::Reassigned
+            } else {
+                ClaimOutcome::Released
+            })
+        } else if done {
+            Some(ClaimOutcome::Cleared)
+        } else {
+            None
+        };
+
+        if let Some(outcome) = ending {
+            sqlx::query!(
+                r#"
+                UPDATE report_claims SET outcome = $2, ended_at = NOW()
+                WHERE report_id = $1 AND outcome = 'active'
+                "#,
+                report_id,
+                outcome as ClaimOutcome
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        if let Some(user_id) = target
+            .claimed_by
+            .filter(|_| current.claimed_by != target.claimed_by)
+        {
+            let (outcome, ended_at) = if done {
+                (ClaimOutcome::Cleared, Some(chrono::Utc::now()))
+            } else {
+                (ClaimOutcome::Active, None)
+            };
+            sqlx::query!(
+                r#"
+                INSERT INTO report_claims (report_id, user_id, outcome, ended_at)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                report_id,
+                user_id,
+                outcome as ClaimOutcome,
+                ended_at
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply(
+        &self,
+        mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+        current: ReportAssignment,
+        target: ReportAssignment,
+        correction: Correction<'_>,
+    ) -> Result<LitterReport, AppError> {
+        for user_id in [target.claimed_by, target.cleared_by].into_iter().flatten() {
+            let exists = sqlx::query_scalar!(
+                r#"SELECT EXISTS(SELECT 1 FROM users WHERE id = $1) AS "exists!""#,
+                user_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            if !exists {
+                return Err(AppError::NotFound(format!("User {user_id} not found")));
+            }
+        }
+
+        // A run groups one user's claims, so a report handed to someone else leaves it
+        let report = sqlx::query_as!(
+            LitterReport,
+            r#"
+            UPDATE litter_reports
+            SET status = $2,
+                claimed_by = $3,
+                claimed_at = CASE WHEN $3::uuid IS NULL THEN NULL
+                                  WHEN claimed_by IS DISTINCT FROM $3 THEN NOW()
+                                  ELSE claimed_at END,
+                cleared_by = $4,
+                cleared_at = CASE WHEN $4::uuid IS NULL THEN NULL
+                                  ELSE COALESCE(cleared_at, NOW()) END,
+                cleanup_run_id = CASE WHEN claimed_by IS DISTINCT FROM $3 THEN NULL
+                                      ELSE cleanup_run_id END
+            WHERE id = $1
+            RETURNING
+                id, reporter_id,
+                ST_Y(location)::double precision as "latitude!",
+                ST_X(location)::double precision as "longitude!",
+                description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
+                photo_before, status as "status: ReportStatus",
+                claimed_by, claimed_at, cleared_by, cleared_at,
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
+            "#,
+            report_id,
+            target.status.clone() as ReportStatus,
+            target.claimed_by,
+            target.cleared_by
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::sync_claim_history(&mut tx, report_id, &current, &target).await?;
+
+        if current.status != target.status {
+            let event = NewReportEvent {
+                actor_id: Some(correction.admin_id),
+                reason: Some(correction.reason),
+                metadata: serde_json::json!({
+                    "before": current.to_json(),
+                    "after": target.to_json(),
+                }),
+                ..NewReportEvent::status_change(
+                    ReportTransition::admin_override(&target.status),
+                    &current.status,
+                    &target.status,
+                )
+            };
+            ReportService::record_event(&mut tx, report_id, event).await?;
+        }
+
+        if current.cleared_by != target.cleared_by {
+            self.scoring_service
+                .transfer_clear_points(&mut tx, report_id, current.cleared_by, target.cleared_by)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        let payload = serde_json::json!({
+            "admin_id": correction.admin_id,
+            "reason": correction.reason,
+            "before": current.to_json(),
+            "after": target.to_json(),
+        });
+        self.event_service
+            .record(
+                correction.event_type,
+                Some("report"),
+                Some(&report_id.to_string()),
+                &payload.to_string(),
+            )
+            .await?;
+
+        tracing::info!(
+            "Admin {} applied {} to report {}: {}",
+            correction.admin_id,
+            correction.event_type,
+            report_id,
+            correction.reason
+        );
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::config::ModerationConfig;
+use crate::error::AppError;
+use crate::models::report::{LitterReport, ReportStatus};
+use crate::models::report_flag::{
+    FlagReportRequest, FlagReportResponse, FlagReviewDecision, FlaggedReportView, ReportFlag,
+    ReviewReportFlagsRequest,
+};
+use crate::services::report_admin_service::ReportAdminService;
+use crate::services::report_service::ReportService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// User flags on reports and the moderation queue they feed. Enough open flags hide a
+/// report from the map and from claiming until a moderator dismisses them or archives it.
+#[derive(Clone)]
+pub struct ReportFlagService {
+    pool: PgPool,
+    report_service: ReportService,
+    report_admin_service: ReportAdminService,
+    config: ModerationConfig,
+}
+
+impl ReportFlagService {
+    #[must_use]
+    pub fn new(
+        pool: PgPool,
+        report_service: ReportService,
+        report_admin_service: ReportAdminService,
+        config: ModerationConfig,
+    ) -> Self {
+        Self {
+            pool,
+            report_service,
+            report_admin_service,
+            config,
+        }
+    }
+
+    /// Flag a report, hiding it once enough users have
+    pub async fn flag_report(
+        &self,
+        report_id: Uuid,
+        user_id: Uuid,
+        request: FlagReportRequest,
+    ) -> Result<FlagReportResponse, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let (reporter_id, status): (Option<Uuid>, ReportStatus) = sqlx::query_as(
+            "SELECT reporter_id, status FROM litter_reports WHERE id = $1 FOR UPDATE",
+        )
+        .bind(report_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+
+        if reporter_id == Some(user_id) {
+            return Err(AppError::BadRequest(
+                "Cannot flag your own report; edit or cancel it instead".to_string(),
+            ));
+        }
+        if matches!(status, ReportStatus::Cancelled | ReportStatus::Archived) {
+            return Err(AppError::BadRequest(
+                "This report is no longer on the map".to_string(),
+            ));
+        }
+
+        let comment = request
+            .comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+        let flag = sqlx::query_as::<_, ReportFlag>(
+            r"
+            INSERT INTO report_flags (report_id, flagger_id, reason, comment)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (report_id, flagger_id) WHERE resolved_at IS NULL DO NOTHING
+            RETURNING id, report_id, flagger_id, reason, comment, created_at,
+                      resolved_at, resolved_by
+            ",
+        )
+        .bind(report_id)
+        .bind(user_id)
+        .bind(request.reason)
+        .bind(comment)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("You have already flagged this report".to_string()))?;
+
+        let open_flags: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM report_flags WHERE report_id = $1 AND resolved_at IS NULL",
+        )
+        .bind(report_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let report_hidden = if open_flags >= self.config.report_flags_to_hide {
+            sqlx::query(
+                "UPDATE litter_reports SET hidden_at = COALESCE(hidden_at, NOW()) WHERE id = $1",
+            )
+            .bind(report_id)
+            .execute(&mut *tx)
+            .await?;
+            true
+        } else {
+            false
+        };
+
+        tx.commit().await?;
+
+        if report_hidden {
+            tracing::info!("Report {report_id} hidden after {open_flags} flags");
+        }
+
+        Ok(FlagReportResponse {
+            flag,
+            report_hidden,
+        })
+    }
+
+    /// Reports with open flags, hidden ones first, then most recently flagged, with the
+    /// total count
+    pub async fn list_flagged(
+        &self,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<FlaggedReportView>, i64), AppError> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(DISTINCT report_id) FROM report_flags WHERE resolved_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let reports = sqlx::query_as::<_, FlaggedReportView>(
+            r"
+            SELECT
+                lr.id AS report_id, lr.status, lr.description, lr.photo_before, lr.hidden_at,
+                COUNT(f.id) AS open_flags,
+                array_agg(DISTINCT f.reason) AS reasons,
+                MIN(f.created_at) AS first_flagged_at,
+                MAX(f.created_at) AS last_flagged_at
+            FROM report_flags f
+            JOIN litter_reports lr ON lr.id = f.report_id
+            WHERE f.resolved_at IS NULL
+            GROUP BY lr.id
+            ORDER BY lr.hidden_at IS NULL, MAX(f.created_at) DESC, lr.id
+            LIMIT $1 OFFSET $2
+            ",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((reports, total))
+    }
+
+    /// Every flag on a report, open or resolved, oldest first
+    pub async fn get_flags(&self, report_id: Uuid) -> Result<Vec<ReportFlag>, AppError> {
+        let flags = sqlx::query_as::<_, ReportFlag>(
+            r"
+            SELECT id, report_id, flagger_id, reason, comment, created_at,
+                   resolved_at, resolved_by
+            FROM report_flags
+            WHERE report_id = $1
+            ORDER BY created_at, id
+            ",
+        )
+        .bind(report_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flags)
+    }
+
+    /// Settle a report's open flags: put it back on the map, or archive it
+    pub async fn review(
+        &self,
+        report_id: Uuid,
+        moderator_id: Uuid,
+        request: &ReviewReportFlagsRequest,
+    ) -> Result<LitterReport, AppError> {
+        let open_flags: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM report_flags WHERE report_id = $1 AND resolved_at IS NULL",
+        )
+        .bind(report_id)
+        .fetch_one(&self.pool)
+        .await?;
+        if open_flags == 0 {
+            return Err(AppError::NotFound(
+                "Report not found or has no open flags".to_string(),
+            ));
+        }
+
+        // Archive first, so the flags stay open if the report cannot be archived
+        if request.decision == FlagReviewDecision::Archive {
+            self.report_admin_service
+                .override_status(
+                    report_id,
+                    moderator_id,
+                    ReportStatus::Archived,
+                    None,
+                    None,
+                    &request.note,
+                )
+                .await?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r"
+            UPDATE report_flags SET resolved_at = NOW(), resolved_by = $2
+            WHERE report_id = $1 AND resolved_at IS NULL
+            ",
+        )
+        .bind(report_id)
+        .bind(moderator_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE litter_reports SET hidden_at = NULL WHERE id = $1")
+            .bind(report_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        tracing::info!(
+            "{} reviewed {} flags on report {}: {:?}",
+            moderator_id,
+            open_flags,
+            report_id,
+            request.decision
+        );
+
+        self.report_service.get_report_by_id(report_id).await
+    }
+}
@@ -0,0 +1,368 @@
+use crate::error::AppError;
+use crate::models::coordinates::Coordinates;
+use crate::models::report_import::{
+    ReportImport, ReportImportResponse, ReportImportResult, ReportImportRow, MAX_IMPORT_ROWS,
+};
+use crate::services::image_service::ImageService;
+use crate::services::report_service::ReportService;
+use sqlx::types::Json;
+use sqlx::PgPool;
+use std::net::IpAddr;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// An open report this close to an imported one makes the import a duplicate
+const DUPLICATE_RADIUS_METERS: f64 = 25.0;
+
+/// How long a single photo download may take
+const PHOTO_DOWNLOAD_TIMEOUT_SECS: u64 = 20;
+
+/// Longest source label kept with an import
+const MAX_SOURCE_CHARS: usize = 255;
+
+const IMPORT_COLUMNS: &str = r"
+    id, created_by, source, status, total_rows, processed_rows,
+    imported_count, duplicate_count, failed_count, results, error,
+    created_at, completed_at
+";
+
+/// Imports batches of reports from other litter databases. Rows are processed one by
+/// one in the background: each is validated, skipped if an open report already sits
+/// within 25m, and otherwise created without a reporter, with its photos downloaded.
+/// Imported reports don't notify watchers, so a migration doesn't flood anyone.
+#[derive(Clone)]
+pub struct ReportImportService {
+    pool: PgPool,
+    report_service: ReportService,
+    image_service: ImageService,
+    client: reqwest::Client,
+    max_photo_bytes: usize,
+}
+
+impl ReportImportService {
+    #[must_use]
+    pub fn new(
+        pool: PgPool,
+        report_service: ReportService,
+        image_service: ImageService,
+        max_photo_size_mb: usize,
+    ) -> Self {
+        // Redirects could lead to hosts the URL check never saw
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(PHOTO_DOWNLOAD_TIMEOUT_SECS))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            pool,
+            report_service,
+            image_service,
+            client,
+            max_photo_bytes: max_photo_size_mb * 1024 * 1024,
+        }
+    }
+
+    /// Record the batch and start processing it. Rows are checked as they are
+    /// processed, so one bad row doesn't hold up the rest.
+    pub async fn start(
+        &self,
+        admin_id: Uuid,
+        source: Option<String>,
+        rows: Vec<ReportImportRow>,
+    ) -> Result<ReportImportResponse, AppError> {
+        if rows.is_empty() {
+            return Err(AppError::BadRequest(
+                "The import has no reports".to_string(),
+            ));
+        }
+        if rows.len() > MAX_IMPORT_ROWS {
+            return Err(AppError::BadRequest(format!(
+                "An import may hold at most {MAX_IMPORT_ROWS} reports"
+            )));
+        }
+
+        let source = source
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        if source
+            .as_ref()
+            .is_some_and(|s| s.chars().count() > MAX_SOURCE_CHARS)
+        {
+            return Err(AppError::BadRequest(format!(
+                "source is longer than {MAX_SOURCE_CHARS} characters"
+            )));
+        }
+
+        let import = sqlx::query_as::<_, ReportImport>(&format!(
+            "INSERT INTO report_imports (created_by, source, total_rows)
+             VALUES ($1, $2, $3)
+             RETURNING {IMPORT_COLUMNS}"
+        ))
+        .bind(admin_id)
+        .bind(&source)
+        .bind(i32::try_from(rows.len()).unwrap_or(i32::MAX))
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::info!(
+            "Admin {} started report import {} with {} rows",
+            admin_id,
+            import.id,
+            rows.len()
+        );
+
+        let service = self.clone();
+        let import_id = import.id;
+        tokio::spawn(async move { service.process(import_id, rows).await });
+
+        Ok(import.into())
+    }
+
+    /// An import's progress and the outcome of each processed row
+    pub async fn get(&self, import_id: Uuid) -> Result<ReportImportResponse, AppError> {
+        let import = sqlx::query_as::<_, ReportImport>(&format!(
+            "SELECT {IMPORT_COLUMNS} FROM report_imports WHERE id = $1"
+        ))
+        .bind(import_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Import not found".to_string()))?;
+
+        Ok(import.into())
+    }
+
+    /// Work through the rows in order, saving progress after each one
+    pub async fn process(&self, import_id: Uuid, rows: Vec<ReportImportRow>) {
+        let result = async {
+            sqlx::query("UPDATE report_imports SET status = 'running' WHERE id = $1")
+                .bind(import_id)
+                .execute(&self.pool)
+                .await?;
+
+            for (index, row) in rows.into_iter().enumerate() {
+                let result = self.import_row(import_id, index, row).await;
+                self.record_result(import_id, result).await?;
+            }
+
+            sqlx::query(
+                "UPDATE report_imports SET status = 'completed', completed_at = NOW()
+                 WHERE id = $1",
+            )
+            .bind(import_id)
+            .execute(&self.pool)
+            .await?;
+
+            Ok::<_, AppError>(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("Report import {} failed: {:?}", import_id, e);
+            if let Err(e) = sqlx::query(
+                "UPDATE report_imports
+                 SET status = 'failed', error = $2, completed_at = NOW()
+                 WHERE id = $1",
+            )
+            .bind(import_id)
+            .bind("Import stopped after an internal error")
+            .execute(&self.pool)
+            .await
+            {
+                tracing::error!("Failed to mark report import {} failed: {:?}", import_id, e);
+            }
+        }
+    }
+
+    async fn import_row(
+        &self,
+        import_id: Uuid,
+        index: usize,
+        row: ReportImportRow,
+    ) -> ReportImportResult {
+        let external_id = row.external_id.clone();
+
+        let outcome = async {
+            let row = row.validate()?;
+
+            if let Some(existing) = self.find_duplicate(row.location).await.map_err(row_error)? {
+                return Ok(("duplicate", existing));
+            }
+
+            let mut photos = Vec::with_capacity(row.photo_urls.len());
+            for url in &row.photo_urls {
+                let bytes = self.download_photo(url).await?;
+                let photo = self
+                    .image_service
+                    .process_image_bytes(bytes)
+                    .await
+                    .map_err(|e| format!("photo {url}: {}", row_error(e)))?;
+                photos.push(photo);
+            }
+
+            let report = self
+                .report_service
+                .create_imported_report(import_id, row, photos)
+                .await
+                .map_err(row_error)?;
+
+            Ok::<_, String>(("imported", report.id))
+        }
+        .await;
+
+        match outcome {
+            Ok((outcome, report_id)) => ReportImportResult {
+                row: index,
+                external_id,
+                outcome: outcome.to_string(),
+                report_id: Some(report_id),
+                error: None,
+            },
+            Err(error) => ReportImportResult {
+                row: index,
+                external_id,
+                outcome: "failed".to_string(),
+                report_id: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    /// The nearest pending or claimed report within `DUPLICATE_RADIUS_METERS`
+    async fn find_duplicate(&self, location: Coordinates) -> Result<Option<Uuid>, AppError> {
+        let existing = sqlx::query_scalar(
+            r"
+            SELECT id
+            FROM litter_reports
+            WHERE status IN ('pending', 'claimed')
+              AND ST_DWithin(
+                  location::geography,
+                  ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography,
+                  $3
+              )
+            ORDER BY location::geography <-> ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography
+            LIMIT 1
+            ",
+        )
+        .bind(location.latitude())
+        .bind(location.longitude())
+        .bind(DUPLICATE_RADIUS_METERS)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(existing)
+    }
+
+    /// Fetch a photo, refusing hosts on private networks and anything too large
+    async fn download_photo(&self, url: &str) -> Result<Vec<u8>, String> {
+        let parsed = reqwest::Url::parse(url).map_err(|_| format!("photo URL {url} is invalid"))?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| format!("photo URL {url} has no host"))?;
+        let port = parsed.port_or_known_default().unwrap_or(443);
+
+        let addresses = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| format!("photo host {host} could not be resolved"))?
+            .collect::<Vec<_>>();
+        if addresses.is_empty() || addresses.iter().any(|a| !is_public(a.ip())) {
+            return Err(format!("photo host {host} is not a public address"));
+        }
+
+        let too_large = || format!("photo {url} is larger than the upload limit");
+        let mut response = self
+            .client
+            .get(parsed.clone())
+            .send()
+            .await
+            .map_err(|_| format!("photo {url} could not be downloaded"))?;
+        if !response.status().is_success() {
+            return Err(format!("photo {url} returned {}", response.status()));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_photo_bytes as u64)
+        {
+            return Err(too_large());
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|_| format!("photo {url} could not be downloaded"))?
+        {
+            if bytes.len() + chunk.len() > self.max_photo_bytes {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(bytes)
+    }
+
+    async fn record_result(
+        &self,
+        import_id: Uuid,
+        result: ReportImportResult,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r"
+            UPDATE report_imports
+            SET processed_rows = processed_rows + 1,
+                imported_count = imported_count + ($2 = 'imported')::int,
+                duplicate_count = duplicate_count + ($2 = 'duplicate')::int,
+                failed_count = failed_count + ($2 = 'failed')::int,
+                results = results || $3
+            WHERE id = $1
+            ",
+        )
+        .bind(import_id)
+        .bind(&result.outcome)
+        .bind(Json(vec![&result]))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// What to tell the uploader about a row that failed. Only request errors are
+/// passed on; anything else is logged.
+fn row_error(error: AppError) -> String {
+    match error {
+        AppError::BadRequest(message) => message,
+        other => {
+            tracing::warn!("Failed to import report row: {:?}", other);
+            "the report could not be saved".to_string()
+        }
+    }
+}
+
+/// Whether an address is reachable on the public internet
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    // Unique local, fc00::/7, and link-local, fe80::/10
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
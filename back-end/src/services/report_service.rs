@@ -1,135 +1,512 @@
+use crate::auth::tokens::{generate_token, hash_token};
+use crate::config::LocationCheckConfig;
 use crate::error::AppError;
-use crate::models::report::{CreateReportRequest, LitterReport, ReportStatus};
-use crate::services::image_service::ImageService;
-use crate::services::s3_service::S3Service;
-use chrono::Utc;
-use serde::Deserialize;
-use sqlx::PgPool;
+use crate::models::cleanup_run::CleanupRunResponse;
+use crate::models::coordinates::Coordinates;
+use crate::models::image::ImageMetadata;
+use crate::models::pagination::KeysetCursor;
+use crate::models::report::{
+    AdminReportView, ClearReportRequest, CreateAnonymousReportRequest, CreateReportRequest,
+    LitterCategory, LitterReport, LitterSeverity, NearbyReportsQuery, ReportCluster, ReportFilter,
+    ReportSort, ReportStatus, ReportsInBoundsQuery, VerificationQueueQuery,
+};
+use crate::models::report_claim::{
+    ClaimOutcome, ReportClaim, ReportTimelineResponse, TimelineEvent, TimelineEventKind,
+};
+use crate::models::report_edit::{ReportEdit, UpdateReportRequest};
+use crate::models::report_import::ValidImportRow;
+use crate::models::report_lifecycle::{
+    ReportEvent, ReportEventKind, ReportHistoryResponse, ReportTransition,
+};
+use crate::models::report_photo::{AddReportPhotoRequest, PhotoKind, ReportPhotos};
+use crate::services::geocode_queue_service::GeocodeQueueService;
+use crate::services::geocoding_service::GeocodingService;
+use crate::services::image_service::{ImageService, PhotoExif, ProcessedImage};
+use crate::services::image_storage_service::ImageStorageService;
+use crate::services::scoring_service::ScoringService;
+use crate::services::upload_service::UploadService;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize)]
-struct NominatimAddress {
-    road: Option<String>,
-    amenity: Option<String>,
-    shop: Option<String>,
-    building: Option<String>,
-    house_number: Option<String>,
-    suburb: Option<String>,
-    city: Option<String>,
-    town: Option<String>,
-    village: Option<String>,
+/// Most reports a single batch claim may include
+pub const MAX_BATCH_CLAIM: usize = 20;
+
+/// Verification queue radius when the caller gives none; wider than the map's default
+const VERIFICATION_QUEUE_RADIUS_KM: f64 = 50.0;
+
+/// How long a guest has to claim an anonymous report
+const ANONYMOUS_CLAIM_TTL_DAYS: i64 = 30;
+
+/// Released or expired claims on one report after which the user may not claim it again
+const MAX_ABANDONS_PER_REPORT: i64 = 2;
+/// Released or expired claims within `ABANDON_WINDOW_DAYS` after which new claims wait
+const MAX_RECENT_ABANDONS: i64 = 5;
+const ABANDON_WINDOW_DAYS: i32 = 7;
+
+/// Why a report hidden by flags cannot be claimed
+const HIDDEN_REPORT_MESSAGE: &str = "Report is hidden while moderators review it";
+
+/// Most before photos, and most after photos, one report may have
+pub const MAX_PHOTOS_PER_KIND: usize = 5;
+
+/// A photo taken within this of the reported location counts as taken there
+const PHOTO_LOCATION_SLACK_KM: f64 = 0.25;
+/// Location confidence of a report whose photo has a capture time but no position
+const TIME_ONLY_CONFIDENCE: f64 = 0.75;
+/// Clock and time zone error allowed on a capture time in the future
+const PHOTO_FUTURE_SLACK_HOURS: i64 = 14;
+/// Reports below this location confidence are listed after the rest
+pub const LOW_LOCATION_CONFIDENCE: f64 = 0.5;
+
+/// Score from 0 to 1 how well a photo's EXIF data agrees with a report made at
+/// `location` and `now`, or `None` when the photo carries no position or time. A photo
+/// taken `reject_distance_km` or more away is refused. Within that the score falls
+/// with distance, and a photo older than `max_photo_age_hours` halves it.
+pub fn location_confidence(
+    exif: &PhotoExif,
+    location: Coordinates,
+    now: DateTime<Utc>,
+    config: &LocationCheckConfig,
+) -> Result<Option<f64>, AppError> {
+    let position_score = match exif.location {
+        Some(taken_at) => {
+            let distance = taken_at.distance_km(&location);
+            if distance >= config.reject_distance_km {
+                return Err(AppError::BadRequest(format!(
+                    "The photo was taken {distance:.1} km from the reported location"
+                )));
+            }
+            let range = (config.reject_distance_km - PHOTO_LOCATION_SLACK_KM).max(f64::EPSILON);
+            let stray = (distance - PHOTO_LOCATION_SLACK_KM).max(0.0) / range;
+            Some(1.0 - 0.9 * stray.min(1.0))
+        }
+        None => None,
+    };
+
+    let stale = exif.taken_at.map(|taken_at| {
+        let age = now - taken_at;
+        age > Duration::hours(config.max_photo_age_hours)
+            || age < -Duration::hours(PHOTO_FUTURE_SLACK_HOURS)
+    });
+
+    let score = match (position_score, stale) {
+        (Some(score), _) => score,
+        (None, Some(_)) => TIME_ONLY_CONFIDENCE,
+        (None, None) => return Ok(None),
+    };
+    Ok(Some(if stale == Some(true) {
+        score / 2.0
+    } else {
+        score
+    }))
+}
+
+/// Map zoom levels below this get clusters instead of individual reports
+pub const CLUSTER_BELOW_ZOOM: u8 = 13;
+/// Most individual reports one map viewport returns
+pub const MAX_REPORTS_IN_BOUNDS: i64 = 500;
+
+/// Geohash length reports are clustered by at `zoom`. Cells are then about a quarter
+/// of a 256px map tile wide, so markers sit roughly 64px apart.
+#[must_use]
+pub fn cluster_geohash_precision(zoom: u8) -> i32 {
+    // A geohash of n characters halves the longitude span ceil(5n / 2) times, and a
+    // quarter tile at `zoom` is 360 / 2^(zoom + 2) degrees
+    ((2 * (i32::from(zoom.min(22)) + 2) + 4) / 5).clamp(1, 12)
+}
+
+/// The zoom at which a viewport spanning `bounds` fills a map about four tiles wide
+#[must_use]
+pub fn zoom_for_bounds(bounds: &ReportsInBoundsQuery) -> u8 {
+    let span = (bounds.max_lon.degrees() - bounds.min_lon.degrees())
+        .max(bounds.max_lat.degrees() - bounds.min_lat.degrees());
+    if span <= 0.0 {
+        return 22;
+    }
+    (360.0 * 4.0 / span).log2().floor().clamp(0.0, 22.0) as u8
+}
+
+/// `SELECT ... FROM litter_reports` for built queries that load whole `LitterReport`s
+const LITTER_REPORT_SELECT: &str = r"
+    SELECT
+        id, reporter_id,
+        ST_Y(location)::double precision as latitude,
+        ST_X(location)::double precision as longitude,
+        description, category, severity,
+        photo_before, status,
+        claimed_by, claimed_at, cleared_by, cleared_at,
+        photo_after, created_at, updated_at, address,
+        address_street, address_city, address_country,
+        photo_before_width, photo_before_height, photo_before_blurhash,
+        photo_after_width, photo_after_height, photo_after_blurhash
+    FROM litter_reports";
+
+/// Push `point` as a geography
+fn push_point(query: &mut QueryBuilder<'_, Postgres>, point: Coordinates) {
+    query
+        .push("ST_SetSRID(ST_MakePoint(")
+        .push_bind(point.longitude())
+        .push(", ")
+        .push_bind(point.latitude())
+        .push("), 4326)::geography");
+}
+
+/// Push a `WHERE` limiting a query to reports inside the viewport
+fn push_bounds(query: &mut QueryBuilder<'_, Postgres>, bounds: &ReportsInBoundsQuery) {
+    query
+        .push(" WHERE location && ST_MakeEnvelope(")
+        .push_bind(bounds.min_lon.degrees())
+        .push(", ")
+        .push_bind(bounds.min_lat.degrees())
+        .push(", ")
+        .push_bind(bounds.max_lon.degrees())
+        .push(", ")
+        .push_bind(bounds.max_lat.degrees())
+        .push(", 4326)");
+}
+
+/// Push `AND` conditions for the filter onto a query that already has a `WHERE`. Reports
+/// hidden awaiting moderation never match.
+fn push_report_filter(query: &mut QueryBuilder<'_, Postgres>, filter: &ReportFilter) {
+    // Expired reports stay off the map even when asked for by status
+    query
+        .push(" AND hidden_at IS NULL AND status <> 'expired' AND status = ANY(")
+        .push_bind(filter.statuses.clone())
+        .push(")");
+    if !filter.categories.is_empty() {
+        query
+            .push(" AND category = ANY(")
+            .push_bind(filter.categories.clone())
+            .push(")");
+    }
+    if let Some(severity) = filter.severity {
+        query.push(" AND severity = ").push_bind(severity);
+    }
+}
+
+/// Push whether a report's location confidence is low; unknown counts as fine
+fn push_low_confidence(query: &mut QueryBuilder<'_, Postgres>) {
+    query
+        .push("COALESCE(location_confidence < ")
+        .push_bind(LOW_LOCATION_CONFIDENCE)
+        .push(", FALSE)");
+}
+
+/// Push the keyset condition for `after` and the `ORDER BY` for `sort`. Distance is
+/// measured from `origin`, and without one the list is newest first. Reports with low
+/// location confidence come after the rest in every order. When paging, whether the
+/// cursor report has low confidence, and for distance its distance, are looked up by id.
+fn push_report_order(
+    query: &mut QueryBuilder<'_, Postgres>,
+    sort: ReportSort,
+    origin: Option<Coordinates>,
+    after: Option<KeysetCursor>,
+) {
+    if let Some(after) = after {
+        let push_cursor_low_confidence = |query: &mut QueryBuilder<'_, Postgres>| {
+            query.push("(SELECT ");
+            push_low_confidence(query);
+            query
+                .push(" FROM litter_reports WHERE id = ")
+                .push_bind(after.id)
+                .push(")");
+        };
+        query.push(" AND (");
+        push_low_confidence(query);
+        query.push(" > ");
+        push_cursor_low_confidence(query);
+        query.push(" OR (");
+        push_low_confidence(query);
+        query.push(" = ");
+        push_cursor_low_confidence(query);
+        query.push(" AND ");
+        match (sort, origin) {
+            (ReportSort::Distance, Some(origin)) => {
+                query.push("(ST_Distance(location::geography, ");
+                push_point(query, origin);
+                query.push("), id) > ((SELECT ST_Distance(c.location::geography, ");
+                push_point(query, origin);
+                query
+                    .push(") FROM litter_reports c WHERE c.id = ")
+                    .push_bind(after.id)
+                    .push("), ")
+                    .push_bind(after.id)
+                    .push(")");
+            }
+            (ReportSort::Oldest, _) => {
+                query
+                    .push("(created_at, id) > (")
+                    .push_bind(after.at)
+                    .push(", ")
+                    .push_bind(after.id)
+                    .push(")");
+            }
+            _ => {
+                query
+                    .push("(created_at, id) < (")
+                    .push_bind(after.at)
+                    .push(", ")
+                    .push_bind(after.id)
+                    .push(")");
+            }
+        }
+        query.push("))");
+    }
+
+    query.push(" ORDER BY ");
+    push_low_confidence(query);
+    query.push(", ");
+    match (sort, origin) {
+        (ReportSort::Distance, Some(origin)) => {
+            query.push("ST_Distance(location::geography, ");
+            push_point(query, origin);
+            query.push(") ASC, id ASC");
+        }
+        (ReportSort::Oldest, _) => {
+            query.push("created_at ASC, id ASC");
+        }
+        _ => {
+            query.push("created_at DESC, id DESC");
+        }
+    }
+}
+
+/// What the reporter told us about the litter, common to signed-in and guest reports
+struct NewReport {
+    location: Coordinates,
+    description: Option<String>,
+    category: LitterCategory,
+    severity: LitterSeverity,
+    /// The bulk import the report came from, if any
+    import_id: Option<Uuid>,
+}
+
+/// An entry for a report's history, written with [`ReportService::record_event`]
+pub struct NewReportEvent<'a> {
+    pub kind: ReportEventKind,
+    pub from: Option<&'a ReportStatus>,
+    pub to: Option<&'a ReportStatus>,
+    pub actor_id: Option<Uuid>,
+    pub reason: Option<&'a str>,
+    pub metadata: serde_json::Value,
+}
+
+impl<'a> NewReportEvent<'a> {
+    /// A checked transition from `from` to `to`, with no actor, reason or metadata
+    #[must_use]
+    pub fn status_change(
+        transition: ReportTransition,
+        from: &'a ReportStatus,
+        to: &'a ReportStatus,
+    ) -> Self {
+        Self {
+            kind: transition.into(),
+            from: Some(from),
+            to: Some(to),
+            actor_id: None,
+            reason: None,
+            metadata: serde_json::json!({}),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-struct NominatimResponse {
-    address: Option<NominatimAddress>,
-    display_name: Option<String>,
+/// An uploaded photo with what its `report_photos` row needs
+struct SavedPhoto {
+    content_hash: String,
+    metadata: ImageMetadata,
 }
 
 #[derive(Clone)]
 pub struct ReportService {
     pool: PgPool,
     image_service: ImageService,
-    s3_service: S3Service,
+    image_storage: ImageStorageService,
+    upload_service: UploadService,
+    geocode_queue: GeocodeQueueService,
+    location_check: LocationCheckConfig,
 }
 
 impl ReportService {
     #[must_use]
-    pub fn new(pool: PgPool, image_service: ImageService, s3_service: S3Service) -> Self {
+    pub fn new(
+        pool: PgPool,
+        image_service: ImageService,
+        image_storage: ImageStorageService,
+        upload_service: UploadService,
+        geocoding: GeocodingService,
+        location_check: LocationCheckConfig,
+    ) -> Self {
         Self {
+            geocode_queue: GeocodeQueueService::new(pool.clone(), geocoding),
             pool,
             image_service,
-            s3_service,
+            image_storage,
+            upload_service,
+            location_check,
         }
     }
 
-    async fn get_address_from_coords(&self, lat: f64, lon: f64) -> Option<String> {
-        let client = reqwest::Client::new();
-        let url = format!(
-            "https://nominatim.openstreetmap.org/reverse?format=json&lat={}&lon={}&zoom=18&addressdetails=1",
-            lat, lon
-        );
-
-        match client
-            .get(&url)
-            .header("User-Agent", "LittyPicky/1.0")
-            .send()
-            .await
-        {
-            Ok(resp) => match resp.json::<NominatimResponse>().await {
-                Ok(data) => {
-                    if let Some(addr) = data.address {
-                        // Prioritize specific POI names if close (Nominatim handles distance logic for us somewhat by returning the specific object)
-                        // We want "Tesco, Example Street" or "52 Example Street" or "Example Street"
-
-                        let street = addr
-                            .road
-                            .or(addr.suburb)
-                            .or(addr.village)
-                            .or(addr.town)
-                            .or(addr.city);
-
-                        // Check for POI/Building
-                        let poi = addr.amenity.or(addr.shop).or(addr.building);
-
-                        match (poi, addr.house_number, street) {
-                            (Some(p), Some(s), _) if p.eq_ignore_ascii_case(&s) => Some(p), // Avoid duplication
-                            (Some(p), _, Some(s)) => Some(format!("{}, {}", p, s)),
-                            (Some(p), _, None) => Some(p),
-                            (None, Some(n), Some(s)) => Some(format!("{} {}", n, s)),
-                            (None, None, Some(s)) => Some(s),
-                            _ => data.display_name, // Fallback to full display name if nothing clean is found
-                        }
-                    } else {
-                        None
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to parse Nominatim response: {}", e);
-                    None
-                }
-            },
-            Err(e) => {
-                eprintln!("Failed to fetch address: {}", e);
-                None
+    /// Process a photo sent either inline as base64 or as a completed chunked upload
+    async fn process_photo(
+        &self,
+        user_id: Uuid,
+        photo_base64: Option<String>,
+        photo_upload_id: Option<Uuid>,
+    ) -> Result<ProcessedImage, AppError> {
+        match (photo_base64, photo_upload_id) {
+            (Some(photo_base64), None) => self.image_service.process_image(photo_base64).await,
+            (None, Some(upload_id)) => {
+                let data = self
+                    .upload_service
+                    .take_completed(upload_id, user_id)
+                    .await?;
+                self.image_service.process_image_bytes(data).await
             }
+            _ => Err(AppError::BadRequest(
+                "Provide exactly one of photo_base64 or photo_upload_id".to_string(),
+            )),
         }
     }
 
-    /// Create a new litter report
-    pub async fn create_report(
+    /// Decode the extra photos sent alongside the first one
+    async fn process_extra_photos(
+        &self,
+        photos: Vec<String>,
+    ) -> Result<Vec<ProcessedImage>, AppError> {
+        if photos.len() >= MAX_PHOTOS_PER_KIND {
+            return Err(AppError::BadRequest(format!(
+                "At most {} extra photos can be sent with the first",
+                MAX_PHOTOS_PER_KIND - 1
+            )));
+        }
+
+        let mut processed = Vec::with_capacity(photos.len());
+        for photo in photos {
+            processed.push(self.image_service.process_image(photo).await?);
+        }
+        Ok(processed)
+    }
+
+    /// Upload processed photos (identical photos share one object), keeping their order
+    async fn save_photos(&self, photos: Vec<ProcessedImage>) -> Result<Vec<SavedPhoto>, AppError> {
+        let mut saved = Vec::with_capacity(photos.len());
+        for photo in photos {
+            let stored = self.image_storage.store(photo.data).await?;
+            saved.push(SavedPhoto {
+                content_hash: stored.content_hash,
+                metadata: ImageMetadata {
+                    url: stored.url,
+                    width: Some(photo.width as i32),
+                    height: Some(photo.height as i32),
+                    blurhash: Some(photo.blurhash),
+                    processing: stored.pending,
+                },
+            });
+        }
+        Ok(saved)
+    }
+
+    async fn insert_photo_rows(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+        kind: PhotoKind,
+        first_position: i32,
+        photos: &[SavedPhoto],
+    ) -> Result<(), AppError> {
+        for (position, photo) in (first_position..).zip(photos) {
+            sqlx::query!(
+                r#"
+                INSERT INTO report_photos (report_id, kind, position, url, width, height, blurhash)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+                report_id,
+                kind as PhotoKind,
+                position,
+                photo.metadata.url,
+                photo.metadata.width,
+                photo.metadata.height,
+                photo.metadata.blurhash
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn record_photo_hashes(
         &self,
         user_id: Uuid,
-        request: CreateReportRequest,
-    ) -> Result<LitterReport, AppError> {
-        // Check if user's email is verified
+        report_id: Uuid,
+        photos: &[SavedPhoto],
+    ) -> Result<(), AppError> {
+        for photo in photos {
+            self.image_storage
+                .record_report_photo(user_id, report_id, &photo.content_hash)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Store a verifier's on-site photo of a report, counted towards their duplicate
+    /// photo tally like the report's own photos
+    pub async fn save_verification_photo(
+        &self,
+        user_id: Uuid,
+        report_id: Uuid,
+        photo_base64: String,
+    ) -> Result<ImageMetadata, AppError> {
+        let processed = self.image_service.process_image(photo_base64).await?;
+        let mut photos = self.save_photos(vec![processed]).await?;
+        self.record_photo_hashes(user_id, report_id, &photos)
+            .await?;
+        Ok(photos.remove(0).metadata)
+    }
+
+    async fn require_verified_email(&self, user_id: Uuid, action: &str) -> Result<(), AppError> {
         let user = sqlx::query!("SELECT email_verified FROM users WHERE id = $1", user_id)
             .fetch_optional(&self.pool)
             .await?
             .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
         if !user.email_verified {
-            return Err(AppError::Forbidden(
-                "Email must be verified to create reports".to_string(),
-            ));
+            return Err(AppError::Forbidden(format!(
+                "Email must be verified to {action}"
+            )));
         }
 
-        // Process the image (async to avoid blocking)
-        let processed_image = self
-            .image_service
-            .process_image(request.photo_base64)
-            .await?;
+        Ok(())
+    }
 
-        // Upload to S3
-        let photo_url = self
-            .s3_service
-            .upload_image(processed_image, "reports/before")
-            .await?;
+    /// Check the first photo's EXIF data against the location, then store the photos
+    /// and insert a pending report. The first photo becomes the report's `photo_before`.
+    /// The address is looked up in the background and filled in once found.
+    async fn insert_report(
+        &self,
+        reporter_id: Option<Uuid>,
+        new_report: NewReport,
+        photos: Vec<ProcessedImage>,
+    ) -> Result<(LitterReport, Vec<SavedPhoto>), AppError> {
+        let NewReport {
+            location,
+            description,
+            category,
+            severity,
+            import_id,
+        } = new_report;
+
+        // Imported reports may come without photos
+        let confidence = match photos.first() {
+            Some(photo) => {
+                location_confidence(&photo.exif, location, Utc::now(), &self.location_check)?
+            }
+            None => None,
+        };
 
-        // Get address from coordinates
-        let address = self
-            .get_address_from_coords(request.latitude, request.longitude)
-            .await;
+        let photos = self.save_photos(photos).await?;
+        let primary = photos.first().map(|photo| &photo.metadata);
+
+        let mut tx = self.pool.begin().await?;
 
         // Create the report with PostGIS geometry
         let report = sqlx::query_as!(
@@ -137,120 +514,545 @@ impl ReportService {
             r#"
             INSERT INTO litter_reports (
                 reporter_id, location, description,
-                photo_before, status, address
+                photo_before, status, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                category, severity
             )
             VALUES (
                 $1,
                 ST_SetSRID(ST_MakePoint($3, $2), 4326),
-                $4, $5, $6, $7
+                $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15
             )
             RETURNING
                 id, reporter_id,
                 ST_Y(location)::double precision as "latitude!",
                 ST_X(location)::double precision as "longitude!",
                 description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
                 photo_before, status as "status: ReportStatus",
                 claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
             "#,
-            user_id,
-            request.latitude,
-            request.longitude,
-            request.description,
-            photo_url,
+            reporter_id,
+            location.latitude(),
+            location.longitude(),
+            description,
+            primary.map(|p| p.url.as_str()),
             ReportStatus::Pending as ReportStatus,
-            address
+            // Filled in by the geocode queue
+            None::<String>,
+            None::<String>,
+            None::<String>,
+            None::<String>,
+            primary.and_then(|p| p.width),
+            primary.and_then(|p| p.height),
+            primary.and_then(|p| p.blurhash.as_deref()),
+            category as LitterCategory,
+            severity as LitterSeverity
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(confidence) = confidence {
+            sqlx::query("UPDATE litter_reports SET location_confidence = $2 WHERE id = $1")
+                .bind(report.id)
+                .bind(confidence)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let mut metadata = serde_json::json!({
+            "category": category,
+            "severity": severity,
+            "anonymous": reporter_id.is_none(),
+            "location_confidence": confidence,
+        });
+        if let Some(import_id) = import_id {
+            metadata["import_id"] = serde_json::json!(import_id);
+        }
+        let created = NewReportEvent {
+            kind: ReportEventKind::Created,
+            from: None,
+            to: Some(&report.status),
+            actor_id: reporter_id,
+            reason: None,
+            metadata,
+        };
+        Self::record_event(&mut tx, report.id, created).await?;
+
+        Self::insert_photo_rows(&mut tx, report.id, PhotoKind::Before, 0, &photos).await?;
+        GeocodeQueueService::enqueue(&mut tx, report.id).await?;
+        tx.commit().await?;
+        self.geocode_queue.spawn_resolve(report.id);
+
+        if let Some(confidence) = confidence.filter(|c| *c < LOW_LOCATION_CONFIDENCE) {
+            tracing::info!(
+                "Report {} has low location confidence {confidence:.2}",
+                report.id
+            );
+        }
+
+        Ok((report, photos))
+    }
+
+    /// Create a new litter report
+    pub async fn create_report(
+        &self,
+        user_id: Uuid,
+        request: CreateReportRequest,
+    ) -> Result<LitterReport, AppError> {
+        self.require_verified_email(user_id, "create reports")
+            .await?;
+
+        // Process the image (async to avoid blocking)
+        let mut photos = vec![
+            self.process_photo(user_id, request.photo_base64, request.photo_upload_id)
+                .await?,
+        ];
+        photos.extend(
+            self.process_extra_photos(request.extra_photos_base64)
+                .await?,
+        );
+
+        let (report, photos) = self
+            .insert_report(
+                Some(user_id),
+                NewReport {
+                    location: Coordinates::new(request.latitude, request.longitude),
+                    description: request.description,
+                    category: request.category,
+                    severity: request.severity,
+                    import_id: None,
+                },
+                photos,
+            )
+            .await?;
+
+        self.record_photo_hashes(user_id, report.id, &photos)
+            .await?;
+
         Ok(report)
     }
 
-    /// Get reports near a location using `PostGIS`
-    pub async fn get_nearby_reports(
+    /// Create a report on behalf of a guest. The report has no reporter until someone
+    /// redeems the returned claim code, which is only stored hashed.
+    pub async fn create_anonymous_report(
         &self,
-        latitude: f64,
-        longitude: f64,
-        radius_km: f64,
-    ) -> Result<Vec<LitterReport>, AppError> {
-        let radius_meters = radius_km * 1000.0;
+        request: CreateAnonymousReportRequest,
+    ) -> Result<(LitterReport, String, DateTime<Utc>), AppError> {
+        let processed_image = self
+            .image_service
+            .process_image(request.photo_base64)
+            .await?;
 
-        let reports = sqlx::query_as!(
-            LitterReport,
-            r#"
-            SELECT
-                id, reporter_id,
-                ST_Y(location)::double precision as "latitude!",
-                ST_X(location)::double precision as "longitude!",
-                description,
-                photo_before, status as "status: ReportStatus",
-                claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
-            FROM litter_reports
-            WHERE ST_DWithin(
-                location::geography,
-                ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography,
-                $3
+        let (report, _) = self
+            .insert_report(
+                None,
+                NewReport {
+                    location: Coordinates::new(request.latitude, request.longitude),
+                    description: request.description,
+                    category: request.category,
+                    severity: request.severity,
+                    import_id: None,
+                },
+                vec![processed_image],
             )
-            AND status IN ('pending', 'claimed')
-            ORDER BY created_at DESC
-            LIMIT 100
-            "#,
-            longitude,
-            latitude,
-            radius_meters
+            .await?;
+
+        let claim_code = generate_token();
+        let expires_at = Utc::now() + Duration::days(ANONYMOUS_CLAIM_TTL_DAYS);
+        sqlx::query!(
+            "INSERT INTO anonymous_report_claims (report_id, code_hash, expires_at) VALUES ($1, $2, $3)",
+            report.id,
+            hash_token(&claim_code),
+            expires_at
         )
-        .fetch_all(&self.pool)
+        .execute(&self.pool)
         .await?;
 
-        Ok(reports)
+        Ok((report, claim_code, expires_at))
     }
 
-    /// Get reports that need verification near a location
-    pub async fn get_verification_queue(
+    /// Create a report from a bulk import. It has no reporter, and `photos` may be empty.
+    pub async fn create_imported_report(
         &self,
-        latitude: f64,
-        longitude: f64,
-        radius_km: f64,
+        import_id: Uuid,
+        row: ValidImportRow,
+        photos: Vec<ProcessedImage>,
+    ) -> Result<LitterReport, AppError> {
+        let (report, _) = self
+            .insert_report(
+                None,
+                NewReport {
+                    location: row.location,
+                    description: row.description,
+                    category: row.category,
+                    severity: row.severity,
+                    import_id: Some(import_id),
+                },
+                photos,
+            )
+            .await?;
+
+        Ok(report)
+    }
+
+    /// Attach a guest report to `user_id` using its claim code, awarding its report
+    /// points in the same transaction. Each code works once.
+    pub async fn claim_anonymous_report(
+        &self,
+        scoring: &ScoringService,
         user_id: Uuid,
-    ) -> Result<Vec<LitterReport>, AppError> {
-        let radius_meters = radius_km * 1000.0;
+        claim_code: &str,
+    ) -> Result<LitterReport, AppError> {
+        self.require_verified_email(user_id, "claim reports")
+            .await?;
 
-        let reports = sqlx::query_as!(
+        let mut tx = self.pool.begin().await?;
+
+        let report_id = sqlx::query_scalar!(
+            r#"
+            DELETE FROM anonymous_report_claims
+            WHERE code_hash = $1 AND expires_at > NOW()
+            RETURNING report_id
+            "#,
+            hash_token(claim_code)
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Invalid or expired claim code".to_string()))?;
+
+        // Someone who has since claimed or cleared the report would otherwise be paid twice
+        let report = sqlx::query_as!(
             LitterReport,
             r#"
-            SELECT
+            UPDATE litter_reports
+            SET reporter_id = $1
+            WHERE id = $2
+              AND reporter_id IS NULL
+              AND claimed_by IS DISTINCT FROM $1
+              AND cleared_by IS DISTINCT FROM $1
+            RETURNING
                 id, reporter_id,
                 ST_Y(location)::double precision as "latitude!",
                 ST_X(location)::double precision as "longitude!",
                 description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
                 photo_before, status as "status: ReportStatus",
                 claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
-            FROM litter_reports
-            WHERE ST_DWithin(
-                location::geography,
-                ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography,
-                $3
-            )
-            AND status = 'cleared'
-            AND (cleared_by IS NULL OR cleared_by != $4)
-            AND id NOT IN (
-                SELECT report_id FROM report_verifications WHERE verifier_id = $4
-            )
-            ORDER BY cleared_at DESC
-            LIMIT 50
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
             "#,
-            longitude,
-            latitude,
-            radius_meters,
-            user_id
+            user_id,
+            report_id
         )
-        .fetch_all(&self.pool)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest("You cannot claim a report you have cleaned up".to_string())
+        })?;
+
+        // The code is spent only if the points are paid
+        scoring
+            .award_report_points_in(&mut tx, user_id, report.id)
+            .await?;
+
+        tx.commit().await?;
+
+        // Photo-reuse checks apply once the report has an owner
+        let content_hash = sqlx::query_scalar!(
+            r#"
+            SELECT s.content_hash
+            FROM stored_images s
+            JOIN litter_reports r ON r.photo_before = s.url
+            WHERE r.id = $1
+            "#,
+            report.id
+        )
+        .fetch_optional(&self.pool)
         .await?;
+        if let Some(content_hash) = content_hash {
+            self.image_storage
+                .record_report_photo(user_id, report.id, &content_hash)
+                .await?;
+        }
 
-        Ok(reports)
+        Ok(report)
+    }
+
+    /// Get one page of reports near a location using `PostGIS`, limited by the filter and
+    /// in the given order, along with the total number of matches
+    pub async fn get_nearby_reports(
+        &self,
+        origin: Coordinates,
+        radius_km: f64,
+        filter: &ReportFilter,
+        sort: ReportSort,
+        after: Option<KeysetCursor>,
+        limit: i64,
+    ) -> Result<(Vec<LitterReport>, i64), AppError> {
+        let radius_meters = radius_km * 1000.0;
+        let push_filter = |query: &mut QueryBuilder<'_, Postgres>| {
+            query.push(" WHERE ST_DWithin(location::geography, ");
+            push_point(query, origin);
+            query.push(", ").push_bind(radius_meters).push(")");
+            push_report_filter(query, filter);
+        };
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM litter_reports");
+        push_filter(&mut count);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut query = QueryBuilder::new(LITTER_REPORT_SELECT);
+        push_filter(&mut query);
+        push_report_order(&mut query, sort, Some(origin), after);
+        query.push(" LIMIT ").push_bind(limit);
+        let reports = query
+            .build_query_as::<LitterReport>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((reports, total))
+    }
+
+    /// Get one page of all reports for the admin list, optionally limited to some
+    /// statuses, along with the total number of matches
+    pub async fn list_admin_reports(
+        &self,
+        statuses: Option<&[ReportStatus]>,
+        sort: ReportSort,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<AdminReportView>, i64), AppError> {
+        let push_filter = |query: &mut QueryBuilder<'_, Postgres>| {
+            if let Some(statuses) = statuses {
+                query
+                    .push(" WHERE lr.status = ANY(")
+                    .push_bind(statuses.to_vec())
+                    .push(")");
+            }
+        };
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM litter_reports lr");
+        push_filter(&mut count);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut query = QueryBuilder::new(
+            r"
+            SELECT
+                lr.id,
+                lr.reporter_id,
+                ST_Y(lr.location)::double precision as latitude,
+                ST_X(lr.location)::double precision as longitude,
+                lr.description,
+                lr.status,
+                lr.claimed_by,
+                lr.claimed_at,
+                lr.cleared_by,
+                lr.cleared_at,
+                lr.created_at,
+                u.full_name as reporter_name,
+                u.email as reporter_email
+            FROM litter_reports lr
+            LEFT JOIN users u ON lr.reporter_id = u.id
+            ",
+        );
+        push_filter(&mut query);
+        query.push(match sort {
+            ReportSort::Oldest => " ORDER BY lr.created_at ASC, lr.id ASC",
+            ReportSort::Newest => " ORDER BY lr.created_at DESC, lr.id DESC",
+            ReportSort::Distance => {
+                return Err(AppError::BadRequest(
+                    "sort=distance needs a location to measure from".to_string(),
+                ))
+            }
+        });
+        query
+            .push(" LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let reports = query
+            .build_query_as::<AdminReportView>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((reports, total))
+    }
+
+    /// Reports inside a map viewport, newest first, with the total count
+    pub async fn get_reports_in_bounds(
+        &self,
+        bounds: &ReportsInBoundsQuery,
+        filter: &ReportFilter,
+        limit: i64,
+    ) -> Result<(Vec<LitterReport>, i64), AppError> {
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM litter_reports");
+        push_bounds(&mut count, bounds);
+        push_report_filter(&mut count, filter);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut query = QueryBuilder::new(LITTER_REPORT_SELECT);
+        push_bounds(&mut query, bounds);
+        push_report_filter(&mut query, filter);
+        query
+            .push(" ORDER BY created_at DESC LIMIT ")
+            .push_bind(limit);
+        let reports = query
+            .build_query_as::<LitterReport>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((reports, total))
+    }
+
+    /// Reports inside a map viewport grouped by geohash prefixes of `precision`
+    /// characters, largest clusters first. Unlike the raw query nothing is cut off, so
+    /// dense areas are never under-counted.
+    pub async fn get_report_clusters(
+        &self,
+        bounds: &ReportsInBoundsQuery,
+        filter: &ReportFilter,
+        precision: i32,
+    ) -> Result<Vec<ReportCluster>, AppError> {
+        let mut query = QueryBuilder::new("SELECT ST_GeoHash(location, ");
+        query.push_bind(precision).push(
+            r") AS geohash,
+                AVG(ST_Y(location))::double precision AS latitude,
+                AVG(ST_X(location))::double precision AS longitude,
+                COUNT(*) AS count,
+                CASE WHEN COUNT(*) = 1 THEN (array_agg(id))[1] END AS report_id
+            FROM litter_reports",
+        );
+        push_bounds(&mut query, bounds);
+        push_report_filter(&mut query, filter);
+        query.push(" GROUP BY 1 ORDER BY 4 DESC, 1");
+        let clusters = query
+            .build_query_as::<ReportCluster>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(clusters)
+    }
+
+    /// Get one page of reports that need verification near a location, with the total
+    /// count. Clear times order the queue unless it asks for the closest first.
+    pub async fn get_verification_queue(
+        &self,
+        area: &NearbyReportsQuery,
+        user_id: Uuid,
+        verifications_needed: i32,
+        queue: &VerificationQueueQuery,
+        after: Option<KeysetCursor>,
+        limit: i64,
+    ) -> Result<(Vec<LitterReport>, i64), AppError> {
+        let origin = Coordinates::new(area.latitude, area.longitude);
+        let radius_meters = area.radius_km.unwrap_or(VERIFICATION_QUEUE_RADIUS_KM) * 1000.0;
+
+        let push_filter = |query: &mut QueryBuilder<'_, Postgres>| {
+            query.push(" WHERE ST_DWithin(location::geography, ");
+            push_point(query, origin);
+            query
+                .push(", ")
+                .push_bind(radius_meters)
+                .push(") AND status = 'cleared' AND hidden_at IS NULL")
+                .push(" AND (cleared_by IS NULL OR cleared_by != ")
+                .push_bind(user_id)
+                .push(
+                    ") AND id NOT IN (
+                        SELECT report_id FROM report_verifications
+                        WHERE superseded_at IS NULL AND verifier_id = ",
+                )
+                .push_bind(user_id)
+                .push(
+                    ") AND id NOT IN (
+                        SELECT report_id FROM photo_retake_requests
+                        WHERE fulfilled_at IS NULL AND expires_at > NOW()
+                    )",
+                )
+                // Leave out reports whose open slots are all held by other verifiers
+                .push(
+                    " AND (
+                        SELECT COUNT(*) FROM verification_reservations vr
+                        WHERE vr.report_id = litter_reports.id AND vr.user_id != ",
+                )
+                .push_bind(user_id)
+                .push(" AND vr.expires_at > NOW()) < GREATEST(")
+                .push_bind(i64::from(verifications_needed))
+                .push(
+                    " - (
+                        SELECT COUNT(*) FROM report_verifications rv
+                        WHERE rv.report_id = litter_reports.id AND rv.superseded_at IS NULL
+                    ), 1)",
+                );
+            if let Some(category) = queue.category {
+                query.push(" AND category = ").push_bind(category);
+            }
+            if let Some(severity) = queue.severity {
+                query.push(" AND severity = ").push_bind(severity);
+            }
+        };
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM litter_reports");
+        push_filter(&mut count);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut query = QueryBuilder::new(LITTER_REPORT_SELECT);
+        push_filter(&mut query);
+        match queue.sort.unwrap_or_default() {
+            ReportSort::Distance => {
+                if let Some(after) = after {
+                    query.push(" AND (ST_Distance(location::geography, ");
+                    push_point(&mut query, origin);
+                    query.push("), id) > ((SELECT ST_Distance(c.location::geography, ");
+                    push_point(&mut query, origin);
+                    query
+                        .push(") FROM litter_reports c WHERE c.id = ")
+                        .push_bind(after.id)
+                        .push("), ")
+                        .push_bind(after.id)
+                        .push(")");
+                }
+                query.push(" ORDER BY ST_Distance(location::geography, ");
+                push_point(&mut query, origin);
+                query.push(") ASC, id ASC");
+            }
+            ReportSort::Oldest => {
+                if let Some(after) = after {
+                    query
+                        .push(" AND (COALESCE(cleared_at, created_at), id) > (")
+                        .push_bind(after.at)
+                        .push(", ")
+                        .push_bind(after.id)
+                        .push(")");
+                }
+                query.push(" ORDER BY COALESCE(cleared_at, created_at) ASC, id ASC");
+            }
+            ReportSort::Newest => {
+                if let Some(after) = after {
+                    query
+                        .push(" AND (COALESCE(cleared_at, created_at), id) < (")
+                        .push_bind(after.at)
+                        .push(", ")
+                        .push_bind(after.id)
+                        .push(")");
+                }
+                query.push(" ORDER BY COALESCE(cleared_at, created_at) DESC, id DESC");
+            }
+        }
+        query.push(" LIMIT ").push_bind(limit);
+        let reports = query
+            .build_query_as::<LitterReport>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((reports, total))
     }
 
     /// Get a single report by ID
@@ -263,9 +1065,13 @@ impl ReportService {
                 ST_Y(location)::double precision as "latitude!",
                 ST_X(location)::double precision as "longitude!",
                 description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
                 photo_before, status as "status: ReportStatus",
                 claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
             FROM litter_reports
             WHERE id = $1
             "#,
@@ -286,20 +1092,27 @@ impl ReportService {
     ) -> Result<LitterReport, AppError> {
         // Check current status
         let current_report = self.get_report_by_id(report_id).await?;
+        let status = ReportTransition::Claim.apply(&current_report.status)?;
+        if self.is_hidden(report_id).await? {
+            return Err(AppError::BadRequest(HIDDEN_REPORT_MESSAGE.to_string()));
+        }
 
-        if current_report.status != ReportStatus::Pending {
+        if current_report.reporter_id == Some(user_id) {
             return Err(AppError::BadRequest(
-                "Report is not available for claiming".to_string(),
+                "Cannot claim your own report".to_string(),
             ));
         }
 
-        if current_report.reporter_id == user_id {
-            return Err(AppError::BadRequest(
-                "Cannot claim your own report".to_string(),
+        self.check_abandoned_claims(user_id).await?;
+        if self.abandoned_claims_on(report_id, user_id).await? >= MAX_ABANDONS_PER_REPORT {
+            return Err(AppError::Forbidden(
+                "You have given up this report too many times to claim it again".to_string(),
             ));
         }
 
-        // Update the report
+        let mut tx = self.pool.begin().await?;
+
+        // Update the report, unless someone else claimed it since we looked
         let report = sqlx::query_as!(
             LitterReport,
             r#"
@@ -307,42 +1120,750 @@ impl ReportService {
             SET status = $1,
                 claimed_by = $2,
                 claimed_at = $3
-            WHERE id = $4
+            WHERE id = $4 AND status = 'pending'
             RETURNING
                 id, reporter_id,
                 ST_Y(location)::double precision as "latitude!",
                 ST_X(location)::double precision as "longitude!",
                 description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
                 photo_before, status as "status: ReportStatus",
                 claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
             "#,
             ReportStatus::Claimed as ReportStatus,
             user_id,
             Utc::now(),
             report_id
         )
-        .fetch_one(&self.pool)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Report is not available for claiming".to_string()))?;
+
+        Self::record_claim(&mut tx, report_id, user_id).await?;
+        Self::record_status_change(
+            &mut tx,
+            report_id,
+            ReportTransition::Claim,
+            &current_report.status,
+            &status,
+            Some(user_id),
+            None,
+        )
         .await?;
+        tx.commit().await?;
 
         Ok(report)
     }
 
-    /// Mark a report as cleared with after photo
-    pub async fn clear_report(
+    /// Give up a claim so someone else can clear the report. The release counts
+    /// towards the claimer's abandon limits whatever the reason.
+    pub async fn unclaim_report(
         &self,
         report_id: Uuid,
         user_id: Uuid,
-        photo_base64: String,
+        reason: Option<&str>,
     ) -> Result<LitterReport, AppError> {
-        // Check current status
         let current_report = self.get_report_by_id(report_id).await?;
 
-        if current_report.status != ReportStatus::Claimed {
+        if current_report.status != ReportStatus::Claimed
+            || current_report.claimed_by != Some(user_id)
+        {
             return Err(AppError::BadRequest(
-                "Report must be claimed before clearing".to_string(),
+                "You have not claimed this report".to_string(),
             ));
         }
+        let status = ReportTransition::Release.apply(&current_report.status)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let report = sqlx::query_as!(
+            LitterReport,
+            r#"
+            UPDATE litter_reports
+            SET status = $1,
+                claimed_by = NULL,
+                claimed_at = NULL,
+                cleanup_run_id = NULL
+            WHERE id = $2 AND status = 'claimed' AND claimed_by = $3
+            RETURNING
+                id, reporter_id,
+                ST_Y(location)::double precision as "latitude!",
+                ST_X(location)::double precision as "longitude!",
+                description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
+                photo_before, status as "status: ReportStatus",
+                claimed_by, claimed_at, cleared_by, cleared_at,
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
+            "#,
+            ReportStatus::Pending as ReportStatus,
+            report_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("You have not claimed this report".to_string()))?;
+
+        let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+        Self::end_claim(&mut tx, report_id, ClaimOutcome::Released, reason).await?;
+        Self::record_status_change(
+            &mut tx,
+            report_id,
+            ReportTransition::Release,
+            &current_report.status,
+            &status,
+            Some(user_id),
+            reason,
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    /// Return claims older than `max_age_hours` to the pending pool, returning each
+    /// released report with the user whose claim expired
+    pub async fn expire_stale_claims(
+        &self,
+        max_age_hours: i64,
+    ) -> Result<Vec<(Uuid, Uuid)>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let expired = sqlx::query!(
+            r#"
+            WITH expired AS (
+                UPDATE litter_reports
+                SET status = 'pending',
+                    claimed_by = NULL,
+                    claimed_at = NULL,
+                    cleanup_run_id = NULL
+                WHERE status = 'claimed'
+                  AND claimed_at < NOW() - make_interval(hours => $1)
+                RETURNING id
+            )
+            UPDATE report_claims c
+            SET outcome = 'expired', ended_at = NOW()
+            FROM expired e
+            WHERE c.report_id = e.id AND c.outcome = 'active'
+            RETURNING c.report_id, c.user_id
+            "#,
+            i32::try_from(max_age_hours).unwrap_or(i32::MAX)
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let expired: Vec<(Uuid, Uuid)> = expired
+            .into_iter()
+            .map(|row| (row.report_id, row.user_id))
+            .collect();
+        let report_ids: Vec<Uuid> = expired.iter().map(|(report_id, _)| *report_id).collect();
+        Self::record_status_changes(&mut tx, &report_ids, ReportTransition::Expire, None).await?;
+        tx.commit().await?;
+
+        Ok(expired)
+    }
+
+    /// Take reports pending for more than `months` (counted from creation, or from their
+    /// last reactivation) off the map, returning their ids
+    pub async fn expire_stale_reports(&self, months: i32) -> Result<Vec<Uuid>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let expired: Vec<Uuid> = sqlx::query_scalar(
+            r"
+            UPDATE litter_reports
+            SET status = 'expired', updated_at = NOW()
+            WHERE status = 'pending'
+              AND COALESCE(reactivated_at, created_at) < NOW() - make_interval(months => $1)
+            RETURNING id
+            ",
+        )
+        .bind(months)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let reason = format!("Pending for over {months} months");
+        Self::record_status_changes(&mut tx, &expired, ReportTransition::Lapse, Some(&reason))
+            .await?;
+        tx.commit().await?;
+
+        Ok(expired)
+    }
+
+    /// Put an expired report back on the map with a fresh expiry period, moving its
+    /// photos back out of the archive storage class
+    pub async fn reactivate_report(
+        &self,
+        report_id: Uuid,
+        admin_id: Uuid,
+    ) -> Result<LitterReport, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let current: ReportStatus =
+            sqlx::query_scalar("SELECT status FROM litter_reports WHERE id = $1 FOR UPDATE")
+                .bind(report_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+        let status = ReportTransition::Reactivate.apply(&current)?;
+
+        sqlx::query(
+            r"
+            UPDATE litter_reports
+            SET status = 'pending', reactivated_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            ",
+        )
+        .bind(report_id)
+        .execute(&mut *tx)
+        .await?;
+
+        Self::record_status_change(
+            &mut tx,
+            report_id,
+            ReportTransition::Reactivate,
+            &current,
+            &status,
+            Some(admin_id),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
+
+        let report = self.get_report_by_id(report_id).await?;
+
+        // Archived photos can still be read, so a failed restore only costs a little more
+        let mut photo_urls: Vec<String> =
+            sqlx::query_scalar("SELECT url FROM report_photos WHERE report_id = $1")
+                .bind(report_id)
+                .fetch_all(&self.pool)
+                .await?;
+        photo_urls.extend(report.photo_before.clone());
+        photo_urls.extend(report.photo_after.clone());
+        if let Err(e) = self.image_storage.restore(&photo_urls).await {
+            tracing::warn!("Failed to restore photos of reactivated report {report_id}: {e:?}");
+        }
+
+        Ok(report)
+    }
+
+    /// Whether flags have hidden the report awaiting moderation; nobody may claim it then
+    async fn is_hidden(&self, report_id: Uuid) -> Result<bool, AppError> {
+        let hidden: Option<bool> =
+            sqlx::query_scalar("SELECT hidden_at IS NOT NULL FROM litter_reports WHERE id = $1")
+                .bind(report_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(hidden == Some(true))
+    }
+
+    /// Refuse new claims from a user who keeps abandoning them
+    async fn check_abandoned_claims(&self, user_id: Uuid) -> Result<(), AppError> {
+        let recent = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!" FROM report_claims
+            WHERE user_id = $1
+              AND outcome IN ('released', 'expired')
+              AND ended_at > NOW() - make_interval(days => $2)
+            "#,
+            user_id,
+            ABANDON_WINDOW_DAYS
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if recent >= MAX_RECENT_ABANDONS {
+            return Err(AppError::TooManyRequests(format!(
+                "You have given up {recent} claims in the last {ABANDON_WINDOW_DAYS} days; clear a report you hold or try again later"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// How often `user_id` has released or let a claim on this report expire
+    async fn abandoned_claims_on(&self, report_id: Uuid, user_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!" FROM report_claims
+            WHERE report_id = $1 AND user_id = $2 AND outcome IN ('released', 'expired')
+            "#,
+            report_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn record_claim(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "INSERT INTO report_claims (report_id, user_id) VALUES ($1, $2)",
+            report_id,
+            user_id
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn end_claim(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+        outcome: ClaimOutcome,
+        reason: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE report_claims SET outcome = $2, ended_at = NOW(), reason = $3
+            WHERE report_id = $1 AND outcome = 'active'
+            "#,
+            report_id,
+            outcome as ClaimOutcome,
+            reason
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Add an entry to a report's history within `tx`
+    pub async fn record_event(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        report_id: Uuid,
+        event: NewReportEvent<'_>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r"
+            INSERT INTO report_events
+                (report_id, kind, from_status, to_status, actor_id, reason, metadata)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ",
+        )
+        .bind(report_id)
+        .bind(event.kind)
+        .bind(event.from)
+        .bind(event.to)
+        .bind(event.actor_id)
+        .bind(event.reason)
+        .bind(event.metadata)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Log a status change made within `tx`, after the transition has been checked
+    pub async fn record_status_change(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        report_id: Uuid,
+        transition: ReportTransition,
+        from: &ReportStatus,
+        to: &ReportStatus,
+        actor_id: Option<Uuid>,
+        reason: Option<&str>,
+    ) -> Result<(), AppError> {
+        let event = NewReportEvent {
+            actor_id,
+            reason,
+            ..NewReportEvent::status_change(transition, from, to)
+        };
+        Self::record_event(tx, report_id, event).await
+    }
+
+    /// Log the same change for many reports at once, e.g. claims expired by a job. Every
+    /// report must have started from the transition's only source status.
+    pub async fn record_status_changes(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        report_ids: &[Uuid],
+        transition: ReportTransition,
+        reason: Option<&str>,
+    ) -> Result<(), AppError> {
+        let ([from], Some(to)) = (transition.sources(), transition.target()) else {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "{transition:?} has no single source and target to record in bulk"
+            )));
+        };
+
+        sqlx::query(
+            r"
+            INSERT INTO report_events (report_id, kind, from_status, to_status, reason)
+            SELECT id, $2, $3, $4, $5 FROM UNNEST($1::uuid[]) AS id
+            ",
+        )
+        .bind(report_ids)
+        .bind(ReportEventKind::from(transition))
+        .bind(from)
+        .bind(to)
+        .bind(reason)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Log that reports are about to be deleted within `tx`, keeping who they belonged
+    /// to. Call before the delete; the entries stay once the reports are gone.
+    pub async fn record_deletions(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        report_ids: &[Uuid],
+        actor_id: Option<Uuid>,
+        reason: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r"
+            INSERT INTO report_events (report_id, kind, from_status, actor_id, reason, metadata)
+            SELECT id, 'deleted', status, $2, $3,
+                   jsonb_build_object(
+                       'reporter_id', reporter_id,
+                       'claimed_by', claimed_by,
+                       'cleared_by', cleared_by
+                   )
+            FROM litter_reports
+            WHERE id = ANY($1)
+            ",
+        )
+        .bind(report_ids)
+        .bind(actor_id)
+        .bind(reason)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// A report's history, or a 404 if the report does not exist
+    pub async fn get_report_history(
+        &self,
+        report_id: Uuid,
+    ) -> Result<ReportHistoryResponse, AppError> {
+        self.get_report_by_id(report_id).await?;
+        Ok(ReportHistoryResponse {
+            report_id,
+            events: self.get_history(report_id).await?,
+        })
+    }
+
+    /// A report's full history, oldest first. It outlives the report, so a deleted
+    /// report's history is found too.
+    pub async fn get_history(&self, report_id: Uuid) -> Result<Vec<ReportEvent>, AppError> {
+        let events = sqlx::query_as::<_, ReportEvent>(
+            r"
+            SELECT id, report_id, kind, from_status, to_status, actor_id, reason, metadata,
+                   occurred_at
+            FROM report_events
+            WHERE report_id = $1
+            ORDER BY occurred_at, id
+            ",
+        )
+        .bind(report_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Everything that has happened to a report so far, including abandoned claims
+    pub async fn get_timeline(&self, report_id: Uuid) -> Result<ReportTimelineResponse, AppError> {
+        let report = self.get_report_by_id(report_id).await?;
+
+        let claims = sqlx::query_as!(
+            ReportClaim,
+            r#"
+            SELECT id, report_id, user_id, claimed_at, ended_at,
+                   outcome as "outcome: ClaimOutcome", reason
+            FROM report_claims
+            WHERE report_id = $1
+            ORDER BY claimed_at
+            "#,
+            report_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut events = vec![TimelineEvent {
+            kind: TimelineEventKind::Reported,
+            user_id: report.reporter_id,
+            at: report.created_at,
+            reason: None,
+        }];
+        for claim in claims {
+            events.push(TimelineEvent {
+                kind: TimelineEventKind::Claimed,
+                user_id: Some(claim.user_id),
+                at: claim.claimed_at,
+                reason: None,
+            });
+
+            let kind = match claim.outcome {
+                ClaimOutcome::Active => None,
+                ClaimOutcome::Cleared => Some(TimelineEventKind::Cleared),
+                ClaimOutcome::Released => Some(TimelineEventKind::Released),
+                ClaimOutcome::Expired => Some(TimelineEventKind::Expired),
+                ClaimOutcome::Reassigned => Some(TimelineEventKind::Reassigned),
+            };
+            if let (Some(kind), Some(at)) = (kind, claim.ended_at) {
+                events.push(TimelineEvent {
+                    kind,
+                    user_id: Some(claim.user_id),
+                    at,
+                    reason: claim.reason,
+                });
+            }
+        }
+
+        // Creation, claims, releases and clears are already covered by the report and
+        // its claim history
+        for event in self.get_history(report_id).await? {
+            let kind = match event.kind {
+                ReportEventKind::Verified => TimelineEventKind::Verified,
+                ReportEventKind::Disputed => TimelineEventKind::Disputed,
+                ReportEventKind::Reopened => TimelineEventKind::Reopened,
+                ReportEventKind::Cancelled => TimelineEventKind::Cancelled,
+                ReportEventKind::Archived => TimelineEventKind::Archived,
+                ReportEventKind::Corrected => TimelineEventKind::Corrected,
+                ReportEventKind::Lapsed => TimelineEventKind::Lapsed,
+                ReportEventKind::Reactivated => TimelineEventKind::Reactivated,
+                ReportEventKind::Created
+                | ReportEventKind::Claimed
+                | ReportEventKind::Released
+                | ReportEventKind::Expired
+                | ReportEventKind::Cleared
+                | ReportEventKind::Deleted => continue,
+            };
+            events.push(TimelineEvent {
+                kind,
+                user_id: event.actor_id,
+                at: event.occurred_at,
+                reason: event.reason,
+            });
+        }
+        events.sort_by_key(|event| event.at);
+
+        Ok(ReportTimelineResponse { report_id, events })
+    }
+
+    /// Claim several reports as one cleanup run. Each report is claimed or rejected on
+    /// its own; the run is only created if at least one claim succeeds.
+    pub async fn claim_reports_batch(
+        &self,
+        report_ids: &[Uuid],
+        user_id: Uuid,
+    ) -> Result<(Option<Uuid>, Vec<(Uuid, Result<LitterReport, String>)>), AppError> {
+        let mut unique_ids: Vec<Uuid> = Vec::with_capacity(report_ids.len());
+        for id in report_ids {
+            if !unique_ids.contains(id) {
+                unique_ids.push(*id);
+            }
+        }
+
+        if unique_ids.is_empty() || unique_ids.len() > MAX_BATCH_CLAIM {
+            return Err(AppError::BadRequest(format!(
+                "A batch must contain between 1 and {} reports",
+                MAX_BATCH_CLAIM
+            )));
+        }
+
+        self.check_abandoned_claims(user_id).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let run_id = sqlx::query_scalar!(
+            "INSERT INTO cleanup_runs (user_id) VALUES ($1) RETURNING id",
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut results = Vec::with_capacity(unique_ids.len());
+        let mut claimed_count = 0;
+
+        for report_id in unique_ids {
+            if self.abandoned_claims_on(report_id, user_id).await? >= MAX_ABANDONS_PER_REPORT {
+                results.push((
+                    report_id,
+                    Err(
+                        "You have given up this report too many times to claim it again"
+                            .to_string(),
+                    ),
+                ));
+                continue;
+            }
+            if self.is_hidden(report_id).await? {
+                results.push((report_id, Err(HIDDEN_REPORT_MESSAGE.to_string())));
+                continue;
+            }
+
+            // The status check and the claim happen in one statement, so a concurrent
+            // claimer cannot take the same report between them
+            let claimed = sqlx::query_as!(
+                LitterReport,
+                r#"
+                UPDATE litter_reports
+                SET status = $1,
+                    claimed_by = $2,
+                    claimed_at = $3,
+                    cleanup_run_id = $4
+                WHERE id = $5 AND status = 'pending' AND reporter_id IS DISTINCT FROM $2
+                RETURNING
+                    id, reporter_id,
+                    ST_Y(location)::double precision as "latitude!",
+                    ST_X(location)::double precision as "longitude!",
+                    description,
+                    category as "category: LitterCategory", severity as "severity: LitterSeverity",
+                    photo_before, status as "status: ReportStatus",
+                    claimed_by, claimed_at, cleared_by, cleared_at,
+                    photo_after, created_at, updated_at, address,
+                    address_street, address_city, address_country,
+                    photo_before_width, photo_before_height, photo_before_blurhash,
+                    photo_after_width, photo_after_height, photo_after_blurhash
+                "#,
+                ReportStatus::Claimed as ReportStatus,
+                user_id,
+                Utc::now(),
+                run_id,
+                report_id
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let result = match claimed {
+                Some(report) => {
+                    Self::record_claim(&mut tx, report_id, user_id).await?;
+                    let event = NewReportEvent {
+                        actor_id: Some(user_id),
+                        metadata: serde_json::json!({ "cleanup_run_id": run_id }),
+                        ..NewReportEvent::status_change(
+                            ReportTransition::Claim,
+                            &ReportStatus::Pending,
+                            &ReportStatus::Claimed,
+                        )
+                    };
+                    Self::record_event(&mut tx, report_id, event).await?;
+                    claimed_count += 1;
+                    Ok(report)
+                }
+                None => {
+                    let reporter_id = sqlx::query_scalar!(
+                        "SELECT reporter_id FROM litter_reports WHERE id = $1",
+                        report_id
+                    )
+                    .fetch_optional(&mut *tx)
+                    .await?;
+
+                    Err(match reporter_id {
+                        None => "Report not found".to_string(),
+                        Some(reporter_id) if reporter_id == Some(user_id) => {
+                            "Cannot claim your own report".to_string()
+                        }
+                        Some(_) => "Report is not available for claiming".to_string(),
+                    })
+                }
+            };
+            results.push((report_id, result));
+        }
+
+        if claimed_count == 0 {
+            tx.rollback().await?;
+            return Ok((None, results));
+        }
+
+        tx.commit().await?;
+
+        Ok((Some(run_id), results))
+    }
+
+    /// Combined progress and points for a cleanup run
+    pub async fn get_cleanup_run(&self, run_id: Uuid) -> Result<CleanupRunResponse, AppError> {
+        let run = sqlx::query!(
+            r#"
+            SELECT
+                r.id, r.user_id, r.created_at,
+                COUNT(lr.id) AS "total_reports!",
+                COUNT(lr.id) FILTER (WHERE lr.status = 'claimed') AS "claimed_reports!",
+                COUNT(lr.id) FILTER (WHERE lr.cleared_by = r.user_id) AS "cleared_reports!",
+                MIN(lr.cleared_at) FILTER (WHERE lr.cleared_by = r.user_id) AS first_cleared_at,
+                MAX(lr.cleared_at) FILTER (WHERE lr.cleared_by = r.user_id) AS last_cleared_at
+            FROM cleanup_runs r
+            LEFT JOIN litter_reports lr ON lr.cleanup_run_id = r.id
+            WHERE r.id = $1
+            GROUP BY r.id
+            "#,
+            run_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Cleanup run not found".to_string()))?;
+
+        let points_earned = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(se.points), 0) AS "points!"
+            FROM score_events se
+            JOIN litter_reports lr ON lr.id = se.report_id
+            WHERE lr.cleanup_run_id = $1 AND se.user_id = $2 AND se.kind = 'clear'
+            "#,
+            run_id,
+            run.user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let reports = sqlx::query_as!(
+            LitterReport,
+            r#"
+            SELECT
+                id, reporter_id,
+                ST_Y(location)::double precision as "latitude!",
+                ST_X(location)::double precision as "longitude!",
+                description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
+                photo_before, status as "status: ReportStatus",
+                claimed_by, claimed_at, cleared_by, cleared_at,
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
+            FROM litter_reports
+            WHERE cleanup_run_id = $1
+            ORDER BY claimed_at, id
+            "#,
+            run_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(CleanupRunResponse {
+            id: run.id,
+            user_id: run.user_id,
+            created_at: run.created_at,
+            total_reports: run.total_reports,
+            claimed_reports: run.claimed_reports,
+            cleared_reports: run.cleared_reports,
+            points_earned,
+            first_cleared_at: run.first_cleared_at,
+            last_cleared_at: run.last_cleared_at,
+            reports: reports.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Mark a report as cleared with after photo
+    pub async fn clear_report(
+        &self,
+        report_id: Uuid,
+        user_id: Uuid,
+        request: ClearReportRequest,
+    ) -> Result<LitterReport, AppError> {
+        // Check current status
+        let current_report = self.get_report_by_id(report_id).await?;
+        let status = ReportTransition::Clear.apply(&current_report.status)?;
 
         if current_report.claimed_by != Some(user_id) {
             return Err(AppError::Forbidden(
@@ -350,14 +1871,20 @@ impl ReportService {
             ));
         }
 
-        // Process the after photo (async to avoid blocking)
-        let processed_image = self.image_service.process_image(photo_base64).await?;
+        // Process the after photos (async to avoid blocking)
+        let mut processed = vec![
+            self.process_photo(user_id, request.photo_base64, request.photo_upload_id)
+                .await?,
+        ];
+        processed.extend(
+            self.process_extra_photos(request.extra_photos_base64)
+                .await?,
+        );
 
-        // Upload to S3
-        let photo_url = self
-            .s3_service
-            .upload_image(processed_image, "reports/after")
-            .await?;
+        let photos = self.save_photos(processed).await?;
+        let primary = &photos[0].metadata;
+
+        let mut tx = self.pool.begin().await?;
 
         // Update the report
         let report = sqlx::query_as!(
@@ -367,30 +1894,538 @@ impl ReportService {
             SET status = $1,
                 cleared_by = $2,
                 cleared_at = $3,
-                photo_after = $4
-            WHERE id = $5
+                photo_after = $4,
+                photo_after_width = $5,
+                photo_after_height = $6,
+                photo_after_blurhash = $7,
+                address = COALESCE(address, $9),
+                address_street = COALESCE(address_street, $10),
+                address_city = COALESCE(address_city, $11),
+                address_country = COALESCE(address_country, $12)
+            WHERE id = $8
             RETURNING
                 id, reporter_id,
                 ST_Y(location)::double precision as "latitude!",
                 ST_X(location)::double precision as "longitude!",
                 description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
                 photo_before, status as "status: ReportStatus",
                 claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
             "#,
             ReportStatus::Cleared as ReportStatus,
             user_id,
             chrono::Utc::now(),
-            photo_url,
+            primary.url,
+            primary.width,
+            primary.height,
+            primary.blurhash,
+            report_id,
+            // The clear shares the report's address, which the geocode queue fills in
+            None::<String>,
+            None::<String>,
+            None::<String>,
+            None::<String>
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::insert_photo_rows(&mut tx, report_id, PhotoKind::After, 0, &photos).await?;
+        Self::end_claim(&mut tx, report_id, ClaimOutcome::Cleared, None).await?;
+        Self::record_status_change(
+            &mut tx,
+            report_id,
+            ReportTransition::Clear,
+            &current_report.status,
+            &status,
+            Some(user_id),
+            None,
+        )
+        .await?;
+        // A report whose lookup gave up gets another go
+        let needs_address = current_report.address.is_none();
+        if needs_address {
+            GeocodeQueueService::enqueue(&mut tx, report_id).await?;
+        }
+        tx.commit().await?;
+        if needs_address {
+            self.geocode_queue.spawn_resolve(report_id);
+        }
+
+        self.record_photo_hashes(user_id, report.id, &photos)
+            .await?;
+
+        Ok(report)
+    }
+
+    /// Replace the after photo of a cleared report while a verifier's request for a
+    /// better one is open
+    pub async fn replace_after_photo(
+        &self,
+        report_id: Uuid,
+        user_id: Uuid,
+        request: ClearReportRequest,
+    ) -> Result<LitterReport, AppError> {
+        let current_report = self.get_report_by_id(report_id).await?;
+
+        if current_report.cleared_by != Some(user_id) {
+            return Err(AppError::Forbidden(
+                "Only the user who cleared this report can replace its photo".to_string(),
+            ));
+        }
+
+        if current_report.status != ReportStatus::Cleared {
+            return Err(AppError::BadRequest(
+                "Only a cleared report's photo can be replaced".to_string(),
+            ));
+        }
+
+        let retake_id = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM photo_retake_requests
+            WHERE report_id = $1 AND fulfilled_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
             report_id
         )
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(
+                "No better photo has been requested for this report, or the window has passed"
+                    .to_string(),
+            )
+        })?;
+
+        let mut processed = vec![
+            self.process_photo(user_id, request.photo_base64, request.photo_upload_id)
+                .await?,
+        ];
+        processed.extend(
+            self.process_extra_photos(request.extra_photos_base64)
+                .await?,
+        );
+        let photos = self.save_photos(processed).await?;
+        let primary = &photos[0].metadata;
+
+        let mut tx = self.pool.begin().await?;
+
+        let report = sqlx::query_as!(
+            LitterReport,
+            r#"
+            UPDATE litter_reports
+            SET photo_after = $1,
+                photo_after_width = $2,
+                photo_after_height = $3,
+                photo_after_blurhash = $4
+            WHERE id = $5
+            RETURNING
+                id, reporter_id,
+                ST_Y(location)::double precision as "latitude!",
+                ST_X(location)::double precision as "longitude!",
+                description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
+                photo_before, status as "status: ReportStatus",
+                claimed_by, claimed_at, cleared_by, cleared_at,
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
+            "#,
+            primary.url,
+            primary.width,
+            primary.height,
+            primary.blurhash,
+            report_id
+        )
+        .fetch_one(&mut *tx)
         .await?;
+
+        // The new set replaces every old after photo
+        sqlx::query!(
+            "DELETE FROM report_photos WHERE report_id = $1 AND kind = 'after'",
+            report_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        Self::insert_photo_rows(&mut tx, report_id, PhotoKind::After, 0, &photos).await?;
+
+        sqlx::query!(
+            "UPDATE photo_retake_requests SET fulfilled_at = NOW() WHERE id = $1",
+            retake_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        self.record_photo_hashes(user_id, report.id, &photos)
+            .await?;
+
         Ok(report)
     }
 
-    /// Get all reports by a user (as reporter)
-    pub async fn get_user_reports(&self, user_id: Uuid) -> Result<Vec<LitterReport>, AppError> {
+    /// Let the reporter fix a report's description, category or first before photo
+    /// while nobody has claimed it. Each edit is kept in `report_edits` for moderators.
+    pub async fn edit_report(
+        &self,
+        report_id: Uuid,
+        user_id: Uuid,
+        request: UpdateReportRequest,
+    ) -> Result<LitterReport, AppError> {
+        if request.is_empty() {
+            return Err(AppError::BadRequest(
+                "Send a description, category or photo to change".to_string(),
+            ));
+        }
+
+        let current_report = self.get_report_by_id(report_id).await?;
+        Self::check_reporter_may_change(&current_report, user_id, "edited")?;
+
+        let photos = if request.replaces_photo() {
+            let processed = self
+                .process_photo(user_id, request.photo_base64, request.photo_upload_id)
+                .await?;
+            self.save_photos(vec![processed]).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        // Lock the row so a claim cannot slip in between the check and the update
+        let current_report = sqlx::query_as::<_, LitterReport>(&format!(
+            "{LITTER_REPORT_SELECT} WHERE id = $1 FOR UPDATE"
+        ))
+        .bind(report_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+        Self::check_reporter_may_change(&current_report, user_id, "edited")?;
+
+        let description = match request.description {
+            Some(description) => Some(description.trim().to_string()).filter(|d| !d.is_empty()),
+            None => current_report.description.clone(),
+        };
+        let category = request.category.unwrap_or(current_report.category);
+        let photo = photos.first().map(|photo| &photo.metadata);
+
+        sqlx::query(
+            r"
+            UPDATE litter_reports
+            SET description = $1,
+                category = $2,
+                photo_before = COALESCE($3, photo_before),
+                photo_before_width = CASE WHEN $3 IS NULL THEN photo_before_width ELSE $4 END,
+                photo_before_height = CASE WHEN $3 IS NULL THEN photo_before_height ELSE $5 END,
+                photo_before_blurhash = CASE WHEN $3 IS NULL THEN photo_before_blurhash ELSE $6 END,
+                updated_at = NOW()
+            WHERE id = $7
+            ",
+        )
+        .bind(&description)
+        .bind(category)
+        .bind(photo.map(|p| &p.url))
+        .bind(photo.and_then(|p| p.width))
+        .bind(photo.and_then(|p| p.height))
+        .bind(photo.and_then(|p| p.blurhash.as_ref()))
+        .bind(report_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(photo) = photo {
+            sqlx::query(
+                r"
+                UPDATE report_photos
+                SET url = $1, width = $2, height = $3, blurhash = $4
+                WHERE report_id = $5 AND kind = 'before' AND position = 0
+                ",
+            )
+            .bind(&photo.url)
+            .bind(photo.width)
+            .bind(photo.height)
+            .bind(&photo.blurhash)
+            .bind(report_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r"
+            INSERT INTO report_edits (
+                report_id, editor_id,
+                previous_description, new_description,
+                previous_category, new_category,
+                previous_photo, new_photo
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ",
+        )
+        .bind(report_id)
+        .bind(user_id)
+        .bind(&current_report.description)
+        .bind(&description)
+        .bind(current_report.category)
+        .bind(category)
+        .bind(&current_report.photo_before)
+        .bind(photo.map_or(current_report.photo_before.as_ref(), |p| Some(&p.url)))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        self.record_photo_hashes(user_id, report_id, &photos)
+            .await?;
+
+        self.get_report_by_id(report_id).await
+    }
+
+    /// Only the reporter may edit or cancel a report, and only until it is claimed.
+    /// `action` completes "Only a pending report can be ...".
+    fn check_reporter_may_change(
+        report: &LitterReport,
+        user_id: Uuid,
+        action: &str,
+    ) -> Result<(), AppError> {
+        if report.reporter_id != Some(user_id) {
+            return Err(AppError::Forbidden(
+                "Only the reporter can change this report".to_string(),
+            ));
+        }
+        if report.status != ReportStatus::Pending {
+            return Err(AppError::BadRequest(format!(
+                "Only a pending report can be {action}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Withdraw a report its reporter made by mistake. The report stays, as `cancelled`,
+    /// but its photos are removed, and deleted from storage unless something else uses them.
+    pub async fn cancel_report(
+        &self,
+        report_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<LitterReport, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let current_report = sqlx::query_as::<_, LitterReport>(&format!(
+            "{LITTER_REPORT_SELECT} WHERE id = $1 FOR UPDATE"
+        ))
+        .bind(report_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+        Self::check_reporter_may_change(&current_report, user_id, "cancelled")?;
+        let status = ReportTransition::Cancel.apply(&current_report.status)?;
+
+        let mut photo_urls: Vec<String> =
+            sqlx::query_scalar("DELETE FROM report_photos WHERE report_id = $1 RETURNING url")
+                .bind(report_id)
+                .fetch_all(&mut *tx)
+                .await?;
+        photo_urls.extend(current_report.photo_before);
+
+        sqlx::query("DELETE FROM report_photo_hashes WHERE report_id = $1")
+            .bind(report_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+            UPDATE litter_reports
+            SET status = 'cancelled',
+                photo_before = NULL,
+                photo_before_width = NULL,
+                photo_before_height = NULL,
+                photo_before_blurhash = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            ",
+        )
+        .bind(report_id)
+        .execute(&mut *tx)
+        .await?;
+
+        Self::record_status_change(
+            &mut tx,
+            report_id,
+            ReportTransition::Cancel,
+            &current_report.status,
+            &status,
+            Some(user_id),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
+
+        // The report is cancelled either way; a failed cleanup only leaves files behind
+        photo_urls.sort();
+        photo_urls.dedup();
+        if let Err(e) = self.image_storage.delete_unreferenced(&photo_urls).await {
+            tracing::warn!("Failed to delete photos of cancelled report {report_id}: {e:?}");
+        }
+
+        self.get_report_by_id(report_id).await
+    }
+
+    /// Every edit to a report, oldest first
+    pub async fn get_edits(&self, report_id: Uuid) -> Result<Vec<ReportEdit>, AppError> {
+        let edits = sqlx::query_as::<_, ReportEdit>(
+            r"
+            SELECT
+                id, report_id, editor_id,
+                previous_description, new_description,
+                previous_category, new_category,
+                previous_photo, new_photo, edited_at
+            FROM report_edits
+            WHERE report_id = $1
+            ORDER BY edited_at, id
+            ",
+        )
+        .bind(report_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(edits)
+    }
+
+    /// A report's before and after photos in display order
+    pub async fn get_photos(&self, report_id: Uuid) -> Result<ReportPhotos, AppError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT kind as "kind: PhotoKind", url, width, height, blurhash
+            FROM report_photos
+            WHERE report_id = $1
+            ORDER BY kind, position
+            "#,
+            report_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let urls: Vec<String> = rows.iter().map(|row| row.url.clone()).collect();
+        let pending = self.image_storage.pending_urls(&urls).await?;
+
+        let mut photos = ReportPhotos::default();
+        for row in rows {
+            let metadata = ImageMetadata {
+                processing: pending.contains(&row.url),
+                url: row.url,
+                width: row.width,
+                height: row.height,
+                blurhash: row.blurhash,
+            };
+            match row.kind {
+                PhotoKind::Before => photos.before.push(metadata),
+                PhotoKind::After => photos.after.push(metadata),
+            }
+        }
+
+        Ok(photos)
+    }
+
+    /// Add a photo after the fact: the reporter's before photos until the report is
+    /// cleared, the clearer's after photos until it is verified
+    pub async fn add_photo(
+        &self,
+        report_id: Uuid,
+        user_id: Uuid,
+        request: AddReportPhotoRequest,
+    ) -> Result<ReportPhotos, AppError> {
+        let report = self.get_report_by_id(report_id).await?;
+
+        let (owner, open) = match request.kind {
+            PhotoKind::Before => (
+                report.reporter_id,
+                matches!(report.status, ReportStatus::Pending | ReportStatus::Claimed),
+            ),
+            PhotoKind::After => (report.cleared_by, report.status == ReportStatus::Cleared),
+        };
+        if owner != Some(user_id) {
+            return Err(AppError::Forbidden(
+                "Only the reporter can add before photos and only the clearer after photos"
+                    .to_string(),
+            ));
+        }
+        if !open {
+            return Err(AppError::BadRequest(
+                "Photos of this kind can no longer be added to this report".to_string(),
+            ));
+        }
+
+        let processed = self
+            .process_photo(user_id, request.photo_base64, request.photo_upload_id)
+            .await?;
+        let photos = self.save_photos(vec![processed]).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        // Serialise additions to the same report so positions stay unique
+        sqlx::query!(
+            "SELECT id FROM litter_reports WHERE id = $1 FOR UPDATE",
+            report_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let existing = sqlx::query!(
+            r#"
+            SELECT COUNT(*) AS "count!", COALESCE(MAX(position) + 1, 0) AS "next_position!"
+            FROM report_photos
+            WHERE report_id = $1 AND kind = $2
+            "#,
+            report_id,
+            request.kind as PhotoKind
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if existing.count >= MAX_PHOTOS_PER_KIND as i64 {
+            return Err(AppError::BadRequest(format!(
+                "A report can have at most {MAX_PHOTOS_PER_KIND} {} photos",
+                match request.kind {
+                    PhotoKind::Before => "before",
+                    PhotoKind::After => "after",
+                }
+            )));
+        }
+
+        Self::insert_photo_rows(
+            &mut tx,
+            report_id,
+            request.kind,
+            existing.next_position,
+            &photos,
+        )
+        .await?;
+        tx.commit().await?;
+
+        self.record_photo_hashes(user_id, report_id, &photos)
+            .await?;
+
+        self.get_photos(report_id).await
+    }
+
+    /// Get one page of reports by a user (as reporter), with the total count
+    pub async fn get_user_reports(
+        &self,
+        user_id: Uuid,
+        after: Option<KeysetCursor>,
+        limit: i64,
+    ) -> Result<(Vec<LitterReport>, i64), AppError> {
+        let total = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM litter_reports WHERE reporter_id = $1",
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(0);
+
         let reports = sqlx::query_as!(
             LitterReport,
             r#"
@@ -399,26 +2434,45 @@ impl ReportService {
                 ST_Y(location)::double precision as "latitude!",
                 ST_X(location)::double precision as "longitude!",
                 description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
                 photo_before, status as "status: ReportStatus",
                 claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
             FROM litter_reports
             WHERE reporter_id = $1
-            ORDER BY created_at DESC
+            AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4::uuid))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
             "#,
-            user_id
+            user_id,
+            limit,
+            after.map(|c| c.at),
+            after.map(|c| c.id)
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(reports)
+        Ok((reports, total))
     }
 
-    /// Get all reports cleared by a user
+    /// Get one page of reports cleared by a user, with the total count
     pub async fn get_user_cleared_reports(
         &self,
         user_id: Uuid,
-    ) -> Result<Vec<LitterReport>, AppError> {
+        after: Option<KeysetCursor>,
+        limit: i64,
+    ) -> Result<(Vec<LitterReport>, i64), AppError> {
+        let total = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM litter_reports WHERE cleared_by = $1",
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(0);
+
         let reports = sqlx::query_as!(
             LitterReport,
             r#"
@@ -427,18 +2481,27 @@ impl ReportService {
                 ST_Y(location)::double precision as "latitude!",
                 ST_X(location)::double precision as "longitude!",
                 description,
+                category as "category: LitterCategory", severity as "severity: LitterSeverity",
                 photo_before, status as "status: ReportStatus",
                 claimed_by, claimed_at, cleared_by, cleared_at,
-                photo_after, created_at, updated_at, address
+                photo_after, created_at, updated_at, address,
+                address_street, address_city, address_country,
+                photo_before_width, photo_before_height, photo_before_blurhash,
+                photo_after_width, photo_after_height, photo_after_blurhash
             FROM litter_reports
             WHERE cleared_by = $1
-            ORDER BY cleared_at DESC
+            AND ($3::timestamptz IS NULL OR (COALESCE(cleared_at, created_at), id) < ($3, $4::uuid))
+            ORDER BY COALESCE(cleared_at, created_at) DESC, id DESC
+            LIMIT $2
             "#,
-            user_id
+            user_id,
+            limit,
+            after.map(|c| c.at),
+            after.map(|c| c.id)
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(reports)
+        Ok((reports, total))
     }
 }
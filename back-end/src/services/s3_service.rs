@@ -1,16 +1,38 @@
-use crate::config::S3Config;
+use crate::config::{S3BucketConfig, S3Config};
 use crate::error::{AppError, Result};
 use aws_config::BehaviorVersion;
 use aws_sdk_s3::{
     config::{Credentials, Region},
     primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart, MetadataDirective, StorageClass},
     Client,
 };
+use std::cmp::Reverse;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
 use uuid::Uuid;
 
+/// The kinds of object kept in storage, each of which can live in its own bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageArea {
+    Avatars,
+    /// Report and feed photos, plus the chunked uploads they are built from
+    ReportPhotos,
+    /// Personal data exports; only ever served through the API
+    Exports,
+}
+
+impl StorageArea {
+    pub const ALL: [Self; 3] = [Self::Avatars, Self::ReportPhotos, Self::Exports];
+
+    /// Whether the bucket is readable by anyone with an object's URL
+    #[must_use]
+    pub fn is_public(self) -> bool {
+        !matches!(self, Self::Exports)
+    }
+}
+
 #[derive(Clone)]
 pub struct S3Service {
     client: Arc<Client>,
@@ -44,29 +66,42 @@ impl S3Service {
         })
     }
 
-    /// Initialize the S3 bucket (create if doesn't exist)
+    /// Initialize every configured bucket (create if doesn't exist)
     pub async fn initialize(&self) -> Result<()> {
+        let mut buckets: Vec<(&str, bool)> = Vec::new();
+        for area in StorageArea::ALL {
+            let bucket = self.bucket_config(area).bucket.as_str();
+            match buckets.iter_mut().find(|(name, _)| *name == bucket) {
+                Some((_, public)) => *public |= area.is_public(),
+                None => buckets.push((bucket, area.is_public())),
+            }
+        }
+
+        for (bucket, public) in buckets {
+            self.initialize_bucket(bucket, public).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Create a bucket if it doesn't exist, with a public read policy when asked
+    async fn initialize_bucket(&self, bucket: &str, public: bool) -> Result<()> {
         for attempt in 1..=5 {
             let bucket_exists = self
                 .client
                 .head_bucket()
-                .bucket(&self.config.bucket)
+                .bucket(bucket)
                 .send()
                 .await
                 .is_ok();
 
             if bucket_exists {
-                tracing::info!("S3 bucket already exists: {}", self.config.bucket);
+                tracing::info!("S3 bucket already exists: {}", bucket);
                 return Ok(());
             }
 
-            tracing::info!("Creating S3 bucket: {}", self.config.bucket);
-            let create_result = self
-                .client
-                .create_bucket()
-                .bucket(&self.config.bucket)
-                .send()
-                .await;
+            tracing::info!("Creating S3 bucket: {}", bucket);
+            let create_result = self.client.create_bucket().bucket(bucket).send().await;
 
             if let Err(err) = create_result {
                 if attempt == 5 {
@@ -79,8 +114,9 @@ impl S3Service {
                 continue;
             }
 
-            let policy = format!(
-                r#"{{
+            if public {
+                let policy = format!(
+                    r#"{{
                     "Version": "2012-10-17",
                     "Statement": [
                         {{
@@ -91,54 +127,109 @@ impl S3Service {
                         }}
                     ]
                 }}"#,
-                self.config.bucket
-            );
+                    bucket
+                );
 
-            self.client
-                .put_bucket_policy()
-                .bucket(&self.config.bucket)
-                .policy(policy)
-                .send()
-                .await
-                .map_err(|e| {
-                    AppError::Internal(anyhow::anyhow!("Failed to set bucket policy: {}", e))
-                })?;
+                self.client
+                    .put_bucket_policy()
+                    .bucket(bucket)
+                    .policy(policy)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!("Failed to set bucket policy: {}", e))
+                    })?;
+            }
 
-            tracing::info!("Bucket created and configured successfully");
+            tracing::info!("Bucket {} created and configured successfully", bucket);
             return Ok(());
         }
 
         Ok(())
     }
 
+    fn bucket_config(&self, area: StorageArea) -> &S3BucketConfig {
+        match area {
+            StorageArea::Avatars => &self.config.avatars,
+            StorageArea::ReportPhotos => &self.config.report_photos,
+            StorageArea::Exports => &self.config.exports,
+        }
+    }
+
+    fn bucket(&self, area: StorageArea) -> &str {
+        &self.bucket_config(area).bucket
+    }
+
+    /// Full object key for `name` under the configured environment/tenant prefix.
+    /// Keys are stored as returned, so changing the prefix later leaves existing objects reachable.
+    #[must_use]
+    pub fn object_key(&self, name: &str) -> String {
+        if self.config.key_prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.config.key_prefix, name)
+        }
+    }
+
+    /// Public URL of an object
+    #[must_use]
+    pub fn public_url(&self, area: StorageArea, key: &str) -> String {
+        format!("{}/{}", self.bucket_config(area).public_url, key)
+    }
+
     /// Upload image to S3 and return the public URL
     /// Takes processed WebP image data
-    pub async fn upload_image(&self, image_data: Vec<u8>, prefix: &str) -> Result<String> {
+    pub async fn upload_image(
+        &self,
+        area: StorageArea,
+        image_data: Vec<u8>,
+        prefix: &str,
+    ) -> Result<String> {
         // Generate unique filename
-        let filename = format!("{}/{}.webp", prefix, Uuid::new_v4());
+        let key = self.object_key(&format!("{}/{}.webp", prefix, Uuid::new_v4()));
+
+        self.put_image(area, &key, image_data).await
+    }
+
+    /// Upload WebP image data under a specific key and return the public URL
+    pub async fn put_image(
+        &self,
+        area: StorageArea,
+        key: &str,
+        image_data: Vec<u8>,
+    ) -> Result<String> {
+        self.put_object(area, key, image_data, "image/webp").await?;
 
-        // Upload to S3
+        Ok(self.public_url(area, key))
+    }
+
+    /// Upload arbitrary data under a specific key
+    pub async fn put_object(
+        &self,
+        area: StorageArea,
+        key: &str,
+        data: Vec<u8>,
+        content_type: &str,
+    ) -> Result<()> {
         self.client
             .put_object()
-            .bucket(&self.config.bucket)
-            .key(&filename)
-            .body(ByteStream::from(image_data))
-            .content_type("image/webp")
+            .bucket(self.bucket(area))
+            .key(key)
+            .body(ByteStream::from(data))
+            .content_type(content_type)
             .send()
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to upload to S3: {}", e)))?;
 
-        // Return public URL
-        let url = format!("{}/{}", self.config.public_url, filename);
-        Ok(url)
+        Ok(())
     }
 
     /// Get image data from S3
-    pub async fn get_image(&self, key: &str) -> Result<Vec<u8>> {
+    pub async fn get_image(&self, area: StorageArea, key: &str) -> Result<Vec<u8>> {
         let response = self
             .client
             .get_object()
-            .bucket(&self.config.bucket)
+            .bucket(self.bucket(area))
             .key(key)
             .send()
             .await
@@ -158,10 +249,10 @@ impl S3Service {
     }
 
     /// Delete image from S3
-    pub async fn delete_image(&self, key: &str) -> Result<()> {
+    pub async fn delete_image(&self, area: StorageArea, key: &str) -> Result<()> {
         self.client
             .delete_object()
-            .bucket(&self.config.bucket)
+            .bucket(self.bucket(area))
             .key(key)
             .send()
             .await
@@ -170,9 +261,155 @@ impl S3Service {
         Ok(())
     }
 
-    /// Extract S3 key from public URL
-    pub fn extract_key_from_url(&self, url: &str) -> Option<String> {
-        url.strip_prefix(&format!("{}/", self.config.public_url))
+    /// Move an object to another storage class (e.g. `STANDARD_IA`) by copying it onto
+    /// itself; its content and metadata are unchanged
+    pub async fn set_storage_class(
+        &self,
+        area: StorageArea,
+        key: &str,
+        storage_class: &str,
+    ) -> Result<()> {
+        let bucket = self.bucket(area);
+        self.client
+            .copy_object()
+            .bucket(bucket)
+            .key(key)
+            .copy_source(format!("{bucket}/{key}"))
+            .storage_class(StorageClass::from(storage_class))
+            .metadata_directive(MetadataDirective::Copy)
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to change S3 storage class: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Start a multipart upload and return its upload id
+    pub async fn create_multipart_upload(&self, area: StorageArea, key: &str) -> Result<String> {
+        let output = self
+            .client
+            .create_multipart_upload()
+            .bucket(self.bucket(area))
+            .key(key)
+            .content_type("application/octet-stream")
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to start multipart upload: {}", e))
+            })?;
+
+        output
+            .upload_id()
             .map(String::from)
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("S3 returned no upload id")))
+    }
+
+    /// Upload one part of a multipart upload and return its ETag
+    pub async fn upload_part(
+        &self,
+        area: StorageArea,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<String> {
+        let output = self
+            .client
+            .upload_part()
+            .bucket(self.bucket(area))
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to upload part: {}", e)))?;
+
+        output
+            .e_tag()
+            .map(String::from)
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("S3 returned no part ETag")))
+    }
+
+    /// Assemble uploaded parts, given as `(part_number, etag)` in order
+    pub async fn complete_multipart_upload(
+        &self,
+        area: StorageArea,
+        key: &str,
+        upload_id: &str,
+        parts: Vec<(i32, String)>,
+    ) -> Result<()> {
+        let parts = parts
+            .into_iter()
+            .map(|(part_number, etag)| {
+                CompletedPart::builder()
+                    .part_number(part_number)
+                    .e_tag(etag)
+                    .build()
+            })
+            .collect();
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(self.bucket(area))
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!(
+                    "Failed to complete multipart upload: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    /// Abandon a multipart upload, discarding any parts already stored
+    pub async fn abort_multipart_upload(
+        &self,
+        area: StorageArea,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<()> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(self.bucket(area))
+            .key(key)
+            .upload_id(upload_id)
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to abort multipart upload: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Extract the storage area and S3 key from a public URL.
+    /// The longest matching base URL wins, so a bucket served from a sub-path of another
+    /// bucket's URL still resolves to the right one; areas sharing a bucket share its URL,
+    /// so any of them will do.
+    #[must_use]
+    pub fn extract_key_from_url(&self, url: &str) -> Option<(StorageArea, String)> {
+        StorageArea::ALL
+            .into_iter()
+            .filter_map(|area| {
+                let base = self.bucket_config(area).public_url.trim_end_matches('/');
+                url.strip_prefix(base)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .filter(|key| !key.is_empty())
+                    .map(|key| (base.len(), area, key.to_string()))
+            })
+            .min_by_key(|(base_len, _, _)| Reverse(*base_len))
+            .map(|(_, area, key)| (area, key))
     }
 }
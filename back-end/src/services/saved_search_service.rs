@@ -0,0 +1,202 @@
+use crate::error::AppError;
+use crate::models::report::{LitterCategory, ReportStatus};
+use crate::models::saved_search::{
+    CreateSavedSearchRequest, SavedSearch, UpdateSavedSearchRequest,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum number of saved searches a single user may keep
+const MAX_SAVED_SEARCHES_PER_USER: i64 = 20;
+
+/// Categories as stored in `saved_searches.categories`
+fn category_names(categories: &[LitterCategory]) -> Vec<String> {
+    categories
+        .iter()
+        .map(|category| category.as_str().to_string())
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct SavedSearchService {
+    pool: PgPool,
+}
+
+impl SavedSearchService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all saved searches belonging to a user
+    pub async fn list_searches(&self, user_id: Uuid) -> Result<Vec<SavedSearch>, AppError> {
+        let searches = sqlx::query_as!(
+            SavedSearch,
+            r#"
+            SELECT id, user_id, name, radius_km,
+                   statuses as "statuses: Vec<ReportStatus>",
+                   categories, notify_on_new, created_at, updated_at
+            FROM saved_searches
+            WHERE user_id = $1
+            ORDER BY created_at ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(searches)
+    }
+
+    /// Get a saved search, ensuring it belongs to the user
+    pub async fn get_search(
+        &self,
+        search_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<SavedSearch, AppError> {
+        let search = sqlx::query_as!(
+            SavedSearch,
+            r#"
+            SELECT id, user_id, name, radius_km,
+                   statuses as "statuses: Vec<ReportStatus>",
+                   categories, notify_on_new, created_at, updated_at
+            FROM saved_searches
+            WHERE id = $1 AND user_id = $2
+            "#,
+            search_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+        Ok(search)
+    }
+
+    /// Create a new saved search
+    pub async fn create_search(
+        &self,
+        user_id: Uuid,
+        request: CreateSavedSearchRequest,
+    ) -> Result<SavedSearch, AppError> {
+        let existing = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM saved_searches WHERE user_id = $1",
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .unwrap_or(0);
+
+        if existing >= MAX_SAVED_SEARCHES_PER_USER {
+            return Err(AppError::BadRequest(format!(
+                "You can save at most {MAX_SAVED_SEARCHES_PER_USER} searches"
+            )));
+        }
+
+        let statuses = request
+            .statuses
+            .unwrap_or_else(|| vec![ReportStatus::Pending, ReportStatus::Claimed]);
+        let categories = category_names(&request.categories.unwrap_or_default());
+
+        let search = sqlx::query_as!(
+            SavedSearch,
+            r#"
+            INSERT INTO saved_searches (user_id, name, radius_km, statuses, categories, notify_on_new)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, radius_km,
+                      statuses as "statuses: Vec<ReportStatus>",
+                      categories, notify_on_new, created_at, updated_at
+            "#,
+            user_id,
+            request.name.trim(),
+            request.radius_km.unwrap_or(5.0),
+            &statuses as &[ReportStatus],
+            &categories,
+            request.notify_on_new.unwrap_or(false)
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(search)
+    }
+
+    /// Update a saved search (ownership required)
+    pub async fn update_search(
+        &self,
+        search_id: Uuid,
+        user_id: Uuid,
+        request: UpdateSavedSearchRequest,
+    ) -> Result<SavedSearch, AppError> {
+        let categories = request.categories.as_deref().map(category_names);
+
+        let search = sqlx::query_as!(
+            SavedSearch,
+            r#"
+            UPDATE saved_searches
+            SET name = COALESCE($3, name),
+                radius_km = COALESCE($4, radius_km),
+                statuses = COALESCE($5, statuses),
+                categories = COALESCE($6, categories),
+                notify_on_new = COALESCE($7, notify_on_new)
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, name, radius_km,
+                      statuses as "statuses: Vec<ReportStatus>",
+                      categories, notify_on_new, created_at, updated_at
+            "#,
+            search_id,
+            user_id,
+            request.name.as_deref().map(str::trim),
+            request.radius_km,
+            request.statuses.as_deref() as Option<&[ReportStatus]>,
+            categories.as_deref(),
+            request.notify_on_new
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+        Ok(search)
+    }
+
+    /// Delete a saved search (ownership required)
+    pub async fn delete_search(&self, search_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM saved_searches WHERE id = $1 AND user_id = $2",
+            search_id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Saved search not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Active users, other than the reporter, with a notifying saved search the report
+    /// matches within its radius of their home area
+    pub async fn subscribers_of(&self, report_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let subscribers = sqlx::query_scalar(
+            r"
+            SELECT DISTINCT s.user_id
+            FROM litter_reports r
+            JOIN saved_searches s ON s.notify_on_new
+            JOIN user_home_areas h ON h.user_id = s.user_id
+            JOIN users u ON u.id = s.user_id
+            WHERE r.id = $1
+              AND s.user_id IS DISTINCT FROM r.reporter_id
+              AND u.is_active
+              AND r.status = ANY(s.statuses)
+              AND (cardinality(s.categories) = 0 OR r.category::text = ANY(s.categories))
+              AND ST_DWithin(h.location::geography, r.location::geography, s.radius_km * 1000)
+            ",
+        )
+        .bind(report_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(subscribers)
+    }
+}
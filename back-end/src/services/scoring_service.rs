@@ -1,10 +1,29 @@
 use crate::config::ScoringConfig;
 use crate::error::AppError;
+use crate::models::coordinates::Coordinates;
+use crate::models::report::LitterSeverity;
 use crate::models::score::UserScore;
+use crate::models::scoring_rules::{
+    ScoringRule, ScoringRuleKey, ScoringRulesResponse, FIRST_IN_AREA_RADIUS_M,
+    FIRST_IN_AREA_WINDOW_HOURS,
+};
 use chrono::{Duration, NaiveDate, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+/// Weight of a verifier with no settled verdicts yet
+pub const DEFAULT_VERIFIER_WEIGHT: f64 = 1.0;
+/// Weight a verifier approaches as their record of agreeing with the outcome grows
+pub const MAX_VERIFIER_WEIGHT: f64 = 2.0;
+
+/// How much a verifier's verdict counts, from how many of their `settled` verdicts
+/// `agreed` with the outcome. Starts at `DEFAULT_VERIFIER_WEIGHT` and moves towards
+/// `MAX_VERIFIER_WEIGHT` or zero as the record grows, so one verdict cannot swing it far.
+#[must_use]
+pub fn verifier_weight(agreed: i32, settled: i32) -> f64 {
+    MAX_VERIFIER_WEIGHT * f64::from(agreed + 1) / f64::from(settled + 2)
+}
+
 #[derive(Clone)]
 pub struct ScoringService {
     pool: PgPool,
@@ -22,14 +41,14 @@ impl ScoringService {
         &self,
         user_id: Uuid,
         report_id: Uuid,
-        latitude: f64,
-        longitude: f64,
+        location: Coordinates,
+        severity: LitterSeverity,
     ) -> Result<UserScore, AppError> {
         // Get or create user score
         let user_score = self.get_or_create_user_score(user_id).await?;
 
-        // Calculate base points
-        let mut points = self.config.base_points_per_clear;
+        // Calculate base points, scaled by how much litter there was
+        let mut points = self.severity_points(severity);
 
         // Calculate streak bonus
         let today = Utc::now().date_naive();
@@ -42,7 +61,7 @@ impl ScoringService {
         points += streak_bonus;
 
         // Check if this is the first clear in the area (1km radius, last 24 hours)
-        let is_first_in_area = self.is_first_clear_in_area(latitude, longitude).await?;
+        let is_first_in_area = self.is_first_clear_in_area(location).await?;
         if is_first_in_area {
             points += self.config.first_in_area_bonus;
         }
@@ -100,18 +119,33 @@ impl ScoringService {
         Ok(updated_score)
     }
 
-    /// Award points to a user who verified a report
+    /// Base points for clearing a report of the given severity
+    fn severity_points(&self, severity: LitterSeverity) -> i32 {
+        let multiplier = match severity {
+            LitterSeverity::Small => self.config.small_severity_multiplier,
+            LitterSeverity::Medium => self.config.medium_severity_multiplier,
+            LitterSeverity::Large => self.config.large_severity_multiplier,
+        };
+        (f64::from(self.config.base_points_per_clear) * multiplier).round() as i32
+    }
+
+    /// Award points to a user who verified a report. An on-site photo earns its bonus
+    /// whichever way the verdict went.
     pub async fn award_verification_points(
         &self,
         user_id: Uuid,
         is_verified: bool,
+        with_photo: bool,
     ) -> Result<UserScore, AppError> {
         let user_score = self.get_or_create_user_score(user_id).await?;
-        let points = if is_verified {
+        let mut points = if is_verified {
             self.config.verification_bonus
         } else {
             0
         };
+        if with_photo {
+            points += self.config.photo_verification_bonus;
+        }
         let new_total = user_score.total_points + points;
 
         let mut tx = self.pool.begin().await?;
@@ -284,6 +318,189 @@ impl ScoringService {
         Ok(updated_score)
     }
 
+    /// Award report points inside the caller's transaction, so they land together with
+    /// whatever made the user the report's owner
+    pub async fn award_report_points_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        report_id: Uuid,
+    ) -> Result<(), AppError> {
+        let points = self.config.report_points;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_scores (user_id) VALUES ($1)
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+            user_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE user_scores
+             SET total_points = total_points + $2,
+                 total_reports = total_reports + 1
+             WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .bind(points)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO score_events (user_id, points, kind, report_id)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user_id,
+            points,
+            "report",
+            report_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Move the credit for clearing a report from one user to another after an admin
+    /// correction. Whatever the previous clearer earned is revoked and granted to the
+    /// new one; a report with no previous credit earns the base clear points.
+    pub async fn transfer_clear_points(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+        from: Option<Uuid>,
+        to: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let mut points = 0;
+
+        if let Some(from) = from {
+            let earned = sqlx::query_scalar!(
+                r#"
+                SELECT COALESCE(SUM(points), 0)::int AS "earned!"
+                FROM score_events
+                WHERE user_id = $1 AND report_id = $2 AND kind IN ('clear', 'correction')
+                "#,
+                from,
+                report_id
+            )
+            .fetch_one(&mut **tx)
+            .await?;
+
+            if earned > 0 {
+                self.apply_correction(tx, from, report_id, -earned, -1)
+                    .await?;
+                points = earned;
+            }
+        }
+
+        if let Some(to) = to {
+            if points == 0 {
+                points = self.config.base_points_per_clear;
+            }
+            self.apply_correction(tx, to, report_id, points, 1).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_correction(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        report_id: Uuid,
+        points: i32,
+        clears: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_scores (user_id) VALUES ($1)
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+            user_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE user_scores
+            SET total_points = total_points + $2,
+                reports_cleared = GREATEST(reports_cleared + $3, 0),
+                total_clears = GREATEST(total_clears + $3, 0)
+            WHERE user_id = $1
+            "#,
+            user_id,
+            points,
+            clears
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO score_events (user_id, points, kind, report_id)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user_id,
+            points,
+            "correction",
+            report_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count the unsettled verdicts on a report's current clear towards their verifiers'
+    /// records now that the clear is settled as `verified` or not, and recompute each
+    /// verifier's weight. Requests for a better photo take no side and are not counted.
+    pub async fn settle_verifications(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        report_id: Uuid,
+        verified: bool,
+    ) -> Result<(), AppError> {
+        let records: Vec<(Uuid, i32, i32)> = sqlx::query_as(
+            r"
+            WITH settled AS (
+                UPDATE report_verifications SET settled_at = NOW()
+                WHERE report_id = $1 AND superseded_at IS NULL AND settled_at IS NULL
+                  AND requested_better_photo = false
+                RETURNING verifier_id, is_verified = $2 AS agreed
+            )
+            INSERT INTO verifier_weights (user_id, agreed, settled, weight)
+            SELECT verifier_id, COUNT(*) FILTER (WHERE agreed), COUNT(*), $3
+            FROM settled
+            GROUP BY verifier_id
+            ON CONFLICT (user_id) DO UPDATE
+            SET agreed = verifier_weights.agreed + EXCLUDED.agreed,
+                settled = verifier_weights.settled + EXCLUDED.settled,
+                updated_at = NOW()
+            RETURNING user_id, agreed, settled
+            ",
+        )
+        .bind(report_id)
+        .bind(verified)
+        .bind(DEFAULT_VERIFIER_WEIGHT)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        for (user_id, agreed, settled) in records {
+            sqlx::query("UPDATE verifier_weights SET weight = $2 WHERE user_id = $1")
+                .bind(user_id)
+                .bind(verifier_weight(agreed, settled))
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     /// Calculate the new streak based on last cleared date
     fn calculate_streak(&self, user_score: &UserScore, today: NaiveDate) -> (i32, bool) {
         if let Some(last_date) = user_score.last_cleared_date {
@@ -310,13 +527,9 @@ impl ScoringService {
     }
 
     /// Check if this is the first clear in the area (1km, 24 hours)
-    async fn is_first_clear_in_area(
-        &self,
-        latitude: f64,
-        longitude: f64,
-    ) -> Result<bool, AppError> {
-        let radius_meters = 1000.0; // 1km
-        let time_threshold = Utc::now() - Duration::hours(24);
+    async fn is_first_clear_in_area(&self, location: Coordinates) -> Result<bool, AppError> {
+        let radius_meters = FIRST_IN_AREA_RADIUS_M;
+        let time_threshold = Utc::now() - Duration::hours(FIRST_IN_AREA_WINDOW_HOURS);
 
         let count = sqlx::query_scalar!(
             r#"
@@ -331,8 +544,8 @@ impl ScoringService {
               )
             "#,
             time_threshold,
-            longitude,
-            latitude,
+            location.longitude(),
+            location.latitude(),
             radius_meters
         )
         .fetch_one(&self.pool)
@@ -346,6 +559,84 @@ impl ScoringService {
         self.get_or_create_user_score(user_id).await
     }
 
+    /// The rules in force, described in `locale`
+    #[must_use]
+    pub fn rules(&self, locale: &str) -> ScoringRulesResponse {
+        let config = &self.config;
+        let rules = [
+            (
+                ScoringRuleKey::ReportPoints,
+                f64::from(config.report_points),
+            ),
+            (
+                ScoringRuleKey::BasePointsPerClear,
+                f64::from(config.base_points_per_clear),
+            ),
+            (
+                ScoringRuleKey::SmallSeverityMultiplier,
+                config.small_severity_multiplier,
+            ),
+            (
+                ScoringRuleKey::MediumSeverityMultiplier,
+                config.medium_severity_multiplier,
+            ),
+            (
+                ScoringRuleKey::LargeSeverityMultiplier,
+                config.large_severity_multiplier,
+            ),
+            (
+                ScoringRuleKey::StreakBonusPoints,
+                f64::from(config.streak_bonus_points),
+            ),
+            (
+                ScoringRuleKey::FirstInAreaBonus,
+                f64::from(config.first_in_area_bonus),
+            ),
+            (
+                ScoringRuleKey::VerificationBonus,
+                f64::from(config.verification_bonus),
+            ),
+            (
+                ScoringRuleKey::PhotoVerificationBonus,
+                f64::from(config.photo_verification_bonus),
+            ),
+            (
+                ScoringRuleKey::VerifiedReportBonus,
+                f64::from(config.verified_report_bonus),
+            ),
+            (
+                ScoringRuleKey::CleanupEventBonus,
+                f64::from(config.cleanup_event_bonus),
+            ),
+            (
+                ScoringRuleKey::MinClearsToVerify,
+                f64::from(config.min_clears_to_verify),
+            ),
+            (
+                ScoringRuleKey::MinVerificationsNeeded,
+                f64::from(config.min_verifications_needed),
+            ),
+            (
+                ScoringRuleKey::PhotoRetakeWindowHours,
+                config.photo_retake_window_hours as f64,
+            ),
+        ]
+        .into_iter()
+        .map(|(key, value)| ScoringRule::new(key, value, locale))
+        .collect();
+
+        ScoringRulesResponse {
+            locale: locale.to_string(),
+            rules,
+        }
+    }
+
+    /// Combined verifier weight that settles a clear
+    #[must_use]
+    pub fn min_verifications_needed(&self) -> i32 {
+        self.config.min_verifications_needed
+    }
+
     /// Check if user can verify reports (has cleared enough reports)
     pub async fn can_verify_reports(&self, user_id: Uuid) -> Result<bool, AppError> {
         let score = self.get_or_create_user_score(user_id).await?;
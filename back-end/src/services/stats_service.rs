@@ -0,0 +1,308 @@
+use crate::error::AppError;
+use crate::models::stats::{
+    Hotspot, HotspotQuery, HotspotShape, HotspotTrend, HotspotsResponse, LocationHistory,
+    LocationHistoryMonth, LocationHistoryQuery, StatsSummary,
+};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::types::Json;
+use sqlx::{FromRow, PgPool};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Counters older than this are recomputed on request, in case the refresh job is
+/// disabled or failing
+const MAX_AGE_MINS: i64 = 15;
+
+/// Hotspot defaults and bounds
+const DEFAULT_HOTSPOT_WINDOW_DAYS: i32 = 30;
+const MAX_HOTSPOT_WINDOW_DAYS: i32 = 365;
+const DEFAULT_HOTSPOT_CELL_M: f64 = 500.0;
+const MIN_HOTSPOT_CELL_M: f64 = 100.0;
+const MAX_HOTSPOT_CELL_M: f64 = 10_000.0;
+const DEFAULT_HOTSPOTS: i64 = 10;
+const MAX_HOTSPOTS: i64 = 50;
+
+/// Location history defaults and bounds
+const DEFAULT_HISTORY_RADIUS_M: f64 = 50.0;
+const MIN_HISTORY_RADIUS_M: f64 = 10.0;
+const MAX_HISTORY_RADIUS_M: f64 = 500.0;
+const HISTORY_MONTHS: i32 = 12;
+/// A spot with this many reports in the last `REPEAT_OFFENDER_WINDOW_DAYS` is a repeat
+/// offender
+const REPEAT_OFFENDER_REPORTS: i64 = 3;
+const REPEAT_OFFENDER_WINDOW_DAYS: i32 = 180;
+
+#[derive(FromRow)]
+struct LocationTotalsRow {
+    reports: i64,
+    cleared: i64,
+    open_reports: i64,
+    recent_reports: i64,
+    first_reported_at: Option<DateTime<Utc>>,
+    last_reported_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct HotspotRow {
+    center_latitude: f64,
+    center_longitude: f64,
+    boundary: Json<Vec<[f64; 2]>>,
+    reports: i64,
+    previous_reports: i64,
+    open_reports: i64,
+}
+
+/// Site-wide counters for the public landing page. A background job recomputes them
+/// and requests are served from memory, so the endpoint can stay unauthenticated.
+#[derive(Clone)]
+pub struct StatsService {
+    pool: PgPool,
+    summary: Arc<RwLock<Option<StatsSummary>>>,
+}
+
+impl StatsService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            summary: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// The cached counters, recomputed first if they are missing or stale
+    pub async fn summary(&self) -> Result<StatsSummary, AppError> {
+        if let Some(summary) = self.summary.read().await.as_ref() {
+            if Utc::now() - summary.computed_at < Duration::minutes(MAX_AGE_MINS) {
+                return Ok(summary.clone());
+            }
+        }
+
+        self.refresh().await
+    }
+
+    /// Recompute the counters and cache them
+    pub async fn refresh(&self) -> Result<StatsSummary, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) as "total_reports!",
+                COUNT(*) FILTER (WHERE status IN ('cleared', 'verified')) as "cleared_reports!",
+                COUNT(*) FILTER (WHERE status = 'verified') as "verified_reports!",
+                (
+                    SELECT COUNT(*) FROM (
+                        SELECT reporter_id FROM litter_reports
+                        WHERE reporter_id IS NOT NULL AND status != 'cancelled'
+                        UNION
+                        SELECT cleared_by FROM litter_reports WHERE cleared_by IS NOT NULL
+                    ) volunteers
+                ) as "volunteers!"
+            FROM litter_reports
+            WHERE status != 'cancelled'
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let summary = StatsSummary {
+            total_reports: row.total_reports,
+            cleared_reports: row.cleared_reports,
+            verified_reports: row.verified_reports,
+            volunteers: row.volunteers,
+            computed_at: Utc::now(),
+        };
+        *self.summary.write().await = Some(summary.clone());
+
+        Ok(summary)
+    }
+
+    /// The cells with the most reports in the last `window_days`, each compared with
+    /// the window before it. Cells are laid out in Web Mercator, scaled so they are
+    /// about `cell_size_m` wide where the reports are.
+    pub async fn hotspots(&self, query: HotspotQuery) -> Result<HotspotsResponse, AppError> {
+        let window_days = query.window_days.unwrap_or(DEFAULT_HOTSPOT_WINDOW_DAYS);
+        if !(1..=MAX_HOTSPOT_WINDOW_DAYS).contains(&window_days) {
+            return Err(AppError::BadRequest(format!(
+                "window_days must be between 1 and {MAX_HOTSPOT_WINDOW_DAYS}"
+            )));
+        }
+        let cell_size_m = query.cell_size_m.unwrap_or(DEFAULT_HOTSPOT_CELL_M);
+        if !(MIN_HOTSPOT_CELL_M..=MAX_HOTSPOT_CELL_M).contains(&cell_size_m) {
+            return Err(AppError::BadRequest(format!(
+                "cell_size_m must be between {MIN_HOTSPOT_CELL_M} and {MAX_HOTSPOT_CELL_M}"
+            )));
+        }
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_HOTSPOTS)
+            .clamp(1, MAX_HOTSPOTS);
+        let city = query
+            .city
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        let shape = query.shape.unwrap_or_default();
+        let grid = match shape {
+            HotspotShape::Hex => "ST_HexagonGrid",
+            HotspotShape::Grid => "ST_SquareGrid",
+        };
+
+        // Mercator stretches distances by 1/cos(latitude), so cells are widened to match
+        let rows = sqlx::query_as::<_, HotspotRow>(&format!(
+            r"
+            WITH points AS (
+                SELECT ST_Transform(location, 3857) AS geom,
+                       ST_Y(location) AS latitude,
+                       created_at >= NOW() - make_interval(days => $2) AS recent,
+                       status IN ('pending', 'claimed') AS open
+                FROM litter_reports
+                WHERE created_at >= NOW() - make_interval(days => $2 * 2)
+                  AND status NOT IN ('cancelled', 'archived', 'expired')
+                  AND ($1::text IS NULL OR lower(address_city) = lower($1))
+            ),
+            bounds AS (
+                SELECT ST_SetSRID(ST_Extent(geom)::geometry, 3857) AS extent,
+                       $3 / cos(radians(AVG(latitude))) AS size
+                FROM points
+                HAVING COUNT(*) > 0
+            ),
+            cells AS (
+                SELECT cell.geom, cell.i, cell.j
+                FROM bounds, {grid}(bounds.size, bounds.extent) AS cell
+            )
+            SELECT
+                ST_Y(ST_Transform(ST_Centroid(c.geom), 4326))::double precision
+                    AS center_latitude,
+                ST_X(ST_Transform(ST_Centroid(c.geom), 4326))::double precision
+                    AS center_longitude,
+                ST_AsGeoJSON(ST_Transform(c.geom, 4326))::jsonb -> 'coordinates' -> 0
+                    AS boundary,
+                COUNT(*) FILTER (WHERE p.recent) AS reports,
+                COUNT(*) FILTER (WHERE NOT p.recent) AS previous_reports,
+                COUNT(*) FILTER (WHERE p.open) AS open_reports
+            FROM cells c
+            JOIN points p ON ST_Intersects(c.geom, p.geom)
+            GROUP BY c.i, c.j, c.geom
+            HAVING COUNT(*) FILTER (WHERE p.recent) > 0
+            ORDER BY reports DESC, open_reports DESC
+            LIMIT $4
+            "
+        ))
+        .bind(&city)
+        .bind(window_days)
+        .bind(cell_size_m)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let hotspots = rows
+            .into_iter()
+            .map(|row| Hotspot {
+                center_latitude: row.center_latitude,
+                center_longitude: row.center_longitude,
+                boundary: row.boundary.0,
+                reports: row.reports,
+                previous_reports: row.previous_reports,
+                open_reports: row.open_reports,
+                trend: HotspotTrend::from_counts(row.reports, row.previous_reports),
+                change_percent: (row.previous_reports > 0).then(|| {
+                    (row.reports - row.previous_reports) as f64 * 100.0
+                        / row.previous_reports as f64
+                }),
+            })
+            .collect();
+
+        Ok(HotspotsResponse {
+            city,
+            window_days,
+            shape,
+            cell_size_m,
+            hotspots,
+            computed_at: Utc::now(),
+        })
+    }
+
+    /// How many reports were made and cleared within `radius` metres of a spot, in
+    /// total and month by month, and whether litter keeps coming back there
+    pub async fn location_history(
+        &self,
+        query: LocationHistoryQuery,
+    ) -> Result<LocationHistory, AppError> {
+        let radius_m = query.radius.unwrap_or(DEFAULT_HISTORY_RADIUS_M);
+        if !(MIN_HISTORY_RADIUS_M..=MAX_HISTORY_RADIUS_M).contains(&radius_m) {
+            return Err(AppError::BadRequest(format!(
+                "radius must be between {MIN_HISTORY_RADIUS_M} and {MAX_HISTORY_RADIUS_M} metres"
+            )));
+        }
+        let latitude = query.lat.degrees();
+        let longitude = query.lon.degrees();
+
+        let spot = r"
+            spot AS (
+                SELECT created_at, cleared_at, status
+                FROM litter_reports
+                WHERE status <> 'cancelled' AND hidden_at IS NULL
+                  AND ST_DWithin(
+                      location::geography,
+                      ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography,
+                      $3
+                  )
+            )
+        ";
+
+        let totals = sqlx::query_as::<_, LocationTotalsRow>(&format!(
+            r"
+            WITH {spot}
+            SELECT
+                COUNT(*) AS reports,
+                COUNT(cleared_at) AS cleared,
+                COUNT(*) FILTER (WHERE status IN ('pending', 'claimed')) AS open_reports,
+                COUNT(*) FILTER (WHERE created_at >= NOW() - make_interval(days => $4))
+                    AS recent_reports,
+                MIN(created_at) AS first_reported_at,
+                MAX(created_at) AS last_reported_at
+            FROM spot
+            "
+        ))
+        .bind(latitude)
+        .bind(longitude)
+        .bind(radius_m)
+        .bind(REPEAT_OFFENDER_WINDOW_DAYS)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let months = sqlx::query_as::<_, LocationHistoryMonth>(&format!(
+            r"
+            WITH {spot}
+            SELECT
+                date_trunc('month', t.at AT TIME ZONE 'UTC')::date AS month,
+                COUNT(*) FILTER (WHERE t.reported) AS reported,
+                COUNT(*) FILTER (WHERE NOT t.reported) AS cleared
+            FROM spot,
+                 LATERAL (VALUES (spot.created_at, TRUE), (spot.cleared_at, FALSE))
+                     AS t(at, reported)
+            WHERE t.at >= date_trunc('month', NOW() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+                         - make_interval(months => $4 - 1)
+            GROUP BY 1
+            ORDER BY 1
+            "
+        ))
+        .bind(latitude)
+        .bind(longitude)
+        .bind(radius_m)
+        .bind(HISTORY_MONTHS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(LocationHistory {
+            latitude,
+            longitude,
+            radius_m,
+            reports: totals.reports,
+            cleared: totals.cleared,
+            open_reports: totals.open_reports,
+            first_reported_at: totals.first_reported_at,
+            last_reported_at: totals.last_reported_at,
+            repeat_offender: totals.recent_reports >= REPEAT_OFFENDER_REPORTS,
+            months,
+        })
+    }
+}
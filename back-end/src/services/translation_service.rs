@@ -0,0 +1,287 @@
+use crate::config::{TranslationConfig, TranslationProvider};
+use crate::error::AppError;
+use crate::models::translation::DescriptionTranslation;
+use axum::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Text in another language, with the language the provider found it was in
+#[derive(Debug, Clone)]
+pub struct TranslatedText {
+    pub text: String,
+    pub source_language: Option<String>,
+}
+
+/// A machine translation service
+#[async_trait]
+pub trait Translator: Send + Sync {
+    /// Short name stored with each translation, e.g. `deepl`
+    fn name(&self) -> &'static str;
+
+    /// Translate `text`, whatever its language, into `target_language` (`xx` or `xx-YY`)
+    async fn translate(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<TranslatedText, AppError>;
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_default()
+}
+
+/// LibreTranslate's `/translate` API
+pub struct LibreTranslate {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibreTranslateResponse {
+    translated_text: String,
+    detected_language: Option<LibreTranslateDetection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibreTranslateDetection {
+    language: String,
+}
+
+impl LibreTranslate {
+    #[must_use]
+    pub fn new(api_url: String, api_key: Option<String>) -> Self {
+        Self {
+            client: http_client(),
+            api_url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl Translator for LibreTranslate {
+    fn name(&self) -> &'static str {
+        "libretranslate"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<TranslatedText, AppError> {
+        // LibreTranslate knows languages, not regional variants
+        let target = target_language.split('-').next().unwrap_or(target_language);
+        let mut body = json!({ "q": text, "source": "auto", "target": target, "format": "text" });
+        if let Some(api_key) = &self.api_key {
+            body["api_key"] = json!(api_key);
+        }
+
+        let response: LibreTranslateResponse = self
+            .client
+            .post(format!("{}/translate", self.api_url))
+            .json(&body)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| anyhow::anyhow!("LibreTranslate request failed: {e}"))?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Invalid LibreTranslate response: {e}"))?;
+
+        Ok(TranslatedText {
+            text: response.translated_text,
+            source_language: response.detected_language.map(|d| d.language),
+        })
+    }
+}
+
+/// DeepL's `/v2/translate` API
+pub struct DeepL {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLTranslation {
+    detected_source_language: Option<String>,
+    text: String,
+}
+
+impl DeepL {
+    #[must_use]
+    pub fn new(api_url: String, api_key: String) -> Self {
+        Self {
+            client: http_client(),
+            api_url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl Translator for DeepL {
+    fn name(&self) -> &'static str {
+        "deepl"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<TranslatedText, AppError> {
+        let response: DeepLResponse = self
+            .client
+            .post(format!("{}/v2/translate", self.api_url))
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+            .json(&json!({
+                "text": [text],
+                "target_lang": target_language.to_ascii_uppercase(),
+            }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| anyhow::anyhow!("DeepL request failed: {e}"))?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Invalid DeepL response: {e}"))?;
+
+        let translation = response
+            .translations
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("DeepL returned no translation"))?;
+
+        Ok(TranslatedText {
+            text: translation.text,
+            // DeepL reports languages in upper case
+            source_language: translation
+                .detected_source_language
+                .map(|language| language.to_ascii_lowercase()),
+        })
+    }
+}
+
+/// Translates report descriptions, keeping each translation so the provider is only
+/// asked once per description and language. Without a provider nothing is translated.
+#[derive(Clone)]
+pub struct TranslationService {
+    pool: PgPool,
+    translator: Option<Arc<dyn Translator>>,
+}
+
+impl TranslationService {
+    #[must_use]
+    pub fn new(pool: PgPool, config: Option<TranslationConfig>) -> Self {
+        let translator = config.map(|config| -> Arc<dyn Translator> {
+            match config.provider {
+                TranslationProvider::LibreTranslate => {
+                    Arc::new(LibreTranslate::new(config.api_url, config.api_key))
+                }
+                TranslationProvider::DeepL => Arc::new(DeepL::new(
+                    config.api_url,
+                    config.api_key.unwrap_or_default(),
+                )),
+            }
+        });
+
+        Self { pool, translator }
+    }
+
+    /// Use a specific translator, e.g. a fake in tests
+    #[must_use]
+    pub fn with_translator(pool: PgPool, translator: Arc<dyn Translator>) -> Self {
+        Self {
+            pool,
+            translator: Some(translator),
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.translator.is_some()
+    }
+
+    /// A report's description in `target_language`, from the stored translations when
+    /// the description hasn't changed since, otherwise from the provider
+    pub async fn translate_description(
+        &self,
+        report_id: Uuid,
+        description: &str,
+        target_language: &str,
+    ) -> Result<DescriptionTranslation, AppError> {
+        let translator = self
+            .translator
+            .as_ref()
+            .ok_or_else(|| AppError::BadRequest("Translation is not available".to_string()))?;
+
+        let source_hash = format!("{:x}", Sha256::digest(description.as_bytes()));
+
+        let stored: Option<(String, Option<String>)> = sqlx::query_as(
+            r"
+            SELECT translated_text, source_language FROM report_translations
+            WHERE report_id = $1 AND target_language = $2 AND source_hash = $3
+            ",
+        )
+        .bind(report_id)
+        .bind(target_language)
+        .bind(&source_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some((text, source_language)) = stored {
+            return Ok(DescriptionTranslation {
+                text,
+                source_language,
+                target_language: target_language.to_string(),
+            });
+        }
+
+        let translated = translator.translate(description, target_language).await?;
+
+        sqlx::query(
+            r"
+            INSERT INTO report_translations
+                (report_id, target_language, source_hash, source_language, translated_text, provider)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (report_id, target_language) DO UPDATE
+            SET source_hash = EXCLUDED.source_hash,
+                source_language = EXCLUDED.source_language,
+                translated_text = EXCLUDED.translated_text,
+                provider = EXCLUDED.provider,
+                created_at = NOW()
+            ",
+        )
+        .bind(report_id)
+        .bind(target_language)
+        .bind(&source_hash)
+        .bind(&translated.source_language)
+        .bind(&translated.text)
+        .bind(translator.name())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(DescriptionTranslation {
+            text: translated.text,
+            source_language: translated.source_language,
+            target_language: target_language.to_string(),
+        })
+    }
+}
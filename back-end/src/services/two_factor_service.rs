@@ -0,0 +1,369 @@
+use crate::auth::{generate_token, hash_token};
+use crate::error::AppError;
+use crate::models::two_factor::{TwoFactorChallenge, TwoFactorSetupResponse};
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sqlx::{PgPool, Postgres, Transaction};
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+const ISSUER: &str = "LittyPicky";
+/// TOTP time step in seconds (RFC 6238 default, what authenticator apps expect)
+const STEP_SECS: u64 = 30;
+const BACKUP_CODE_COUNT: usize = 10;
+/// Unambiguous characters for backup codes (no 0/o, 1/l/i)
+const BACKUP_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+const CHALLENGE_EXPIRY_SECS: i64 = 300;
+/// Wrong codes allowed per challenge before the user must sign in again
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
+#[derive(Clone)]
+pub struct TwoFactorService {
+    pool: PgPool,
+}
+
+impl TwoFactorService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let enabled = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM user_two_factor WHERE user_id = $1 AND enabled_at IS NOT NULL
+            ) AS "enabled!"
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(enabled)
+    }
+
+    /// Start enrolment with a fresh secret. It only takes effect once a code is confirmed.
+    pub async fn begin_setup(
+        &self,
+        user_id: Uuid,
+        email: &str,
+    ) -> Result<TwoFactorSetupResponse, AppError> {
+        if self.is_enabled(user_id).await? {
+            return Err(AppError::Conflict(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        let secret = rand::thread_rng().gen::<[u8; 20]>().to_vec();
+        let totp = Self::totp(secret, email)?;
+        let encoded = totp.get_secret_base32();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_two_factor (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, created_at = NOW()
+            "#,
+            user_id,
+            encoded
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(TwoFactorSetupResponse {
+            secret: encoded,
+            otpauth_url: totp.get_url(),
+        })
+    }
+
+    /// Confirm enrolment with a first code and issue a fresh set of backup codes
+    pub async fn enable(&self, user_id: Uuid, code: &str) -> Result<Vec<String>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query!(
+            "SELECT secret, enabled_at, last_used_step FROM user_two_factor WHERE user_id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest("Start two-factor setup before verifying a code".to_string())
+        })?;
+
+        if row.enabled_at.is_some() {
+            return Err(AppError::Conflict(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        let step = Self::match_totp(&row.secret, code, row.last_used_step)?
+            .ok_or_else(|| AppError::BadRequest("Invalid verification code".to_string()))?;
+
+        sqlx::query!(
+            "UPDATE user_two_factor SET enabled_at = NOW(), last_used_step = $2 WHERE user_id = $1",
+            user_id,
+            step
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let backup_codes = Self::replace_backup_codes(&mut tx, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(backup_codes)
+    }
+
+    /// Turn two-factor off; requires a current TOTP or backup code
+    pub async fn disable(&self, user_id: Uuid, code: &str) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        if !Self::verify_second_factor(&mut tx, user_id, code).await? {
+            return Err(AppError::BadRequest(
+                "Invalid verification code".to_string(),
+            ));
+        }
+
+        sqlx::query!("DELETE FROM user_two_factor WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            "DELETE FROM two_factor_backup_codes WHERE user_id = $1",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM two_factor_challenges WHERE user_id = $1",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Check a TOTP or backup code outside of a login, e.g. to re-authenticate
+    pub async fn verify_code(&self, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let valid = Self::verify_second_factor(&mut tx, user_id, code).await?;
+        tx.commit().await?;
+        Ok(valid)
+    }
+
+    /// Issue a short-lived challenge for a login that still needs its second factor
+    pub async fn create_challenge(&self, user_id: Uuid) -> Result<TwoFactorChallenge, AppError> {
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::seconds(CHALLENGE_EXPIRY_SECS);
+
+        sqlx::query!(
+            "INSERT INTO two_factor_challenges (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+            user_id,
+            hash_token(&token),
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(TwoFactorChallenge {
+            two_factor_required: true,
+            challenge_token: token,
+            expires_in: CHALLENGE_EXPIRY_SECS,
+        })
+    }
+
+    /// Check a code against a pending challenge, returning the user it belongs to
+    pub async fn complete_challenge(
+        &self,
+        challenge_token: &str,
+        code: &str,
+    ) -> Result<Uuid, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let challenge = sqlx::query!(
+            "SELECT id, user_id, attempts, expires_at FROM two_factor_challenges WHERE token_hash = $1 FOR UPDATE",
+            hash_token(challenge_token)
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Auth("Invalid or expired two-factor challenge".to_string()))?;
+
+        if challenge.expires_at < Utc::now() || challenge.attempts >= MAX_CHALLENGE_ATTEMPTS {
+            sqlx::query!(
+                "DELETE FROM two_factor_challenges WHERE id = $1",
+                challenge.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Err(AppError::Auth(
+                "Invalid or expired two-factor challenge".to_string(),
+            ));
+        }
+
+        if !Self::verify_second_factor(&mut tx, challenge.user_id, code).await? {
+            sqlx::query!(
+                "UPDATE two_factor_challenges SET attempts = attempts + 1 WHERE id = $1",
+                challenge.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Err(AppError::Auth("Invalid verification code".to_string()));
+        }
+
+        sqlx::query!(
+            "DELETE FROM two_factor_challenges WHERE id = $1",
+            challenge.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(challenge.user_id)
+    }
+
+    /// Accept a TOTP code (not replayed) or consume an unused backup code
+    async fn verify_second_factor(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<bool, AppError> {
+        let row = sqlx::query!(
+            "SELECT secret, last_used_step FROM user_two_factor WHERE user_id = $1 AND enabled_at IS NOT NULL FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest("Two-factor authentication is not enabled".to_string())
+        })?;
+
+        if let Some(step) = Self::match_totp(&row.secret, code, row.last_used_step)? {
+            sqlx::query!(
+                "UPDATE user_two_factor SET last_used_step = $2 WHERE user_id = $1",
+                user_id,
+                step
+            )
+            .execute(&mut **tx)
+            .await?;
+            return Ok(true);
+        }
+
+        let consumed = sqlx::query!(
+            r#"
+            UPDATE two_factor_backup_codes
+            SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            hash_token(&Self::normalize_backup_code(code))
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        Ok(consumed)
+    }
+
+    async fn replace_backup_codes(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+    ) -> Result<Vec<String>, AppError> {
+        sqlx::query!(
+            "DELETE FROM two_factor_backup_codes WHERE user_id = $1",
+            user_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        let codes: Vec<String> = (0..BACKUP_CODE_COUNT)
+            .map(|_| Self::generate_backup_code())
+            .collect();
+        let hashes: Vec<String> = codes
+            .iter()
+            .map(|code| hash_token(&Self::normalize_backup_code(code)))
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO two_factor_backup_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::VARCHAR[])
+            "#,
+            user_id,
+            &hashes
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(codes)
+    }
+
+    /// Returns the matching time step, allowing one step of clock drift either way.
+    /// Steps at or before the last accepted one are rejected so codes are single-use.
+    fn match_totp(
+        secret: &str,
+        code: &str,
+        last_used_step: Option<i64>,
+    ) -> Result<Option<i64>, AppError> {
+        let code = code.trim();
+        if code.len() != 6 || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok(None);
+        }
+
+        let bytes = Secret::Encoded(secret.to_string())
+            .to_bytes()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid TOTP secret: {e}")))?;
+        let totp = Self::totp(bytes, "")?;
+
+        let now = u64::try_from(Utc::now().timestamp()).unwrap_or_default();
+        let current = now / STEP_SECS;
+
+        for step in [current.saturating_sub(1), current, current + 1] {
+            let step_id = i64::try_from(step).unwrap_or(i64::MAX);
+            if last_used_step.is_some_and(|last| step_id <= last) {
+                continue;
+            }
+            if totp.generate(step * STEP_SECS) == code {
+                return Ok(Some(step_id));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn totp(secret: Vec<u8>, account_name: &str) -> Result<TOTP, AppError> {
+        TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            STEP_SECS,
+            secret,
+            Some(ISSUER.to_string()),
+            account_name.to_string(),
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to build TOTP: {e}")))
+    }
+
+    /// Backup codes look like `k7m2p-9xq4r`
+    fn generate_backup_code() -> String {
+        let mut rng = rand::thread_rng();
+        let chars: String = (0..10)
+            .map(|_| BACKUP_CODE_ALPHABET[rng.gen_range(0..BACKUP_CODE_ALPHABET.len())] as char)
+            .collect();
+        format!("{}-{}", &chars[..5], &chars[5..])
+    }
+
+    fn normalize_backup_code(code: &str) -> String {
+        code.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+}
@@ -0,0 +1,336 @@
+use crate::config::{UploadScanConfig, UploadScanProvider};
+use crate::error::AppError;
+use crate::models::upload_scan::{QuarantinedUpload, UploadScanCounts, UploadScanReport};
+use axum::async_trait;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+/// clamd reads `INSTREAM` data in length-prefixed chunks; its default limit per
+/// chunk is far above this
+const CLAMD_CHUNK_SIZE: usize = 64 * 1024;
+/// Longest reply clamd sends, with room to spare for long signature names
+const CLAMD_MAX_REPLY_BYTES: u64 = 4096;
+
+/// What a scanner made of an upload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// Malware was found; holds the scanner's name for it
+    Infected(String),
+}
+
+/// A malware scanner uploads are checked with before they are stored
+#[async_trait]
+pub trait MalwareScanner: Send + Sync {
+    /// Short name stored with each quarantined upload, e.g. `clamav`
+    fn name(&self) -> &'static str;
+
+    /// Scan `data`, failing if no verdict could be reached
+    async fn scan(&self, data: &[u8]) -> anyhow::Result<ScanVerdict>;
+}
+
+/// A `clamd` daemon, sent each upload with the `INSTREAM` command
+pub struct ClamAvScanner {
+    address: String,
+    timeout: Duration,
+}
+
+impl ClamAvScanner {
+    #[must_use]
+    pub fn new(address: String, timeout: Duration) -> Self {
+        Self { address, timeout }
+    }
+
+    async fn instream(&self, data: &[u8]) -> anyhow::Result<String> {
+        let mut stream = TcpStream::connect(&self.address).await?;
+        stream.write_all(b"zINSTREAM\0").await?;
+        for chunk in data.chunks(CLAMD_CHUNK_SIZE) {
+            stream
+                .write_all(&u32::try_from(chunk.len())?.to_be_bytes())
+                .await?;
+            stream.write_all(chunk).await?;
+        }
+        stream.write_all(&0u32.to_be_bytes()).await?;
+
+        // clamd answers with one NUL-terminated line and closes the connection
+        let mut reply = Vec::new();
+        stream
+            .take(CLAMD_MAX_REPLY_BYTES)
+            .read_to_end(&mut reply)
+            .await?;
+        Ok(String::from_utf8_lossy(&reply).into_owned())
+    }
+}
+
+/// Read clamd's answer to `INSTREAM`: `stream: OK`, `stream: <signature> FOUND`, or
+/// an error such as `INSTREAM size limit exceeded. ERROR`
+pub fn parse_clamd_reply(reply: &str) -> anyhow::Result<ScanVerdict> {
+    let reply = reply.trim_end_matches(['\0', '\n']).trim();
+    let result = reply.strip_prefix("stream:").map(str::trim);
+    match result {
+        Some("OK") => Ok(ScanVerdict::Clean),
+        Some(found) if found.ends_with(" FOUND") => Ok(ScanVerdict::Infected(
+            found.trim_end_matches(" FOUND").trim().to_string(),
+        )),
+        _ => anyhow::bail!("clamd answered {reply:?}"),
+    }
+}
+
+#[async_trait]
+impl MalwareScanner for ClamAvScanner {
+    fn name(&self) -> &'static str {
+        "clamav"
+    }
+
+    async fn scan(&self, data: &[u8]) -> anyhow::Result<ScanVerdict> {
+        let reply = tokio::time::timeout(self.timeout, self.instream(data))
+            .await
+            .map_err(|_| anyhow::anyhow!("clamd did not answer in time"))??;
+        parse_clamd_reply(&reply)
+    }
+}
+
+/// A scanning endpoint, e.g. a cloud function, that takes the raw bytes as the request
+/// body and answers whether they are clean
+pub struct HttpScanner {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpScanResponse {
+    clean: bool,
+    signature: Option<String>,
+}
+
+impl HttpScanner {
+    #[must_use]
+    pub fn new(url: String, api_key: Option<String>, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl MalwareScanner for HttpScanner {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn scan(&self, data: &[u8]) -> anyhow::Result<ScanVerdict> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(data.to_vec());
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response: HttpScanResponse = request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)?
+            .json()
+            .await?;
+
+        Ok(if response.clean {
+            ScanVerdict::Clean
+        } else {
+            ScanVerdict::Infected(response.signature.unwrap_or_else(|| "unknown".to_string()))
+        })
+    }
+}
+
+#[derive(Default)]
+struct ScanCounters {
+    scanned: AtomicU64,
+    clean: AtomicU64,
+    infected: AtomicU64,
+    errors: AtomicU64,
+    skipped: AtomicU64,
+}
+
+/// Checks uploads for malware before they reach storage, quarantining anything the
+/// scanner flags. Without a scanner every upload passes.
+#[derive(Clone)]
+pub struct UploadScanService {
+    pool: PgPool,
+    scanner: Option<Arc<dyn MalwareScanner>>,
+    fail_open: bool,
+    counters: Arc<ScanCounters>,
+}
+
+impl UploadScanService {
+    #[must_use]
+    pub fn new(pool: PgPool, config: Option<UploadScanConfig>) -> Self {
+        let fail_open = config.as_ref().is_some_and(|config| config.fail_open);
+        let scanner = config.map(|config| -> Arc<dyn MalwareScanner> {
+            let timeout = Duration::from_secs(config.timeout_secs);
+            match config.provider {
+                UploadScanProvider::ClamAv => Arc::new(ClamAvScanner::new(config.address, timeout)),
+                UploadScanProvider::Http => {
+                    Arc::new(HttpScanner::new(config.address, config.api_key, timeout))
+                }
+            }
+        });
+
+        Self {
+            pool,
+            scanner,
+            fail_open,
+            counters: Arc::default(),
+        }
+    }
+
+    /// Use a specific scanner, e.g. a fake in tests
+    #[must_use]
+    pub fn with_scanner(pool: PgPool, scanner: Arc<dyn MalwareScanner>, fail_open: bool) -> Self {
+        Self {
+            pool,
+            scanner: Some(scanner),
+            fail_open,
+            counters: Arc::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.scanner.is_some()
+    }
+
+    /// Reject `data` if the scanner finds malware in it, keeping a copy in quarantine.
+    /// If no verdict can be reached the upload is refused, unless scanning fails open.
+    pub async fn check(&self, data: &[u8]) -> Result<(), AppError> {
+        let Some(scanner) = &self.scanner else {
+            return Ok(());
+        };
+        self.counters.scanned.fetch_add(1, Ordering::Relaxed);
+
+        match scanner.scan(data).await {
+            Ok(ScanVerdict::Clean) => {
+                self.counters.clean.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Ok(ScanVerdict::Infected(signature)) => {
+                self.counters.infected.fetch_add(1, Ordering::Relaxed);
+                let id = self.quarantine(scanner.name(), &signature, data).await?;
+                tracing::warn!(
+                    "Upload quarantined as {} by {}: {}",
+                    id,
+                    scanner.name(),
+                    signature
+                );
+                Err(AppError::Image(
+                    "The upload was rejected by the malware scan".to_string(),
+                ))
+            }
+            Err(e) => {
+                self.counters.errors.fetch_add(1, Ordering::Relaxed);
+                if self.fail_open {
+                    self.counters.skipped.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!("Malware scan failed, accepting upload unscanned: {:?}", e);
+                    return Ok(());
+                }
+                Err(AppError::Internal(e.context("Malware scan failed")))
+            }
+        }
+    }
+
+    async fn quarantine(
+        &self,
+        scanner: &str,
+        signature: &str,
+        data: &[u8],
+    ) -> Result<Uuid, AppError> {
+        Ok(sqlx::query_scalar(
+            r"
+            INSERT INTO quarantined_uploads (content_hash, size_bytes, signature, scanner, data)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            ",
+        )
+        .bind(format!("{:x}", Sha256::digest(data)))
+        .bind(i64::try_from(data.len()).unwrap_or(i64::MAX))
+        .bind(signature)
+        .bind(scanner)
+        .bind(data)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    /// Scan outcomes on this instance since it started
+    #[must_use]
+    pub fn counts(&self) -> UploadScanCounts {
+        let counters = &self.counters;
+        UploadScanCounts {
+            scanned: counters.scanned.load(Ordering::Relaxed),
+            clean: counters.clean.load(Ordering::Relaxed),
+            infected: counters.infected.load(Ordering::Relaxed),
+            errors: counters.errors.load(Ordering::Relaxed),
+            skipped: counters.skipped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The scan counts with the `limit` most recently quarantined uploads
+    pub async fn report(&self, limit: i64) -> Result<UploadScanReport, AppError> {
+        let quarantined = sqlx::query_as::<_, QuarantinedUpload>(
+            r"
+            SELECT id, content_hash, size_bytes, signature, scanner, created_at
+            FROM quarantined_uploads
+            ORDER BY created_at DESC
+            LIMIT $1
+            ",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(UploadScanReport {
+            scanner: self.scanner.as_ref().map(|s| s.name().to_string()),
+            counts: self.counts(),
+            quarantined,
+        })
+    }
+
+    /// The bytes of a quarantined upload, for handing to a security team
+    pub async fn quarantined_data(&self, id: Uuid) -> Result<Vec<u8>, AppError> {
+        sqlx::query_scalar("SELECT data FROM quarantined_uploads WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Quarantined upload not found".to_string()))
+    }
+
+    /// Delete a quarantined upload once it has been dealt with
+    pub async fn delete_quarantined(&self, id: Uuid) -> Result<(), AppError> {
+        let deleted = sqlx::query("DELETE FROM quarantined_uploads WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(AppError::NotFound(
+                "Quarantined upload not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
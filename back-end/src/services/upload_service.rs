@@ -0,0 +1,404 @@
+use crate::config::ImageConfig;
+use crate::error::AppError;
+use crate::models::upload::{PhotoUpload, UploadStatusResponse};
+use crate::services::s3_service::{S3Service, StorageArea};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Largest chunk a client may send in one request; small enough to get through on a poor connection
+pub const MAX_CHUNK_BYTES: usize = 1024 * 1024;
+/// Buffered chunks are flushed to S3 once a part reaches this size (the S3 multipart minimum)
+const PART_SIZE_BYTES: usize = 5 * 1024 * 1024;
+const UPLOAD_EXPIRY_HOURS: i64 = 24;
+
+#[derive(Clone)]
+pub struct UploadService {
+    pool: PgPool,
+    s3_service: S3Service,
+    config: ImageConfig,
+}
+
+impl UploadService {
+    #[must_use]
+    pub fn new(pool: PgPool, s3_service: S3Service, config: ImageConfig) -> Self {
+        Self {
+            pool,
+            s3_service,
+            config,
+        }
+    }
+
+    /// Start a chunked upload backed by an S3 multipart upload
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        total_size: i64,
+    ) -> Result<UploadStatusResponse, AppError> {
+        let max_size_bytes =
+            i64::try_from(self.config.max_size_mb * 1024 * 1024).unwrap_or(i64::MAX);
+        if total_size <= 0 || total_size > max_size_bytes {
+            return Err(AppError::BadRequest(format!(
+                "Upload size must be between 1 byte and {}MB",
+                self.config.max_size_mb
+            )));
+        }
+
+        let upload_id = Uuid::new_v4();
+        let s3_key = self
+            .s3_service
+            .object_key(&format!("uploads/{}/{}", user_id, upload_id));
+        let s3_upload_id = self
+            .s3_service
+            .create_multipart_upload(StorageArea::ReportPhotos, &s3_key)
+            .await?;
+        let expires_at = Utc::now() + Duration::hours(UPLOAD_EXPIRY_HOURS);
+
+        let upload = sqlx::query_as!(
+            PhotoUpload,
+            r#"
+            INSERT INTO photo_uploads (id, user_id, s3_key, s3_upload_id, total_size, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, s3_key, s3_upload_id, total_size, received_bytes,
+                      next_part_number, completed_at, expires_at, created_at
+            "#,
+            upload_id,
+            user_id,
+            s3_key,
+            s3_upload_id,
+            total_size,
+            expires_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Self::status_response(&upload))
+    }
+
+    pub async fn status(
+        &self,
+        upload_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<UploadStatusResponse, AppError> {
+        let upload = sqlx::query_as!(
+            PhotoUpload,
+            r#"
+            SELECT id, user_id, s3_key, s3_upload_id, total_size, received_bytes,
+                   next_part_number, completed_at, expires_at, created_at
+            FROM photo_uploads
+            WHERE id = $1 AND user_id = $2
+            "#,
+            upload_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Upload not found".to_string()))?;
+
+        Ok(Self::status_response(&upload))
+    }
+
+    /// Append a chunk at `offset`, which must match the bytes received so far.
+    /// Chunks are buffered until a full S3 part is available.
+    pub async fn append(
+        &self,
+        upload_id: Uuid,
+        user_id: Uuid,
+        offset: i64,
+        chunk: &[u8],
+    ) -> Result<UploadStatusResponse, AppError> {
+        if chunk.is_empty() || chunk.len() > MAX_CHUNK_BYTES {
+            return Err(AppError::BadRequest(format!(
+                "Chunks must be between 1 and {} bytes",
+                MAX_CHUNK_BYTES
+            )));
+        }
+
+        // The row lock serialises appends, so a retried chunk cannot race the original
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT s3_key, s3_upload_id, total_size, received_bytes, pending,
+                   next_part_number, completed_at, expires_at
+            FROM photo_uploads
+            WHERE id = $1 AND user_id = $2
+            FOR UPDATE
+            "#,
+            upload_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Upload not found".to_string()))?;
+
+        if row.completed_at.is_some() {
+            return Err(AppError::Conflict("Upload is already complete".to_string()));
+        }
+        if row.expires_at < Utc::now() {
+            return Err(AppError::BadRequest("Upload has expired".to_string()));
+        }
+        if offset != row.received_bytes {
+            return Err(AppError::Conflict(format!(
+                "Chunk offset {} does not match the {} bytes received so far",
+                offset, row.received_bytes
+            )));
+        }
+
+        let chunk_len = i64::try_from(chunk.len()).unwrap_or(i64::MAX);
+        if row.received_bytes + chunk_len > row.total_size {
+            return Err(AppError::BadRequest(
+                "Chunk extends past the declared upload size".to_string(),
+            ));
+        }
+
+        let mut pending = row.pending;
+        pending.extend_from_slice(chunk);
+        let mut next_part_number = row.next_part_number;
+
+        if pending.len() >= PART_SIZE_BYTES {
+            self.flush_part(
+                &mut tx,
+                upload_id,
+                &row.s3_key,
+                &row.s3_upload_id,
+                next_part_number,
+                std::mem::take(&mut pending),
+            )
+            .await?;
+            next_part_number += 1;
+        }
+
+        let upload = sqlx::query_as!(
+            PhotoUpload,
+            r#"
+            UPDATE photo_uploads
+            SET received_bytes = received_bytes + $2,
+                pending = $3,
+                next_part_number = $4
+            WHERE id = $1
+            RETURNING id, user_id, s3_key, s3_upload_id, total_size, received_bytes,
+                      next_part_number, completed_at, expires_at, created_at
+            "#,
+            upload_id,
+            chunk_len,
+            pending,
+            next_part_number
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Self::status_response(&upload))
+    }
+
+    /// Flush any buffered bytes and assemble the S3 object. Completing twice is a no-op.
+    pub async fn complete(
+        &self,
+        upload_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<UploadStatusResponse, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT s3_key, s3_upload_id, total_size, received_bytes, pending,
+                   next_part_number, completed_at, expires_at
+            FROM photo_uploads
+            WHERE id = $1 AND user_id = $2
+            FOR UPDATE
+            "#,
+            upload_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Upload not found".to_string()))?;
+
+        if row.completed_at.is_none() {
+            if row.expires_at < Utc::now() {
+                return Err(AppError::BadRequest("Upload has expired".to_string()));
+            }
+            if row.received_bytes != row.total_size {
+                return Err(AppError::BadRequest(format!(
+                    "Upload incomplete: {} of {} bytes received",
+                    row.received_bytes, row.total_size
+                )));
+            }
+
+            // The final part may be smaller than the S3 minimum
+            if !row.pending.is_empty() {
+                self.flush_part(
+                    &mut tx,
+                    upload_id,
+                    &row.s3_key,
+                    &row.s3_upload_id,
+                    row.next_part_number,
+                    row.pending,
+                )
+                .await?;
+            }
+
+            let parts = sqlx::query!(
+                "SELECT part_number, etag FROM photo_upload_parts WHERE upload_id = $1 ORDER BY part_number",
+                upload_id
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|part| (part.part_number, part.etag))
+            .collect();
+
+            self.s3_service
+                .complete_multipart_upload(
+                    StorageArea::ReportPhotos,
+                    &row.s3_key,
+                    &row.s3_upload_id,
+                    parts,
+                )
+                .await?;
+        }
+
+        let upload = sqlx::query_as!(
+            PhotoUpload,
+            r#"
+            UPDATE photo_uploads
+            SET completed_at = COALESCE(completed_at, NOW()),
+                pending = ''::bytea
+            WHERE id = $1
+            RETURNING id, user_id, s3_key, s3_upload_id, total_size, received_bytes,
+                      next_part_number, completed_at, expires_at, created_at
+            "#,
+            upload_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Self::status_response(&upload))
+    }
+
+    /// Consume a completed upload, returning its bytes. Each upload can be used once.
+    pub async fn take_completed(
+        &self,
+        upload_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<u8>, AppError> {
+        let s3_key = sqlx::query_scalar!(
+            r#"
+            DELETE FROM photo_uploads
+            WHERE id = $1 AND user_id = $2 AND completed_at IS NOT NULL
+            RETURNING s3_key
+            "#,
+            upload_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest("Photo upload not found or not complete".to_string())
+        })?;
+
+        let data = self
+            .s3_service
+            .get_image(StorageArea::ReportPhotos, &s3_key)
+            .await?;
+
+        if let Err(e) = self
+            .s3_service
+            .delete_image(StorageArea::ReportPhotos, &s3_key)
+            .await
+        {
+            tracing::warn!("Failed to delete consumed upload {}: {:?}", s3_key, e);
+        }
+
+        Ok(data)
+    }
+
+    /// Cancel an upload and discard whatever was stored
+    pub async fn abort(&self, upload_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let upload = sqlx::query!(
+            r#"
+            DELETE FROM photo_uploads
+            WHERE id = $1 AND user_id = $2
+            RETURNING s3_key, s3_upload_id, completed_at
+            "#,
+            upload_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Upload not found".to_string()))?;
+
+        let result = if upload.completed_at.is_some() {
+            self.s3_service
+                .delete_image(StorageArea::ReportPhotos, &upload.s3_key)
+                .await
+        } else {
+            self.s3_service
+                .abort_multipart_upload(
+                    StorageArea::ReportPhotos,
+                    &upload.s3_key,
+                    &upload.s3_upload_id,
+                )
+                .await
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to discard upload {}: {:?}", upload.s3_key, e);
+        }
+
+        Ok(())
+    }
+
+    async fn flush_part(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        upload_id: Uuid,
+        s3_key: &str,
+        s3_upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<(), AppError> {
+        let size = i64::try_from(data.len()).unwrap_or(i64::MAX);
+        let etag = self
+            .s3_service
+            .upload_part(
+                StorageArea::ReportPhotos,
+                s3_key,
+                s3_upload_id,
+                part_number,
+                data,
+            )
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO photo_upload_parts (upload_id, part_number, etag, size)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (upload_id, part_number) DO UPDATE
+            SET etag = EXCLUDED.etag, size = EXCLUDED.size
+            "#,
+            upload_id,
+            part_number,
+            etag,
+            size
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    fn status_response(upload: &PhotoUpload) -> UploadStatusResponse {
+        UploadStatusResponse {
+            upload_id: upload.id,
+            total_size: upload.total_size,
+            received_bytes: upload.received_bytes,
+            max_chunk_size: MAX_CHUNK_BYTES as i64,
+            completed: upload.completed_at.is_some(),
+            expires_at: upload.expires_at,
+        }
+    }
+}
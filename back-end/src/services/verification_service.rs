@@ -0,0 +1,825 @@
+use crate::config::ScoringConfig;
+use crate::error::AppError;
+use crate::models::notification::NotificationKind;
+use crate::models::report::{LitterReport, ReportStatus};
+use crate::models::report_lifecycle::{ReportEventKind, ReportTransition};
+use crate::models::verification::{
+    CreateVerificationRequest, ReportVerification, VerificationReservation,
+};
+use crate::services::notification_service::NotificationService;
+use crate::services::report_service::ReportService;
+use crate::services::scoring_service::{ScoringService, DEFAULT_VERIFIER_WEIGHT};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Community verification of cleared reports: who may verify, how votes are tallied and
+/// when a report is promoted to verified
+#[derive(Clone)]
+pub struct VerificationService {
+    pool: PgPool,
+    report_service: ReportService,
+    scoring_service: ScoringService,
+    notification_service: NotificationService,
+    config: ScoringConfig,
+}
+
+impl VerificationService {
+    #[must_use]
+    pub fn new(
+        pool: PgPool,
+        report_service: ReportService,
+        scoring_service: ScoringService,
+        notification_service: NotificationService,
+        config: ScoringConfig,
+    ) -> Self {
+        Self {
+            pool,
+            report_service,
+            scoring_service,
+            notification_service,
+            config,
+        }
+    }
+
+    /// Record `verifier_id`'s verdict on a cleared report, award their points and promote
+    /// the report once enough verifiers agree
+    pub async fn create_verification(
+        &self,
+        report_id: Uuid,
+        verifier_id: Uuid,
+        request: CreateVerificationRequest,
+    ) -> Result<ReportVerification, AppError> {
+        check_verdict(&request)?;
+
+        let report = self.check_can_verify(report_id, verifier_id).await?;
+
+        let photo = match request.photo_base64 {
+            Some(photo_base64) => Some(
+                self.report_service
+                    .save_verification_photo(verifier_id, report_id, photo_base64)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let verification = sqlx::query_as::<_, ReportVerification>(
+            r"
+            INSERT INTO report_verifications
+                (report_id, verifier_id, is_verified, requested_better_photo, comment,
+                 photo, photo_width, photo_height, photo_blurhash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, report_id, verifier_id, is_verified, requested_better_photo,
+                      comment, photo, photo_width, photo_height, photo_blurhash, created_at
+            ",
+        )
+        .bind(report_id)
+        .bind(verifier_id)
+        .bind(request.is_verified)
+        .bind(request.request_better_photo)
+        .bind(&request.comment)
+        .bind(photo.as_ref().map(|photo| &photo.url))
+        .bind(photo.as_ref().and_then(|photo| photo.width))
+        .bind(photo.as_ref().and_then(|photo| photo.height))
+        .bind(photo.as_ref().and_then(|photo| photo.blurhash.as_ref()))
+        .fetch_one(&self.pool)
+        .await?;
+
+        // The verdict is in, so the slot the verifier held is no longer needed
+        sqlx::query("DELETE FROM verification_reservations WHERE report_id = $1 AND user_id = $2")
+            .bind(report_id)
+            .bind(verifier_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.scoring_service
+            .award_verification_points(verifier_id, request.is_verified, photo.is_some())
+            .await?;
+
+        if request.request_better_photo {
+            self.request_photo_retake(report_id, verifier_id).await?;
+
+            if let Some(clearer_id) = report.cleared_by {
+                self.notification_service
+                    .notify(
+                        clearer_id,
+                        NotificationKind::PhotoRetakeRequested,
+                        "A verifier asked for a better photo",
+                        &format!(
+                            "Your after photo was hard to judge. Upload a clearer one within {} hours so your cleanup can be verified.",
+                            self.config.photo_retake_window_hours
+                        ),
+                        Some(report.id),
+                    )
+                    .await;
+            }
+        } else if request.is_verified {
+            self.promote_to_verified(report_id).await?;
+        } else {
+            self.dispute_if_rejected(report_id).await?;
+        }
+
+        Ok(verification)
+    }
+
+    /// Everything that stops `verifier_id` giving a verdict on the report right now
+    async fn check_can_verify(
+        &self,
+        report_id: Uuid,
+        verifier_id: Uuid,
+    ) -> Result<LitterReport, AppError> {
+        // Check if user can verify reports (has cleared enough)
+        if !self.scoring_service.can_verify_reports(verifier_id).await? {
+            return Err(AppError::Forbidden(format!(
+                "You need to clear at least {} reports before you can verify others",
+                self.config.min_clears_to_verify
+            )));
+        }
+
+        let report = self.report_service.get_report_by_id(report_id).await?;
+
+        ReportTransition::Verify.apply(&report.status)?;
+
+        check_not_own_clear(report.cleared_by, verifier_id)?;
+
+        // Hold off until the clearer uploads the better photo someone asked for, or the
+        // window passes
+        if self.retake_pending(report_id).await? {
+            return Err(AppError::BadRequest(
+                "A better photo has been requested for this report; verify it once uploaded"
+                    .to_string(),
+            ));
+        }
+
+        let existing = sqlx::query!(
+            r#"
+            SELECT id FROM report_verifications
+            WHERE report_id = $1 AND verifier_id = $2 AND superseded_at IS NULL
+            "#,
+            report_id,
+            verifier_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if existing.is_some() {
+            return Err(AppError::BadRequest(
+                "You have already verified this report".to_string(),
+            ));
+        }
+
+        Ok(report)
+    }
+
+    /// Hold one of the report's open verification slots for `verifier_id`, so others
+    /// browsing the queue don't all head out to check the same clear. Reserving again
+    /// extends the hold.
+    pub async fn reserve(
+        &self,
+        report_id: Uuid,
+        verifier_id: Uuid,
+    ) -> Result<VerificationReservation, AppError> {
+        self.check_can_verify(report_id, verifier_id).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        // Serialise reservations on the report so two verifiers can't take the last slot
+        sqlx::query("SELECT id FROM litter_reports WHERE id = $1 FOR UPDATE")
+            .bind(report_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let slots_left: i64 = sqlx::query_scalar(
+            r"
+            SELECT GREATEST(
+                $2::bigint - (
+                    SELECT COUNT(*) FROM report_verifications
+                    WHERE report_id = $1 AND superseded_at IS NULL
+                ),
+                1
+            ) - (
+                SELECT COUNT(*) FROM verification_reservations
+                WHERE report_id = $1 AND user_id != $3 AND expires_at > NOW()
+            )
+            ",
+        )
+        .bind(report_id)
+        .bind(i64::from(self.config.min_verifications_needed))
+        .bind(verifier_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if slots_left <= 0 {
+            return Err(AppError::Conflict(
+                "Every open verification slot on this report is reserved".to_string(),
+            ));
+        }
+
+        let reservation = sqlx::query_as::<_, VerificationReservation>(
+            r"
+            INSERT INTO verification_reservations (report_id, user_id, expires_at)
+            VALUES ($1, $2, NOW() + make_interval(mins => $3))
+            ON CONFLICT (report_id, user_id)
+            DO UPDATE SET reserved_at = NOW(), expires_at = EXCLUDED.expires_at
+            RETURNING report_id, reserved_at, expires_at
+            ",
+        )
+        .bind(report_id)
+        .bind(verifier_id)
+        .bind(i32::try_from(self.config.verification_reservation_mins).unwrap_or(i32::MAX))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(reservation)
+    }
+
+    /// Give up `verifier_id`'s reservation on the report
+    pub async fn release(&self, report_id: Uuid, verifier_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r"
+            DELETE FROM verification_reservations
+            WHERE report_id = $1 AND user_id = $2 AND expires_at > NOW()
+            ",
+        )
+        .bind(report_id)
+        .bind(verifier_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Reservation not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Combined weight of the verifiers approving the report's current photo
+    pub async fn tally(&self, report_id: Uuid) -> Result<f64, AppError> {
+        self.weighted_verdicts(report_id, true).await
+    }
+
+    /// Combined weight of the verifiers who gave `is_verified` on the report's current
+    /// photo. Requests for a better photo are not rejections.
+    async fn weighted_verdicts(&self, report_id: Uuid, is_verified: bool) -> Result<f64, AppError> {
+        let weight: f64 = sqlx::query_scalar(
+            r"
+            SELECT COALESCE(SUM(COALESCE(w.weight, $3)), 0)::double precision
+            FROM report_verifications v
+            LEFT JOIN verifier_weights w ON w.user_id = v.verifier_id
+            WHERE v.report_id = $1 AND v.is_verified = $2
+              AND v.requested_better_photo = false AND v.superseded_at IS NULL
+            ",
+        )
+        .bind(report_id)
+        .bind(is_verified)
+        .bind(DEFAULT_VERIFIER_WEIGHT)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(weight)
+    }
+
+    /// Mark a cleared report verified if its tally has reached the threshold, awarding the
+    /// clearer's bonus and settling the verdicts. Returns whether the report was promoted
+    /// by this call.
+    pub async fn promote_to_verified(&self, report_id: Uuid) -> Result<bool, AppError> {
+        if !reaches_threshold(
+            self.tally(report_id).await?,
+            self.config.min_verifications_needed,
+        ) {
+            return Ok(false);
+        }
+
+        self.mark_verified(report_id, None).await
+    }
+
+    /// Move a cleared report to verified, paying the clearer's bonus and settling the
+    /// verdicts. Returns false if the report was no longer cleared.
+    async fn mark_verified(&self, report_id: Uuid, note: Option<&str>) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Only one caller wins the transition, so the bonus is paid once
+        let promoted = sqlx::query!(
+            r#"
+            UPDATE litter_reports SET status = $1
+            WHERE id = $2 AND status = $3
+            RETURNING cleared_by
+            "#,
+            ReportStatus::Verified as ReportStatus,
+            report_id,
+            ReportStatus::Cleared as ReportStatus
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(promoted) = promoted else {
+            return Ok(false);
+        };
+
+        ReportService::record_status_change(
+            &mut tx,
+            report_id,
+            ReportTransition::Verify,
+            &ReportStatus::Cleared,
+            &ReportStatus::Verified,
+            None,
+            note,
+        )
+        .await?;
+        self.scoring_service
+            .settle_verifications(&mut tx, report_id, true)
+            .await?;
+        tx.commit().await?;
+
+        if let Some(clearer_id) = promoted.cleared_by {
+            self.scoring_service
+                .award_verified_report_bonus(clearer_id)
+                .await?;
+
+            self.notification_service
+                .notify(
+                    clearer_id,
+                    NotificationKind::ReportVerified,
+                    "Your cleanup was verified",
+                    "The community confirmed your cleanup. Bonus points have been added to your score.",
+                    Some(report_id),
+                )
+                .await;
+        }
+
+        Ok(true)
+    }
+
+    /// Mark a cleared report disputed once verifiers rejecting its current photo carry
+    /// as much weight as it takes to verify one, settling the verdicts. Returns whether
+    /// this call disputed it.
+    pub async fn dispute_if_rejected(&self, report_id: Uuid) -> Result<bool, AppError> {
+        let rejections = self.weighted_verdicts(report_id, false).await?;
+        if !reaches_threshold(rejections, self.config.min_verifications_needed) {
+            return Ok(false);
+        }
+
+        let disputed = self
+            .mark_disputed(
+                report_id,
+                &format!("Verifiers with weight {rejections:.2} rejected the clear"),
+            )
+            .await?;
+        if disputed {
+            tracing::info!("Report {report_id} disputed after rejections weighing {rejections:.2}");
+        }
+        Ok(disputed)
+    }
+
+    /// Move a cleared report to disputed and settle the verdicts. Returns false if the
+    /// report was no longer cleared.
+    async fn mark_disputed(&self, report_id: Uuid, note: &str) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let disputed =
+            sqlx::query("UPDATE litter_reports SET status = $1 WHERE id = $2 AND status = $3")
+                .bind(ReportStatus::Disputed)
+                .bind(report_id)
+                .bind(ReportStatus::Cleared)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+                > 0;
+        if !disputed {
+            return Ok(false);
+        }
+
+        ReportService::record_status_change(
+            &mut tx,
+            report_id,
+            ReportTransition::Dispute,
+            &ReportStatus::Cleared,
+            &ReportStatus::Disputed,
+            None,
+            Some(note),
+        )
+        .await?;
+        self.scoring_service
+            .settle_verifications(&mut tx, report_id, false)
+            .await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Settle clears that have waited more than `after_days` without reaching a verdict,
+    /// on whatever verifiers have said so far: disputed if rejections outweigh approvals,
+    /// otherwise verified, which is also how a clear nobody judged ends. Reports waiting
+    /// on a photo retake are left until it is uploaded or lapses. Returns each settled
+    /// report with whether it was verified.
+    pub async fn finalize_overdue_clears(
+        &self,
+        after_days: i64,
+    ) -> Result<Vec<(Uuid, bool)>, AppError> {
+        let due: Vec<Uuid> = sqlx::query_scalar(
+            r"
+            SELECT r.id FROM litter_reports r
+            WHERE r.status = $1
+              AND r.cleared_at < NOW() - make_interval(days => $2)
+              AND NOT EXISTS (
+                  SELECT 1 FROM photo_retake_requests p
+                  WHERE p.report_id = r.id AND p.fulfilled_at IS NULL AND p.expires_at > NOW()
+              )
+            ORDER BY r.cleared_at
+            ",
+        )
+        .bind(ReportStatus::Cleared)
+        .bind(i32::try_from(after_days).unwrap_or(i32::MAX))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut finalized = Vec::new();
+        for report_id in due {
+            let approvals = self.weighted_verdicts(report_id, true).await?;
+            let rejections = self.weighted_verdicts(report_id, false).await?;
+            let verified = rejections <= approvals;
+            let note = format!(
+                "Verification window of {after_days} days closed with approvals weighing {approvals:.2} and rejections {rejections:.2}"
+            );
+
+            let settled = if verified {
+                self.mark_verified(report_id, Some(&note)).await?
+            } else {
+                self.mark_disputed(report_id, &note).await?
+            };
+            if settled {
+                finalized.push((report_id, verified));
+            }
+        }
+
+        Ok(finalized)
+    }
+
+    /// Send reports disputed more than `after_hours` ago back to pending, since nobody
+    /// has shown the litter is gone. Each clearer loses what the clear earned, and the
+    /// after photos and verdicts on them are dropped so the next clear starts afresh.
+    /// Returns each reopened report with its clearer.
+    pub async fn reopen_rejected_clears(
+        &self,
+        after_hours: i64,
+    ) -> Result<Vec<(Uuid, Option<Uuid>)>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Disputes an admin set by hand have no dispute event and wait for them
+        let due: Vec<(Uuid, Option<Uuid>)> = sqlx::query_as(
+            r"
+            SELECT r.id, r.cleared_by FROM litter_reports r
+            WHERE r.status = $1
+              AND (
+                  SELECT MAX(e.occurred_at) FROM report_events e
+                  WHERE e.report_id = r.id AND e.kind = $2
+              ) < NOW() - make_interval(hours => $3)
+            FOR UPDATE OF r SKIP LOCKED
+            ",
+        )
+        .bind(ReportStatus::Disputed)
+        .bind(ReportEventKind::Disputed)
+        .bind(i32::try_from(after_hours).unwrap_or(i32::MAX))
+        .fetch_all(&mut *tx)
+        .await?;
+        if due.is_empty() {
+            return Ok(due);
+        }
+        let report_ids: Vec<Uuid> = due.iter().map(|(report_id, _)| *report_id).collect();
+
+        self.undo_clears(&mut tx, &due).await?;
+        ReportService::record_status_changes(
+            &mut tx,
+            &report_ids,
+            ReportTransition::Reopen,
+            Some(&format!("Dispute unsettled after {after_hours} hours")),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(due)
+    }
+
+    /// Settle a cleared or disputed report by hand, whatever verifiers have said:
+    /// `verified` pays the clearer's bonus, otherwise the clear is undone as if a dispute
+    /// had gone unsettled. Open verdicts count towards their verifiers' records against
+    /// the decision. The clearer is notified either way.
+    pub async fn override_verdict(
+        &self,
+        report_id: Uuid,
+        admin_id: Uuid,
+        verified: bool,
+        reason: &str,
+    ) -> Result<LitterReport, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let (status, cleared_by): (ReportStatus, Option<Uuid>) = sqlx::query_as(
+            "SELECT status, cleared_by FROM litter_reports WHERE id = $1 FOR UPDATE",
+        )
+        .bind(report_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))?;
+        if !matches!(status, ReportStatus::Cleared | ReportStatus::Disputed) {
+            return Err(AppError::BadRequest(
+                "Only a cleared or disputed report's verification can be overridden".to_string(),
+            ));
+        }
+
+        self.scoring_service
+            .settle_verifications(&mut tx, report_id, verified)
+            .await?;
+        if verified {
+            sqlx::query("UPDATE litter_reports SET status = $1 WHERE id = $2")
+                .bind(ReportStatus::Verified)
+                .bind(report_id)
+                .execute(&mut *tx)
+                .await?;
+            ReportService::record_status_change(
+                &mut tx,
+                report_id,
+                ReportTransition::Verify,
+                &status,
+                &ReportStatus::Verified,
+                Some(admin_id),
+                Some(reason),
+            )
+            .await?;
+        } else {
+            self.undo_clears(&mut tx, &[(report_id, cleared_by)])
+                .await?;
+            ReportService::record_status_change(
+                &mut tx,
+                report_id,
+                ReportTransition::Reopen,
+                &status,
+                &ReportStatus::Pending,
+                Some(admin_id),
+                Some(reason),
+            )
+            .await?;
+        }
+        tx.commit().await?;
+
+        tracing::info!(
+            "{admin_id} overrode verification of report {report_id} as {}",
+            if verified { "verified" } else { "rejected" }
+        );
+
+        if let Some(clearer_id) = cleared_by {
+            if verified {
+                self.scoring_service
+                    .award_verified_report_bonus(clearer_id)
+                    .await?;
+                self.notification_service
+                    .notify(
+                        clearer_id,
+                        NotificationKind::ReportVerified,
+                        "Your cleanup was verified",
+                        "A moderator confirmed your cleanup. Bonus points have been added to your score.",
+                        Some(report_id),
+                    )
+                    .await;
+            } else {
+                self.notification_service
+                    .notify(
+                        clearer_id,
+                        NotificationKind::ClearRejected,
+                        "Your cleanup was rejected",
+                        "A moderator found litter still there, so the report is open to pickers again and the points for clearing it have been removed.",
+                        Some(report_id),
+                    )
+                    .await;
+            }
+        }
+
+        self.report_service.get_report_by_id(report_id).await
+    }
+
+    /// Send reports back to pending within `tx`: the after photos and verdicts on them
+    /// are dropped and each clearer loses what the clear earned
+    async fn undo_clears(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        clears: &[(Uuid, Option<Uuid>)],
+    ) -> Result<(), AppError> {
+        let report_ids: Vec<Uuid> = clears.iter().map(|(report_id, _)| *report_id).collect();
+
+        sqlx::query(
+            r"
+            UPDATE litter_reports
+            SET status = $2,
+                claimed_by = NULL,
+                claimed_at = NULL,
+                cleared_by = NULL,
+                cleared_at = NULL,
+                cleanup_run_id = NULL,
+                photo_after = NULL,
+                photo_after_width = NULL,
+                photo_after_height = NULL,
+                photo_after_blurhash = NULL
+            WHERE id = ANY($1)
+            ",
+        )
+        .bind(&report_ids)
+        .bind(ReportStatus::Pending)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query("DELETE FROM report_photos WHERE report_id = ANY($1) AND kind = 'after'")
+            .bind(&report_ids)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query(
+            r"
+            UPDATE report_verifications SET superseded_at = NOW()
+            WHERE report_id = ANY($1) AND superseded_at IS NULL
+            ",
+        )
+        .bind(&report_ids)
+        .execute(&mut **tx)
+        .await?;
+
+        for (report_id, cleared_by) in clears {
+            if cleared_by.is_some() {
+                self.scoring_service
+                    .transfer_clear_points(tx, *report_id, *cleared_by, None)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get one page of a report's verifications, newest first, with the total count
+    pub async fn list_verifications(
+        &self,
+        report_id: Uuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ReportVerification>, i64), AppError> {
+        // Verify report exists
+        self.report_service.get_report_by_id(report_id).await?;
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM report_verifications WHERE report_id = $1"#,
+            report_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let verifications = sqlx::query_as::<_, ReportVerification>(
+            r"
+            SELECT id, report_id, verifier_id, is_verified, requested_better_photo,
+                   comment, photo, photo_width, photo_height, photo_blurhash, created_at
+            FROM report_verifications
+            WHERE report_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            ",
+        )
+        .bind(report_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((verifications, total))
+    }
+
+    async fn retake_pending(&self, report_id: Uuid) -> Result<bool, AppError> {
+        let pending = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM photo_retake_requests
+                WHERE report_id = $1 AND fulfilled_at IS NULL AND expires_at > NOW()
+            ) AS "pending!"
+            "#,
+            report_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(pending)
+    }
+
+    /// Open a retake window for the clearer and restart the report's verification tally
+    async fn request_photo_retake(
+        &self,
+        report_id: Uuid,
+        requested_by: Uuid,
+    ) -> Result<(), AppError> {
+        let expires_at = Utc::now() + Duration::hours(self.config.photo_retake_window_hours);
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE report_verifications SET superseded_at = NOW()
+            WHERE report_id = $1 AND superseded_at IS NULL
+            "#,
+            report_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO photo_retake_requests (report_id, requested_by, expires_at)
+            VALUES ($1, $2, $3)
+            "#,
+            report_id,
+            requested_by,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// A better photo can only be asked for instead of approving the current one
+fn check_verdict(request: &CreateVerificationRequest) -> Result<(), AppError> {
+    if request.request_better_photo && request.is_verified {
+        return Err(AppError::BadRequest(
+            "A better photo can only be requested with is_verified: false".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Clearers can't judge their own cleanup. The reporter IS allowed to verify someone
+/// else's cleanup of their report.
+fn check_not_own_clear(cleared_by: Option<Uuid>, verifier_id: Uuid) -> Result<(), AppError> {
+    if cleared_by == Some(verifier_id) {
+        return Err(AppError::BadRequest(
+            "You cannot verify a report you cleared yourself".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether verdicts carrying `weight` are enough to settle a clear either way
+fn reaches_threshold(weight: f64, min_verifications_needed: i32) -> bool {
+    weight >= f64::from(min_verifications_needed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::scoring_service::verifier_weight;
+
+    fn verdict(is_verified: bool, request_better_photo: bool) -> CreateVerificationRequest {
+        CreateVerificationRequest {
+            is_verified,
+            request_better_photo,
+            comment: None,
+            photo_base64: None,
+        }
+    }
+
+    #[test]
+    fn a_better_photo_request_cannot_also_approve() {
+        assert!(matches!(
+            check_verdict(&verdict(true, true)),
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(check_verdict(&verdict(false, true)).is_ok());
+        assert!(check_verdict(&verdict(true, false)).is_ok());
+        assert!(check_verdict(&verdict(false, false)).is_ok());
+    }
+
+    #[test]
+    fn clearers_cannot_verify_their_own_clear() {
+        let clearer = Uuid::new_v4();
+        assert!(matches!(
+            check_not_own_clear(Some(clearer), clearer),
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(check_not_own_clear(Some(clearer), Uuid::new_v4()).is_ok());
+        assert!(check_not_own_clear(None, clearer).is_ok());
+    }
+
+    #[test]
+    fn new_verifiers_settle_a_clear_at_the_configured_count() {
+        assert!(!reaches_threshold(2.0 * DEFAULT_VERIFIER_WEIGHT, 3));
+        assert!(reaches_threshold(3.0 * DEFAULT_VERIFIER_WEIGHT, 3));
+    }
+
+    #[test]
+    fn trusted_verifiers_settle_a_clear_sooner() {
+        let trusted = verifier_weight(18, 18);
+        assert!(reaches_threshold(2.0 * trusted, 3));
+    }
+
+    #[test]
+    fn unreliable_verifiers_need_more_company() {
+        let unreliable = verifier_weight(0, 8);
+        assert!(!reaches_threshold(3.0 * unreliable, 3));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::error::AppError;
+use crate::models::watched_area::{
+    CreateWatchedAreaRequest, WatchedArea, WatchedAreaShape, MAX_WATCHED_RADIUS_KM,
+};
+use sqlx::PgPool;
+use std::f64::consts::PI;
+use uuid::Uuid;
+
+/// Maximum number of areas a single user may watch
+const MAX_WATCHED_AREAS_PER_USER: i64 = 10;
+
+/// Columns of `WatchedArea`; the polygon ring is only read back for polygon areas
+const WATCHED_AREA_COLUMNS: &str = r"
+    id, user_id, name,
+    ST_Y(center)::double precision AS latitude,
+    ST_X(center)::double precision AS longitude,
+    radius_km,
+    CASE WHEN center IS NULL THEN ST_AsGeoJSON(area)::jsonb -> 'coordinates' -> 0 END
+        AS polygon,
+    created_at
+";
+
+#[derive(Clone)]
+pub struct WatchedAreaService {
+    pool: PgPool,
+}
+
+impl WatchedAreaService {
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all areas a user watches
+    pub async fn list_areas(&self, user_id: Uuid) -> Result<Vec<WatchedArea>, AppError> {
+        let areas = sqlx::query_as::<_, WatchedArea>(&format!(
+            "SELECT {WATCHED_AREA_COLUMNS} FROM watched_areas
+             WHERE user_id = $1
+             ORDER BY created_at ASC"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(areas)
+    }
+
+    /// Start watching an area
+    pub async fn create_area(
+        &self,
+        user_id: Uuid,
+        request: CreateWatchedAreaRequest,
+    ) -> Result<WatchedArea, AppError> {
+        let shape = request.shape()?;
+
+        let existing: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM watched_areas WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        if existing >= MAX_WATCHED_AREAS_PER_USER {
+            return Err(AppError::BadRequest(format!(
+                "You can watch at most {MAX_WATCHED_AREAS_PER_USER} areas"
+            )));
+        }
+
+        let name = request.name.trim();
+        let area = match shape {
+            WatchedAreaShape::Circle { center, radius_km } => {
+                sqlx::query_as::<_, WatchedArea>(&format!(
+                    "INSERT INTO watched_areas (user_id, name, center, radius_km, area)
+                     SELECT $1, $2, c.point, $5,
+                            ST_Buffer(c.point::geography, $5 * 1000.0)::geometry
+                     FROM (SELECT ST_SetSRID(ST_MakePoint($4, $3), 4326) AS point) c
+                     RETURNING {WATCHED_AREA_COLUMNS}"
+                ))
+                .bind(user_id)
+                .bind(name)
+                .bind(center.latitude())
+                .bind(center.longitude())
+                .bind(radius_km)
+                .fetch_one(&self.pool)
+                .await?
+            }
+            WatchedAreaShape::Polygon(ring) => {
+                let wkt = format!(
+                    "POLYGON(({}))",
+                    ring.iter()
+                        .map(|point| format!("{} {}", point.longitude(), point.latitude()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+
+                let (valid, area_km2): (bool, f64) = sqlx::query_as(
+                    "SELECT ST_IsValid(g), ST_Area(g::geography) / 1000000.0
+                     FROM (SELECT ST_GeomFromText($1, 4326) AS g) p",
+                )
+                .bind(&wkt)
+                .fetch_one(&self.pool)
+                .await?;
+
+                if !valid {
+                    return Err(AppError::BadRequest(
+                        "The polygon's edges must not cross".to_string(),
+                    ));
+                }
+                // No bigger than the largest circle allowed
+                if area_km2 > PI * MAX_WATCHED_RADIUS_KM * MAX_WATCHED_RADIUS_KM {
+                    return Err(AppError::BadRequest(format!(
+                        "A watched area may be no larger than a circle of radius {MAX_WATCHED_RADIUS_KM} km"
+                    )));
+                }
+
+                sqlx::query_as::<_, WatchedArea>(&format!(
+                    "INSERT INTO watched_areas (user_id, name, area)
+                     VALUES ($1, $2, ST_GeomFromText($3, 4326))
+                     RETURNING {WATCHED_AREA_COLUMNS}"
+                ))
+                .bind(user_id)
+                .bind(name)
+                .bind(&wkt)
+                .fetch_one(&self.pool)
+                .await?
+            }
+        };
+
+        Ok(area)
+    }
+
+    /// Stop watching an area (ownership required)
+    pub async fn delete_area(&self, area_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM watched_areas WHERE id = $1 AND user_id = $2")
+            .bind(area_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Watched area not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Active users, other than the reporter, watching an area that holds the report
+    pub async fn watchers_of(&self, report_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let watchers = sqlx::query_scalar(
+            r"
+            SELECT DISTINCT w.user_id
+            FROM litter_reports r
+            JOIN watched_areas w ON ST_Covers(w.area, r.location)
+            JOIN users u ON u.id = w.user_id
+            WHERE r.id = $1
+              AND w.user_id IS DISTINCT FROM r.reporter_id
+              AND u.is_active
+            ",
+        )
+        .bind(report_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(watchers)
+    }
+}
@@ -1,3 +1,6 @@
+/// Locales the bundled templates are written in
+pub const SUPPORTED_LOCALES: &[&str] = &["en"];
+
 #[must_use]
 pub fn get_email_verification_html() -> &'static str {
     include_str!("email_verification.html")
@@ -28,6 +31,66 @@ pub fn get_password_reset_confirmation_text() -> &'static str {
     include_str!("password_reset_confirmation.txt")
 }
 
+#[must_use]
+pub fn get_account_link_html() -> &'static str {
+    include_str!("account_link.html")
+}
+
+#[must_use]
+pub fn get_account_link_text() -> &'static str {
+    include_str!("account_link.txt")
+}
+
+#[must_use]
+pub fn get_magic_link_html() -> &'static str {
+    include_str!("magic_link.html")
+}
+
+#[must_use]
+pub fn get_magic_link_text() -> &'static str {
+    include_str!("magic_link.txt")
+}
+
+#[must_use]
+pub fn get_email_change_html() -> &'static str {
+    include_str!("email_change.html")
+}
+
+#[must_use]
+pub fn get_email_change_text() -> &'static str {
+    include_str!("email_change.txt")
+}
+
+#[must_use]
+pub fn get_password_setup_html() -> &'static str {
+    include_str!("password_setup.html")
+}
+
+#[must_use]
+pub fn get_password_setup_text() -> &'static str {
+    include_str!("password_setup.txt")
+}
+
+#[must_use]
+pub fn get_login_alert_html() -> &'static str {
+    include_str!("login_alert.html")
+}
+
+#[must_use]
+pub fn get_login_alert_text() -> &'static str {
+    include_str!("login_alert.txt")
+}
+
+#[must_use]
+pub fn get_verification_reminder_html() -> &'static str {
+    include_str!("verification_reminder.html")
+}
+
+#[must_use]
+pub fn get_verification_reminder_text() -> &'static str {
+    include_str!("verification_reminder.txt")
+}
+
 #[must_use]
 pub fn render_template(template: &str, replacements: &[(&str, &str)]) -> String {
     let mut result = template.to_string();
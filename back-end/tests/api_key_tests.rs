@@ -0,0 +1,147 @@
+// Integration tests for partner API key access
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::models::{ApiKeyScope, CreateApiKeyRequest};
+use back_end::services::ApiKeyService;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+const NEARBY: &str = "/api/reports/nearby?latitude=51.5074&longitude=-0.1278&radius_km=5";
+
+/// Register an admin to own the keys and return their id
+async fn create_key_owner(app: &axum::Router) -> Uuid {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": "key-owner@example.com",
+                        "password": "password123",
+                        "full_name": "Key Owner",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind("key-owner@example.com")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to find key owner")
+}
+
+async fn issue_key(
+    owner: Uuid,
+    scopes: Vec<ApiKeyScope>,
+    rate_limit_per_min: i32,
+) -> (Uuid, String) {
+    let service = ApiKeyService::new(get_test_pool().await);
+    let created = service
+        .create_key(
+            owner,
+            CreateApiKeyRequest {
+                name: "Test Council".to_string(),
+                scopes,
+                rate_limit_per_min: Some(rate_limit_per_min),
+            },
+        )
+        .await
+        .expect("Failed to create API key");
+    (created.key.id, created.api_key)
+}
+
+async fn get(app: &axum::Router, uri: &str, api_key: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::builder().method("GET").uri(uri);
+    if let Some(api_key) = api_key {
+        request = request.header("x-api-key", api_key);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_api_key_reads_nearby_reports() {
+    let app = create_test_app().await;
+    let owner = create_key_owner(&app).await;
+    let (_, api_key) = issue_key(owner, vec![ApiKeyScope::ReportsRead], 60).await;
+
+    let (status, body) = get(&app, NEARBY, Some(&api_key)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["items"].is_array());
+
+    // Without a key the route still wants a bearer token
+    let (status, _) = get(&app, NEARBY, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = get(&app, NEARBY, Some("lp_not-a-real-key")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_api_key_scopes_are_enforced() {
+    let app = create_test_app().await;
+    let owner = create_key_owner(&app).await;
+    let (_, api_key) = issue_key(owner, vec![ApiKeyScope::LeaderboardsRead], 60).await;
+
+    let (status, _) = get(&app, "/api/leaderboards", Some(&api_key)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = get(&app, NEARBY, Some(&api_key)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_api_key_rate_limit() {
+    let app = create_test_app().await;
+    let owner = create_key_owner(&app).await;
+    let (_, api_key) = issue_key(owner, vec![ApiKeyScope::ReportsRead], 2).await;
+
+    for _ in 0..2 {
+        let (status, _) = get(&app, NEARBY, Some(&api_key)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _) = get(&app, NEARBY, Some(&api_key)).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_revoked_api_key_is_rejected() {
+    let app = create_test_app().await;
+    let owner = create_key_owner(&app).await;
+    let (key_id, api_key) = issue_key(owner, vec![ApiKeyScope::ReportsRead], 60).await;
+
+    let service = ApiKeyService::new(get_test_pool().await);
+    let revoked = service.revoke_key(key_id).await.unwrap();
+    assert!(revoked.revoked_at.is_some());
+
+    let (status, _) = get(&app, NEARBY, Some(&api_key)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -4,12 +4,13 @@ use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
-use serde_json::json;
+use back_end::models::UserRole;
+use serde_json::{json, Value};
 use tower::ServiceExt;
 
 // Test helper to create test app
 mod helpers;
-use helpers::create_test_app;
+use helpers::{access_token, create_test_app, get_test_pool, insert_user};
 
 #[tokio::test]
 async fn test_user_registration() {
@@ -181,3 +182,1282 @@ async fn test_registration_validation() {
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn test_logout_all_revokes_existing_tokens() {
+    let app = create_test_app().await;
+    let email = "logout-all@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+        .bind(email)
+        .execute(&pool)
+        .await
+        .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "email": email, "password": "password123" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let tokens: Value = serde_json::from_slice(&body).unwrap();
+    let access_token = tokens["access_token"].as_str().unwrap().to_string();
+    let refresh_token = tokens["refresh_token"].as_str().unwrap().to_string();
+
+    // Token issue times have one-second resolution
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/logout-all")
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The old access token is rejected...
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/logout-all")
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // ...and the refresh token can no longer mint new ones
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/refresh")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "refresh_token": refresh_token }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+async fn confirm_account_link(app: &axum::Router, token: &str) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/link-account")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "token": token }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_confirm_account_link_attaches_google_identity() {
+    let app = create_test_app().await;
+    let email = "link-account@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Stand in for the emailed link an OAuth login with this address would create
+    let pool = get_test_pool().await;
+    let token = "pending-google-link";
+    sqlx::query(
+        "INSERT INTO oauth_link_tokens (user_id, token_hash, oauth_provider, oauth_subject, expires_at)
+         SELECT id, $2, 'google', 'google-subject-123', NOW() + INTERVAL '1 hour'
+         FROM users WHERE email = $1",
+    )
+    .bind(email)
+    .bind(back_end::auth::hash_token(token))
+    .execute(&pool)
+    .await
+    .expect("Failed to create link token");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/link-account")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "token": token }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let tokens: Value = serde_json::from_slice(&body).unwrap();
+    assert!(tokens["access_token"].is_string());
+
+    let (provider, subject, verified): (Option<String>, Option<String>, bool) = sqlx::query_as(
+        "SELECT oauth_provider, oauth_subject, email_verified FROM users WHERE email = $1",
+    )
+    .bind(email)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(provider.as_deref(), Some("google"));
+    assert_eq!(subject.as_deref(), Some("google-subject-123"));
+    assert!(verified);
+
+    // Link tokens are single-use
+    assert_eq!(
+        confirm_account_link(&app, token).await,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_conflicting_account_link_token_cannot_be_replayed() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let user_id = insert_user(&pool, "link-conflict@example.com").await;
+    let other_id = insert_user(&pool, "link-conflict-other@example.com").await;
+
+    // Someone else signed in with this Google account after the link was emailed
+    sqlx::query(
+        "UPDATE users SET oauth_provider = 'google', oauth_subject = 'google-subject-789'
+         WHERE id = $1",
+    )
+    .bind(other_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let token = "conflicting-google-link";
+    sqlx::query(
+        "INSERT INTO oauth_link_tokens (user_id, token_hash, oauth_provider, oauth_subject, expires_at)
+         VALUES ($1, $2, 'google', 'google-subject-789', NOW() + INTERVAL '1 hour')",
+    )
+    .bind(user_id)
+    .bind(back_end::auth::hash_token(token))
+    .execute(&pool)
+    .await
+    .expect("Failed to create link token");
+
+    assert_eq!(
+        confirm_account_link(&app, token).await,
+        StatusCode::CONFLICT
+    );
+
+    // The token was consumed even though the link failed
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM oauth_link_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, 0);
+    assert_eq!(
+        confirm_account_link(&app, token).await,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_confirm_account_link_rejects_expired_token() {
+    let app = create_test_app().await;
+    let email = "link-expired@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    let token = "expired-google-link";
+    sqlx::query(
+        "INSERT INTO oauth_link_tokens (user_id, token_hash, oauth_provider, oauth_subject, expires_at)
+         SELECT id, $2, 'google', 'google-subject-456', NOW() - INTERVAL '1 minute'
+         FROM users WHERE email = $1",
+    )
+    .bind(email)
+    .bind(back_end::auth::hash_token(token))
+    .execute(&pool)
+    .await
+    .expect("Failed to create link token");
+
+    assert_eq!(
+        confirm_account_link(&app, token).await,
+        StatusCode::BAD_REQUEST
+    );
+
+    let provider: Option<String> =
+        sqlx::query_scalar("SELECT oauth_provider FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(provider.is_none());
+}
+
+async fn login_from_device(app: &axum::Router, email: &str, user_agent: &str) -> Value {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .header("user-agent", user_agent)
+                .header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+                .body(Body::from(
+                    json!({ "email": email, "password": "password123" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_list_and_revoke_sessions() {
+    let app = create_test_app().await;
+    let email = "sessions@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+        .bind(email)
+        .execute(&pool)
+        .await
+        .expect("Failed to verify user");
+
+    let laptop = login_from_device(&app, email, "Laptop Browser").await;
+    let phone = login_from_device(&app, email, "Phone App").await;
+    let access_token = laptop["access_token"].as_str().unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/users/me/sessions")
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let sessions: Value = serde_json::from_slice(&body).unwrap();
+    let sessions = sessions.as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+
+    let current = sessions
+        .iter()
+        .find(|s| s["current"] == true)
+        .expect("current session should be marked");
+    assert_eq!(current["user_agent"], "Laptop Browser");
+    assert_eq!(current["ip_address"], "203.0.113.7");
+
+    let other = sessions
+        .iter()
+        .find(|s| s["current"] == false)
+        .expect("other session should be listed");
+    assert_eq!(other["user_agent"], "Phone App");
+    let other_id = other["id"].as_str().unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/users/me/sessions/{}", other_id))
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The revoked session's refresh token no longer works...
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/refresh")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "refresh_token": phone["refresh_token"] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // ...while the current one still does
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/refresh")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "refresh_token": laptop["refresh_token"] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Revoking it again, or a session that never existed, is a 404
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/users/me/sessions/{}", other_id))
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+async fn verify_magic_link(app: &axum::Router, token: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/magic-link/verify")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "token": token }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_magic_link_login() {
+    let app = create_test_app().await;
+    let email = "magic-link@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Requesting a link answers the same for known and unknown addresses
+    for address in [email, "nobody@example.com"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/auth/magic-link")
+                    .header("content-type", "application/json")
+                    .body(Body::from(json!({ "email": address }).to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let pool = get_test_pool().await;
+    let issued: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM magic_link_tokens t JOIN users u ON u.id = t.user_id WHERE u.email = $1",
+    )
+    .bind(email)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(issued, 1);
+
+    // Stand in for the emailed token, whose plain value is only in the email
+    let token = "emailed-magic-link";
+    sqlx::query(
+        "UPDATE magic_link_tokens SET token_hash = $2
+         WHERE user_id = (SELECT id FROM users WHERE email = $1)",
+    )
+    .bind(email)
+    .bind(back_end::auth::hash_token(token))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, tokens) = verify_magic_link(&app, token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(tokens["access_token"].is_string());
+    assert!(tokens["refresh_token"].is_string());
+
+    // The emailed link also verifies the address
+    let verified: bool = sqlx::query_scalar("SELECT email_verified FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(verified);
+
+    // Links are single-use
+    let (status, _) = verify_magic_link(&app, token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_magic_link_rejects_expired_token() {
+    let app = create_test_app().await;
+    let email = "magic-link-expired@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    let token = "expired-magic-link";
+    sqlx::query(
+        "INSERT INTO magic_link_tokens (user_id, token_hash, expires_at)
+         SELECT id, $2, NOW() - INTERVAL '1 minute'
+         FROM users WHERE email = $1",
+    )
+    .bind(email)
+    .bind(back_end::auth::hash_token(token))
+    .execute(&pool)
+    .await
+    .expect("Failed to create magic link token");
+
+    let (status, _) = verify_magic_link(&app, token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+async fn unlink_google(app: &axum::Router, access_token: &str, password: &str) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/users/me/oauth/google")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::from(json!({ "password": password }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_unlink_oauth_requires_password() {
+    let app = create_test_app().await;
+    let email = "unlink-google@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // A password account that has since linked Google
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users
+         SET email_verified = true, oauth_provider = 'google', oauth_subject = 'google-subject-unlink'
+         WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to link user");
+
+    let tokens = login_from_device(&app, email, "Laptop Browser").await;
+    let access_token = tokens["access_token"].as_str().unwrap();
+    assert_eq!(tokens["user"]["oauth_provider"], "google");
+    assert_eq!(tokens["user"]["has_password"], true);
+
+    assert_eq!(
+        unlink_google(&app, access_token, "wrong-password").await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        unlink_google(&app, access_token, "password123").await,
+        StatusCode::OK
+    );
+
+    let (provider, subject): (Option<String>, Option<String>) =
+        sqlx::query_as("SELECT oauth_provider, oauth_subject FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(provider.is_none());
+    assert!(subject.is_none());
+
+    // Nothing left to unlink
+    assert_eq!(
+        unlink_google(&app, access_token, "password123").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+async fn post_json(
+    app: &axum::Router,
+    uri: &str,
+    access_token: Option<&str>,
+    body: Value,
+) -> StatusCode {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = access_token {
+        request = request.header("authorization", format!("Bearer {token}"));
+    }
+    app.clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_google_only_account_adds_a_password_from_an_emailed_link() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let email = "google-only@example.com";
+    let user_id = insert_user(&pool, email).await;
+    sqlx::query(
+        "UPDATE users
+         SET password_hash = NULL, oauth_provider = 'google', oauth_subject = 'google-subject-only'
+         WHERE id = $1",
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let token = access_token(user_id, email, UserRole::User);
+
+    // Without a password there is nothing to re-authenticate with...
+    assert_eq!(
+        unlink_google(&app, &token, "password123").await,
+        StatusCode::BAD_REQUEST
+    );
+
+    // ...so the emailed link to the account's address stands in for it
+    assert_eq!(
+        post_json(
+            &app,
+            "/api/users/me/password/setup",
+            Some(&token),
+            json!({})
+        )
+        .await,
+        StatusCode::OK
+    );
+
+    // Stand in for the emailed token, whose plain value is only in the email
+    let setup_token = "emailed-password-setup";
+    sqlx::query("UPDATE password_setup_tokens SET token_hash = $2 WHERE user_id = $1")
+        .bind(user_id)
+        .bind(back_end::auth::hash_token(setup_token))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        post_json(
+            &app,
+            "/api/auth/set-password",
+            None,
+            json!({ "token": setup_token, "new_password": "short" }),
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        post_json(
+            &app,
+            "/api/auth/set-password",
+            None,
+            json!({ "token": setup_token, "new_password": "password123" }),
+        )
+        .await,
+        StatusCode::OK
+    );
+
+    // Setup links are single-use
+    assert_eq!(
+        post_json(
+            &app,
+            "/api/auth/set-password",
+            None,
+            json!({ "token": setup_token, "new_password": "password456" }),
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+
+    // The account now signs in with a password, and a second setup is refused
+    let tokens = login_from_device(&app, email, "Laptop Browser").await;
+    assert_eq!(tokens["user"]["has_password"], true);
+    assert_eq!(tokens["user"]["oauth_provider"], "google");
+    assert_eq!(
+        post_json(
+            &app,
+            "/api/users/me/password/setup",
+            Some(&token),
+            json!({})
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+
+    // With a password to fall back on, Google can be unlinked
+    assert_eq!(
+        unlink_google(&app, &token, "password123").await,
+        StatusCode::OK
+    );
+}
+
+async fn request_email_change(
+    app: &axum::Router,
+    access_token: &str,
+    new_email: &str,
+    password: &str,
+) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/users/me/email-change")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::from(
+                    json!({ "new_email": new_email, "password": password }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_change_email_after_confirmation() {
+    let app = create_test_app().await;
+    let email = "change-email-old@example.com";
+    let new_email = "change-email-new@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+        .bind(email)
+        .execute(&pool)
+        .await
+        .expect("Failed to verify user");
+
+    let tokens = login_from_device(&app, email, "Laptop Browser").await;
+    let access_token = tokens["access_token"].as_str().unwrap();
+
+    assert_eq!(
+        request_email_change(&app, access_token, new_email, "wrong-password").await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        request_email_change(&app, access_token, "not-an-email", "password123").await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        request_email_change(&app, access_token, new_email, "password123").await,
+        StatusCode::OK
+    );
+
+    // Nothing changes until the emailed link is followed
+    let current: String = sqlx::query_scalar(
+        "SELECT email FROM users WHERE id = (SELECT user_id FROM email_change_tokens WHERE new_email = $1)",
+    )
+    .bind(new_email)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(current, email);
+
+    // Stand in for the emailed token, whose plain value is only in the email
+    let token = "emailed-email-change";
+    sqlx::query("UPDATE email_change_tokens SET token_hash = $2 WHERE new_email = $1")
+        .bind(new_email)
+        .bind(back_end::auth::hash_token(token))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/confirm-email-change")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "token": token }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Existing sessions are signed out...
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/refresh")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "refresh_token": tokens["refresh_token"] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // ...and the new address is used to sign in
+    let tokens = login_from_device(&app, new_email, "Laptop Browser").await;
+    assert_eq!(tokens["user"]["email"], new_email);
+}
+
+async fn change_password(
+    app: &axum::Router,
+    access_token: &str,
+    current_password: &str,
+    new_password: &str,
+) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/api/users/me/password")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::from(
+                    json!({
+                        "current_password": current_password,
+                        "new_password": new_password
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_change_password_revokes_sessions() {
+    let app = create_test_app().await;
+    let email = "change-password@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+        .bind(email)
+        .execute(&pool)
+        .await
+        .expect("Failed to verify user");
+
+    let tokens = login_from_device(&app, email, "Laptop Browser").await;
+    let access_token = tokens["access_token"].as_str().unwrap();
+
+    assert_eq!(
+        change_password(&app, access_token, "wrong-password", "newpassword456").await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        change_password(&app, access_token, "password123", "short").await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        change_password(&app, access_token, "password123", "password123").await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        change_password(&app, access_token, "password123", "newpassword456").await,
+        StatusCode::OK
+    );
+
+    // The old refresh token no longer works
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/refresh")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "refresh_token": tokens["refresh_token"] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Only the new password signs in
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "email": email, "password": "password123" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "email": email, "password": "newpassword456" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+async fn login_from(app: &axum::Router, email: &str, user_agent: &str, country: &str) -> Value {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .header("user-agent", user_agent)
+                .header("cf-ipcountry", country)
+                .body(Body::from(
+                    json!({ "email": email, "password": "password123" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_login_history_flags_new_devices_and_countries() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let email = "history@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+        .bind(email)
+        .execute(&pool)
+        .await
+        .expect("Failed to verify user");
+
+    // The first sign-in is never flagged, and neither is a familiar device and country
+    login_from(&app, email, "Laptop Browser", "GB").await;
+    login_from(&app, email, "Laptop Browser", "gb").await;
+    login_from(&app, email, "Phone App", "GB").await;
+    // Unknown-country placeholders are not treated as a country
+    login_from(&app, email, "Phone App", "XX").await;
+    let latest = login_from(&app, email, "Laptop Browser", "FR").await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/users/me/logins?limit=10")
+                .header(
+                    "authorization",
+                    format!("Bearer {}", latest["access_token"].as_str().unwrap()),
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let history: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(history["total"], 5);
+
+    let flags: Vec<(String, Value, bool, bool)> = history["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|login| {
+            (
+                login["user_agent"].as_str().unwrap().to_string(),
+                login["country"].clone(),
+                login["new_device"].as_bool().unwrap(),
+                login["new_country"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        flags,
+        vec![
+            ("Laptop Browser".to_string(), json!("FR"), false, true),
+            ("Phone App".to_string(), Value::Null, false, false),
+            ("Phone App".to_string(), json!("GB"), true, false),
+            ("Laptop Browser".to_string(), json!("GB"), false, false),
+            ("Laptop Browser".to_string(), json!("GB"), false, false),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_public_profile_hides_private_fields() {
+    let app = create_test_app().await;
+    let email = "viewer@example.com";
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+        .bind(email)
+        .execute(&pool)
+        .await
+        .expect("Failed to verify user");
+    let other: uuid::Uuid = sqlx::query_scalar(
+        "INSERT INTO users (email, full_name, city, country, email_verified)
+         VALUES ('other@example.com', 'Other User', 'Leeds', 'UK', true)
+         RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let tokens = login_from_device(&app, email, "Laptop Browser").await;
+    let access_token = tokens["access_token"].as_str().unwrap();
+    let view_profile = |id: uuid::Uuid| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/api/users/{}", id))
+                .header("authorization", format!("Bearer {}", access_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let response = view_profile(other).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let profile: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(profile["full_name"], "Other User");
+    assert_eq!(profile["city"], "Leeds");
+    for field in [
+        "email",
+        "role",
+        "email_verified",
+        "has_password",
+        "oauth_provider",
+    ] {
+        assert!(
+            profile.get(field).is_none(),
+            "public profile leaks {}",
+            field
+        );
+    }
+
+    sqlx::query("UPDATE users SET is_active = false WHERE id = $1")
+        .bind(other)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let response = view_profile(other).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
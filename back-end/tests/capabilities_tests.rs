@@ -0,0 +1,45 @@
+// Tests for the capability map clients use to hide disabled subsystems
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::create_test_app;
+
+#[tokio::test]
+async fn test_capabilities_reflect_config() {
+    let app = create_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/capabilities")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CACHE_CONTROL).unwrap(),
+        "public, max-age=300"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+
+    // .env.test turns on the trending feed and Google sign-in, and leaves CAPTCHA off
+    assert_eq!(body["feed"]["enabled"], true);
+    assert_eq!(body["feed"]["trending"], true);
+    assert_eq!(body["cleanup_events"]["enabled"], true);
+    assert_eq!(body["open_data"]["enabled"], true);
+    assert_eq!(body["google_sign_in"]["enabled"], true);
+    assert_eq!(body["captcha"]["enabled"], false);
+    assert!(body["captcha"]["provider"].is_null());
+}
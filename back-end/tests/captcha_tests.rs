@@ -0,0 +1,81 @@
+// Tests for CAPTCHA verification against a stand-in siteverify endpoint
+
+use axum::{routing::post, Form, Json, Router};
+use back_end::config::{CaptchaConfig, CaptchaProvider};
+use back_end::error::AppError;
+use back_end::services::CaptchaService;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Serve a fake provider that accepts only `valid-token` sent with the right secret
+async fn spawn_provider() -> String {
+    async fn siteverify(Form(form): Form<HashMap<String, String>>) -> Json<Value> {
+        let valid = form.get("secret").map(String::as_str) == Some("test-secret")
+            && form.get("response").map(String::as_str) == Some("valid-token");
+        if valid {
+            Json(json!({ "success": true }))
+        } else {
+            Json(json!({ "success": false, "error-codes": ["invalid-input-response"] }))
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            Router::new().route("/siteverify", post(siteverify)),
+        )
+        .await
+        .unwrap();
+    });
+    format!("http://{addr}/siteverify")
+}
+
+fn captcha_service(verify_url: String) -> CaptchaService {
+    CaptchaService::new(Some(CaptchaConfig {
+        provider: CaptchaProvider::Turnstile,
+        secret_key: "test-secret".to_string(),
+        verify_url,
+    }))
+}
+
+#[tokio::test]
+async fn test_valid_token_is_accepted_and_others_rejected() {
+    let service = captcha_service(spawn_provider().await);
+
+    assert!(service
+        .verify(Some("valid-token"), Some("203.0.113.7"))
+        .await
+        .is_ok());
+    assert!(matches!(
+        service.verify(Some("forged-token"), None).await,
+        Err(AppError::BadRequest(_))
+    ));
+    assert!(matches!(
+        service.verify(None, None).await,
+        Err(AppError::BadRequest(_))
+    ));
+    assert!(matches!(
+        service.verify(Some("  "), None).await,
+        Err(AppError::BadRequest(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_unreachable_provider_fails_closed() {
+    // Nothing listens on the discard port
+    let service = captcha_service("http://127.0.0.1:9/siteverify".to_string());
+
+    assert!(matches!(
+        service.verify(Some("valid-token"), None).await,
+        Err(AppError::Internal(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_disabled_captcha_accepts_every_request() {
+    let service = CaptchaService::new(None);
+
+    assert!(service.verify(None, None).await.is_ok());
+}
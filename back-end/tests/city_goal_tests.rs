@@ -0,0 +1,206 @@
+// Tests for weekly city goals: progress from clears and the celebration when reached
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::error::AppError;
+use back_end::models::city_goal::{CreateCityGoalRequest, UpdateCityGoalRequest};
+use back_end::services::{CityGoalService, NotificationService};
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, setup_test_db};
+
+/// A city name no other test uses
+fn city_name() -> String {
+    format!("Goalton {}", &Uuid::new_v4().to_string()[..8])
+}
+
+fn goal_service(pool: &PgPool) -> CityGoalService {
+    CityGoalService::new(pool.clone(), NotificationService::new(pool.clone()))
+}
+
+async fn create_user(pool: &PgPool, city: &str) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO users (email, password_hash, full_name, city, country, email_verified)
+        VALUES ($1, 'not-a-real-hash', 'Goal User', $2, 'UK', true)
+        RETURNING id
+        "#,
+    )
+    .bind(format!("goal-{}@example.com", Uuid::new_v4()))
+    .bind(city)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// A report cleared by `clearer` now, optionally geocoded to `address_city`
+async fn insert_clear(pool: &PgPool, clearer: Uuid, address_city: Option<&str>) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO litter_reports
+            (reporter_id, location, status, claimed_by, claimed_at, cleared_by, cleared_at,
+             address_city)
+         VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'cleared',
+                 $1, NOW(), $1, NOW(), $2)
+         RETURNING id",
+    )
+    .bind(clearer)
+    .bind(address_city)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn celebrations(pool: &PgPool, user_id: Uuid) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND kind = 'city_goal_reached'",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_goal_is_reached_once_and_celebrated() {
+    let pool = setup_test_db().await;
+    let service = goal_service(&pool);
+    let city = city_name();
+
+    let admin = create_user(&pool, "London").await;
+    let resident = create_user(&pool, &city).await;
+    let visitor = create_user(&pool, "Elsewhere").await;
+    let goal = service
+        .create_goal(
+            admin,
+            CreateCityGoalRequest {
+                city: city.clone(),
+                weekly_target: 2,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(goal.cleared, 0);
+
+    // The resident's clear counts through their own city
+    let first = insert_clear(&pool, resident, None).await;
+    let progress = service.record_clear(first).await.unwrap().unwrap();
+    assert_eq!(progress.cleared, 1);
+    assert!(progress.reached_at.is_none());
+
+    // The visitor's counts through the report's address, whatever the case
+    let second = insert_clear(&pool, visitor, Some(&city.to_uppercase())).await;
+    let progress = service.record_clear(second).await.unwrap().unwrap();
+    assert_eq!(progress.cleared, 2);
+    assert!(progress.reached_at.is_some());
+
+    assert_eq!(celebrations(&pool, resident).await, 1);
+    assert_eq!(celebrations(&pool, visitor).await, 1);
+    assert_eq!(celebrations(&pool, admin).await, 0);
+
+    // Going past the target doesn't celebrate again
+    let third = insert_clear(&pool, resident, None).await;
+    let progress = service.record_clear(third).await.unwrap().unwrap();
+    assert_eq!(progress.cleared, 3);
+    assert_eq!(celebrations(&pool, resident).await, 1);
+}
+
+#[tokio::test]
+async fn test_clears_without_a_goal_are_ignored() {
+    let pool = setup_test_db().await;
+    let clearer = create_user(&pool, &city_name()).await;
+    let report_id = insert_clear(&pool, clearer, None).await;
+
+    let progress = goal_service(&pool).record_clear(report_id).await.unwrap();
+    assert!(progress.is_none());
+}
+
+#[tokio::test]
+async fn test_admin_manages_goals() {
+    let pool = setup_test_db().await;
+    let service = goal_service(&pool);
+    let admin = create_user(&pool, "London").await;
+    let city = city_name();
+
+    let goal = service
+        .create_goal(
+            admin,
+            CreateCityGoalRequest {
+                city: city.clone(),
+                weekly_target: 200,
+            },
+        )
+        .await
+        .unwrap();
+
+    let duplicate = service
+        .create_goal(
+            admin,
+            CreateCityGoalRequest {
+                city: city.to_lowercase(),
+                weekly_target: 50,
+            },
+        )
+        .await;
+    assert!(matches!(duplicate, Err(AppError::Conflict(_))));
+
+    let updated = service
+        .update_goal(goal.id, UpdateCityGoalRequest { weekly_target: 250 })
+        .await
+        .unwrap();
+    assert_eq!(updated.weekly_target, 250);
+
+    service.delete_goal(goal.id).await.unwrap();
+    let result = service.delete_goal(goal.id).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_progress_endpoint_is_public() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin = create_user(&pool, "London").await;
+    let city = city_name();
+    let goal = goal_service(&pool)
+        .create_goal(
+            admin,
+            CreateCityGoalRequest {
+                city: city.clone(),
+                weekly_target: 10,
+            },
+        )
+        .await
+        .unwrap();
+
+    let uri = format!("/api/city-goals?city={}", city.replace(' ', "%20"));
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["id"], goal.id.to_string());
+    assert_eq!(body["items"][0]["weekly_target"], 10);
+    assert_eq!(body["items"][0]["cleared"], 0);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/city-goals/{}", Uuid::new_v4()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,158 @@
+// Tests for claim history: releasing, expiring and the limits on abandoned claims
+
+use back_end::config::Config;
+use back_end::error::AppError;
+use back_end::jobs::ClaimExpiryJob;
+use back_end::models::report::ReportStatus;
+use back_end::models::report_claim::TimelineEventKind;
+use back_end::services::{
+    GeocodingService, ImageService, ImageStorageService, NotificationService, ReportService,
+    S3Service, UploadService,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{insert_user, setup_test_db};
+
+async fn report_service(pool: &PgPool) -> ReportService {
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().expect("Failed to load config");
+    let s3_service = S3Service::new(config.s3.clone())
+        .await
+        .expect("Failed to create S3 service");
+    let image_storage =
+        ImageStorageService::new(pool.clone(), s3_service.clone(), config.s3.spill_max_mb);
+
+    ReportService::new(
+        pool.clone(),
+        ImageService::new(config.image.clone()),
+        image_storage,
+        UploadService::new(pool.clone(), s3_service, config.image.clone()),
+        GeocodingService::new(pool.clone(), config.geocoding.clone()),
+        config.location_check.clone(),
+    )
+}
+
+async fn create_pending_report(pool: &PgPool, reporter_id: Uuid) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO litter_reports (reporter_id, location, photo_before)
+        VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'before.webp')
+        RETURNING id
+        "#,
+    )
+    .bind(reporter_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_unclaim_returns_report_and_shows_in_timeline() {
+    let pool = setup_test_db().await;
+    let service = report_service(&pool).await;
+
+    let reporter = insert_user(&pool, "history-reporter@example.com").await;
+    let picker = insert_user(&pool, "history-picker@example.com").await;
+    let other = insert_user(&pool, "history-other@example.com").await;
+    let report_id = create_pending_report(&pool, reporter).await;
+
+    service.claim_report(report_id, picker).await.unwrap();
+
+    // Only the claimer can give it up
+    let result = service.unclaim_report(report_id, other, None).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+    let report = service
+        .unclaim_report(report_id, picker, Some("  Needs a skip  "))
+        .await
+        .unwrap();
+    assert_eq!(report.status, ReportStatus::Pending);
+    assert_eq!(report.claimed_by, None);
+
+    service.claim_report(report_id, other).await.unwrap();
+
+    let timeline = service.get_timeline(report_id).await.unwrap();
+    let kinds: Vec<_> = timeline.events.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TimelineEventKind::Reported,
+            TimelineEventKind::Claimed,
+            TimelineEventKind::Released,
+            TimelineEventKind::Claimed,
+        ]
+    );
+    assert_eq!(timeline.events[2].user_id, Some(picker));
+    assert_eq!(timeline.events[2].reason.as_deref(), Some("Needs a skip"));
+    assert_eq!(timeline.events[3].user_id, Some(other));
+}
+
+#[tokio::test]
+async fn test_repeatedly_abandoned_report_cannot_be_claimed_again() {
+    let pool = setup_test_db().await;
+    let service = report_service(&pool).await;
+
+    let reporter = insert_user(&pool, "abandon-reporter@example.com").await;
+    let picker = insert_user(&pool, "abandon-picker@example.com").await;
+    let report_id = create_pending_report(&pool, reporter).await;
+
+    for _ in 0..2 {
+        service.claim_report(report_id, picker).await.unwrap();
+        service
+            .unclaim_report(report_id, picker, None)
+            .await
+            .unwrap();
+    }
+
+    let result = service.claim_report(report_id, picker).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+    // Someone else can still take it
+    let other = insert_user(&pool, "abandon-other@example.com").await;
+    service.claim_report(report_id, other).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_claim_expiry_job_releases_stale_claims() {
+    let pool = setup_test_db().await;
+    let service = report_service(&pool).await;
+    let job = ClaimExpiryJob::new(service.clone(), NotificationService::new(pool.clone()), 72);
+
+    let reporter = insert_user(&pool, "expiry-reporter@example.com").await;
+    let picker = insert_user(&pool, "expiry-picker@example.com").await;
+    let stale = create_pending_report(&pool, reporter).await;
+    let fresh = create_pending_report(&pool, reporter).await;
+
+    service.claim_report(stale, picker).await.unwrap();
+    service.claim_report(fresh, picker).await.unwrap();
+    sqlx::query("UPDATE litter_reports SET claimed_at = NOW() - INTERVAL '4 days' WHERE id = $1")
+        .bind(stale)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(job.run().await.unwrap(), 1);
+    assert_eq!(job.run().await.unwrap(), 0);
+
+    let report = service.get_report_by_id(stale).await.unwrap();
+    assert_eq!(report.status, ReportStatus::Pending);
+    let report = service.get_report_by_id(fresh).await.unwrap();
+    assert_eq!(report.status, ReportStatus::Claimed);
+
+    let timeline = service.get_timeline(stale).await.unwrap();
+    assert_eq!(
+        timeline.events.last().map(|e| e.kind),
+        Some(TimelineEventKind::Expired)
+    );
+
+    let notified: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND kind = 'claim_expired'",
+    )
+    .bind(picker)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(notified, 1);
+}
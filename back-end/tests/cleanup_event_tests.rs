@@ -0,0 +1,283 @@
+// Integration tests for cleanup events, RSVPs and the group bonus
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::models::cleanup_event::{CleanupEventStatus, CreateCleanupEventRequest};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+const TEST_PHOTO: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// Helper to create a verified user and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn post_json(app: &axum::Router, uri: &str, token: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn event_bonus_points(email: &str) -> i64 {
+    let pool = get_test_pool().await;
+    sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(e.points), 0)::bigint FROM score_events e
+        JOIN users u ON u.id = e.user_id
+        WHERE u.email = $1 AND e.kind = 'cleanup_event_bonus'
+        "#,
+    )
+    .bind(email)
+    .fetch_one(&pool)
+    .await
+    .unwrap()
+}
+
+fn event_request(starts_in: Duration, length: Duration) -> CreateCleanupEventRequest {
+    let starts_at = Utc::now() + starts_in;
+    serde_json::from_value(json!({
+        "title": "Canal clean",
+        "latitude": 51.5074,
+        "longitude": -0.1278,
+        "starts_at": starts_at,
+        "ends_at": starts_at + length,
+        "report_ids": [Uuid::new_v4()]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_event_window_checks() {
+    let now = Utc::now();
+    assert!(event_request(Duration::hours(1), Duration::hours(3))
+        .check_window(now)
+        .is_ok());
+
+    for request in [
+        event_request(Duration::hours(1), Duration::zero()),
+        event_request(Duration::hours(1), Duration::hours(13)),
+        event_request(-Duration::hours(1), Duration::hours(3)),
+        event_request(Duration::days(91), Duration::hours(3)),
+    ] {
+        assert!(request.check_window(now).is_err());
+    }
+
+    let start = now + Duration::hours(1);
+    let end = start + Duration::hours(2);
+    assert_eq!(
+        CleanupEventStatus::at(start, end, now),
+        CleanupEventStatus::Upcoming
+    );
+    assert_eq!(
+        CleanupEventStatus::at(start, end, start),
+        CleanupEventStatus::Ongoing
+    );
+    assert_eq!(
+        CleanupEventStatus::at(start, end, end),
+        CleanupEventStatus::Finished
+    );
+}
+
+#[tokio::test]
+async fn test_clearing_a_linked_report_during_the_event_pays_every_attendee() {
+    let app = create_test_app().await;
+    let reporter = create_verified_user_and_login(&app, "event-reporter@example.com").await;
+    let organizer = create_verified_user_and_login(&app, "event-organizer@example.com").await;
+    let attendee = create_verified_user_and_login(&app, "event-attendee@example.com").await;
+    let bystander = create_verified_user_and_login(&app, "event-bystander@example.com").await;
+
+    let mut report_ids = Vec::new();
+    for _ in 0..2 {
+        let (status, report) = post_json(
+            &app,
+            "/api/reports",
+            &reporter,
+            json!({ "latitude": 51.5080, "longitude": -0.1281, "photo_base64": TEST_PHOTO }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        report_ids.push(report["id"].as_str().unwrap().to_string());
+    }
+
+    let starts_at = Utc::now() + Duration::hours(1);
+    let event_body = |report_ids: Value| {
+        json!({
+            "title": "Canal clean",
+            "latitude": 51.5074,
+            "longitude": -0.1278,
+            "starts_at": starts_at,
+            "ends_at": starts_at + Duration::hours(3),
+            "report_ids": report_ids
+        })
+    };
+
+    // A report far from the meeting point can't be taken on
+    let (status, _) = post_json(
+        &app,
+        "/api/cleanup-events",
+        &organizer,
+        event_body(json!([report_ids[0], Uuid::new_v4()])),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, event) = post_json(
+        &app,
+        "/api/cleanup-events",
+        &organizer,
+        event_body(json!(report_ids)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(event["status"], "upcoming");
+    assert_eq!(event["attendee_count"], 1);
+    assert_eq!(event["attending"], true);
+    let event_id = event["id"].as_str().unwrap().to_string();
+
+    let (status, event) = post_json(
+        &app,
+        &format!("/api/cleanup-events/{event_id}/rsvp"),
+        &attendee,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event["attendee_count"], 2);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/cleanup-events?latitude=51.5074&longitude=-0.1278")
+                .header("authorization", format!("Bearer {}", bystander))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let events: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(events["items"][0]["id"], event_id.as_str());
+    assert_eq!(events["items"][0]["attending"], false);
+
+    let clear = |token: String, report_id: String| {
+        let app = app.clone();
+        async move {
+            let (status, _) = post_json(
+                &app,
+                &format!("/api/reports/{report_id}/claim"),
+                &token,
+                json!({}),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            let (status, _) = post_json(
+                &app,
+                &format!("/api/reports/{report_id}/clear"),
+                &token,
+                json!({ "photo_base64": TEST_PHOTO }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+    };
+    // A clear before the event starts earns no bonus
+    clear(attendee.clone(), report_ids[0].clone()).await;
+    assert_eq!(event_bonus_points("event-attendee@example.com").await, 0);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE cleanup_events SET starts_at = NOW() - INTERVAL '1 hour' WHERE id = $1::uuid",
+    )
+    .bind(&event_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    clear(attendee.clone(), report_ids[1].clone()).await;
+    assert_eq!(event_bonus_points("event-attendee@example.com").await, 5);
+    assert_eq!(event_bonus_points("event-organizer@example.com").await, 5);
+    assert_eq!(event_bonus_points("event-bystander@example.com").await, 0);
+}
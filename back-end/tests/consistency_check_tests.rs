@@ -0,0 +1,110 @@
+// Integration tests for foreign key clean-up and the data consistency check job
+
+use back_end::jobs::ConsistencyCheckJob;
+use back_end::models::consistency_check::ConsistencyCheckRun;
+use back_end::models::pagination::PageParams;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{insert_user, setup_test_db};
+
+async fn create_user(pool: &PgPool, email: &str, with_score: bool) -> Uuid {
+    let user_id = insert_user(pool, email).await;
+
+    if with_score {
+        sqlx::query("INSERT INTO user_scores (user_id) VALUES ($1)")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    user_id
+}
+
+fn found_by(run: &ConsistencyCheckRun, check: &str) -> (i64, i64) {
+    let anomaly = run
+        .anomalies
+        .iter()
+        .find(|a| a.check == check)
+        .unwrap_or_else(|| panic!("check {check} did not run"));
+    (anomaly.found, anomaly.fixed)
+}
+
+async fn score_rows(pool: &PgPool, user_id: Uuid) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM user_scores WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_deleting_a_claimer_releases_their_claims() {
+    let pool = setup_test_db().await;
+    let reporter = create_user(&pool, "fk-reporter@example.com", true).await;
+    let claimer = create_user(&pool, "fk-claimer@example.com", true).await;
+
+    let report_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO litter_reports (reporter_id, location, status, claimed_by, claimed_at)
+         VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'claimed', $2, NOW())
+         RETURNING id",
+    )
+    .bind(reporter)
+    .bind(claimer)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(claimer)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, claimed_by): (String, Option<Uuid>) =
+        sqlx::query_as("SELECT status::text, claimed_by FROM litter_reports WHERE id = $1")
+            .bind(report_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "pending");
+    assert_eq!(claimed_by, None);
+    assert_eq!(score_rows(&pool, claimer).await, 0);
+
+    // Nothing is left for the check to repair
+    let run = ConsistencyCheckJob::new(pool.clone())
+        .run(None, false)
+        .await
+        .unwrap();
+    assert_eq!(run.total_found, 0);
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_anomalies_without_fixing_them() {
+    let pool = setup_test_db().await;
+    let admin = create_user(&pool, "fk-admin@example.com", true).await;
+    let user = create_user(&pool, "fk-scoreless@example.com", false).await;
+    let job = ConsistencyCheckJob::new(pool.clone());
+
+    let dry = job.run(Some(admin), true).await.unwrap();
+    assert!(dry.dry_run);
+    assert_eq!(found_by(&dry, "users_without_scores"), (1, 0));
+    assert_eq!(dry.total_fixed, 0);
+    assert_eq!(score_rows(&pool, user).await, 0);
+
+    let fixed = job.run(None, false).await.unwrap();
+    assert_eq!(found_by(&fixed, "users_without_scores"), (1, 1));
+    assert_eq!(score_rows(&pool, user).await, 1);
+
+    let clean = job.run(None, false).await.unwrap();
+    assert_eq!(clean.total_found, 0);
+
+    // Every run is recorded, newest first
+    let runs = job.list_runs(&PageParams::default()).await.unwrap();
+    assert_eq!(runs.total, 3);
+    assert_eq!(runs.items[0].id, clean.id);
+    assert_eq!(runs.items[2].triggered_by, Some(admin));
+    assert_eq!(runs.items[2].anomalies.len(), dry.anomalies.len());
+}
@@ -0,0 +1,321 @@
+// Contract tests: the request/response examples documented in the OpenAPI spec are
+// checked against their schemas and replayed against the test app, so the docs
+// cannot drift from what the API actually accepts and returns
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use back_end::{openapi, ApiDoc};
+use serde_json::{json, Map, Value};
+use tower::ServiceExt;
+use utoipa::OpenApi;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+/// The account the documented examples sign in as (see `LoginRequest`)
+const EXAMPLE_EMAIL: &str = "user@example.com";
+const EXAMPLE_PASSWORD: &str = "SecurePassword123";
+
+const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+/// One documented operation
+struct Operation<'a> {
+    method: &'a str,
+    path: &'a str,
+    spec: &'a Value,
+}
+
+impl Operation<'_> {
+    fn name(&self) -> String {
+        format!("{} {}", self.method.to_uppercase(), self.path)
+    }
+
+    fn request_example(&self) -> Option<&Value> {
+        self.spec
+            .pointer("/requestBody/content/application~1json/example")
+    }
+
+    /// Documented responses that carry an example, by status
+    fn response_examples(&self) -> Vec<(&str, &Value)> {
+        self.responses()
+            .filter_map(|(status, response)| {
+                response
+                    .pointer("/content/application~1json/example")
+                    .map(|example| (status, example))
+            })
+            .collect()
+    }
+
+    fn responses(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.spec["responses"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(status, response)| (status.as_str(), response))
+    }
+
+    fn parameters(&self) -> impl Iterator<Item = &Value> {
+        self.spec["parameters"].as_array().into_iter().flatten()
+    }
+
+    fn requires_bearer_token(&self) -> bool {
+        self.spec["security"]
+            .as_array()
+            .is_some_and(|options| options.iter().all(|o| o.get("bearer_auth").is_some()))
+    }
+
+    fn has_examples(&self) -> bool {
+        self.request_example().is_some() || !self.response_examples().is_empty()
+    }
+}
+
+fn operations(spec: &Value) -> Vec<Operation<'_>> {
+    spec["paths"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .flat_map(|(path, item)| {
+            METHODS.iter().filter_map(move |method| {
+                item.get(*method)
+                    .map(|spec| Operation { method, path, spec })
+            })
+        })
+        .collect()
+}
+
+/// OpenAPI 3.0 schemas mark optional values with `nullable`; JSON Schema spells that
+/// as a `null` type
+fn to_json_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(fields) => {
+            let mut converted: Map<String, Value> = fields
+                .iter()
+                .filter(|(key, _)| *key != "nullable")
+                .map(|(key, value)| (key.clone(), to_json_schema(value)))
+                .collect();
+            if fields.get("nullable") != Some(&Value::Bool(true)) {
+                return Value::Object(converted);
+            }
+            if let Some(Value::Array(values)) = converted.get_mut("enum") {
+                values.push(Value::Null);
+            }
+            match converted.remove("type") {
+                Some(Value::String(ty)) => {
+                    converted.insert("type".to_string(), json!([ty, "null"]));
+                    Value::Object(converted)
+                }
+                _ => json!({ "anyOf": [{ "type": "null" }, converted] }),
+            }
+        }
+        Value::Array(items) => Value::Array(items.iter().map(to_json_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Check `instance` against a schema taken from the spec; `$ref`s resolve against the
+/// spec's components
+fn assert_conforms(spec: &Value, schema: &Value, instance: &Value, context: &str) {
+    let mut root = to_json_schema(schema);
+    if let Value::Object(fields) = &mut root {
+        fields.insert(
+            "components".to_string(),
+            to_json_schema(&spec["components"]),
+        );
+    } else {
+        panic!("{context}: schema is not an object");
+    }
+
+    let validator = jsonschema::validator_for(&root)
+        .unwrap_or_else(|e| panic!("{context}: invalid schema: {e}"));
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|e| format!("{} at {}", e, e.instance_path))
+        .collect();
+    assert!(
+        errors.is_empty(),
+        "{context} does not match its schema:\n  {}\n{instance:#}",
+        errors.join("\n  ")
+    );
+}
+
+/// Register, verify and sign in the account the examples refer to
+async fn sign_in_example_user(app: &Router) -> String {
+    let response = send(
+        app,
+        Request::builder()
+            .method("POST")
+            .uri("/api/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "email": EXAMPLE_EMAIL,
+                    "password": EXAMPLE_PASSWORD,
+                    "full_name": "John Doe",
+                    "city": "London",
+                    "country": "UK"
+                })
+                .to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.0, StatusCode::CREATED);
+
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = $1")
+        .bind(EXAMPLE_EMAIL)
+        .execute(&get_test_pool().await)
+        .await
+        .unwrap();
+
+    let (status, body) = send(
+        app,
+        Request::builder()
+            .method("POST")
+            .uri("/api/auth/login")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "email": EXAMPLE_EMAIL, "password": EXAMPLE_PASSWORD }).to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Build the request an operation's examples describe
+fn example_request(operation: &Operation, token: &str) -> Request<Body> {
+    let as_text = |value: &Value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    let mut uri = operation.path.to_string();
+    let mut query = Vec::new();
+    for param in operation.parameters() {
+        let name = param["name"].as_str().unwrap();
+        let example = param.get("example");
+        match param["in"].as_str() {
+            Some("path") => {
+                let example = example.unwrap_or_else(|| {
+                    panic!(
+                        "{}: path parameter {name} needs an example",
+                        operation.name()
+                    )
+                });
+                uri = uri.replace(&format!("{{{name}}}"), &as_text(example));
+            }
+            Some("query") => {
+                if let Some(example) = example {
+                    query.push(format!("{name}={}", as_text(example)));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !query.is_empty() {
+        uri = format!("{uri}?{}", query.join("&"));
+    }
+
+    let mut request = Request::builder()
+        .method(operation.method.to_uppercase().as_str())
+        .uri(uri);
+    if operation.requires_bearer_token() {
+        request = request.header("authorization", format!("Bearer {token}"));
+    }
+    match operation.request_example() {
+        Some(example) => request
+            .header("content-type", "application/json")
+            .body(Body::from(example.to_string()))
+            .unwrap(),
+        None => request.body(Body::empty()).unwrap(),
+    }
+}
+
+#[test]
+fn test_documented_examples_match_their_schemas() {
+    let spec = openapi::to_json(&ApiDoc::openapi());
+    let operations = operations(&spec);
+    assert!(
+        operations.iter().any(Operation::has_examples),
+        "no operation documents an example"
+    );
+
+    for operation in &operations {
+        if let Some(example) = operation.request_example() {
+            let schema = operation
+                .spec
+                .pointer("/requestBody/content/application~1json/schema")
+                .unwrap();
+            let context = format!("{} request example", operation.name());
+            assert_conforms(&spec, schema, example, &context);
+        }
+
+        for (status, example) in operation.response_examples() {
+            let schema = operation.spec["responses"][status]
+                .pointer("/content/application~1json/schema")
+                .unwrap_or_else(|| panic!("{} {status} example has no schema", operation.name()));
+            let context = format!("{} {status} response example", operation.name());
+            assert_conforms(&spec, schema, example, &context);
+        }
+
+        for param in operation.parameters() {
+            if let Some(example) = param.get("example") {
+                let context = format!("{} parameter {} example", operation.name(), param["name"]);
+                assert_conforms(&spec, &param["schema"], example, &context);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_every_schema_reference_resolves() {
+    let spec = openapi::to_json(&ApiDoc::openapi());
+    let text = spec.to_string();
+
+    for reference in text.split("\"#/components/schemas/").skip(1) {
+        let name = &reference[..reference.find('"').unwrap()];
+        assert!(
+            spec["components"]["schemas"].get(name).is_some(),
+            "dangling schema reference {name}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_documented_examples_replay_against_the_app() {
+    let app = create_test_app().await;
+    let token = sign_in_example_user(&app).await;
+    let spec = openapi::to_json(&ApiDoc::openapi());
+
+    for operation in operations(&spec).iter().filter(|o| o.has_examples()) {
+        let (status, body) = send(&app, example_request(operation, &token)).await;
+        let name = operation.name();
+
+        // An example documents the happy path, so its status is what replay must return
+        let documented = operation.response_examples();
+        if let [(expected, _)] = documented.as_slice() {
+            assert_eq!(status.as_str(), *expected, "{name} returned {body}");
+        }
+
+        let response = operation
+            .spec
+            .pointer(&format!("/responses/{}", status.as_str()))
+            .unwrap_or_else(|| panic!("{name} returned undocumented status {status}: {body}"));
+        if let Some(schema) = response.pointer("/content/application~1json/schema") {
+            assert_conforms(&spec, schema, &body, &format!("{name} {status} response"));
+        }
+    }
+}
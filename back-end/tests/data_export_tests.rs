@@ -0,0 +1,151 @@
+// Integration tests for personal data exports
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+/// Helper to create a verified user in an existing app and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn send(app: &axum::Router, method: &str, uri: &str, token: &str) -> (StatusCode, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, body.to_vec())
+}
+
+/// Request an export and wait for it to be assembled
+async fn wait_for_export(app: &axum::Router, token: &str) -> Value {
+    let (status, _) = send(app, "POST", "/api/users/me/export", token).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    for _ in 0..50 {
+        let (status, body) = send(app, "GET", "/api/users/me/export", token).await;
+        assert_eq!(status, StatusCode::OK);
+        let export: Value = serde_json::from_slice(&body).unwrap();
+        if export["status"] != "pending" {
+            return export;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("data export was not assembled in time");
+}
+
+#[tokio::test]
+async fn test_export_contains_profile_and_reports() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "exporter@example.com").await;
+
+    let export = wait_for_export(&app, &token).await;
+    assert_eq!(export["status"], "ready");
+    let download_url = export["download_url"].as_str().unwrap();
+
+    let (status, body) = send(&app, "GET", download_url, &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let archive: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(archive["profile"]["email"], "exporter@example.com");
+    assert!(archive["profile"].get("password_hash").is_none());
+    assert_eq!(archive["reports"], json!([]));
+    assert_eq!(archive["feed_posts"], json!([]));
+}
+
+#[tokio::test]
+async fn test_export_cannot_be_downloaded_by_another_user() {
+    let app = create_test_app().await;
+    let owner_token = create_verified_user_and_login(&app, "export-owner@example.com").await;
+    let other_token = create_verified_user_and_login(&app, "export-snoop@example.com").await;
+
+    let export = wait_for_export(&app, &owner_token).await;
+    let download_url = export["download_url"].as_str().unwrap();
+
+    let (status, _) = send(&app, "GET", download_url, &other_token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_no_export_requested() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "no-export@example.com").await;
+
+    let (status, _) = send(&app, "GET", "/api/users/me/export", &token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
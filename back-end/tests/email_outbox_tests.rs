@@ -0,0 +1,114 @@
+// Tests for the email outbox: registration queues its email in the same transaction,
+// and failed sends are kept for a later retry
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::config::Config;
+use back_end::services::{EmailOutboxService, EmailService};
+use serde_json::json;
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, setup_test_db};
+
+async fn register(app: &axum::Router, email: &str) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Outbox User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_registration_sends_verification_email_through_outbox() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    assert_eq!(
+        register(&app, "outbox-new@example.com").await,
+        StatusCode::CREATED
+    );
+
+    let (subject, text_body, sent): (String, String, bool) = sqlx::query_as(
+        "SELECT subject, text_body, sent_at IS NOT NULL FROM email_outbox WHERE recipient = $1",
+    )
+    .bind("outbox-new@example.com")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(subject, "Verify your LittyPicky account");
+    assert!(sent);
+    // The body held a single-use link, so it is dropped once sent
+    assert!(text_body.is_empty());
+
+    // A rejected registration queues nothing
+    assert_eq!(
+        register(&app, "outbox-new@example.com").await,
+        StatusCode::CONFLICT
+    );
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(queued, 1);
+}
+
+#[tokio::test]
+async fn test_failed_outbox_email_is_kept_for_retry() {
+    let pool = setup_test_db().await;
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().expect("Failed to load config");
+    let email_service = EmailService::new(pool.clone(), config.email).unwrap();
+    let outbox = EmailOutboxService::new(pool.clone(), email_service.clone());
+
+    let mut tx = pool.begin().await.unwrap();
+    let id = EmailOutboxService::enqueue(
+        &mut tx,
+        "not an address",
+        &email_service.render_verification_email("Nobody", "some-token"),
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    assert!(!outbox.send(id).await.unwrap());
+
+    let (attempts, last_error, text_body): (i32, Option<String>, String) =
+        sqlx::query_as("SELECT attempts, last_error, text_body FROM email_outbox WHERE id = $1")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(attempts, 1);
+    assert!(last_error.is_some());
+    assert!(!text_body.is_empty());
+
+    // Not due again until the backoff passes
+    assert_eq!(outbox.send_due().await.unwrap(), 0);
+
+    sqlx::query("UPDATE email_outbox SET next_attempt_at = NOW(), recipient = $2 WHERE id = $1")
+        .bind(id)
+        .bind("retry@example.com")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(outbox.send_due().await.unwrap(), 1);
+}
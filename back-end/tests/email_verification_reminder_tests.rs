@@ -0,0 +1,170 @@
+// Integration tests for the reminder sent to users who never verified their email
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::auth::{hash_token, JwtService};
+use back_end::config::Config;
+use back_end::jobs::EmailVerificationReminderJob;
+use back_end::services::{AuthService, EmailService, TwoFactorService};
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+async fn register(app: &axum::Router, email: &str) -> Uuid {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+}
+
+async fn registered_hours_ago(pool: &PgPool, user_id: Uuid, hours: i32) {
+    sqlx::query("UPDATE users SET created_at = NOW() - make_interval(hours => $2) WHERE id = $1")
+        .bind(user_id)
+        .bind(hours)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn reminded(pool: &PgPool, user_id: Uuid) -> bool {
+    sqlx::query_scalar("SELECT verification_reminder_sent_at IS NOT NULL FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+fn reminder_job(pool: &PgPool) -> EmailVerificationReminderJob {
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().expect("Failed to load config");
+    let auth_service = AuthService::new(
+        pool.clone(),
+        JwtService::new(config.jwt.clone()).unwrap(),
+        EmailService::new(pool.clone(), config.email.clone()).unwrap(),
+        TwoFactorService::new(pool.clone()),
+        config,
+    );
+    EmailVerificationReminderJob::new(pool.clone(), Arc::new(auth_service), 48)
+}
+
+#[tokio::test]
+async fn test_unverified_users_are_reminded_once() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    let stale = register(&app, "stale-signup@example.com").await;
+    let recent = register(&app, "recent-signup@example.com").await;
+    let verified = register(&app, "verified-signup@example.com").await;
+    registered_hours_ago(&pool, stale, 50).await;
+    registered_hours_ago(&pool, recent, 10).await;
+    registered_hours_ago(&pool, verified, 50).await;
+    sqlx::query("UPDATE users SET email_verified = TRUE WHERE id = $1")
+        .bind(verified)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let old_token: String =
+        sqlx::query_scalar("SELECT token FROM email_verification_tokens WHERE user_id = $1")
+            .bind(stale)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    let job = reminder_job(&pool);
+    job.run().await.unwrap();
+
+    assert!(reminded(&pool, stale).await);
+    assert!(!reminded(&pool, recent).await);
+    assert!(!reminded(&pool, verified).await);
+
+    // The reminder carries a fresh link, since the original has likely expired
+    let new_token: String =
+        sqlx::query_scalar("SELECT token FROM email_verification_tokens WHERE user_id = $1")
+            .bind(stale)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_ne!(old_token, new_token);
+
+    // A later run leaves them alone
+    job.run().await.unwrap();
+    let tokens: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM email_verification_tokens WHERE user_id = $1 AND token = $2",
+    )
+    .bind(stale)
+    .bind(&new_token)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(tokens, 1);
+}
+
+#[tokio::test]
+async fn test_unsubscribe_link_stops_reminders() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    let user_id = register(&app, "no-reminders@example.com").await;
+    registered_hours_ago(&pool, user_id, 72).await;
+    // Stand-in for the token a reminder would have carried
+    sqlx::query("UPDATE users SET reminder_unsubscribe_token = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(hash_token("unsubscribe-me"))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let unsubscribe = |token: &'static str| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/verification-reminders/unsubscribe")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "token": token }).to_string()))
+                .unwrap(),
+        )
+    };
+    assert_eq!(
+        unsubscribe("not-a-real-token").await.unwrap().status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        unsubscribe("unsubscribe-me").await.unwrap().status(),
+        StatusCode::OK
+    );
+
+    reminder_job(&pool).run().await.unwrap();
+    assert!(!reminded(&pool, user_id).await);
+}
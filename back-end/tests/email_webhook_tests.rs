@@ -0,0 +1,156 @@
+// Integration tests for email bounce/complaint webhooks
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+const WEBHOOK_TOKEN: &str = "test-email-webhook-secret";
+
+async fn post_webhook(app: &axum::Router, uri: &str, body: String) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                // SNS delivers notifications as text/plain
+                .header("content-type", "text/plain; charset=UTF-8")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, value)
+}
+
+async fn suppression_reason(email: &str) -> Option<String> {
+    let pool = get_test_pool().await;
+    sqlx::query_scalar::<_, String>("SELECT reason FROM email_suppressions WHERE email = $1")
+        .bind(email)
+        .fetch_optional(&pool)
+        .await
+        .expect("Failed to query email_suppressions")
+}
+
+#[tokio::test]
+async fn test_sendgrid_hard_bounce_suppresses_address() {
+    let app = create_test_app().await;
+
+    let events = json!([
+        { "email": "Bounced@Example.com", "event": "bounce", "type": "bounce", "reason": "550 5.1.1 User unknown" },
+        { "email": "blocked@example.com", "event": "bounce", "type": "blocked", "reason": "421 try again later" },
+        { "email": "ok@example.com", "event": "delivered" }
+    ]);
+
+    let (status, body) = post_webhook(
+        &app,
+        &format!("/api/webhooks/email/sendgrid?token={WEBHOOK_TOKEN}"),
+        events.to_string(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["suppressed"], 1);
+    assert_eq!(
+        suppression_reason("bounced@example.com").await.as_deref(),
+        Some("bounce")
+    );
+    assert_eq!(suppression_reason("blocked@example.com").await, None);
+}
+
+#[tokio::test]
+async fn test_ses_complaint_is_not_downgraded_by_later_bounce() {
+    let app = create_test_app().await;
+    let uri = format!("/api/webhooks/email/ses?token={WEBHOOK_TOKEN}");
+
+    let complaint = json!({
+        "notificationType": "Complaint",
+        "complaint": {
+            "complainedRecipients": [{ "emailAddress": "angry@example.com" }],
+            "complaintFeedbackType": "abuse"
+        }
+    });
+    let envelope = json!({ "Type": "Notification", "Message": complaint.to_string() });
+    let (status, body) = post_webhook(&app, &uri, envelope.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["suppressed"], 1);
+
+    let bounce = json!({
+        "notificationType": "Bounce",
+        "bounce": {
+            "bounceType": "Permanent",
+            "bouncedRecipients": [{ "emailAddress": "angry@example.com" }]
+        }
+    });
+    let envelope = json!({ "Type": "Notification", "Message": bounce.to_string() });
+    let (status, _) = post_webhook(&app, &uri, envelope.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(
+        suppression_reason("angry@example.com").await.as_deref(),
+        Some("complaint")
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_rejects_invalid_token() {
+    let app = create_test_app().await;
+
+    let events = json!([{ "email": "victim@example.com", "event": "spamreport" }]);
+    let (status, _) = post_webhook(
+        &app,
+        "/api/webhooks/email/sendgrid?token=wrong",
+        events.to_string(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(suppression_reason("victim@example.com").await, None);
+}
+
+#[tokio::test]
+async fn test_deliveries_are_logged_with_status() {
+    let app = create_test_app().await;
+    let uri = format!("/api/webhooks/email/sendgrid?token={WEBHOOK_TOKEN}");
+
+    let (status, _) = post_webhook(&app, &uri, "not json".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let events = json!([{ "email": "logged@example.com", "event": "spamreport" }]);
+    let (status, _) = post_webhook(&app, &uri, events.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let pool = get_test_pool().await;
+    let deliveries = sqlx::query_as::<_, (String, String, i32)>(
+        "SELECT payload, delivery_status, attempts FROM event_log WHERE event_type = 'webhook.email.sendgrid' ORDER BY created_at",
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to query event_log");
+
+    assert_eq!(deliveries.len(), 2);
+    assert_eq!(deliveries[0].0, "not json");
+    assert_eq!(deliveries[0].1, "failed");
+    assert_eq!(deliveries[0].2, 1);
+    assert_eq!(deliveries[1].1, "succeeded");
+
+    let suppressed_events: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM event_log WHERE event_type = 'email.suppressed' AND entity_id = 'logged@example.com'",
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to query event_log");
+    assert_eq!(suppressed_events, 1);
+}
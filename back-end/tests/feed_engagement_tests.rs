@@ -0,0 +1,133 @@
+// Tests for feed engagement tracking and the trending feed
+
+use axum::{
+    async_trait,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::error::AppError;
+use back_end::models::EngagementKind;
+use back_end::services::feed_engagement_service::EngagementEvent;
+use back_end::services::{
+    EngagementRecorder, EngagementSink, FeedEngagementService, PgEngagementSink,
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, insert_user};
+
+/// Keeps every batch it is handed
+#[derive(Default)]
+struct MemorySink {
+    batches: Mutex<Vec<Vec<EngagementEvent>>>,
+}
+
+#[async_trait]
+impl EngagementSink for MemorySink {
+    async fn write(&self, events: Vec<EngagementEvent>) -> Result<(), AppError> {
+        self.batches.lock().unwrap().push(events);
+        Ok(())
+    }
+}
+
+async fn create_post(pool: &PgPool, email: &str, content: &str) -> Uuid {
+    let user_id = insert_user(pool, email).await;
+
+    sqlx::query_scalar("INSERT INTO feed_posts (user_id, content) VALUES ($1, $2) RETURNING id")
+        .bind(user_id)
+        .bind(content)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn feed_order(app: &axum::Router, uri: &str) -> Vec<String> {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let feed: Value = serde_json::from_slice(&body).unwrap();
+    feed["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|post| post["content"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_trending_feed_ranks_by_engagement() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    let popular = create_post(&pool, "trending-popular@example.com", "Popular").await;
+    let quiet = create_post(&pool, "trending-quiet@example.com", "Quiet").await;
+    create_post(&pool, "trending-newest@example.com", "Newest").await;
+
+    let recorder = EngagementRecorder::new(Arc::new(PgEngagementSink::new(pool.clone())));
+    recorder.record(popular, None, EngagementKind::Like);
+    recorder.record(popular, None, EngagementKind::Comment);
+    recorder.record_dwell(popular, None, 30_000);
+    recorder.record(quiet, None, EngagementKind::View);
+    recorder.flush().await.unwrap();
+
+    // Engagement that has decayed away no longer counts
+    sqlx::query(
+        "INSERT INTO feed_engagement_events (post_id, kind, occurred_at)
+         VALUES ($1, 'like', NOW() - INTERVAL '30 days')",
+    )
+    .bind(quiet)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let scored = FeedEngagementService::new(pool.clone())
+        .recompute_scores(24.0)
+        .await
+        .unwrap();
+    assert_eq!(scored, 2);
+
+    let stale: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM feed_engagement_events WHERE occurred_at < NOW() - INTERVAL '1 day'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(stale, 0);
+
+    assert_eq!(
+        feed_order(&app, "/api/feed?sort=trending").await,
+        vec!["Popular", "Quiet", "Newest"]
+    );
+    assert_eq!(
+        feed_order(&app, "/api/feed").await,
+        vec!["Newest", "Quiet", "Popular"]
+    );
+}
+
+#[tokio::test]
+async fn test_recorder_writes_in_batches() {
+    let sink = Arc::new(MemorySink::default());
+    let recorder = EngagementRecorder::new(sink.clone());
+    let post_id = Uuid::new_v4();
+
+    recorder.record(post_id, None, EngagementKind::View);
+    recorder.record_dwell(post_id, Some(Uuid::new_v4()), 1200);
+    assert!(sink.batches.lock().unwrap().is_empty());
+
+    recorder.flush().await.unwrap();
+    let batches = sink.batches.lock().unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].len(), 2);
+    assert_eq!(batches[0][1].kind, EngagementKind::Dwell);
+    assert_eq!(batches[0][1].dwell_ms, Some(1200));
+}
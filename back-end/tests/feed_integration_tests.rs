@@ -1,4 +1,5 @@
 // Integration tests for feed feature
+#![allow(clippy::needless_borrows_for_generic_args, clippy::same_item_push)]
 
 use axum::{
     body::Body,
@@ -250,8 +251,10 @@ async fn test_get_feed_pagination() {
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let posts: Vec<Value> = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
-    assert_eq!(posts.len(), 5);
+    let page: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    assert_eq!(page["items"].as_array().unwrap().len(), 5);
+    assert_eq!(page["total"], 5);
+    assert!(page["next_cursor"].is_null());
 
     // Test with limit=2
     let response = app
@@ -270,8 +273,9 @@ async fn test_get_feed_pagination() {
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let posts: Vec<Value> = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
-    assert_eq!(posts.len(), 2);
+    let page: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    assert_eq!(page["next_cursor"], "2");
 
     // Test with offset=2, limit=2
     let response = app
@@ -290,8 +294,29 @@ async fn test_get_feed_pagination() {
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let posts: Vec<Value> = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
-    assert_eq!(posts.len(), 2);
+    let page: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+
+    // The cursor from the last full page returns the remainder
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/feed?cursor=4&limit=2")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let page: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    assert!(page["next_cursor"].is_null());
 }
 
 #[tokio::test]
@@ -564,6 +589,115 @@ async fn test_unlike_post() {
     assert_eq!(json["like_count"].as_i64().unwrap(), 0);
 }
 
+async fn send_like(app: &axum::Router, method: &str, post_id: &str, token: &str) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(format!("/api/feed/{}/like", post_id))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+async fn get_like_count(app: &axum::Router, post_id: &str) -> i64 {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/api/feed/{}", post_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    json["like_count"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn test_concurrent_likes_and_unlikes_keep_count_consistent() {
+    let mut app = create_test_app().await;
+    let (_, author_token) = create_user_and_get_token(&mut app, "like-race-author@test.com").await;
+
+    let mut tokens = Vec::new();
+    for i in 0..5 {
+        let (_, token) =
+            create_user_and_get_token(&mut app, &format!("like-race-{}@test.com", i)).await;
+        tokens.push(token);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/feed")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", author_token))
+                .body(Body::from(
+                    json!({
+                        "content": "Test post for concurrent likes",
+                        "images": []
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    let post_id = json["id"].as_str().unwrap().to_string();
+
+    // Every user likes the post several times at once; duplicates must not error
+    let mut handles = Vec::new();
+    for token in &tokens {
+        for _ in 0..4 {
+            let (app, post_id, token) = (app.clone(), post_id.clone(), token.clone());
+            handles.push(tokio::spawn(async move {
+                send_like(&app, "POST", &post_id, &token).await
+            }));
+        }
+    }
+    for handle in handles {
+        assert_eq!(handle.await.unwrap(), StatusCode::CREATED);
+    }
+
+    assert_eq!(get_like_count(&app, &post_id).await, tokens.len() as i64);
+
+    // Concurrent unlikes by the same users only decrement once each
+    let mut handles = Vec::new();
+    for token in tokens.iter().take(3) {
+        for _ in 0..4 {
+            let (app, post_id, token) = (app.clone(), post_id.clone(), token.clone());
+            handles.push(tokio::spawn(async move {
+                send_like(&app, "DELETE", &post_id, &token).await
+            }));
+        }
+    }
+    for handle in handles {
+        assert_eq!(handle.await.unwrap(), StatusCode::NO_CONTENT);
+    }
+
+    assert_eq!(
+        get_like_count(&app, &post_id).await,
+        tokens.len() as i64 - 3
+    );
+}
+
 // ============================================================================
 // COMMENT TESTS
 // ============================================================================
@@ -743,12 +877,100 @@ async fn test_delete_comment_soft_delete() {
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let comments: Vec<Value> = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    let page: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    let comments = page["items"].as_array().unwrap();
 
     assert_eq!(comments.len(), 1);
     assert!(comments[0]["is_deleted"].as_bool().unwrap());
 }
 
+#[tokio::test]
+async fn test_comment_count_survives_repeated_delete() {
+    let mut app = create_test_app().await;
+    let (_, token) = create_user_and_get_token(&mut app, "comment-count@test.com").await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/feed")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({
+                        "content": "Test post for comment counts",
+                        "images": []
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    let post_id = json["id"].as_str().unwrap().to_string();
+
+    let mut comment_ids = Vec::new();
+    for content in ["First", "Second"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/feed/{}/comments", post_id))
+                    .header("content-type", "application/json")
+                    .header("authorization", format!("Bearer {}", token))
+                    .body(Body::from(json!({ "content": content }).to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+        comment_ids.push(json["id"].as_str().unwrap().to_string());
+    }
+
+    // Deleting the same comment twice must only decrement once
+    for _ in 0..2 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(format!("/api/feed/comments/{}", comment_ids[0]))
+                    .header("authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/api/feed/{}", post_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_str(&String::from_utf8_lossy(&body)).unwrap();
+    assert_eq!(json["comment_count"].as_i64().unwrap(), 1);
+}
+
 // ============================================================================
 // DELETE TESTS
 // ============================================================================
@@ -0,0 +1,154 @@
+// Tests for the collusion checks run before a verification counts
+
+use back_end::error::AppError;
+use back_end::services::FraudService;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{insert_user, setup_test_db};
+
+async fn create_user(pool: &PgPool) -> Uuid {
+    insert_user(pool, &format!("fraud-{}@example.com", Uuid::new_v4())).await
+}
+
+/// A report cleared by `clearer_id` at the given point in the Southern Ocean
+async fn create_cleared_report(pool: &PgPool, clearer_id: Uuid, longitude: f64) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO litter_reports (
+            reporter_id, location, status, claimed_by, claimed_at, cleared_by, cleared_at
+        )
+        VALUES (
+            $1, ST_SetSRID(ST_MakePoint($2, -60.0), 4326), 'cleared', $1, NOW(), $1, NOW()
+        )
+        RETURNING id
+        "#,
+    )
+    .bind(clearer_id)
+    .bind(longitude)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn approve(pool: &PgPool, report_id: Uuid, verifier_id: Uuid, minutes_ago: i32) {
+    sqlx::query(
+        "INSERT INTO report_verifications (report_id, verifier_id, is_verified, created_at)
+         VALUES ($1, $2, true, NOW() - make_interval(mins => $3))",
+    )
+    .bind(report_id)
+    .bind(verifier_id)
+    .bind(minutes_ago)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn open_flags(pool: &PgPool, user_id: Uuid, reason: &str) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM account_flags
+         WHERE user_id = $1 AND reason = $2 AND resolved_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(reason)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_reciprocal_ring_is_blocked_until_reviewed() {
+    let pool = setup_test_db().await;
+    let fraud = FraudService::new(pool.clone());
+
+    let alice = create_user(&pool).await;
+    let bob = create_user(&pool).await;
+    // Three approvals each way, all at the same spot so travel doesn't come into it
+    for _ in 0..3 {
+        let report = create_cleared_report(&pool, alice, 10.0).await;
+        approve(&pool, report, bob, 600).await;
+        let report = create_cleared_report(&pool, bob, 10.0).await;
+        approve(&pool, report, alice, 600).await;
+    }
+    let report = create_cleared_report(&pool, alice, 10.0).await;
+
+    // A rejection gains the clearer nothing
+    fraud
+        .check_verification(report, bob, false, None)
+        .await
+        .unwrap();
+
+    let result = fraud.check_verification(report, bob, true, None).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+    assert_eq!(open_flags(&pool, bob, "verification_ring").await, 1);
+    assert_eq!(open_flags(&pool, alice, "verification_ring").await, 1);
+
+    // Flags aren't duplicated while waiting for review
+    let result = fraud.check_verification(report, bob, true, None).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+    assert_eq!(open_flags(&pool, bob, "verification_ring").await, 1);
+
+    sqlx::query("UPDATE account_flags SET resolved_at = NOW() WHERE user_id = ANY($1)")
+        .bind(vec![alice, bob])
+        .execute(&pool)
+        .await
+        .unwrap();
+    fraud
+        .check_verification(report, bob, true, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_verifier_on_clearers_address_is_blocked() {
+    let pool = setup_test_db().await;
+    let fraud = FraudService::new(pool.clone());
+
+    let clearer = create_user(&pool).await;
+    let verifier = create_user(&pool).await;
+    let report = create_cleared_report(&pool, clearer, 20.0).await;
+    let ip_address = "198.51.100.7";
+    sqlx::query("INSERT INTO login_events (user_id, ip_address) VALUES ($1, $2)")
+        .bind(clearer)
+        .bind(ip_address)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    fraud
+        .check_verification(report, verifier, true, Some("192.0.2.1"))
+        .await
+        .unwrap();
+
+    let result = fraud
+        .check_verification(report, verifier, true, Some(ip_address))
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+    assert_eq!(open_flags(&pool, verifier, "shared_ip").await, 1);
+    assert_eq!(open_flags(&pool, clearer, "shared_ip").await, 1);
+}
+
+#[tokio::test]
+async fn test_impossible_travel_between_verifications_is_blocked() {
+    let pool = setup_test_db().await;
+    let fraud = FraudService::new(pool.clone());
+
+    let clearer = create_user(&pool).await;
+    let verifier = create_user(&pool).await;
+    let earlier = create_cleared_report(&pool, clearer, 30.0).await;
+    approve(&pool, earlier, verifier, 10).await;
+
+    // A degree of longitude at 60°S is about 55 km; ten minutes is not long enough
+    let nearby = create_cleared_report(&pool, clearer, 30.01).await;
+    fraud
+        .check_verification(nearby, verifier, true, None)
+        .await
+        .unwrap();
+
+    let far = create_cleared_report(&pool, clearer, 31.0).await;
+    let result = fraud.check_verification(far, verifier, true, None).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+    assert_eq!(open_flags(&pool, verifier, "impossible_travel").await, 1);
+    assert_eq!(open_flags(&pool, clearer, "impossible_travel").await, 0);
+}
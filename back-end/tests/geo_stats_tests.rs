@@ -0,0 +1,193 @@
+// Tests for the admin per-area report metrics
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::models::UserRole;
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{access_token, create_test_app, get_test_pool, insert_user, insert_user_with_role};
+
+/// A report at the given point; cleared ones were cleared now, `hours_to_clear` after
+/// they were made
+async fn create_report(
+    pool: &PgPool,
+    reporter_id: Uuid,
+    status: &str,
+    hours_to_clear: Option<f64>,
+    (latitude, longitude): (f64, f64),
+) {
+    sqlx::query(
+        r#"
+        INSERT INTO litter_reports
+            (reporter_id, location, status, created_at, claimed_by, claimed_at,
+             cleared_by, cleared_at)
+        VALUES ($1, ST_SetSRID(ST_MakePoint($5, $4), 4326), $2::report_status,
+                NOW() - make_interval(secs => COALESCE($3, 1) * 3600),
+                CASE WHEN $2 = 'pending' THEN NULL ELSE $1 END,
+                CASE WHEN $2 = 'pending' THEN NULL ELSE NOW() END,
+                CASE WHEN $3::float8 IS NULL THEN NULL ELSE $1 END,
+                CASE WHEN $3::float8 IS NULL THEN NULL ELSE NOW() END)
+        "#,
+    )
+    .bind(reporter_id)
+    .bind(status)
+    .bind(hours_to_clear)
+    .bind(latitude)
+    .bind(longitude)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+/// Three open reports, three clears taking 4, 6 and 10 hours of which one is verified,
+/// and a cancelled report that doesn't count
+async fn create_area_reports(pool: &PgPool, reporter_id: Uuid, point: (f64, f64)) {
+    create_report(pool, reporter_id, "pending", None, point).await;
+    create_report(pool, reporter_id, "pending", None, point).await;
+    create_report(pool, reporter_id, "claimed", None, point).await;
+    create_report(pool, reporter_id, "cleared", Some(4.0), point).await;
+    create_report(pool, reporter_id, "cleared", Some(10.0), point).await;
+    create_report(pool, reporter_id, "verified", Some(6.0), point).await;
+    create_report(pool, reporter_id, "cancelled", None, point).await;
+}
+
+async fn get_geo_stats(app: &axum::Router, token: &str, query: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/admin/stats/geo?{query}"))
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn assert_area_metrics(area: &Value) {
+    assert_eq!(area["total_reports"], 6);
+    assert_eq!(area["cleared_reports"], 3);
+    assert_eq!(area["verified_reports"], 1);
+    assert_eq!(area["unresolved_backlog"], 3);
+    let median = area["median_hours_to_clear"].as_f64().unwrap();
+    assert!((median - 6.0).abs() < 0.01, "median was {median}");
+    let rate = area["verification_rate"].as_f64().unwrap();
+    assert!((rate - 1.0 / 3.0).abs() < 1e-9, "rate was {rate}");
+}
+
+#[tokio::test]
+async fn test_geo_stats_by_grid_cell() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin = insert_user_with_role(&pool, "geo-grid-admin@example.com", UserRole::Admin).await;
+    let token = access_token(admin, "geo-grid-admin@example.com", UserRole::Admin);
+    let reporter = insert_user(&pool, "geo-grid-reporter@example.com").await;
+
+    // Two cells in the Southern Ocean, a degree apart
+    create_area_reports(&pool, reporter, (-48.2, 110.2)).await;
+    create_report(&pool, reporter, "pending", None, (-48.2, 111.2)).await;
+
+    let (status, body) = get_geo_stats(&app, &token, "group_by=grid&cell_size_deg=1").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let areas = body.as_array().unwrap();
+    let area = areas
+        .iter()
+        .find(|a| a["area"] == "-48.000,110.000")
+        .expect("cell missing");
+    assert_area_metrics(area);
+    assert!((area["center_latitude"].as_f64().unwrap() + 48.2).abs() < 1e-6);
+
+    let neighbour = areas
+        .iter()
+        .find(|a| a["area"] == "-48.000,111.000")
+        .expect("neighbouring cell missing");
+    assert_eq!(neighbour["unresolved_backlog"], 1);
+    assert!(neighbour["median_hours_to_clear"].is_null());
+    assert!(neighbour["verification_rate"].is_null());
+
+    let (status, _) = get_geo_stats(&app, &token, "group_by=grid&cell_size_deg=20").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_geo_stats(&app, &token, "group_by=street").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_geo_stats_by_reporter_city() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin = insert_user_with_role(&pool, "geo-city-admin@example.com", UserRole::Admin).await;
+    let token = access_token(admin, "geo-city-admin@example.com", UserRole::Admin);
+    let reporter = insert_user(&pool, "geo-city-reporter@example.com").await;
+    let city = format!("Geo-{}", Uuid::new_v4());
+    sqlx::query("UPDATE users SET city = $2 WHERE id = $1")
+        .bind(reporter)
+        .bind(&city)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    create_area_reports(&pool, reporter, (-48.7, 112.3)).await;
+
+    let (status, body) = get_geo_stats(&app, &token, "group_by=city").await;
+    assert_eq!(status, StatusCode::OK);
+    let area = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["area"] == city.as_str())
+        .expect("city missing");
+    assert_area_metrics(area);
+}
+
+#[tokio::test]
+async fn test_geo_stats_by_city_counts_anonymous_reports_as_unknown() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin = insert_user_with_role(&pool, "geo-anon-admin@example.com", UserRole::Admin).await;
+    let token = access_token(admin, "geo-anon-admin@example.com", UserRole::Admin);
+    let reporter = insert_user(&pool, "geo-anon-reporter@example.com").await;
+
+    create_report(&pool, reporter, "pending", None, (-49.1, 113.4)).await;
+    sqlx::query(
+        "INSERT INTO litter_reports (reporter_id, location, status)
+         VALUES (NULL, ST_SetSRID(ST_MakePoint(113.4, -49.1), 4326), 'pending')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, body) = get_geo_stats(&app, &token, "group_by=city").await;
+    assert_eq!(status, StatusCode::OK);
+    let area = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["area"] == "unknown")
+        .expect("anonymous reports missing");
+    assert!(area["unresolved_backlog"].as_i64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_geo_stats_needs_admin() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let user = insert_user(&pool, "geo-user@example.com").await;
+    let token = access_token(user, "geo-user@example.com", UserRole::User);
+
+    let (status, _) = get_geo_stats(&app, &token, "group_by=grid").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,239 @@
+// Tests for the queue that fills in report addresses after the report is saved
+
+use axum::{
+    async_trait,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::config::{GeocodingConfig, GeocodingProvider};
+use back_end::models::report::AddressComponents;
+use back_end::services::geocoding_service::{GeocodedAddress, Geocoder, LookupError};
+use back_end::services::{GeocodeQueueService, GeocodingService};
+use rand::Rng;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+const TEST_PHOTO: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// Answers with a fixed street, or fails the way it is told to
+enum FakeGeocoder {
+    Found,
+    Transient,
+    Permanent,
+}
+
+#[async_trait]
+impl Geocoder for FakeGeocoder {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn reverse(&self, _lat: f64, _lon: f64) -> Result<Option<GeocodedAddress>, LookupError> {
+        match self {
+            Self::Found => Ok(Some(GeocodedAddress {
+                label: "12 Test Street".to_string(),
+                components: AddressComponents {
+                    street: Some("Test Street".to_string()),
+                    city: Some("Testville".to_string()),
+                    country: Some("Testland".to_string()),
+                },
+            })),
+            Self::Transient => Err(LookupError::Transient("timed out".to_string())),
+            Self::Permanent => Err(LookupError::Permanent("bad request".to_string())),
+        }
+    }
+}
+
+fn queue(pool: &PgPool, geocoder: FakeGeocoder) -> GeocodeQueueService {
+    let config = GeocodingConfig {
+        provider: GeocodingProvider::Nominatim,
+        api_url: "http://127.0.0.1:1".to_string(),
+        api_key: None,
+        min_interval_ms: 0,
+        cache_decimals: 4,
+        cache_ttl_days: 30,
+    };
+    GeocodeQueueService::new(
+        pool.clone(),
+        GeocodingService::with_geocoder(pool.clone(), &config, Arc::new(geocoder)),
+    )
+}
+
+/// Helper to create a verified user and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn post_json(app: &axum::Router, uri: &str, token: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Create a report somewhere no cached address covers, queued afresh for geocoding
+async fn create_queued_report(app: &axum::Router, pool: &PgPool, token: &str) -> Uuid {
+    let (latitude, longitude) = {
+        let mut rng = rand::thread_rng();
+        (rng.gen_range(-60.0..60.0), rng.gen_range(-170.0..170.0))
+    };
+    let (status, report) = post_json(
+        app,
+        "/api/reports",
+        token,
+        json!({
+            "latitude": latitude,
+            "longitude": longitude,
+            "description": "Bottles by the bench",
+            "photo_base64": TEST_PHOTO
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let report_id: Uuid = report["id"].as_str().unwrap().parse().unwrap();
+
+    // Undo whatever the background lookup after creation managed
+    let mut tx = pool.begin().await.unwrap();
+    GeocodeQueueService::enqueue(&mut tx, report_id)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    report_id
+}
+
+async fn job_state(pool: &PgPool, report_id: Uuid) -> Option<(i32, Option<String>)> {
+    sqlx::query_as("SELECT attempts, last_error FROM geocode_jobs WHERE report_id = $1")
+        .bind(report_id)
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_report_address_is_filled_in_by_the_queue() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let token = create_verified_user_and_login(&app, "geocode-queue@example.com").await;
+    let report_id = create_queued_report(&app, &pool, &token).await;
+
+    assert!(queue(&pool, FakeGeocoder::Found)
+        .resolve(report_id)
+        .await
+        .unwrap());
+    assert!(job_state(&pool, report_id).await.is_none());
+
+    let address: Option<String> =
+        sqlx::query_scalar("SELECT address FROM litter_reports WHERE id = $1")
+            .bind(report_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(address.is_some());
+}
+
+#[tokio::test]
+async fn test_failed_lookups_stay_queued_until_they_give_up() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let token = create_verified_user_and_login(&app, "geocode-retry@example.com").await;
+
+    let report_id = create_queued_report(&app, &pool, &token).await;
+    assert!(!queue(&pool, FakeGeocoder::Transient)
+        .resolve(report_id)
+        .await
+        .unwrap());
+    let (attempts, last_error) = job_state(&pool, report_id).await.expect("still queued");
+    assert_eq!(attempts, 1);
+    assert_eq!(last_error.as_deref(), Some("timed out"));
+
+    // Backed off, so not due again yet
+    assert!(!queue(&pool, FakeGeocoder::Found)
+        .resolve(report_id)
+        .await
+        .unwrap());
+
+    let report_id = create_queued_report(&app, &pool, &token).await;
+    queue(&pool, FakeGeocoder::Permanent)
+        .resolve(report_id)
+        .await
+        .unwrap();
+    let (attempts, _) = job_state(&pool, report_id).await.expect("still queued");
+    assert!(attempts > 1, "a permanent failure is not retried");
+}
@@ -0,0 +1,131 @@
+// Tests for reverse geocoding through a pluggable provider with a rounded-coordinate cache
+
+use axum::async_trait;
+use back_end::config::{GeocodingConfig, GeocodingProvider};
+use back_end::models::report::AddressComponents;
+use back_end::services::geocoding_service::{
+    cache_key, GeocodedAddress, Geocoder, GeocodingService, LookupError,
+};
+use rand::Rng;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+mod helpers;
+use helpers::setup_test_db;
+
+/// Answers every point with the same street, or with nothing, and counts lookups
+struct FakeGeocoder {
+    calls: AtomicUsize,
+    found: bool,
+}
+
+impl FakeGeocoder {
+    fn new(found: bool) -> Arc<Self> {
+        Arc::new(Self {
+            calls: AtomicUsize::new(0),
+            found,
+        })
+    }
+}
+
+#[async_trait]
+impl Geocoder for FakeGeocoder {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn reverse(&self, _lat: f64, _lon: f64) -> Result<Option<GeocodedAddress>, LookupError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(self.found.then(|| GeocodedAddress {
+            label: "12 Test Street".to_string(),
+            components: AddressComponents {
+                street: Some("Test Street".to_string()),
+                city: Some("Testville".to_string()),
+                country: Some("Testland".to_string()),
+            },
+        }))
+    }
+}
+
+fn geocoding_config() -> GeocodingConfig {
+    GeocodingConfig {
+        provider: GeocodingProvider::Nominatim,
+        api_url: "http://127.0.0.1:1".to_string(),
+        api_key: None,
+        min_interval_ms: 0,
+        cache_decimals: 4,
+        cache_ttl_days: 30,
+    }
+}
+
+/// A point no other test run is likely to have cached, on a cache cell boundary
+fn random_point() -> (f64, f64) {
+    let mut rng = rand::thread_rng();
+    cache_key(rng.gen_range(-60.0..60.0), rng.gen_range(-170.0..170.0), 4)
+}
+
+#[test]
+fn test_cache_key_rounds_to_the_configured_decimals() {
+    assert_eq!(cache_key(51.507_351, -0.127_758, 4), (51.5074, -0.1278));
+    assert_eq!(cache_key(51.507_351, -0.127_758, 2), (51.51, -0.13));
+    assert_eq!(cache_key(51.507_351, -0.127_758, 0), (52.0, -0.0));
+    assert_eq!(
+        cache_key(51.507_351, -0.127_758, 4),
+        cache_key(51.507_38, -0.127_81, 4)
+    );
+}
+
+#[tokio::test]
+async fn test_nearby_points_share_one_provider_lookup() {
+    let pool = setup_test_db().await;
+    let geocoder = FakeGeocoder::new(true);
+    let service = GeocodingService::with_geocoder(pool, &geocoding_config(), geocoder.clone());
+    let (lat, lon) = random_point();
+
+    let first = service.reverse(lat, lon).await.expect("address");
+    assert_eq!(first.label, "12 Test Street");
+
+    // Within the same rounded cell, so served from the cache
+    let second = service
+        .reverse(lat + 0.000_01, lon - 0.000_01)
+        .await
+        .expect("cached address");
+    assert_eq!(second.label, "12 Test Street");
+    assert_eq!(second.components.city.as_deref(), Some("Testville"));
+    assert_eq!(geocoder.calls.load(Ordering::SeqCst), 1);
+
+    // A different cell asks the provider again
+    service.reverse(lat + 0.01, lon).await.expect("address");
+    assert_eq!(geocoder.calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_places_without_an_address_are_cached_too() {
+    let pool = setup_test_db().await;
+    let geocoder = FakeGeocoder::new(false);
+    let service = GeocodingService::with_geocoder(pool, &geocoding_config(), geocoder.clone());
+    let (lat, lon) = random_point();
+
+    assert!(service.reverse(lat, lon).await.is_none());
+    assert!(service.reverse(lat, lon).await.is_none());
+    assert_eq!(geocoder.calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_expired_cache_entries_are_looked_up_again() {
+    let pool = setup_test_db().await;
+    let geocoder = FakeGeocoder::new(true);
+    let service = GeocodingService::with_geocoder(
+        pool,
+        &GeocodingConfig {
+            cache_ttl_days: 0,
+            ..geocoding_config()
+        },
+        geocoder.clone(),
+    );
+    let (lat, lon) = random_point();
+
+    service.reverse(lat, lon).await.expect("address");
+    service.reverse(lat, lon).await.expect("address");
+    assert_eq!(geocoder.calls.load(Ordering::SeqCst), 2);
+}
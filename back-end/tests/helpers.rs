@@ -1,13 +1,25 @@
 // Test helpers for integration tests
 
-use axum::Router;
+use axum::{extract::connect_info::MockConnectInfo, Router};
 use sqlx::PgPool;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use uuid::Uuid;
 
 // Re-export modules for tests
-use back_end::{auth, config, db, handlers, services};
+use back_end::{auth, config, db, handlers, idempotency, jobs, models, perf, rate_limit, services};
 
+#[allow(dead_code)]
 pub async fn create_test_app() -> Router {
+    let pool = setup_test_db().await;
+    let config = config::Config::from_env().expect("Failed to load config");
+
+    build_test_router(config, pool).await
+}
+
+/// A pool on a migrated and emptied test database, for tests that call services directly
+#[allow(dead_code)]
+pub async fn setup_test_db() -> PgPool {
     // Load test environment variables
     dotenvy::from_filename(".env.test").ok();
 
@@ -28,7 +40,39 @@ pub async fn create_test_app() -> Router {
     // Clean up test data before each test
     cleanup_test_data(&pool).await;
 
-    build_test_router(config, pool).await
+    pool
+}
+
+/// Insert a verified user straight into the database, skipping registration
+#[allow(dead_code)]
+pub async fn insert_user(pool: &PgPool, email: &str) -> Uuid {
+    insert_user_with_role(pool, email, models::UserRole::User).await
+}
+
+/// A signed access token for a user, as login would issue
+#[allow(dead_code)]
+pub fn access_token(user_id: Uuid, email: &str, role: models::UserRole) -> String {
+    dotenvy::from_filename(".env.test").ok();
+    let config = config::Config::from_env().expect("Failed to load config");
+    auth::JwtService::new(config.jwt)
+        .expect("Failed to create JWT service")
+        .create_access_token(user_id, email, &role, false, Uuid::new_v4())
+        .expect("Failed to create access token")
+}
+
+/// Insert a verified user with `role`; they have no usable password
+#[allow(dead_code)]
+pub async fn insert_user_with_role(pool: &PgPool, email: &str, role: models::UserRole) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash, full_name, city, country, email_verified, role)
+         VALUES ($1, 'not-a-real-hash', 'Test User', 'London', 'UK', true, $2)
+         RETURNING id",
+    )
+    .bind(email)
+    .bind(role)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to insert user")
 }
 
 /// Helper to get a database pool for test helpers
@@ -52,102 +96,537 @@ async fn build_test_router(config: config::Config, pool: sqlx::PgPool) -> Router
         .expect("Failed to initialize S3 bucket");
 
     // Initialize services
-    let jwt_service = auth::JwtService::new(config.jwt.clone());
+    let jwt_service =
+        auth::JwtService::new(config.jwt.clone()).expect("Failed to create JWT service");
     // Use real email service with MailHog for tests
-    let email_service =
-        services::EmailService::new(config.email.clone()).expect("Failed to create email service");
-    let image_service = services::ImageService::new(config.image.clone());
-    let report_service =
-        services::ReportService::new(pool.clone(), image_service.clone(), s3_service.clone());
-    let feed_service = services::FeedService::new(pool.clone(), image_service, s3_service.clone());
+    let email_service = services::EmailService::new(pool.clone(), config.email.clone())
+        .expect("Failed to create email service");
+    let upload_scan_service =
+        services::UploadScanService::new(pool.clone(), config.upload_scan.clone());
+    let image_service =
+        services::ImageService::new(config.image.clone()).with_scanner(upload_scan_service.clone());
+    let upload_service =
+        services::UploadService::new(pool.clone(), s3_service.clone(), config.image.clone());
+    let image_storage = services::ImageStorageService::new(
+        pool.clone(),
+        s3_service.clone(),
+        config.s3.spill_max_mb,
+    );
+    let report_service = services::ReportService::new(
+        pool.clone(),
+        image_service.clone(),
+        image_storage.clone(),
+        upload_service.clone(),
+        services::GeocodingService::new(pool.clone(), config.geocoding.clone()),
+        config.location_check.clone(),
+    );
+    let feed_service =
+        services::FeedService::new(pool.clone(), image_service.clone(), image_storage);
     let scoring_service = services::ScoringService::new(pool.clone(), config.scoring.clone());
+    let notification_service = services::NotificationService::new(pool.clone());
+
+    let two_factor_service = services::TwoFactorService::new(pool.clone());
 
     let auth_service = Arc::new(services::AuthService::new(
         pool.clone(),
         jwt_service.clone(),
-        email_service,
+        email_service.clone(),
+        two_factor_service.clone(),
         config.clone(),
     ));
 
+    let rate_limiters = rate_limit::RateLimiters::new(&config.rate_limit);
+    let idempotency_service = services::IdempotencyService::new(pool.clone());
+    let auth_middleware_state = auth::AuthMiddlewareState {
+        jwt_service: jwt_service.clone(),
+        pool: pool.clone(),
+        rate_limiter: Some(rate_limiters.general.clone()),
+        token_scope: None,
+    };
+    let upload_auth_state = auth::AuthMiddlewareState {
+        token_scope: Some(models::TokenScope::UploadOnly),
+        ..auth_middleware_state.clone()
+    };
+
+    let api_key_service = services::ApiKeyService::new(pool.clone());
+    let reports_api_key_state = auth::ApiKeyMiddlewareState {
+        api_key_service: api_key_service.clone(),
+        scope: models::ApiKeyScope::ReportsRead,
+        auth: Some(auth_middleware_state.clone()),
+    };
+    let leaderboards_api_key_state = auth::ApiKeyMiddlewareState {
+        api_key_service: api_key_service.clone(),
+        scope: models::ApiKeyScope::LeaderboardsRead,
+        auth: None,
+    };
+
+    let event_service = services::EventService::new(pool.clone());
+    let email_webhook_service =
+        services::EmailWebhookService::new(email_service.clone(), event_service.clone());
+    let email_webhook_state = Arc::new(handlers::EmailWebhookState {
+        email_webhook_service: email_webhook_service.clone(),
+        webhook_secret: config.email.webhook_secret.clone(),
+    });
+
     let user_state = Arc::new(handlers::UserHandlerState { pool: pool.clone() });
 
+    let saved_search_service = services::SavedSearchService::new(pool.clone());
+    let watched_area_service = services::WatchedAreaService::new(pool.clone());
+    let cleanup_event_service =
+        services::CleanupEventService::new(pool.clone(), config.scoring.cleanup_event_bonus);
+
+    let report_admin_service = services::ReportAdminService::new(
+        pool.clone(),
+        scoring_service.clone(),
+        event_service.clone(),
+    );
+    let report_flag_service = services::ReportFlagService::new(
+        pool.clone(),
+        report_service.clone(),
+        report_admin_service.clone(),
+        config.moderation.clone(),
+    );
+
+    let city_goal_service =
+        services::CityGoalService::new(pool.clone(), notification_service.clone());
+
     let report_state = Arc::new(handlers::ReportHandlerState {
         report_service: report_service.clone(),
+        report_flag_service: report_flag_service.clone(),
         scoring_service: scoring_service.clone(),
+        saved_search_service: saved_search_service.clone(),
+        notification_service: notification_service.clone(),
+        captcha_service: services::CaptchaService::new(config.captcha.clone()),
+        watched_area_service: watched_area_service.clone(),
+        cleanup_event_service: cleanup_event_service.clone(),
+        city_goal_service: city_goal_service.clone(),
+        translation_service: services::TranslationService::new(
+            pool.clone(),
+            config.translation.clone(),
+        ),
+    });
+
+    let saved_search_state = Arc::new(handlers::SavedSearchHandlerState {
+        saved_search_service,
+    });
+
+    let watched_area_state = Arc::new(handlers::WatchedAreaHandlerState {
+        watched_area_service,
+    });
+
+    let home_area_state = Arc::new(handlers::HomeAreaHandlerState {
+        home_area_service: services::HomeAreaService::new(pool.clone()),
     });
 
+    let cleanup_event_state = Arc::new(handlers::CleanupEventHandlerState {
+        cleanup_event_service,
+    });
+
+    let verification_service = services::VerificationService::new(
+        pool.clone(),
+        report_service.clone(),
+        scoring_service.clone(),
+        notification_service.clone(),
+        config.scoring.clone(),
+    );
     let verification_state = Arc::new(handlers::VerificationHandlerState {
+        verification_service: verification_service.clone(),
+        fraud_service: services::FraudService::new(pool.clone()),
+    });
+
+    // Admin state; tests run consistency checks and read latency themselves
+    let admin_state = Arc::new(handlers::AdminHandlerState {
         pool: pool.clone(),
+        email_service,
+        event_service,
+        email_webhook_service,
+        report_admin_service,
         report_service: report_service.clone(),
-        scoring_service: scoring_service.clone(),
-        scoring_config: config.scoring.clone(),
+        report_flag_service,
+        report_import_service: services::ReportImportService::new(
+            pool.clone(),
+            report_service.clone(),
+            image_service,
+            config.image.max_size_mb,
+        ),
+        api_key_service,
+        impersonation_service: services::ImpersonationService::new(pool.clone(), jwt_service),
+        consistency_check_job: jobs::ConsistencyCheckJob::new(pool.clone()),
+        feed_service: feed_service.clone(),
+        latency_monitor: perf::LatencyMonitor::new(config.latency.clone()),
+        upload_scan_service,
+        city_goal_service: city_goal_service.clone(),
+        verification_service,
+    });
+
+    let export_state = Arc::new(handlers::ExportHandlerState {
+        data_export_service: services::DataExportService::new(
+            pool.clone(),
+            s3_service.clone(),
+            notification_service.clone(),
+        ),
+    });
+
+    let notification_state = Arc::new(handlers::NotificationHandlerState {
+        notification_service,
     });
 
-    let leaderboard_state = Arc::new(handlers::LeaderboardHandlerState { pool: pool.clone() });
+    let two_factor_state = Arc::new(handlers::TwoFactorHandlerState { two_factor_service });
+
+    let upload_state = Arc::new(handlers::UploadHandlerState { upload_service });
 
+    let leaderboard_state = Arc::new(handlers::LeaderboardHandlerState {
+        pool: pool.clone(),
+        timezone: config.scoring.leaderboard_timezone.clone(),
+    });
+
+    // Tests flush and score engagement themselves, so no jobs are spawned
     let feed_state = Arc::new(handlers::FeedHandlerState {
         feed_service: feed_service.clone(),
+        engagement: config.feed_trending.as_ref().map(|_| {
+            services::EngagementRecorder::new(Arc::new(services::PgEngagementSink::new(
+                pool.clone(),
+            )))
+        }),
     });
 
     // Build router - using nested routers to properly separate auth states
-    use axum::routing::{delete, get, patch, post};
+    use axum::routing::{delete, get, patch, post, put};
 
     // Auth routes (no auth middleware)
     let auth_router = Router::new()
         .route("/api/auth/register", post(handlers::register))
         .route("/api/auth/login", post(handlers::login))
         .route("/api/auth/verify-email", post(handlers::verify_email))
+        .route(
+            "/api/auth/link-account",
+            post(handlers::confirm_account_link),
+        )
+        .route("/api/auth/2fa/login", post(handlers::two_factor_login))
+        .route(
+            "/api/auth/confirm-email-change",
+            post(handlers::confirm_email_change),
+        )
+        .route("/api/auth/set-password", post(handlers::set_password))
+        .route("/api/auth/magic-link", post(handlers::request_magic_link))
+        .route(
+            "/api/auth/magic-link/verify",
+            post(handlers::verify_magic_link),
+        )
         .route(
             "/api/auth/resend-verification",
             post(handlers::resend_verification),
         )
+        .route(
+            "/api/auth/verification-reminders/unsubscribe",
+            post(handlers::unsubscribe_verification_reminders),
+        )
         .route("/api/auth/forgot-password", post(handlers::forgot_password))
         .route("/api/auth/reset-password", post(handlers::reset_password))
         .route("/api/auth/refresh", post(handlers::refresh_token))
         .route("/api/auth/logout", post(handlers::logout))
-        .with_state(auth_service.clone());
+        .with_state(auth_service.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.auth.clone(),
+            rate_limit::limit_by_ip,
+        ));
+
+    let auth_session_router = Router::new()
+        .route("/api/auth/logout-all", post(handlers::logout_all))
+        .route(
+            "/api/auth/service-tokens",
+            post(handlers::create_service_token),
+        )
+        .route("/api/users/me/sessions", get(handlers::list_sessions))
+        .route("/api/users/me/logins", get(handlers::list_login_history))
+        .route(
+            "/api/users/me/sessions/:id",
+            delete(handlers::revoke_session),
+        )
+        .route(
+            "/api/users/me/oauth/:provider",
+            delete(handlers::unlink_oauth_provider),
+        )
+        .route("/api/users/me/password", put(handlers::change_password))
+        .route(
+            "/api/users/me/password/setup",
+            post(handlers::request_password_setup),
+        )
+        .route(
+            "/api/users/me/email-change",
+            post(handlers::request_email_change),
+        )
+        .with_state(auth_service.clone())
+        .route_layer(axum::middleware::from_fn(
+            auth::middleware::reject_impersonation,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
 
     // User routes (with auth middleware)
     let user_router = Router::new()
         .route("/api/users/me", get(handlers::get_current_user))
+        .route(
+            "/api/users/me",
+            patch(handlers::update_current_user).route_layer(axum::middleware::from_fn(
+                auth::middleware::reject_impersonation,
+            )),
+        )
+        .route("/api/users/me/score", get(handlers::get_current_user_score))
+        .route("/api/users/:id", get(handlers::get_user_profile))
         .with_state(user_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Saved search routes (with auth middleware)
+    let saved_search_router = Router::new()
+        .route("/api/users/me/searches", get(handlers::list_saved_searches))
+        .route(
+            "/api/users/me/searches",
+            post(handlers::create_saved_search),
+        )
+        .route(
+            "/api/users/me/searches/:id",
+            get(handlers::get_saved_search),
+        )
+        .route(
+            "/api/users/me/searches/:id",
+            patch(handlers::update_saved_search),
+        )
+        .route(
+            "/api/users/me/searches/:id",
+            delete(handlers::delete_saved_search),
+        )
+        .with_state(saved_search_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    let watched_area_router = Router::new()
+        .route(
+            "/api/users/me/watched-areas",
+            get(handlers::list_watched_areas),
+        )
+        .route(
+            "/api/users/me/watched-areas",
+            post(handlers::create_watched_area),
+        )
+        .route(
+            "/api/users/me/watched-areas/:id",
+            delete(handlers::delete_watched_area),
+        )
+        .with_state(watched_area_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    let home_area_router = Router::new()
+        .route(
+            "/api/users/me/area",
+            get(handlers::get_home_area)
+                .put(handlers::set_home_area)
+                .delete(handlers::delete_home_area),
+        )
+        .with_state(home_area_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
             auth::middleware::require_auth,
         ));
 
+    let cleanup_event_router = Router::new()
+        .route(
+            "/api/cleanup-events",
+            get(handlers::list_cleanup_events).post(handlers::create_cleanup_event),
+        )
+        .route("/api/cleanup-events/:id", get(handlers::get_cleanup_event))
+        .route(
+            "/api/cleanup-events/:id/rsvp",
+            post(handlers::rsvp_cleanup_event).delete(handlers::cancel_cleanup_event_rsvp),
+        )
+        .with_state(cleanup_event_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Nearby reports (with auth middleware, or a partner API key)
+    let nearby_report_router = Router::new()
+        .route("/api/reports/nearby", get(handlers::get_nearby_reports))
+        .route(
+            "/api/reports/in-bounds",
+            get(handlers::get_reports_in_bounds),
+        )
+        .route("/api/reports/clusters", get(handlers::get_report_clusters))
+        .with_state(report_state.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            reports_api_key_state,
+            auth::middleware::accept_api_key,
+        ));
+
+    // Guest reports (limited per IP)
+    let anonymous_report_router = Router::new()
+        .route(
+            "/api/reports/anonymous",
+            post(handlers::create_anonymous_report),
+        )
+        .with_state(report_state.clone())
+        .route_layer(axum::middleware::from_fn_with_state(
+            rate_limiters.reports.clone(),
+            rate_limit::limit_by_ip,
+        ));
+
     // Report routes (with auth middleware)
     let report_router = Router::new()
-        .route("/api/reports", post(handlers::create_report))
-        .route("/api/reports/nearby", get(handlers::get_nearby_reports))
+        .route(
+            "/api/reports",
+            post(handlers::create_report)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.reports.clone(),
+                    rate_limit::limit_by_user,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    idempotency_service.clone(),
+                    idempotency::idempotent,
+                )),
+        )
+        .route(
+            "/api/reports/verification-queue",
+            get(handlers::get_verification_queue),
+        )
         .route("/api/reports/my-reports", get(handlers::get_my_reports))
         .route(
             "/api/reports/my-clears",
             get(handlers::get_my_cleared_reports),
         )
-        .route("/api/reports/:id", get(handlers::get_report))
+        .route(
+            "/api/reports/:id",
+            get(handlers::get_report)
+                .patch(handlers::update_report)
+                .delete(handlers::cancel_report),
+        )
+        .route(
+            "/api/reports/claim-batch",
+            post(handlers::claim_report_batch),
+        )
         .route("/api/reports/:id/claim", post(handlers::claim_report))
-        .route("/api/reports/:id/clear", post(handlers::clear_report))
+        .route("/api/reports/:id/unclaim", post(handlers::unclaim_report))
+        .route("/api/reports/:id/flag", post(handlers::flag_report))
+        .route(
+            "/api/reports/:id/timeline",
+            get(handlers::get_report_timeline),
+        )
+        .route(
+            "/api/reports/:id/history",
+            get(handlers::get_report_history),
+        )
+        .route(
+            "/api/reports/anonymous/claim",
+            post(handlers::claim_anonymous_report),
+        )
+        .route("/api/cleanup-runs/:id", get(handlers::get_cleanup_run))
+        .route(
+            "/api/reports/:id/clear",
+            post(handlers::clear_report)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    idempotency_service.clone(),
+                    idempotency::idempotent,
+                )),
+        )
+        .route(
+            "/api/reports/:id/after-photo",
+            put(handlers::replace_after_photo).route_layer(axum::middleware::from_fn_with_state(
+                rate_limiters.uploads_in_flight.clone(),
+                rate_limit::limit_in_flight,
+            )),
+        )
+        .route(
+            "/api/reports/:id/photos",
+            post(handlers::add_report_photo)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .get(handlers::get_report_photos),
+        )
         .with_state(report_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Personal data export routes (with auth middleware)
+    let export_router = Router::new()
+        .route(
+            "/api/users/me/export",
+            post(handlers::request_data_export).get(handlers::get_data_export),
+        )
+        .route(
+            "/api/users/me/export/:id/download",
+            get(handlers::download_data_export),
+        )
+        .with_state(export_state)
+        .route_layer(axum::middleware::from_fn(
+            auth::middleware::reject_impersonation,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Chunked upload routes (with auth middleware)
+    let upload_router = Router::new()
+        .route("/api/uploads", post(handlers::create_upload))
+        .route("/api/uploads/:id", get(handlers::get_upload))
+        .route("/api/uploads/:id", delete(handlers::cancel_upload))
+        .route(
+            "/api/uploads/:id/chunks",
+            post(handlers::append_upload_chunk),
+        )
+        .route("/api/uploads/:id/complete", post(handlers::complete_upload))
+        .with_state(upload_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            upload_auth_state,
             auth::middleware::require_auth,
         ));
 
     // Verification routes (with auth middleware)
     let verification_router = Router::new()
-        .route("/api/reports/:id/verify", post(handlers::verify_report))
+        .route(
+            "/api/reports/:id/verify",
+            post(handlers::verify_report).route_layer(axum::middleware::from_fn_with_state(
+                rate_limiters.verifications.clone(),
+                rate_limit::limit_by_user,
+            )),
+        )
+        .route(
+            "/api/reports/:id/verify/reserve",
+            post(handlers::reserve_verification).delete(handlers::release_verification),
+        )
         .route(
             "/api/reports/:id/verifications",
             get(handlers::get_report_verifications),
         )
         .with_state(verification_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
             auth::middleware::require_auth,
         ));
 
-    // Leaderboard routes (with auth middleware)
+    // Leaderboard routes (public; partner API keys need leaderboards:read)
     let leaderboard_router = Router::new()
         .route("/api/leaderboards", get(handlers::get_global_leaderboard))
         .route(
@@ -160,13 +639,64 @@ async fn build_test_router(config: config::Config, pool: sqlx::PgPool) -> Router
         )
         .with_state(leaderboard_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
-            auth::middleware::require_auth,
+            leaderboards_api_key_state,
+            auth::middleware::accept_api_key,
         ));
 
+    // Landing page counters (public)
+    let stats_router = Router::new()
+        .route("/api/stats/summary", get(handlers::get_stats_summary))
+        .route("/api/stats/hotspots", get(handlers::get_hotspots))
+        .route(
+            "/api/locations/history",
+            get(handlers::get_location_history),
+        )
+        .with_state(Arc::new(handlers::StatsHandlerState {
+            stats_service: services::StatsService::new(pool.clone()),
+        }));
+
+    let scoring_router = Router::new()
+        .route("/api/scoring/rules", get(handlers::get_scoring_rules))
+        .with_state(Arc::new(handlers::ScoringHandlerState {
+            scoring_service: scoring_service.clone(),
+        }));
+
+    let capabilities_router = Router::new()
+        .route("/api/capabilities", get(handlers::get_capabilities))
+        .with_state(Arc::new(handlers::CapabilitiesHandlerState {
+            capabilities: models::Capabilities::from_config(&config),
+        }));
+
+    let city_goal_router = Router::new()
+        .route("/api/city-goals", get(handlers::list_city_goals))
+        .route("/api/city-goals/:id", get(handlers::get_city_goal))
+        .with_state(Arc::new(handlers::CityGoalHandlerState {
+            city_goal_service,
+        }));
+
+    let open_data_router = Router::new()
+        .route(
+            "/api/open-data/reports",
+            get(handlers::get_open_data_reports),
+        )
+        .with_state(Arc::new(handlers::OpenDataHandlerState {
+            open_data_service: services::OpenDataService::new(pool.clone()),
+        }));
+
     // Feed routes (with auth middleware)
     let feed_router = Router::new()
-        .route("/api/feed", post(handlers::create_post))
+        .route(
+            "/api/feed",
+            post(handlers::create_post)
+                .route_layer(axum::middleware::from_fn_with_state(
+                    rate_limiters.uploads_in_flight.clone(),
+                    rate_limit::limit_in_flight,
+                ))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    idempotency_service,
+                    idempotency::idempotent,
+                )),
+        )
         .route("/api/feed", get(handlers::get_feed))
         .route("/api/feed/:id", get(handlers::get_post))
         .route("/api/feed/:id", patch(handlers::update_post))
@@ -186,22 +716,262 @@ async fn build_test_router(config: config::Config, pool: sqlx::PgPool) -> Router
         )
         .route("/api/feed/:post_id/like", post(handlers::like_post))
         .route("/api/feed/:post_id/like", delete(handlers::unlike_post))
+        .route("/api/feed/:post_id/dwell", post(handlers::record_dwell))
         .with_state(feed_state)
         .route_layer(axum::middleware::from_fn_with_state(
-            jwt_service.clone(),
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Two-factor management routes (with auth middleware)
+    let two_factor_router = Router::new()
+        .route("/api/auth/2fa", get(handlers::get_two_factor_status))
+        .route("/api/auth/2fa/setup", post(handlers::setup_two_factor))
+        .route("/api/auth/2fa/verify", post(handlers::verify_two_factor))
+        .route("/api/auth/2fa/disable", post(handlers::disable_two_factor))
+        .with_state(two_factor_state)
+        .route_layer(axum::middleware::from_fn(
+            auth::middleware::reject_impersonation,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Notification routes (with auth middleware)
+    let notification_router = Router::new()
+        .route("/api/notifications/poll", get(handlers::poll_notifications))
+        .with_state(notification_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
+            auth::middleware::require_auth,
+        ));
+
+    // Admin and moderation routes (each group needs its own permission)
+    let require = |permission| {
+        axum::middleware::from_fn_with_state(permission, auth::middleware::require_permission)
+    };
+    let admin_router = Router::new()
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/feed/:id",
+                    delete(handlers::moderate_delete_feed_post),
+                )
+                .route_layer(require(models::Permission::DeleteFeedPosts)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/account-flags",
+                    get(handlers::list_account_flags),
+                )
+                .route(
+                    "/api/admin/account-flags/:id/resolve",
+                    post(handlers::resolve_account_flag),
+                )
+                .route(
+                    "/api/admin/report-flags",
+                    get(handlers::list_flagged_reports),
+                )
+                .route(
+                    "/api/admin/reports/:id/flags",
+                    get(handlers::list_report_flags),
+                )
+                .route(
+                    "/api/admin/reports/:id/flags/review",
+                    post(handlers::review_report_flags),
+                )
+                .route_layer(require(models::Permission::ResolveFlags)),
+        )
+        .merge(
+            Router::new()
+                .route("/api/admin/users", get(handlers::list_users))
+                .route("/api/admin/users/:id", get(handlers::get_user_by_id))
+                .route(
+                    "/api/admin/users/:id/impersonate",
+                    post(handlers::impersonate_user),
+                )
+                .route(
+                    "/api/admin/impersonations",
+                    get(handlers::list_impersonations),
+                )
+                .route(
+                    "/api/admin/users/:id/email-suppression",
+                    delete(handlers::clear_email_suppression),
+                )
+                .route_layer(require(models::Permission::ManageUsers)),
+        )
+        .merge(
+            Router::new()
+                .route("/api/admin/users/:id/ban", put(handlers::toggle_user_ban))
+                .route_layer(require(models::Permission::BanUsers)),
+        )
+        .merge(
+            Router::new()
+                .route("/api/admin/users/:id/role", put(handlers::set_user_role))
+                .route_layer(require(models::Permission::ManageRoles)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/users/:id/reports",
+                    delete(handlers::purge_user_reports),
+                )
+                .route("/api/admin/reports", get(handlers::list_all_reports))
+                .route("/api/admin/reports/import", post(handlers::import_reports))
+                .route(
+                    "/api/admin/reports/import/:id",
+                    get(handlers::get_report_import),
+                )
+                .route("/api/admin/reports/:id", delete(handlers::delete_report))
+                .route(
+                    "/api/admin/reports/:id/reassign",
+                    post(handlers::reassign_report),
+                )
+                .route(
+                    "/api/admin/reports/:id/status",
+                    post(handlers::override_report_status),
+                )
+                .route(
+                    "/api/admin/reports/:id/verification-override",
+                    post(handlers::override_report_verification),
+                )
+                .route(
+                    "/api/admin/reports/:id/reactivate",
+                    post(handlers::reactivate_report),
+                )
+                .route(
+                    "/api/admin/reports/:id/edits",
+                    get(handlers::list_report_edits),
+                )
+                .route(
+                    "/api/admin/reports/:id/history",
+                    get(handlers::get_admin_report_history),
+                )
+                .route("/api/admin/stats/geo", get(handlers::get_geo_stats))
+                .route_layer(require(models::Permission::ManageReports)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/api/admin/emails/preview/:template",
+                    get(handlers::preview_email),
+                )
+                .route("/api/admin/emails/test", post(handlers::send_test_email))
+                .route("/api/admin/events", get(handlers::list_events))
+                .route("/api/admin/events/:id", get(handlers::get_event))
+                .route("/api/admin/events/:id/retry", post(handlers::retry_event))
+                .route(
+                    "/api/admin/api-keys",
+                    get(handlers::list_api_keys).post(handlers::create_api_key),
+                )
+                .route(
+                    "/api/admin/api-keys/:id",
+                    patch(handlers::update_api_key).delete(handlers::revoke_api_key),
+                )
+                .route(
+                    "/api/admin/consistency-checks",
+                    get(handlers::list_consistency_checks).post(handlers::run_consistency_check),
+                )
+                .route("/api/admin/perf", get(handlers::get_perf_report))
+                .route(
+                    "/api/admin/city-goals",
+                    get(handlers::admin_list_city_goals).post(handlers::create_city_goal),
+                )
+                .route(
+                    "/api/admin/city-goals/:id",
+                    put(handlers::update_city_goal).delete(handlers::delete_city_goal),
+                )
+                .route(
+                    "/api/admin/quarantine",
+                    get(handlers::get_upload_quarantine),
+                )
+                .route(
+                    "/api/admin/quarantine/:id",
+                    delete(handlers::delete_quarantined_upload),
+                )
+                .route(
+                    "/api/admin/quarantine/:id/data",
+                    get(handlers::download_quarantined_upload),
+                )
+                .route_layer(require(models::Permission::ManageSystem)),
+        )
+        .with_state(admin_state)
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth_middleware_state.clone(),
             auth::middleware::require_auth,
         ));
 
+    // Webhook routes (shared secret, no auth middleware)
+    let webhook_router = Router::new()
+        .route(
+            "/api/webhooks/email/:provider",
+            post(handlers::receive_email_webhook),
+        )
+        .with_state(email_webhook_state);
+
+    // Test helper routes; in-process requests have no socket, so they come from localhost
+    let mut test_helper_router = Router::new();
+    if let Some(test_helpers) = &config.test_helpers {
+        let test_helper_state = Arc::new(handlers::TestHelperState {
+            pool: pool.clone(),
+            auth_service: auth_service.clone(),
+        });
+        test_helper_router = Router::new()
+            .route("/api/test/status", get(handlers::test_status))
+            .route(
+                "/api/test/verify-email/:email",
+                post(handlers::verify_email_for_testing),
+            )
+            .route("/api/test/cleanup", delete(handlers::cleanup_test_data))
+            .route("/api/test/users", post(handlers::create_test_users))
+            .route(
+                "/api/test/users/:email/clears",
+                post(handlers::fast_forward_clears),
+            )
+            .route("/api/test/reports", post(handlers::create_test_report))
+            .route(
+                "/api/test/swagger-session",
+                post(handlers::create_swagger_session),
+            )
+            .with_state(test_helper_state)
+            .route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(test_helpers.clone()),
+                handlers::require_test_helper_access,
+            ))
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+    }
+
     // Combine all routers
     Router::new()
         .route("/", get(|| async { "LittyPicky API v0.1.0" }))
         .route("/health", get(health_check))
         .merge(auth_router)
+        .merge(auth_session_router)
+        .merge(two_factor_router)
         .merge(user_router)
+        .merge(saved_search_router)
+        .merge(watched_area_router)
+        .merge(home_area_router)
+        .merge(cleanup_event_router)
+        .merge(nearby_report_router)
+        .merge(anonymous_report_router)
         .merge(report_router)
+        .merge(upload_router)
+        .merge(export_router)
         .merge(verification_router)
         .merge(leaderboard_router)
+        .merge(stats_router)
+        .merge(scoring_router)
+        .merge(capabilities_router)
+        .merge(city_goal_router)
+        .merge(open_data_router)
         .merge(feed_router)
+        .merge(notification_router)
+        .merge(admin_router)
+        .merge(webhook_router)
+        .merge(test_helper_router)
 }
 
 async fn health_check() -> &'static str {
@@ -221,6 +991,41 @@ pub async fn cleanup_test_data(pool: &PgPool) {
         .await
         .expect("Failed to clean user_scores");
 
+    sqlx::query!("DELETE FROM saved_searches")
+        .execute(pool)
+        .await
+        .expect("Failed to clean saved_searches");
+
+    sqlx::query("DELETE FROM city_goals")
+        .execute(pool)
+        .await
+        .expect("Failed to clean city_goals");
+
+    sqlx::query("DELETE FROM cleanup_events")
+        .execute(pool)
+        .await
+        .expect("Failed to clean cleanup_events");
+
+    sqlx::query("DELETE FROM watched_areas")
+        .execute(pool)
+        .await
+        .expect("Failed to clean watched_areas");
+
+    sqlx::query("DELETE FROM report_imports")
+        .execute(pool)
+        .await
+        .expect("Failed to clean report_imports");
+
+    sqlx::query!("DELETE FROM notifications")
+        .execute(pool)
+        .await
+        .expect("Failed to clean notifications");
+
+    sqlx::query!("DELETE FROM email_outbox")
+        .execute(pool)
+        .await
+        .expect("Failed to clean email_outbox");
+
     sqlx::query!("DELETE FROM litter_reports")
         .execute(pool)
         .await
@@ -246,6 +1051,11 @@ pub async fn cleanup_test_data(pool: &PgPool) {
         .await
         .expect("Failed to clean feed_posts");
 
+    sqlx::query!("DELETE FROM login_events")
+        .execute(pool)
+        .await
+        .expect("Failed to clean login_events");
+
     sqlx::query!("DELETE FROM refresh_tokens")
         .execute(pool)
         .await
@@ -256,11 +1066,36 @@ pub async fn cleanup_test_data(pool: &PgPool) {
         .await
         .expect("Failed to clean email_verification_tokens");
 
+    sqlx::query!("DELETE FROM event_log")
+        .execute(pool)
+        .await
+        .expect("Failed to clean event_log");
+
+    sqlx::query!("DELETE FROM email_suppressions")
+        .execute(pool)
+        .await
+        .expect("Failed to clean email_suppressions");
+
     sqlx::query!("DELETE FROM password_reset_tokens")
         .execute(pool)
         .await
         .expect("Failed to clean password_reset_tokens");
 
+    sqlx::query!("DELETE FROM oauth_link_tokens")
+        .execute(pool)
+        .await
+        .expect("Failed to clean oauth_link_tokens");
+
+    sqlx::query!("DELETE FROM impersonations")
+        .execute(pool)
+        .await
+        .expect("Failed to clean impersonations");
+
+    sqlx::query!("DELETE FROM consistency_check_runs")
+        .execute(pool)
+        .await
+        .expect("Failed to clean consistency_check_runs");
+
     sqlx::query!("DELETE FROM users")
         .execute(pool)
         .await
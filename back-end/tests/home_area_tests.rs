@@ -0,0 +1,235 @@
+// Integration tests for the home area dashboard
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rand::Rng;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+/// Helper to create a verified user and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Home User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn send(
+    app: &axum::Router,
+    method: &str,
+    token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri("/api/users/me/area")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, json)
+}
+
+async fn user_id(pool: &PgPool, email: &str) -> Uuid {
+    sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+/// A spot in the Southern Ocean no other test puts reports near
+fn remote_point() -> (f64, f64) {
+    let mut rng = rand::thread_rng();
+    (rng.gen_range(-55.0..-50.0), rng.gen_range(-140.0..-120.0))
+}
+
+async fn insert_report(
+    pool: &PgPool,
+    reporter: Uuid,
+    clearer: Option<Uuid>,
+    latitude: f64,
+    longitude: f64,
+) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO litter_reports
+            (reporter_id, location, status, claimed_by, claimed_at, cleared_by, cleared_at)
+         VALUES ($1, ST_SetSRID(ST_MakePoint($4, $3), 4326),
+                 (CASE WHEN $2::uuid IS NULL THEN 'pending' ELSE 'cleared' END)::report_status,
+                 $2, CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END,
+                 $2, CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END)
+         RETURNING id",
+    )
+    .bind(reporter)
+    .bind(clearer)
+    .bind(latitude)
+    .bind(longitude)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn award(pool: &PgPool, user_id: Uuid, report_id: Uuid, points: i32) {
+    sqlx::query(
+        "INSERT INTO score_events (user_id, points, kind, report_id) VALUES ($1, $2, 'clear', $3)",
+    )
+    .bind(user_id)
+    .bind(points)
+    .bind(report_id)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_dashboard_needs_a_home_area() {
+    let app = create_test_app().await;
+    let email = format!("home-unset-{}@example.com", Uuid::new_v4());
+    let token = create_verified_user_and_login(&app, &email).await;
+
+    let (status, _) = send(&app, "GET", &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, "DELETE", &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &token,
+        Some(json!({ "latitude": 95.0, "longitude": 0.0 })),
+    )
+    .await;
+    assert!(status.is_client_error());
+}
+
+#[tokio::test]
+async fn test_dashboard_summarises_the_area() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let email = format!("home-me-{}@example.com", Uuid::new_v4());
+    let rival_email = format!("home-rival-{}@example.com", Uuid::new_v4());
+    let token = create_verified_user_and_login(&app, &email).await;
+    create_verified_user_and_login(&app, &rival_email).await;
+    let me = user_id(&pool, &email).await;
+    let rival = user_id(&pool, &rival_email).await;
+    let (latitude, longitude) = remote_point();
+
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &token,
+        Some(json!({ "latitude": latitude, "longitude": longitude })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["open_reports"], 0);
+    assert_eq!(body["leaderboard"]["rank"], Value::Null);
+
+    // Two open reports within the default 5 km, one well outside it
+    insert_report(&pool, rival, None, latitude + 0.01, longitude).await;
+    insert_report(&pool, rival, None, latitude, longitude + 0.01).await;
+    insert_report(&pool, rival, None, latitude + 1.0, longitude).await;
+
+    let mine = insert_report(&pool, rival, Some(me), latitude - 0.01, longitude).await;
+    let theirs = insert_report(&pool, me, Some(rival), latitude, longitude - 0.01).await;
+    award(&pool, me, mine, 10).await;
+    award(&pool, rival, theirs, 25).await;
+
+    sqlx::query(
+        "INSERT INTO cleanup_events (organizer_id, title, meeting_point, starts_at, ends_at)
+         VALUES ($1, 'Beach sweep', ST_SetSRID(ST_MakePoint($3, $2), 4326),
+                 NOW() - INTERVAL '1 hour', NOW() + INTERVAL '1 hour')",
+    )
+    .bind(rival)
+    .bind(latitude + 0.02)
+    .bind(longitude)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, body) = send(&app, "GET", &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["radius_km"], 5);
+    assert_eq!(body["open_reports"], 2);
+    assert_eq!(body["recent_clears"].as_array().unwrap().len(), 2);
+    assert_eq!(body["leaderboard"]["rank"], 2);
+    assert_eq!(body["leaderboard"]["points"], 10);
+    assert_eq!(body["leaderboard"]["participants"], 2);
+
+    let zones = body["boost_zones"].as_array().unwrap();
+    assert_eq!(zones.len(), 1);
+    assert_eq!(zones[0]["title"], "Beach sweep");
+    assert_eq!(zones[0]["ongoing"], true);
+
+    let (status, _) = send(&app, "DELETE", &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, "GET", &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,127 @@
+// Integration tests for Idempotency-Key handling on mutating endpoints
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use back_end::idempotency::{IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER};
+use back_end::models::UserRole;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::{access_token, create_test_app, get_test_pool, insert_user};
+
+async fn create_user_token(pool: &PgPool, email: &str) -> String {
+    let user_id = insert_user(pool, email).await;
+    access_token(user_id, email, UserRole::User)
+}
+
+/// POST a feed post, returning the status, whether it was replayed, and the body
+async fn create_post(
+    app: &Router,
+    token: &str,
+    key: Option<&str>,
+    content: &str,
+) -> (StatusCode, bool, Value) {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri("/api/feed")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {token}"));
+    if let Some(key) = key {
+        request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            request
+                .body(Body::from(
+                    json!({ "content": content, "images": [] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let replayed = response.headers().contains_key(IDEMPOTENT_REPLAYED_HEADER);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        replayed,
+        serde_json::from_slice(&body).unwrap_or(Value::Null),
+    )
+}
+
+async fn post_count(pool: &PgPool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM feed_posts")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_retry_with_same_key_replays_the_original_response() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let token = create_user_token(&pool, "retrying@example.com").await;
+
+    let (status, replayed, first) = create_post(&app, &token, Some("post-1"), "Park cleaned").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+
+    let (status, replayed, second) =
+        create_post(&app, &token, Some("post-1"), "Park cleaned").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(replayed);
+    assert_eq!(second["id"], first["id"]);
+    assert_eq!(post_count(&pool).await, 1);
+
+    // Without a key every request is applied
+    create_post(&app, &token, None, "Park cleaned").await;
+    create_post(&app, &token, None, "Park cleaned").await;
+    assert_eq!(post_count(&pool).await, 3);
+}
+
+#[tokio::test]
+async fn test_reusing_a_key_for_a_different_request_is_rejected() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let token = create_user_token(&pool, "reuser@example.com").await;
+
+    let (status, _, _) = create_post(&app, &token, Some("post-1"), "First post").await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _, _) = create_post(&app, &token, Some("post-1"), "Second post").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(post_count(&pool).await, 1);
+
+    let (status, _, _) = create_post(&app, &token, Some("bad key"), "Second post").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_failed_requests_and_other_users_are_not_replayed() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let alice = create_user_token(&pool, "alice@example.com").await;
+    let bob = create_user_token(&pool, "bob@example.com").await;
+
+    // A validation failure releases the key, so a corrected retry goes through
+    let (status, replayed, _) = create_post(&app, &alice, Some("post-1"), "").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(!replayed);
+    let (status, _, _) = create_post(&app, &alice, Some("post-1"), "Fixed post").await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Keys are scoped to the user
+    let (status, replayed, _) = create_post(&app, &bob, Some("post-1"), "Fixed post").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(!replayed);
+    assert_eq!(post_count(&pool).await, 2);
+}
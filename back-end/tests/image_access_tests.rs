@@ -0,0 +1,212 @@
+// Tests for signed image URLs, hotlink protection and the per-IP limit on image routes
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use back_end::config::ImageAccessConfig;
+use back_end::image_access::{self, ImageAccess, ImageUrlSigner};
+use back_end::rate_limit::{self, RateLimiter};
+use chrono::{Duration, Utc};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const PHOTO_PATH: &str = "/api/images/reports/7f1c2f4e-8a3b-4c55-9d1e-2b6a0c9e5d11/before/0";
+
+fn access_config() -> ImageAccessConfig {
+    ImageAccessConfig {
+        signing_secret: None,
+        require_signed_urls: false,
+        signed_url_ttl_secs: 3600,
+        allowed_referers: Vec::new(),
+        allow_missing_referer: true,
+        access_log: false,
+    }
+}
+
+fn signed_config() -> ImageAccessConfig {
+    ImageAccessConfig {
+        signing_secret: Some("test-signing-secret".to_string()),
+        require_signed_urls: true,
+        ..access_config()
+    }
+}
+
+fn guarded_app(access: ImageAccess, limiter: RateLimiter) -> Router {
+    Router::new()
+        .route(
+            "/api/images/reports/:id/:kind/:position",
+            get(|| async { "image" }),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            limiter,
+            rate_limit::limit_by_ip,
+        ))
+        .route_layer(axum::middleware::from_fn_with_state(
+            Arc::new(access),
+            image_access::guard_images,
+        ))
+}
+
+async fn status_of(app: &Router, request: axum::http::request::Builder) -> StatusCode {
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[test]
+fn test_signed_url_verifies_only_for_its_path_until_it_expires() {
+    let signer = ImageUrlSigner::new("test-signing-secret", 60);
+    let now = Utc::now();
+    let signed = signer.sign(PHOTO_PATH, now);
+    assert_eq!(signed.expires_at, now + Duration::seconds(60));
+
+    let expires = signed.expires_at.timestamp();
+    let signature = signer.signature(PHOTO_PATH, expires);
+    assert_eq!(
+        signed.url,
+        format!("{PHOTO_PATH}?expires={expires}&signature={signature}")
+    );
+
+    assert!(signer.verify(PHOTO_PATH, expires, &signature, now));
+    assert!(!signer.verify(
+        "/api/images/reports/7f1c2f4e-8a3b-4c55-9d1e-2b6a0c9e5d11/before/1",
+        expires,
+        &signature,
+        now
+    ));
+    assert!(!signer.verify(PHOTO_PATH, expires + 60, &signature, now));
+    assert!(!signer.verify(PHOTO_PATH, expires, &signature, now + Duration::seconds(61)));
+    assert!(!signer.verify(PHOTO_PATH, expires, "not base64!", now));
+
+    let other_key = ImageUrlSigner::new("another-secret", 60);
+    assert!(!other_key.verify(PHOTO_PATH, expires, &signature, now));
+}
+
+#[test]
+fn test_referer_check_allows_listed_hosts_and_their_subdomains() {
+    let open = ImageAccess::new(&access_config(), false);
+    assert!(open.referer_allowed(Some("https://elsewhere.example/page")));
+    assert!(open.referer_allowed(None));
+
+    let config = ImageAccessConfig {
+        allowed_referers: vec!["littypicky.app".to_string()],
+        ..access_config()
+    };
+    let access = ImageAccess::new(&config, false);
+    assert!(access.referer_allowed(Some("https://littypicky.app/reports/1")));
+    assert!(access.referer_allowed(Some("https://www.LittyPicky.app:8443/")));
+    assert!(!access.referer_allowed(Some("https://notlittypicky.app/")));
+    assert!(!access.referer_allowed(Some("https://littypicky.app.evil.example/")));
+    assert!(!access.referer_allowed(Some("not a url")));
+    assert!(access.referer_allowed(None));
+
+    let strict = ImageAccess::new(
+        &ImageAccessConfig {
+            allow_missing_referer: false,
+            ..config
+        },
+        false,
+    );
+    assert!(!strict.referer_allowed(None));
+}
+
+#[tokio::test]
+async fn test_hotlinked_requests_are_refused() {
+    let config = ImageAccessConfig {
+        allowed_referers: vec!["littypicky.app".to_string()],
+        ..access_config()
+    };
+    let app = guarded_app(
+        ImageAccess::new(&config, false),
+        RateLimiter::per_minute("images", 100, false),
+    );
+
+    let from = |referer: &str| {
+        Request::builder()
+            .uri(PHOTO_PATH)
+            .header(header::REFERER, referer)
+    };
+    assert_eq!(
+        status_of(&app, from("https://littypicky.app/map")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(&app, from("https://scraper.example/gallery")).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn test_signed_urls_are_required_when_configured() {
+    let access = ImageAccess::new(&signed_config(), false);
+    let signed = access.signer().unwrap().sign(PHOTO_PATH, Utc::now());
+    let app = guarded_app(access, RateLimiter::per_minute("images", 100, false));
+
+    assert_eq!(
+        status_of(&app, Request::builder().uri(PHOTO_PATH)).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status_of(&app, Request::builder().uri(signed.url.as_str())).await,
+        StatusCode::OK
+    );
+
+    // The signature does not carry over to another photo
+    let moved = signed.url.replace("/before/0", "/before/1");
+    assert_eq!(
+        status_of(&app, Request::builder().uri(moved.as_str())).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn test_unsigned_urls_pass_when_signing_is_optional() {
+    let config = ImageAccessConfig {
+        require_signed_urls: false,
+        ..signed_config()
+    };
+    let app = guarded_app(
+        ImageAccess::new(&config, false),
+        RateLimiter::per_minute("images", 100, false),
+    );
+
+    assert_eq!(
+        status_of(&app, Request::builder().uri(PHOTO_PATH)).await,
+        StatusCode::OK
+    );
+    // A link that claims a signature must still be valid
+    let forged = format!("{PHOTO_PATH}?expires=4102444800&signature=forged");
+    assert_eq!(
+        status_of(&app, Request::builder().uri(forged.as_str())).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn test_image_requests_are_limited_per_ip() {
+    let app = guarded_app(
+        ImageAccess::new(&access_config(), false),
+        RateLimiter::per_minute("images", 2, false),
+    );
+    let from = |addr: &str| {
+        let addr: SocketAddr = addr.parse().unwrap();
+        Request::builder()
+            .uri(PHOTO_PATH)
+            .extension(ConnectInfo(addr))
+    };
+
+    assert_eq!(status_of(&app, from("10.0.0.1:5000")).await, StatusCode::OK);
+    assert_eq!(status_of(&app, from("10.0.0.1:5001")).await, StatusCode::OK);
+    assert_eq!(
+        status_of(&app, from("10.0.0.1:5002")).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(status_of(&app, from("10.0.0.2:5000")).await, StatusCode::OK);
+}
@@ -0,0 +1,167 @@
+// Integration tests for admin impersonation
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::auth::JwtService;
+use back_end::config::Config;
+use back_end::error::AppError;
+use back_end::models::{PageParams, UserRole};
+use back_end::services::ImpersonationService;
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, insert_user, insert_user_with_role, setup_test_db};
+
+fn impersonation_service(pool: &PgPool) -> ImpersonationService {
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().unwrap();
+    ImpersonationService::new(pool.clone(), JwtService::new(config.jwt).unwrap())
+}
+
+#[tokio::test]
+async fn test_impersonation_token_acts_as_user_and_is_audited() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin = insert_user_with_role(&pool, "support@example.com", UserRole::Admin).await;
+    let user = insert_user(&pool, "stuck-user@example.com").await;
+
+    let service = impersonation_service(&pool);
+    let response = service
+        .impersonate(admin, user, "  Ticket #12: map is empty ")
+        .await
+        .unwrap();
+    assert_eq!(response.expires_in, 900);
+    assert_eq!(response.impersonation.admin_email, "support@example.com");
+    assert_eq!(response.impersonation.user_email, "stuck-user@example.com");
+    assert_eq!(response.impersonation.reason, "Ticket #12: map is empty");
+
+    let claims = JwtService::new(Config::from_env().unwrap().jwt)
+        .unwrap()
+        .verify_token(&response.access_token)
+        .unwrap();
+    assert_eq!(claims.sub, user.to_string());
+    assert_eq!(claims.role, "user");
+    assert_eq!(claims.impersonated_by, Some(admin));
+    assert!(claims.sid.is_none());
+
+    let me = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/users/me")
+                .header("authorization", format!("Bearer {}", response.access_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(me.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(me.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let me: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(me["email"], "stuck-user@example.com");
+
+    let audit = service
+        .list(Some(user), &PageParams::default())
+        .await
+        .unwrap();
+    assert_eq!(audit.total, 1);
+    assert_eq!(audit.items[0].admin_id, Some(admin));
+}
+
+#[tokio::test]
+async fn test_admins_and_self_cannot_be_impersonated() {
+    let pool = setup_test_db().await;
+    let admin = insert_user_with_role(&pool, "support-a@example.com", UserRole::Admin).await;
+    let other_admin = insert_user_with_role(&pool, "support-b@example.com", UserRole::Admin).await;
+    let moderator =
+        insert_user_with_role(&pool, "support-mod@example.com", UserRole::Moderator).await;
+
+    let service = impersonation_service(&pool);
+    assert!(matches!(
+        service.impersonate(admin, other_admin, "Curious").await,
+        Err(AppError::Forbidden(_))
+    ));
+    assert!(matches!(
+        service.impersonate(admin, moderator, "Curious").await,
+        Err(AppError::Forbidden(_))
+    ));
+    assert!(matches!(
+        service.impersonate(admin, admin, "Curious").await,
+        Err(AppError::BadRequest(_))
+    ));
+    assert!(matches!(
+        service.impersonate(admin, Uuid::new_v4(), "Curious").await,
+        Err(AppError::NotFound(_))
+    ));
+
+    let audit = service.list(None, &PageParams::default()).await.unwrap();
+    assert_eq!(audit.total, 0);
+}
+
+#[tokio::test]
+async fn test_impersonation_token_cannot_touch_the_account() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin = insert_user_with_role(&pool, "support-guard@example.com", UserRole::Admin).await;
+    let user = insert_user(&pool, "guarded-user@example.com").await;
+    let token = impersonation_service(&pool)
+        .impersonate(admin, user, "Ticket #13")
+        .await
+        .unwrap()
+        .access_token;
+
+    let session = Uuid::new_v4();
+    let export = Uuid::new_v4();
+    let endpoints = [
+        ("PATCH", "/api/users/me".to_string()),
+        ("POST", "/api/auth/logout-all".to_string()),
+        ("POST", "/api/auth/service-tokens".to_string()),
+        ("GET", "/api/users/me/sessions".to_string()),
+        ("GET", "/api/users/me/logins".to_string()),
+        ("DELETE", format!("/api/users/me/sessions/{session}")),
+        ("DELETE", "/api/users/me/oauth/google".to_string()),
+        ("PUT", "/api/users/me/password".to_string()),
+        ("POST", "/api/users/me/password/setup".to_string()),
+        ("POST", "/api/users/me/email-change".to_string()),
+        ("GET", "/api/auth/2fa".to_string()),
+        ("POST", "/api/auth/2fa/setup".to_string()),
+        ("POST", "/api/auth/2fa/verify".to_string()),
+        ("POST", "/api/auth/2fa/disable".to_string()),
+        ("POST", "/api/users/me/export".to_string()),
+        ("GET", "/api/users/me/export".to_string()),
+        ("GET", format!("/api/users/me/export/{export}/download")),
+    ];
+
+    for (method, uri) in endpoints {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(&uri)
+                    .header("authorization", format!("Bearer {token}"))
+                    .header("content-type", "application/json")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{method} {uri}");
+    }
+
+    // Nothing reached the account
+    let revoked: bool =
+        sqlx::query_scalar("SELECT tokens_invalidated_at IS NOT NULL FROM users WHERE id = $1")
+            .bind(user)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(!revoked);
+}
@@ -0,0 +1,130 @@
+// Tests for running scheduled jobs once per tick across instances
+
+use back_end::error::AppError;
+use back_end::jobs::JobLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, setup_test_db};
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// A job name no other test run has used, so `job_runs` starts empty for it
+fn job_name() -> String {
+    format!("test_job_{}", Uuid::new_v4())
+}
+
+#[tokio::test]
+async fn test_job_runs_once_per_interval() {
+    let pool = setup_test_db().await;
+    // Two locks on separate pools stand in for two instances
+    let first = JobLock::new(pool.clone());
+    let second = JobLock::new(get_test_pool().await);
+    let name = job_name();
+
+    let ran = first
+        .run_exclusive(&name, HOUR, async { Ok::<_, AppError>(1) })
+        .await
+        .unwrap();
+    assert_eq!(ran, Some(1));
+
+    let ran = second
+        .run_exclusive(&name, HOUR, async { Ok::<_, AppError>(2) })
+        .await
+        .unwrap();
+    assert_eq!(ran, None, "the same tick ran again on another instance");
+
+    let finished: Option<chrono::DateTime<chrono::Utc>> =
+        sqlx::query_scalar("SELECT last_finished_at FROM job_runs WHERE name = $1")
+            .bind(&name)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(finished.is_some());
+}
+
+#[tokio::test]
+async fn test_job_runs_again_after_interval() {
+    let pool = setup_test_db().await;
+    let lock = JobLock::new(pool.clone());
+    let name = job_name();
+
+    lock.run_exclusive(&name, HOUR, async { Ok::<_, AppError>(()) })
+        .await
+        .unwrap();
+    sqlx::query("UPDATE job_runs SET last_started_at = NOW() - INTERVAL '2 hours' WHERE name = $1")
+        .bind(&name)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let ran = lock
+        .run_exclusive(&name, HOUR, async { Ok::<_, AppError>(()) })
+        .await
+        .unwrap();
+    assert_eq!(ran, Some(()));
+}
+
+#[tokio::test]
+async fn test_running_job_is_skipped_elsewhere() {
+    let _app = create_test_app().await;
+    let first = JobLock::new(get_test_pool().await);
+    let second = JobLock::new(get_test_pool().await);
+    let name = job_name();
+    let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+    let (finish_tx, finish_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let running = {
+        let name = name.clone();
+        tokio::spawn(async move {
+            first
+                .run_exclusive(&name, Duration::ZERO, async move {
+                    started_tx.send(()).unwrap();
+                    finish_rx.await.unwrap();
+                    Ok::<_, AppError>(())
+                })
+                .await
+        })
+    };
+    started_rx.await.unwrap();
+
+    // A zero interval would let the run through if the lock did not stop it
+    let ran = second
+        .run_exclusive(&name, Duration::ZERO, async { Ok::<_, AppError>(()) })
+        .await
+        .unwrap();
+    assert_eq!(ran, None);
+
+    finish_tx.send(()).unwrap();
+    assert_eq!(running.await.unwrap().unwrap(), Some(()));
+
+    // The lock is released once the run ends
+    let ran = second
+        .run_exclusive(&name, Duration::ZERO, async { Ok::<_, AppError>(()) })
+        .await
+        .unwrap();
+    assert_eq!(ran, Some(()));
+}
+
+#[tokio::test]
+async fn test_failed_run_is_recorded() {
+    let pool = setup_test_db().await;
+    let lock = JobLock::new(pool.clone());
+    let name = job_name();
+
+    let result = lock
+        .run_exclusive(&name, HOUR, async {
+            Err::<(), _>(AppError::Internal(anyhow::anyhow!("boom")))
+        })
+        .await;
+    assert!(result.is_err());
+
+    let error: Option<String> =
+        sqlx::query_scalar("SELECT last_error FROM job_runs WHERE name = $1")
+            .bind(&name)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(error.unwrap().contains("boom"));
+}
@@ -0,0 +1,155 @@
+// Tests for per-route latency budgets and the slow-request report
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use back_end::config::{self, LatencyConfig};
+use back_end::db;
+use back_end::perf::{self, LatencyMonitor};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+fn monitor(default_budget_ms: u64, routes: &[(&str, u64)]) -> LatencyMonitor {
+    LatencyMonitor::new(LatencyConfig {
+        default_budget_ms,
+        route_budgets_ms: routes
+            .iter()
+            .map(|(route, ms)| ((*route).to_string(), *ms))
+            .collect::<HashMap<_, _>>(),
+    })
+}
+
+async fn sleepy() -> &'static str {
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    "done"
+}
+
+async fn send(app: &Router, uri: &str) -> StatusCode {
+    app.clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_only_routes_over_budget_are_reported() {
+    let monitor = monitor(10, &[("GET /relaxed/:id", 5_000)]);
+    let app = Router::new()
+        .route("/tight/:id", get(sleepy))
+        .route("/relaxed/:id", get(sleepy))
+        .layer(axum::middleware::from_fn_with_state(
+            monitor.clone(),
+            perf::track_latency,
+        ));
+
+    assert_eq!(send(&app, "/tight/1").await, StatusCode::OK);
+    assert_eq!(send(&app, "/tight/2").await, StatusCode::OK);
+    assert_eq!(send(&app, "/relaxed/1").await, StatusCode::OK);
+    assert_eq!(send(&app, "/missing").await, StatusCode::NOT_FOUND);
+
+    let report = monitor.report(20);
+    assert_eq!(report.len(), 1);
+    // Requests are keyed by route pattern, not the concrete path
+    assert_eq!(report[0].route, "GET /tight/:id");
+    assert_eq!(report[0].budget_ms, 10);
+    assert_eq!(report[0].requests, 2);
+    assert_eq!(report[0].violations, 2);
+    assert!(report[0].worst_ms >= 30.0);
+    assert!(report[0].last_violation_at.is_some());
+}
+
+#[test]
+fn test_report_ranks_by_violations_and_honours_limit() {
+    let monitor = monitor(100, &[]);
+    for _ in 0..3 {
+        assert!(monitor.record("GET /a", 150.0, &[]));
+    }
+    assert!(monitor.record("GET /b", 900.0, &[]));
+    assert!(!monitor.record("GET /c", 50.0, &[]));
+
+    let routes: Vec<_> = monitor
+        .report(20)
+        .into_iter()
+        .map(|route| route.route)
+        .collect();
+    assert_eq!(routes, ["GET /a", "GET /b"]);
+    assert_eq!(monitor.report(1).len(), 1);
+}
+
+#[tokio::test]
+async fn test_dropped_request_is_reported_as_cancelled() {
+    let monitor = monitor(5_000, &[]);
+    let app =
+        Router::new()
+            .route("/slow", get(sleepy))
+            .layer(axum::middleware::from_fn_with_state(
+                monitor.clone(),
+                perf::track_latency,
+            ));
+
+    // A client that gives up drops the request before the handler finishes
+    assert!(
+        tokio::time::timeout(Duration::from_millis(5), send(&app, "/slow"))
+            .await
+            .is_err()
+    );
+
+    let report = monitor.report(20);
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].route, "GET /slow");
+    assert_eq!(report[0].requests, 1);
+    assert_eq!(report[0].cancelled, 1);
+    assert_eq!(report[0].violations, 0);
+}
+
+async fn test_pool() -> PgPool {
+    dotenvy::from_filename(".env.test").ok();
+    let config = config::Config::from_env().expect("Failed to load config");
+    db::create_pool(&config)
+        .await
+        .expect("Failed to create pool")
+}
+
+async fn run_queries(State(pool): State<PgPool>) -> &'static str {
+    for _ in 0..2 {
+        sqlx::query("SELECT pg_sleep(0.02)")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("SELECT 1").execute(&pool).await.unwrap();
+    "done"
+}
+
+#[tokio::test]
+async fn test_violation_records_sql_breakdown() {
+    // The test runtime is single-threaded, so a thread-local subscriber sees every statement
+    let _guard = tracing::subscriber::set_default(
+        tracing_subscriber::registry().with(perf::SqlTimingLayer::filtered()),
+    );
+
+    let monitor = monitor(0, &[]);
+    let app = Router::new()
+        .route("/queries", get(run_queries))
+        .with_state(test_pool().await)
+        .layer(axum::middleware::from_fn_with_state(
+            monitor.clone(),
+            perf::track_latency,
+        ));
+    assert_eq!(send(&app, "/queries").await, StatusCode::OK);
+
+    let report = monitor.report(20);
+    let sql = &report[0].worst_sql;
+    assert_eq!(sql[0].statement, "SELECT pg_sleep(0.02)");
+    assert_eq!(sql[0].calls, 2);
+    assert!(sql[0].total_ms >= 40.0);
+    assert!(sql.iter().any(|span| span.statement == "SELECT 1"));
+}
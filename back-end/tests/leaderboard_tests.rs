@@ -0,0 +1,94 @@
+// Tests for leaderboard periods: calendar-aligned weeks and months with their bounds
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{DateTime, Datelike, Duration, Months, Timelike, Utc, Weekday};
+use serde_json::Value;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+async fn get(app: &axum::Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn bound(board: &Value, field: &str) -> DateTime<Utc> {
+    board[field].as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_leaderboard_periods_follow_the_calendar() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    // Points from just before this week began, which a rolling seven days would count
+    let city = format!("Boardton-{}", &Uuid::new_v4().to_string()[..8]);
+    let user_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO users (email, password_hash, full_name, city, country, email_verified)
+         VALUES ($1, 'not-a-real-hash', 'Board User', $2, 'UK', true)
+         RETURNING id",
+    )
+    .bind(format!("board-{}@example.com", Uuid::new_v4()))
+    .bind(&city)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO score_events (user_id, points, kind, created_at)
+         VALUES ($1, 10, 'clear', date_trunc('week', NOW() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+                                  - INTERVAL '1 minute')",
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // LEADERBOARD_TIMEZONE is UTC in .env.test
+    let (status, board) = get(
+        &app,
+        &format!("/api/leaderboards/city/{city}?period=weekly"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(board["period"], "weekly");
+    assert_eq!(board["timezone"], "UTC");
+    assert_eq!(board["total"], 0);
+    let start = bound(&board, "period_start");
+    assert_eq!(start.weekday(), Weekday::Mon);
+    assert_eq!((start.hour(), start.minute(), start.second()), (0, 0, 0));
+    assert!(start <= Utc::now());
+    assert_eq!(bound(&board, "period_end"), start + Duration::weeks(1));
+
+    let (_, board) = get(
+        &app,
+        &format!("/api/leaderboards/city/{city}?period=monthly"),
+    )
+    .await;
+    let start = bound(&board, "period_start");
+    assert_eq!((start.day(), start.hour()), (1, 0));
+    assert_eq!(
+        bound(&board, "period_end"),
+        start.checked_add_months(Months::new(1)).unwrap()
+    );
+
+    let (_, board) = get(&app, &format!("/api/leaderboards/city/{city}")).await;
+    assert_eq!(board["period"], "all_time");
+    assert!(board["period_start"].is_null());
+    assert!(board["period_end"].is_null());
+
+    let (status, _) = get(&app, "/api/leaderboards?period=yearly").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,96 @@
+// Tests for the migration policy that keeps destructive schema changes out of startup
+
+use back_end::migration_policy::{
+    blocked, is_destructive, looks_destructive, StartupMode, LAST_UNCHECKED_MIGRATION,
+};
+use sqlx::migrate::{Migration, MigrationType};
+use std::borrow::Cow;
+
+fn migration(version: i64, sql: &'static str) -> Migration {
+    Migration::new(
+        version,
+        Cow::Borrowed("test"),
+        MigrationType::Simple,
+        Cow::Borrowed(sql),
+    )
+}
+
+#[test]
+fn test_startup_mode_from_args() {
+    let serve = |skip_migrations, allow_destructive| StartupMode::Serve {
+        skip_migrations,
+        allow_destructive,
+    };
+
+    assert_eq!(
+        StartupMode::from_args(Vec::<String>::new()),
+        Ok(serve(false, false))
+    );
+    assert_eq!(
+        StartupMode::from_args(["--skip-migrations"]),
+        Ok(serve(true, false))
+    );
+    assert_eq!(
+        StartupMode::from_args(["migrate", "--allow-destructive-migrations"]),
+        Ok(StartupMode::Migrate {
+            allow_destructive: true
+        })
+    );
+    assert!(StartupMode::from_args(["migrate", "--skip-migrations"]).is_err());
+    assert!(StartupMode::from_args(["--skip-migrations", "migrate"]).is_err());
+    assert!(StartupMode::from_args(["--migrate"]).is_err());
+}
+
+#[test]
+fn test_only_marked_migrations_are_blocked() {
+    let additive = migration(70, "ALTER TABLE users ADD COLUMN nickname TEXT;");
+    let destructive = migration(
+        71,
+        "-- migration: destructive\nALTER TABLE users DROP COLUMN city;",
+    );
+    let pending = [&additive, &destructive];
+
+    let versions = |migrations: Vec<&Migration>| -> Vec<i64> {
+        migrations.iter().map(|m| m.version).collect()
+    };
+    assert_eq!(versions(blocked(&pending, false, false)), [71]);
+    assert!(blocked(&pending, false, true).is_empty());
+    // Nothing runs against a new database yet, so everything may be applied
+    assert!(blocked(&pending, true, false).is_empty());
+}
+
+#[test]
+fn test_destructive_sql_is_recognised() {
+    assert!(looks_destructive("ALTER TABLE users DROP COLUMN city;"));
+    assert!(looks_destructive(
+        "alter table litter_reports alter column photo_before type varchar(1024);"
+    ));
+    assert!(looks_destructive(
+        "ALTER TABLE users ALTER COLUMN city SET DATA TYPE TEXT;"
+    ));
+    assert!(looks_destructive(
+        "ALTER TABLE users RENAME COLUMN city TO town;"
+    ));
+    assert!(!looks_destructive(
+        "-- We never DROP COLUMN here\nALTER TABLE users ADD COLUMN town TEXT;"
+    ));
+    assert!(!looks_destructive(
+        "CREATE INDEX idx_users_city ON users(city);"
+    ));
+}
+
+#[test]
+fn test_new_destructive_migrations_carry_the_marker() {
+    for migration in sqlx::migrate!("./migrations").iter() {
+        if migration.version <= LAST_UNCHECKED_MIGRATION {
+            continue;
+        }
+        assert!(
+            !looks_destructive(&migration.sql) || is_destructive(&migration.sql),
+            "Migration {} {} drops or changes existing schema; mark it with \
+             `-- migration: destructive`",
+            migration.version,
+            migration.description
+        );
+    }
+}
@@ -0,0 +1,296 @@
+// Integration tests for long-poll notification delivery
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, insert_user, setup_test_db};
+
+/// Helper to create a verified user in an existing app and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn create_test_report(app: &axum::Router, token: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/reports")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({
+                        "latitude": 51.5074,
+                        "longitude": -0.1278,
+                        "description": "Test litter",
+                        "photo_base64": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    report["id"].as_str().unwrap().to_string()
+}
+
+async fn poll(app: &axum::Router, token: &str, query: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/api/notifications/poll?{query}"))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn since_now() -> String {
+    Utc::now().to_rfc3339().replace('+', "%2B")
+}
+
+#[tokio::test]
+async fn test_poll_times_out_with_no_notifications() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "quiet-poller@example.com").await;
+
+    let started = Instant::now();
+    let (status, body) = poll(
+        &app,
+        &token,
+        &format!("since={}&timeout_secs=1", since_now()),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(body["notifications"].as_array().unwrap().len(), 0);
+    assert!(body["next_since"].is_string());
+}
+
+#[tokio::test]
+async fn test_poll_wakes_when_report_is_claimed() {
+    let app = create_test_app().await;
+    let reporter_token = create_verified_user_and_login(&app, "poll-reporter@example.com").await;
+    let claimer_token = create_verified_user_and_login(&app, "poll-claimer@example.com").await;
+    let report_id = create_test_report(&app, &reporter_token).await;
+
+    let query = format!("since={}&timeout_secs=10", since_now());
+    let waiting = {
+        let app = app.clone();
+        let token = reporter_token.clone();
+        tokio::spawn(async move { poll(&app, &token, &query).await })
+    };
+
+    // Give the poll a moment to start waiting before triggering the notification
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/reports/{}/claim", report_id))
+                .header("authorization", format!("Bearer {}", claimer_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (status, body) = tokio::time::timeout(Duration::from_secs(5), waiting)
+        .await
+        .expect("poll was not woken by the new notification")
+        .unwrap();
+
+    assert_eq!(status, StatusCode::OK);
+    let notifications = body["notifications"].as_array().unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0]["kind"], "report_claimed");
+    assert_eq!(notifications[0]["report_id"], report_id.as_str());
+    assert_eq!(body["next_since"], notifications[0]["created_at"]);
+}
+
+#[tokio::test]
+async fn test_poll_requires_auth() {
+    let app = create_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/notifications/poll")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+async fn insert_user_with_clears(pool: &sqlx::PgPool, email: &str, total_clears: i32) -> Uuid {
+    let user_id = insert_user(pool, email).await;
+
+    sqlx::query("INSERT INTO user_scores (user_id, total_clears) VALUES ($1, $2)")
+        .bind(user_id)
+        .bind(total_clears)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    user_id
+}
+
+async fn insert_cleared_report(
+    pool: &sqlx::PgPool,
+    reporter: Uuid,
+    clearer: Uuid,
+    longitude: f64,
+    latitude: f64,
+) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO litter_reports
+            (reporter_id, location, status, claimed_by, claimed_at, cleared_by, cleared_at)
+         VALUES ($1, ST_SetSRID(ST_MakePoint($3, $4), 4326), 'cleared', $2, NOW(), $2, NOW())
+         RETURNING id",
+    )
+    .bind(reporter)
+    .bind(clearer)
+    .bind(longitude)
+    .bind(latitude)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn reminders_for(pool: &sqlx::PgPool, user_id: Uuid) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND kind = 'verification_reminder'",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_verification_reminders_reach_nearby_verifiers_once_within_daily_cap() {
+    let pool = setup_test_db().await;
+
+    let verifier = insert_user_with_clears(&pool, "reminder-verifier@example.com", 10).await;
+    let novice = insert_user_with_clears(&pool, "reminder-novice@example.com", 1).await;
+    let far_verifier = insert_user_with_clears(&pool, "reminder-far@example.com", 10).await;
+    let reporter = insert_user_with_clears(&pool, "reminder-reporter@example.com", 0).await;
+    let clearer = insert_user_with_clears(&pool, "reminder-clearer@example.com", 10).await;
+
+    // Where each verifier has been picking recently
+    insert_cleared_report(&pool, reporter, verifier, -0.1278, 51.5074).await;
+    insert_cleared_report(&pool, reporter, novice, -0.1278, 51.5074).await;
+    insert_cleared_report(&pool, reporter, far_verifier, 2.3522, 48.8566).await;
+
+    // Clears awaiting verification around the first spot, more than the daily cap
+    for _ in 0..4 {
+        insert_cleared_report(&pool, reporter, clearer, -0.1280, 51.5076).await;
+    }
+
+    let job = back_end::jobs::VerificationReminderJob::new(
+        pool.clone(),
+        back_end::services::NotificationService::new(pool.clone()),
+        5,
+        3,
+    );
+    job.run().await.unwrap();
+
+    assert_eq!(reminders_for(&pool, verifier).await, 3);
+    // Not enough clears to verify
+    assert_eq!(reminders_for(&pool, novice).await, 0);
+    // Picks in another city
+    assert_eq!(reminders_for(&pool, far_verifier).await, 0);
+
+    // The cap holds for the rest of the day
+    job.run().await.unwrap();
+    assert_eq!(reminders_for(&pool, verifier).await, 3);
+}
@@ -0,0 +1,49 @@
+// Integration tests for the OAuth state stores
+
+use back_end::services::{InMemoryOAuthStateStore, OAuthStateStore, PgOAuthStateStore};
+
+mod helpers;
+use helpers::setup_test_db;
+
+async fn assert_single_use(store: &dyn OAuthStateStore, state: &str) {
+    store.insert(state, "nonce-value").await.unwrap();
+
+    assert_eq!(
+        store.take(state).await.unwrap().as_deref(),
+        Some("nonce-value")
+    );
+    // States are consumed by the callback
+    assert_eq!(store.take(state).await.unwrap(), None);
+    assert_eq!(store.take("never-issued").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_in_memory_state_store_is_single_use() {
+    let store = InMemoryOAuthStateStore::new();
+    assert_single_use(&store, "csrf-in-memory").await;
+}
+
+#[tokio::test]
+async fn test_postgres_state_store_is_single_use() {
+    let pool = setup_test_db().await;
+    let store = PgOAuthStateStore::new(pool);
+    assert_single_use(&store, "csrf-postgres").await;
+}
+
+#[tokio::test]
+async fn test_postgres_state_store_ignores_expired_states() {
+    let pool = setup_test_db().await;
+    let store = PgOAuthStateStore::new(pool.clone());
+    store.insert("csrf-expired", "nonce-value").await.unwrap();
+
+    sqlx::query(
+        "UPDATE oauth_states SET expires_at = NOW() - INTERVAL '1 second'
+         WHERE state_hash = $1",
+    )
+    .bind(back_end::auth::hash_token("csrf-expired"))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(store.take("csrf-expired").await.unwrap(), None);
+}
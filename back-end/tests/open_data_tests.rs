@@ -0,0 +1,126 @@
+// Tests for the public open-data export of cleared reports
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use back_end::models::{LitterCategory, LitterSeverity, OpenDataReport, ReportStatus};
+use chrono::{NaiveDate, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, insert_user};
+
+async fn create_report(pool: &PgPool, reporter_id: Uuid, status: &str, cleared_by: Option<Uuid>) {
+    sqlx::query(
+        r#"
+        INSERT INTO litter_reports
+            (reporter_id, location, description, photo_before, status, claimed_by,
+             claimed_at, cleared_by, cleared_at, photo_after, address_city, created_at)
+        VALUES ($1, ST_SetSRID(ST_MakePoint(-0.12783, 51.50741), 4326),
+                'Outside number 12', 'before.webp', $2::report_status, $3,
+                CASE WHEN $3::uuid IS NULL THEN NULL ELSE NOW() END,
+                $3, CASE WHEN $3::uuid IS NULL THEN NULL ELSE NOW() END,
+                CASE WHEN $3::uuid IS NULL THEN NULL ELSE 'after.webp' END,
+                'London', NOW() - INTERVAL '5 hours')
+        "#,
+    )
+    .bind(reporter_id)
+    .bind(status)
+    .bind(cleared_by)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, header::HeaderMap, String) {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_open_data_lists_anonymized_clears() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    let reporter = insert_user(&pool, "open-data-reporter@example.com").await;
+    let picker = insert_user(&pool, "open-data-picker@example.com").await;
+    create_report(&pool, reporter, "pending", None).await;
+    create_report(&pool, reporter, "cleared", Some(picker)).await;
+    create_report(&pool, reporter, "verified", Some(picker)).await;
+    create_report(&pool, reporter, "disputed", Some(picker)).await;
+
+    // No account needed
+    let (status, headers, body) = get(&app, "/api/open-data/reports").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CACHE_CONTROL], "public, max-age=300");
+    let body: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["to"], Utc::now().date_naive().to_string());
+
+    let report = &body["reports"][0];
+    assert_eq!(report["latitude"], 51.51);
+    assert_eq!(report["longitude"], -0.13);
+    assert_eq!(report["city"], "London");
+    assert_eq!(report["hours_to_clear"], 5);
+    for field in [
+        "id",
+        "reporter_id",
+        "cleared_by",
+        "description",
+        "photo_before",
+    ] {
+        assert!(report.get(field).is_none(), "open data leaks {field}");
+    }
+
+    let daily = body["daily"].as_array().unwrap();
+    assert_eq!(daily.len(), 1);
+    assert_eq!(daily[0]["cleared_reports"], 2);
+    assert_eq!(daily[0]["verified_reports"], 1);
+
+    let (status, headers, csv) = get(&app, "/api/open-data/reports?format=csv").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "text/csv; charset=utf-8");
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], OpenDataReport::CSV_HEADER);
+    assert_eq!(lines.len(), 3);
+
+    let (status, _, _) = get(&app, "/api/open-data/reports?from=2024-05-02&to=2024-05-01").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, _) = get(&app, "/api/open-data/reports?from=2023-01-01&to=2024-05-01").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_open_data_csv_quotes_free_text() {
+    let report = OpenDataReport {
+        status: ReportStatus::Verified,
+        category: LitterCategory::default(),
+        severity: LitterSeverity::default(),
+        latitude: 51.51,
+        longitude: -0.13,
+        city: Some("=HYPERLINK(\"x\")".to_string()),
+        country: Some("Bosnia, Herzegovina".to_string()),
+        reported_on: NaiveDate::from_ymd_opt(2024, 4, 2).unwrap(),
+        cleared_on: NaiveDate::from_ymd_opt(2024, 4, 3).unwrap(),
+        hours_to_clear: 26,
+    };
+
+    let row = report.csv_row();
+    assert!(row.starts_with("verified,"));
+    assert!(row.contains(",\"'=HYPERLINK(\"\"x\"\")\",\"Bosnia, Herzegovina\","));
+    assert!(row.ends_with(",2024-04-02,2024-04-03,26"));
+}
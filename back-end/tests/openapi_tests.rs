@@ -0,0 +1,68 @@
+// Tests for the shape of the served OpenAPI spec
+
+use back_end::{openapi, ApiDoc};
+use std::collections::HashSet;
+use utoipa::OpenApi;
+
+#[test]
+fn test_every_operation_has_a_unique_id_and_a_grouped_tag() {
+    let spec = openapi::to_json(&ApiDoc::openapi());
+
+    let grouped: HashSet<&str> = openapi::TAG_GROUPS
+        .iter()
+        .flat_map(|(_, tags)| tags.iter().copied())
+        .collect();
+    let declared: HashSet<&str> = spec["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(grouped, declared);
+
+    let mut operation_ids = HashSet::new();
+    for (path, item) in spec["paths"].as_object().unwrap() {
+        for (method, operation) in item.as_object().unwrap() {
+            let operation_id = operation["operationId"]
+                .as_str()
+                .unwrap_or_else(|| panic!("{method} {path} has no operationId"));
+            assert!(
+                operation_ids.insert(operation_id.to_string()),
+                "duplicate operationId {operation_id}"
+            );
+            // Generated clients turn these into method names
+            assert!(
+                operation_id.chars().all(char::is_alphanumeric)
+                    && operation_id.starts_with(|c: char| c.is_ascii_lowercase()),
+                "{operation_id} is not camelCase"
+            );
+
+            let tags = operation["tags"].as_array().unwrap();
+            assert_eq!(tags.len(), 1, "{method} {path} should have one tag");
+            assert!(grouped.contains(tags[0].as_str().unwrap()));
+        }
+    }
+}
+
+#[test]
+fn test_redoc_page_points_at_the_spec() {
+    let html = openapi::redoc_html("/api/openapi.json");
+    assert!(html.contains(r#"<redoc spec-url="/api/openapi.json">"#));
+}
+
+#[test]
+fn test_swagger_auth_link_is_added_to_the_spec() {
+    let mut spec = openapi::to_json(&ApiDoc::openapi());
+    assert!(spec.get("x-swagger-auth").is_none());
+
+    openapi::add_swagger_auth_link(&mut spec, "/swagger-auth");
+    assert_eq!(spec["x-swagger-auth"], "/swagger-auth");
+    assert!(spec["info"]["description"]
+        .as_str()
+        .unwrap()
+        .contains("[/swagger-auth](/swagger-auth)"));
+
+    let html = openapi::swagger_auth_html("/api/test/swagger-session", "/swagger-ui/");
+    assert!(html.contains(r#"fetch("/api/test/swagger-session""#));
+    assert!(html.contains(r#"localStorage.setItem("authorized""#));
+}
@@ -0,0 +1,86 @@
+// Tests for the role permissions matrix and the require_permission middleware
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use back_end::auth::{middleware::require_permission, AuthUser};
+use back_end::models::{Permission, UserRole};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const ALL_PERMISSIONS: [Permission; 7] = [
+    Permission::DeleteFeedPosts,
+    Permission::ResolveFlags,
+    Permission::ManageUsers,
+    Permission::BanUsers,
+    Permission::ManageRoles,
+    Permission::ManageReports,
+    Permission::ManageSystem,
+];
+
+#[test]
+fn test_permissions_matrix() {
+    for permission in ALL_PERMISSIONS {
+        assert!(UserRole::Admin.has_permission(permission));
+        assert!(!UserRole::User.has_permission(permission));
+    }
+
+    assert!(UserRole::Moderator.has_permission(Permission::DeleteFeedPosts));
+    assert!(UserRole::Moderator.has_permission(Permission::ResolveFlags));
+    assert!(!UserRole::Moderator.has_permission(Permission::BanUsers));
+    assert!(!UserRole::Moderator.has_permission(Permission::ManageRoles));
+    assert!(!UserRole::Moderator.has_permission(Permission::ManageUsers));
+}
+
+async fn status(permission: Permission, role: Option<UserRole>) -> StatusCode {
+    let app = Router::new()
+        .route("/guarded", get(|| async { "ok" }))
+        .route_layer(axum::middleware::from_fn_with_state(
+            permission,
+            require_permission,
+        ));
+
+    let mut request = Request::builder().uri("/guarded");
+    if let Some(role) = role {
+        request = request.extension(AuthUser {
+            id: Uuid::new_v4(),
+            email: "staff@example.com".to_string(),
+            role,
+            two_factor_verified: false,
+            session_id: None,
+            impersonated_by: None,
+        });
+    }
+
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_require_permission_follows_role() {
+    assert_eq!(
+        status(Permission::DeleteFeedPosts, Some(UserRole::Moderator)).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(Permission::BanUsers, Some(UserRole::Moderator)).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(Permission::BanUsers, Some(UserRole::Admin)).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(Permission::ResolveFlags, Some(UserRole::User)).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(Permission::ResolveFlags, None).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
@@ -0,0 +1,117 @@
+// Tests for reading EXIF data from report photos and scoring it against the report
+
+use back_end::config::LocationCheckConfig;
+use back_end::models::Coordinates;
+use back_end::services::image_service::{read_exif, PhotoExif};
+use back_end::services::report_service::location_confidence;
+use chrono::{Duration, TimeZone, Utc};
+
+fn rationals(values: [u32; 3]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|v| [v.to_le_bytes(), 1u32.to_le_bytes()].concat())
+        .collect()
+}
+
+fn entry(tag: u16, kind: u16, count: u32, value: [u8; 4]) -> Vec<u8> {
+    [
+        &tag.to_le_bytes()[..],
+        &kind.to_le_bytes(),
+        &count.to_le_bytes(),
+        &value,
+    ]
+    .concat()
+}
+
+/// A JPEG whose only content is an EXIF block placing it in central London, taken at
+/// 09:30 on 17 October 2026
+fn london_jpeg() -> Vec<u8> {
+    // Little-endian TIFF: header, IFD0 at 8, its capture time at 38, the GPS IFD at 58
+    // and the GPS rationals at 112 and 136
+    let mut tiff = b"II\x2a\x00".to_vec();
+    tiff.extend(8u32.to_le_bytes());
+    tiff.extend(2u16.to_le_bytes());
+    tiff.extend(entry(0x0132, 2, 20, 38u32.to_le_bytes()));
+    tiff.extend(entry(0x8825, 4, 1, 58u32.to_le_bytes()));
+    tiff.extend(0u32.to_le_bytes());
+    tiff.extend(b"2026:10:17 09:30:00\0");
+    tiff.extend(4u16.to_le_bytes());
+    tiff.extend(entry(0x0001, 2, 2, *b"N\0\0\0"));
+    tiff.extend(entry(0x0002, 5, 3, 112u32.to_le_bytes()));
+    tiff.extend(entry(0x0003, 2, 2, *b"W\0\0\0"));
+    tiff.extend(entry(0x0004, 5, 3, 136u32.to_le_bytes()));
+    tiff.extend(0u32.to_le_bytes());
+    tiff.extend(rationals([51, 30, 26]));
+    tiff.extend(rationals([0, 7, 39]));
+
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend(u16::try_from(2 + 6 + tiff.len()).unwrap().to_be_bytes());
+    jpeg.extend(b"Exif\0\0");
+    jpeg.extend(tiff);
+    jpeg.extend([0xFF, 0xD9]);
+    jpeg
+}
+
+fn config() -> LocationCheckConfig {
+    LocationCheckConfig {
+        reject_distance_km: 5.0,
+        max_photo_age_hours: 72,
+    }
+}
+
+fn point(latitude: f64, longitude: f64) -> Coordinates {
+    Coordinates::try_from_degrees(latitude, longitude).unwrap()
+}
+
+#[test]
+fn test_read_exif_finds_position_and_time() {
+    let exif = read_exif(&london_jpeg());
+
+    let location = exif.location.expect("GPS position");
+    assert!((location.latitude() - 51.507_222).abs() < 1e-5);
+    assert!((location.longitude() + 0.1275).abs() < 1e-5);
+    assert_eq!(
+        exif.taken_at,
+        Some(Utc.with_ymd_and_hms(2026, 10, 17, 9, 30, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_read_exif_without_metadata_is_empty() {
+    assert_eq!(read_exif(&[0xFF, 0xD8, 0xFF, 0xD9]), PhotoExif::default());
+    assert_eq!(read_exif(b"\x89PNG\r\n\x1a\n"), PhotoExif::default());
+    // Cut off halfway through the EXIF block
+    let jpeg = london_jpeg();
+    assert_eq!(read_exif(&jpeg[..40]), PhotoExif::default());
+}
+
+#[test]
+fn test_location_confidence_scores_distance_and_age() {
+    let exif = read_exif(&london_jpeg());
+    let taken_at = exif.taken_at.unwrap();
+    let london = point(51.5072, -0.1276);
+
+    let same_spot = location_confidence(&exif, london, taken_at, &config()).unwrap();
+    assert_eq!(same_spot, Some(1.0));
+
+    // About 2.5 km away: accepted, but with less confidence
+    let nearby = location_confidence(&exif, point(51.53, -0.13), taken_at, &config())
+        .unwrap()
+        .unwrap();
+    assert!(nearby > 0.1 && nearby < 1.0);
+
+    let stale = location_confidence(&exif, london, taken_at + Duration::days(10), &config());
+    assert_eq!(stale.unwrap(), Some(0.5));
+
+    let no_exif = location_confidence(&PhotoExif::default(), london, taken_at, &config());
+    assert_eq!(no_exif.unwrap(), None);
+}
+
+#[test]
+fn test_location_confidence_refuses_distant_photos() {
+    let exif = read_exif(&london_jpeg());
+    let paris = point(48.8566, 2.3522);
+
+    let err = location_confidence(&exif, paris, exif.taken_at.unwrap(), &config()).unwrap_err();
+    assert!(err.to_string().contains("km from the reported location"));
+}
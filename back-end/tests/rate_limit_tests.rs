@@ -0,0 +1,250 @@
+// Integration tests for per-IP and per-user rate limiting and in-flight request caps
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use back_end::auth::{self, AuthUser, JwtService};
+use back_end::config::Config;
+use back_end::models::UserRole;
+use back_end::rate_limit::{self, InFlightLimiter, RateLimiter};
+use std::net::SocketAddr;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{insert_user, setup_test_db};
+
+fn ip_limited_app(limiter: RateLimiter) -> Router {
+    Router::new()
+        .route("/limited", get(|| async { "ok" }))
+        .route_layer(axum::middleware::from_fn_with_state(
+            limiter,
+            rate_limit::limit_by_ip,
+        ))
+}
+
+fn from_addr(addr: &str) -> axum::http::request::Builder {
+    let addr: SocketAddr = addr.parse().unwrap();
+    Request::builder()
+        .uri("/limited")
+        .extension(ConnectInfo(addr))
+}
+
+async fn status_of(app: &Router, request: axum::http::request::Builder) -> StatusCode {
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_ip_limit_rejects_with_retry_after_per_client() {
+    let app = ip_limited_app(RateLimiter::per_minute("test", 2, false));
+
+    assert_eq!(
+        status_of(&app, from_addr("10.0.0.1:5000")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(&app, from_addr("10.0.0.1:5001")).await,
+        StatusCode::OK
+    );
+
+    let response = app
+        .clone()
+        .oneshot(from_addr("10.0.0.1:5002").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+
+    // Another client has its own allowance
+    assert_eq!(
+        status_of(&app, from_addr("10.0.0.2:5000")).await,
+        StatusCode::OK
+    );
+}
+
+fn header_u64(response: &axum::response::Response, name: &str) -> u64 {
+    response.headers()[name].to_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limit_headers_warn_before_rejecting() {
+    let app = ip_limited_app(RateLimiter::per_minute("test", 5, false).with_warning_at(60));
+    let send = || {
+        app.clone()
+            .oneshot(from_addr("10.0.1.1:5000").body(Body::empty()).unwrap())
+    };
+
+    let first = send().await.unwrap();
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(header_u64(&first, "x-ratelimit-limit"), 5);
+    assert_eq!(header_u64(&first, "x-ratelimit-remaining"), 4);
+    assert!((1..=12).contains(&header_u64(&first, "x-ratelimit-reset")));
+    assert!(first.headers().get("x-ratelimit-warning").is_none());
+
+    send().await.unwrap();
+    // 60% of 5 used: still allowed, but warned
+    let third = send().await.unwrap();
+    assert_eq!(third.status(), StatusCode::OK);
+    assert_eq!(header_u64(&third, "x-ratelimit-remaining"), 2);
+    assert!(third.headers().get("x-ratelimit-warning").is_some());
+
+    send().await.unwrap();
+    send().await.unwrap();
+    let rejected = send().await.unwrap();
+    assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header_u64(&rejected, "x-ratelimit-remaining"), 0);
+    assert!(header_u64(&rejected, "x-ratelimit-reset") >= header_u64(&rejected, "retry-after"));
+}
+
+#[tokio::test]
+async fn test_requests_without_client_ip_are_not_limited() {
+    let app = ip_limited_app(RateLimiter::per_minute("test", 1, false));
+
+    for _ in 0..3 {
+        assert_eq!(
+            status_of(&app, Request::builder().uri("/limited")).await,
+            StatusCode::OK
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_forwarded_ip_only_used_when_proxy_is_trusted() {
+    let trusted = ip_limited_app(RateLimiter::per_minute("test", 1, true));
+    let proxied = |client: &str| {
+        from_addr("127.0.0.1:8080").header("X-Forwarded-For", format!("{client}, 127.0.0.1"))
+    };
+
+    assert_eq!(
+        status_of(&trusted, proxied("203.0.113.5")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(&trusted, proxied("203.0.113.6")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(&trusted, proxied("203.0.113.5")).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // Untrusted: the header is ignored and both requests count against the proxy
+    let untrusted = ip_limited_app(RateLimiter::per_minute("test", 1, false));
+    assert_eq!(
+        status_of(&untrusted, proxied("203.0.113.5")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(&untrusted, proxied("203.0.113.6")).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[tokio::test]
+async fn test_authenticated_requests_are_limited_per_user() {
+    let pool = setup_test_db().await;
+    let config = Config::from_env().unwrap();
+    let jwt_service = JwtService::new(config.jwt).unwrap();
+
+    let mut tokens = Vec::new();
+    for email in ["limited-a@example.com", "limited-b@example.com"] {
+        let user_id = insert_user(&pool, email).await;
+        tokens.push(
+            jwt_service
+                .create_access_token(user_id, email, &UserRole::User, false, Uuid::new_v4())
+                .unwrap(),
+        );
+    }
+
+    let app = Router::new()
+        .route("/limited", get(|| async { "ok" }))
+        .route_layer(axum::middleware::from_fn_with_state(
+            auth::AuthMiddlewareState {
+                jwt_service,
+                pool,
+                rate_limiter: Some(RateLimiter::per_minute("test", 2, false)),
+                token_scope: None,
+            },
+            auth::middleware::require_auth,
+        ));
+    let as_user = |token: &str| {
+        Request::builder()
+            .uri("/limited")
+            .header("authorization", format!("Bearer {token}"))
+    };
+
+    assert_eq!(status_of(&app, as_user(&tokens[0])).await, StatusCode::OK);
+    assert_eq!(status_of(&app, as_user(&tokens[0])).await, StatusCode::OK);
+    assert_eq!(
+        status_of(&app, as_user(&tokens[0])).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(status_of(&app, as_user(&tokens[1])).await, StatusCode::OK);
+
+    // Unauthenticated requests are rejected before they are counted
+    assert_eq!(
+        status_of(&app, Request::builder().uri("/limited")).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn test_in_flight_limit_caps_concurrent_requests_per_user() {
+    let limiter = InFlightLimiter::new("test", 2);
+    let app = Router::new()
+        .route("/limited", get(|| async { "ok" }))
+        .route_layer(axum::middleware::from_fn_with_state(
+            limiter.clone(),
+            rate_limit::limit_in_flight,
+        ));
+    let alice = Uuid::new_v4();
+    let bob = Uuid::new_v4();
+    let as_user = |id: Uuid| {
+        Request::builder().uri("/limited").extension(AuthUser {
+            id,
+            email: "user@example.com".to_string(),
+            role: UserRole::User,
+            two_factor_verified: false,
+            session_id: None,
+            impersonated_by: None,
+        })
+    };
+
+    // Two of Alice's requests still running: a third is turned away, Bob is not
+    let first = limiter.try_acquire(alice).unwrap();
+    let second = limiter.try_acquire(alice).unwrap();
+    assert!(limiter.try_acquire(alice).is_none());
+    let response = app
+        .clone()
+        .oneshot(as_user(alice).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key(header::RETRY_AFTER));
+    assert_eq!(status_of(&app, as_user(bob)).await, StatusCode::OK);
+
+    // Finishing a request frees its slot, and completed requests hold none
+    drop(first);
+    assert_eq!(status_of(&app, as_user(alice)).await, StatusCode::OK);
+    assert_eq!(status_of(&app, as_user(alice)).await, StatusCode::OK);
+    drop(second);
+    assert!(limiter.try_acquire(alice).is_some());
+
+    assert_eq!(
+        status_of(&app, Request::builder().uri("/limited")).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
@@ -0,0 +1,381 @@
+// Integration tests for admin report reassignment, status and verification overrides
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::config::Config;
+use back_end::models::{ReportStatus, UserRole};
+use back_end::services::{EventService, ReportAdminService, ScoringService};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{
+    access_token, create_test_app, get_test_pool, insert_user, insert_user_with_role, setup_test_db,
+};
+
+async fn total_points(pool: &PgPool, user_id: Uuid) -> i32 {
+    sqlx::query_scalar(
+        "SELECT COALESCE((SELECT total_points FROM user_scores WHERE user_id = $1), 0)",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_admin_status_override_and_reassignment_correct_scores() {
+    let pool = setup_test_db().await;
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().unwrap();
+
+    let admin = insert_user(&pool, "report-admin@example.com").await;
+    let reporter = insert_user(&pool, "report-admin-reporter@example.com").await;
+    let first = insert_user(&pool, "report-admin-first@example.com").await;
+    let second = insert_user(&pool, "report-admin-second@example.com").await;
+
+    let report_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO litter_reports (reporter_id, location, status)
+         VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'pending')
+         RETURNING id",
+    )
+    .bind(reporter)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let service = ReportAdminService::new(
+        pool.clone(),
+        ScoringService::new(pool.clone(), config.scoring.clone()),
+        EventService::new(pool.clone()),
+    );
+
+    // A clearer is required to mark a report cleared
+    assert!(service
+        .override_status(report_id, admin, ReportStatus::Cleared, None, None, "Fix")
+        .await
+        .is_err());
+
+    let report = service
+        .override_status(
+            report_id,
+            admin,
+            ReportStatus::Cleared,
+            None,
+            Some(first),
+            "Clear confirmed by support",
+        )
+        .await
+        .unwrap();
+    assert_eq!(report.status, ReportStatus::Cleared);
+    assert_eq!(report.claimed_by, Some(first));
+    assert_eq!(report.cleared_by, Some(first));
+    assert_eq!(
+        total_points(&pool, first).await,
+        config.scoring.base_points_per_clear
+    );
+
+    // The clear points follow the clear to the new clearer
+    let report = service
+        .reassign(
+            report_id,
+            admin,
+            None,
+            Some(second),
+            "Wrong volunteer credited",
+        )
+        .await
+        .unwrap();
+    assert_eq!(report.cleared_by, Some(second));
+    assert_eq!(total_points(&pool, first).await, 0);
+    assert_eq!(
+        total_points(&pool, second).await,
+        config.scoring.base_points_per_clear
+    );
+
+    // Resetting the report takes the credit away again
+    let report = service
+        .override_status(report_id, admin, ReportStatus::Pending, None, None, "Spam")
+        .await
+        .unwrap();
+    assert_eq!(report.status, ReportStatus::Pending);
+    assert_eq!(report.claimed_by, None);
+    assert_eq!(total_points(&pool, second).await, 0);
+
+    // A pending report has no claim to hand over
+    assert!(service
+        .reassign(report_id, admin, Some(first), None, "Handover")
+        .await
+        .is_err());
+
+    let events: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM event_log WHERE entity_type = 'report' AND entity_id = $1",
+    )
+    .bind(report_id.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(events, 3);
+}
+
+#[tokio::test]
+async fn test_purge_user_reports_dry_run_changes_nothing() {
+    let pool = setup_test_db().await;
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().unwrap();
+
+    let spammer = insert_user(&pool, "report-purge-spammer@example.com").await;
+    let verifier = insert_user(&pool, "report-purge-verifier@example.com").await;
+    let admin = insert_user(&pool, "report-purge-admin@example.com").await;
+
+    let mut report_ids = Vec::new();
+    for _ in 0..3 {
+        let report_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO litter_reports (reporter_id, location, status)
+             VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'cleared')
+             RETURNING id",
+        )
+        .bind(spammer)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        report_ids.push(report_id);
+    }
+    sqlx::query(
+        "INSERT INTO report_verifications (report_id, verifier_id, is_verified) VALUES ($1, $2, true)",
+    )
+    .bind(report_ids[0])
+    .bind(verifier)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let service = ReportAdminService::new(
+        pool.clone(),
+        ScoringService::new(pool.clone(), config.scoring.clone()),
+        EventService::new(pool.clone()),
+    );
+
+    let summary = service
+        .purge_user_reports(spammer, admin, true)
+        .await
+        .unwrap();
+    assert!(summary.dry_run);
+    assert_eq!(summary.affected["litter_reports"], 3);
+    assert_eq!(summary.affected["report_verifications"], 1);
+    assert_eq!(summary.sample_ids.len(), 3);
+
+    let remaining = |pool: PgPool| async move {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM litter_reports WHERE reporter_id = $1")
+            .bind(spammer)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+    assert_eq!(remaining(pool.clone()).await, 3);
+
+    let deletions = |pool: PgPool, report_ids: Vec<Uuid>| async move {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM report_events WHERE report_id = ANY($1) AND kind = 'deleted'",
+        )
+        .bind(report_ids)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+    };
+    assert_eq!(deletions(pool.clone(), report_ids.clone()).await, 0);
+
+    let summary = service
+        .purge_user_reports(spammer, admin, false)
+        .await
+        .unwrap();
+    assert!(!summary.dry_run);
+    assert_eq!(summary.affected["litter_reports"], 3);
+    assert_eq!(remaining(pool.clone()).await, 0);
+    // The history outlives the reports
+    assert_eq!(deletions(pool.clone(), report_ids).await, 3);
+}
+
+async fn post_json(app: &axum::Router, token: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("authorization", format!("Bearer {token}"))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn create_report(
+    pool: &PgPool,
+    reporter_id: Uuid,
+    status: &str,
+    clearer: Option<Uuid>,
+) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO litter_reports
+             (reporter_id, location, status, claimed_by, claimed_at, cleared_by, cleared_at)
+         VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), $2::report_status,
+                 $3, CASE WHEN $3::uuid IS NULL THEN NULL ELSE NOW() END,
+                 $3, CASE WHEN $3::uuid IS NULL THEN NULL ELSE NOW() END)
+         RETURNING id",
+    )
+    .bind(reporter_id)
+    .bind(status)
+    .bind(clearer)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_reassign_report_over_http() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin =
+        insert_user_with_role(&pool, "reassign-http-admin@example.com", UserRole::Admin).await;
+    let admin_token = access_token(admin, "reassign-http-admin@example.com", UserRole::Admin);
+    let user = insert_user(&pool, "reassign-http-user@example.com").await;
+    let user_token = access_token(user, "reassign-http-user@example.com", UserRole::User);
+    let first = insert_user(&pool, "reassign-http-first@example.com").await;
+    let second = insert_user(&pool, "reassign-http-second@example.com").await;
+
+    let report_id = create_report(&pool, user, "cleared", Some(first)).await;
+    let uri = format!("/api/admin/reports/{report_id}/reassign");
+    let body = json!({ "cleared_by": second, "reason": "Wrong volunteer credited" });
+
+    let (status, _) = post_json(&app, &user_token, &uri, body.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = post_json(
+        &app,
+        &admin_token,
+        &uri,
+        json!({ "cleared_by": second, "reason": "" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, report) = post_json(&app, &admin_token, &uri, body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["status"], "cleared");
+    assert_eq!(report["cleared_by"], second.to_string());
+
+    // A pending report has nothing to hand over
+    let pending = create_report(&pool, user, "pending", None).await;
+    let (status, _) = post_json(
+        &app,
+        &admin_token,
+        &format!("/api/admin/reports/{pending}/reassign"),
+        json!({ "claimed_by": second, "reason": "Handover" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_override_report_status_over_http() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin =
+        insert_user_with_role(&pool, "status-http-admin@example.com", UserRole::Admin).await;
+    let admin_token = access_token(admin, "status-http-admin@example.com", UserRole::Admin);
+    let user = insert_user(&pool, "status-http-user@example.com").await;
+    let user_token = access_token(user, "status-http-user@example.com", UserRole::User);
+    let clearer = insert_user(&pool, "status-http-clearer@example.com").await;
+
+    let report_id = create_report(&pool, user, "pending", None).await;
+    let uri = format!("/api/admin/reports/{report_id}/status");
+    let body = json!({
+        "status": "cleared",
+        "cleared_by": clearer,
+        "reason": "Clear photo confirmed by support"
+    });
+
+    let (status, _) = post_json(&app, &user_token, &uri, body.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // A cleared report needs a clearer
+    let (status, _) = post_json(
+        &app,
+        &admin_token,
+        &uri,
+        json!({ "status": "cleared", "reason": "Fix" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, report) = post_json(&app, &admin_token, &uri, body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["status"], "cleared");
+    assert_eq!(report["claimed_by"], clearer.to_string());
+    assert_eq!(report["cleared_by"], clearer.to_string());
+
+    let (status, _) = post_json(
+        &app,
+        &admin_token,
+        &format!("/api/admin/reports/{}/status", Uuid::new_v4()),
+        json!({ "status": "pending", "reason": "Spam" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_override_report_verification_over_http() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let admin =
+        insert_user_with_role(&pool, "verdict-http-admin@example.com", UserRole::Admin).await;
+    let admin_token = access_token(admin, "verdict-http-admin@example.com", UserRole::Admin);
+    let user = insert_user(&pool, "verdict-http-user@example.com").await;
+    let user_token = access_token(user, "verdict-http-user@example.com", UserRole::User);
+    let clearer = insert_user(&pool, "verdict-http-clearer@example.com").await;
+
+    let report_id = create_report(&pool, user, "cleared", Some(clearer)).await;
+    let uri = format!("/api/admin/reports/{report_id}/verification-override");
+    let body = json!({ "verdict": "verified", "reason": "Site visited by the council" });
+
+    let (status, _) = post_json(&app, &user_token, &uri, body.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = post_json(
+        &app,
+        &admin_token,
+        &uri,
+        json!({ "verdict": "verified", "reason": "" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Only a cleared or disputed report has a clear to settle
+    let pending = create_report(&pool, user, "pending", None).await;
+    let (status, _) = post_json(
+        &app,
+        &admin_token,
+        &format!("/api/admin/reports/{pending}/verification-override"),
+        body.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, report) = post_json(&app, &admin_token, &uri, body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["status"], "verified");
+}
@@ -0,0 +1,86 @@
+// Tests for expiring reports left pending for months and reactivating them
+
+use back_end::config::Config;
+use back_end::error::AppError;
+use back_end::models::report::ReportStatus;
+use back_end::services::{
+    GeocodingService, ImageService, ImageStorageService, ReportService, S3Service, UploadService,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{insert_user, setup_test_db};
+
+async fn report_service(pool: &PgPool) -> ReportService {
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().expect("Failed to load config");
+    let s3_service = S3Service::new(config.s3.clone())
+        .await
+        .expect("Failed to create S3 service");
+    let image_storage =
+        ImageStorageService::new(pool.clone(), s3_service.clone(), config.s3.spill_max_mb);
+
+    ReportService::new(
+        pool.clone(),
+        ImageService::new(config.image.clone()),
+        image_storage,
+        UploadService::new(pool.clone(), s3_service, config.image.clone()),
+        GeocodingService::new(pool.clone(), config.geocoding.clone()),
+        config.location_check.clone(),
+    )
+}
+
+async fn create_user(pool: &PgPool) -> Uuid {
+    insert_user(pool, &format!("expiry-{}@example.com", Uuid::new_v4())).await
+}
+
+/// A pending report created `age_months` ago
+async fn create_report_aged(pool: &PgPool, reporter_id: Uuid, age_months: i32) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO litter_reports (reporter_id, location, photo_before, created_at)
+        VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'before.webp',
+                NOW() - make_interval(months => $2))
+        RETURNING id
+        "#,
+    )
+    .bind(reporter_id)
+    .bind(age_months)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_stale_reports_expire_until_reactivated() {
+    let pool = setup_test_db().await;
+    let service = report_service(&pool).await;
+
+    let reporter = create_user(&pool).await;
+    let admin = create_user(&pool).await;
+    let stale = create_report_aged(&pool, reporter, 7).await;
+    let fresh = create_report_aged(&pool, reporter, 1).await;
+
+    let expired = service.expire_stale_reports(6).await.unwrap();
+    assert!(expired.contains(&stale));
+    assert!(!expired.contains(&fresh));
+
+    let report = service.get_report_by_id(stale).await.unwrap();
+    assert_eq!(report.status, ReportStatus::Expired);
+
+    // Expired reports cannot be claimed
+    let result = service.claim_report(stale, admin).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+    let report = service.reactivate_report(stale, admin).await.unwrap();
+    assert_eq!(report.status, ReportStatus::Pending);
+
+    // Reactivating restarts the clock, so the next run leaves it alone
+    let expired = service.expire_stale_reports(6).await.unwrap();
+    assert!(!expired.contains(&stale));
+
+    // Only expired reports can be reactivated
+    let result = service.reactivate_report(fresh, admin).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+}
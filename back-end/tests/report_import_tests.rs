@@ -0,0 +1,138 @@
+// Tests for bulk report imports
+
+use back_end::config::Config;
+use back_end::models::{
+    parse_import_csv, LitterCategory, LitterSeverity, ReportImportRow, UserRole,
+};
+use back_end::services::{
+    GeocodingService, ImageService, ImageStorageService, ReportImportService, ReportService,
+    S3Service, UploadService,
+};
+use serde_json::json;
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{insert_user_with_role, setup_test_db};
+
+fn row(latitude: f64, longitude: f64) -> ReportImportRow {
+    serde_json::from_value(json!({ "latitude": latitude, "longitude": longitude })).unwrap()
+}
+
+async fn insert_admin(pool: &PgPool) -> Uuid {
+    insert_user_with_role(pool, "report-import-admin@example.com", UserRole::Admin).await
+}
+
+#[test]
+fn test_csv_rows_parse_and_validate() {
+    let csv = "\
+latitude,longitude,description,category,severity,photo_urls,external_id
+51.5074,-0.1278, Bags by the bins ,Fly_Tipping,LARGE,https://a.example/1.jpg https://a.example/2.jpg,WCC-1
+95.0,-0.1278,,,,,WCC-2
+";
+    let rows = parse_import_csv(csv.as_bytes()).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].external_id.as_deref(), Some("WCC-1"));
+
+    let valid = rows[0].validate().unwrap();
+    assert_eq!(valid.description.as_deref(), Some("Bags by the bins"));
+    assert_eq!(valid.category, LitterCategory::FlyTipping);
+    assert_eq!(valid.severity, LitterSeverity::Large);
+    assert_eq!(valid.photo_urls.len(), 2);
+
+    assert!(rows[1].validate().is_err());
+
+    let mut insecure = row(51.5, -0.12);
+    insecure.photo_urls = vec!["http://a.example/1.jpg".to_string()];
+    assert!(insecure.validate().unwrap_err().contains("not https"));
+
+    let error = parse_import_csv(b"latitude,longitude\nnorth,-0.12\n").unwrap_err();
+    assert!(error.to_string().contains("CSV line 2"));
+}
+
+#[tokio::test]
+async fn test_import_creates_reports_and_skips_duplicates() {
+    let pool = setup_test_db().await;
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().unwrap();
+
+    let admin = insert_admin(&pool).await;
+    let existing: Uuid = sqlx::query_scalar(
+        "INSERT INTO litter_reports (location, status)
+         VALUES (ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'pending')
+         RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let s3_service = S3Service::new(config.s3.clone()).await.unwrap();
+    let image_service = ImageService::new(config.image.clone());
+    let image_storage =
+        ImageStorageService::new(pool.clone(), s3_service.clone(), config.s3.spill_max_mb);
+    let report_service = ReportService::new(
+        pool.clone(),
+        image_service.clone(),
+        image_storage,
+        UploadService::new(pool.clone(), s3_service, config.image.clone()),
+        GeocodingService::new(pool.clone(), config.geocoding.clone()),
+        config.location_check.clone(),
+    );
+    let service = ReportImportService::new(
+        pool.clone(),
+        report_service,
+        image_service,
+        config.image.max_size_mb,
+    );
+
+    assert!(service.start(admin, None, Vec::new()).await.is_err());
+
+    let started = service
+        .start(
+            admin,
+            Some("Test Council".to_string()),
+            vec![
+                // Within 25m of the existing report
+                row(51.50745, -0.12785),
+                row(51.52, -0.10),
+                // Same place as the row before it
+                row(51.52, -0.10),
+                row(95.0, -0.10),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(started.total_rows, 4);
+
+    let mut import = started;
+    for _ in 0..100 {
+        import = service.get(import.import_id).await.unwrap();
+        if import.status == "completed" || import.status == "failed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(import.status, "completed");
+    assert_eq!(import.processed_rows, 4);
+    assert_eq!(import.imported_count, 1);
+    assert_eq!(import.duplicate_count, 2);
+    assert_eq!(import.failed_count, 1);
+
+    let outcomes: Vec<&str> = import.results.iter().map(|r| r.outcome.as_str()).collect();
+    assert_eq!(
+        outcomes,
+        vec!["duplicate", "imported", "duplicate", "failed"]
+    );
+    assert_eq!(import.results[0].report_id, Some(existing));
+    assert_eq!(import.results[2].report_id, import.results[1].report_id);
+
+    let reporter: Option<Uuid> =
+        sqlx::query_scalar("SELECT reporter_id FROM litter_reports WHERE id = $1")
+            .bind(import.results[1].report_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(reporter, None);
+}
@@ -0,0 +1,150 @@
+// Tests for the report status state machine
+
+use back_end::models::{IllegalTransition, ReportStatus, ReportTransition};
+
+#[test]
+fn test_normal_flow_is_allowed() {
+    let flow = [
+        (
+            ReportTransition::Claim,
+            ReportStatus::Pending,
+            ReportStatus::Claimed,
+        ),
+        (
+            ReportTransition::Release,
+            ReportStatus::Claimed,
+            ReportStatus::Pending,
+        ),
+        (
+            ReportTransition::Expire,
+            ReportStatus::Claimed,
+            ReportStatus::Pending,
+        ),
+        (
+            ReportTransition::Clear,
+            ReportStatus::Claimed,
+            ReportStatus::Cleared,
+        ),
+        (
+            ReportTransition::Verify,
+            ReportStatus::Cleared,
+            ReportStatus::Verified,
+        ),
+        (
+            ReportTransition::Dispute,
+            ReportStatus::Cleared,
+            ReportStatus::Disputed,
+        ),
+        (
+            ReportTransition::Reopen,
+            ReportStatus::Disputed,
+            ReportStatus::Pending,
+        ),
+        (
+            ReportTransition::Cancel,
+            ReportStatus::Pending,
+            ReportStatus::Cancelled,
+        ),
+        (
+            ReportTransition::Archive,
+            ReportStatus::Verified,
+            ReportStatus::Archived,
+        ),
+    ];
+
+    for (transition, from, to) in flow {
+        assert_eq!(transition.apply(&from), Ok(to));
+    }
+}
+
+#[test]
+fn test_skipping_a_step_is_refused() {
+    assert_eq!(
+        ReportTransition::Clear.apply(&ReportStatus::Pending),
+        Err(IllegalTransition {
+            transition: ReportTransition::Clear,
+            from: ReportStatus::Pending,
+            to: ReportStatus::Cleared,
+        })
+    );
+    assert!(ReportTransition::Claim
+        .apply(&ReportStatus::Cleared)
+        .is_err());
+    assert!(ReportTransition::Verify
+        .apply(&ReportStatus::Disputed)
+        .is_err());
+    assert!(ReportTransition::Cancel
+        .apply(&ReportStatus::Claimed)
+        .is_err());
+
+    // Existing error messages are kept
+    let err = ReportTransition::Claim
+        .apply(&ReportStatus::Claimed)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Report is not available for claiming");
+}
+
+#[test]
+fn test_admin_corrections_stay_within_open_statuses() {
+    let correct = ReportTransition::admin_override(&ReportStatus::Pending);
+    assert_eq!(correct, ReportTransition::Correct);
+    assert!(correct
+        .check(&ReportStatus::Disputed, &ReportStatus::Pending)
+        .is_ok());
+    assert!(correct
+        .check(&ReportStatus::Verified, &ReportStatus::Claimed)
+        .is_ok());
+    assert!(correct
+        .check(&ReportStatus::Pending, &ReportStatus::Cancelled)
+        .is_err());
+
+    let archive = ReportTransition::admin_override(&ReportStatus::Archived);
+    assert_eq!(archive, ReportTransition::Archive);
+
+    // Cancelled and archived reports are final
+    for status in [ReportStatus::Cancelled, ReportStatus::Archived] {
+        let err = correct.check(&status, &ReportStatus::Pending).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "A cancelled or archived report can no longer change status"
+        );
+        assert!(archive.check(&status, &ReportStatus::Archived).is_err());
+    }
+}
+
+#[test]
+fn test_stale_reports_expire_and_can_be_reactivated() {
+    assert_eq!(
+        ReportTransition::Lapse.apply(&ReportStatus::Pending),
+        Ok(ReportStatus::Expired)
+    );
+    assert_eq!(
+        ReportTransition::Reactivate.apply(&ReportStatus::Expired),
+        Ok(ReportStatus::Pending)
+    );
+    assert_eq!(
+        ReportTransition::Archive.apply(&ReportStatus::Expired),
+        Ok(ReportStatus::Archived)
+    );
+
+    // Only pending reports lapse, and only expired ones come back
+    assert!(ReportTransition::Lapse
+        .apply(&ReportStatus::Claimed)
+        .is_err());
+    let err = ReportTransition::Reactivate
+        .apply(&ReportStatus::Pending)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Only an expired report can be reactivated");
+
+    // An expired report is off the map until it is reactivated
+    assert!(ReportTransition::Claim
+        .apply(&ReportStatus::Expired)
+        .is_err());
+    let err = ReportTransition::Correct
+        .check(&ReportStatus::Expired, &ReportStatus::Pending)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Reactivate an expired report before changing its status"
+    );
+}
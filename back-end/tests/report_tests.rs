@@ -1,4 +1,5 @@
 // Integration tests for report endpoints
+#![allow(clippy::needless_borrows_for_generic_args)]
 
 use axum::{
     body::Body,
@@ -273,8 +274,8 @@ async fn test_get_my_reports() {
         .await
         .unwrap();
     let reports: Value = serde_json::from_slice(&body).unwrap();
-    assert!(reports.is_array());
-    assert_eq!(reports.as_array().unwrap().len(), 0);
+    assert_eq!(reports["items"].as_array().unwrap().len(), 0);
+    assert_eq!(reports["total"], 0);
 }
 
 #[tokio::test]
@@ -301,8 +302,8 @@ async fn test_get_my_clears() {
         .await
         .unwrap();
     let clears: Value = serde_json::from_slice(&body).unwrap();
-    assert!(clears.is_array());
-    assert_eq!(clears.as_array().unwrap().len(), 0);
+    assert_eq!(clears["items"].as_array().unwrap().len(), 0);
+    assert_eq!(clears["total"], 0);
 }
 
 /// Helper to create a report and return the report ID
@@ -514,6 +515,44 @@ async fn test_clear_report_success() {
         .unwrap();
     let report: Value = serde_json::from_slice(&body).unwrap();
     assert_eq!(report["status"].as_str().unwrap(), "Cleared");
+    assert_eq!(
+        report["photo_after_metadata"]["url"],
+        report["photo_after"].clone()
+    );
+    assert_eq!(report["photo_after_metadata"]["width"], 1);
+    assert!(report["photo_after_metadata"]["blurhash"].is_string());
+}
+
+#[tokio::test]
+async fn test_report_includes_photo_metadata() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "metadata-reporter@example.com").await;
+    let report_id = create_test_report(&app, &token).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/api/reports/{}", report_id))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    let metadata = &report["photo_before_metadata"];
+    assert_eq!(metadata["url"], report["photo_before"].clone());
+    assert_eq!(metadata["width"], 1);
+    assert_eq!(metadata["height"], 1);
+    // 4x3 components: 1 size + 1 max + 4 DC + 11 * 2 AC characters
+    assert_eq!(metadata["blurhash"].as_str().unwrap().len(), 28);
+    assert!(report["photo_after_metadata"].is_null());
 }
 
 #[tokio::test]
@@ -611,3 +650,1034 @@ async fn test_cannot_clear_report_claimed_by_another_user() {
         .unwrap()
         .contains("Only the user who claimed"));
 }
+
+#[tokio::test]
+async fn test_identical_photos_share_storage_and_flag_reuse() {
+    let app = create_test_app().await;
+    let email = "photo-reuser@example.com";
+    let token = create_verified_user_and_login(&app, email).await;
+    let pool = get_test_pool().await;
+
+    let flag_count = || async {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM account_flags f JOIN users u ON f.user_id = u.id WHERE u.email = $1 AND f.reason = 'duplicate_photo'",
+        )
+        .bind(email)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+    };
+
+    create_test_report(&app, &token).await;
+    create_test_report(&app, &token).await;
+    assert_eq!(flag_count().await, 0);
+
+    create_test_report(&app, &token).await;
+    assert_eq!(flag_count().await, 1);
+
+    // All three reports point at the same stored object
+    let distinct_urls: i64 = sqlx::query_scalar(
+        "SELECT COUNT(DISTINCT lr.photo_before) FROM litter_reports lr JOIN users u ON lr.reporter_id = u.id WHERE u.email = $1",
+    )
+    .bind(email)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(distinct_urls, 1);
+}
+
+#[tokio::test]
+async fn test_claim_batch_groups_reports_into_run() {
+    let app = create_test_app().await;
+
+    let reporter_token = create_verified_user_and_login(&app, "batch-reporter@example.com").await;
+    let first_id = create_test_report(&app, &reporter_token).await;
+    let second_id = create_test_report(&app, &reporter_token).await;
+    let missing_id = uuid::Uuid::new_v4().to_string();
+
+    let claimer_token = create_verified_user_and_login(&app, "batch-claimer@example.com").await;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/reports/claim-batch")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", claimer_token))
+                .body(Body::from(
+                    json!({ "report_ids": [first_id, second_id, first_id, missing_id] })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let batch: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(batch["claimed_count"], 2);
+    let run_id = batch["run_id"].as_str().unwrap().to_string();
+
+    // Duplicates are collapsed; each report gets its own result
+    let results = batch["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["claimed"], true);
+    assert_eq!(results[1]["claimed"], true);
+    assert_eq!(results[2]["claimed"], false);
+    assert_eq!(results[2]["error"], "Report not found");
+
+    // Clear one of the two
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/reports/{}/clear", first_id))
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", claimer_token))
+                .body(Body::from(
+                    json!({
+                        "photo_base64": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/api/cleanup-runs/{}", run_id))
+                .header("authorization", format!("Bearer {}", claimer_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let run: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(run["total_reports"], 2);
+    assert_eq!(run["claimed_reports"], 1);
+    assert_eq!(run["cleared_reports"], 1);
+    assert!(run["points_earned"].as_i64().unwrap() > 0);
+    assert_eq!(run["reports"].as_array().unwrap().len(), 2);
+
+    // Nothing claimable means no run is created
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/reports/claim-batch")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", claimer_token))
+                .body(Body::from(json!({ "report_ids": [second_id] }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let batch: Value = serde_json::from_slice(&body).unwrap();
+    assert!(batch["run_id"].is_null());
+    assert_eq!(batch["claimed_count"], 0);
+}
+
+#[tokio::test]
+async fn test_anonymous_report_can_be_claimed_once() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    // CAPTCHA is disabled in the test environment, so no token is needed
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/reports/anonymous")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "latitude": 51.5074,
+                        "longitude": -0.1278,
+                        "description": "Reported by a guest",
+                        "photo_base64": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let created: Value = serde_json::from_slice(&body).unwrap();
+    assert!(created["report"]["reporter_id"].is_null());
+    let report_id = created["report"]["id"].as_str().unwrap().to_string();
+    let claim_code = created["claim_code"].as_str().unwrap().to_string();
+
+    let email = "guest-claimer@example.com";
+    let token = create_verified_user_and_login(&app, email).await;
+    let claim = || {
+        Request::builder()
+            .method("POST")
+            .uri("/api/reports/anonymous/claim")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token))
+            .body(Body::from(json!({ "claim_code": claim_code }).to_string()))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(claim()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["id"], report_id.as_str());
+    assert!(report["reporter_id"].is_string());
+
+    // The claimant is credited as if they had reported it themselves
+    let total_reports: i32 = sqlx::query_scalar(
+        "SELECT s.total_reports FROM user_scores s JOIN users u ON s.user_id = u.id WHERE u.email = $1",
+    )
+    .bind(email)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(total_reports, 1);
+
+    let response = app.oneshot(claim()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // The points were paid exactly once, together with the claim
+    let report_events: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM score_events e JOIN users u ON e.user_id = u.id
+         WHERE u.email = $1 AND e.kind = 'report' AND e.report_id = $2::uuid",
+    )
+    .bind(email)
+    .bind(&report_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(report_events, 1);
+}
+
+/// Helper to create a report with a category and severity and return its JSON
+async fn create_categorised_report(
+    app: &axum::Router,
+    token: &str,
+    category: &str,
+    severity: &str,
+) -> Value {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/reports")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({
+                        "latitude": 51.5074,
+                        "longitude": -0.1278,
+                        "category": category,
+                        "severity": severity,
+                        "photo_base64": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_nearby_reports_filter_by_category_and_severity() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "categories@example.com").await;
+
+    let dumped = create_categorised_report(&app, &token, "fly_tipping", "large").await;
+    assert_eq!(dumped["category"], "fly_tipping");
+    assert_eq!(dumped["severity"], "large");
+
+    // Both are optional
+    let untagged = create_test_report(&app, &token).await;
+
+    let nearby = |query: &'static str| {
+        let app = app.clone();
+        let token = token.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!(
+                            "/api/reports/nearby?latitude=51.5074&longitude=-0.1278&radius_km=5{}",
+                            query
+                        ))
+                        .header("authorization", format!("Bearer {}", token))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let page: Value = serde_json::from_slice(&body).unwrap();
+            page["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|report| report["id"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        nearby("&category=fly_tipping").await,
+        vec![dumped["id"].as_str().unwrap().to_string()]
+    );
+    assert_eq!(
+        nearby("&category=other&severity=small").await,
+        vec![untagged]
+    );
+    assert!(nearby("&category=fly_tipping&severity=small")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_clear_points_scale_with_severity() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+    let reporter_token =
+        create_verified_user_and_login(&app, "severity_reporter@example.com").await;
+
+    let mut clear_points = Vec::new();
+    for (i, severity) in ["small", "large"].into_iter().enumerate() {
+        let report = create_categorised_report(&app, &reporter_token, "plastic", severity).await;
+        let report_id = report["id"].as_str().unwrap();
+
+        // A fresh clearer each time so streak bonuses match
+        let email = format!("severity_clearer_{}@example.com", i);
+        let clearer_token = create_verified_user_and_login(&app, &email).await;
+        for (uri, body) in [
+            (format!("/api/reports/{}/claim", report_id), Body::empty()),
+            (
+                format!("/api/reports/{}/clear", report_id),
+                Body::from(
+                    json!({
+                        "photo_base64": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
+                    })
+                    .to_string(),
+                ),
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .header("authorization", format!("Bearer {}", clearer_token))
+                        .body(body)
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let points: i32 = sqlx::query_scalar(
+            "SELECT points FROM score_events WHERE report_id = $1::uuid AND kind = 'clear'",
+        )
+        .bind(report_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        clear_points.push(points);
+    }
+
+    // Base 10 points at 1.0x for small and 2.0x for large
+    assert_eq!(clear_points[1] - clear_points[0], 10);
+}
+
+const PIXEL_PNG: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// Send a JSON request and return the status with the parsed body (null when empty)
+async fn send_json(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn test_report_with_extra_photos_lists_them_in_order() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "multi_photo@example.com").await;
+
+    let request = |extra: usize| {
+        json!({
+            "latitude": 51.5074,
+            "longitude": -0.1278,
+            "photo_base64": PIXEL_PNG,
+            "extra_photos_base64": vec![PIXEL_PNG; extra]
+        })
+    };
+
+    let (status, _) = send_json(&app, "POST", "/api/reports", &token, Some(request(5))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, report) = send_json(&app, "POST", "/api/reports", &token, Some(request(2))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let before = report["photos"]["before"].as_array().unwrap();
+    assert_eq!(before.len(), 3);
+    assert_eq!(before[0]["url"], report["photo_before"]);
+    assert!(report["photos"]["after"].as_array().unwrap().is_empty());
+
+    let report_id = report["id"].as_str().unwrap();
+    let (status, photos) = send_json(
+        &app,
+        "GET",
+        &format!("/api/reports/{}/photos", report_id),
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(photos["before"], report["photos"]["before"]);
+}
+
+#[tokio::test]
+async fn test_add_report_photo_checks_owner_and_limit() {
+    let app = create_test_app().await;
+    let reporter_token =
+        create_verified_user_and_login(&app, "add_photo_reporter@example.com").await;
+    let other_token = create_verified_user_and_login(&app, "add_photo_other@example.com").await;
+    let report_id = create_test_report(&app, &reporter_token).await;
+    let uri = format!("/api/reports/{}/photos", report_id);
+    let before_photo = json!({ "kind": "before", "photo_base64": PIXEL_PNG });
+
+    let (status, _) = send_json(&app, "POST", &uri, &other_token, Some(before_photo.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Nobody has cleared it, so there is no clearer to add after photos
+    let (status, _) = send_json(
+        &app,
+        "POST",
+        &uri,
+        &reporter_token,
+        Some(json!({ "kind": "after", "photo_base64": PIXEL_PNG })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    for expected in 2..=5 {
+        let (status, photos) = send_json(
+            &app,
+            "POST",
+            &uri,
+            &reporter_token,
+            Some(before_photo.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(photos["before"].as_array().unwrap().len(), expected);
+    }
+
+    let (status, _) = send_json(&app, "POST", &uri, &reporter_token, Some(before_photo)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_reports_in_bounds_returns_reports_or_clusters_by_zoom() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "in_bounds@example.com").await;
+
+    let first = create_test_report(&app, &token).await;
+    let second = create_test_report(&app, &token).await;
+
+    let london = "/api/reports/in-bounds?min_lat=51.4&min_lon=-0.3&max_lat=51.6&max_lon=0.0";
+
+    let (status, body) = send_json(&app, "GET", &format!("{london}&zoom=15"), &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["clustered"], false);
+    assert_eq!(body["total"], 2);
+    let ids: Vec<&str> = body["reports"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec![second.as_str(), first.as_str()]);
+
+    // Zoomed out, the two reports at the same spot become one marker
+    let (status, body) = send_json(&app, "GET", &format!("{london}&zoom=5"), &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["clustered"], true);
+    assert_eq!(body["total"], 2);
+    assert!(body["reports"].as_array().unwrap().is_empty());
+    let clusters = body["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0]["count"], 2);
+    assert!((clusters[0]["latitude"].as_f64().unwrap() - 51.5074).abs() < 1e-6);
+
+    // A viewport elsewhere is empty
+    let (status, body) = send_json(
+        &app,
+        "GET",
+        "/api/reports/in-bounds?min_lat=48.8&min_lon=2.2&max_lat=48.9&max_lon=2.4",
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 0);
+
+    // Inverted bounds are rejected
+    let (status, _) = send_json(
+        &app,
+        "GET",
+        "/api/reports/in-bounds?min_lat=51.6&min_lon=-0.3&max_lat=51.4&max_lon=0.0",
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_report_lists_as_geojson() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "geojson@example.com").await;
+    let report_id = create_test_report(&app, &token).await;
+
+    // Asked for by Accept header
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/reports/nearby?latitude=51.5074&longitude=-0.1278&radius_km=5")
+                .header("accept", "application/geo+json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/geo+json");
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["type"], "FeatureCollection");
+    assert_eq!(body["total"], 1);
+    let feature = &body["features"][0];
+    assert_eq!(feature["type"], "Feature");
+    assert_eq!(feature["id"], report_id.as_str());
+    assert_eq!(feature["geometry"]["type"], "Point");
+    let coordinates = feature["geometry"]["coordinates"].as_array().unwrap();
+    assert!((coordinates[0].as_f64().unwrap() + 0.1278).abs() < 1e-6);
+    assert!((coordinates[1].as_f64().unwrap() - 51.5074).abs() < 1e-6);
+    assert_eq!(feature["properties"]["status"], "pending");
+
+    // Or by query parameter, with clusters when zoomed out
+    let (status, body) = send_json(
+        &app,
+        "GET",
+        "/api/reports/in-bounds?min_lat=51.4&min_lon=-0.3&max_lat=51.6&max_lon=0.0&zoom=5&format=geojson",
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["type"], "FeatureCollection");
+    assert_eq!(body["clustered"], true);
+    assert_eq!(body["features"][0]["properties"]["count"], 1);
+
+    let (status, _) = send_json(
+        &app,
+        "GET",
+        "/api/reports/in-bounds?min_lat=51.4&min_lon=-0.3&max_lat=51.6&max_lon=0.0&format=kml",
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_report_clusters_cover_every_report_in_view() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "clusters@example.com").await;
+
+    create_test_report(&app, &token).await;
+    create_test_report(&app, &token).await;
+    let (status, manchester) = send_json(
+        &app,
+        "POST",
+        "/api/reports",
+        &token,
+        Some(json!({
+            "latitude": 53.4808,
+            "longitude": -2.2426,
+            "photo_base64": PIXEL_PNG
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = send_json(
+        &app,
+        "GET",
+        "/api/reports/clusters?min_lat=50&min_lon=-3&max_lat=55&max_lon=1",
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    // A 5-degree viewport is about zoom 8, grouped by 4-character geohashes
+    assert_eq!(body["precision"], 4);
+    assert_eq!(body["total"], 3);
+
+    let clusters = body["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[0]["geohash"], "gcpv");
+    assert_eq!(clusters[0]["count"], 2);
+    assert!(clusters[0]["report_id"].is_null());
+    assert_eq!(clusters[1]["count"], 1);
+    assert_eq!(clusters[1]["report_id"], manchester["id"]);
+}
+
+#[tokio::test]
+async fn test_my_reports_pages_by_cursor_without_repeats() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "report_pages@example.com").await;
+
+    let mut created = Vec::new();
+    for _ in 0..3 {
+        created.push(create_test_report(&app, &token).await);
+    }
+    created.reverse();
+
+    let (status, first) =
+        send_json(&app, "GET", "/api/reports/my-reports?limit=2", &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["total"], 3);
+    let cursor = first["next_cursor"].as_str().unwrap().to_string();
+    assert!(cursor.ends_with(created[1].as_str()));
+
+    // A report added after the first page does not shift the second
+    create_test_report(&app, &token).await;
+
+    let (status, second) = send_json(
+        &app,
+        "GET",
+        &format!("/api/reports/my-reports?limit=2&cursor={cursor}"),
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let ids: Vec<&str> = first["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .chain(second["items"].as_array().unwrap())
+        .map(|r| r["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, created.iter().map(String::as_str).collect::<Vec<_>>());
+    assert!(second["next_cursor"].is_null());
+
+    let (status, _) = send_json(
+        &app,
+        "GET",
+        "/api/reports/my-reports?cursor=not-a-cursor",
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_nearby_reports_sort_and_status_filter() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "report_sort@example.com").await;
+
+    let mut ids = Vec::new();
+    for (latitude, longitude) in [(55.98, -3.15), (55.9533, -3.1883), (55.965, -3.17)] {
+        let (status, report) = send_json(
+            &app,
+            "POST",
+            "/api/reports",
+            &token,
+            Some(json!({
+                "latitude": latitude,
+                "longitude": longitude,
+                "photo_base64": PIXEL_PNG
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        ids.push(report["id"].as_str().unwrap().to_string());
+    }
+    let (far, near, middle) = (ids[0].as_str(), ids[1].as_str(), ids[2].as_str());
+
+    let nearby = |query: &str| {
+        let uri =
+            format!("/api/reports/nearby?latitude=55.9533&longitude=-3.1883&radius_km=10{query}");
+        let app = app.clone();
+        let token = token.clone();
+        async move { send_json(&app, "GET", &uri, &token, None).await }
+    };
+    fn item_ids(page: &Value) -> Vec<&str> {
+        page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect()
+    }
+
+    let (_, page) = nearby("").await;
+    assert_eq!(item_ids(&page), vec![middle, near, far]);
+    let (_, page) = nearby("&sort=oldest").await;
+    assert_eq!(item_ids(&page), vec![far, near, middle]);
+
+    let (status, page) = nearby("&sort=distance&limit=2").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item_ids(&page), vec![near, middle]);
+    let cursor = page["next_cursor"].as_str().unwrap().to_string();
+    let (_, page) = nearby(&format!("&sort=distance&limit=2&cursor={cursor}")).await;
+    assert_eq!(item_ids(&page), vec![far]);
+
+    let (_, page) = nearby("&status=cleared,verified").await;
+    assert_eq!(page["total"], 0);
+    let (status, _) = nearby("&status=pending,lost").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = nearby("&sort=sideways").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_out_of_range_coordinates_are_rejected() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "coordinates@example.com").await;
+
+    let (status, _) = send_json(
+        &app,
+        "POST",
+        "/api/reports",
+        &token,
+        Some(json!({
+            "latitude": 999.0,
+            "longitude": -0.1278,
+            "photo_base64": PIXEL_PNG
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, _) = send_json(
+        &app,
+        "POST",
+        "/api/reports",
+        &token,
+        Some(json!({
+            "latitude": 51.5074,
+            "longitude": 180.5,
+            "photo_base64": PIXEL_PNG
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    for uri in [
+        "/api/reports/nearby?latitude=999&longitude=-0.1278",
+        "/api/reports/verification-queue?latitude=51.5&longitude=-181",
+        "/api/reports/in-bounds?min_lat=-91&min_lon=-0.3&max_lat=51.6&max_lon=0.0",
+    ] {
+        let (status, _) = send_json(&app, "GET", uri, &token, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+    }
+
+    // The poles and the antimeridian are still on the map
+    let (status, _) = send_json(
+        &app,
+        "GET",
+        "/api/reports/nearby?latitude=90&longitude=-180",
+        &token,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_reporter_can_edit_pending_report_and_edits_are_kept() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "report_editor@example.com").await;
+    let other = create_verified_user_and_login(&app, "report_edit_other@example.com").await;
+    let report_id = create_test_report(&app, &token).await;
+    let uri = format!("/api/reports/{report_id}");
+
+    let (status, report) = send_json(
+        &app,
+        "PATCH",
+        &uri,
+        &token,
+        Some(json!({ "description": "Two bags of glass", "category": "glass" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["description"], "Two bags of glass");
+    assert_eq!(report["category"], "glass");
+
+    let (status, _) = send_json(&app, "PATCH", &uri, &token, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send_json(
+        &app,
+        "PATCH",
+        &uri,
+        &other,
+        Some(json!({ "description": "Not mine" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send_json(&app, "POST", &format!("{uri}/claim"), &other, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_json(
+        &app,
+        "PATCH",
+        &uri,
+        &token,
+        Some(json!({ "description": "Too late" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let pool = get_test_pool().await;
+    let edits: Vec<(Option<String>, Option<String>, String, String)> = sqlx::query_as(
+        r"
+        SELECT previous_description, new_description,
+               previous_category::text, new_category::text
+        FROM report_edits
+        WHERE report_id = $1::uuid
+        ",
+    )
+    .bind(&report_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        edits,
+        vec![(
+            Some("Test litter".to_string()),
+            Some("Two bags of glass".to_string()),
+            "other".to_string(),
+            "glass".to_string()
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_reporter_can_cancel_pending_report() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "report_canceller@example.com").await;
+    let other = create_verified_user_and_login(&app, "report_cancel_other@example.com").await;
+    let report_id = create_test_report(&app, &token).await;
+    let uri = format!("/api/reports/{report_id}");
+
+    let (status, _) = send_json(&app, "DELETE", &uri, &other, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, report) = send_json(&app, "DELETE", &uri, &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["status"], "cancelled");
+    assert!(report["photo_before"].is_null());
+
+    // Cancelled reports are final and drop out of the map
+    let (status, _) = send_json(&app, "DELETE", &uri, &token, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send_json(&app, "POST", &format!("{uri}/claim"), &other, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (_, nearby) = send_json(
+        &app,
+        "GET",
+        "/api/reports/nearby?latitude=51.5074&longitude=-0.1278&radius_km=5",
+        &token,
+        None,
+    )
+    .await;
+    assert!(nearby["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|r| r["id"] != report_id.as_str()));
+
+    // Its photos are detached; storage keeps only images other reports still use
+    let pool = get_test_pool().await;
+    let photos: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM report_photos WHERE report_id = $1::uuid")
+            .bind(&report_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(photos, 0);
+
+    // The cancellation is on the timeline
+    let (_, timeline) = send_json(&app, "GET", &format!("{uri}/timeline"), &token, None).await;
+    let kinds: Vec<&str> = timeline["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["reported", "cancelled"]);
+}
+
+#[tokio::test]
+async fn test_history_records_each_event_with_actor() {
+    let app = create_test_app().await;
+    let reporter = create_verified_user_and_login(&app, "history_reporter@example.com").await;
+    let picker = create_verified_user_and_login(&app, "history_picker@example.com").await;
+    let report_id = create_test_report(&app, &reporter).await;
+    let uri = format!("/api/reports/{report_id}");
+
+    send_json(&app, "POST", &format!("{uri}/claim"), &picker, None).await;
+    let body = json!({ "reason": "Needs a skip" });
+    send_json(&app, "POST", &format!("{uri}/unclaim"), &picker, Some(body)).await;
+
+    let (status, history) =
+        send_json(&app, "GET", &format!("{uri}/history"), &reporter, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let events = history["events"].as_array().unwrap();
+    let kinds: Vec<&str> = events.iter().map(|e| e["kind"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["created", "claimed", "released"]);
+    assert!(events[0]["from_status"].is_null());
+    assert_eq!(events[0]["metadata"]["category"], "other");
+    assert_eq!(events[1]["actor_id"], events[2]["actor_id"]);
+    assert_eq!(events[2]["from_status"], "claimed");
+    assert_eq!(events[2]["to_status"], "pending");
+    assert_eq!(events[2]["reason"], "Needs a skip");
+
+    let (status, _) = send_json(
+        &app,
+        "GET",
+        &format!("/api/reports/{}/history", uuid::Uuid::new_v4()),
+        &reporter,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_flags_hide_report_until_reviewed() {
+    let app = create_test_app().await;
+    let reporter = create_verified_user_and_login(&app, "flag_reporter@example.com").await;
+    let report_id = create_test_report(&app, &reporter).await;
+    let flag_uri = format!("/api/reports/{report_id}/flag");
+    let body = json!({ "reason": "not_litter", "comment": "Just a garden" });
+
+    let (status, _) = send_json(&app, "POST", &flag_uri, &reporter, Some(body.clone())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // The default REPORT_FLAGS_TO_HIDE is 3
+    for n in 1..=3 {
+        let flagger =
+            create_verified_user_and_login(&app, &format!("flagger{n}@example.com")).await;
+        let (status, flagged) =
+            send_json(&app, "POST", &flag_uri, &flagger, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(flagged["flag"]["reason"], "not_litter");
+        assert_eq!(flagged["report_hidden"], n == 3);
+
+        let (status, _) = send_json(&app, "POST", &flag_uri, &flagger, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    // Hidden reports leave the map and cannot be claimed
+    let claimer = create_verified_user_and_login(&app, "flag_claimer@example.com").await;
+    let (_, nearby) = send_json(
+        &app,
+        "GET",
+        "/api/reports/nearby?latitude=51.5074&longitude=-0.1278&radius_km=5",
+        &claimer,
+        None,
+    )
+    .await;
+    assert!(nearby["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|r| r["id"] != report_id.as_str()));
+    let (status, error) = send_json(
+        &app,
+        "POST",
+        &format!("/api/reports/{report_id}/claim"),
+        &claimer,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error["error"].as_str().unwrap().contains("hidden"));
+}
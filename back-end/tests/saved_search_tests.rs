@@ -0,0 +1,336 @@
+// Integration tests for saved search endpoints
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+/// Helper to create a verified user and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn create_search(app: &axum::Router, token: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/users/me/searches")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_create_and_list_saved_searches() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "searcher@example.com").await;
+
+    let (status, search) = create_search(
+        &app,
+        &token,
+        json!({ "name": "Near the office", "radius_km": 2.5, "statuses": ["pending"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(search["name"], "Near the office");
+    assert_eq!(search["statuses"], json!(["pending"]));
+    assert_eq!(search["notify_on_new"], false);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/users/me/searches")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let searches: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(searches["items"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_saved_search_rejects_invalid_radius() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "badradius@example.com").await;
+
+    let (status, _) = create_search(
+        &app,
+        &token,
+        json!({ "name": "Too far", "radius_km": 500.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_cannot_use_another_users_saved_search() {
+    let app = create_test_app().await;
+    let owner_token = create_verified_user_and_login(&app, "searchowner@example.com").await;
+    let other_token = create_verified_user_and_login(&app, "searchother@example.com").await;
+
+    let (_, search) = create_search(&app, &owner_token, json!({ "name": "Mine" })).await;
+    let search_id = search["id"].as_str().unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!(
+                    "/api/reports/nearby?latitude=51.5074&longitude=-0.1278&search_id={}",
+                    search_id
+                ))
+                .header("authorization", format!("Bearer {}", other_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/users/me/searches/{}", search_id))
+                .header("authorization", format!("Bearer {}", other_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+const TEST_PHOTO: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// An empty stretch of the Indian Ocean no other test puts reports near
+const SEARCH_LATITUDE: f64 = -47.123;
+const SEARCH_LONGITUDE: f64 = 101.456;
+
+async fn send_json(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    token: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn create_report(app: &axum::Router, token: &str, category: &str) -> String {
+    let (status, report) = send_json(
+        app,
+        "POST",
+        "/api/reports",
+        token,
+        json!({
+            "latitude": SEARCH_LATITUDE,
+            "longitude": SEARCH_LONGITUDE + 0.001,
+            "category": category,
+            "photo_base64": TEST_PHOTO
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    report["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_saved_search_categories_filter_nearby() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "searchcategories@example.com").await;
+
+    let (status, _) = create_search(
+        &app,
+        &token,
+        json!({ "name": "Tyres", "categories": ["tyres"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, search) = create_search(
+        &app,
+        &token,
+        json!({ "name": "Glass", "radius_km": 1.0, "categories": ["glass"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(search["categories"], json!(["glass"]));
+
+    let plastic = create_report(&app, &token, "plastic").await;
+    let glass = create_report(&app, &token, "glass").await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/reports/nearby?latitude={}&longitude={}&search_id={}",
+                    SEARCH_LATITUDE,
+                    SEARCH_LONGITUDE,
+                    search["id"].as_str().unwrap()
+                ))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let reports: Value = serde_json::from_slice(&body).unwrap();
+    let ids: Vec<&str> = reports["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["id"].as_str().unwrap())
+        .collect();
+    assert!(ids.contains(&glass.as_str()));
+    assert!(!ids.contains(&plastic.as_str()));
+}
+
+#[tokio::test]
+async fn test_saved_search_notifies_on_matching_reports_near_home() {
+    let app = create_test_app().await;
+    let subscriber = create_verified_user_and_login(&app, "searchsubscriber@example.com").await;
+    let reporter = create_verified_user_and_login(&app, "searchreporter@example.com").await;
+
+    let (status, _) = send_json(
+        &app,
+        "PUT",
+        "/api/users/me/area",
+        &subscriber,
+        json!({ "latitude": SEARCH_LATITUDE, "longitude": SEARCH_LONGITUDE }),
+    )
+    .await;
+    assert!(status.is_success());
+
+    let (status, _) = create_search(
+        &app,
+        &subscriber,
+        json!({ "name": "Glass at home", "radius_km": 1.0, "categories": ["glass"], "notify_on_new": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    create_report(&app, &reporter, "plastic").await;
+    let glass = create_report(&app, &reporter, "glass").await;
+
+    let pool = get_test_pool().await;
+    let notified: Vec<uuid::Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT n.report_id FROM notifications n
+        JOIN users u ON u.id = n.user_id
+        WHERE u.email = $1 AND n.kind = 'saved_search_report'
+        "#,
+    )
+    .bind("searchsubscriber@example.com")
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(notified, vec![glass.parse::<uuid::Uuid>().unwrap()]);
+}
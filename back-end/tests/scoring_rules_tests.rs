@@ -0,0 +1,97 @@
+// Tests for the public scoring rules endpoint
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use back_end::models::scoring_rules::{negotiate_locale, ScoringRule, ScoringRuleKey};
+use serde_json::Value;
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::create_test_app;
+
+#[test]
+fn test_locale_negotiation() {
+    assert_eq!(negotiate_locale(None, None).unwrap(), "en");
+    assert_eq!(negotiate_locale(Some("EN"), Some("fr")).unwrap(), "en");
+    assert!(negotiate_locale(Some("fr"), None).is_err());
+
+    // Unsupported languages fall back rather than fail
+    assert_eq!(
+        negotiate_locale(None, Some("fr-FR, de;q=0.8")).unwrap(),
+        "en"
+    );
+    assert_eq!(
+        negotiate_locale(None, Some("fr;q=0.9, en-GB;q=0.5")).unwrap(),
+        "en"
+    );
+    assert_eq!(negotiate_locale(None, Some("en;q=0, fr")).unwrap(), "en");
+}
+
+#[test]
+fn test_rules_describe_their_values() {
+    let rule = ScoringRule::new(ScoringRuleKey::MediumSeverityMultiplier, 1.5, "en");
+    assert_eq!(
+        rule.description,
+        "Clearing a medium report earns 1.5× the clearing points."
+    );
+
+    let rule = ScoringRule::new(ScoringRuleKey::ReportPoints, 10.0, "en");
+    assert_eq!(
+        rule.description,
+        "Earn 10 points for each litter report you submit."
+    );
+}
+
+#[tokio::test]
+async fn test_scoring_rules_reflect_the_configuration() {
+    let app = create_test_app().await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/scoring/rules")
+                .header(header::ACCEPT_LANGUAGE, "fr-FR, en;q=0.5")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::VARY], "Accept-Language");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let rules: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(rules["locale"], "en");
+
+    let rules = rules["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 14);
+    let rule = |key: &str| {
+        rules
+            .iter()
+            .find(|rule| rule["key"] == key)
+            .unwrap_or_else(|| panic!("missing rule {key}"))
+    };
+    // Values from .env.test
+    assert_eq!(rule("base_points_per_clear")["value"], 10.0);
+    assert_eq!(rule("base_points_per_clear")["category"], "points");
+    assert_eq!(rule("large_severity_multiplier")["value"], 2.0);
+    assert_eq!(rule("large_severity_multiplier")["unit"], "multiplier");
+    assert_eq!(rule("min_clears_to_verify")["value"], 5.0);
+    assert_eq!(rule("min_clears_to_verify")["unit"], "clears");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/scoring/rules?locale=xx")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,239 @@
+// Tests for the public stats summary served on the landing page
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use back_end::services::StatsService;
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool, insert_user};
+
+async fn create_report(pool: &PgPool, reporter_id: Uuid, cleared_by: Option<Uuid>) {
+    sqlx::query(
+        r#"
+        INSERT INTO litter_reports
+            (reporter_id, location, photo_before, status, claimed_by, claimed_at,
+             cleared_by, cleared_at, photo_after)
+        VALUES ($1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'before.webp',
+                CASE WHEN $2::uuid IS NULL THEN 'pending' ELSE 'cleared' END::report_status,
+                $2, CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END,
+                $2, CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END,
+                CASE WHEN $2::uuid IS NULL THEN NULL ELSE 'after.webp' END)
+        "#,
+    )
+    .bind(reporter_id)
+    .bind(cleared_by)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_stats_summary_is_public_and_cached() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    let reporter = insert_user(&pool, "stats-reporter@example.com").await;
+    let picker = insert_user(&pool, "stats-picker@example.com").await;
+    insert_user(&pool, "stats-idle@example.com").await;
+    create_report(&pool, reporter, None).await;
+    create_report(&pool, reporter, Some(picker)).await;
+
+    let fetch = || async {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/stats/summary")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=300"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let summary = fetch().await;
+    assert_eq!(summary["total_reports"], 2);
+    assert_eq!(summary["cleared_reports"], 1);
+    assert_eq!(summary["verified_reports"], 0);
+    // Only users who reported or cleared something count
+    assert_eq!(summary["volunteers"], 2);
+
+    // Served from memory until the next refresh
+    create_report(&pool, reporter, None).await;
+    assert_eq!(fetch().await["total_reports"], 2);
+
+    let refreshed = StatsService::new(pool.clone()).refresh().await.unwrap();
+    assert_eq!(refreshed.total_reports, 3);
+}
+
+#[test]
+fn test_hotspot_trend_needs_a_quarter_change() {
+    use back_end::models::stats::HotspotTrend;
+
+    assert_eq!(HotspotTrend::from_counts(3, 0), HotspotTrend::Rising);
+    assert_eq!(HotspotTrend::from_counts(5, 4), HotspotTrend::Rising);
+    assert_eq!(HotspotTrend::from_counts(4, 4), HotspotTrend::Steady);
+    assert_eq!(HotspotTrend::from_counts(7, 8), HotspotTrend::Steady);
+    assert_eq!(HotspotTrend::from_counts(3, 4), HotspotTrend::Falling);
+}
+
+async fn create_city_report(pool: &PgPool, longitude: f64, city: &str, days_ago: i32) {
+    sqlx::query(
+        r#"
+        INSERT INTO litter_reports (location, status, address_city, created_at)
+        VALUES (ST_SetSRID(ST_MakePoint($1, 51.5074), 4326), 'pending', $2,
+                NOW() - make_interval(days => $3))
+        "#,
+    )
+    .bind(longitude)
+    .bind(city)
+    .bind(days_ago)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_hotspots_rank_cells_and_compare_windows() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    // Three recent reports in one spot, one a month earlier
+    for _ in 0..3 {
+        create_city_report(&pool, -0.1278, "London", 2).await;
+    }
+    create_city_report(&pool, -0.1278, "London", 40).await;
+    // About 7km east: one recent report, two earlier
+    create_city_report(&pool, -0.0278, "London", 5).await;
+    create_city_report(&pool, -0.0278, "London", 35).await;
+    create_city_report(&pool, -0.0278, "London", 50).await;
+    create_city_report(&pool, -0.1278, "Paris", 1).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/stats/hotspots?city=london&window_days=30&cell_size_m=500")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key(header::CACHE_CONTROL));
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    let hotspots = json["hotspots"].as_array().unwrap();
+    assert_eq!(hotspots.len(), 2);
+
+    assert_eq!(hotspots[0]["reports"], 3);
+    assert_eq!(hotspots[0]["previous_reports"], 1);
+    assert_eq!(hotspots[0]["trend"], "rising");
+    assert!(hotspots[0]["boundary"].as_array().unwrap().len() >= 7);
+
+    assert_eq!(hotspots[1]["reports"], 1);
+    assert_eq!(hotspots[1]["previous_reports"], 2);
+    assert_eq!(hotspots[1]["trend"], "falling");
+}
+
+async fn create_spot_report(
+    pool: &PgPool,
+    latitude: f64,
+    longitude: f64,
+    status: &str,
+    days_ago: i32,
+) {
+    sqlx::query(
+        r#"
+        INSERT INTO litter_reports (location, status, created_at, cleared_at)
+        VALUES (ST_SetSRID(ST_MakePoint($2, $1), 4326), $3::report_status,
+                NOW() - make_interval(days => $4),
+                CASE WHEN $3 IN ('cleared', 'verified')
+                     THEN NOW() - make_interval(days => $4) + INTERVAL '1 hour' END)
+        "#,
+    )
+    .bind(latitude)
+    .bind(longitude)
+    .bind(status)
+    .bind(days_ago)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn location_history(app: &axum::Router, query: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/locations/history?{query}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_location_history_counts_repeat_reports() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    // A spot in the South Pacific no other test uses: three reports in the last few
+    // months, one long ago, and one cancelled
+    let (lat, lon) = (-47.25, -139.75);
+    create_spot_report(&pool, lat, lon, "verified", 5).await;
+    create_spot_report(&pool, lat + 0.0001, lon, "cleared", 60).await;
+    create_spot_report(&pool, lat, lon + 0.0001, "pending", 1).await;
+    create_spot_report(&pool, lat, lon, "cleared", 800).await;
+    create_spot_report(&pool, lat, lon, "cancelled", 2).await;
+    // About 1km away
+    create_spot_report(&pool, lat + 0.01, lon, "pending", 1).await;
+
+    let (status, history) = location_history(&app, &format!("lat={lat}&lon={lon}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(history["reports"], 4);
+    assert_eq!(history["cleared"], 3);
+    assert_eq!(history["open_reports"], 1);
+    assert_eq!(history["repeat_offender"], true);
+
+    // The report from two years ago is outside the monthly window
+    let months = history["months"].as_array().unwrap();
+    let reported: i64 = months.iter().map(|m| m["reported"].as_i64().unwrap()).sum();
+    let cleared: i64 = months.iter().map(|m| m["cleared"].as_i64().unwrap()).sum();
+    assert_eq!(reported, 3);
+    assert_eq!(cleared, 2);
+
+    // A tighter radius around the far report only finds it
+    let (_, history) =
+        location_history(&app, &format!("lat={}&lon={lon}&radius=10", lat + 0.01)).await;
+    assert_eq!(history["reports"], 1);
+    assert_eq!(history["repeat_offender"], false);
+
+    let (status, _) = location_history(&app, &format!("lat={lat}&lon={lon}&radius=5000")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = location_history(&app, "lat=95&lon=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,136 @@
+// Tests for the object storage bucket layout and key prefixes
+
+use back_end::config::{S3BucketConfig, S3Config};
+use back_end::services::{S3Service, StorageArea};
+
+fn bucket(name: &str, public_url: &str) -> S3BucketConfig {
+    S3BucketConfig {
+        bucket: name.to_string(),
+        public_url: public_url.to_string(),
+    }
+}
+
+async fn service(key_prefix: &str, exports: S3BucketConfig) -> S3Service {
+    let shared = bucket("layout-shared", "http://127.0.0.1:9000/layout-shared");
+    S3Service::new(S3Config {
+        endpoint: "http://127.0.0.1:9000".to_string(),
+        region: "us-east-1".to_string(),
+        access_key: "minioadmin".to_string(),
+        secret_key: "minioadmin123".to_string(),
+        key_prefix: key_prefix.to_string(),
+        spill_max_mb: 0,
+        archive_storage_class: None,
+        avatars: bucket("layout-avatars", "https://cdn.example.com/avatars"),
+        report_photos: shared.clone(),
+        exports,
+    })
+    .await
+    .expect("Failed to create S3 service")
+}
+
+#[tokio::test]
+async fn test_object_keys_carry_the_prefix() {
+    let shared = bucket("layout-shared", "http://127.0.0.1:9000/layout-shared");
+
+    let prefixed = service("staging", shared.clone()).await;
+    assert_eq!(
+        prefixed.object_key("images/a.webp"),
+        "staging/images/a.webp"
+    );
+
+    let unprefixed = service("", shared).await;
+    assert_eq!(unprefixed.object_key("images/a.webp"), "images/a.webp");
+}
+
+#[tokio::test]
+async fn test_urls_resolve_to_their_bucket() {
+    let s3 = service(
+        "staging",
+        bucket("layout-exports", "http://127.0.0.1:9000/layout-exports"),
+    )
+    .await;
+
+    let photo_url = s3.public_url(StorageArea::ReportPhotos, "staging/images/a.webp");
+    assert_eq!(
+        s3.extract_key_from_url(&photo_url),
+        Some((
+            StorageArea::ReportPhotos,
+            "staging/images/a.webp".to_string()
+        ))
+    );
+
+    let avatar_url = s3.public_url(StorageArea::Avatars, "staging/u/1.webp");
+    assert_eq!(
+        avatar_url,
+        "https://cdn.example.com/avatars/staging/u/1.webp"
+    );
+    assert_eq!(
+        s3.extract_key_from_url(&avatar_url),
+        Some((StorageArea::Avatars, "staging/u/1.webp".to_string()))
+    );
+
+    // Objects stored before the prefix was introduced still resolve
+    assert_eq!(
+        s3.extract_key_from_url("http://127.0.0.1:9000/layout-shared/images/old.webp"),
+        Some((StorageArea::ReportPhotos, "images/old.webp".to_string()))
+    );
+
+    assert_eq!(
+        s3.extract_key_from_url("http://127.0.0.1:9000/layout-shared-2/x.webp"),
+        None
+    );
+    assert_eq!(
+        s3.extract_key_from_url("https://elsewhere.example.com/x.webp"),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_nested_public_urls_pick_the_longest_match() {
+    // Exports served from a sub-path of the shared bucket's URL
+    let s3 = service(
+        "",
+        bucket(
+            "layout-exports",
+            "http://127.0.0.1:9000/layout-shared/exports-cdn",
+        ),
+    )
+    .await;
+
+    assert_eq!(
+        s3.extract_key_from_url("http://127.0.0.1:9000/layout-shared/exports-cdn/e.json"),
+        Some((StorageArea::Exports, "e.json".to_string()))
+    );
+    assert_eq!(
+        s3.extract_key_from_url("http://127.0.0.1:9000/layout-shared/images/a.webp"),
+        Some((StorageArea::ReportPhotos, "images/a.webp".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_areas_write_to_their_own_buckets() {
+    let s3 = service(
+        "tenant-a",
+        bucket("layout-exports", "http://127.0.0.1:9000/layout-exports"),
+    )
+    .await;
+    s3.initialize().await.expect("Failed to initialize buckets");
+
+    let key = s3.object_key("exports/e.json");
+    s3.put_object(
+        StorageArea::Exports,
+        &key,
+        b"{}".to_vec(),
+        "application/json",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        s3.get_image(StorageArea::Exports, &key).await.unwrap(),
+        b"{}"
+    );
+    assert!(s3.get_image(StorageArea::ReportPhotos, &key).await.is_err());
+
+    s3.delete_image(StorageArea::Exports, &key).await.unwrap();
+}
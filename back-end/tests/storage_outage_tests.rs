@@ -0,0 +1,98 @@
+// Tests for accepting report photos while object storage is unreachable
+
+use back_end::config::{S3BucketConfig, S3Config};
+use back_end::models::image::ImageMetadata;
+use back_end::services::{ImageStorageService, S3Service};
+use rand::RngCore;
+
+mod helpers;
+use helpers::setup_test_db;
+
+/// A store nothing listens on, so every upload fails
+async fn unreachable_s3() -> S3Service {
+    let bucket = S3BucketConfig {
+        bucket: "outage-photos".to_string(),
+        public_url: "http://127.0.0.1:1/outage-photos".to_string(),
+    };
+    S3Service::new(S3Config {
+        endpoint: "http://127.0.0.1:1".to_string(),
+        region: "us-east-1".to_string(),
+        access_key: "minioadmin".to_string(),
+        secret_key: "minioadmin123".to_string(),
+        key_prefix: String::new(),
+        spill_max_mb: 0,
+        archive_storage_class: None,
+        avatars: bucket.clone(),
+        report_photos: bucket.clone(),
+        exports: bucket,
+    })
+    .await
+    .expect("Failed to create S3 service")
+}
+
+/// Bytes no other test run has stored
+fn random_image() -> Vec<u8> {
+    let mut data = vec![0u8; 256];
+    rand::thread_rng().fill_bytes(&mut data);
+    data
+}
+
+#[test]
+fn test_processing_is_only_serialized_while_pending() {
+    let metadata = ImageMetadata {
+        url: "https://photos.example.com/images/abc.webp".to_string(),
+        width: Some(640),
+        height: Some(480),
+        blurhash: None,
+        processing: false,
+    };
+    let json = serde_json::to_value(&metadata).unwrap();
+    assert!(json.get("processing").is_none());
+
+    let json = serde_json::to_value(ImageMetadata {
+        processing: true,
+        ..metadata
+    })
+    .unwrap();
+    assert_eq!(json["processing"], true);
+}
+
+#[tokio::test]
+async fn test_photos_are_held_while_storage_is_down() {
+    let pool = setup_test_db().await;
+    let storage = ImageStorageService::new(pool, unreachable_s3().await, 16);
+    let data = random_image();
+
+    let stored = storage.store(data.clone()).await.expect("held image");
+    assert!(stored.pending);
+    assert_eq!(
+        storage.pending_image(&stored.url).await.unwrap(),
+        Some(data.clone())
+    );
+    let pending = storage
+        .pending_urls(&[
+            stored.url.clone(),
+            "https://elsewhere.example/x.webp".into(),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert!(pending.contains(&stored.url));
+
+    // The same bytes again reuse the held image
+    let again = storage.store(data).await.expect("held image");
+    assert_eq!(again.content_hash, stored.content_hash);
+    assert!(again.pending);
+
+    // Storage is still down, so the retry leaves it held
+    storage.upload_pending().await.unwrap();
+    assert!(storage.pending_image(&stored.url).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_uploads_fail_when_holding_is_disabled() {
+    let pool = setup_test_db().await;
+    let storage = ImageStorageService::new(pool, unreachable_s3().await, 0);
+
+    assert!(storage.store(random_image()).await.is_err());
+}
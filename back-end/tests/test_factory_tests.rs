@@ -0,0 +1,182 @@
+// Tests for the test-helper data factories
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::create_test_app;
+
+/// Matches TEST_HELPERS_SECRET in .env.test
+const SECRET: &str = "test-helpers-secret";
+
+async fn call(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+    body: Value,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("X-Test-Helpers-Secret", SECRET);
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {token}"));
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_factories_make_a_user_ready_to_verify() {
+    let app = create_test_app().await;
+
+    let (status, created) = call(
+        &app,
+        "POST",
+        "/api/test/users",
+        None,
+        json!({ "count": 3, "email_prefix": "factory-verify" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let users = created["users"].as_array().unwrap();
+    assert_eq!(users.len(), 3);
+    let email = |i: usize| users[i]["email"].as_str().unwrap().to_string();
+    let (reporter, cleaner, verifier) = (email(0), email(1), email(2));
+
+    let (status, report) = call(
+        &app,
+        "POST",
+        "/api/test/reports",
+        None,
+        json!({
+            "reporter_email": reporter,
+            "claimer_email": cleaner,
+            "latitude": 51.5074,
+            "longitude": -0.1278,
+            "status": "cleared"
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(report["status"], "cleared");
+    assert_eq!(report["cleared_by"], users[1]["id"]);
+    let report_id = report["id"].as_str().unwrap();
+
+    let (status, clears) = call(
+        &app,
+        "POST",
+        &format!("/api/test/users/{verifier}/clears"),
+        None,
+        json!({ "count": 5 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(clears["total_clears"], 5);
+
+    // Factory users sign in with the shared password straight away
+    let (status, login) = call(
+        &app,
+        "POST",
+        "/api/auth/login",
+        None,
+        json!({ "email": verifier, "password": created["password"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let token = login["access_token"].as_str().unwrap();
+
+    let (status, _) = call(
+        &app,
+        "POST",
+        &format!("/api/reports/{report_id}/verify"),
+        Some(token),
+        json!({ "is_verified": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Like registered users, factory users start with a score
+    let (status, login) = call(
+        &app,
+        "POST",
+        "/api/auth/login",
+        None,
+        json!({ "email": reporter, "password": created["password"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, score) = call(
+        &app,
+        "GET",
+        "/api/users/me/score",
+        login["access_token"].as_str(),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(score["total_points"], 0);
+}
+
+#[tokio::test]
+async fn test_report_factory_needs_a_claimer_unless_pending() {
+    let app = create_test_app().await;
+
+    let (status, _) = call(
+        &app,
+        "POST",
+        "/api/test/reports",
+        None,
+        json!({ "latitude": 51.5, "longitude": -0.12, "status": "claimed" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, report) = call(
+        &app,
+        "POST",
+        "/api/test/reports",
+        None,
+        json!({ "latitude": 51.5, "longitude": -0.12 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(report["status"], "pending");
+    assert!(report["reporter_id"].is_null());
+}
+
+#[tokio::test]
+async fn test_swagger_session_user_has_a_score() {
+    let app = create_test_app().await;
+
+    let (status, session) =
+        call(&app, "POST", "/api/test/swagger-session", None, Value::Null).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, score) = call(
+        &app,
+        "GET",
+        "/api/users/me/score",
+        session["tokens"]["access_token"].as_str(),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(score["total_points"], 0);
+}
@@ -0,0 +1,97 @@
+// Tests for the allowlist and shared secret guarding /api/test/*
+
+use axum::{
+    body::Body,
+    extract::connect_info::MockConnectInfo,
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use back_end::config::{IpNetwork, TestHelpersConfig};
+use back_end::handlers;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn guarded_router(peer: &str) -> Router {
+    let config = TestHelpersConfig {
+        allowed_networks: vec![
+            "127.0.0.1".parse().unwrap(),
+            "10.20.0.0/16".parse().unwrap(),
+        ],
+        secret: "s3cret".to_string(),
+    };
+
+    Router::new()
+        .route("/api/test/status", get(handlers::test_status))
+        .route_layer(axum::middleware::from_fn_with_state(
+            Arc::new(config),
+            handlers::require_test_helper_access,
+        ))
+        .layer(MockConnectInfo(peer.parse::<SocketAddr>().unwrap()))
+}
+
+async fn status(peer: &str, secret: Option<&str>) -> StatusCode {
+    let mut request = Request::builder().uri("/api/test/status");
+    if let Some(secret) = secret {
+        request = request.header("X-Test-Helpers-Secret", secret);
+    }
+
+    guarded_router(peer)
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_allowed_caller_with_secret_is_admitted() {
+    assert_eq!(
+        status("127.0.0.1:5000", Some("s3cret")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status("10.20.3.4:5000", Some("s3cret")).await,
+        StatusCode::OK
+    );
+    // Dual-stack sockets report IPv4 peers as IPv4-mapped IPv6 addresses
+    assert_eq!(
+        status("[::ffff:127.0.0.1]:5000", Some("s3cret")).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn test_wrong_secret_or_address_is_refused() {
+    assert_eq!(status("127.0.0.1:5000", None).await, StatusCode::FORBIDDEN);
+    assert_eq!(
+        status("127.0.0.1:5000", Some("guess")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status("10.21.0.1:5000", Some("s3cret")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status("203.0.113.9:5000", Some("s3cret")).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[test]
+fn test_network_parsing() {
+    let network: IpNetwork = "192.168.1.0/24".parse().unwrap();
+    assert!(network.contains("192.168.1.200".parse().unwrap()));
+    assert!(!network.contains("192.168.2.1".parse().unwrap()));
+    assert!(!network.contains("::1".parse().unwrap()));
+
+    let v6: IpNetwork = "fd00::/8".parse().unwrap();
+    assert!(v6.contains("fd12:3456::1".parse().unwrap()));
+
+    let everything: IpNetwork = "0.0.0.0/0".parse().unwrap();
+    assert!(everything.matches_everything());
+    assert!(everything.contains("8.8.8.8".parse().unwrap()));
+
+    assert!("10.0.0.0/33".parse::<IpNetwork>().is_err());
+    assert!("not-an-ip".parse::<IpNetwork>().is_err());
+}
@@ -0,0 +1,202 @@
+// Tests for machine translation of report descriptions
+
+use axum::{
+    async_trait,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use back_end::error::AppError;
+use back_end::models::translation::parse_language_code;
+use back_end::services::translation_service::{TranslatedText, TranslationService, Translator};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+const TEST_PHOTO: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// Upper-cases text and counts how often it is asked
+#[derive(Default)]
+struct ShoutingTranslator {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl Translator for ShoutingTranslator {
+    fn name(&self) -> &'static str {
+        "shouting"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        _target_language: &str,
+    ) -> Result<TranslatedText, AppError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(TranslatedText {
+            text: text.to_uppercase(),
+            source_language: Some("es".to_string()),
+        })
+    }
+}
+
+/// Helper to create a verified user and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn post_json(app: &axum::Router, uri: &str, token: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[test]
+fn test_language_codes() {
+    assert_eq!(parse_language_code("en").unwrap(), "en");
+    assert_eq!(parse_language_code("PT-br").unwrap(), "pt-BR");
+    for code in ["", "e", "eng", "en_GB", "en-", "1a", "en-GBR"] {
+        assert!(
+            parse_language_code(code).is_err(),
+            "{code} should be invalid"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_translations_are_stored_until_the_description_changes() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "translate@example.com").await;
+
+    let (status, report) = post_json(
+        &app,
+        "/api/reports",
+        &token,
+        json!({
+            "latitude": 51.5074,
+            "longitude": -0.1278,
+            "description": "Vidrio roto",
+            "photo_base64": TEST_PHOTO
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let report_id: Uuid = report["id"].as_str().unwrap().parse().unwrap();
+
+    // The test config has no provider
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/reports/{report_id}?translate_to=en"))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let translator = Arc::new(ShoutingTranslator::default());
+    let service = TranslationService::with_translator(get_test_pool().await, translator.clone());
+
+    for _ in 0..2 {
+        let translation = service
+            .translate_description(report_id, "Vidrio roto", "en")
+            .await
+            .unwrap();
+        assert_eq!(translation.text, "VIDRIO ROTO");
+        assert_eq!(translation.source_language.as_deref(), Some("es"));
+        assert_eq!(translation.target_language, "en");
+    }
+    assert_eq!(translator.calls.load(Ordering::SeqCst), 1);
+
+    let translation = service
+        .translate_description(report_id, "Vidrio roto y latas", "en")
+        .await
+        .unwrap();
+    assert_eq!(translation.text, "VIDRIO ROTO Y LATAS");
+    assert_eq!(translator.calls.load(Ordering::SeqCst), 2);
+
+    service
+        .translate_description(report_id, "Vidrio roto y latas", "fr")
+        .await
+        .unwrap();
+    assert_eq!(translator.calls.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,270 @@
+// Integration tests for TOTP two-factor authentication
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use base64::{engine::general_purpose, Engine};
+use serde_json::{json, Value};
+use totp_rs::{Algorithm, Secret, TOTP};
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+/// Helper to create a verified user in an existing app and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let (status, body) = login(app, email).await;
+    assert_eq!(status, StatusCode::OK);
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+async fn login(app: &axum::Router, email: &str) -> (StatusCode, Value) {
+    post_json(
+        app,
+        "/api/auth/login",
+        None,
+        json!({ "email": email, "password": "password123" }),
+    )
+    .await
+}
+
+async fn post_json(
+    app: &axum::Router,
+    uri: &str,
+    token: Option<&str>,
+    body: Value,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {}", token));
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn current_code(secret: &str) -> String {
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        Secret::Encoded(secret.to_string()).to_bytes().unwrap(),
+        None,
+        "test".to_string(),
+    )
+    .unwrap();
+    totp.generate_current().unwrap()
+}
+
+fn jwt_claims(token: &str) -> Value {
+    let payload = token.split('.').nth(1).unwrap();
+    let bytes = general_purpose::URL_SAFE_NO_PAD.decode(payload).unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Enable 2FA for a logged-in user, returning the secret and backup codes
+async fn enable_two_factor(app: &axum::Router, token: &str) -> (String, Vec<String>) {
+    let (status, setup) = post_json(app, "/api/auth/2fa/setup", Some(token), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let secret = setup["secret"].as_str().unwrap().to_string();
+    assert!(setup["otpauth_url"]
+        .as_str()
+        .unwrap()
+        .starts_with("otpauth://totp/"));
+
+    let (status, body) = post_json(
+        app,
+        "/api/auth/2fa/verify",
+        Some(token),
+        json!({ "code": current_code(&secret) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let backup_codes = body["backup_codes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_str().unwrap().to_string())
+        .collect();
+    (secret, backup_codes)
+}
+
+#[tokio::test]
+async fn test_login_requires_second_factor_once_enabled() {
+    let app = create_test_app().await;
+    let email = "totp-login@example.com";
+    let token = create_verified_user_and_login(&app, email).await;
+
+    let (_, backup_codes) = enable_two_factor(&app, &token).await;
+    assert_eq!(backup_codes.len(), 10);
+
+    // Password alone now yields a challenge instead of tokens
+    let (status, body) = login(&app, email).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["two_factor_required"], true);
+    assert!(body.get("access_token").is_none());
+    let challenge = body["challenge_token"].as_str().unwrap().to_string();
+
+    let (status, _) = post_json(
+        &app,
+        "/api/auth/2fa/login",
+        None,
+        json!({ "challenge_token": challenge, "code": "000000" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // A backup code completes the challenge and the session is marked as 2FA-verified
+    let (status, body) = post_json(
+        &app,
+        "/api/auth/2fa/login",
+        None,
+        json!({ "challenge_token": challenge, "code": backup_codes[0] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let claims = jwt_claims(body["access_token"].as_str().unwrap());
+    assert_eq!(claims["two_factor_verified"], true);
+
+    // Challenges and backup codes are single-use
+    let (status, _) = post_json(
+        &app,
+        "/api/auth/2fa/login",
+        None,
+        json!({ "challenge_token": challenge, "code": backup_codes[1] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (_, body) = login(&app, email).await;
+    let challenge = body["challenge_token"].as_str().unwrap().to_string();
+    let (status, _) = post_json(
+        &app,
+        "/api/auth/2fa/login",
+        None,
+        json!({ "challenge_token": challenge, "code": backup_codes[0] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_verify_rejects_wrong_code() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "totp-wrong@example.com").await;
+
+    let (status, _) = post_json(&app, "/api/auth/2fa/setup", Some(&token), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = post_json(
+        &app,
+        "/api/auth/2fa/verify",
+        Some(&token),
+        json!({ "code": "not-a-code" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Still disabled, so password login returns tokens directly
+    let (status, body) = login(&app, "totp-wrong@example.com").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        jwt_claims(body["access_token"].as_str().unwrap())["two_factor_verified"],
+        false
+    );
+}
+
+#[tokio::test]
+async fn test_disable_two_factor() {
+    let app = create_test_app().await;
+    let email = "totp-disable@example.com";
+    let token = create_verified_user_and_login(&app, email).await;
+    let (_, backup_codes) = enable_two_factor(&app, &token).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/auth/2fa")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let status: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status["enabled"], true);
+
+    // The enrolment code was already used this time step, so disable with a backup code
+    let (status, _) = post_json(
+        &app,
+        "/api/auth/2fa/disable",
+        Some(&token),
+        json!({ "code": backup_codes[0] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = login(&app, email).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["access_token"].is_string());
+}
+
+#[tokio::test]
+async fn test_two_factor_setup_requires_auth() {
+    let app = create_test_app().await;
+
+    let (status, _) = post_json(&app, "/api/auth/2fa/setup", None, json!({})).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
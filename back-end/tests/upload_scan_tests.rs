@@ -0,0 +1,176 @@
+// Tests for malware scanning of uploads before they are stored
+
+use axum::async_trait;
+use back_end::config::ImageConfig;
+use back_end::services::upload_scan_service::{
+    parse_clamd_reply, ClamAvScanner, MalwareScanner, ScanVerdict,
+};
+use back_end::services::{ImageService, UploadScanService};
+use rand::RngCore;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+mod helpers;
+use helpers::setup_test_db;
+
+const TEST_PHOTO: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// Gives every upload the same verdict, or fails to reach one
+enum FakeScanner {
+    Clean,
+    Infected,
+    Unreachable,
+}
+
+#[async_trait]
+impl MalwareScanner for FakeScanner {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn scan(&self, _data: &[u8]) -> anyhow::Result<ScanVerdict> {
+        match self {
+            Self::Clean => Ok(ScanVerdict::Clean),
+            Self::Infected => Ok(ScanVerdict::Infected("Test.Fake-Signature".to_string())),
+            Self::Unreachable => anyhow::bail!("scanner unreachable"),
+        }
+    }
+}
+
+/// A pool that never connects, for checks that should not touch the database
+fn unused_pool() -> PgPool {
+    PgPool::connect_lazy("postgres://unused@127.0.0.1:1/unused").unwrap()
+}
+
+fn image_service(scanner: UploadScanService) -> ImageService {
+    ImageService::new(ImageConfig {
+        max_size_mb: 5,
+        webp_quality: 80.0,
+        max_width: 1920,
+        max_height: 1920,
+    })
+    .with_scanner(scanner)
+}
+
+/// Answers one `INSTREAM` request like clamd, returning the bytes it was sent
+async fn fake_clamd(listener: TcpListener, reply: &'static str) -> Vec<u8> {
+    let (mut socket, _) = listener.accept().await.unwrap();
+    let mut command = [0u8; 10];
+    socket.read_exact(&mut command).await.unwrap();
+    assert_eq!(&command, b"zINSTREAM\0");
+
+    let mut received = Vec::new();
+    loop {
+        let mut len = [0u8; 4];
+        socket.read_exact(&mut len).await.unwrap();
+        let len = u32::from_be_bytes(len) as usize;
+        if len == 0 {
+            break;
+        }
+        let mut chunk = vec![0u8; len];
+        socket.read_exact(&mut chunk).await.unwrap();
+        received.extend_from_slice(&chunk);
+    }
+
+    socket.write_all(reply.as_bytes()).await.unwrap();
+    received
+}
+
+#[test]
+fn test_clamd_replies_are_parsed() {
+    assert_eq!(
+        parse_clamd_reply("stream: OK\0").unwrap(),
+        ScanVerdict::Clean
+    );
+    assert_eq!(
+        parse_clamd_reply("stream: Win.Test.EICAR_HDB-1 FOUND\0").unwrap(),
+        ScanVerdict::Infected("Win.Test.EICAR_HDB-1".to_string())
+    );
+    assert!(parse_clamd_reply("INSTREAM size limit exceeded. ERROR\0").is_err());
+    assert!(parse_clamd_reply("").is_err());
+}
+
+#[tokio::test]
+async fn test_clamav_scanner_streams_the_upload_in_chunks() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let clamd = tokio::spawn(fake_clamd(listener, "stream: Eicar-Signature FOUND\0"));
+
+    // Larger than one chunk, so the stream is split
+    let mut data = vec![0u8; 150 * 1024];
+    rand::thread_rng().fill_bytes(&mut data);
+    let scanner = ClamAvScanner::new(address, Duration::from_secs(5));
+    let verdict = scanner.scan(&data).await.unwrap();
+
+    assert_eq!(
+        verdict,
+        ScanVerdict::Infected("Eicar-Signature".to_string())
+    );
+    assert_eq!(clamd.await.unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_clean_uploads_are_processed() {
+    let scanner =
+        UploadScanService::with_scanner(unused_pool(), Arc::new(FakeScanner::Clean), false);
+    let processed = image_service(scanner.clone())
+        .process_image(TEST_PHOTO.to_string())
+        .await
+        .expect("processed image");
+
+    assert_eq!((processed.width, processed.height), (1, 1));
+    let counts = scanner.counts();
+    assert_eq!((counts.scanned, counts.clean, counts.infected), (1, 1, 0));
+}
+
+#[tokio::test]
+async fn test_unreachable_scanner_refuses_uploads_unless_failing_open() {
+    let closed =
+        UploadScanService::with_scanner(unused_pool(), Arc::new(FakeScanner::Unreachable), false);
+    assert!(image_service(closed.clone())
+        .process_image(TEST_PHOTO.to_string())
+        .await
+        .is_err());
+    assert_eq!(closed.counts().errors, 1);
+
+    let open =
+        UploadScanService::with_scanner(unused_pool(), Arc::new(FakeScanner::Unreachable), true);
+    image_service(open.clone())
+        .process_image(TEST_PHOTO.to_string())
+        .await
+        .expect("unscanned image");
+    let counts = open.counts();
+    assert_eq!((counts.errors, counts.skipped), (1, 1));
+}
+
+#[tokio::test]
+async fn test_infected_uploads_are_quarantined() {
+    let pool = setup_test_db().await;
+    let scanner =
+        UploadScanService::with_scanner(pool.clone(), Arc::new(FakeScanner::Infected), false);
+
+    let result = image_service(scanner.clone())
+        .process_image(TEST_PHOTO.to_string())
+        .await;
+    assert!(result.is_err());
+    assert_eq!(scanner.counts().infected, 1);
+
+    let report = scanner.report(100).await.unwrap();
+    assert_eq!(report.scanner.as_deref(), Some("fake"));
+    let quarantined = report
+        .quarantined
+        .iter()
+        .find(|upload| upload.signature == "Test.Fake-Signature")
+        .expect("quarantined upload");
+    assert_eq!(quarantined.scanner, "fake");
+
+    let data = scanner.quarantined_data(quarantined.id).await.unwrap();
+    assert_eq!(i64::try_from(data.len()).unwrap(), quarantined.size_bytes);
+
+    scanner.delete_quarantined(quarantined.id).await.unwrap();
+    assert!(scanner.quarantined_data(quarantined.id).await.is_err());
+    assert!(scanner.delete_quarantined(quarantined.id).await.is_err());
+}
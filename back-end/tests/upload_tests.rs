@@ -0,0 +1,337 @@
+// Integration tests for resumable chunked photo uploads
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use base64::{engine::general_purpose, Engine};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+const PNG_BASE64: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// Helper to create a verified user in an existing app and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let (status, _) = send(
+        app,
+        "POST",
+        "/api/auth/register",
+        None,
+        Body::from(
+            json!({
+                "email": email,
+                "password": "password123",
+                "full_name": "Test User",
+                "city": "London",
+                "country": "UK"
+            })
+            .to_string(),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let (status, body) = send(
+        app,
+        "POST",
+        "/api/auth/login",
+        None,
+        Body::from(json!({ "email": email, "password": "password123" }).to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+async fn send(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+    body: Body,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {}", token));
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn start_upload(app: &axum::Router, token: &str, total_size: usize) -> String {
+    let (status, body) = send(
+        app,
+        "POST",
+        "/api/uploads",
+        Some(token),
+        Body::from(json!({ "total_size": total_size }).to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["received_bytes"], 0);
+    assert_eq!(body["completed"], false);
+    body["upload_id"].as_str().unwrap().to_string()
+}
+
+async fn append_chunk(
+    app: &axum::Router,
+    token: &str,
+    upload_id: &str,
+    offset: usize,
+    chunk: &[u8],
+) -> (StatusCode, Value) {
+    send(
+        app,
+        "POST",
+        &format!("/api/uploads/{}/chunks?offset={}", upload_id, offset),
+        Some(token),
+        Body::from(chunk.to_vec()),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_chunked_upload_resumes_and_creates_report() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "chunked-uploader@example.com").await;
+    let photo = general_purpose::STANDARD.decode(PNG_BASE64).unwrap();
+    let (first, rest) = photo.split_at(30);
+
+    let upload_id = start_upload(&app, &token, photo.len()).await;
+
+    let (status, body) = append_chunk(&app, &token, &upload_id, 0, first).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["received_bytes"], 30);
+
+    // A retried chunk after a dropped response is rejected rather than duplicated
+    let (status, _) = append_chunk(&app, &token, &upload_id, 0, first).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // The client asks where to resume and sends the rest
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/api/uploads/{}", upload_id),
+        Some(&token),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let resume_at = body["received_bytes"].as_u64().unwrap() as usize;
+    assert_eq!(resume_at, 30);
+
+    let (status, _) = append_chunk(&app, &token, &upload_id, resume_at, rest).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        &format!("/api/uploads/{}/complete", upload_id),
+        Some(&token),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["completed"], true);
+    assert_eq!(body["received_bytes"], photo.len());
+
+    let report_body = json!({
+        "latitude": 51.5074,
+        "longitude": -0.1278,
+        "description": "Uploaded in chunks",
+        "photo_upload_id": upload_id
+    });
+    let (status, report) = send(
+        &app,
+        "POST",
+        "/api/reports",
+        Some(&token),
+        Body::from(report_body.to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(report["photo_before"].is_string());
+    assert_eq!(report["photo_before_metadata"]["width"], 1);
+
+    // Uploads are consumed by the report that uses them
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/reports",
+        Some(&token),
+        Body::from(report_body.to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_complete_rejects_missing_bytes() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "partial-uploader@example.com").await;
+    let photo = general_purpose::STANDARD.decode(PNG_BASE64).unwrap();
+
+    let upload_id = start_upload(&app, &token, photo.len()).await;
+    let (status, _) = append_chunk(&app, &token, &upload_id, 0, &photo[..10]).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/uploads/{}/complete", upload_id),
+        Some(&token),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Chunks may not run past the declared size
+    let (status, _) = append_chunk(&app, &token, &upload_id, 10, &photo).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/uploads/{}", upload_id),
+        Some(&token),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/api/uploads/{}", upload_id),
+        Some(&token),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_uploads_are_private_to_their_owner() {
+    let app = create_test_app().await;
+    let owner_token = create_verified_user_and_login(&app, "upload-owner@example.com").await;
+    let other_token = create_verified_user_and_login(&app, "upload-other@example.com").await;
+
+    let upload_id = start_upload(&app, &owner_token, 100).await;
+
+    let (status, _) = append_chunk(&app, &other_token, &upload_id, 0, b"hello").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_upload_size_is_limited() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "huge-uploader@example.com").await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/uploads",
+        Some(&token),
+        Body::from(json!({ "total_size": 1_000_000_000 }).to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_create_upload_requires_auth() {
+    let app = create_test_app().await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/uploads",
+        None,
+        Body::from(json!({ "total_size": 100 }).to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_upload_only_token_is_limited_to_uploads() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "background-sync@example.com").await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/auth/service-tokens",
+        Some(&token),
+        Body::from(json!({ "scope": "upload-only" }).to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["scope"], "upload-only");
+    assert_eq!(body["expires_in"], 3600);
+    let upload_token = body["token"].as_str().unwrap().to_string();
+
+    // Background sync can run a whole upload with it
+    let png = general_purpose::STANDARD.decode(PNG_BASE64).unwrap();
+    let upload_id = start_upload(&app, &upload_token, png.len()).await;
+    let (status, _) = append_chunk(&app, &upload_token, &upload_id, 0, &png).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/uploads/{}/complete", upload_id),
+        Some(&upload_token),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // ...but nothing else, including minting further tokens
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/api/users/me",
+        Some(&upload_token),
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/auth/service-tokens",
+        Some(&upload_token),
+        Body::from(json!({ "scope": "upload-only" }).to_string()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The full token still works alongside it
+    let (status, _) = send(&app, "GET", "/api/users/me", Some(&token), Body::empty()).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,524 @@
+// Tests for the verification rules, exercised on the service without going through HTTP
+
+use back_end::config::Config;
+use back_end::error::AppError;
+use back_end::jobs::{ClearReopenJob, VerificationWindowJob};
+use back_end::models::report::ReportStatus;
+use back_end::models::verification::CreateVerificationRequest;
+use back_end::services::scoring_service::verifier_weight;
+use back_end::services::{
+    GeocodingService, ImageService, ImageStorageService, NotificationService, ReportService,
+    S3Service, ScoringService, UploadService, VerificationService,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{insert_user, setup_test_db};
+
+/// A 1x1 PNG
+const TEST_IMAGE: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+async fn verification_service(pool: &PgPool) -> VerificationService {
+    dotenvy::from_filename(".env.test").ok();
+    let config = Config::from_env().expect("Failed to load config");
+    let s3_service = S3Service::new(config.s3.clone())
+        .await
+        .expect("Failed to create S3 service");
+    let image_storage =
+        ImageStorageService::new(pool.clone(), s3_service.clone(), config.s3.spill_max_mb);
+    let report_service = ReportService::new(
+        pool.clone(),
+        ImageService::new(config.image.clone()),
+        image_storage,
+        UploadService::new(pool.clone(), s3_service, config.image.clone()),
+        GeocodingService::new(pool.clone(), config.geocoding.clone()),
+        config.location_check.clone(),
+    );
+
+    VerificationService::new(
+        pool.clone(),
+        report_service,
+        ScoringService::new(pool.clone(), config.scoring.clone()),
+        NotificationService::new(pool.clone()),
+        config.scoring,
+    )
+}
+
+/// Insert a verified user with `clears` cleared reports to their name
+async fn create_user(pool: &PgPool, email: &str, clears: i32) -> Uuid {
+    let user_id = insert_user(pool, email).await;
+
+    sqlx::query(
+        "INSERT INTO user_scores (user_id, reports_cleared, total_clears) VALUES ($1, $2, $2)",
+    )
+    .bind(user_id)
+    .bind(clears)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    user_id
+}
+
+async fn create_cleared_report(pool: &PgPool, reporter_id: Uuid, clearer_id: Uuid) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO litter_reports (
+            reporter_id, location, photo_before, status,
+            claimed_by, claimed_at, cleared_by, cleared_at, photo_after
+        )
+        VALUES (
+            $1, ST_SetSRID(ST_MakePoint(-0.1278, 51.5074), 4326), 'before.webp', 'cleared',
+            $2, NOW(), $2, NOW(), 'after.webp'
+        )
+        RETURNING id
+        "#,
+    )
+    .bind(reporter_id)
+    .bind(clearer_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+fn verdict(is_verified: bool) -> CreateVerificationRequest {
+    CreateVerificationRequest {
+        is_verified,
+        request_better_photo: false,
+        comment: None,
+        photo_base64: None,
+    }
+}
+
+#[tokio::test]
+async fn test_create_verification_enforces_rules() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+
+    let reporter = create_user(&pool, "svc-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "svc-clearer@example.com", 5).await;
+    let novice = create_user(&pool, "svc-novice@example.com", 1).await;
+    let report_id = create_cleared_report(&pool, reporter, clearer).await;
+
+    let result = service
+        .create_verification(report_id, novice, verdict(true))
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+    let result = service
+        .create_verification(report_id, clearer, verdict(true))
+        .await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+    let result = service
+        .create_verification(Uuid::new_v4(), clearer, verdict(true))
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_tally_promotes_report_once() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+
+    let reporter = create_user(&pool, "tally-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "tally-clearer@example.com", 0).await;
+    let report_id = create_cleared_report(&pool, reporter, clearer).await;
+
+    // Rejections don't count; three approvals (MIN_VERIFICATIONS_NEEDED) do
+    let doubter = create_user(&pool, "tally-doubter@example.com", 5).await;
+    service
+        .create_verification(report_id, doubter, verdict(false))
+        .await
+        .unwrap();
+    for i in 0..2 {
+        let verifier = create_user(&pool, &format!("tally-verifier{i}@example.com"), 5).await;
+        service
+            .create_verification(report_id, verifier, verdict(true))
+            .await
+            .unwrap();
+    }
+    assert_eq!(service.tally(report_id).await.unwrap(), 2.0);
+    assert!(!service.promote_to_verified(report_id).await.unwrap());
+
+    let last = create_user(&pool, "tally-verifier2@example.com", 5).await;
+    service
+        .create_verification(report_id, last, verdict(true))
+        .await
+        .unwrap();
+    assert_eq!(service.tally(report_id).await.unwrap(), 3.0);
+
+    let status: String =
+        sqlx::query_scalar("SELECT status::text FROM litter_reports WHERE id = $1")
+            .bind(report_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "verified");
+
+    // Already promoted, so a repeat (e.g. from a background job) pays no second bonus
+    assert!(!service.promote_to_verified(report_id).await.unwrap());
+    let notified: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND kind = 'report_verified'",
+    )
+    .bind(clearer)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(notified, 1);
+}
+
+#[tokio::test]
+async fn test_unsettled_dispute_reopens_report_and_revokes_clear() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+    let job = ClearReopenJob::new(service.clone(), NotificationService::new(pool.clone()), 24);
+
+    let reporter = create_user(&pool, "reopen-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "reopen-clearer@example.com", 1).await;
+    let report_id = create_cleared_report(&pool, reporter, clearer).await;
+    sqlx::query("UPDATE user_scores SET total_points = 10 WHERE user_id = $1")
+        .bind(clearer)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO score_events (user_id, points, kind, report_id) VALUES ($1, 10, 'clear', $2)",
+    )
+    .bind(clearer)
+    .bind(report_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Three rejections (MIN_VERIFICATIONS_NEEDED) dispute the clear
+    for i in 0..3 {
+        let verifier = create_user(&pool, &format!("reopen-doubter{i}@example.com"), 5).await;
+        service
+            .create_verification(report_id, verifier, verdict(false))
+            .await
+            .unwrap();
+    }
+    let status: String =
+        sqlx::query_scalar("SELECT status::text FROM litter_reports WHERE id = $1")
+            .bind(report_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "disputed");
+
+    // Admins get a day to settle it first
+    assert_eq!(job.run().await.unwrap(), 0);
+    sqlx::query(
+        "UPDATE report_events SET occurred_at = NOW() - INTERVAL '2 days'
+         WHERE report_id = $1 AND kind = 'disputed'",
+    )
+    .bind(report_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert_eq!(job.run().await.unwrap(), 1);
+    assert_eq!(job.run().await.unwrap(), 0);
+
+    let (status, cleared_by, photo_after): (String, Option<Uuid>, Option<String>) = sqlx::query_as(
+        "SELECT status::text, cleared_by, photo_after FROM litter_reports WHERE id = $1",
+    )
+    .bind(report_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(status, "pending");
+    assert_eq!(cleared_by, None);
+    assert_eq!(photo_after, None);
+    assert_eq!(service.tally(report_id).await.unwrap(), 0.0);
+
+    let (points, clears): (i32, i32) =
+        sqlx::query_as("SELECT total_points, total_clears FROM user_scores WHERE user_id = $1")
+            .bind(clearer)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!((points, clears), (0, 0));
+
+    let notified: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND kind = 'clear_rejected'",
+    )
+    .bind(clearer)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(notified, 1);
+}
+
+#[tokio::test]
+async fn test_photo_backed_verification_earns_extra_points() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+
+    let reporter = create_user(&pool, "photo-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "photo-clearer@example.com", 0).await;
+    let with_photo = create_user(&pool, "photo-verifier@example.com", 5).await;
+    let without_photo = create_user(&pool, "plain-verifier@example.com", 5).await;
+    let report_id = create_cleared_report(&pool, reporter, clearer).await;
+
+    let verification = service
+        .create_verification(
+            report_id,
+            with_photo,
+            CreateVerificationRequest {
+                photo_base64: Some(TEST_IMAGE.to_string()),
+                ..verdict(true)
+            },
+        )
+        .await
+        .unwrap();
+    assert!(verification.photo.is_some());
+    assert_eq!(verification.photo_width, Some(1));
+
+    let verification = service
+        .create_verification(report_id, without_photo, verdict(true))
+        .await
+        .unwrap();
+    assert!(verification.photo.is_none());
+
+    // VERIFICATION_BONUS plus PHOTO_VERIFICATION_BONUS from .env.test
+    let points = |user_id| {
+        sqlx::query_scalar::<_, i32>("SELECT total_points FROM user_scores WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+    };
+    assert_eq!(points(with_photo).await.unwrap(), 5);
+    assert_eq!(points(without_photo).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_verdicts_are_weighed_by_record() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+
+    let reporter = create_user(&pool, "weight-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "weight-clearer@example.com", 0).await;
+    let doubter = create_user(&pool, "weight-doubter@example.com", 5).await;
+    let report_id = create_cleared_report(&pool, reporter, clearer).await;
+
+    service
+        .create_verification(report_id, doubter, verdict(false))
+        .await
+        .unwrap();
+
+    // Each agreed with the outcome of all 8 clears they judged before
+    let mut trusted = Vec::new();
+    for i in 0..2 {
+        let verifier = create_user(&pool, &format!("weight-trusted{i}@example.com"), 5).await;
+        sqlx::query(
+            "INSERT INTO verifier_weights (user_id, agreed, settled, weight)
+             VALUES ($1, 8, 8, $2)",
+        )
+        .bind(verifier)
+        .bind(verifier_weight(8, 8))
+        .execute(&pool)
+        .await
+        .unwrap();
+        service
+            .create_verification(report_id, verifier, verdict(true))
+            .await
+            .unwrap();
+        trusted.push(verifier);
+    }
+
+    // Two approvals weighing 1.8 each reach MIN_VERIFICATIONS_NEEDED (3)
+    assert!((service.tally(report_id).await.unwrap() - 3.6).abs() < 1e-9);
+    let status: String =
+        sqlx::query_scalar("SELECT status::text FROM litter_reports WHERE id = $1")
+            .bind(report_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "verified");
+
+    // Settling the clear moves each verifier's weight with their verdict
+    let weight = |user_id| {
+        sqlx::query_scalar::<_, f64>("SELECT weight FROM verifier_weights WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+    };
+    assert!((weight(trusted[0]).await.unwrap() - verifier_weight(9, 9)).abs() < 1e-9);
+    let doubter_weight = weight(doubter).await.unwrap();
+    assert!((doubter_weight - verifier_weight(0, 1)).abs() < 1e-9);
+    assert!(doubter_weight < 1.0);
+}
+
+#[tokio::test]
+async fn test_reservations_hold_open_slots() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+
+    let reporter = create_user(&pool, "svc-reserve-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "svc-reserve-clearer@example.com", 5).await;
+    let mut verifiers = Vec::new();
+    for i in 0..5 {
+        verifiers
+            .push(create_user(&pool, &format!("svc-reserve-verifier{i}@example.com"), 5).await);
+    }
+    let report_id = create_cleared_report(&pool, reporter, clearer).await;
+
+    // MIN_VERIFICATIONS_NEEDED is 3 in .env.test, so three verifiers fill the slots
+    for verifier in &verifiers[..3] {
+        service.reserve(report_id, *verifier).await.unwrap();
+    }
+    let result = service.reserve(report_id, verifiers[3]).await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+
+    // Reserving again only extends the hold
+    service.reserve(report_id, verifiers[0]).await.unwrap();
+
+    service.release(report_id, verifiers[0]).await.unwrap();
+    let result = service.release(report_id, verifiers[0]).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+    service.reserve(report_id, verifiers[3]).await.unwrap();
+
+    // A verdict frees the verifier's reservation and takes up its slot
+    service
+        .create_verification(report_id, verifiers[3], verdict(true))
+        .await
+        .unwrap();
+    let result = service.release(report_id, verifiers[3]).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+    let result = service.reserve(report_id, verifiers[4]).await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+}
+
+#[tokio::test]
+async fn test_verification_window_finalizes_overdue_clears() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+    let job = VerificationWindowJob::new(service.clone(), 7);
+
+    let reporter = create_user(&pool, "window-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "window-clearer@example.com", 1).await;
+    let doubter = create_user(&pool, "window-doubter@example.com", 5).await;
+    let unjudged = create_cleared_report(&pool, reporter, clearer).await;
+    let rejected = create_cleared_report(&pool, reporter, clearer).await;
+    let recent = create_cleared_report(&pool, reporter, clearer).await;
+    service
+        .create_verification(rejected, doubter, verdict(false))
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE litter_reports SET cleared_at = NOW() - INTERVAL '8 days' WHERE id = ANY($1)",
+    )
+    .bind(vec![unjudged, rejected])
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    job.run().await.unwrap();
+
+    let status = |report_id: Uuid| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, String>("SELECT status::text FROM litter_reports WHERE id = $1")
+                .bind(report_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        }
+    };
+    // Nobody objected, so the clear stands; one rejection and no approvals disputes it
+    assert_eq!(status(unjudged).await, "verified");
+    assert_eq!(status(rejected).await, "disputed");
+    assert_eq!(status(recent).await, "cleared");
+
+    let notified: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications
+         WHERE user_id = $1 AND kind = 'report_verified' AND report_id = $2",
+    )
+    .bind(clearer)
+    .bind(unjudged)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(notified, 1);
+}
+
+#[tokio::test]
+async fn test_admin_override_settles_clears() {
+    let pool = setup_test_db().await;
+    let service = verification_service(&pool).await;
+
+    let admin = create_user(&pool, "override-admin@example.com", 0).await;
+    let reporter = create_user(&pool, "override-reporter@example.com", 0).await;
+    let clearer = create_user(&pool, "override-clearer@example.com", 1).await;
+    let approver = create_user(&pool, "override-approver@example.com", 5).await;
+    let points = |user_id| {
+        sqlx::query_scalar::<_, i32>("SELECT total_points FROM user_scores WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+    };
+
+    // One approval is short of the threshold; the admin verifies it anyway
+    let verified = create_cleared_report(&pool, reporter, clearer).await;
+    service
+        .create_verification(verified, approver, verdict(true))
+        .await
+        .unwrap();
+    let report = service
+        .override_verdict(verified, admin, true, "Checked on site")
+        .await
+        .unwrap();
+    assert_eq!(report.status, ReportStatus::Verified);
+    assert_eq!(points(clearer).await.unwrap(), 10);
+
+    // Rejecting takes back what the clear earned and reopens the report
+    let rejected = create_cleared_report(&pool, reporter, clearer).await;
+    sqlx::query(
+        "INSERT INTO score_events (user_id, points, kind, report_id) VALUES ($1, 10, 'clear', $2)",
+    )
+    .bind(clearer)
+    .bind(rejected)
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("UPDATE user_scores SET total_points = total_points + 10 WHERE user_id = $1")
+        .bind(clearer)
+        .execute(&pool)
+        .await
+        .unwrap();
+    service
+        .create_verification(rejected, approver, verdict(true))
+        .await
+        .unwrap();
+    let report = service
+        .override_verdict(rejected, admin, false, "Litter still visible")
+        .await
+        .unwrap();
+    assert_eq!(report.status, ReportStatus::Pending);
+    assert_eq!(report.cleared_by, None);
+    assert_eq!(points(clearer).await.unwrap(), 10);
+
+    // The approver agreed with one outcome of the two
+    let (agreed, settled): (i32, i32) =
+        sqlx::query_as("SELECT agreed, settled FROM verifier_weights WHERE user_id = $1")
+            .bind(approver)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!((agreed, settled), (1, 2));
+
+    let (actor, reason): (Option<Uuid>, Option<String>) = sqlx::query_as(
+        "SELECT actor_id, reason FROM report_events
+         WHERE report_id = $1 AND kind = 'reopened'",
+    )
+    .bind(rejected)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(actor, Some(admin));
+    assert_eq!(reason.as_deref(), Some("Litter still visible"));
+
+    let result = service
+        .override_verdict(rejected, admin, true, "Too late")
+        .await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+}
@@ -1,4 +1,8 @@
 // Integration tests for verification endpoints
+#![allow(
+    clippy::needless_borrows_for_generic_args,
+    clippy::bool_assert_comparison
+)]
 
 use axum::{
     body::Body,
@@ -738,6 +742,286 @@ async fn test_get_report_verifications() {
         .await
         .unwrap();
     let verifications: Value = serde_json::from_slice(&body).unwrap();
-    assert!(verifications.is_array());
-    assert_eq!(verifications.as_array().unwrap().len(), 2);
+    assert_eq!(verifications["items"].as_array().unwrap().len(), 2);
+    assert_eq!(verifications["total"], 2);
+}
+
+/// Helper to submit a verification and return the response status
+async fn submit_verification(
+    app: &axum::Router,
+    token: &str,
+    report_id: &str,
+    body: Value,
+) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/reports/{}/verify", report_id))
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+/// Helper to upload a replacement after photo and return the response status
+async fn replace_after_photo(app: &axum::Router, token: &str, report_id: &str) -> StatusCode {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/reports/{}/after-photo", report_id))
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({
+                        "photo_base64": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_request_better_photo_resets_verification() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    let reporter_token = create_verified_user_and_login(&app, "reporter7@example.com").await;
+    let report_id = create_test_report(&app, &reporter_token).await;
+
+    let claimer_token = create_verified_user_and_login(&app, "claimer7@example.com").await;
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/reports/{}/claim", report_id))
+                .header("authorization", format!("Bearer {}", claimer_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Nothing to replace until a verifier asks
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/reports/{}/clear", report_id))
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", claimer_token))
+                .body(Body::from(
+                    json!({
+                        "photo_base64": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        replace_after_photo(&app, &claimer_token, &report_id).await,
+        StatusCode::BAD_REQUEST
+    );
+
+    let verifier1_token = create_verified_user_and_login(&app, "verifier7_1@example.com").await;
+    enable_verification_for_user(&app, &verifier1_token, "verifier7_1@example.com").await;
+    let verifier2_token = create_verified_user_and_login(&app, "verifier7_2@example.com").await;
+    enable_verification_for_user(&app, &verifier2_token, "verifier7_2@example.com").await;
+
+    assert_eq!(
+        submit_verification(
+            &app,
+            &verifier1_token,
+            &report_id,
+            json!({ "is_verified": true })
+        )
+        .await,
+        StatusCode::CREATED
+    );
+
+    // A retake request can't also approve the photo
+    assert_eq!(
+        submit_verification(
+            &app,
+            &verifier2_token,
+            &report_id,
+            json!({ "is_verified": true, "request_better_photo": true })
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        submit_verification(
+            &app,
+            &verifier2_token,
+            &report_id,
+            json!({ "is_verified": false, "request_better_photo": true, "comment": "Too blurry" })
+        )
+        .await,
+        StatusCode::CREATED
+    );
+
+    let notified: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications n JOIN users u ON u.id = n.user_id WHERE u.email = $1 AND n.kind = 'photo_retake_requested'",
+    )
+    .bind("claimer7@example.com")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(notified, 1);
+
+    // Verification waits for the new photo
+    assert_eq!(
+        submit_verification(
+            &app,
+            &verifier1_token,
+            &report_id,
+            json!({ "is_verified": true })
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+
+    // Only the clearer may replace it, and only once
+    assert_eq!(
+        replace_after_photo(&app, &verifier1_token, &report_id).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        replace_after_photo(&app, &claimer_token, &report_id).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        replace_after_photo(&app, &claimer_token, &report_id).await,
+        StatusCode::BAD_REQUEST
+    );
+
+    // The earlier approval no longer counts, so the same verifier judges the new photo
+    assert_eq!(
+        submit_verification(
+            &app,
+            &verifier1_token,
+            &report_id,
+            json!({ "is_verified": true })
+        )
+        .await,
+        StatusCode::CREATED
+    );
+    let current: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM report_verifications WHERE report_id = $1::uuid AND superseded_at IS NULL",
+    )
+    .bind(&report_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(current, 1);
+}
+
+#[tokio::test]
+async fn test_verification_queue_filters_and_sorts() {
+    let app = create_test_app().await;
+    let pool = get_test_pool().await;
+
+    let token = create_verified_user_and_login(&app, "queue-verifier@example.com").await;
+    create_verified_user_and_login(&app, "queue-clearer@example.com").await;
+    let clearer_id: uuid::Uuid =
+        sqlx::query_scalar("SELECT id FROM users WHERE email = 'queue-clearer@example.com'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    // Three clears east of a point in the South Atlantic, the closest cleared first
+    let mut ids = Vec::new();
+    for (offset, category, severity, hours_ago) in [
+        (0.01, "plastic", "small", 3),
+        (0.02, "glass", "large", 2),
+        (0.03, "plastic", "large", 1),
+    ] {
+        let id: uuid::Uuid = sqlx::query_scalar(
+            "INSERT INTO litter_reports
+                (reporter_id, location, status, category, severity,
+                 claimed_by, claimed_at, cleared_by, cleared_at)
+             VALUES ($1, ST_SetSRID(ST_MakePoint(-20.0 + $2, -40.0), 4326), 'cleared',
+                     $3::litter_category, $4::litter_severity,
+                     $1, NOW(), $1, NOW() - make_interval(hours => $5))
+             RETURNING id",
+        )
+        .bind(clearer_id)
+        .bind(offset)
+        .bind(category)
+        .bind(severity)
+        .bind(hours_ago)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        ids.push(id.to_string());
+    }
+
+    let queue = |query: &str| {
+        let app = app.clone();
+        let token = token.clone();
+        let uri = format!(
+            "/api/reports/verification-queue?latitude=-40.0&longitude=-20.0&radius_km=10{query}"
+        );
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(uri)
+                        .header("authorization", format!("Bearer {}", token))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<Value>(&body).unwrap()
+        }
+    };
+    let item_ids = |page: &Value| -> Vec<String> {
+        page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Newest clear first by default
+    let page = queue("").await;
+    assert_eq!(
+        item_ids(&page),
+        vec![ids[2].clone(), ids[1].clone(), ids[0].clone()]
+    );
+
+    let page = queue("&category=plastic").await;
+    assert_eq!(item_ids(&page), vec![ids[2].clone(), ids[0].clone()]);
+
+    let page = queue("&category=plastic&severity=large").await;
+    assert_eq!(item_ids(&page), vec![ids[2].clone()]);
+
+    // Closest first, one page at a time
+    let page = queue("&sort=distance&limit=2").await;
+    assert_eq!(page["total"], 3);
+    assert_eq!(item_ids(&page), vec![ids[0].clone(), ids[1].clone()]);
+    let cursor = page["next_cursor"].as_str().unwrap().to_string();
+    let page = queue(&format!("&sort=distance&limit=2&cursor={cursor}")).await;
+    assert_eq!(item_ids(&page), vec![ids[2].clone()]);
+    assert!(page["next_cursor"].is_null());
 }
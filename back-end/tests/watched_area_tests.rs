@@ -0,0 +1,233 @@
+// Integration tests for watched areas and the notifications they send
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+mod helpers;
+use helpers::{create_test_app, get_test_pool};
+
+const TEST_PHOTO: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+/// Helper to create a verified user and get auth token
+async fn create_verified_user_and_login(app: &axum::Router, email: &str) -> String {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123",
+                        "full_name": "Test User",
+                        "city": "London",
+                        "country": "UK"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let pool = get_test_pool().await;
+    sqlx::query(
+        "UPDATE users SET email_verified = true, email_verified_at = NOW() WHERE email = $1",
+    )
+    .bind(email)
+    .execute(&pool)
+    .await
+    .expect("Failed to verify user");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "email": email,
+                        "password": "password123"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let auth_response: Value = serde_json::from_slice(&body).unwrap();
+    auth_response["access_token"].as_str().unwrap().to_string()
+}
+
+async fn post_json(app: &axum::Router, uri: &str, token: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn watched_area_notifications(email: &str) -> Vec<Uuid> {
+    let pool = get_test_pool().await;
+    sqlx::query_scalar(
+        r#"
+        SELECT n.report_id FROM notifications n
+        JOIN users u ON u.id = n.user_id
+        WHERE u.email = $1 AND n.kind = 'watched_area_report'
+        "#,
+    )
+    .bind(email)
+    .fetch_all(&pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_watched_area_shapes() {
+    let app = create_test_app().await;
+    let token = create_verified_user_and_login(&app, "area-shapes@example.com").await;
+
+    let (status, circle) = post_json(
+        &app,
+        "/api/users/me/watched-areas",
+        &token,
+        json!({ "name": "Around the park", "latitude": 51.5074, "longitude": -0.1278, "radius_km": 1.5 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(circle["radius_km"], 1.5);
+    assert!(circle.get("polygon").is_none());
+
+    let (status, polygon) = post_json(
+        &app,
+        "/api/users/me/watched-areas",
+        &token,
+        json!({ "name": "High street", "polygon": [[-0.13, 51.50], [-0.12, 51.50], [-0.12, 51.51]] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(polygon["polygon"].as_array().unwrap().len(), 4);
+    assert!(polygon.get("radius_km").is_none());
+
+    for body in [
+        json!({ "name": "Both", "latitude": 51.5, "longitude": -0.1, "radius_km": 1.0,
+                "polygon": [[-0.13, 51.50], [-0.12, 51.50], [-0.12, 51.51]] }),
+        json!({ "name": "No radius", "latitude": 51.5, "longitude": -0.1 }),
+        json!({ "name": "Too wide", "latitude": 51.5, "longitude": -0.1, "radius_km": 100.0 }),
+        json!({ "name": "Line", "polygon": [[-0.13, 51.50], [-0.12, 51.50]] }),
+        json!({ "name": "Bow tie", "polygon": [[0.0, 0.0], [0.01, 0.01], [0.01, 0.0], [0.0, 0.01]] }),
+        json!({ "name": "County", "polygon": [[-1.0, 51.0], [0.0, 51.0], [0.0, 52.0], [-1.0, 52.0]] }),
+    ] {
+        let (status, _) = post_json(&app, "/api/users/me/watched-areas", &token, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!(
+                    "/api/users/me/watched-areas/{}",
+                    circle["id"].as_str().unwrap()
+                ))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/users/me/watched-areas")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let areas: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(areas["items"].as_array().unwrap().len(), 1);
+    assert_eq!(areas["items"][0]["name"], "High street");
+}
+
+#[tokio::test]
+async fn test_new_report_notifies_area_watchers() {
+    let app = create_test_app().await;
+    let watcher = create_verified_user_and_login(&app, "area-watcher@example.com").await;
+    let elsewhere = create_verified_user_and_login(&app, "area-elsewhere@example.com").await;
+    let reporter = create_verified_user_and_login(&app, "area-reporter@example.com").await;
+
+    for (token, latitude, longitude) in [
+        (&watcher, 51.5074, -0.1278),
+        (&elsewhere, 53.4808, -2.2426),
+        (&reporter, 51.5074, -0.1278),
+    ] {
+        let (status, _) = post_json(
+            &app,
+            "/api/users/me/watched-areas",
+            token,
+            json!({ "name": "Home", "latitude": latitude, "longitude": longitude, "radius_km": 2.0 }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let (status, report) = post_json(
+        &app,
+        "/api/reports",
+        &reporter,
+        json!({ "latitude": 51.5080, "longitude": -0.1281, "photo_base64": TEST_PHOTO }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let report_id: Uuid = report["id"].as_str().unwrap().parse().unwrap();
+
+    assert_eq!(
+        watched_area_notifications("area-watcher@example.com").await,
+        vec![report_id]
+    );
+    assert!(watched_area_notifications("area-elsewhere@example.com")
+        .await
+        .is_empty());
+    // Nobody is told about their own report
+    assert!(watched_area_notifications("area-reporter@example.com")
+        .await
+        .is_empty());
+}
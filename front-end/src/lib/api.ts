@@ -2,18 +2,37 @@ import type { components } from "./api-types";
 import { get } from "svelte/store";
 import { auth } from "$lib/stores/auth";
 
-export type User = components["schemas"]["UserResponse"];
+export type User = components["schemas"]["UserResponse"] & {
+  /** Linked sign-in provider, e.g. "google" */
+  oauth_provider?: string | null;
+  /** False for accounts that can only sign in through their provider */
+  has_password?: boolean;
+};
 export type LoginRequest = components["schemas"]["LoginRequest"];
 export type RegisterRequest = components["schemas"]["RegisterRequest"];
 export type AuthTokens = components["schemas"]["AuthTokens"];
 export type Report = components["schemas"]["ReportResponse"];
+export type LitterCategory = components["schemas"]["LitterCategory"];
+export type LitterSeverity = components["schemas"]["LitterSeverity"];
+export type ReportStatus = components["schemas"]["ReportStatus"];
+export type ReportSort = components["schemas"]["ReportSort"];
+export type ReportTimeline = components["schemas"]["ReportTimelineResponse"];
+export type ReportCluster = components["schemas"]["ReportCluster"];
+export type ReportsInBounds = components["schemas"]["ReportsInBoundsResponse"];
+export type ReportClusters = components["schemas"]["ReportClustersResponse"];
+export type PhotoKind = components["schemas"]["PhotoKind"];
+export type ReportPhotos = components["schemas"]["ReportPhotos"];
+export type AddReportPhotoRequest =
+  components["schemas"]["AddReportPhotoRequest"];
 export type CreateReportRequest = components["schemas"]["CreateReportRequest"];
 export type ClearReportRequest = components["schemas"]["ClearReportRequest"];
+export type UpdateReportRequest = components["schemas"]["UpdateReportRequest"];
 export type CreateVerificationRequest =
   components["schemas"]["CreateVerificationRequest"];
 export type VerificationResponse =
   components["schemas"]["VerificationResponse"];
 export type LeaderboardEntry = components["schemas"]["LeaderboardEntry"];
+export type StatsSummary = components["schemas"]["StatsSummary"];
 export type UserScoreRecord = components["schemas"]["UserScoreRecord"];
 export type UpdateUserRequest = components["schemas"]["UpdateUserRequest"];
 export type RefreshTokenRequest = components["schemas"]["RefreshTokenRequest"];
@@ -36,6 +55,173 @@ export type ResetPasswordRequest =
   components["schemas"]["ResetPasswordRequest"];
 export type MessageResponse = components["schemas"]["MessageResponse"];
 
+/** Envelope returned by every list endpoint */
+export interface Paginated<T> {
+  items: T[];
+  total: number;
+  /** Pass back as `cursor` to fetch the next page; null on the last page */
+  next_cursor?: string | null;
+}
+
+export interface BatchClaimResult {
+  report_id: string;
+  claimed: boolean;
+  error?: string | null;
+  report?: Report | null;
+}
+
+export interface ClaimBatchResponse {
+  /** Groups the claimed reports; null when nothing could be claimed */
+  run_id: string | null;
+  claimed_count: number;
+  results: BatchClaimResult[];
+}
+
+export type ReportFlagReason =
+  | "not_litter"
+  | "private_property"
+  | "offensive_photo"
+  | "wrong_location";
+
+export interface FlagReportRequest {
+  reason: ReportFlagReason;
+  comment?: string | null;
+}
+
+export interface FlagReportResponse {
+  flag: {
+    id: string;
+    report_id: string;
+    reason: ReportFlagReason;
+    comment?: string | null;
+    created_at: string;
+  };
+  /** True once enough users have flagged the report for it to be hidden */
+  report_hidden: boolean;
+}
+
+export type ReportEventKind =
+  | "created"
+  | "claimed"
+  | "released"
+  | "expired"
+  | "cleared"
+  | "verified"
+  | "disputed"
+  | "cancelled"
+  | "archived"
+  | "corrected"
+  | "deleted";
+
+export interface ReportEvent {
+  id: string;
+  report_id: string;
+  kind: ReportEventKind;
+  from_status?: ReportStatus | null;
+  to_status?: ReportStatus | null;
+  actor_id?: string | null;
+  reason?: string | null;
+  metadata: Record<string, unknown>;
+  occurred_at: string;
+}
+
+export interface ReportHistory {
+  report_id: string;
+  /** Oldest first */
+  events: ReportEvent[];
+}
+
+export interface CleanupRun {
+  id: string;
+  user_id: string;
+  created_at: string;
+  total_reports: number;
+  claimed_reports: number;
+  cleared_reports: number;
+  points_earned: number;
+  first_cleared_at?: string | null;
+  last_cleared_at?: string | null;
+  reports: Report[];
+}
+
+export interface AppNotification {
+  id: string;
+  user_id: string;
+  kind:
+    | "report_claimed"
+    | "report_cleared"
+    | "report_verified"
+    | "verification_reminder"
+    | "data_export_ready"
+    | "photo_retake_requested"
+    | "claim_expired";
+  title: string;
+  body: string;
+  report_id?: string | null;
+  created_at: string;
+}
+
+export interface NotificationPollResponse {
+  notifications: AppNotification[];
+  /** Pass back as `since` on the next poll */
+  next_since: string;
+}
+
+/** Returned by login instead of tokens when the account has two-factor enabled */
+export interface TwoFactorChallenge {
+  two_factor_required: true;
+  challenge_token: string;
+  /** Seconds until the challenge expires */
+  expires_in: number;
+}
+
+/** Progress of a personal data export */
+export interface DataExport {
+  export_id: string;
+  status: "pending" | "ready" | "failed";
+  /** Set once ready; fetch with the same bearer token */
+  download_url: string | null;
+  size_bytes: number | null;
+  error: string | null;
+  created_at: string;
+  completed_at: string | null;
+  expires_at: string | null;
+}
+
+/** A signed-in device; one per refresh token */
+export interface Session {
+  id: string;
+  user_agent: string | null;
+  ip_address: string | null;
+  created_at: string;
+  last_used_at: string;
+  expires_at: string;
+  /** True for the session making the request */
+  current: boolean;
+}
+
+export interface TwoFactorSetupResponse {
+  /** Base32 secret for manual entry */
+  secret: string;
+  /** otpauth:// URI for authenticator apps */
+  otpauth_url: string;
+}
+
+export function isTwoFactorChallenge(
+  res: AuthTokens | TwoFactorChallenge,
+): res is TwoFactorChallenge {
+  return "two_factor_required" in res && res.two_factor_required === true;
+}
+
+/** Build the paging part of a query string, prefixed with `separator` when non-empty */
+function pageQuery(cursor?: string, limit?: number, separator = "&"): string {
+  const params = new URLSearchParams();
+  if (cursor) params.set("cursor", cursor);
+  if (limit) params.set("limit", String(limit));
+  const query = params.toString();
+  return query ? `${separator}${query}` : "";
+}
+
 const API_BASE = "/api";
 
 class ApiError extends Error {
@@ -81,7 +267,11 @@ async function request<T>(
   const response = await fetch(`${API_BASE}${path}`, options);
 
   if (response.status === 401) {
-    if (path === "/auth/refresh" || path === "/auth/login") {
+    if (
+      path === "/auth/refresh" ||
+      path === "/auth/login" ||
+      path === "/auth/2fa/login"
+    ) {
       throw new ApiError("Unauthorized", 401);
     }
 
@@ -161,11 +351,31 @@ async function request<T>(
 export const api = {
   auth: {
     login: (data: LoginRequest) =>
-      request<AuthTokens>("POST", "/auth/login", data),
+      request<AuthTokens | TwoFactorChallenge>("POST", "/auth/login", data),
     register: (data: RegisterRequest) =>
       request<{ message: string }>("POST", "/auth/register", data),
     verifyEmail: (token: string) =>
       request<AuthTokens>("POST", "/auth/verify-email", { token }),
+    confirmAccountLink: (token: string) =>
+      request<AuthTokens | TwoFactorChallenge>("POST", "/auth/link-account", {
+        token,
+      }),
+    requestMagicLink: (email: string) =>
+      request<MessageResponse>("POST", "/auth/magic-link", { email }),
+    verifyMagicLink: (token: string) =>
+      request<AuthTokens | TwoFactorChallenge>(
+        "POST",
+        "/auth/magic-link/verify",
+        { token },
+      ),
+    confirmEmailChange: (token: string) =>
+      request<MessageResponse>("POST", "/auth/confirm-email-change", { token }),
+    unsubscribeVerificationReminders: (token: string) =>
+      request<MessageResponse>(
+        "POST",
+        "/auth/verification-reminders/unsubscribe",
+        { token },
+      ),
     refreshToken: (data: RefreshTokenRequest) =>
       request<RefreshTokenResponse>("POST", "/auth/refresh", data),
     getMe: (token: string) =>
@@ -174,12 +384,92 @@ export const api = {
       request<MessageResponse>("POST", "/auth/forgot-password", data),
     resetPassword: (data: ResetPasswordRequest) =>
       request<MessageResponse>("POST", "/auth/reset-password", data),
+    logoutAll: (token: string) =>
+      request<MessageResponse>("POST", "/auth/logout-all", {}, token),
+  },
+  twoFactor: {
+    login: (challenge_token: string, code: string) =>
+      request<AuthTokens>("POST", "/auth/2fa/login", { challenge_token, code }),
+    status: (token: string) =>
+      request<{ enabled: boolean }>("GET", "/auth/2fa", undefined, token),
+    setup: (token: string) =>
+      request<TwoFactorSetupResponse>("POST", "/auth/2fa/setup", {}, token),
+    verify: (code: string, token: string) =>
+      request<{ backup_codes: string[] }>(
+        "POST",
+        "/auth/2fa/verify",
+        { code },
+        token,
+      ),
+    disable: (code: string, token: string) =>
+      request<MessageResponse>("POST", "/auth/2fa/disable", { code }, token),
   },
   users: {
     updateMe: (data: UpdateUserRequest, token: string) =>
       request<User>("PATCH", "/users/me", data, token),
     getMyScore: (token: string) =>
       request<UserScoreRecord>("GET", "/users/me/score", undefined, token),
+    linkOAuth: (
+      provider: string,
+      password: string,
+      code: string | undefined,
+      token: string,
+    ) =>
+      request<{ auth_url: string }>(
+        "POST",
+        "/users/me/oauth/link",
+        { provider, password, code },
+        token,
+      ),
+    unlinkOAuth: (
+      provider: string,
+      password: string,
+      code: string | undefined,
+      token: string,
+    ) =>
+      request<MessageResponse>(
+        "DELETE",
+        `/users/me/oauth/${provider}`,
+        { password, code },
+        token,
+      ),
+    changePassword: (
+      current_password: string,
+      new_password: string,
+      code: string | undefined,
+      token: string,
+    ) =>
+      request<MessageResponse>(
+        "PUT",
+        "/users/me/password",
+        { current_password, new_password, code },
+        token,
+      ),
+    requestEmailChange: (
+      new_email: string,
+      password: string,
+      code: string | undefined,
+      token: string,
+    ) =>
+      request<MessageResponse>(
+        "POST",
+        "/users/me/email-change",
+        { new_email, password, code },
+        token,
+      ),
+    listSessions: (token: string) =>
+      request<Session[]>("GET", "/users/me/sessions", undefined, token),
+    revokeSession: (id: string, token: string) =>
+      request<MessageResponse>(
+        "DELETE",
+        `/users/me/sessions/${id}`,
+        undefined,
+        token,
+      ),
+    requestDataExport: (token: string) =>
+      request<DataExport>("POST", "/users/me/export", {}, token),
+    getDataExport: (token: string) =>
+      request<DataExport>("GET", "/users/me/export", undefined, token),
   },
   reports: {
     create: (data: CreateReportRequest, token: string) =>
@@ -189,10 +479,41 @@ export const api = {
       longitude: number,
       radius_km: number,
       token: string,
+      cursor?: string,
+      filter: {
+        category?: LitterCategory;
+        severity?: LitterSeverity;
+        status?: ReportStatus[];
+        sort?: ReportSort;
+      } = {},
+    ) =>
+      request<Paginated<Report>>(
+        "GET",
+        `/reports/nearby?latitude=${latitude}&longitude=${longitude}&radius_km=${radius_km}${filter.category ? `&category=${filter.category}` : ""}${filter.severity ? `&severity=${filter.severity}` : ""}${filter.status?.length ? `&status=${filter.status.join(",")}` : ""}${filter.sort ? `&sort=${filter.sort}` : ""}${pageQuery(cursor)}`,
+        undefined,
+        token,
+      ),
+    getInBounds: (
+      bounds: { min_lat: number; min_lon: number; max_lat: number; max_lon: number },
+      zoom: number,
+      token: string,
+      filter: { category?: LitterCategory; severity?: LitterSeverity } = {},
+    ) =>
+      request<ReportsInBounds>(
+        "GET",
+        `/reports/in-bounds?min_lat=${bounds.min_lat}&min_lon=${bounds.min_lon}&max_lat=${bounds.max_lat}&max_lon=${bounds.max_lon}&zoom=${Math.floor(zoom)}${filter.category ? `&category=${filter.category}` : ""}${filter.severity ? `&severity=${filter.severity}` : ""}`,
+        undefined,
+        token,
+      ),
+    getClusters: (
+      bounds: { min_lat: number; min_lon: number; max_lat: number; max_lon: number },
+      zoom: number,
+      token: string,
+      filter: { category?: LitterCategory; severity?: LitterSeverity } = {},
     ) =>
-      request<Report[]>(
+      request<ReportClusters>(
         "GET",
-        `/reports/nearby?latitude=${latitude}&longitude=${longitude}&radius_km=${radius_km}`,
+        `/reports/clusters?min_lat=${bounds.min_lat}&min_lon=${bounds.min_lon}&max_lat=${bounds.max_lat}&max_lon=${bounds.max_lon}&zoom=${Math.floor(zoom)}${filter.category ? `&category=${filter.category}` : ""}${filter.severity ? `&severity=${filter.severity}` : ""}`,
         undefined,
         token,
       ),
@@ -201,23 +522,61 @@ export const api = {
       longitude: number,
       radius_km: number,
       token: string,
+      cursor?: string,
     ) =>
-      request<Report[]>(
+      request<Paginated<Report>>(
+        "GET",
+        `/reports/verification-queue?latitude=${latitude}&longitude=${longitude}&radius_km=${radius_km}${pageQuery(cursor)}`,
+        undefined,
+        token,
+      ),
+    getMyReports: (token: string, cursor?: string) =>
+      request<Paginated<Report>>(
+        "GET",
+        `/reports/my-reports${pageQuery(cursor, undefined, "?")}`,
+        undefined,
+        token,
+      ),
+    getMyClears: (token: string, cursor?: string) =>
+      request<Paginated<Report>>(
         "GET",
-        `/reports/verification-queue?latitude=${latitude}&longitude=${longitude}&radius_km=${radius_km}`,
+        `/reports/my-clears${pageQuery(cursor, undefined, "?")}`,
         undefined,
         token,
       ),
-    getMyReports: (token: string) =>
-      request<Report[]>("GET", "/reports/my-reports", undefined, token),
-    getMyClears: (token: string) =>
-      request<Report[]>("GET", "/reports/my-clears", undefined, token),
     getById: (id: string, token: string) =>
       request<Report>("GET", `/reports/${id}`, undefined, token),
+    update: (id: string, data: UpdateReportRequest, token: string) =>
+      request<Report>("PATCH", `/reports/${id}`, data, token),
+    cancel: (id: string, token: string) =>
+      request<Report>("DELETE", `/reports/${id}`, undefined, token),
     claim: (id: string, token: string) =>
       request<Report>("POST", `/reports/${id}/claim`, {}, token),
+    unclaim: (id: string, token: string, reason?: string) =>
+      request<Report>("POST", `/reports/${id}/unclaim`, { reason }, token),
+    flag: (id: string, data: FlagReportRequest, token: string) =>
+      request<FlagReportResponse>("POST", `/reports/${id}/flag`, data, token),
+    getTimeline: (id: string, token: string) =>
+      request<ReportTimeline>("GET", `/reports/${id}/timeline`, undefined, token),
+    getHistory: (id: string, token: string) =>
+      request<ReportHistory>("GET", `/reports/${id}/history`, undefined, token),
+    claimBatch: (reportIds: string[], token: string) =>
+      request<ClaimBatchResponse>(
+        "POST",
+        "/reports/claim-batch",
+        { report_ids: reportIds },
+        token,
+      ),
+    getCleanupRun: (id: string, token: string) =>
+      request<CleanupRun>("GET", `/cleanup-runs/${id}`, undefined, token),
     clear: (id: string, data: ClearReportRequest, token: string) =>
       request<Report>("POST", `/reports/${id}/clear`, data, token),
+    replaceAfterPhoto: (id: string, data: ClearReportRequest, token: string) =>
+      request<Report>("PUT", `/reports/${id}/after-photo`, data, token),
+    getPhotos: (id: string, token: string) =>
+      request<ReportPhotos>("GET", `/reports/${id}/photos`, undefined, token),
+    addPhoto: (id: string, data: AddReportPhotoRequest, token: string) =>
+      request<ReportPhotos>("POST", `/reports/${id}/photos`, data, token),
     verify: (id: string, data: CreateVerificationRequest, token: string) =>
       request<VerificationResponse>(
         "POST",
@@ -225,42 +584,67 @@ export const api = {
         data,
         token,
       ),
-    getVerifications: (id: string, token: string) =>
-      request<VerificationResponse[]>(
+    getVerifications: (id: string, token: string, cursor?: string) =>
+      request<Paginated<VerificationResponse>>(
         "GET",
-        `/reports/${id}/verifications`,
+        `/reports/${id}/verifications${pageQuery(cursor, undefined, "?")}`,
         undefined,
         token,
       ),
   },
   leaderboards: {
-    getGlobal: (period: string = "weekly", token?: string) =>
-      request<LeaderboardEntry[]>(
+    getGlobal: (period: string = "weekly", token?: string, cursor?: string) =>
+      request<Paginated<LeaderboardEntry>>(
+        "GET",
+        `/leaderboards?period=${period}${pageQuery(cursor)}`,
+        undefined,
+        token,
+      ),
+    getCity: (
+      city: string,
+      period: string = "weekly",
+      token?: string,
+      cursor?: string,
+    ) =>
+      request<Paginated<LeaderboardEntry>>(
         "GET",
-        `/leaderboards?period=${period}`,
+        `/leaderboards/city/${city}?period=${period}${pageQuery(cursor)}`,
         undefined,
         token,
       ),
-    getCity: (city: string, period: string = "weekly", token?: string) =>
-      request<LeaderboardEntry[]>(
+    getCountry: (
+      country: string,
+      period: string = "weekly",
+      token?: string,
+      cursor?: string,
+    ) =>
+      request<Paginated<LeaderboardEntry>>(
         "GET",
-        `/leaderboards/city/${city}?period=${period}`,
+        `/leaderboards/country/${country}?period=${period}${pageQuery(cursor)}`,
         undefined,
         token,
       ),
-    getCountry: (country: string, period: string = "weekly", token?: string) =>
-      request<LeaderboardEntry[]>(
+  },
+  stats: {
+    getSummary: () => request<StatsSummary>("GET", "/stats/summary"),
+  },
+  notifications: {
+    /** Long-poll: resolves as soon as something new arrives, or empty after ~25s */
+    poll: (since?: string, token?: string) =>
+      request<NotificationPollResponse>(
         "GET",
-        `/leaderboards/country/${country}?period=${period}`,
+        since
+          ? `/notifications/poll?since=${encodeURIComponent(since)}`
+          : "/notifications/poll",
         undefined,
         token,
       ),
   },
   feed: {
-    getAll: (offset: number = 0, limit: number = 20, token?: string) =>
-      request<FeedPostResponse[]>(
+    getAll: (cursor?: string, limit: number = 20, token?: string) =>
+      request<Paginated<FeedPostResponse>>(
         "GET",
-        `/feed?offset=${offset}&limit=${limit}`,
+        `/feed${pageQuery(cursor, limit, "?")}`,
         undefined,
         token,
       ),
@@ -279,10 +663,10 @@ export const api = {
         token?: string,
       ) =>
         request<FeedComment>("POST", `/feed/${postId}/comments`, data, token),
-      get: (postId: string, token?: string) =>
-        request<FeedCommentResponse[]>(
+      get: (postId: string, token?: string, cursor?: string) =>
+        request<Paginated<FeedCommentResponse>>(
           "GET",
-          `/feed/${postId}/comments`,
+          `/feed/${postId}/comments${pageQuery(cursor, undefined, "?")}`,
           undefined,
           token,
         ),
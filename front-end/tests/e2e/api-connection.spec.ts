@@ -1,9 +1,12 @@
 import { test, expect } from "@playwright/test";
+import { testHelperHeaders } from "./helpers/test-helpers";
 
 test.describe("API Connection Test", () => {
   test("should connect to backend test endpoint from Node.js", async () => {
     // This tests the Node.js -> Backend connection (test helpers)
-    const response = await fetch("http://localhost:8080/api/test/status");
+    const response = await fetch("http://localhost:8080/api/test/status", {
+      headers: testHelperHeaders(),
+    });
     expect(response.ok).toBe(true);
 
     const data = await response.json();
@@ -18,14 +21,14 @@ test.describe("API Connection Test", () => {
     await page.goto("/");
 
     // Make API request from browser context
-    const response = await page.evaluate(async () => {
-      const res = await fetch("/api/test/status");
+    const response = await page.evaluate(async (headers) => {
+      const res = await fetch("/api/test/status", { headers });
       return {
         ok: res.ok,
         status: res.status,
         data: await res.json(),
       };
-    });
+    }, testHelperHeaders());
 
     console.log("Browser API response:", response);
     expect(response.ok).toBe(true);
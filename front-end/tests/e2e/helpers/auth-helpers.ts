@@ -1,4 +1,5 @@
 import { Page, expect } from "@playwright/test";
+import { testHelperHeaders } from "./test-helpers";
 
 /**
  * Helper functions for authentication flows in e2e tests
@@ -66,6 +67,7 @@ export async function verifyEmailViaAPI(email: string): Promise<void> {
     `${baseUrl}/api/test/verify-email/${encodeURIComponent(email)}`,
     {
       method: "POST",
+      headers: testHelperHeaders(),
     },
   );
 
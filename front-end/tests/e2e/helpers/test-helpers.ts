@@ -5,6 +5,16 @@ import { Page, expect } from "@playwright/test";
  * These provide reusable setup and teardown logic
  */
 
+/**
+ * Headers the backend requires on /api/test/* routes (see TEST_HELPERS_SECRET)
+ */
+export function testHelperHeaders(): Record<string, string> {
+  return {
+    "X-Test-Helpers-Secret":
+      process.env.TEST_HELPERS_SECRET || "test-helpers-secret",
+  };
+}
+
 /**
  * Database cleanup fixture
  * Cleans up test data after each test
@@ -19,6 +29,7 @@ export async function cleanupTestData(email: string): Promise<void> {
       method: "DELETE",
       headers: {
         "Content-Type": "application/json",
+        ...testHelperHeaders(),
       },
       body: JSON.stringify({ email }),
     });
@@ -39,6 +50,32 @@ export async function cleanupTestData(email: string): Promise<void> {
   }
 }
 
+/**
+ * Create verified users via the backend factory
+ * All users share the returned password
+ */
+export async function createTestUsers(
+  count: number,
+  emailPrefix?: string,
+): Promise<{ users: { id: string; email: string }[]; password: string }> {
+  const baseUrl = process.env.API_BASE_URL || "http://localhost:8080";
+
+  const response = await fetch(`${baseUrl}/api/test/users`, {
+    method: "POST",
+    headers: {
+      "Content-Type": "application/json",
+      ...testHelperHeaders(),
+    },
+    body: JSON.stringify({ count, email_prefix: emailPrefix }),
+  });
+
+  if (!response.ok) {
+    throw new Error(`Failed to create test users: ${response.statusText}`);
+  }
+
+  return response.json();
+}
+
 /**
  * API request helper with authentication
  */